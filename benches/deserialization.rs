@@ -0,0 +1,451 @@
+//! Deserialization benchmarks.
+#![allow(
+	clippy::missing_docs_in_private_items,
+	clippy::unwrap_used,
+	missing_docs,
+	reason = "Benchmark"
+)]
+
+use ::criterion::{criterion_group, criterion_main, Criterion};
+use ::serde::{Deserialize, Serialize};
+
+/// Struct with many fields, so that field names dominate.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct ManyFields {
+	first_field: u32,
+	second_field: u32,
+	third_field: u32,
+	fourth_field: u32,
+	fifth_field: u32,
+	sixth_field: u32,
+	seventh_field: u32,
+	eighth_field: u32,
+	ninth_field: u32,
+	tenth_field: u32,
+	eleventh_field: u32,
+	twelfth_field: u32,
+	thirteenth_field: u32,
+	fourteenth_field: u32,
+	fifteenth_field: u32,
+	sixteenth_field: u32,
+	seventeenth_field: u32,
+	eighteenth_field: u32,
+	nineteenth_field: u32,
+	twentieth_field: u32,
+}
+
+/// Struct of integers, so that keys dominate with indices.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct TenIntegers {
+	a: u8,
+	b: u16,
+	c: u32,
+	d: u64,
+	e: i8,
+	f: i16,
+	g: i32,
+	h: i64,
+	i: u32,
+	j: i32,
+}
+
+/// Large document of a few megabytes.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Document {
+	entries: Vec<Entry>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Entry {
+	id: u64,
+	name: String,
+	score: f64,
+	tags: Vec<String>,
+	#[serde(with = "serde_bytes")]
+	data: Vec<u8>,
+}
+
+/// Enum with unit and struct variants, so that variant names dominate.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum Event {
+	Started,
+	Stopped,
+	Progress { percent: u8 },
+}
+
+/// Many events with all kinds of variants.
+fn events() -> Vec<Event> {
+	(0 .. 1000_u16)
+		.map(|i| match i % 3 {
+			0 => Event::Started,
+			1 => Event::Stopped,
+			_ => Event::Progress { percent: (i % 100) as u8 },
+		})
+		.collect()
+}
+
+/// Struct that only knows two of the serialized fields.
+#[derive(Debug, Clone, Deserialize)]
+struct TwoKnownFields {
+	#[allow(dead_code, reason = "Only deserialized")]
+	known_a: String,
+	#[allow(dead_code, reason = "Only deserialized")]
+	known_b: String,
+}
+
+/// Map with the two known and 50 unknown fields with large string values.
+fn many_unknown_fields() -> ::std::collections::BTreeMap<String, String> {
+	let value = "x".repeat(1024);
+	(0 .. 50)
+		.map(|i| format!("unknown_{i}"))
+		.chain(["known_a".to_owned(), "known_b".to_owned()])
+		.map(|key| (key, value.clone()))
+		.collect()
+}
+
+fn document() -> Document {
+	let entries = (0 .. 20_000)
+		.map(|i| Entry {
+			id: i,
+			name: format!("entry-{i}"),
+			score: i as f64 / 3.0,
+			tags: (0 .. 4).map(|t| format!("tag-{t}")).collect(),
+			data: vec![i as u8; 64],
+		})
+		.collect();
+	Document { entries }
+}
+
+fn from_reader(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&ManyFields::default()).unwrap();
+	let mut group = c.benchmark_group("from_reader");
+
+	group.bench_function("many_fields", |b| {
+		b.iter(|| {
+			serde_brief::from_reader::<_, ManyFields>(::core::hint::black_box(bytes.as_slice()))
+				.unwrap()
+		});
+	});
+
+	let bytes = serde_brief::to_vec(&document()).unwrap();
+	group.bench_function("large_document", |b| {
+		b.iter(|| {
+			serde_brief::from_reader::<_, Document>(::core::hint::black_box(bytes.as_slice()))
+				.unwrap()
+		});
+	});
+	let path = ::std::env::temp_dir().join("serde-brief-bench-document.bin");
+	::std::fs::write(&path, &bytes).unwrap();
+	group.bench_function("large_document_from_file", |b| {
+		b.iter(|| {
+			let file = ::std::fs::File::open(&path).unwrap();
+			serde_brief::from_reader::<_, Document>(file).unwrap()
+		});
+	});
+	group.bench_function("large_document_from_slice", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Document>(::core::hint::black_box(bytes.as_slice())).unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn field_names(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&vec![ManyFields::default(); 1000]).unwrap();
+	let interned = serde_brief::Config { intern_field_names: true, ..Default::default() };
+	let mut group = c.benchmark_group("field_names");
+
+	group.bench_function("many_fields", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<ManyFields>>(::core::hint::black_box(&bytes)).unwrap()
+		});
+	});
+	group.bench_function("many_fields_interned", |b| {
+		b.iter(|| {
+			serde_brief::from_slice_with_config::<Vec<ManyFields>>(
+				::core::hint::black_box(&bytes),
+				interned,
+			)
+			.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn index_keys(c: &mut Criterion) {
+	let config = serde_brief::Config { use_indices: true, ..Default::default() };
+	let values = vec![TenIntegers { d: u64::MAX, h: -1, ..Default::default() }; 1000];
+	let bytes = serde_brief::to_vec_with_config(&values, config).unwrap();
+	let mut group = c.benchmark_group("index_keys");
+
+	group.bench_function("ten_integers", |b| {
+		b.iter(|| {
+			serde_brief::from_slice_with_config::<Vec<TenIntegers>>(
+				::core::hint::black_box(&bytes),
+				config,
+			)
+			.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn length_prefixed_seqs(c: &mut Criterion) {
+	let values: Vec<u64> = (0 .. 10_000).collect();
+	let mut group = c.benchmark_group("length_prefixed_seqs");
+
+	for length_prefixed_seqs in [false, true] {
+		let config = serde_brief::Config { length_prefixed_seqs, ..Default::default() };
+		let bytes = serde_brief::to_vec_with_config(&values, config).unwrap();
+		let name = if length_prefixed_seqs { "vec_length_prefixed" } else { "vec_delimited" };
+		group.bench_function(name, |b| {
+			b.iter(|| {
+				serde_brief::from_slice::<Vec<u64>>(::core::hint::black_box(&bytes)).unwrap()
+			});
+		});
+	}
+
+	group.finish();
+}
+
+fn enum_variants(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&events()).unwrap();
+	let mut group = c.benchmark_group("enum_variants");
+
+	group.bench_function("from_slice", |b| {
+		b.iter(|| serde_brief::from_slice::<Vec<Event>>(::core::hint::black_box(&bytes)).unwrap());
+	});
+	group.bench_function("from_reader", |b| {
+		b.iter(|| {
+			serde_brief::from_reader::<_, Vec<Event>>(::core::hint::black_box(bytes.as_slice()))
+				.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn validate(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&document()).unwrap();
+	let mut group = c.benchmark_group("validate");
+
+	group.bench_function("large_document", |b| {
+		b.iter(|| {
+			serde_brief::validate(::core::hint::black_box(&bytes), serde_brief::Config::default())
+				.unwrap();
+		});
+	});
+	group.bench_function("large_document_as_value", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<serde_brief::value::Value<'_>>(::core::hint::black_box(
+				&bytes,
+			))
+			.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn skip_unknown_fields(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&many_unknown_fields()).unwrap();
+	let mut group = c.benchmark_group("skip_unknown_fields");
+
+	group.bench_function("from_slice", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<TwoKnownFields>(::core::hint::black_box(&bytes)).unwrap()
+		});
+	});
+	group.bench_function("from_reader", |b| {
+		b.iter(|| {
+			serde_brief::from_reader::<_, TwoKnownFields>(::core::hint::black_box(bytes.as_slice()))
+				.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+fn get_path(c: &mut Criterion) {
+	let bytes = serde_brief::to_vec(&document()).unwrap();
+	let mut group = c.benchmark_group("get_path");
+
+	group.bench_function("large_document", |b| {
+		b.iter(|| {
+			serde_brief::get_path::<&str>(
+				::core::hint::black_box(&bytes),
+				&["entries", "19999", "name"],
+			)
+			.unwrap()
+			.unwrap()
+		});
+	});
+	group.bench_function("large_document_as_value", |b| {
+		b.iter(|| {
+			let value = serde_brief::from_slice::<serde_brief::value::Value<'_>>(
+				::core::hint::black_box(&bytes),
+			)
+			.unwrap();
+			let name = value.get("entries").unwrap().get_index(19_999).unwrap().get("name");
+			name.unwrap().as_string().map(str::len)
+		});
+	});
+
+	group.finish();
+}
+
+/// Either of two structs, decided by trying to parse them.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum Fallback {
+	Integers(#[allow(dead_code, reason = "Only deserialized")] TenIntegers),
+	Fields(#[allow(dead_code, reason = "Only deserialized")] ManyFields),
+}
+
+fn fallback(c: &mut Criterion) {
+	// Mostly the first variant, which a fork parses without buffering.
+	let mut bytes = Vec::new();
+	for i in 0 .. 1000 {
+		if i % 10 != 0 {
+			bytes.extend(serde_brief::to_vec(&TenIntegers::default()).unwrap());
+		} else {
+			bytes.extend(serde_brief::to_vec(&ManyFields::default()).unwrap());
+		}
+	}
+	let mut group = c.benchmark_group("fallback");
+
+	group.bench_function("untagged", |b| {
+		b.iter(|| {
+			let mut rest = ::core::hint::black_box(bytes.as_slice());
+			while !rest.is_empty() {
+				let (value, next) = serde_brief::from_slice_partial::<Fallback>(rest).unwrap();
+				::core::hint::black_box(value);
+				rest = next;
+			}
+		});
+	});
+	group.bench_function("try_parse", |b| {
+		b.iter(|| {
+			let mut de = serde_brief::Deserializer::new(::core::hint::black_box(bytes.as_slice()));
+			while !de.fork().into_input().is_empty() {
+				if let Ok(value) = de.try_parse::<TenIntegers>() {
+					::core::hint::black_box(value);
+				} else {
+					::core::hint::black_box(de.try_parse::<ManyFields>().unwrap());
+				}
+			}
+		});
+	});
+
+	group.finish();
+}
+
+/// Record with a hash as plain byte array.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct PlainHash {
+	id: u32,
+	hash: [u8; 32],
+}
+
+/// Record with a hash encoded by the byte array helper.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct BytesHash {
+	id: u32,
+	#[serde(with = "serde_brief::helpers::byte_array")]
+	hash: [u8; 32],
+}
+
+fn byte_arrays(c: &mut Criterion) {
+	let hash = ::core::array::from_fn(|i| u8::try_from(i * 7).unwrap_or_default());
+	let plain = vec![PlainHash { id: 1, hash }; 1000];
+	let helper = vec![BytesHash { id: 1, hash }; 1000];
+	let plain_bytes = serde_brief::to_vec(&plain).unwrap();
+	let helper_bytes = serde_brief::to_vec(&helper).unwrap();
+	let mut group = c.benchmark_group("byte_arrays");
+
+	group.bench_function("plain", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<PlainHash>>(::core::hint::black_box(&plain_bytes))
+				.unwrap()
+		});
+	});
+	group.bench_function("plain_from_bytes", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<PlainHash>>(::core::hint::black_box(&helper_bytes))
+				.unwrap()
+		});
+	});
+	group.bench_function("helper", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<BytesHash>>(::core::hint::black_box(&helper_bytes))
+				.unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+/// Pseudo-random numbers of all magnitudes (xorshift), so that var-ints have all lengths.
+fn random_numbers(len: usize) -> impl Iterator<Item = u64> {
+	let mut state = 0x2545_F491_4F6C_DD1D_u64;
+	::core::iter::repeat_with(move || {
+		state ^= state << 13;
+		state ^= state >> 7;
+		state ^= state << 17;
+		state >> (state % 64)
+	})
+	.take(len)
+}
+
+fn varints(c: &mut Criterion) {
+	let unsigned: Vec<u64> = random_numbers(10_000).collect();
+	let signed: Vec<i32> = random_numbers(10_000)
+		.map(|x| {
+			let [a, b, c, d, ..] = x.to_le_bytes();
+			i32::from_le_bytes([a, b, c, d])
+		})
+		.collect();
+	// Mostly two-byte var-ints, like lengths, small ids and field indices.
+	let small: Vec<u64> = (0 .. 10_000).collect();
+	let unsigned_bytes = serde_brief::to_vec(&unsigned).unwrap();
+	let signed_bytes = serde_brief::to_vec(&signed).unwrap();
+	let small_bytes = serde_brief::to_vec(&small).unwrap();
+	let mut group = c.benchmark_group("varints");
+
+	group.bench_function("vec_u64", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<u64>>(::core::hint::black_box(&unsigned_bytes)).unwrap()
+		});
+	});
+	group.bench_function("vec_i32", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<i32>>(::core::hint::black_box(&signed_bytes)).unwrap()
+		});
+	});
+	group.bench_function("vec_u64_small", |b| {
+		b.iter(|| {
+			serde_brief::from_slice::<Vec<u64>>(::core::hint::black_box(&small_bytes)).unwrap()
+		});
+	});
+
+	group.finish();
+}
+
+criterion_group!(
+	benches,
+	from_reader,
+	field_names,
+	index_keys,
+	length_prefixed_seqs,
+	enum_variants,
+	validate,
+	skip_unknown_fields,
+	get_path,
+	fallback,
+	byte_arrays,
+	varints
+);
+criterion_main!(benches);
@@ -0,0 +1,66 @@
+//! Serialization benchmarks.
+#![allow(
+	clippy::missing_docs_in_private_items,
+	clippy::unwrap_used,
+	missing_docs,
+	reason = "Benchmark"
+)]
+
+use ::criterion::{criterion_group, criterion_main, Criterion};
+use ::serde::Serialize;
+use ::serde_bytes::ByteBuf;
+
+/// Medium-sized struct with a few kilobytes of strings and bytes.
+#[derive(Debug, Clone, Serialize)]
+struct Document {
+	id: u64,
+	title: String,
+	tags: Vec<String>,
+	body: String,
+	attachments: Vec<ByteBuf>,
+	checksums: Vec<u32>,
+}
+
+fn document() -> Document {
+	Document {
+		id: 0x0123_4567_89AB_CDEF,
+		title: "Serde-Brief benchmark document".to_owned(),
+		tags: (0 .. 32).map(|i| format!("tag-number-{i}")).collect(),
+		body: "Lorem ipsum dolor sit amet, consectetur adipiscing elit. ".repeat(40),
+		attachments: (0 .. 4).map(|i| ByteBuf::from(vec![i; 512])).collect(),
+		checksums: (0 .. 64).map(|i| i * 0x0101_0101).collect(),
+	}
+}
+
+fn to_vec(c: &mut Criterion) {
+	let data = document();
+	let mut group = c.benchmark_group("to_vec");
+
+	group.bench_function("exact", |b| {
+		b.iter(|| serde_brief::to_vec_exact(::core::hint::black_box(&data)).unwrap());
+	});
+	group.bench_function("growing", |b| {
+		b.iter(|| serde_brief::to_vec(::core::hint::black_box(&data)).unwrap());
+	});
+
+	group.finish();
+}
+
+fn to_writer(c: &mut Criterion) {
+	let data = ByteBuf::from(vec![0xAB; 100 * 1024 * 1024]);
+	let path = ::std::env::temp_dir().join("serde-brief-bench-bytes.bin");
+	let mut group = c.benchmark_group("to_writer");
+	group.sample_size(10);
+
+	group.bench_function("large_bytes_to_file", |b| {
+		b.iter(|| {
+			let file = ::std::fs::File::create(&path).unwrap();
+			serde_brief::to_writer(::core::hint::black_box(&data), file).unwrap();
+		});
+	});
+
+	group.finish();
+}
+
+criterion_group!(benches, to_vec, to_writer);
+criterion_main!(benches);
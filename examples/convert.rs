@@ -0,0 +1,45 @@
+//! Minimal command line tool converting between JSON and the binary format, reading from stdin and
+//! writing to stdout:
+//!
+//! ```sh
+//! echo '{"id":5,"tags":["a"]}' | cargo run --example convert --features std,json -- to-brief \
+//! 	| cargo run --example convert --features std,json -- to-json --pretty
+//! ```
+#![allow(clippy::missing_docs_in_private_items, clippy::unwrap_used, reason = "Example")]
+
+use std::io::{BufReader, Read, Write};
+
+use serde_brief::{convert, Config, Error, Result};
+
+fn convert<R: Read, W: Write>(args: &[&str], reader: R, writer: W) -> Result<()> {
+	match args {
+		["to-brief"] => convert::json_to_brief(BufReader::new(reader), writer, Config::default()),
+		["to-brief", "--indices"] => convert::json_to_brief(
+			BufReader::new(reader),
+			writer,
+			Config { use_indices: true, ..Default::default() },
+		),
+		["to-json"] => convert::brief_to_json(BufReader::new(reader), writer, false),
+		["to-json", "--pretty"] => convert::brief_to_json(BufReader::new(reader), writer, true),
+		_ => Err(Error::Message(
+			"usage: convert (to-brief [--indices] | to-json [--pretty])".to_owned(),
+		)),
+	}
+}
+
+fn main() -> Result<()> {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	let args: Vec<&str> = args.iter().map(String::as_str).collect();
+	convert(&args, std::io::stdin().lock(), std::io::stdout().lock())
+}
+
+#[test]
+fn run() {
+	let json = r#"{"id":5,"tags":["a","b"],"score":0.5}"#;
+	let mut bytes = Vec::new();
+	convert(&["to-brief"], json.as_bytes(), &mut bytes).unwrap();
+	let mut output = Vec::new();
+	convert(&["to-json"], bytes.as_slice(), &mut output).unwrap();
+	assert_eq!(output, json.as_bytes());
+	assert!(convert(&["to-yaml"], bytes.as_slice(), &mut output).is_err());
+}
@@ -0,0 +1,138 @@
+//! Serializing into and deserializing from a custom ring buffer, by implementing `Output` and
+//! `Input` for it.
+#![allow(clippy::missing_docs_in_private_items, clippy::unwrap_used, reason = "Example")]
+
+use serde::{Deserialize, Serialize};
+use serde_brief::{
+	buffer::Buffer,
+	io::{Input, Output},
+	Deserializer, Error, Result, Serializer,
+};
+
+/// Fixed-size ring buffer of bytes, e.g. a queue between an interrupt handler and the main loop.
+struct RingBuffer<const N: usize> {
+	bytes: [u8; N],
+	start: usize,
+	len: usize,
+}
+
+impl<const N: usize> RingBuffer<N> {
+	const fn new() -> Self {
+		Self { bytes: [0; N], start: 0, len: 0 }
+	}
+
+	fn push(&mut self, byte: u8) -> Result<()> {
+		if self.len == N {
+			return Err(Error::BufferTooSmall);
+		}
+		*self.bytes.get_mut((self.start + self.len) % N).ok_or(Error::BufferTooSmall)? = byte;
+		self.len += 1;
+		Ok(())
+	}
+
+	fn peek(&self) -> Result<u8> {
+		if self.len == 0 {
+			return Err(Error::UnexpectedEnd);
+		}
+		self.bytes.get(self.start).copied().ok_or(Error::UnexpectedEnd)
+	}
+
+	fn pop(&mut self) -> Result<u8> {
+		let byte = self.peek()?;
+		self.start = (self.start + 1) % N;
+		self.len -= 1;
+		Ok(byte)
+	}
+}
+
+impl<const N: usize> Output for RingBuffer<N> {
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.push(byte)
+	}
+
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		if N - self.len < bytes.len() {
+			return Err(Error::BufferTooSmall);
+		}
+		bytes.iter().try_for_each(|byte| self.push(*byte))
+	}
+}
+
+/// The data wraps around in the ring, so it cannot be borrowed for `'de`. Strings and bytes are
+/// copied to the deserializer's buffer instead. Short ones, like here, fit into its inline buffer,
+/// longer ones need a buffer set with `Deserializer::with_buffer`.
+impl<'de, const N: usize> Input<'de> for RingBuffer<N> {
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.peek()
+	}
+
+	fn read_byte(&mut self) -> Result<u8> {
+		self.pop()
+	}
+
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		if self.len < buffer.len() {
+			return Err(Error::UnexpectedEnd);
+		}
+		buffer.iter_mut().try_for_each(|byte| {
+			*byte = self.pop()?;
+			Ok(())
+		})
+	}
+
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		if len == 0 {
+			return Ok(Some(&[]));
+		}
+		let buffer = buffer.ok_or(Error::BufferTooSmall)?;
+		self.read_exact(buffer.reserve_slice(len)?)?;
+		Ok(None)
+	}
+
+	fn skip_bytes(&mut self, len: usize) -> Result<()> {
+		if self.len < len {
+			return Err(Error::UnexpectedEnd);
+		}
+		self.start = (self.start + len) % N;
+		self.len -= len;
+		Ok(())
+	}
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Measurement {
+	sensor: String,
+	values: Vec<i16>,
+}
+
+fn main() {
+	let mut ser = Serializer::new(RingBuffer::<128>::new());
+	for index in 0 .. 3 {
+		let measurement = Measurement { sensor: format!("sensor-{index}"), values: vec![-1, 2] };
+		measurement.serialize(&mut ser).unwrap();
+	}
+	let ring = ser.into_output();
+
+	// Read one value to make room, then write across the end of the ring.
+	let mut de = Deserializer::new(ring);
+	let first = Measurement::deserialize(&mut de).unwrap();
+	assert_eq!(first.sensor, "sensor-0");
+	let mut ser = Serializer::new(de.into_input());
+	Measurement { sensor: "sensor-3".to_owned(), values: vec![3] }.serialize(&mut ser).unwrap();
+
+	let mut de = Deserializer::new(ser.into_output());
+	for index in 1 .. 4 {
+		let measurement = Measurement::deserialize(&mut de).unwrap();
+		assert_eq!(measurement.sensor, format!("sensor-{index}"));
+	}
+	de.end().unwrap();
+	assert_eq!(de.into_input().len, 0);
+}
+
+#[test]
+fn run() {
+	main();
+}
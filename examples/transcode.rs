@@ -0,0 +1,29 @@
+//! Transcoding between JSON and the binary format without intermediate types, using
+//! `serde_transcode`.
+#![allow(clippy::missing_docs_in_private_items, clippy::unwrap_used, reason = "Example")]
+
+use serde_brief::{Deserializer, Serializer};
+
+fn main() {
+	let json = r#"{"name":"Holla","age":21,"tags":["a","b"],"nested":{"value":null}}"#;
+
+	// JSON to binary.
+	let mut json_de = serde_json::Deserializer::from_str(json);
+	let mut brief_ser = Serializer::new(Vec::new());
+	serde_transcode::transcode(&mut json_de, &mut brief_ser).unwrap();
+	json_de.end().unwrap();
+	let bytes = brief_ser.into_output();
+	assert!(bytes.len() < json.len());
+
+	// Binary back to JSON.
+	let mut brief_de = Deserializer::new(bytes.as_slice());
+	let mut output = Vec::new();
+	let mut json_ser = serde_json::Serializer::new(&mut output);
+	serde_transcode::transcode(&mut brief_de, &mut json_ser).unwrap();
+	assert_eq!(String::from_utf8(output).unwrap(), json);
+}
+
+#[test]
+fn run() {
+	main();
+}
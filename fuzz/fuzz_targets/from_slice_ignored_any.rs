@@ -0,0 +1,10 @@
+//! Skip arbitrary bytes via `IgnoredAny`, which must never panic.
+#![no_main]
+
+use ::libfuzzer_sys::fuzz_target;
+use ::serde::de::IgnoredAny;
+
+fuzz_target!(|data: &[u8]| {
+	_ = serde_brief::from_slice::<IgnoredAny>(data);
+	_ = serde_brief::from_reader::<_, IgnoredAny>(data);
+});
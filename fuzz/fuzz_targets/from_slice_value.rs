@@ -0,0 +1,12 @@
+//! Deserialize arbitrary bytes into a `Value`, which must never panic.
+#![no_main]
+
+use ::libfuzzer_sys::fuzz_target;
+use ::serde_brief::value::Value;
+
+fuzz_target!(|data: &[u8]| {
+	if let Ok(value) = serde_brief::from_slice::<Value<'_>>(data) {
+		// Everything that was deserialized can be serialized again.
+		serde_brief::to_vec(&value).expect("serializing deserialized value");
+	}
+});
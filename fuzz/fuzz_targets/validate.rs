@@ -0,0 +1,12 @@
+//! Validate arbitrary bytes, which must never panic and agree with deserializing a `Value`.
+#![no_main]
+
+use ::libfuzzer_sys::fuzz_target;
+use ::serde_brief::{value::Value, Config};
+
+fuzz_target!(|data: &[u8]| {
+	let valid = serde_brief::validate(data, Config::default()).is_ok();
+	if serde_brief::from_slice::<Value<'_>>(data).is_ok() {
+		assert!(valid, "deserialized data must be valid");
+	}
+});
@@ -0,0 +1,218 @@
+//! Derive macros for [serde-brief](https://docs.rs/serde-brief). Use them via the `derive` feature
+//! of `serde-brief` instead of depending on this crate directly.
+
+use ::proc_macro2::TokenStream;
+use ::quote::quote;
+use ::syn::{
+	ext::IdentExt, meta::ParseNestedMeta, parse_macro_input, parse_quote, spanned::Spanned,
+	Attribute, Data, DeriveInput, Error, Expr, Fields, LitStr, Result, Token,
+};
+
+/// Serde attributes that change the encoding in ways the derive cannot compute.
+const UNSUPPORTED: &[&str] = &[
+	"flatten",
+	"with",
+	"serialize_with",
+	"getter",
+	"remote",
+	"into",
+	"tag",
+	"content",
+	"untagged",
+	"rename_all",
+	"rename_all_fields",
+];
+
+/// Derive `serde_brief::MaxSize`, the maximum encoded size of the type in all configurations.
+///
+/// The type must be serialized via `#[derive(Serialize)]` with compatible serde attributes, see the
+/// `serde_brief::max_size` module.
+#[proc_macro_derive(MaxSize)]
+pub fn derive_max_size(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	expand(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+/// Serde attributes of a container, field or variant that affect the encoded size.
+#[derive(Debug, Default)]
+struct SerdeAttrs {
+	/// The serialized name.
+	rename: Option<String>,
+	/// Whether the item is never serialized.
+	skip: bool,
+	/// Whether the container is serialized as its single field.
+	transparent: bool,
+}
+
+impl SerdeAttrs {
+	/// Parse the relevant `#[serde(...)]` attributes and reject unsupported ones.
+	fn parse(attrs: &[Attribute]) -> Result<Self> {
+		let mut parsed = Self::default();
+		for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+			attr.parse_nested_meta(|meta| {
+				if meta.path.is_ident("rename") {
+					parsed.rename = Some(parse_rename(&meta)?);
+				} else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+					parsed.skip = true;
+				} else if meta.path.is_ident("transparent") {
+					parsed.transparent = true;
+				} else if UNSUPPORTED.iter().any(|name| meta.path.is_ident(name)) {
+					return Err(meta.error("this serde attribute is not supported by MaxSize"));
+				} else {
+					skip_meta(&meta)?;
+				}
+				Ok(())
+			})?;
+		}
+		Ok(parsed)
+	}
+}
+
+/// Parse `rename = "..."` or the serialization name of `rename(serialize = "...")`.
+fn parse_rename(meta: &ParseNestedMeta<'_>) -> Result<String> {
+	if meta.input.peek(Token![=]) {
+		return Ok(meta.value()?.parse::<LitStr>()?.value());
+	}
+
+	let mut rename = None;
+	meta.parse_nested_meta(|inner| {
+		if inner.path.is_ident("serialize") {
+			rename = Some(inner.value()?.parse::<LitStr>()?.value());
+			Ok(())
+		} else {
+			skip_meta(&inner)
+		}
+	})?;
+	rename.ok_or_else(|| meta.error("missing serialization name"))
+}
+
+/// Skip the value of an attribute that is irrelevant for the size.
+fn skip_meta(meta: &ParseNestedMeta<'_>) -> Result<()> {
+	if meta.input.peek(Token![=]) {
+		meta.value()?.parse::<Expr>()?;
+	} else if meta.input.peek(::syn::token::Paren) {
+		meta.parse_nested_meta(|inner| skip_meta(&inner))?;
+	}
+	Ok(())
+}
+
+/// Generate the `MaxSize` implementation.
+fn expand(input: DeriveInput) -> Result<TokenStream> {
+	let attrs = SerdeAttrs::parse(&input.attrs)?;
+
+	let max_size = match &input.data {
+		Data::Struct(data) if attrs.transparent => transparent_size(&data.fields)?,
+		Data::Struct(data) => struct_size(&data.fields)?,
+		Data::Enum(data) => {
+			let mut variants = Vec::new();
+			for (index, variant) in data.variants.iter().enumerate() {
+				let variant_attrs = SerdeAttrs::parse(&variant.attrs)?;
+				if variant_attrs.skip {
+					continue;
+				}
+				let name =
+					variant_attrs.rename.unwrap_or_else(|| variant.ident.unraw().to_string());
+				let key = quote!(::serde_brief::max_size::key_size(#name, #index));
+				let size = match &variant.fields {
+					Fields::Unit => key,
+					// Other variants are maps with a single entry of the variant key and content.
+					fields => {
+						let content = struct_size(fields)?;
+						quote!(2 + #key + #content)
+					}
+				};
+				variants.push(size);
+			}
+			if variants.is_empty() {
+				return Ok(impl_max_size(input, &quote!(0)));
+			}
+			quote!({
+				let mut max = 0;
+				#(max = ::serde_brief::max_size::max(max, #variants);)*
+				max
+			})
+		}
+		Data::Union(data) => {
+			return Err(Error::new(data.union_token.span(), "unions are not supported by MaxSize"))
+		}
+	};
+
+	Ok(impl_max_size(input, &max_size))
+}
+
+/// Generate the implementation with the given size expression, bounding all type parameters.
+fn impl_max_size(mut input: DeriveInput, max_size: &TokenStream) -> TokenStream {
+	for param in input.generics.type_params_mut() {
+		param.bounds.push(parse_quote!(::serde_brief::MaxSize));
+	}
+	let name = &input.ident;
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	quote! {
+		#[automatically_derived]
+		impl #impl_generics ::serde_brief::MaxSize for #name #ty_generics #where_clause {
+			const MAX_SIZE: usize = #max_size;
+		}
+	}
+}
+
+/// Maximum size of the fields of a struct or enum variant.
+fn struct_size(fields: &Fields) -> Result<TokenStream> {
+	match fields {
+		Fields::Unit => Ok(quote!(1)),
+		// Newtypes are serialized as their content.
+		Fields::Unnamed(unnamed) if unnamed.unnamed.len() == 1 => transparent_size(fields),
+		Fields::Unnamed(unnamed) => {
+			let mut sizes = Vec::new();
+			for field in &unnamed.unnamed {
+				if !SerdeAttrs::parse(&field.attrs)?.skip {
+					let ty = &field.ty;
+					sizes.push(quote!(<#ty as ::serde_brief::MaxSize>::MAX_SIZE));
+				}
+			}
+			// Tuple structs are sequences, which may be length-prefixed.
+			let len = sizes.len();
+			Ok(quote!(::serde_brief::max_size::seq_overhead(#len) #(+ #sizes)*))
+		}
+		Fields::Named(named) => {
+			let mut sizes = Vec::new();
+			for (index, field) in named.named.iter().enumerate() {
+				let field_attrs = SerdeAttrs::parse(&field.attrs)?;
+				if field_attrs.skip {
+					continue;
+				}
+				let name = match field_attrs.rename {
+					Some(name) => name,
+					None => field
+						.ident
+						.as_ref()
+						.map(|ident| ident.unraw().to_string())
+						.unwrap_or_default(),
+				};
+				let ty = &field.ty;
+				// Indices of skipped fields are not reused, so `index` is an upper bound.
+				sizes.push(quote!(
+					::serde_brief::max_size::key_size(#name, #index)
+						+ <#ty as ::serde_brief::MaxSize>::MAX_SIZE
+				));
+			}
+			Ok(quote!(2 #(+ #sizes)*))
+		}
+	}
+}
+
+/// Maximum size of the single non-skipped field, which is serialized on its own.
+fn transparent_size(fields: &Fields) -> Result<TokenStream> {
+	let mut serialized = Vec::new();
+	for field in fields {
+		if !SerdeAttrs::parse(&field.attrs)?.skip {
+			serialized.push(field);
+		}
+	}
+	match serialized.as_slice() {
+		[field] => {
+			let ty = &field.ty;
+			Ok(quote!(<#ty as ::serde_brief::MaxSize>::MAX_SIZE))
+		}
+		_ => Err(Error::new(fields.span(), "expected exactly one serialized field")),
+	}
+}
@@ -0,0 +1,301 @@
+//! Async (de-)serialization using [tokio]'s IO traits.
+//!
+//! Serde is synchronous, so values are serialized to a buffer before writing it. When reading, a
+//! single top-level value is read into a buffer by following the format's structure, so that no
+//! data after the value is consumed from the reader.
+
+use ::serde::{de::DeserializeOwned, Serialize};
+use ::tokio::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+use crate::{
+	format::{decode_length, Type, VarInt},
+	Config, Error, Result,
+};
+
+/// Serialize a type into an [AsyncWrite]r using the given configuration.
+///
+/// The value is serialized into a buffer first, which is then written and flushed.
+#[allow(clippy::future_not_send, reason = "Send if the value is Sync and the writer is Send")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub async fn to_writer_async_with_config<T, W>(
+	value: &T,
+	mut writer: W,
+	config: Config,
+) -> Result<()>
+where
+	T: Serialize,
+	W: AsyncWrite + Unpin,
+{
+	let bytes = crate::to_vec_with_config(value, config)?;
+	writer.write_all(&bytes).await?;
+	writer.flush().await?;
+	Ok(())
+}
+
+/// Serialize a type into an [AsyncWrite]r.
+#[allow(clippy::future_not_send, reason = "Send if the value is Sync and the writer is Send")]
+pub async fn to_writer_async<T, W>(value: &T, writer: W) -> Result<()>
+where
+	T: Serialize,
+	W: AsyncWrite + Unpin,
+{
+	to_writer_async_with_config(value, writer, Config::default()).await
+}
+
+/// Deserialize a type from an [AsyncRead]er using the given configuration.
+///
+/// Exactly one value is read from the reader, so it can be used for consecutive values in a
+/// stream. The reader is read in small pieces, so it is best to wrap unbuffered readers in a
+/// [`tokio::io::BufReader`]. The configured `max_size` limits the buffered bytes.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub async fn from_reader_async_with_config<R, T>(reader: R, config: Config) -> Result<T>
+where
+	R: AsyncRead + Unpin,
+	T: DeserializeOwned,
+{
+	let limit = config.max_size.map_or(usize::MAX, ::core::num::NonZeroUsize::get);
+	let mut frame = FrameReader { reader, data: Vec::new(), limit };
+	frame.read_value().await?;
+	crate::from_slice_with_config(&frame.data, config)
+}
+
+/// Deserialize a type from an [AsyncRead]er.
+pub async fn from_reader_async<R, T>(reader: R) -> Result<T>
+where
+	R: AsyncRead + Unpin,
+	T: DeserializeOwned,
+{
+	from_reader_async_with_config(reader, Config::default()).await
+}
+
+/// Reader of a single top-level value into a buffer, without reading past its end.
+#[derive(Debug)]
+struct FrameReader<R> {
+	/// The inner reader.
+	reader: R,
+	/// The bytes of the value read so far.
+	data: Vec<u8>,
+	/// Maximum number of bytes to read.
+	limit: usize,
+}
+
+impl<R> FrameReader<R>
+where
+	R: AsyncRead + Unpin,
+{
+	/// Read a full value, including nested values of sequences and maps.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	async fn read_value(&mut self) -> Result<()> {
+		// Open collections, with the number of remaining elements of length-prefixed sequences.
+		let mut open = Vec::<Option<usize>>::new();
+		loop {
+			let byte = self.read_byte().await?;
+			match Type::try_from(byte)? {
+				Type::Null | Type::BooleanFalse | Type::BooleanTrue => {}
+				// The contained value follows.
+				Type::Some => continue,
+				// Small unsigned integers are inline in the type byte.
+				Type::UnsignedInt if byte != u8::from(Type::UnsignedInt) => {}
+				Type::UnsignedInt | Type::SignedInt | Type::StringRef => {
+					_ = self.read_varint().await?;
+				}
+				Type::Float16 => self.read_bytes(2).await?,
+				Type::Float32 => self.read_bytes(4).await?,
+				Type::Float64 => self.read_bytes(8).await?,
+				Type::Float128 => self.read_bytes(16).await?,
+				Type::Bytes | Type::String | Type::StringDef => {
+					let start = self.read_varint().await?;
+					let len = decode_length(&mut self.data.get(start ..).unwrap_or_default())?;
+					self.read_bytes(len).await?;
+				}
+				Type::SeqStart | Type::MapStart => {
+					open.push(None);
+					continue;
+				}
+				Type::SeqFixed => {
+					let start = self.read_varint().await?;
+					let len = decode_length(&mut self.data.get(start ..).unwrap_or_default())?;
+					if len > 0 {
+						open.push(Some(len));
+						continue;
+					}
+				}
+				// Unbalanced ends are left for the deserializer to report.
+				Type::SeqEnd | Type::MapEnd => _ = open.pop(),
+			}
+
+			// A value is complete, which may complete length-prefixed sequences as well.
+			while let Some(Some(remaining)) = open.last_mut() {
+				*remaining -= 1;
+				if *remaining > 0 {
+					break;
+				}
+				_ = open.pop();
+			}
+			if open.is_empty() {
+				return Ok(());
+			}
+		}
+	}
+
+	/// Read a single byte.
+	async fn read_byte(&mut self) -> Result<u8> {
+		self.reserve(1)?;
+		let byte = self.reader.read_u8().await.map_err(map_eof)?;
+		self.data.push(byte);
+		Ok(byte)
+	}
+
+	/// Read the bytes of a `VarInt` and return the index where it starts.
+	async fn read_varint(&mut self) -> Result<usize> {
+		let start = self.data.len();
+		for _ in 0 .. u128::MAX_BYTES {
+			if self.read_byte().await? & 0x80 == 0 {
+				return Ok(start);
+			}
+		}
+		Err(Error::VarIntTooLarge)
+	}
+
+	/// Read exactly the given number of bytes. The buffer grows with the read data instead of
+	/// reserving the full length up front.
+	async fn read_bytes(&mut self, len: usize) -> Result<()> {
+		self.reserve(len)?;
+		let len64 = u64::try_from(len).map_err(|_| Error::UsizeOverflow)?;
+		let read = (&mut self.reader).take(len64).read_to_end(&mut self.data).await?;
+		if read != len {
+			return Err(Error::UnexpectedEnd);
+		}
+		Ok(())
+	}
+
+	/// Check that the given number of additional bytes fit into the limit.
+	fn reserve(&self, len: usize) -> Result<()> {
+		let total = self.data.len().checked_add(len).ok_or_else(|| Error::UsizeOverflow)?;
+		if total > self.limit {
+			return Err(Error::LimitReached);
+		}
+		Ok(())
+	}
+}
+
+/// Map unexpected EOF errors to [Error::UnexpectedEnd].
+fn map_eof(err: ::std::io::Error) -> Error {
+	if err.kind() == ::std::io::ErrorKind::UnexpectedEof {
+		Error::UnexpectedEnd
+	} else {
+		Error::Io(err)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, reason = "Tests")]
+
+	use ::serde::{Deserialize, Serialize};
+
+	use super::*;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Message {
+		id: u64,
+		name: String,
+		payload: Vec<u8>,
+		nested: Vec<(i32, Option<f64>)>,
+	}
+
+	fn message(id: u64) -> Message {
+		Message {
+			id,
+			name: format!("Message number {id}"),
+			payload: vec![5; 100],
+			nested: vec![(-1, Some(1.5)), (2, None)],
+		}
+	}
+
+	fn block_on<F: ::core::future::Future>(future: F) -> F::Output {
+		::tokio::runtime::Builder::new_current_thread().build().unwrap().block_on(future)
+	}
+
+	#[test]
+	fn roundtrip_consecutive_values() {
+		block_on(async {
+			let mut stream = Vec::new();
+			to_writer_async(&message(1), &mut stream).await.unwrap();
+			to_writer_async(&message(2), &mut stream).await.unwrap();
+			to_writer_async(&"end", &mut stream).await.unwrap();
+			let mut expected = crate::to_vec(&message(1)).unwrap();
+			expected.extend(crate::to_vec(&message(2)).unwrap());
+			expected.extend(crate::to_vec(&"end").unwrap());
+			assert_eq!(stream, expected);
+
+			let mut reader = stream.as_slice();
+			let first: Message = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(first, message(1));
+			let second: Message = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(second, message(2));
+			let end: String = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(end, "end");
+			assert!(reader.is_empty());
+
+			let result = from_reader_async::<_, Message>(&mut reader).await;
+			assert!(matches!(result, Err(Error::UnexpectedEnd)));
+
+			// Inline integers have no following bytes.
+			let bytes = [Type::SeqStart.into(), 64, 127, Type::SeqEnd.into(), 65];
+			let mut reader = bytes.as_slice();
+			let values: Vec<u8> = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(values, [0, 63]);
+			assert_eq!(reader, [65]);
+
+			// Length-prefixed sequences end after their elements, including empty ones.
+			let config = Config { length_prefixed_seqs: true, ..Default::default() };
+			let mut stream = Vec::new();
+			to_writer_async_with_config(&message(3), &mut stream, config).await.unwrap();
+			to_writer_async_with_config(&Vec::<Vec<u8>>::from([vec![]]), &mut stream, config)
+				.await
+				.unwrap();
+			let mut reader = stream.as_slice();
+			let third: Message = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(third, message(3));
+			let empty: Vec<Vec<u8>> = from_reader_async(&mut reader).await.unwrap();
+			assert_eq!(empty, [Vec::<u8>::new()]);
+			assert!(reader.is_empty());
+
+			// Strings of the string table are read like strings and integers.
+			let config = Config { key_dictionary: true, ..Default::default() };
+			let bytes = crate::to_vec_with_config(&[message(4), message(5)], config).unwrap();
+			let values: [Message; 2] = from_reader_async(bytes.as_slice()).await.unwrap();
+			assert_eq!(values, [message(4), message(5)]);
+
+			// Values do not need to be thread-safe.
+			let mut stream = Vec::new();
+			to_writer_async(&::core::cell::Cell::new(7_u8), &mut stream).await.unwrap();
+			let cell: ::core::cell::Cell<u8> = from_reader_async(stream.as_slice()).await.unwrap();
+			assert_eq!(cell.get(), 7);
+		});
+	}
+
+	#[test]
+	fn reading_honors_limits() {
+		block_on(async {
+			let bytes = crate::to_vec(&message(1)).unwrap();
+
+			let config = Config { max_size: Some(10.try_into().unwrap()), ..Default::default() };
+			let result =
+				from_reader_async_with_config::<_, Message>(bytes.as_slice(), config).await;
+			assert!(matches!(result, Err(Error::LimitReached)));
+			let result = to_writer_async_with_config(&message(1), Vec::new(), config).await;
+			assert!(matches!(result, Err(Error::LimitReached)));
+
+			// Huge lengths do not allocate up front.
+			let bytes = [Type::Bytes.into(), 0xFF, 0xFF, 0xFF, 0xFF, 0x0F];
+			let result = from_reader_async::<_, Vec<u8>>(bytes.as_slice()).await;
+			assert!(matches!(result, Err(Error::UnexpectedEnd)));
+
+			let bytes = [Type::SeqStart.into(), Type::UnsignedInt.into(), 1];
+			let result = from_reader_async::<_, Vec<u8>>(bytes.as_slice()).await;
+			assert!(matches!(result, Err(Error::UnexpectedEnd)));
+		});
+	}
+}
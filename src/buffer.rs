@@ -1,4 +1,7 @@
 //! Scratch/buffer implementation for different environments.
+//!
+//! When deserializing from inputs that cannot lend their data, e.g. readers, strings and bytes are
+//! copied to a [Buffer] first. Implement it to use custom storage.
 #![cfg_attr(
 	feature = "tracing",
 	allow(clippy::used_underscore_binding, reason = "Only used in tracing::instrument")
@@ -7,6 +10,8 @@
 use crate::{Error, Result};
 
 /// Scratch/buffer implementation for different environments.
+///
+/// Buffers with limited capacity return [Error::BufferTooSmall] when data does not fit.
 pub trait Buffer {
 	/// Clear the buffer.
 	fn clear(&mut self);
@@ -16,7 +21,8 @@ pub trait Buffer {
 	fn push(&mut self, byte: u8) -> Result<()>;
 	/// Extend the buffer with the given slice.
 	fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<()>;
-	/// Reserve space in the buffer and return a mutable slice to it to be written.
+	/// Reserve space in the buffer and return a mutable slice to it to be written. The space is
+	/// appended to the written value, so it is part of [as_slice](Self::as_slice) afterwards.
 	fn reserve_slice(&mut self, len: usize) -> Result<&mut [u8]>;
 }
 
@@ -53,6 +59,98 @@ impl Buffer for () {
 	}
 }
 
+/// Borrowed buffers, so that a buffer can be reused for multiple deserializations.
+impl<B> Buffer for &mut B
+where
+	B: Buffer + ?Sized,
+{
+	#[inline]
+	fn clear(&mut self) {
+		(**self).clear();
+	}
+
+	#[inline]
+	fn as_slice(&self) -> &[u8] {
+		(**self).as_slice()
+	}
+
+	#[inline]
+	fn push(&mut self, byte: u8) -> Result<()> {
+		(**self).push(byte)
+	}
+
+	#[inline]
+	fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<()> {
+		(**self).extend_from_slice(bytes)
+	}
+
+	#[inline]
+	fn reserve_slice(&mut self, len: usize) -> Result<&mut [u8]> {
+		(**self).reserve_slice(len)
+	}
+}
+
+/// Small buffer on the stack, used for short data (like field names) so that the main buffer is not
+/// needed.
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct InlineBuffer {
+	/// The buffer's storage.
+	bytes: [u8; Self::CAPACITY],
+	/// The number of bytes written.
+	len: usize,
+}
+
+impl InlineBuffer {
+	/// The number of bytes that fit into the buffer.
+	pub const CAPACITY: usize = 32;
+
+	/// Create a new, empty buffer.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { bytes: [0; Self::CAPACITY], len: 0 }
+	}
+}
+
+impl Default for InlineBuffer {
+	#[inline]
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Buffer for InlineBuffer {
+	#[inline]
+	fn clear(&mut self) {
+		self.len = 0;
+	}
+
+	#[inline]
+	fn as_slice(&self) -> &[u8] {
+		self.bytes.get(.. self.len).unwrap_or_default()
+	}
+
+	#[inline]
+	fn push(&mut self, byte: u8) -> Result<()> {
+		self.extend_from_slice(&[byte])
+	}
+
+	#[inline]
+	fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<()> {
+		self.reserve_slice(bytes.len())?.copy_from_slice(bytes);
+		Ok(())
+	}
+
+	#[inline]
+	fn reserve_slice(&mut self, len: usize) -> Result<&mut [u8]> {
+		let prev = self.len;
+		let end = prev.checked_add(len).ok_or_else(|| Error::UsizeOverflow)?;
+		let slice = self.bytes.get_mut(prev .. end).ok_or_else(|| Error::BufferTooSmall)?;
+		slice.fill(0);
+		self.len = end;
+		Ok(slice)
+	}
+}
+
 #[cfg(feature = "alloc")]
 impl Buffer for ::alloc::vec::Vec<u8> {
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
@@ -117,6 +215,38 @@ impl<const N: usize> Buffer for ::heapless::Vec<u8, N> {
 	}
 }
 
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Buffer for ::arrayvec::ArrayVec<u8, N> {
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	fn clear(&mut self) {
+		self.clear();
+	}
+
+	fn as_slice(&self) -> &[u8] {
+		self.as_slice()
+	}
+
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	fn push(&mut self, byte: u8) -> Result<()> {
+		self.try_push(byte).map_err(|_| Error::BufferTooSmall)
+	}
+
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, bytes)))]
+	fn extend_from_slice(&mut self, bytes: &[u8]) -> Result<()> {
+		self.try_extend_from_slice(bytes).map_err(|_| Error::BufferTooSmall)
+	}
+
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	fn reserve_slice(&mut self, len: usize) -> Result<&mut [u8]> {
+		let prev = self.len();
+		if len > self.remaining_capacity() {
+			return Err(Error::BufferTooSmall);
+		}
+		self.extend(::core::iter::repeat(0).take(len));
+		Ok(self.as_mut_slice().split_at_mut(prev).1)
+	}
+}
+
 #[cfg(test)]
 mod tests {
 	#![allow(clippy::unwrap_used, clippy::expect_used, clippy::indexing_slicing, reason = "Tests")]
@@ -136,7 +266,6 @@ mod tests {
 		_ = buffer.reserve_slice(usize::MAX);
 	}
 
-	#[allow(dead_code, reason = "Different feature sets")]
 	fn basics_work<B: Buffer>(mut buffer: B) {
 		buffer.clear();
 		let expected: &[u8] = &[];
@@ -161,7 +290,6 @@ mod tests {
 		assert_eq!(buffer.as_slice(), &[1, 2, 3, 4, 5]);
 	}
 
-	#[allow(dead_code, reason = "Different feature sets")]
 	fn reserve_slice_works<B: Buffer>(mut buffer: B) {
 		buffer.clear();
 		let slice = buffer.reserve_slice(0).unwrap();
@@ -189,6 +317,18 @@ mod tests {
 		does_not_panic(());
 	}
 
+	#[test]
+	fn inline_buffer_behaves() {
+		does_not_panic(InlineBuffer::new());
+		basics_work(InlineBuffer::new());
+		reserve_slice_works(InlineBuffer::new());
+
+		let mut buffer = InlineBuffer::new();
+		buffer.extend_from_slice(&[1; InlineBuffer::CAPACITY]).unwrap();
+		assert!(matches!(buffer.push(2), Err(Error::BufferTooSmall)));
+		assert_eq!(buffer.as_slice(), &[1; InlineBuffer::CAPACITY]);
+	}
+
 	#[cfg(feature = "alloc")]
 	#[test]
 	fn vec_buffer_behaves() {
@@ -204,4 +344,12 @@ mod tests {
 		basics_work(::heapless::Vec::<_, 100>::new());
 		reserve_slice_works(::heapless::Vec::<_, 100>::new());
 	}
+
+	#[cfg(feature = "arrayvec")]
+	#[test]
+	fn arrayvec_buffer_behaves() {
+		does_not_panic(::arrayvec::ArrayVec::<_, 100>::new());
+		basics_work(::arrayvec::ArrayVec::<_, 100>::new());
+		reserve_slice_works(::arrayvec::ArrayVec::<_, 100>::new());
+	}
 }
@@ -0,0 +1,625 @@
+//! CRC-32 checksums to detect corrupted data, e.g. when storing values in flash memory or sending
+//! them over unreliable transports.
+//!
+//! The checksum is appended to the encoded value as 4 bytes in little endian. It is the common
+//! CRC-32 (IEEE 802.3) as used by zip, PNG and Ethernet, computed over the encoded value only.
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use serde_brief::{checksum, Error};
+//!
+//! let mut bytes = checksum::to_vec_crc32(&("Hello", 42_u32)).unwrap();
+//! let value: (&str, u32) = checksum::from_slice_crc32(&bytes).unwrap();
+//! assert_eq!(value, ("Hello", 42));
+//!
+//! bytes[3] ^= 0x01;
+//! let result = checksum::from_slice_crc32::<(&str, u32)>(&bytes);
+//! assert!(matches!(result, Err(Error::ChecksumMismatch)));
+//! # }
+//! ```
+//!
+//! [ChecksumOutput] and [ChecksumInput] compute the checksum while the data passes through, so
+//! they work with all outputs and inputs, e.g. to write into a `heapless::Vec`:
+//!
+//! ```rust
+//! # #[cfg(feature = "heapless")] {
+//! use serde::Serialize;
+//! use serde_brief::{checksum::ChecksumOutput, Serializer};
+//!
+//! let mut ser = Serializer::new(ChecksumOutput::new(heapless::Vec::<u8, 16>::new()));
+//! 12345_u16.serialize(&mut ser).unwrap();
+//! let bytes = ser.into_output().finish().unwrap();
+//!
+//! let value: u16 = serde_brief::checksum::from_slice_crc32(&bytes).unwrap();
+//! assert_eq!(value, 12345);
+//! # }
+//! ```
+
+#[cfg(feature = "std")]
+use ::serde::de::DeserializeOwned;
+use ::serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use ::std::io::{Read, Write};
+
+use crate::{
+	buffer::Buffer,
+	io::{Input, Output, ReadBytes},
+	Config, Error, Result,
+};
+
+/// Number of bytes of the checksum trailer.
+pub const CHECKSUM_SIZE: usize = 4;
+
+/// Reversed polynomial of CRC-32 (IEEE 802.3).
+const POLYNOMIAL: u32 = 0xEDB8_8320;
+
+/// Lookup table to process a byte at once.
+#[allow(clippy::indexing_slicing, reason = "Index is always below 256")]
+const TABLE: [u32; 256] = {
+	let mut table = [0; 256];
+	let mut index = 0;
+	while index < 256 {
+		let mut crc = index as u32;
+		let mut bit = 0;
+		while bit < 8 {
+			crc = if crc & 1 == 0 { crc >> 1 } else { (crc >> 1) ^ POLYNOMIAL };
+			bit += 1;
+		}
+		table[index] = crc;
+		index += 1;
+	}
+	table
+};
+
+/// Incremental CRC-32 (IEEE 802.3) computation.
+///
+/// ```rust
+/// use serde_brief::checksum::Crc32;
+///
+/// let mut crc = Crc32::new();
+/// crc.update(b"1234");
+/// crc.update(b"56789");
+/// assert_eq!(crc.finish(), 0xCBF4_3926);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Crc32 {
+	/// The inverted intermediate checksum.
+	state: u32,
+}
+
+impl Default for Crc32 {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Crc32 {
+	/// Start a new checksum.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { state: u32::MAX }
+	}
+
+	/// Compute the checksum of the given data at once.
+	#[must_use]
+	pub fn checksum(bytes: &[u8]) -> u32 {
+		let mut crc = Self::new();
+		crc.update(bytes);
+		crc.finish()
+	}
+
+	/// Add the given data to the checksum.
+	#[inline]
+	pub fn update(&mut self, bytes: &[u8]) {
+		for byte in bytes {
+			let index = self.state.to_le_bytes()[0] ^ byte;
+			self.state =
+				(self.state >> 8) ^ TABLE.get(usize::from(index)).copied().unwrap_or_default();
+		}
+	}
+
+	/// Get the checksum of all data so far.
+	#[must_use]
+	pub const fn finish(&self) -> u32 {
+		!self.state
+	}
+}
+
+/// [Output] wrapper that computes the checksum of all written data. [finish](Self::finish) appends
+/// the checksum.
+#[derive(Debug)]
+pub struct ChecksumOutput<O> {
+	/// The inner output.
+	inner: O,
+	/// The checksum of the data written so far.
+	crc: Crc32,
+}
+
+impl<O> ChecksumOutput<O>
+where
+	O: Output,
+{
+	/// Create a new checksum computation for data written to the given output.
+	#[must_use]
+	pub const fn new(inner: O) -> Self {
+		Self { inner, crc: Crc32::new() }
+	}
+
+	/// Get the checksum of the data written so far.
+	#[must_use]
+	pub const fn checksum(&self) -> u32 {
+		self.crc.finish()
+	}
+
+	/// Append the checksum to the output and return the inner output.
+	pub fn finish(mut self) -> Result<O> {
+		self.inner.write_all(&self.checksum().to_le_bytes())?;
+		Ok(self.inner)
+	}
+}
+
+impl<O> Output for ChecksumOutput<O>
+where
+	O: Output,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.crc.update(&[byte]);
+		self.inner.write_byte(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.crc.update(bytes);
+		self.inner.write_all(bytes)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_borrowed(&mut self, bytes: &[u8]) -> Result<()> {
+		self.crc.update(bytes);
+		self.inner.write_borrowed(bytes)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn flush(&mut self) -> Result<()> {
+		self.inner.flush()
+	}
+}
+
+/// [Input] wrapper that computes the checksum of all read data. [finish](Self::finish) reads and
+/// verifies the checksum.
+#[derive(Debug)]
+pub struct ChecksumInput<I> {
+	/// The inner input.
+	inner: I,
+	/// The checksum of the data read so far.
+	crc: Crc32,
+}
+
+impl<'de, I> ChecksumInput<I>
+where
+	I: Input<'de>,
+{
+	/// Create a new checksum computation for data read from the given input.
+	#[must_use]
+	pub const fn new(inner: I) -> Self {
+		Self { inner, crc: Crc32::new() }
+	}
+
+	/// Get the checksum of the data read so far.
+	#[must_use]
+	pub const fn checksum(&self) -> u32 {
+		self.crc.finish()
+	}
+
+	/// Read the checksum from the input and compare it to the read data. Returns the inner input
+	/// if they match or [Error::ChecksumMismatch] otherwise.
+	pub fn finish(mut self) -> Result<I> {
+		let mut trailer = [0; CHECKSUM_SIZE];
+		self.inner.read_exact(&mut trailer)?;
+		if u32::from_le_bytes(trailer) != self.checksum() {
+			return Err(Error::ChecksumMismatch);
+		}
+		Ok(self.inner)
+	}
+}
+
+impl<'de, I> Input<'de> for ChecksumInput<I>
+where
+	I: Input<'de>,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.inner.peek_byte()
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		let byte = self.inner.read_byte()?;
+		self.crc.update(&[byte]);
+		Ok(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		self.inner.read_exact(buffer)?;
+		self.crc.update(buffer);
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		let mut buffer = buffer;
+		let start = buffer.as_deref().map_or(0, |buffer| buffer.as_slice().len());
+		let borrowed = self.inner.read_bytes(len, buffer.as_deref_mut())?;
+		match borrowed {
+			Some(bytes) => self.crc.update(bytes),
+			None => self.crc.update(
+				buffer
+					.as_deref()
+					.and_then(|buffer| buffer.as_slice().get(start ..))
+					.unwrap_or_default(),
+			),
+		}
+		Ok(borrowed)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes_transient<'s, B>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut B>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		B: Buffer,
+	{
+		let bytes = self.inner.read_bytes_transient(len, buffer)?;
+		match &bytes {
+			ReadBytes::Borrowed(bytes) => self.crc.update(bytes),
+			ReadBytes::Transient(bytes) => self.crc.update(bytes),
+		}
+		Ok(bytes)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, mut len: usize) -> Result<()> {
+		// Skipped data is part of the checksum, so it needs to be read.
+		let mut discard = [0; 64];
+		while len > 0 {
+			let skip = discard.len().min(len);
+			self.read_exact(discard.get_mut(.. skip).unwrap_or_default())?;
+			len -= skip;
+		}
+		Ok(())
+	}
+
+	#[inline]
+	fn remaining_borrowed(&self) -> Option<&'de [u8]> {
+		// Includes the trailer, but only the span between two calls is of interest.
+		self.inner.remaining_borrowed()
+	}
+
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		self.inner.can_borrow()
+	}
+
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		// Peeked bytes are only added to the checksum once they are consumed.
+		self.inner.peek_chunk(n)
+	}
+
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn start_recording(&mut self) -> bool {
+		self.inner.start_recording()
+	}
+
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn finish_recording(&mut self) -> ::alloc::vec::Vec<u8> {
+		self.inner.finish_recording()
+	}
+}
+
+/// Split the checksum off the end of the data and verify it. Returns the data without checksum.
+fn verified_payload(bytes: &[u8]) -> Result<&[u8]> {
+	let (payload, trailer) =
+		bytes.split_last_chunk::<CHECKSUM_SIZE>().ok_or_else(|| Error::UnexpectedEnd)?;
+	if u32::from_le_bytes(*trailer) != Crc32::checksum(payload) {
+		return Err(Error::ChecksumMismatch);
+	}
+	Ok(payload)
+}
+
+/// Serialize a type into a slice of bytes followed by its checksum using the given configuration.
+/// Returns the slice with the serialized data and checksum. The size limit only applies to the
+/// serialized data.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_slice_crc32_with_config<'buf, T>(
+	value: &T,
+	buffer: &'buf mut [u8],
+	config: Config,
+) -> Result<&'buf mut [u8]>
+where
+	T: Serialize,
+{
+	let len = crate::to_slice_with_config(value, buffer, config)?.len();
+	let (payload, rest) = buffer.split_at_mut(len);
+	let trailer = rest.first_chunk_mut::<CHECKSUM_SIZE>().ok_or_else(|| Error::BufferTooSmall)?;
+	*trailer = Crc32::checksum(payload).to_le_bytes();
+	let end = len + CHECKSUM_SIZE;
+	buffer.get_mut(.. end).ok_or_else(|| Error::BufferTooSmall)
+}
+
+/// Serialize a type into a slice of bytes followed by its checksum. Returns the slice with the
+/// serialized data and checksum.
+pub fn to_slice_crc32<'buf, T>(value: &T, buffer: &'buf mut [u8]) -> Result<&'buf mut [u8]>
+where
+	T: Serialize,
+{
+	to_slice_crc32_with_config(value, buffer, Config::default())
+}
+
+/// Serialize a type into a [Vec] of bytes followed by its checksum using the given configuration.
+/// The [Vec] grows as needed and the checksum is appended at the end. The size limit only applies
+/// to the serialized data.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_vec_crc32_with_config<T>(value: &T, config: Config) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	let output = ChecksumOutput::new(::alloc::vec::Vec::new());
+
+	if let Some(max) = config.max_size {
+		let mut ser = crate::Serializer::new(crate::io::SizeLimit::new(output, max.into()))
+			.with_config(config);
+		value.serialize(&mut ser)?;
+		ser.into_output().into_inner().finish()
+	} else {
+		let mut ser = crate::Serializer::new(output).with_config(config);
+		value.serialize(&mut ser)?;
+		ser.into_output().finish()
+	}
+}
+
+/// Serialize a type into a [Vec] of bytes followed by its checksum.
+#[cfg(feature = "alloc")]
+pub fn to_vec_crc32<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	to_vec_crc32_with_config(value, Config::default())
+}
+
+/// Serialize a type into a [Write]r followed by its checksum using the given configuration. The
+/// size limit only applies to the serialized data.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_writer_crc32_with_config<T, W>(value: &T, writer: W, config: Config) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	let writer =
+		ChecksumOutput::new(crate::io::IoWriter::with_capacity(writer, config.writer_buffer_size));
+	let mut writer = if let Some(max) = config.max_size {
+		let mut ser = crate::Serializer::new(crate::io::SizeLimit::new(writer, max.into()))
+			.with_config(config);
+		value.serialize(&mut ser)?;
+		ser.into_output().into_inner().finish()?
+	} else {
+		let mut ser = crate::Serializer::new(writer).with_config(config);
+		value.serialize(&mut ser)?;
+		ser.into_output().finish()?
+	};
+	writer.flush()
+}
+
+/// Serialize a type into a [Write]r followed by its checksum.
+#[cfg(feature = "std")]
+pub fn to_writer_crc32<T, W>(value: &T, writer: W) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	to_writer_crc32_with_config(value, writer, Config::default())
+}
+
+/// Verify the checksum at the end of a slice of bytes and deserialize the value before it using
+/// the given configuration. Returns [Error::ChecksumMismatch] if the data is corrupted.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_slice_crc32_with_config<'de, T>(bytes: &'de [u8], config: Config) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	crate::from_slice_with_config(verified_payload(bytes)?, config)
+}
+
+/// Verify the checksum at the end of a slice of bytes and deserialize the value before it.
+/// Returns [Error::ChecksumMismatch] if the data is corrupted.
+pub fn from_slice_crc32<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	from_slice_crc32_with_config(bytes, Config::default())
+}
+
+/// Deserialize a type from a [Read]er and verify the checksum after it using the given
+/// configuration. Returns [Error::ChecksumMismatch] if the data is corrupted. The size limit only
+/// applies to the serialized data. The reader is read like in
+/// [from_reader_with_config](crate::from_reader_with_config).
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_reader_crc32_with_config<R, T>(reader: R, config: Config) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	let error_on_excess = config.error_on_excess_data;
+	let mut chunk = [0; crate::io::READER_CHUNK_SIZE];
+	let input = ChecksumInput::new(crate::io::value_reader(reader, &mut chunk, error_on_excess));
+	let mut buffer = Vec::new();
+
+	// Excess data is checked after the checksum.
+	let value_config = Config { error_on_excess_data: false, ..config };
+	let (value, mut input) = if let Some(max) = config.max_size {
+		let input = crate::io::SizeLimit::new(input, max.into());
+		let (value, input) = crate::deserialize_input(input, &mut buffer, value_config)?;
+		(value, input.into_inner().finish()?)
+	} else {
+		let (value, input) = crate::deserialize_input(input, &mut buffer, value_config)?;
+		(value, input.finish()?)
+	};
+
+	if error_on_excess {
+		input.check_excess()?;
+	}
+
+	Ok(value)
+}
+
+/// Deserialize a type from a [Read]er and verify the checksum after it. Returns
+/// [Error::ChecksumMismatch] if the data is corrupted. The reader is read ahead in chunks.
+#[cfg(feature = "std")]
+pub fn from_reader_crc32<R, T>(reader: R) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	from_reader_crc32_with_config(reader, Config::default())
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, clippy::indexing_slicing, reason = "Tests")]
+
+	use super::*;
+
+	#[test]
+	fn crc32_matches_reference() {
+		assert_eq!(Crc32::checksum(b""), 0);
+		assert_eq!(Crc32::checksum(b"123456789"), 0xCBF4_3926);
+		assert_eq!(Crc32::checksum(b"The quick brown fox jumps over the lazy dog"), 0x414F_A339);
+	}
+
+	#[test]
+	fn slices_roundtrip() {
+		let mut buffer = [0; 32];
+		let bytes = to_slice_crc32(&("key", 7_u8), &mut buffer).unwrap();
+		let len = bytes.len();
+		let (payload, trailer) = bytes.split_at(len - CHECKSUM_SIZE);
+		assert_eq!(trailer, Crc32::checksum(payload).to_le_bytes());
+		let value: (&str, u8) = from_slice_crc32(&buffer[.. len]).unwrap();
+		assert_eq!(value, ("key", 7));
+
+		// No space for the checksum.
+		let mut buffer = [0; 6];
+		let result = to_slice_crc32(&("key", 7_u8), &mut buffer);
+		assert!(matches!(result, Err(Error::BufferTooSmall)));
+	}
+
+	#[test]
+	fn flipped_bits_are_detected() {
+		let mut buffer = [0; 32];
+		let len = to_slice_crc32(&(1_u32, "data", true), &mut buffer).unwrap().len();
+		for bit in 0 .. len * 8 {
+			let mut corrupted = buffer;
+			corrupted[bit / 8] ^= 1 << (bit % 8);
+			let result = from_slice_crc32::<(u32, &str, bool)>(&corrupted[.. len]);
+			assert!(matches!(result, Err(Error::ChecksumMismatch)), "bit {bit}: {result:?}");
+		}
+	}
+
+	#[test]
+	fn empty_payload() {
+		assert!(matches!(from_slice_crc32::<()>(&[]), Err(Error::UnexpectedEnd)));
+		assert!(matches!(from_slice_crc32::<()>(&[0; 3]), Err(Error::UnexpectedEnd)));
+		// Valid checksum of no data, but no value.
+		assert!(matches!(from_slice_crc32::<()>(&[0; 4]), Err(Error::UnexpectedEnd)));
+		assert!(matches!(from_slice_crc32::<()>(&[0, 0, 0, 1]), Err(Error::ChecksumMismatch)));
+
+		let input = ChecksumInput::new([0_u8; 4].as_slice());
+		assert!(input.finish().unwrap().is_empty());
+		let mut buffer = [0xFF; 4];
+		let output = ChecksumOutput::new(buffer.as_mut_slice());
+		assert!(output.finish().unwrap().is_empty());
+		assert_eq!(buffer, [0; 4]);
+	}
+
+	#[test]
+	fn wrappers_compute_checksums() {
+		let mut buffer = [0; 32];
+		let mut output = ChecksumOutput::new(crate::SliceOutput::new(&mut buffer));
+		output.write_byte(1).unwrap();
+		output.write_all(&[2, 3]).unwrap();
+		output.write_borrowed(&[4; 10]).unwrap();
+		let bytes: &[u8] = output.finish().unwrap().into_written();
+		let len = bytes.len();
+		assert_eq!(len, 13 + CHECKSUM_SIZE);
+
+		let mut input = ChecksumInput::new(bytes);
+		assert_eq!(input.peek_chunk(3), Some([1, 2, 3].as_slice()));
+		assert_eq!(input.peek_byte().unwrap(), 1);
+		assert_eq!(input.read_byte().unwrap(), 1);
+		let mut read = [0; 2];
+		input.read_exact(&mut read).unwrap();
+		assert_eq!(read, [2, 3]);
+		assert_eq!(input.read_bytes::<()>(4, None).unwrap(), Some([4; 4].as_slice()));
+		input.skip_bytes(6).unwrap();
+		assert!(input.finish().unwrap().is_empty());
+
+		let mut input = ChecksumInput::new(bytes);
+		input.skip_bytes(12).unwrap();
+		assert!(matches!(input.finish(), Err(Error::ChecksumMismatch)));
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn readers_and_writers_roundtrip() {
+		let mut stream = Vec::new();
+		to_writer_crc32(&vec![0x1234_u16; 100], &mut stream).unwrap();
+		assert_eq!(stream, to_vec_crc32(&vec![0x1234_u16; 100]).unwrap());
+		let value: Vec<u16> = from_reader_crc32(stream.as_slice()).unwrap();
+		assert_eq!(value, [0x1234; 100]);
+
+		stream.push(0);
+		let config = Config { error_on_excess_data: false, ..Default::default() };
+		let _: Vec<u16> = from_reader_crc32_with_config(stream.as_slice(), config).unwrap();
+		let result = from_reader_crc32::<_, Vec<u16>>(stream.as_slice());
+		assert!(matches!(result, Err(Error::ExcessData { .. })));
+
+		stream.pop();
+		stream[11] ^= 0x10;
+		let result = from_reader_crc32::<_, Vec<u16>>(stream.as_slice());
+		assert!(matches!(result, Err(Error::ChecksumMismatch)));
+	}
+
+	#[cfg(feature = "heapless")]
+	#[test]
+	fn heapless_output() {
+		use ::serde::Serialize;
+
+		let mut ser = crate::Serializer::new(ChecksumOutput::new(::heapless::Vec::<u8, 9>::new()));
+		"abc".serialize(&mut ser).unwrap();
+		let bytes = ser.into_output().finish().unwrap();
+		assert_eq!(bytes.len(), 5 + CHECKSUM_SIZE);
+		let value: &str = from_slice_crc32(&bytes).unwrap();
+		assert_eq!(value, "abc");
+	}
+}
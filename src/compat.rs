@@ -0,0 +1,289 @@
+//! Check whether data written with an old version of a type can be decoded with a new version.
+//!
+//! Both versions are described by their [Schema], e.g. recorded with
+//! [schema_of](crate::schema::schema_of) and stored alongside the code. [check] compares them and
+//! reports every change that breaks decoding old data with the new type, so that CI can verify
+//! that fields and variants were only added or re-ordered in compatible ways.
+//!
+//! The schemas are recorded from samples, so the check has the same limits: only the seen
+//! variants are compared and [Schema::Any] is compatible with everything. Whether a field has a
+//! `#[serde(default)]` is not part of the schema, so fields that are new and not optional are
+//! reported as [Incompatibility::MissingField]. Ignore those that have a default.
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use serde_brief::{compat, schema::schema_of};
+//!
+//! #[derive(Serialize)]
+//! struct V1 {
+//! 	id: u32,
+//! }
+//!
+//! #[derive(Serialize)]
+//! struct V2 {
+//! 	id: u32,
+//! 	comment: Option<String>,
+//! 	count: u32,
+//! }
+//!
+//! let old = schema_of(&V1 { id: 1 }).unwrap();
+//! let new = schema_of(&V2 { id: 1, comment: None, count: 0 }).unwrap();
+//! let incompatibilities = compat::check(&old, &new);
+//! assert_eq!(incompatibilities.len(), 1);
+//! assert_eq!(incompatibilities[0].to_string(), "Field `count` at `V2` is missing in old data");
+//! ```
+
+use ::alloc::{
+	borrow::ToOwned,
+	format,
+	string::{String, ToString},
+	vec::Vec,
+};
+
+use crate::{
+	schema::{Member, Schema},
+	Config,
+};
+
+/// A change that breaks decoding old data with the new type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Incompatibility {
+	/// A field of the new type is not in the old data and is not optional. This is fine if the
+	/// field has a `#[serde(default)]`.
+	MissingField {
+		/// Path to the struct.
+		path: String,
+		/// Name of the field.
+		field: String,
+	},
+	/// A value has a layout that cannot be decoded from the old layout.
+	TypeChanged {
+		/// Path to the value.
+		path: String,
+		/// Old layout.
+		old: Schema,
+		/// New layout.
+		new: Schema,
+	},
+	/// A variant of the old data does not exist in the new enum, i.e. it was removed or renamed
+	/// (or its index was removed with `use_indices`).
+	UnknownVariant {
+		/// Path to the enum.
+		path: String,
+		/// Name of the old variant.
+		variant: String,
+	},
+	/// A field or variant moved to a different index, so with `use_indices` old data is decoded
+	/// into a different member.
+	IndexChanged {
+		/// Path to the struct or enum.
+		path: String,
+		/// Name of the field or variant.
+		name: String,
+		/// Old index.
+		old: u32,
+		/// New index.
+		new: u32,
+	},
+}
+
+impl ::core::fmt::Display for Incompatibility {
+	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		match self {
+			Self::MissingField { path, field } => {
+				write!(f, "Field `{field}` at `{path}` is missing in old data")
+			}
+			Self::TypeChanged { path, old, new } => {
+				write!(f, "Type at `{path}` changed from {} to {}", describe(old), describe(new))
+			}
+			Self::UnknownVariant { path, variant } => {
+				write!(f, "Variant `{variant}` at `{path}` does not exist anymore")
+			}
+			Self::IndexChanged { path, name, old, new } => {
+				write!(f, "Index of `{name}` at `{path}` changed from {old} to {new}")
+			}
+		}
+	}
+}
+
+/// Short description of the layout for error messages.
+const fn describe(schema: &Schema) -> &'static str {
+	match schema {
+		Schema::Any => "any",
+		Schema::Null => "null",
+		Schema::Bool => "bool",
+		Schema::UInt => "unsigned integer",
+		Schema::Int => "signed integer",
+		Schema::F16 | Schema::F32 | Schema::F64 | Schema::F128 => "float",
+		Schema::Bytes => "bytes",
+		Schema::String => "string",
+		Schema::Option(_) => "option",
+		Schema::Seq(_) => "sequence",
+		Schema::Tuple(_) => "tuple",
+		Schema::Map { .. } => "map",
+		Schema::Struct { .. } => "struct",
+		Schema::Enum { .. } => "enum",
+	}
+}
+
+/// Find the changes that break decoding data of the `old` schema with the `new` schema, with
+/// field and variant names as keys.
+#[must_use]
+pub fn check(old: &Schema, new: &Schema) -> Vec<Incompatibility> {
+	check_with_config(old, new, Config::default())
+}
+
+/// Find the changes that break decoding data of the `old` schema with the `new` schema, with the
+/// keys of the given configuration.
+#[must_use]
+pub fn check_with_config(old: &Schema, new: &Schema, config: Config) -> Vec<Incompatibility> {
+	let mut checker = Checker {
+		use_indices: config.use_indices,
+		lenient_numbers: config.lenient_numbers,
+		found: Vec::new(),
+	};
+	let path = match new {
+		Schema::Struct { name, .. } | Schema::Enum { name, .. } => name.clone(),
+		_ => String::new(),
+	};
+	checker.compare(&path, old, new);
+	checker.found
+}
+
+/// State of a check.
+struct Checker {
+	/// Whether members are identified by their index instead of their name.
+	use_indices: bool,
+	/// Whether integers of the other signedness and booleans as integers are accepted.
+	lenient_numbers: bool,
+	/// The incompatibilities found so far.
+	found: Vec<Incompatibility>,
+}
+
+impl Checker {
+	/// Compare the layouts at the given path.
+	fn compare(&mut self, path: &str, old: &Schema, new: &Schema) {
+		match (old, new) {
+			(Schema::Any, _) | (_, Schema::Any) => {}
+			(old, new) if old == new => {}
+			(
+				Schema::F16 | Schema::F32 | Schema::F64 | Schema::F128,
+				Schema::F16 | Schema::F32 | Schema::F64 | Schema::F128,
+			)
+			| (Schema::String, Schema::Bytes)
+			| (Schema::Null, Schema::Option(_)) => {}
+			(Schema::UInt, Schema::Int)
+			| (Schema::Int, Schema::UInt)
+			| (Schema::Bool, Schema::UInt | Schema::Int)
+				if self.lenient_numbers => {}
+			(Schema::Option(old), Schema::Option(new)) => self.compare(path, old, new),
+			(old, Schema::Option(new)) => self.compare(path, old, new),
+			(Schema::Seq(old), Schema::Seq(new)) => self.compare(&join(path, "[]"), old, new),
+			(Schema::Tuple(old), Schema::Seq(new)) => {
+				for (index, old) in old.iter().enumerate() {
+					self.compare(&join(path, &index.to_string()), old, new);
+				}
+			}
+			(Schema::Tuple(old), Schema::Tuple(new)) if old.len() == new.len() => {
+				for (index, (old, new)) in old.iter().zip(new).enumerate() {
+					self.compare(&join(path, &index.to_string()), old, new);
+				}
+			}
+			(
+				Schema::Map { key: old_key, value: old },
+				Schema::Map { key: new_key, value: new },
+			) => {
+				self.compare(&join(path, "{key}"), old_key, new_key);
+				self.compare(&join(path, "{value}"), old, new);
+			}
+			(Schema::Struct { fields: old, .. }, Schema::Struct { fields: new, .. }) => {
+				self.compare_fields(path, old, new);
+			}
+			(Schema::Enum { variants: old, .. }, Schema::Enum { variants: new, .. }) => {
+				self.compare_variants(path, old, new);
+			}
+			(old, new) => self.found.push(Incompatibility::TypeChanged {
+				path: path.to_owned(),
+				old: old.clone(),
+				new: new.clone(),
+			}),
+		}
+	}
+
+	/// Find the member of the new version that old data of the member is decoded into, or report
+	/// a moved index.
+	fn matching<'a>(&mut self, path: &str, old: &Member, new: &'a [Member]) -> Option<&'a Member> {
+		if !self.use_indices {
+			return new.iter().find(|member| member.name == old.name);
+		}
+
+		let matching = new.iter().find(|member| member.index == old.index);
+		if let Some(moved) = new.iter().find(|member| member.name == old.name) {
+			if moved.index != old.index {
+				self.found.push(Incompatibility::IndexChanged {
+					path: path.to_owned(),
+					name: old.name.clone(),
+					old: old.index,
+					new: moved.index,
+				});
+				return None;
+			}
+		}
+		matching
+	}
+
+	/// Compare the fields of structs or struct variants.
+	fn compare_fields(&mut self, path: &str, old: &[Member], new: &[Member]) {
+		for old_field in old {
+			// Unknown fields are ignored when decoding, so removed fields are fine.
+			if let Some(new_field) = self.matching(path, old_field, new) {
+				self.compare(&join(path, &new_field.name), &old_field.schema, &new_field.schema);
+			}
+		}
+
+		for new_field in new {
+			let exists = old.iter().any(|old_field| {
+				if self.use_indices {
+					old_field.index == new_field.index
+				} else {
+					old_field.name == new_field.name
+				}
+			});
+			if !exists && !matches!(new_field.schema, Schema::Option(_)) {
+				self.found.push(Incompatibility::MissingField {
+					path: path.to_owned(),
+					field: new_field.name.clone(),
+				});
+			}
+		}
+	}
+
+	/// Compare the variants of enums.
+	fn compare_variants(&mut self, path: &str, old: &[Member], new: &[Member]) {
+		for old_variant in old {
+			let before = self.found.len();
+			match self.matching(path, old_variant, new) {
+				Some(new_variant) => self.compare(
+					&join(path, &new_variant.name),
+					&old_variant.schema,
+					&new_variant.schema,
+				),
+				// Moved variants were reported already.
+				None if self.found.len() > before => {}
+				None => self.found.push(Incompatibility::UnknownVariant {
+					path: path.to_owned(),
+					variant: old_variant.name.clone(),
+				}),
+			}
+		}
+	}
+}
+
+/// Append a segment to the path.
+fn join(path: &str, segment: &str) -> String {
+	if path.is_empty() {
+		segment.to_owned()
+	} else {
+		format!("{path}.{segment}")
+	}
+}
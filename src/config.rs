@@ -4,20 +4,153 @@ use ::core::num::NonZeroUsize;
 
 // TODO:
 // - Add max sequence/map size limit.
-// - Add max-depth limit.
+/// Default maximum nesting depth when deserializing.
+pub(crate) const DEFAULT_MAX_DEPTH: usize = 128;
+
 /// Configuration for (de-)serialization.
 #[derive(Debug, PartialEq, Eq, Clone, Copy, Hash)]
 pub struct Config {
-	/// Whether to use indices instead of strings as keys for struct-fields/enum-variants.
+	/// Whether to use indices instead of strings as keys for struct-fields/enum-variants. Keys of
+	/// a [Value](crate::value::Value) are not re-keyed, they are written as they are. Fields that
+	/// are only skipped on one side, with `#[serde(skip_serializing)]` or
+	/// `#[serde(skip_deserializing)]`, shift the indices of the following fields and are not
+	/// supported, while `skip` and `skip_serializing_if` work.
 	pub use_indices: bool,
 	/// Whether to return an error if there is excess data in the input.
 	pub error_on_excess_data: bool,
 	/// Maximum number of bytes to read or write, in any limit.
 	pub max_size: Option<NonZeroUsize>,
+	/// What to leave in the output when serializing fails, e.g. because `max_size` is reached.
+	/// See [LimitBehavior] for which outputs can be rolled back.
+	pub on_limit: LimitBehavior,
+	/// Maximum nesting depth of sequences, maps and `Some` markers when deserializing or
+	/// validating, so that malicious input cannot overflow the stack. Deeply nested types need
+	/// roughly a few kilobytes of stack per level, so only raise it with enough stack available.
+	pub max_depth: usize,
+	/// Number of bytes to buffer when serializing into a writer, before writing them to the
+	/// writer. 0 disables buffering.
+	pub writer_buffer_size: usize,
+	/// Whether to require all keys of a struct to be of the same kind when deserializing. The
+	/// first key locks whether fields are identified by strings or indices, so that documents
+	/// mixing both are rejected with [Error::WrongType](crate::Error::WrongType).
+	pub strict_keys: bool,
+	/// Whether to match string keys of structs against the field names before validating them as
+	/// UTF-8. Matching keys are visited as the static field name, which speeds up deserializing
+	/// many small structs. Other keys, e.g. aliases and unknown fields, are handled as usual after
+	/// comparing them to all field names.
+	pub intern_field_names: bool,
+	/// Whether to accept integers of the other signedness when deserializing, as long as the value
+	/// fits into the target type. This allows reading old data after changing a field from `u32`
+	/// to `i64`, for example. Deserializing from a [Value](crate::value::Value) always accepts
+	/// both.
+	///
+	/// Integers also accept finite, integral floats that fit, e.g. whole numbers from JSON, with
+	/// `Float128` only if it is exact as `f64`, and floats accept integers that they represent
+	/// exactly. Precision is never lost
+	/// silently, e.g. `2^53 + 1` is rejected for `f64`, but floats that were rounded before
+	/// serialization look integral just the same.
+	///
+	/// Booleans are accepted as integers `0` and `1` of any width, and integers as booleans if
+	/// they are `0` or `1`. Other integers are rejected. Without this, booleans and integers are
+	/// never mixed.
+	pub lenient_numbers: bool,
+	/// Whether to write a marker before the value of `Some` options, so that nested options like
+	/// `Some(None)` keep their meaning. The deserializer accepts both encodings, but older
+	/// versions cannot read data with the marker. See the format specification for more info.
+	pub explicit_option: bool,
+	/// Whether to write unsigned integers up to 63 inline in the type byte, so that they take a
+	/// single byte instead of two, e.g. small numbers and field or variant indices. The
+	/// deserializer accepts both encodings, but older versions cannot read data with inline
+	/// integers. See the format specification for more info.
+	pub compact_ints: bool,
+	/// Whether to write sequences, tuples and tuple structs with their number of elements up
+	/// front instead of an end designator, if the length is known. Deserializers then get an
+	/// exact size hint, so that e.g. vectors are allocated once. The deserializer accepts both
+	/// encodings, but older versions cannot read length-prefixed sequences. See the format
+	/// specification for more info.
+	pub length_prefixed_seqs: bool,
+	/// Whether the (de-)serializers report to be human-readable, which makes types like
+	/// `uuid::Uuid` or `chrono::DateTime` choose between a compact and a textual representation.
+	/// `None` keeps the default of not being human-readable, for bytes and for
+	/// [Value](crate::value::Value) alike, so that data round-trips through values unchanged.
+	pub human_readable: Option<bool>,
+	/// Names of the tag fields of internally tagged enums (`#[serde(tag = "...")]`), so that they
+	/// work with `use_indices`. When the first field of a struct has one of these names, it is
+	/// encoded with its name and does not count towards the indices of the other fields, like
+	/// serde expects when deserializing the enum's content. Make sure that no other structs start
+	/// with a field of these names, as they could not be decoded.
+	pub tag_fields: &'static [&'static str],
+	/// Whether to sort the entries of maps and structs by their serialized keys, so that the
+	/// output is deterministic regardless of field declaration or map iteration order. Entries
+	/// are buffered per map before writing them, so this is ignored without the **alloc**
+	/// feature.
+	pub canonical: bool,
+	/// Whether to reject maps and structs that contain the same key more than once when
+	/// deserializing, e.g. to prevent smuggling a second value past a check of the first one. Keys
+	/// are compared by their encoded bytes. By default, it depends on the deserialized type how
+	/// duplicates are handled, e.g. the last value wins in a `HashMap`. Without the **alloc**
+	/// feature, keys cannot be compared, so maps return
+	/// [Error::CannotRecord](crate::Error::CannotRecord).
+	pub reject_duplicate_map_keys: bool,
+	/// Whether to write each string key of maps and structs, e.g. field names, only once and
+	/// refer to it by its number in a string table afterwards. This makes repetitive data almost
+	/// as small as with `use_indices`, while staying self-describing. The deserializer accepts
+	/// both encodings, but older versions cannot read data with a string table. It is not applied
+	/// with `canonical`, as sorting would reorder the definitions. Ignored without the **alloc**
+	/// feature, as the table needs to be kept. See the format specification for more info.
+	pub key_dictionary: bool,
+	/// Whether to return [Error::DuplicateSerializedKey](crate::Error::DuplicateSerializedKey)
+	/// when serializing a map or struct with the same key more than once, e.g. when fields
+	/// collide after `#[serde(rename)]` or `#[serde(flatten)]`. Deserializers would otherwise
+	/// pick one of the values, depending on the type. Keys are compared by their encoded bytes,
+	/// which are recorded per map, so this is ignored without the **alloc** feature.
+	pub detect_duplicate_keys: bool,
 }
 
 impl Default for Config {
 	fn default() -> Self {
-		Self { use_indices: false, error_on_excess_data: true, max_size: None }
+		Self {
+			use_indices: false,
+			error_on_excess_data: true,
+			max_size: None,
+			on_limit: LimitBehavior::Error,
+			max_depth: DEFAULT_MAX_DEPTH,
+			writer_buffer_size: 8 * 1024,
+			strict_keys: false,
+			intern_field_names: false,
+			lenient_numbers: false,
+			explicit_option: false,
+			compact_ints: false,
+			length_prefixed_seqs: false,
+			human_readable: None,
+			tag_fields: &[],
+			canonical: false,
+			reject_duplicate_map_keys: false,
+			key_dictionary: false,
+			detect_duplicate_keys: false,
+		}
 	}
 }
+
+/// Behavior of the serialization functions when serializing fails, e.g. when hitting `max_size`
+/// of the [Config].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub enum LimitBehavior {
+	/// Return the error and leave the partially written data in the output.
+	#[default]
+	Error,
+	/// Return the error and leave in-memory outputs unchanged:
+	/// - [to_vec_in](crate::to_vec_in_with_config) truncates the `Vec` to the length it started
+	///   with.
+	/// - [to_slice](crate::to_slice_with_config) computes the size first and only writes to the
+	///   buffer if the value fits, which serializes the value twice.
+	/// - Functions returning a new output, e.g. [`to_heapless_vec`](crate::to_heapless_vec), do
+	///   not return partial data either way.
+	///
+	/// Writers cannot be rolled back. [to_writer](crate::to_writer_with_config) discards the data
+	/// it has buffered, so that the writer is unchanged if nothing was written to it yet. If bytes
+	/// already reached the writer when the limit was hit, it returns
+	/// [Error::PartialWrite](crate::Error::PartialWrite) with their number instead, so that the
+	/// caller can resynchronize the stream.
+	ErrorAfterRollback,
+}
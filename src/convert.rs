@@ -0,0 +1,496 @@
+//! **std** + **json**: Streaming conversion between JSON and the binary format, e.g. for tools
+//! inspecting message dumps.
+//!
+//! Values are transcoded from the deserializer to the serializer directly, without building a
+//! [Value](crate::value::Value) first, so only single strings and bytes are held in memory. The
+//! conversion rules are the same as for [converting Values to JSON](crate::value::json):
+//! - Bytes become arrays of numbers by default, or base64 strings with [JsonBytes::Base64]. JSON
+//!   strings and arrays never turn back into bytes, but arrays of numbers are accepted when
+//!   deserializing bytes, so typed byte fields can still be read after a round trip.
+//! - Integers outside of the JSON number range (`i64::MIN ..= u64::MAX`) fail to convert by
+//!   default, or become decimal strings.
+//! - Floats become numbers, NaN and infinities become `null`.
+//! - Map keys must be strings, numbers or bools. Numbers and bools are converted to strings.
+//!
+//! ```rust
+//! let json = r#"{"name":"Holla","tags":["a","b"],"nested":{"value":null}}"#;
+//! let mut bytes = Vec::new();
+//! serde_brief::convert::json_to_brief(json.as_bytes(), &mut bytes, Default::default()).unwrap();
+//! assert!(bytes.len() < json.len());
+//!
+//! let mut output = Vec::new();
+//! serde_brief::convert::brief_to_json(bytes.as_slice(), &mut output, false).unwrap();
+//! assert_eq!(output, json.as_bytes());
+//! ```
+
+use ::alloc::{string::ToString, vec::Vec};
+use ::core::{cell::Cell, convert::identity, fmt::Display};
+use ::serde::{
+	de::{self, DeserializeSeed},
+	ser::{self, Error as _, SerializeMap, SerializeSeq},
+	Serialize,
+};
+use ::std::io::{BufWriter, Read, Write};
+
+use crate::{
+	buffer::Buffer,
+	io::{self, Input, IoWriter, SizeLimit},
+	value::json::{base64, JsonBytes, JsonLargeIntegers, JsonOptions},
+	Config, Deserializer, Error, Result, Serializer,
+};
+
+/// Convert a single JSON value from the reader to the binary format, written to the writer. The
+/// reader is read byte by byte, so it is best to use a buffered reader. Trailing whitespace is
+/// allowed, other trailing data fails.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn json_to_brief<R, W>(reader: R, writer: W, config: Config) -> Result<()>
+where
+	R: Read,
+	W: Write,
+{
+	let mut json = ::serde_json::Deserializer::from_reader(reader);
+	let writer = IoWriter::with_capacity(writer, config.writer_buffer_size);
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(SizeLimit::new(writer, max.into())).with_config(config);
+		transcode(&mut json, &mut ser, JsonOptions::default())
+			.map_err(|failure| failure.into_error(json_error, identity))?;
+		json.end().map_err(json_error)?;
+		ser.flush()?;
+	} else {
+		let mut ser = Serializer::new(writer).with_config(config);
+		transcode(&mut json, &mut ser, JsonOptions::default())
+			.map_err(|failure| failure.into_error(json_error, identity))?;
+		json.end().map_err(json_error)?;
+		ser.flush()?;
+	}
+	Ok(())
+}
+
+/// Convert a single value of the binary format from the reader to JSON, written to the writer,
+/// using the default [JsonOptions] and [Config]. The JSON is indented if `pretty` is set.
+pub fn brief_to_json<R, W>(reader: R, writer: W, pretty: bool) -> Result<()>
+where
+	R: Read,
+	W: Write,
+{
+	brief_to_json_with(reader, writer, pretty, JsonOptions::default(), Config::default())
+}
+
+/// Convert a single value of the binary format from the reader to JSON, written to the writer,
+/// using the given options. The JSON is indented if `pretty` is set. The configuration limits the
+/// input, e.g. via `max_depth` and `max_size`. Like for [from_reader](crate::from_reader), the
+/// reader is only read up to the end of the value if `error_on_excess_data` is off, so that
+/// following values can still be read from it.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(reader, writer)))]
+pub fn brief_to_json_with<R, W>(
+	reader: R,
+	writer: W,
+	pretty: bool,
+	options: JsonOptions,
+	config: Config,
+) -> Result<()>
+where
+	R: Read,
+	W: Write,
+{
+	let mut chunk = [0; io::READER_CHUNK_SIZE];
+	let reader = io::value_reader(reader, &mut chunk, config.error_on_excess_data);
+	let mut writer = BufWriter::new(writer);
+	if let Some(max) = config.max_size {
+		input_to_json(SizeLimit::new(reader, max.into()), &mut writer, pretty, options, config)?;
+	} else {
+		input_to_json(reader, &mut writer, pretty, options, config)?;
+	}
+	writer.flush()?;
+	Ok(())
+}
+
+/// Convert the value of the input to JSON, written to the writer.
+fn input_to_json<'de, I, W>(
+	input: I,
+	writer: W,
+	pretty: bool,
+	options: JsonOptions,
+	config: Config,
+) -> Result<()>
+where
+	I: Input<'de>,
+	W: Write,
+{
+	let mut buffer = Vec::new();
+	if config.reject_duplicate_map_keys {
+		// Readers cannot lend their data, so the keys are recorded to compare them.
+		let input = io::RecordingInput::new(input);
+		let de = Deserializer::new(input).with_config(config).with_buffer(&mut buffer);
+		return deserializer_to_json(de, writer, pretty, options);
+	}
+	let de = Deserializer::new(input).with_config(config).with_buffer(&mut buffer);
+	deserializer_to_json(de, writer, pretty, options)
+}
+
+/// Convert the value of the deserializer to JSON, written to the writer.
+fn deserializer_to_json<'de, I, B, W>(
+	mut de: Deserializer<I, B>,
+	writer: W,
+	pretty: bool,
+	options: JsonOptions,
+) -> Result<()>
+where
+	I: Input<'de>,
+	B: Buffer,
+	W: Write,
+{
+	if pretty {
+		transcode(&mut de, &mut ::serde_json::Serializer::pretty(writer), options)
+	} else {
+		transcode(&mut de, &mut ::serde_json::Serializer::new(writer), options)
+	}
+	.map_err(|failure| failure.into_error(identity, json_error))?;
+	de.end()
+}
+
+/// Convert a JSON error, keeping IO errors and the unexpected end of the input intact.
+fn json_error(error: ::serde_json::Error) -> Error {
+	if error.is_io() {
+		Error::Io(error.into())
+	} else if error.is_eof() {
+		Error::UnexpectedEnd
+	} else {
+		Error::Message(error.to_string())
+	}
+}
+
+/// Error of the transcoding, coming from either the deserializer or the serializer.
+#[derive(Debug)]
+enum Failure<D, S> {
+	/// The deserializer failed.
+	Deserializer(D),
+	/// The serializer failed.
+	Serializer(S),
+}
+
+impl<D, S> Failure<D, S> {
+	/// Convert the error of the side that failed.
+	fn into_error(self, de: impl FnOnce(D) -> Error, ser: impl FnOnce(S) -> Error) -> Error {
+		match self {
+			Failure::Deserializer(error) => de(error),
+			Failure::Serializer(error) => ser(error),
+		}
+	}
+}
+
+/// Abort the other side with the message of the error, keeping the original error for the caller.
+/// The errors of both sides are passed on unchanged this way, instead of being wrapped into
+/// messages at every nesting level.
+fn abort<E, F>(failed: &Cell<Option<F>>, error: F) -> E
+where
+	E: de::Error,
+	F: Display,
+{
+	let abort = E::custom(&error);
+	failed.set(Some(error));
+	abort
+}
+
+/// Forward the result of the serializer, aborting the deserializer if it failed.
+fn forward<T, E, F>(failed: &Cell<Option<F>>, result: Result<T, F>) -> Result<T, E>
+where
+	E: de::Error,
+	F: Display,
+{
+	result.map_err(|error| abort(failed, error))
+}
+
+/// Transcode the next value of the deserializer into the serializer.
+fn transcode<'de, D, S>(
+	deserializer: D,
+	serializer: S,
+	options: JsonOptions,
+) -> Result<S::Ok, Failure<D::Error, S::Error>>
+where
+	D: de::Deserializer<'de>,
+	S: ser::Serializer,
+{
+	let failed = Cell::new(None);
+	deserializer
+		.deserialize_any(Visitor { serializer, failed: &failed, options })
+		.map_err(|error| failed.take().map_or(Failure::Deserializer(error), Failure::Serializer))
+}
+
+/// Let the serializer serialize the next value of the deserializer. Errors of the deserializer are
+/// returned unchanged, errors of the serializer are kept in `failed`.
+fn serialize_next<'de, D, T, F>(
+	deserializer: D,
+	options: JsonOptions,
+	failed: &Cell<Option<F>>,
+	serialize: impl FnOnce(&Transcoder<'_, D, D::Error>) -> Result<T, F>,
+) -> Result<T, D::Error>
+where
+	D: de::Deserializer<'de>,
+	F: Display,
+{
+	let failed_de = Cell::new(None);
+	serialize(&Transcoder::new(deserializer, &failed_de, options))
+		.map_err(|error| failed_de.take().unwrap_or_else(|| abort(failed, error)))
+}
+
+/// Serializes the next value of the contained deserializer, which can only happen once.
+struct Transcoder<'a, D, E> {
+	/// The deserializer, taken when serializing.
+	deserializer: Cell<Option<D>>,
+	/// The error of the deserializer, if it failed.
+	failed: &'a Cell<Option<E>>,
+	/// Options for the conversion to JSON.
+	options: JsonOptions,
+}
+
+impl<'a, D, E> Transcoder<'a, D, E> {
+	/// Create a new transcoder for the next value of the deserializer.
+	const fn new(deserializer: D, failed: &'a Cell<Option<E>>, options: JsonOptions) -> Self {
+		Self { deserializer: Cell::new(Some(deserializer)), failed, options }
+	}
+}
+
+impl<'de, D, E> Serialize for Transcoder<'_, D, E>
+where
+	D: de::Deserializer<'de, Error = E>,
+	E: Display,
+{
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ser::Serializer,
+	{
+		let deserializer = self
+			.deserializer
+			.take()
+			.ok_or_else(|| S::Error::custom("value was already transcoded"))?;
+		transcode(deserializer, serializer, self.options).map_err(|failure| match failure {
+			Failure::Serializer(error) => error,
+			Failure::Deserializer(error) => {
+				let abort = S::Error::custom(&error);
+				self.failed.set(Some(error));
+				abort
+			}
+		})
+	}
+}
+
+/// Visitor serializing everything it visits.
+struct Visitor<'a, S>
+where
+	S: ser::Serializer,
+{
+	/// The serializer to forward the values to.
+	serializer: S,
+	/// The error of the serializer, if it failed.
+	failed: &'a Cell<Option<S::Error>>,
+	/// Options for the conversion to JSON.
+	options: JsonOptions,
+}
+
+impl<S> Visitor<'_, S>
+where
+	S: ser::Serializer,
+{
+	/// Serialize an integer outside of the JSON number range as configured.
+	fn large_integer<E, T>(self, int: T) -> Result<S::Ok, E>
+	where
+		E: de::Error,
+		T: Display,
+	{
+		match self.options.large_integers {
+			JsonLargeIntegers::String => forward(self.failed, self.serializer.collect_str(&int)),
+			JsonLargeIntegers::Error => {
+				Err(E::custom(Error::NotJsonCompatible("integer is out of the JSON number range")))
+			}
+		}
+	}
+}
+
+impl<'de, S> de::Visitor<'de> for Visitor<'_, S>
+where
+	S: ser::Serializer,
+{
+	type Value = S::Ok;
+
+	fn expecting(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		formatter.write_str("any value")
+	}
+
+	fn visit_bool<E: de::Error>(self, v: bool) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_bool(v))
+	}
+
+	fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_i64(v))
+	}
+
+	fn visit_i128<E: de::Error>(self, v: i128) -> Result<Self::Value, E> {
+		match i64::try_from(v) {
+			Ok(v) => self.visit_i64(v),
+			Err(_) => self.large_integer(v),
+		}
+	}
+
+	fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_u64(v))
+	}
+
+	fn visit_u128<E: de::Error>(self, v: u128) -> Result<Self::Value, E> {
+		match u64::try_from(v) {
+			Ok(v) => self.visit_u64(v),
+			Err(_) => self.large_integer(v),
+		}
+	}
+
+	fn visit_f32<E: de::Error>(self, v: f32) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_f32(v))
+	}
+
+	fn visit_f64<E: de::Error>(self, v: f64) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_f64(v))
+	}
+
+	fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_str(v))
+	}
+
+	fn visit_bytes<E: de::Error>(self, v: &[u8]) -> Result<Self::Value, E> {
+		let result = match self.options.bytes {
+			JsonBytes::Array => self.serializer.serialize_bytes(v),
+			JsonBytes::Base64 => self.serializer.serialize_str(&base64(v)),
+		};
+		forward(self.failed, result)
+	}
+
+	fn visit_none<E: de::Error>(self) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_none())
+	}
+
+	fn visit_some<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		serialize_next(deserializer, self.options, self.failed, |value| {
+			self.serializer.serialize_some(value)
+		})
+	}
+
+	fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+		forward(self.failed, self.serializer.serialize_unit())
+	}
+
+	fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		transcode(deserializer, self.serializer, self.options).map_err(|failure| match failure {
+			Failure::Deserializer(error) => error,
+			Failure::Serializer(error) => abort(self.failed, error),
+		})
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::SeqAccess<'de>,
+	{
+		let mut target = forward(self.failed, self.serializer.serialize_seq(seq.size_hint()))?;
+		while seq
+			.next_element_seed(ElementSeed {
+				target: &mut target,
+				failed: self.failed,
+				options: self.options,
+			})?
+			.is_some()
+		{}
+		forward(self.failed, target.end())
+	}
+
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::MapAccess<'de>,
+	{
+		let mut target = forward(self.failed, self.serializer.serialize_map(map.size_hint()))?;
+		while map
+			.next_key_seed(EntrySeed {
+				target: &mut target,
+				failed: self.failed,
+				key: true,
+				options: self.options,
+			})?
+			.is_some()
+		{
+			map.next_value_seed(EntrySeed {
+				target: &mut target,
+				failed: self.failed,
+				key: false,
+				options: self.options,
+			})?;
+		}
+		forward(self.failed, target.end())
+	}
+}
+
+/// Seed transcoding a sequence element into the sequence serializer.
+struct ElementSeed<'a, T>
+where
+	T: SerializeSeq,
+{
+	/// The sequence serializer.
+	target: &'a mut T,
+	/// The error of the serializer, if it failed.
+	failed: &'a Cell<Option<T::Error>>,
+	/// Options for the conversion to JSON.
+	options: JsonOptions,
+}
+
+impl<'de, T> DeserializeSeed<'de> for ElementSeed<'_, T>
+where
+	T: SerializeSeq,
+{
+	type Value = ();
+
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		serialize_next(deserializer, self.options, self.failed, |value| {
+			self.target.serialize_element(value)
+		})
+	}
+}
+
+/// Seed transcoding a map key or value into the map serializer.
+struct EntrySeed<'a, T>
+where
+	T: SerializeMap,
+{
+	/// The map serializer.
+	target: &'a mut T,
+	/// The error of the serializer, if it failed.
+	failed: &'a Cell<Option<T::Error>>,
+	/// Whether this is the key or the value.
+	key: bool,
+	/// Options for the conversion to JSON.
+	options: JsonOptions,
+}
+
+impl<'de, T> DeserializeSeed<'de> for EntrySeed<'_, T>
+where
+	T: SerializeMap,
+{
+	type Value = ();
+
+	fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: de::Deserializer<'de>,
+	{
+		serialize_next(deserializer, self.options, self.failed, |value| {
+			if self.key {
+				self.target.serialize_key(value)
+			} else {
+				self.target.serialize_value(value)
+			}
+		})
+	}
+}
@@ -4,37 +4,116 @@
 	allow(clippy::used_underscore_binding, reason = "Only used in tracing::instrument")
 )]
 
+#[cfg(feature = "alloc")]
+use ::alloc::{boxed::Box, rc::Rc, vec::Vec};
 use ::core::str;
-use ::serde::de::{IntoDeserializer, Unexpected, Visitor};
+use ::serde::de::{
+	value::BorrowedStrDeserializer, Deserialize, IntoDeserializer, Unexpected, Visitor,
+};
 
 use crate::{
-	buffer::Buffer,
-	format::{Type, VarInt},
-	io::Input,
-	Error, Result,
+	buffer::{Buffer, InlineBuffer},
+	format::{decode_length, decode_unsigned, Type, VarInt},
+	io::{Input, ReadBytes},
+	Config, Error, Result,
 };
 
 /// The deserializer for the binary format.
-#[derive(Debug)]
+///
+/// It can be cloned if the input and buffer can be, e.g. to save a checkpoint before parsing
+/// speculatively and to rewind to it afterwards. See [fork](Self::fork) for a cheaper way
+/// without cloning the buffer.
+#[derive(Debug, Clone)]
 pub struct Deserializer<I, B = ()> {
 	/// The input to read from.
 	input: I,
 	/// The buffer/scratch to read data to temporarily.
 	buffer: Option<B>,
+	/// Small buffer for short data (e.g. field names), so that the buffer is not touched for them.
+	inline: InlineBuffer,
+	/// The configuration, of which the deserialization flags are used.
+	config: Config,
+	/// Number of sequences, maps and `Some` markers that may still be nested.
+	remaining_depth: usize,
+	/// Field names of the struct whose key is currently deserialized.
+	fields: &'static [&'static str],
+	/// The last deserialized struct key, to look up its field name for errors.
+	key: FieldKey,
+	/// Copy of the last short string key of a struct, see [FieldKey::Name].
+	key_name: InlineBuffer,
+	/// Number of string keys copied into `key_name` so far.
+	key_names: usize,
+	/// Strings of the string table, in the order of their definitions, or `None` if there are
+	/// none. Shared with forks and clones, see [fork](Self::fork).
+	#[cfg(feature = "alloc")]
+	#[allow(clippy::rc_buffer, reason = "Definitions are pushed in place when not shared")]
+	strings: Option<Rc<Vec<Box<str>>>>,
+	/// Kind of the struct fields and enum variants seen so far.
+	key_mode: Option<KeyMode>,
+}
+
+/// Whether struct fields and enum variants were identified by their index or their name, see
+/// [Deserializer::detected_key_mode].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KeyMode {
+	/// All identifiers were indices, as written with `use_indices`.
+	Indices,
+	/// All identifiers were names, as written without `use_indices`.
+	Names,
+	/// Both indices and names were seen.
+	Mixed,
+}
+
+/// Struct key that was deserialized, so that the field name can be looked up when its value fails.
+/// Looking it up for every key would slow down the common case.
+#[derive(Debug, Clone, Copy)]
+enum FieldKey {
+	/// No key or a key that is not a struct field.
+	None,
+	/// Index of the field.
+	Index(usize),
+	/// Short field name, copied into the deserializer's `key_name` as the copy with the given
+	/// number. Once a later key overwrites the copy, the field name is no longer available.
+	Name(usize),
+}
+
+impl KeyMode {
+	/// Combine the modes of two parts of a document.
+	#[must_use]
+	pub const fn merge(self, other: Self) -> Self {
+		match (self, other) {
+			(Self::Indices, Self::Indices) => Self::Indices,
+			(Self::Names, Self::Names) => Self::Names,
+			_ => Self::Mixed,
+		}
+	}
 }
 
 impl<I> Deserializer<I, ()> {
 	/// Create a new deserializer from the given input, without a scratch/buffer. When reading from
 	/// a non-borrowed source (e.g. a reader), set a read-buffer with
-	/// [with_buffer](Self::with_buffer) or deserialization will fail.
-	#[expect(clippy::missing_const_for_fn, reason = "Probably not const in the future")]
+	/// [with_buffer](Self::with_buffer) or deserialization of strings and bytes longer than 32
+	/// bytes will fail.
 	#[must_use]
 	pub fn new<'de>(input: I) -> Self
 	where
 		// Same bounds as `serde::Deserializer` impl.
 		I: Input<'de>,
 	{
-		Self { input, buffer: None }
+		Self {
+			input,
+			buffer: None,
+			inline: InlineBuffer::new(),
+			config: Config::default(),
+			remaining_depth: crate::config::DEFAULT_MAX_DEPTH,
+			fields: &[],
+			key: FieldKey::None,
+			key_name: InlineBuffer::new(),
+			key_names: 0,
+			#[cfg(feature = "alloc")]
+			strings: None,
+			key_mode: None,
+		}
 	}
 
 	/// Create a new deserializer from the given input, without a scratch/buffer. Reading from a
@@ -45,12 +124,121 @@ impl<I> Deserializer<I, ()> {
 		// Same bounds as `serde::Deserializer` impl.
 		B: Buffer,
 	{
-		Deserializer { input: self.input, buffer: Some(buffer) }
+		Deserializer {
+			input: self.input,
+			buffer: Some(buffer),
+			inline: self.inline,
+			config: self.config,
+			remaining_depth: self.remaining_depth,
+			fields: self.fields,
+			key: self.key,
+			key_name: self.key_name,
+			key_names: self.key_names,
+			#[cfg(feature = "alloc")]
+			strings: self.strings,
+			key_mode: self.key_mode,
+		}
 	}
 }
 
 impl<I, B> Deserializer<I, B> {
-	/// Consume the deserializer and return the input.
+	/// Use the deserialization flags of the given configuration, e.g. `strict_keys`, `max_depth`
+	/// and `error_on_excess_data`. Settings that only affect serialization or the free functions,
+	/// like `max_size`, are ignored.
+	#[must_use]
+	pub const fn with_config(mut self, config: Config) -> Self {
+		self.remaining_depth = config.max_depth;
+		self.config = config;
+		self
+	}
+
+	/// Set whether all keys of a struct must be of the same type. When enabled, the first key of a
+	/// struct decides whether fields are identified by strings or indices, and other keys are
+	/// rejected. Disabled by default.
+	#[must_use]
+	pub const fn strict_keys(mut self, strict_keys: bool) -> Self {
+		self.config.strict_keys = strict_keys;
+		self
+	}
+
+	/// Set whether to match string keys of structs against the field names before validating
+	/// them as UTF-8. Matching keys are visited as the static field name. Disabled by default.
+	#[must_use]
+	pub const fn intern_field_names(mut self, intern_field_names: bool) -> Self {
+		self.config.intern_field_names = intern_field_names;
+		self
+	}
+
+	/// Set whether types are told that the format is human-readable, so that they expect their
+	/// textual representation. Must match the setting used for serialization. Disabled by default.
+	#[must_use]
+	pub const fn human_readable(mut self, human_readable: bool) -> Self {
+		self.config.human_readable = Some(human_readable);
+		self
+	}
+
+	/// Set whether to accept integers of the other signedness, e.g. unsigned integers for `i64`.
+	/// The value must still fit into the target type. Disabled by default.
+	#[must_use]
+	pub const fn lenient_numbers(mut self, lenient_numbers: bool) -> Self {
+		self.config.lenient_numbers = lenient_numbers;
+		self
+	}
+
+	/// Set the maximum nesting depth of sequences, maps and `Some` markers, so that malicious input
+	/// cannot overflow the stack. Defaults to 128.
+	#[must_use]
+	pub const fn max_depth(mut self, max_depth: usize) -> Self {
+		self.config.max_depth = max_depth;
+		self.remaining_depth = max_depth;
+		self
+	}
+
+	/// Set whether to reject maps that contain the same key more than once. Keys are compared by
+	/// their encoded bytes, so e.g. a field name and the field's index are not considered equal.
+	/// Disabled by default, where it depends on the deserialized type how duplicates are handled.
+	///
+	/// Inputs that cannot lend their data like slices do, e.g. readers, need to record the keys.
+	/// Wrap them in a [RecordingInput](crate::io::RecordingInput), otherwise maps return
+	/// [Error::CannotRecord], like all maps without the **alloc** feature.
+	#[must_use]
+	pub const fn reject_duplicate_map_keys(mut self, reject_duplicate_map_keys: bool) -> Self {
+		self.config.reject_duplicate_map_keys = reject_duplicate_map_keys;
+		self
+	}
+
+	/// Whether the struct fields and enum variants deserialized so far were identified by their
+	/// index or their name, or `None` if there were none. This tells how the data was written, so
+	/// that it can be written back the same way, e.g. with `use_indices` for [KeyMode::Indices].
+	/// Keys of other maps, e.g. a `HashMap`, are not identifiers and not taken into account.
+	///
+	/// ```rust
+	/// use serde::{Deserialize, Serialize};
+	/// use serde_brief::{de::KeyMode, Config, Deserializer};
+	///
+	/// #[derive(Serialize, Deserialize)]
+	/// struct Point {
+	/// 	x: i32,
+	/// 	y: i32,
+	/// }
+	///
+	/// let config = Config { use_indices: true, ..Default::default() };
+	/// let bytes = serde_brief::to_slice_with_config(&Point { x: 1, y: 2 }, &mut [0; 16], config)
+	/// 	.unwrap()
+	/// 	.to_vec();
+	/// let mut de = Deserializer::new(bytes.as_slice());
+	/// let _point = Point::deserialize(&mut de).unwrap();
+	/// assert_eq!(de.detected_key_mode(), Some(KeyMode::Indices));
+	/// ```
+	#[must_use]
+	pub const fn detected_key_mode(&self) -> Option<KeyMode> {
+		self.key_mode
+	}
+
+	/// Consume the deserializer and return the input. It is positioned directly after the
+	/// deserialized data, e.g. slices are the remaining bytes, so that trailing data can be read
+	/// from it. Inputs with read-ahead buffers, like [IoReader](crate::io::IoReader), might have
+	/// consumed more from their underlying reader.
 	#[inline]
 	pub fn into_input(self) -> I {
 		self.input
@@ -61,6 +249,68 @@ impl<I, B> Deserializer<I, B> {
 	pub fn into_parts(self) -> (I, Option<B>) {
 		(self.input, self.buffer)
 	}
+
+	/// Create a deserializer at the current position of the input, with the same configuration
+	/// and string table, but without a buffer. The input is cloned, which is cheap for slices, so
+	/// the fork can try to parse a value and be dropped on failure, leaving this deserializer
+	/// untouched. This avoids the buffering of `#[serde(untagged)]` enums, which makes it faster
+	/// when the first attempt usually succeeds.
+	///
+	/// The string table of data written with `key_dictionary` is shared with the fork. It is only
+	/// copied when one of them defines a new string while the other one is still alive, e.g. when
+	/// the fork reads a definition. [try_parse](Self::try_parse) hands the table over to the fork
+	/// instead, so it never copies it.
+	///
+	/// ```rust
+	/// use serde::{Deserialize, Serialize};
+	/// use serde_brief::Deserializer;
+	///
+	/// #[derive(Serialize, Deserialize)]
+	/// struct Point {
+	/// 	x: i32,
+	/// 	y: i32,
+	/// }
+	///
+	/// #[derive(Serialize, Deserialize)]
+	/// struct Named {
+	/// 	name: String,
+	/// }
+	///
+	/// # #[cfg(feature = "alloc")] {
+	/// let bytes = serde_brief::to_vec(&Named { name: "origin".to_owned() }).unwrap();
+	/// let mut de = Deserializer::new(bytes.as_slice());
+	/// let mut fork = de.fork();
+	/// let name = match Point::deserialize(&mut fork) {
+	/// 	Ok(_) => {
+	/// 		de = fork;
+	/// 		None
+	/// 	}
+	/// 	Err(_) => Some(Named::deserialize(&mut de).unwrap().name),
+	/// };
+	/// de.end().unwrap();
+	/// assert_eq!(name.as_deref(), Some("origin"));
+	/// # }
+	/// ```
+	#[must_use]
+	pub fn fork(&self) -> Deserializer<I>
+	where
+		I: Clone,
+	{
+		Deserializer {
+			input: self.input.clone(),
+			buffer: None,
+			inline: InlineBuffer::new(),
+			config: self.config,
+			remaining_depth: self.remaining_depth,
+			fields: self.fields,
+			key: self.key,
+			key_name: self.key_name,
+			key_names: self.key_names,
+			#[cfg(feature = "alloc")]
+			strings: self.strings.clone(),
+			key_mode: self.key_mode,
+		}
+	}
 }
 
 impl<'de, I, B> Deserializer<I, B>
@@ -68,6 +318,120 @@ where
 	I: Input<'de>,
 	B: Buffer,
 {
+	/// Finish deserialization and check that the input is at its end, unless
+	/// `error_on_excess_data` is disabled in the [Config]. Call this after deserializing the value
+	/// to get the same checks as the free functions.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	pub fn end(&mut self) -> Result<()> {
+		if self.config.error_on_excess_data {
+			self.input.check_excess()
+		} else {
+			Ok(())
+		}
+	}
+
+	/// Try to deserialize a value of the type on a [fork](Self::fork). On success, this
+	/// deserializer continues after the value, otherwise it is left untouched, so that another type
+	/// can be tried. Manually trying the alternatives like this is faster than `#[serde(untagged)]`
+	/// enums when the first alternatives usually match, as untagged enums buffer every value before
+	/// trying them. The fork has no buffer, so this is meant for slice inputs.
+	///
+	/// ```rust
+	/// use serde::{Deserialize, Serialize};
+	/// use serde_brief::Deserializer;
+	///
+	/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+	/// struct Point {
+	/// 	x: i32,
+	/// 	y: i32,
+	/// }
+	///
+	/// #[derive(Debug, PartialEq)]
+	/// enum Shape<'a> {
+	/// 	Point(Point),
+	/// 	Label(&'a str),
+	/// }
+	///
+	/// fn parse_shape<'de>(de: &mut Deserializer<&'de [u8]>) -> serde_brief::Result<Shape<'de>> {
+	/// 	if let Ok(point) = de.try_parse() {
+	/// 		return Ok(Shape::Point(point));
+	/// 	}
+	/// 	Ok(Shape::Label(de.try_parse()?))
+	/// }
+	///
+	/// # #[cfg(feature = "alloc")] {
+	/// let mut bytes = serde_brief::to_vec(&Point { x: 1, y: 2 }).unwrap();
+	/// bytes.extend(serde_brief::to_vec(&"label").unwrap());
+	/// let mut de = Deserializer::new(bytes.as_slice());
+	/// assert_eq!(parse_shape(&mut de).unwrap(), Shape::Point(Point { x: 1, y: 2 }));
+	/// assert_eq!(parse_shape(&mut de).unwrap(), Shape::Label("label"));
+	/// de.end().unwrap();
+	/// # }
+	/// ```
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	pub fn try_parse<T>(&mut self) -> Result<T>
+	where
+		I: Clone,
+		T: Deserialize<'de>,
+	{
+		let mut fork = self.fork();
+		// Hand the string table over, so that new definitions do not copy it. On failure, the
+		// fork's definitions are removed again.
+		#[cfg(feature = "alloc")]
+		let strings_len = {
+			self.strings = None;
+			fork.table().len()
+		};
+		let value = match T::deserialize(&mut fork) {
+			Ok(value) => value,
+			Err(err) => {
+				#[cfg(feature = "alloc")]
+				{
+					if let Some(strings) = fork.strings.as_mut() {
+						Rc::make_mut(strings).truncate(strings_len);
+					}
+					self.strings = fork.strings;
+				}
+				return Err(err);
+			}
+		};
+		self.input = fork.input;
+		self.fields = fork.fields;
+		self.key = fork.key;
+		self.key_name = fork.key_name;
+		self.key_names = fork.key_names;
+		self.key_mode = fork.key_mode;
+		#[cfg(feature = "alloc")]
+		{
+			self.strings = fork.strings;
+		}
+		Ok(value)
+	}
+
+	/// Look up the field name of a struct key among the struct's fields.
+	fn field_name(&self, key: FieldKey, fields: &'static [&'static str]) -> Option<&'static str> {
+		match key {
+			FieldKey::None => None,
+			FieldKey::Index(index) => fields.get(index).copied(),
+			FieldKey::Name(copy) if copy == self.key_names => {
+				fields.iter().find(|f| f.as_bytes() == self.key_name.as_slice()).copied()
+			}
+			FieldKey::Name(_) => None,
+		}
+	}
+
+	/// Record the type of a struct field or enum variant identifier for
+	/// [detected_key_mode](Self::detected_key_mode).
+	#[inline]
+	fn record_identifier(&mut self, t: Type) {
+		let mode = match t {
+			Type::UnsignedInt => KeyMode::Indices,
+			Type::String | Type::StringDef | Type::StringRef => KeyMode::Names,
+			_ => return,
+		};
+		self.key_mode = Some(self.key_mode.map_or(mode, |seen| seen.merge(mode)));
+	}
+
 	/// Reset the buffer, if available.
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
@@ -77,10 +441,18 @@ where
 		}
 	}
 
-	/// Get the buffer as slice.
+	/// Read a number of bytes, either borrowed from the input or only valid until the next read,
+	/// e.g. copied to a buffer. Short data is copied to the inline buffer instead of the buffer.
 	#[inline]
-	fn buffer_slice(&self) -> Result<&[u8]> {
-		Ok(self.buffer.as_ref().ok_or_else(|| Error::BufferTooSmall)?.as_slice())
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	fn read_bytes_or_copy<'s>(&'s mut self, len: usize) -> Result<ReadBytes<'de, 's>> {
+		if len <= InlineBuffer::CAPACITY {
+			self.inline.clear();
+			return self.input.read_bytes_transient(len, Some(&mut self.inline));
+		}
+
+		self.reset_buffer();
+		self.input.read_bytes_transient(len, self.buffer.as_mut())
 	}
 
 	/// Read a number of bytes, regardless of lifetime.
@@ -90,17 +462,191 @@ where
 	where
 		'de: 's,
 	{
-		self.reset_buffer();
-		if let Some(data) = self.input.read_bytes(len, self.buffer.as_mut())? {
-			Ok(data)
+		match self.read_bytes_or_copy(len)? {
+			ReadBytes::Borrowed(data) => Ok(data),
+			ReadBytes::Transient(data) => Ok(data),
+		}
+	}
+
+	/// Read the type byte of an unsigned integer and decode its value, which might be inline.
+	#[inline]
+	fn read_unsigned<T: VarInt>(&mut self) -> Result<T> {
+		let byte = self.input.read_byte()?;
+		decode_unsigned(byte, &mut self.input)
+	}
+
+	/// Read the next element of a sequence of bytes that are encoded as unsigned integers. The
+	/// number of remaining elements is given for length-prefixed sequences, otherwise the end
+	/// designator is consumed. Returns `None` at the end of the sequence.
+	fn read_byte_element(&mut self, remaining: &mut Option<usize>) -> Result<Option<u8>> {
+		match remaining {
+			Some(0) => return Ok(None),
+			Some(remaining) => *remaining -= 1,
+			None => {
+				if Type::try_from(self.input.peek_byte()?)? == Type::SeqEnd {
+					_ = self.input.read_byte()?;
+					return Ok(None);
+				}
+			}
+		}
+
+		let t = Type::try_from(self.input.peek_byte()?)?;
+		if t != Type::UnsignedInt {
+			return Err(Error::WrongType(t, &[Type::UnsignedInt]));
+		}
+		Ok(Some(self.read_unsigned()?))
+	}
+
+	/// The strings of the string table.
+	#[cfg(feature = "alloc")]
+	fn table(&self) -> &[Box<str>] {
+		self.strings.as_deref().map_or(&[], Vec::as_slice)
+	}
+
+	/// Read a string of the string table after its type byte. Definitions are added to the table.
+	#[cfg(feature = "alloc")]
+	fn read_table_string(&mut self, t: Type) -> Result<&str> {
+		let index = if t == Type::StringDef {
+			let len = decode_length(&mut self.input)?;
+			let (ReadBytes::Borrowed(bytes) | ReadBytes::Transient(bytes)) =
+				self.read_bytes_or_copy(len)?;
+			let s = Box::from(str::from_utf8(bytes)?);
+			// Copies the table only if it is shared with a fork.
+			let strings = Rc::make_mut(self.strings.get_or_insert_with(Rc::default));
+			strings.push(s);
+			strings.len() - 1
 		} else {
-			self.buffer_slice()
+			decode_length(&mut self.input)?
+		};
+		self.table().get(index).map(|s| &**s).ok_or_else(|| Error::UnknownStringRef(index))
+	}
+
+	/// Convert an encoded key of the string table to a plain string, so that the same keys have
+	/// the same bytes.
+	#[cfg(feature = "alloc")]
+	fn plain_key(&self, mut encoded: Vec<u8>) -> Result<Vec<u8>> {
+		let Some((&byte, mut rest)) = encoded.split_first() else {
+			return Ok(encoded);
+		};
+		if byte == u8::from(Type::StringRef) {
+			let index = decode_length(&mut rest)?;
+			let s = self.table().get(index).ok_or_else(|| Error::UnknownStringRef(index))?;
+			let mut plain = Vec::with_capacity(s.len() + 4);
+			plain.push(Type::String.into());
+			crate::format::encode_length(s.len(), &mut plain)?;
+			plain.extend_from_slice(s.as_bytes());
+			return Ok(plain);
 		}
+		if byte == u8::from(Type::StringDef) {
+			if let Some(first) = encoded.first_mut() {
+				*first = Type::String.into();
+			}
+		}
+		Ok(encoded)
 	}
 
-	/// Deserialize a usize/isize and visit it, regardless of size.
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
-	fn deserialize_ptr<V>(&mut self, visitor: V) -> Result<V::Value>
+	/// Run the closure one nesting level deeper, or fail if the depth limit is reached.
+	#[inline]
+	fn nested<T>(&mut self, f: impl FnOnce(&mut Self) -> Result<T>) -> Result<T> {
+		self.remaining_depth =
+			self.remaining_depth.checked_sub(1).ok_or_else(|| Error::DepthLimitReached)?;
+		let result = f(self);
+		self.remaining_depth += 1;
+		result
+	}
+
+	/// Skip the next value in the input like [skip_value](Self::skip_value) and return its encoded
+	/// bytes. These are only available if the input is a contiguous slice, e.g. `&[u8]`, otherwise
+	/// the value is skipped and `None` is returned.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	pub fn skip_value_span(&mut self) -> Result<Option<&'de [u8]>> {
+		let before = self.input.remaining_borrowed();
+		self.skip_value()?;
+		let after = self.input.remaining_borrowed();
+		Ok(before.zip(after).and_then(|(before, after)| {
+			before.len().checked_sub(after.len()).and_then(|len| before.get(.. len))
+		}))
+	}
+
+	/// Skip the next value in the input, including all nested values of sequences and maps. The
+	/// wire format is walked without visiting anything, so strings are neither copied nor checked
+	/// to be valid UTF-8.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
+	pub fn skip_value(&mut self) -> Result<()> {
+		let byte = self.input.read_byte()?;
+		let t = Type::try_from(byte)?;
+		match t {
+			Type::Null | Type::BooleanFalse | Type::BooleanTrue => {}
+			Type::UnsignedInt | Type::SignedInt if byte == u8::from(t) => {
+				crate::validate::skip_var_int(&mut self.input)?;
+			}
+			// Inline integers have no further bytes.
+			Type::UnsignedInt | Type::SignedInt => {}
+			Type::Float16 => self.input.skip_bytes(2)?,
+			Type::Float32 => self.input.skip_bytes(4)?,
+			Type::Float64 => self.input.skip_bytes(8)?,
+			Type::Float128 => self.input.skip_bytes(16)?,
+			Type::Bytes | Type::String => {
+				let len = decode_length(&mut self.input)?;
+				self.input.skip_bytes(len)?;
+			}
+			// Definitions are needed for later references, even if skipped.
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => _ = self.read_table_string(t)?,
+			#[cfg(not(feature = "alloc"))]
+			Type::StringDef => {
+				let len = decode_length(&mut self.input)?;
+				self.input.skip_bytes(len)?;
+			}
+			#[cfg(not(feature = "alloc"))]
+			Type::StringRef => _ = decode_length(&mut self.input)?,
+			Type::SeqStart => self.nested(|de| {
+				while de.input.peek_byte()? != u8::from(Type::SeqEnd) {
+					de.skip_value()?;
+				}
+				_ = de.input.read_byte()?;
+				Ok(())
+			})?,
+			Type::SeqFixed => {
+				let len = decode_length(&mut self.input)?;
+				self.nested(|de| (0 .. len).try_for_each(|_| de.skip_value()))?;
+			}
+			Type::MapStart => self.nested(|de| {
+				while de.input.peek_byte()? != u8::from(Type::MapEnd) {
+					de.skip_value()?;
+					de.skip_value()?;
+				}
+				_ = de.input.read_byte()?;
+				Ok(())
+			})?,
+			Type::Some => self.nested(Self::skip_value)?,
+			Type::SeqEnd | Type::MapEnd => {
+				return Err(Error::WrongType(t, crate::validate::VALUE_TYPES));
+			}
+		}
+		Ok(())
+	}
+
+	/// Read the next value without visiting it and return its encoded bytes. The input needs to
+	/// record, e.g. a [RecordingInput](crate::io::RecordingInput).
+	#[cfg(feature = "std")]
+	pub(crate) fn read_value_bytes(&mut self) -> Result<::alloc::vec::Vec<u8>> {
+		if !self.input.start_recording() {
+			return Err(Error::CannotRecord);
+		}
+		let result = self.skip_value();
+		let bytes = self.input.finish_recording();
+		result.map(|()| bytes)
+	}
+
+	/// Deserialize a map and visit its entries. For structs, the name and field names are given,
+	/// which are used to add context to errors and to check the keys with `strict_keys`.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
+	fn deserialize_map_entries<V>(
+		&mut self,
+		visitor: V,
+		structure: Option<(&'static str, &'static [&'static str])>,
+	) -> Result<V::Value>
 	where
 		V: Visitor<'de>,
 	{
@@ -111,31 +657,51 @@ where
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
-			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let number = usize::decode(&mut self.input)?;
-				match size_of::<usize>() {
-					1 => visitor.visit_u8(number as u8),
-					2 => visitor.visit_u16(number as u16),
-					4 => visitor.visit_u32(number as u32),
-					8 => visitor.visit_u64(number as u64),
-					16 => visitor.visit_u128(number as u128),
-					_ => unreachable!("usize must have one of these sizes"),
-				}
-			}
-			Type::SignedInt => {
+			Type::MapStart => {
 				_ = self.input.read_byte()?;
-				let number = isize::decode(&mut self.input)?;
-				match size_of::<isize>() {
-					1 => visitor.visit_i8(number as i8),
-					2 => visitor.visit_i16(number as i16),
-					4 => visitor.visit_i32(number as i32),
-					8 => visitor.visit_i64(number as i64),
-					16 => visitor.visit_i128(number as i128),
-					_ => unreachable!("isize must have one of these sizes"),
+				let check_keys = self.config.strict_keys && structure.is_some();
+				let value = self.nested(|de| {
+					let mut map = MapDeserializer {
+						de,
+						check_keys,
+						key_type: None,
+						structure,
+						key: FieldKey::None,
+						index_keys: false,
+						name_keys: false,
+						index_layout: false,
+						access_failed: false,
+						#[cfg(feature = "alloc")]
+						seen_keys: ::alloc::collections::BTreeSet::new(),
+					};
+					visitor.visit_map(&mut map).map_err(|err| {
+						// Visitors that fail on their own after seeing only index keys in a map
+						// that is not a known struct most likely looked for field names, which
+						// serde does for flattened structs and internally tagged enums. The
+						// visitor's error is kept, as the index keys might have been intended.
+						if map.index_keys
+							&& !map.name_keys && !map.access_failed
+							&& map.structure.is_none()
+						{
+							Error::IndicesNotSupportedHere {
+								#[cfg(feature = "alloc")]
+								cause: ::alloc::boxed::Box::new(err),
+							}
+						} else {
+							err
+						}
+					})
+				})?;
+
+				let byte = self.input.read_byte()?;
+				let t = Type::try_from(byte)?;
+				if t == Type::MapEnd {
+					Ok(value)
+				} else {
+					Err(Error::WrongType(t, &[Type::MapEnd]))
 				}
 			}
-			_ => Err(Error::WrongType(t, &[Type::UnsignedInt, Type::SignedInt])),
+			_ => Err(Error::WrongType(t, &[Type::MapStart])),
 		}
 	}
 
@@ -152,7 +718,13 @@ where
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
-			// Add Float16 once stable.
+			Type::Float16 => {
+				_ = self.input.read_byte()?;
+				let mut bytes = [0; 2];
+				self.input.read_exact(&mut bytes)?;
+				let value = crate::format::f16_bits_to_f32(u16::from_le_bytes(bytes));
+				visitor.visit_f32(value)
+			}
 			Type::Float32 => {
 				_ = self.input.read_byte()?;
 				let mut bytes = [0; 4];
@@ -167,7 +739,14 @@ where
 				let value = f64::from_le_bytes(bytes);
 				visitor.visit_f64(value)
 			}
-			// Add Float128 once stable.
+			Type::Float128 => {
+				_ = self.input.read_byte()?;
+				let mut bytes = [0; 16];
+				self.input.read_exact(&mut bytes)?;
+				// Loses precision, but there is no stable `f128` yet.
+				let value = crate::format::f128_bits_to_f64(u128::from_le_bytes(bytes));
+				visitor.visit_f64(value)
+			}
 			_ => Err(Error::WrongType(
 				t,
 				&[Type::Float16, Type::Float32, Type::Float64, Type::Float128],
@@ -175,6 +754,102 @@ where
 		}
 	}
 
+	/// Deserialize a float that represents an integer exactly as integer, see
+	/// [visit_integral_float].
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn deserialize_float_as_int<V>(&mut self, visitor: V) -> Result<V::Value>
+	where
+		V: Visitor<'de>,
+	{
+		let t = Type::try_from(self.input.read_byte()?)?;
+		let value = match t {
+			Type::Float16 => {
+				let mut bytes = [0; 2];
+				self.input.read_exact(&mut bytes)?;
+				f64::from(crate::format::f16_bits_to_f32(u16::from_le_bytes(bytes)))
+			}
+			Type::Float32 => {
+				let mut bytes = [0; 4];
+				self.input.read_exact(&mut bytes)?;
+				f64::from(f32::from_le_bytes(bytes))
+			}
+			Type::Float64 => {
+				let mut bytes = [0; 8];
+				self.input.read_exact(&mut bytes)?;
+				f64::from_le_bytes(bytes)
+			}
+			Type::Float128 => {
+				let mut bytes = [0; 16];
+				self.input.read_exact(&mut bytes)?;
+				let bits = u128::from_le_bytes(bytes);
+				let value = crate::format::f128_bits_to_f64(bits);
+				if crate::format::f64_to_f128_bits(value) != bits {
+					// Rounded, so it is rejected as float.
+					return visitor.visit_f64(value);
+				}
+				value
+			}
+			_ => {
+				return Err(Error::WrongType(
+					t,
+					&[Type::Float16, Type::Float32, Type::Float64, Type::Float128],
+				))
+			}
+		};
+		visit_integral_float(value, visitor)
+	}
+
+	/// Deserialize an integer as float, if the float represents it exactly, see
+	/// [visit_exact_float].
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
+	fn deserialize_int_as_float<V>(
+		&mut self,
+		single_precision: bool,
+		visitor: V,
+	) -> Result<V::Value>
+	where
+		V: Visitor<'de>,
+	{
+		let byte = self.input.read_byte()?;
+		let t = Type::try_from(byte)?;
+		let value = match t {
+			Type::UnsignedInt => unsigned_to_f64(decode_unsigned(byte, &mut self.input)?),
+			Type::SignedInt => signed_to_f64(i128::decode(&mut self.input)?),
+			_ => return Err(Error::WrongType(t, &[Type::UnsignedInt, Type::SignedInt])),
+		};
+		visit_exact_float(value, single_precision, visitor)
+	}
+
+	/// Deserialize a boolean as integer `0` or `1`.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn deserialize_bool_as_int<V>(&mut self, visitor: V) -> Result<V::Value>
+	where
+		V: Visitor<'de>,
+	{
+		let t = Type::try_from(self.input.read_byte()?)?;
+		match t {
+			Type::BooleanFalse => visitor.visit_u8(0),
+			Type::BooleanTrue => visitor.visit_u8(1),
+			_ => Err(Error::WrongType(t, &[Type::BooleanFalse, Type::BooleanTrue])),
+		}
+	}
+
+	/// Deserialize an integer as boolean, see [visit_int_as_bool].
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn deserialize_int_as_bool<V>(&mut self, visitor: V) -> Result<V::Value>
+	where
+		V: Visitor<'de>,
+	{
+		let byte = self.input.read_byte()?;
+		let t = Type::try_from(byte)?;
+		let value = match t {
+			Type::UnsignedInt => Some(decode_unsigned(byte, &mut self.input)?),
+			Type::SignedInt => u128::try_from(i128::decode(&mut self.input)?).ok(),
+			_ => return Err(Error::WrongType(t, &[Type::UnsignedInt, Type::SignedInt])),
+		};
+		visit_int_as_bool(value, visitor)
+	}
+
 	/// Deserialize an unsigned integer.
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn deserialize_unsigned_int<V>(&mut self, visitor: V) -> Result<V::Value>
@@ -189,24 +864,47 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u128::decode(&mut self.input)?;
-				if value <= u8::MAX as u128 {
-					visitor.visit_u8(value as u8)
-				} else if value <= u16::MAX as u128 {
-					visitor.visit_u16(value as u16)
-				} else if value <= u32::MAX as u128 {
-					visitor.visit_u32(value as u32)
-				} else if value <= u64::MAX as u128 {
-					visitor.visit_u64(value as u64)
-				} else {
-					visitor.visit_u128(value)
-				}
+				let value = self.read_unsigned::<u128>()?;
+				visit_unsigned(value, visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
 	}
 
+	/// Deserialize a var-int for [Value](crate::value::Value), which keeps integers exceeding 128
+	/// bits as their encoding, see
+	/// [BIG_INTEGER_NEWTYPE_NAME](crate::format::BIG_INTEGER_NEWTYPE_NAME).
+	#[cfg(feature = "big-integers")]
+	#[inline(never)] // Keep the stack frame of recursive deserialization small.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
+	fn deserialize_value_integer<V>(&mut self, t: Type, visitor: V) -> Result<V::Value>
+	where
+		V: Visitor<'de>,
+	{
+		let mut encoded = Vec::new();
+		encoded.push(self.input.read_byte()?);
+		loop {
+			let byte = self.input.read_byte()?;
+			encoded.push(byte);
+			if byte & 0x80 == 0 {
+				break;
+			}
+			if encoded.len() > crate::value::BigInteger::MAX_VARINT_BYTES {
+				return Err(Error::VarIntTooLarge);
+			}
+		}
+
+		let varint = encoded.get(1 ..).unwrap_or_default();
+		if t == Type::SignedInt {
+			if let Ok(value) = i128::decode(&mut &*varint) {
+				return visit_signed(value, visitor);
+			}
+		} else if let Ok(value) = u128::decode(&mut &*varint) {
+			return visit_unsigned(value, visitor);
+		}
+		visitor.visit_map(BigIntegerMapDeserializer::new(&encoded))
+	}
+
 	/// Deserialize a signed integer.
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn deserialize_signed_int<V>(&mut self, visitor: V) -> Result<V::Value>
@@ -220,28 +918,212 @@ where
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
-			#[allow(clippy::cast_lossless, reason = "We won't change it")]
 			Type::SignedInt => {
 				_ = self.input.read_byte()?;
 				let value = i128::decode(&mut self.input)?;
-				if (i8::MIN as i128 ..= i8::MAX as i128).contains(&value) {
-					visitor.visit_i8(value as i8)
-				} else if (i16::MIN as i128 ..= i16::MAX as i128).contains(&value) {
-					visitor.visit_i16(value as i16)
-				} else if (i32::MIN as i128 ..= i32::MAX as i128).contains(&value) {
-					visitor.visit_i32(value as i32)
-				} else if (i64::MIN as i128 ..= i64::MAX as i128).contains(&value) {
-					visitor.visit_i64(value as i64)
-				} else {
-					visitor.visit_i128(value)
-				}
+				visit_signed(value, visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
 }
 
-impl<'a, 'de, I, B> ::serde::Deserializer<'de> for &'a mut Deserializer<I, B>
+/// Map with a single entry, the bits newtype name of a float as key and its raw bits as value, for
+/// [Value](crate::value::Value). See [VALUE_NEWTYPE_NAME](crate::format::VALUE_NEWTYPE_NAME).
+#[derive(Debug)]
+pub(crate) struct FloatBitsMapDeserializer<T> {
+	/// The bits newtype name of the float.
+	name: &'static str,
+	/// The raw bits, until the value was visited.
+	bits: Option<T>,
+}
+
+impl<T> FloatBitsMapDeserializer<T> {
+	/// Create a map deserializer for the float bits.
+	pub(crate) const fn new(name: &'static str, bits: T) -> Self {
+		Self { name, bits: Some(bits) }
+	}
+}
+
+impl<'de, T> ::serde::de::MapAccess<'de> for FloatBitsMapDeserializer<T>
+where
+	T: IntoDeserializer<'de, Error>,
+{
+	type Error = Error;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+	where
+		K: ::serde::de::DeserializeSeed<'de>,
+	{
+		if self.bits.is_none() {
+			return Ok(None);
+		}
+		seed.deserialize(BorrowedStrDeserializer::new(self.name)).map(Some)
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+	where
+		V: ::serde::de::DeserializeSeed<'de>,
+	{
+		let bits = self.bits.take().ok_or_else(|| Error::UnexpectedEnd)?;
+		seed.deserialize(bits.into_deserializer())
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(usize::from(self.bits.is_some()))
+	}
+}
+
+/// Map with a single entry, [BIG_INTEGER_NEWTYPE_NAME](crate::format::BIG_INTEGER_NEWTYPE_NAME)
+/// as key and the encoded integer as value, for [Value](crate::value::Value).
+#[cfg(feature = "big-integers")]
+pub(crate) struct BigIntegerMapDeserializer<'a> {
+	/// The encoded integer, until the value was visited.
+	encoded: Option<&'a [u8]>,
+}
+
+#[cfg(feature = "big-integers")]
+impl<'a> BigIntegerMapDeserializer<'a> {
+	/// Create a map deserializer for the encoded integer.
+	pub(crate) const fn new(encoded: &'a [u8]) -> Self {
+		Self { encoded: Some(encoded) }
+	}
+}
+
+#[cfg(feature = "big-integers")]
+impl<'de> ::serde::de::MapAccess<'de> for BigIntegerMapDeserializer<'_> {
+	type Error = Error;
+
+	fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+	where
+		K: ::serde::de::DeserializeSeed<'de>,
+	{
+		if self.encoded.is_none() {
+			return Ok(None);
+		}
+		seed.deserialize(BorrowedStrDeserializer::new(crate::format::BIG_INTEGER_NEWTYPE_NAME))
+			.map(Some)
+	}
+
+	fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value>
+	where
+		V: ::serde::de::DeserializeSeed<'de>,
+	{
+		let encoded = self.encoded.take().unwrap_or_default();
+		seed.deserialize(::serde::de::value::BytesDeserializer::new(encoded))
+	}
+
+	fn size_hint(&self) -> Option<usize> {
+		Some(usize::from(self.encoded.is_some()))
+	}
+}
+
+/// Visit an unsigned integer with the smallest type it fits into.
+#[inline]
+pub(crate) fn visit_unsigned<'de, V>(value: u128, visitor: V) -> Result<V::Value>
+where
+	V: Visitor<'de>,
+{
+	if let Ok(value) = u8::try_from(value) {
+		visitor.visit_u8(value)
+	} else if let Ok(value) = u16::try_from(value) {
+		visitor.visit_u16(value)
+	} else if let Ok(value) = u32::try_from(value) {
+		visitor.visit_u32(value)
+	} else if let Ok(value) = u64::try_from(value) {
+		visitor.visit_u64(value)
+	} else {
+		visitor.visit_u128(value)
+	}
+}
+
+/// Visit a signed integer with the smallest type it fits into.
+#[inline]
+pub(crate) fn visit_signed<'de, V>(value: i128, visitor: V) -> Result<V::Value>
+where
+	V: Visitor<'de>,
+{
+	if let Ok(value) = i8::try_from(value) {
+		visitor.visit_i8(value)
+	} else if let Ok(value) = i16::try_from(value) {
+		visitor.visit_i16(value)
+	} else if let Ok(value) = i32::try_from(value) {
+		visitor.visit_i32(value)
+	} else if let Ok(value) = i64::try_from(value) {
+		visitor.visit_i64(value)
+	} else {
+		visitor.visit_i128(value)
+	}
+}
+
+/// Visit a float as the integer it represents, if it is finite and integral. Other floats are
+/// visited as float, so that integer visitors reject them.
+#[allow(clippy::float_cmp, reason = "Checking for exact conversions")]
+pub(crate) fn visit_integral_float<'de, V>(value: f64, visitor: V) -> Result<V::Value>
+where
+	V: Visitor<'de>,
+{
+	// The casts saturate, so the range is checked first and the result is converted back.
+	if value >= 0.0 && value < u128::MAX as f64 {
+		let int = value as u128;
+		if int as f64 == value {
+			return visit_unsigned(int, visitor);
+		}
+	} else if value < 0.0 && value >= i128::MIN as f64 {
+		let int = value as i128;
+		if int as f64 == value {
+			return visit_signed(int, visitor);
+		}
+	}
+	visitor.visit_f64(value)
+}
+
+/// Convert the integer to `f64`, if it is represented exactly.
+pub(crate) fn unsigned_to_f64(int: u128) -> Option<f64> {
+	let value = int as f64;
+	(value < u128::MAX as f64 && value as u128 == int).then_some(value)
+}
+
+/// Convert the integer to `f64`, if it is represented exactly.
+pub(crate) fn signed_to_f64(int: i128) -> Option<f64> {
+	let value = int as f64;
+	(value < i128::MAX as f64 && value as i128 == int).then_some(value)
+}
+
+/// Visit the float converted from an integer with the target precision, or return an error if it
+/// does not represent the integer exactly.
+#[allow(clippy::float_cmp, reason = "Checking for exact conversions")]
+pub(crate) fn visit_exact_float<'de, V>(
+	value: Option<f64>,
+	single_precision: bool,
+	visitor: V,
+) -> Result<V::Value>
+where
+	V: Visitor<'de>,
+{
+	match value {
+		Some(value) if !single_precision => visitor.visit_f64(value),
+		Some(value) if f64::from(value as f32) == value => visitor.visit_f32(value as f32),
+		_ => Err(::serde::de::Error::invalid_value(
+			Unexpected::Other("integer"),
+			&"integer that is exactly representable as float",
+		)),
+	}
+}
+
+/// Visit `0` as `false` and `1` as `true`, or return an error for other (or negative) integers.
+pub(crate) fn visit_int_as_bool<'de, V>(value: Option<u128>, visitor: V) -> Result<V::Value>
+where
+	V: Visitor<'de>,
+{
+	match value {
+		Some(0) => visitor.visit_bool(false),
+		Some(1) => visitor.visit_bool(true),
+		_ => Err(::serde::de::Error::invalid_value(Unexpected::Other("integer"), &"0 or 1")),
+	}
+}
+
+impl<'de, I, B> ::serde::Deserializer<'de> for &mut Deserializer<I, B>
 where
 	I: Input<'de>,
 	B: Buffer,
@@ -250,7 +1132,7 @@ where
 
 	#[inline]
 	fn is_human_readable(&self) -> bool {
-		false
+		self.config.human_readable.unwrap_or(false)
 	}
 
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
@@ -269,9 +1151,13 @@ where
 				self.deserialize_float(visitor)
 			}
 			Type::Bytes => self.deserialize_byte_buf(visitor),
-			Type::String => self.deserialize_string(visitor),
-			Type::SeqStart => self.deserialize_seq(visitor),
+			Type::String | Type::StringDef | Type::StringRef => self.deserialize_string(visitor),
+			Type::SeqStart | Type::SeqFixed => self.deserialize_seq(visitor),
 			Type::MapStart => self.deserialize_map(visitor),
+			Type::Some => {
+				_ = self.input.read_byte()?;
+				self.nested(|de| visitor.visit_some(de))
+			}
 			Type::SeqEnd | Type::MapEnd => Err(Error::WrongType(
 				t,
 				&[
@@ -286,8 +1172,12 @@ where
 					Type::Float128,
 					Type::Bytes,
 					Type::String,
+					Type::StringDef,
+					Type::StringRef,
 					Type::SeqStart,
+					Type::SeqFixed,
 					Type::MapStart,
+					Type::Some,
 				],
 			)),
 		}
@@ -351,7 +1241,9 @@ where
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
-			Type::UnsignedInt | Type::SignedInt => self.deserialize_ptr(visitor),
+			Type::UnsignedInt | Type::SignedInt if self.config.lenient_numbers => {
+				self.deserialize_int_as_bool(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::BooleanFalse, Type::BooleanTrue])),
 		}
 	}
@@ -373,6 +1265,17 @@ where
 				let value = i8::decode(&mut self.input)?;
 				visitor.visit_i8(value)
 			}
+			Type::UnsignedInt if self.config.lenient_numbers => {
+				self.deserialize_unsigned_int(visitor)
+			}
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
+			}
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
@@ -394,6 +1297,17 @@ where
 				let value = i16::decode(&mut self.input)?;
 				visitor.visit_i16(value)
 			}
+			Type::UnsignedInt if self.config.lenient_numbers => {
+				self.deserialize_unsigned_int(visitor)
+			}
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
+			}
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
@@ -415,6 +1329,17 @@ where
 				let value = i32::decode(&mut self.input)?;
 				visitor.visit_i32(value)
 			}
+			Type::UnsignedInt if self.config.lenient_numbers => {
+				self.deserialize_unsigned_int(visitor)
+			}
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
+			}
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
@@ -436,6 +1361,17 @@ where
 				let value = i64::decode(&mut self.input)?;
 				visitor.visit_i64(value)
 			}
+			Type::UnsignedInt if self.config.lenient_numbers => {
+				self.deserialize_unsigned_int(visitor)
+			}
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
+			}
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
@@ -457,6 +1393,17 @@ where
 				let value = i128::decode(&mut self.input)?;
 				visitor.visit_i128(value)
 			}
+			Type::UnsignedInt if self.config.lenient_numbers => {
+				self.deserialize_unsigned_int(visitor)
+			}
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
+			}
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
+			}
 			_ => Err(Error::WrongType(t, &[Type::SignedInt])),
 		}
 	}
@@ -474,17 +1421,17 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u8::decode(&mut self.input)?;
+				let value = self.read_unsigned::<u8>()?;
 				visitor.visit_u8(value)
 			}
-			Type::BooleanFalse => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(false)
+			Type::SignedInt if self.config.lenient_numbers => self.deserialize_signed_int(visitor),
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
 			}
-			Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(true)
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
@@ -503,17 +1450,17 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u16::decode(&mut self.input)?;
+				let value = self.read_unsigned::<u16>()?;
 				visitor.visit_u16(value)
 			}
-			Type::BooleanFalse => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(false)
+			Type::SignedInt if self.config.lenient_numbers => self.deserialize_signed_int(visitor),
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
 			}
-			Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(true)
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
@@ -532,17 +1479,17 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u32::decode(&mut self.input)?;
+				let value = self.read_unsigned::<u32>()?;
 				visitor.visit_u32(value)
 			}
-			Type::BooleanFalse => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(false)
+			Type::SignedInt if self.config.lenient_numbers => self.deserialize_signed_int(visitor),
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
 			}
-			Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(true)
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
@@ -561,17 +1508,17 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u64::decode(&mut self.input)?;
+				let value = self.read_unsigned::<u64>()?;
 				visitor.visit_u64(value)
 			}
-			Type::BooleanFalse => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(false)
+			Type::SignedInt if self.config.lenient_numbers => self.deserialize_signed_int(visitor),
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
 			}
-			Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(true)
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
@@ -590,17 +1537,17 @@ where
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let value = u128::decode(&mut self.input)?;
+				let value = self.read_unsigned::<u128>()?;
 				visitor.visit_u128(value)
 			}
-			Type::BooleanFalse => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(false)
+			Type::SignedInt if self.config.lenient_numbers => self.deserialize_signed_int(visitor),
+			Type::Float16 | Type::Float32 | Type::Float64 | Type::Float128
+				if self.config.lenient_numbers =>
+			{
+				self.deserialize_float_as_int(visitor)
 			}
-			Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-				visitor.visit_bool(true)
+			Type::BooleanFalse | Type::BooleanTrue if self.config.lenient_numbers => {
+				self.deserialize_bool_as_int(visitor)
 			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt])),
 		}
@@ -612,7 +1559,13 @@ where
 	where
 		V: Visitor<'de>,
 	{
-		self.deserialize_float(visitor)
+		let t = Type::try_from(self.input.peek_byte()?)?;
+		match t {
+			Type::UnsignedInt | Type::SignedInt if self.config.lenient_numbers => {
+				self.deserialize_int_as_float(true, visitor)
+			}
+			_ => self.deserialize_float(visitor),
+		}
 	}
 
 	#[inline]
@@ -621,7 +1574,13 @@ where
 	where
 		V: Visitor<'de>,
 	{
-		self.deserialize_float(visitor)
+		let t = Type::try_from(self.input.peek_byte()?)?;
+		match t {
+			Type::UnsignedInt | Type::SignedInt if self.config.lenient_numbers => {
+				self.deserialize_int_as_float(false, visitor)
+			}
+			_ => self.deserialize_float(visitor),
+		}
 	}
 
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
@@ -638,17 +1597,17 @@ where
 			}
 			Type::String => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
-				let bytes = self.read_bytes(len)?;
-				let s = str::from_utf8(bytes)?;
-
-				let mut chars = s.chars();
-				let c = chars.next().ok_or_else(|| Error::NotOneChar)?;
-				if chars.next().is_some() {
-					return Err(Error::NotOneChar);
-				}
-
-				visitor.visit_char(c)
+				let len = decode_length(&mut self.input)?;
+				// A char has at most 4 bytes, so no buffer is needed.
+				let mut bytes = [0; 4];
+				let bytes = bytes.get_mut(.. len).ok_or_else(|| Error::NotOneChar)?;
+				self.input.read_exact(bytes)?;
+				visitor.visit_char(single_char(str::from_utf8(bytes)?)?)
+			}
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => {
+				_ = self.input.read_byte()?;
+				visitor.visit_char(single_char(self.read_table_string(t)?)?)
 			}
 			_ => Err(Error::WrongType(t, &[Type::String])),
 		}
@@ -668,18 +1627,26 @@ where
 			}
 			Type::String => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
+				let len = decode_length(&mut self.input)?;
 
-				self.reset_buffer();
-				let borrowed = self.input.read_bytes(len, self.buffer.as_mut())?;
-				if let Some(borrowed) = borrowed {
-					let s = str::from_utf8(borrowed)?;
-					visitor.visit_borrowed_str(s)
-				} else {
-					let s = str::from_utf8(self.buffer_slice()?)?;
-					visitor.visit_str(s)
+				let can_borrow = self.input.can_borrow();
+				match self.read_bytes_or_copy(len)? {
+					ReadBytes::Borrowed(bytes) => {
+						visitor.visit_borrowed_str(str::from_utf8(bytes)?)
+					}
+					ReadBytes::Transient(bytes) => visitor
+						.visit_str::<TransientError>(str::from_utf8(bytes)?)
+						.map_err(|err| err.into_error(can_borrow)),
 				}
 			}
+			// Strings of the string table are never borrowed.
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => {
+				_ = self.input.read_byte()?;
+				visitor
+					.visit_str::<TransientError>(self.read_table_string(t)?)
+					.map_err(|err| err.into_error(false))
+			}
 			_ => Err(Error::WrongType(t, &[Type::String])),
 		}
 	}
@@ -701,9 +1668,65 @@ where
 	{
 		let byte = self.input.peek_byte()?;
 		let t = Type::try_from(byte)?;
+		self.record_identifier(t);
 		match t {
-			Type::UnsignedInt => self.deserialize_u32(visitor),
-			Type::String => self.deserialize_str(visitor),
+			Type::UnsignedInt => {
+				let index = self.read_unsigned::<u32>()?;
+				self.key = usize::try_from(index).map_or(FieldKey::None, FieldKey::Index);
+				visitor.visit_u32(index)
+			}
+			Type::String => {
+				_ = self.input.read_byte()?;
+				let len = decode_length(&mut self.input)?;
+
+				let fields = self.fields;
+				let intern = self.config.intern_field_names;
+				let read = self.read_bytes_or_copy(len)?;
+				let (ReadBytes::Borrowed(bytes) | ReadBytes::Transient(bytes)) = &read;
+				let interned =
+					if intern { fields.iter().position(|f| f.as_bytes() == *bytes) } else { None };
+				// Short names are only copied, so that the field is looked up when its value fails.
+				let mut copy = InlineBuffer::new();
+				let copied = interned.is_none()
+					&& !fields.is_empty()
+					&& copy.extend_from_slice(bytes).is_ok();
+				let key = match interned {
+					Some(index) => Some(index),
+					None if copied || fields.is_empty() => None,
+					None => fields.iter().position(|f| f.as_bytes() == *bytes),
+				};
+
+				let value = match (interned.and_then(|i| fields.get(i)), read) {
+					(Some(field), _) => visitor.visit_borrowed_str(field),
+					(None, ReadBytes::Borrowed(bytes)) => {
+						visitor.visit_borrowed_str(str::from_utf8(bytes)?)
+					}
+					(None, ReadBytes::Transient(bytes)) => {
+						visitor.visit_str(str::from_utf8(bytes)?)
+					}
+				};
+				self.key = if copied {
+					self.key_name = copy;
+					self.key_names += 1;
+					FieldKey::Name(self.key_names)
+				} else {
+					key.map_or(FieldKey::None, FieldKey::Index)
+				};
+				value
+			}
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => {
+				_ = self.input.read_byte()?;
+				let fields = self.fields;
+				let s = self.read_table_string(t)?;
+				let index = fields.iter().position(|f| *f == s);
+				let value = match index.and_then(|i| fields.get(i)) {
+					Some(field) => visitor.visit_borrowed_str(field),
+					None => visitor.visit_str(s),
+				};
+				self.key = index.map_or(FieldKey::None, FieldKey::Index);
+				value
+			}
 			_ => Err(Error::WrongType(t, &[Type::UnsignedInt, Type::String])),
 		}
 	}
@@ -722,14 +1745,61 @@ where
 			}
 			Type::Bytes | Type::String => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
+				let len = decode_length(&mut self.input)?;
 
-				self.reset_buffer();
-				let borrowed = self.input.read_bytes(len, self.buffer.as_mut())?;
-				if let Some(borrowed) = borrowed {
-					visitor.visit_borrowed_bytes(borrowed)
-				} else {
-					visitor.visit_bytes(self.buffer_slice()?)
+				let can_borrow = self.input.can_borrow();
+				match self.read_bytes_or_copy(len)? {
+					ReadBytes::Borrowed(bytes) => visitor.visit_borrowed_bytes(bytes),
+					ReadBytes::Transient(bytes) => visitor
+						.visit_bytes::<TransientError>(bytes)
+						.map_err(|err| err.into_error(can_borrow)),
+				}
+			}
+			// Strings of the string table are never borrowed.
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => {
+				_ = self.input.read_byte()?;
+				visitor
+					.visit_bytes::<TransientError>(self.read_table_string(t)?.as_bytes())
+					.map_err(|err| err.into_error(false))
+			}
+			// Bytes serialized as sequence of integers, e.g. `Vec<u8>` without `serde_bytes`. They
+			// are collected from the elements, so they are never borrowed.
+			Type::SeqStart | Type::SeqFixed => {
+				_ = self.input.read_byte()?;
+				let mut remaining =
+					if t == Type::SeqFixed { Some(decode_length(&mut self.input)?) } else { None };
+
+				if self.buffer.is_some() {
+					self.reset_buffer();
+					while let Some(byte) = self.read_byte_element(&mut remaining)? {
+						self.buffer.as_mut().ok_or_else(|| Error::BufferTooSmall)?.push(byte)?;
+					}
+					let bytes: &[u8] = self.buffer.as_ref().map_or(&[], B::as_slice);
+					return visitor
+						.visit_bytes::<TransientError>(bytes)
+						.map_err(|err| err.into_error(false));
+				}
+
+				#[cfg(feature = "alloc")]
+				{
+					let mut bytes = Vec::new();
+					while let Some(byte) = self.read_byte_element(&mut remaining)? {
+						bytes.push(byte);
+					}
+					visitor
+						.visit_byte_buf::<TransientError>(bytes)
+						.map_err(|err| err.into_error(false))
+				}
+				#[cfg(not(feature = "alloc"))]
+				{
+					self.inline.clear();
+					while let Some(byte) = self.read_byte_element(&mut remaining)? {
+						self.inline.push(byte)?;
+					}
+					visitor
+						.visit_bytes::<TransientError>(self.inline.as_slice())
+						.map_err(|err| err.into_error(false))
 				}
 			}
 			_ => Err(Error::WrongType(t, &[Type::Bytes])),
@@ -758,6 +1828,10 @@ where
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
+			Type::Some => {
+				_ = self.input.read_byte()?;
+				self.nested(|de| visitor.visit_some(de))
+			}
 			_ => visitor.visit_some(self),
 		}
 	}
@@ -766,12 +1840,64 @@ where
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
 	fn deserialize_newtype_struct<V>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		visitor: V,
 	) -> Result<V::Value, Self::Error>
 	where
 		V: Visitor<'de>,
 	{
+		if name == crate::format::RAW_VALUE_NEWTYPE_NAME {
+			return match self.skip_value_span()? {
+				Some(raw) => visitor.visit_borrowed_bytes(raw),
+				None => Err(::serde::de::Error::invalid_type(
+					Unexpected::Other("value in a non-borrowed input"),
+					&visitor,
+				)),
+			};
+		}
+
+		if name == crate::format::VALUE_NEWTYPE_NAME {
+			// `Value` wants the raw bits of floats that serde does not support, see the name's
+			// documentation.
+			let byte = self.input.peek_byte()?;
+			let t = Type::try_from(byte)?;
+			return match t {
+				Type::Float16 => {
+					_ = self.input.read_byte()?;
+					let mut bytes = [0; 2];
+					self.input.read_exact(&mut bytes)?;
+					let bits = u16::from_le_bytes(bytes);
+					visitor.visit_map(FloatBitsMapDeserializer::new(
+						crate::format::F16_BITS_NEWTYPE_NAME,
+						bits,
+					))
+				}
+				Type::Float128 => {
+					_ = self.input.read_byte()?;
+					let mut bytes = [0; 16];
+					self.input.read_exact(&mut bytes)?;
+					let bits = u128::from_le_bytes(bytes);
+					visitor.visit_map(FloatBitsMapDeserializer::new(
+						crate::format::F128_BITS_NEWTYPE_NAME,
+						bits,
+					))
+				}
+				#[cfg(feature = "big-integers")]
+				Type::UnsignedInt | Type::SignedInt if byte == u8::from(t) => {
+					self.deserialize_value_integer(t, visitor)
+				}
+				_ => self.deserialize_any(visitor),
+			};
+		}
+
+		#[cfg(feature = "half")]
+		if name == crate::format::F16_BITS_NEWTYPE_NAME
+			&& self.input.peek_byte()? == u8::from(Type::UnsignedInt)
+		{
+			// `f16_as_float16` also accepts the bits (`u16`) of the `f16` implementation.
+			return self.deserialize_u16(visitor);
+		}
+
 		visitor.visit_newtype_struct(self)
 	}
 
@@ -789,7 +1915,9 @@ where
 			}
 			Type::SeqStart => {
 				_ = self.input.read_byte()?;
-				let value = visitor.visit_seq(SequenceDeserializer(self))?;
+				let value = self.nested(|de| {
+					visitor.visit_seq(&mut SequenceDeserializer { de, remaining: None })
+				})?;
 
 				let byte = self.input.read_byte()?;
 				let t = Type::try_from(byte)?;
@@ -799,16 +1927,31 @@ where
 					Err(Error::WrongType(t, &[Type::SeqEnd]))
 				}
 			}
+			Type::SeqFixed => {
+				_ = self.input.read_byte()?;
+				let len = decode_length(&mut self.input)?;
+				self.nested(|de| {
+					let mut seq = SequenceDeserializer { de, remaining: Some(len) };
+					let value = visitor.visit_seq(&mut seq)?;
+					match seq.remaining {
+						Some(0) | None => Ok(value),
+						Some(remaining) => Err(::serde::de::Error::invalid_length(
+							len,
+							&ExpectedInSeq(len - remaining),
+						)),
+					}
+				})
+			}
 			Type::Bytes => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
+				let len = decode_length(&mut self.input)?;
 				let bytes = self.read_bytes(len)?;
 				let value = visitor.visit_seq(ByteSequenceDeserializer(bytes))?;
 				Ok(value)
 			}
 			Type::String => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
+				let len = decode_length(&mut self.input)?;
 				let bytes = self.read_bytes(len)?;
 				let s = str::from_utf8(bytes)?;
 				let value = visitor.visit_seq(CharSequenceDeserializer(s.chars()))?;
@@ -846,41 +1989,21 @@ where
 	where
 		V: Visitor<'de>,
 	{
-		let byte = self.input.peek_byte()?;
-		let t = Type::try_from(byte)?;
-		match t {
-			Type::Null => {
-				_ = self.input.read_byte()?;
-				visitor.visit_none()
-			}
-			Type::MapStart => {
-				_ = self.input.read_byte()?;
-				let value = visitor.visit_map(MapDeserializer(self))?;
-
-				let byte = self.input.read_byte()?;
-				let t = Type::try_from(byte)?;
-				if t == Type::MapEnd {
-					Ok(value)
-				} else {
-					Err(Error::WrongType(t, &[Type::MapEnd]))
-				}
-			}
-			_ => Err(Error::WrongType(t, &[Type::MapStart])),
-		}
+		self.deserialize_map_entries(visitor, None)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
 	fn deserialize_struct<V>(
 		self,
-		_name: &'static str,
-		_fields: &'static [&'static str],
+		name: &'static str,
+		fields: &'static [&'static str],
 		visitor: V,
 	) -> Result<V::Value, Self::Error>
 	where
 		V: Visitor<'de>,
 	{
-		self.deserialize_map(visitor)
+		self.deserialize_map_entries(visitor, Some((name, fields)))
 	}
 
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
@@ -895,26 +2018,38 @@ where
 	{
 		let byte = self.input.peek_byte()?;
 		let t = Type::try_from(byte)?;
+		self.record_identifier(t);
 		match t {
 			Type::Null => {
 				_ = self.input.read_byte()?;
 				visitor.visit_none()
 			}
 			Type::UnsignedInt => {
-				_ = self.input.read_byte()?;
-				let index = u32::decode(&mut self.input)?;
+				let index = self.read_unsigned::<u32>()?;
 				visitor.visit_enum(index.into_deserializer())
 			}
 			Type::String => {
 				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
-				let bytes = self.read_bytes(len)?;
-				let s = str::from_utf8(bytes)?;
-				visitor.visit_enum(s.into_deserializer())
+				let len = decode_length(&mut self.input)?;
+				// Variant names are short and only matched against the known names, so they are
+				// borrowed or copied to the inline buffer instead of the buffer.
+				match self.read_bytes_or_copy(len)? {
+					ReadBytes::Borrowed(bytes) => {
+						visitor.visit_enum(BorrowedStrDeserializer::new(str::from_utf8(bytes)?))
+					}
+					ReadBytes::Transient(bytes) => {
+						visitor.visit_enum(str::from_utf8(bytes)?.into_deserializer())
+					}
+				}
+			}
+			#[cfg(feature = "alloc")]
+			Type::StringDef | Type::StringRef => {
+				_ = self.input.read_byte()?;
+				visitor.visit_enum(self.read_table_string(t)?.into_deserializer())
 			}
 			Type::MapStart => {
 				_ = self.input.read_byte()?;
-				let value = visitor.visit_enum(EnumMapDeserializer(self))?;
+				let value = self.nested(|de| visitor.visit_enum(EnumMapDeserializer(de)))?;
 
 				let byte = self.input.read_byte()?;
 				let t = Type::try_from(byte)?;
@@ -933,65 +2068,92 @@ where
 	where
 		V: Visitor<'de>,
 	{
-		let byte = self.input.peek_byte()?;
-		let t = Type::try_from(byte)?;
-		match t {
-			Type::Null | Type::BooleanFalse | Type::BooleanTrue => {
-				_ = self.input.read_byte()?;
-			}
-			Type::UnsignedInt | Type::SignedInt => {
-				_ = self.input.read_byte()?;
-				while self.input.read_byte()? & 0x80 != 0 {}
-			}
-			Type::Float16 => {
-				self.input.skip_bytes(3)?; // Also the previous type byte.
-			}
-			Type::Float32 => {
-				self.input.skip_bytes(5)?; // Also the previous type byte.
-			}
-			Type::Float64 => {
-				self.input.skip_bytes(9)?; // Also the previous type byte.
-			}
-			Type::Float128 => {
-				self.input.skip_bytes(17)?; // Also the previous type byte.
-			}
-			Type::Bytes | Type::String => {
-				_ = self.input.read_byte()?;
-				let len = usize::decode(&mut self.input)?;
-				self.input.skip_bytes(len)?;
-			}
-			Type::SeqStart => return self.deserialize_seq(visitor),
-			Type::MapStart => return self.deserialize_map(visitor),
-			Type::SeqEnd | Type::MapEnd => {
-				return Err(Error::WrongType(
-					t,
-					&[
-						Type::Null,
-						Type::BooleanFalse,
-						Type::BooleanTrue,
-						Type::UnsignedInt,
-						Type::SignedInt,
-						Type::Float16,
-						Type::Float32,
-						Type::Float64,
-						Type::Float128,
-						Type::Bytes,
-						Type::String,
-						Type::SeqStart,
-						Type::MapStart,
-					],
-				))
-			}
-		}
+		self.skip_value()?;
 		visitor.visit_unit()
 	}
 }
 
+/// Number of elements a visitor took from a length-prefixed sequence, for
+/// [invalid_length](::serde::de::Error::invalid_length) errors.
+struct ExpectedInSeq(usize);
+
+impl ::serde::de::Expected for ExpectedInSeq {
+	fn fmt(&self, formatter: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		if self.0 == 1 {
+			formatter.write_str("1 element in sequence")
+		} else {
+			write!(formatter, "{} elements in sequence", self.0)
+		}
+	}
+}
+
+/// Error of visitors that are given strings or bytes that are not borrowed from the input. Visitors
+/// that reject them fail with [Error::CannotBorrow] if the data could not have been borrowed, see
+/// [Input::can_borrow], instead of a confusing "invalid type" message. Other rejections stay.
+#[derive(Debug)]
+struct TransientError {
+	/// The visitor's error.
+	error: Error,
+	/// Whether the visitor rejected the string or bytes it was given.
+	rejected: bool,
+}
+
+impl TransientError {
+	/// Get the error, which is [Error::CannotBorrow] for rejected data if it could not be borrowed.
+	#[inline]
+	#[allow(clippy::missing_const_for_fn, reason = "Different feature sets")]
+	fn into_error(self, can_borrow: bool) -> Error {
+		if self.rejected && !can_borrow {
+			Error::CannotBorrow
+		} else {
+			self.error
+		}
+	}
+}
+
+impl ::core::fmt::Display for TransientError {
+	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		self.error.fmt(f)
+	}
+}
+
+impl ::serde::de::StdError for TransientError {}
+
+impl ::serde::de::Error for TransientError {
+	#[inline]
+	fn custom<T>(msg: T) -> Self
+	where
+		T: ::core::fmt::Display,
+	{
+		Self { error: Error::custom(msg), rejected: false }
+	}
+
+	fn invalid_type(unexp: Unexpected<'_>, exp: &dyn ::serde::de::Expected) -> Self {
+		let rejected = matches!(unexp, Unexpected::Str(_) | Unexpected::Bytes(_));
+		Self { error: Error::invalid_type(unexp, exp), rejected }
+	}
+}
+
+/// Get the only character of the string.
+fn single_char(s: &str) -> Result<char> {
+	let mut chars = s.chars();
+	let c = chars.next().ok_or_else(|| Error::NotOneChar)?;
+	if chars.next().is_some() {
+		return Err(Error::NotOneChar);
+	}
+	Ok(c)
+}
+
 /// Deserialize sequence elements until the end of the sequence.
 #[derive(Debug)]
-pub struct SequenceDeserializer<'a, I, B>(&'a mut Deserializer<I, B>);
+pub struct SequenceDeserializer<'a, I, B> {
+	/// The deserializer to read the elements from.
+	de: &'a mut Deserializer<I, B>,
+	/// Number of remaining elements, if the sequence has a length instead of an end designator.
+	remaining: Option<usize>,
+}
 
-impl<'a, 'de, I, B> ::serde::de::SeqAccess<'de> for SequenceDeserializer<'a, I, B>
+impl<'a, 'de, I, B> ::serde::de::SeqAccess<'de> for &mut SequenceDeserializer<'a, I, B>
 where
 	I: Input<'de>,
 	B: Buffer,
@@ -1000,7 +2162,7 @@ where
 
 	#[inline]
 	fn size_hint(&self) -> Option<usize> {
-		None
+		self.remaining
 	}
 
 	#[inline]
@@ -1009,13 +2171,19 @@ where
 	where
 		T: ::serde::de::DeserializeSeed<'de>,
 	{
-		let byte = self.0.input.peek_byte()?;
-		let t = Type::try_from(byte)?;
-		if t == Type::SeqEnd {
-			return Ok(None);
+		match &mut self.remaining {
+			Some(0) => return Ok(None),
+			Some(remaining) => *remaining -= 1,
+			None => {
+				let byte = self.de.input.peek_byte()?;
+				let t = Type::try_from(byte)?;
+				if t == Type::SeqEnd {
+					return Ok(None);
+				}
+			}
 		}
 
-		seed.deserialize(&mut *self.0).map(Some)
+		seed.deserialize(&mut *self.de).map(Some)
 	}
 }
 
@@ -1074,7 +2242,130 @@ impl<'a, 'de> ::serde::de::SeqAccess<'de> for CharSequenceDeserializer<'a> {
 
 /// Deserialize map entries until the end of the map.
 #[derive(Debug)]
-pub struct MapDeserializer<'a, I, B>(&'a mut Deserializer<I, B>);
+pub struct MapDeserializer<'a, I, B> {
+	/// The deserializer to read the entries from.
+	de: &'a mut Deserializer<I, B>,
+	/// Whether all keys must be of the same type as the first key.
+	check_keys: bool,
+	/// Type of the first key, when checking keys.
+	key_type: Option<Type>,
+	/// Name and field names of the struct, if the map is a struct.
+	structure: Option<(&'static str, &'static [&'static str])>,
+	/// The last key, to look up its field name for errors.
+	key: FieldKey,
+	/// Whether any key was an unsigned integer, i.e. possibly an index.
+	index_keys: bool,
+	/// Whether any key was a string, i.e. a name.
+	name_keys: bool,
+	/// Whether the struct's keys were indices so far, so that the next index key is decoded
+	/// directly instead of via the identifier.
+	index_layout: bool,
+	/// Whether reading a key or value failed, so that errors of the visitor are passed on.
+	access_failed: bool,
+	/// Encoded keys seen so far, when rejecting duplicate keys.
+	#[cfg(feature = "alloc")]
+	seen_keys: ::alloc::collections::BTreeSet<::alloc::vec::Vec<u8>>,
+}
+
+impl<'a, 'de, I, B> MapDeserializer<'a, I, B>
+where
+	I: Input<'de>,
+	B: Buffer,
+{
+	/// Deserialize the next key, keeping track of struct fields.
+	fn deserialize_key<K>(&mut self, seed: K) -> Result<K::Value>
+	where
+		K: ::serde::de::DeserializeSeed<'de>,
+	{
+		let Some((_, fields)) = self.structure else {
+			return seed.deserialize(&mut *self.de);
+		};
+		self.de.fields = fields;
+		self.de.key = FieldKey::None;
+		let key = seed.deserialize(&mut *self.de);
+		self.de.fields = &[];
+		self.key = self.de.key;
+		key
+	}
+
+	/// Deserialize the next key, if the map has not ended.
+	fn read_key<K>(&mut self, seed: K) -> Result<Option<K::Value>>
+	where
+		K: ::serde::de::DeserializeSeed<'de>,
+	{
+		let byte = self.de.input.peek_byte()?;
+		if self.index_layout && Type::try_from(byte).is_ok_and(|t| t == Type::UnsignedInt) {
+			return self.read_index_key(seed).map(Some);
+		}
+
+		let t = Type::try_from(byte)?;
+		if t == Type::MapEnd {
+			return Ok(None);
+		}
+		self.index_keys |= t == Type::UnsignedInt;
+		self.name_keys |= matches!(t, Type::String | Type::StringDef | Type::StringRef);
+		if self.check_keys {
+			// Keys of the string table are names as well.
+			let key_type = match t {
+				Type::StringDef | Type::StringRef => Type::String,
+				t => t,
+			};
+			match self.key_type {
+				None => self.key_type = Some(key_type),
+				Some(Type::UnsignedInt) if key_type != Type::UnsignedInt => {
+					return Err(Error::WrongType(t, &[Type::UnsignedInt]));
+				}
+				Some(Type::String) if key_type != Type::String => {
+					return Err(Error::WrongType(t, &[Type::String]));
+				}
+				// Other key types are rejected by the identifier already.
+				Some(_) => {}
+			}
+		}
+
+		#[cfg(feature = "alloc")]
+		if self.de.config.reject_duplicate_map_keys {
+			// Borrowed data gives the key's bytes without recording them.
+			let before = self.de.input.remaining_borrowed();
+			if before.is_none() && !self.de.input.start_recording() {
+				return Err(Error::CannotRecord);
+			}
+			let key = self.deserialize_key(seed);
+			let encoded = match before {
+				Some(before) => {
+					let len = self.de.input.remaining_borrowed().map_or(0, <[u8]>::len);
+					before.get(.. before.len() - len).unwrap_or_default().to_vec()
+				}
+				None => self.de.input.finish_recording(),
+			};
+			let key = key?;
+			if !self.seen_keys.insert(self.de.plain_key(encoded)?) {
+				return Err(Error::DuplicateMapKey);
+			}
+			return Ok(Some(key));
+		}
+		#[cfg(not(feature = "alloc"))]
+		if self.de.config.reject_duplicate_map_keys {
+			return Err(Error::CannotRecord);
+		}
+
+		// Keys of the other type would have been rejected above.
+		self.index_layout = t == Type::UnsignedInt && self.structure.is_some();
+		self.deserialize_key(seed).map(Some)
+	}
+
+	/// Deserialize the next key of a struct, which is known to be an index. The identifier is
+	/// skipped, as it would check the type again.
+	fn read_index_key<K>(&mut self, seed: K) -> Result<K::Value>
+	where
+		K: ::serde::de::DeserializeSeed<'de>,
+	{
+		self.de.record_identifier(Type::UnsignedInt);
+		let index = self.de.read_unsigned::<u32>()?;
+		self.key = usize::try_from(index).map_or(FieldKey::None, FieldKey::Index);
+		seed.deserialize(index.into_deserializer())
+	}
+}
 
 impl<'a, 'de, I, B> ::serde::de::MapAccess<'de> for MapDeserializer<'a, I, B>
 where
@@ -1094,13 +2385,9 @@ where
 	where
 		K: ::serde::de::DeserializeSeed<'de>,
 	{
-		let byte = self.0.input.peek_byte()?;
-		let t = Type::try_from(byte)?;
-		if t == Type::MapEnd {
-			return Ok(None);
-		}
-
-		seed.deserialize(&mut *self.0).map(Some)
+		let key = self.read_key(seed);
+		self.access_failed |= key.is_err();
+		key
 	}
 
 	#[inline]
@@ -1109,7 +2396,17 @@ where
 	where
 		V: ::serde::de::DeserializeSeed<'de>,
 	{
-		seed.deserialize(&mut *self.0)
+		let value = seed.deserialize(&mut *self.de).map_err(|err| match (err, self.structure) {
+			(Error::WrongType(found, expected), Some((name, fields))) => {
+				match self.de.field_name(self.key, fields) {
+					Some(field) => Error::WrongFieldType { name, field, found, expected },
+					None => Error::WrongType(found, expected),
+				}
+			}
+			(err, _) => err,
+		});
+		self.access_failed |= value.is_err();
+		value
 	}
 
 	#[inline]
@@ -1173,7 +2470,7 @@ where
 		let byte = self.0.input.peek_byte()?;
 		let t = Type::try_from(byte)?;
 		let found = match t {
-			Type::SeqStart => Unexpected::TupleVariant,
+			Type::SeqStart | Type::SeqFixed => Unexpected::TupleVariant,
 			Type::MapStart => Unexpected::StructVariant,
 			_ => Unexpected::NewtypeVariant,
 		};
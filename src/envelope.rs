@@ -0,0 +1,345 @@
+//! Header to identify serde-brief data and its format version, e.g. for files on disk.
+//!
+//! The header consists of the magic bytes `SBRF`, the format version and a flags byte, followed by
+//! the encoded value. The flags record the parts of the configuration that the reader needs to
+//! know, so they are taken from the header when reading instead of the given configuration:
+//!
+//! | Bit | Meaning |
+//! |---|---|
+//! | 0 | `use_indices`: Struct fields and enum variants are identified by their index. |
+//! | 1 | `human_readable`: Types like `uuid::Uuid` choose their human-readable representation. |
+//!
+//! Other flags are reserved and rejected with [Error::InvalidHeader], as well as data that does
+//! not start with the magic bytes. Versions other than [Header::VERSION] are rejected with
+//! [Error::UnsupportedVersion].
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use serde_brief::{envelope, Config, Error};
+//!
+//! let config = Config { use_indices: true, ..Default::default() };
+//! let bytes = envelope::to_vec_with_header_and_config(&("Hello", 42_u32), config).unwrap();
+//! assert_eq!(bytes[.. 6], *b"SBRF\x01\x01");
+//!
+//! let value: (&str, u32) = envelope::from_slice_with_header(&bytes).unwrap();
+//! assert_eq!(value, ("Hello", 42));
+//!
+//! let result = envelope::from_slice_with_header::<(&str, u32)>(&bytes[6 ..]);
+//! assert!(matches!(result, Err(Error::InvalidHeader)));
+//! # }
+//! ```
+
+#[cfg(feature = "std")]
+use ::serde::de::DeserializeOwned;
+use ::serde::{Deserialize, Serialize};
+#[cfg(feature = "std")]
+use ::std::io::{Read, Write};
+
+use crate::{Config, Error, Result};
+
+/// Header in front of the encoded value.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Header {
+	/// Whether struct fields and enum variants are identified by their index.
+	pub use_indices: bool,
+	/// Whether the value was serialized as human-readable.
+	pub human_readable: bool,
+}
+
+impl Header {
+	/// Magic bytes at the start of the header.
+	pub const MAGIC: [u8; 4] = *b"SBRF";
+	/// Current format version.
+	pub const VERSION: u8 = 1;
+	/// Number of bytes of the header.
+	pub const SIZE: usize = 6;
+
+	/// Flag for `use_indices`.
+	const USE_INDICES: u8 = 0b01;
+	/// Flag for `human_readable`.
+	const HUMAN_READABLE: u8 = 0b10;
+
+	/// Header for data serialized with the given configuration.
+	#[must_use]
+	pub fn new(config: Config) -> Self {
+		Self {
+			use_indices: config.use_indices,
+			human_readable: config.human_readable.unwrap_or(false),
+		}
+	}
+
+	/// Encode the header.
+	#[must_use]
+	pub const fn to_bytes(self) -> [u8; Self::SIZE] {
+		let mut flags = 0;
+		if self.use_indices {
+			flags |= Self::USE_INDICES;
+		}
+		if self.human_readable {
+			flags |= Self::HUMAN_READABLE;
+		}
+		let [m0, m1, m2, m3] = Self::MAGIC;
+		[m0, m1, m2, m3, Self::VERSION, flags]
+	}
+
+	/// Decode and validate the header.
+	pub fn from_bytes(bytes: [u8; Self::SIZE]) -> Result<Self> {
+		let [m0, m1, m2, m3, version, flags] = bytes;
+		if [m0, m1, m2, m3] != Self::MAGIC {
+			return Err(Error::InvalidHeader);
+		}
+		if version != Self::VERSION {
+			return Err(Error::UnsupportedVersion(version));
+		}
+		if flags & !(Self::USE_INDICES | Self::HUMAN_READABLE) != 0 {
+			return Err(Error::InvalidHeader);
+		}
+		Ok(Self {
+			use_indices: flags & Self::USE_INDICES != 0,
+			human_readable: flags & Self::HUMAN_READABLE != 0,
+		})
+	}
+
+	/// Split the header off the start of the data and decode it. Returns the header and the
+	/// remaining data.
+	pub fn split(bytes: &[u8]) -> Result<(Self, &[u8])> {
+		let (header, rest) =
+			bytes.split_first_chunk::<{ Self::SIZE }>().ok_or_else(|| Error::UnexpectedEnd)?;
+		Ok((Self::from_bytes(*header)?, rest))
+	}
+
+	/// Take the flags of the header into the given configuration, for reading the data.
+	#[must_use]
+	pub const fn apply(self, config: Config) -> Config {
+		Config {
+			use_indices: self.use_indices,
+			human_readable: Some(self.human_readable),
+			..config
+		}
+	}
+}
+
+/// Serialize a type into a slice of bytes after a header using the given configuration. Returns
+/// the slice with the header and serialized data. The size limit only applies to the serialized
+/// data.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_slice_with_header_and_config<'buf, T>(
+	value: &T,
+	buffer: &'buf mut [u8],
+	config: Config,
+) -> Result<&'buf mut [u8]>
+where
+	T: Serialize,
+{
+	let (header, rest) =
+		buffer.split_first_chunk_mut::<{ Header::SIZE }>().ok_or_else(|| Error::BufferTooSmall)?;
+	*header = Header::new(config).to_bytes();
+	let len = crate::to_slice_with_config(value, rest, config)?.len();
+	let end = Header::SIZE + len;
+	buffer.get_mut(.. end).ok_or_else(|| Error::BufferTooSmall)
+}
+
+/// Serialize a type into a slice of bytes after a header. Returns the slice with the header and
+/// serialized data.
+pub fn to_slice_with_header<'buf, T>(value: &T, buffer: &'buf mut [u8]) -> Result<&'buf mut [u8]>
+where
+	T: Serialize,
+{
+	to_slice_with_header_and_config(value, buffer, Config::default())
+}
+
+/// Serialize a type into a [Vec] of bytes after a header using the given configuration. The [Vec]
+/// grows as needed. The size limit only applies to the serialized data.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_vec_with_header_and_config<T>(value: &T, config: Config) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	let output = Header::new(config).to_bytes().to_vec();
+
+	if let Some(max) = config.max_size {
+		let mut ser = crate::Serializer::new(crate::io::SizeLimit::new(output, max.into()))
+			.with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().into_inner())
+	} else {
+		let mut ser = crate::Serializer::new(output).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output())
+	}
+}
+
+/// Serialize a type into a [Vec] of bytes after a header.
+#[cfg(feature = "alloc")]
+pub fn to_vec_with_header<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	to_vec_with_header_and_config(value, Config::default())
+}
+
+/// Serialize a type into a [Write]r after a header using the given configuration. The size limit
+/// only applies to the serialized data.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_writer_with_header_and_config<T, W>(
+	value: &T,
+	mut writer: W,
+	config: Config,
+) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	writer.write_all(&Header::new(config).to_bytes())?;
+	crate::to_writer_with_config(value, writer, config)
+}
+
+/// Serialize a type into a [Write]r after a header.
+#[cfg(feature = "std")]
+pub fn to_writer_with_header<T, W>(value: &T, writer: W) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	to_writer_with_header_and_config(value, writer, Config::default())
+}
+
+/// Validate the header at the start of a slice of bytes and deserialize the value after it using
+/// the given configuration, with the flags taken from the header.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_slice_with_header_and_config<'de, T>(bytes: &'de [u8], config: Config) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	let (header, bytes) = Header::split(bytes)?;
+	crate::from_slice_with_config(bytes, header.apply(config))
+}
+
+/// Validate the header at the start of a slice of bytes and deserialize the value after it.
+pub fn from_slice_with_header<'de, T>(bytes: &'de [u8]) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	from_slice_with_header_and_config(bytes, Config::default())
+}
+
+/// Validate the header at the start of a [Read]er and deserialize the value after it using the
+/// given configuration, with the flags taken from the header.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_reader_with_header_and_config<R, T>(mut reader: R, config: Config) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	let mut header = [0; Header::SIZE];
+	reader.read_exact(&mut header).map_err(|err| {
+		if err.kind() == ::std::io::ErrorKind::UnexpectedEof {
+			Error::UnexpectedEnd
+		} else {
+			Error::Io(err)
+		}
+	})?;
+	let header = Header::from_bytes(header)?;
+	crate::from_reader_with_config(reader, header.apply(config))
+}
+
+/// Validate the header at the start of a [Read]er and deserialize the value after it.
+#[cfg(feature = "std")]
+pub fn from_reader_with_header<R, T>(reader: R) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	from_reader_with_header_and_config(reader, Config::default())
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, clippy::indexing_slicing, reason = "Tests")]
+
+	use ::serde::{Deserialize, Serialize};
+
+	use super::*;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Data<'a> {
+		name: &'a str,
+		kind: Kind,
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Kind {
+		First,
+		Second,
+	}
+
+	#[test]
+	fn headers_roundtrip() {
+		let value = Data { name: "data", kind: Kind::Second };
+		for use_indices in [false, true] {
+			let config = Config { use_indices, ..Default::default() };
+			let mut buffer = [0; 64];
+			let bytes = to_slice_with_header_and_config(&value, &mut buffer, config).unwrap();
+			assert_eq!(bytes[.. 4], *b"SBRF");
+			assert_eq!(bytes[4 .. 6], [Header::VERSION, u8::from(use_indices)]);
+			assert_eq!(
+				Header::split(bytes).unwrap(),
+				(Header { use_indices, human_readable: false }, &bytes[6 ..])
+			);
+
+			// The flags are taken from the header, not from the configuration.
+			let parsed: Data<'_> = from_slice_with_header(bytes).unwrap();
+			assert_eq!(parsed, value);
+		}
+
+		let header = Header { use_indices: true, human_readable: true };
+		assert_eq!(Header::from_bytes(header.to_bytes()).unwrap(), header);
+		let config = header.apply(Config::default());
+		assert!(config.use_indices);
+		assert_eq!(config.human_readable, Some(true));
+
+		let mut buffer = [0; 6];
+		let result = to_slice_with_header(&(), &mut buffer);
+		assert!(matches!(result, Err(Error::BufferTooSmall)));
+	}
+
+	#[test]
+	fn wrong_headers_are_rejected() {
+		let mut buffer = [0; 16];
+		let bytes = to_slice_with_header(&5_u8, &mut buffer).unwrap();
+		assert_eq!(from_slice_with_header::<u8>(bytes).unwrap(), 5);
+
+		let result = from_slice_with_header::<u8>(&bytes[6 ..]);
+		assert!(matches!(result, Err(Error::UnexpectedEnd)));
+		let result = from_slice_with_header::<u8>(b"SBRG\x01\x00\x03\x05");
+		assert!(matches!(result, Err(Error::InvalidHeader)));
+		let result = from_slice_with_header::<u8>(b"SBRF\x02\x00\x03\x05");
+		assert!(matches!(result, Err(Error::UnsupportedVersion(2))));
+		let result = from_slice_with_header::<u8>(b"SBRF\x00\x00\x03\x05");
+		assert!(matches!(result, Err(Error::UnsupportedVersion(0))));
+		let result = from_slice_with_header::<u8>(b"SBRF\x01\x04\x03\x05");
+		assert!(matches!(result, Err(Error::InvalidHeader)));
+		let result = from_slice_with_header::<u8>(b"SBRF\x01");
+		assert!(matches!(result, Err(Error::UnexpectedEnd)));
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn readers_and_writers_roundtrip() {
+		let config = Config { use_indices: true, ..Default::default() };
+		let mut stream = Vec::new();
+		to_writer_with_header_and_config(&Kind::First, &mut stream, config).unwrap();
+		assert_eq!(stream, to_vec_with_header_and_config(&Kind::First, config).unwrap());
+		let value: Kind = from_reader_with_header(stream.as_slice()).unwrap();
+		assert_eq!(value, Kind::First);
+
+		let result = from_reader_with_header::<_, Kind>(&stream[.. 3]);
+		assert!(matches!(result, Err(Error::UnexpectedEnd)));
+		stream[0] = b'X';
+		let result = from_reader_with_header::<_, Kind>(stream.as_slice());
+		assert!(matches!(result, Err(Error::InvalidHeader)));
+	}
+}
@@ -5,12 +5,23 @@ use ::core::fmt::Display;
 use crate::format::Type;
 
 /// Error when (de-)serializing.
+///
+/// Errors can be cloned and compared, e.g. to assert on them in tests. IO errors are cloned and
+/// compared by their kind and message, or only their kind respectively. Use [Error::kind] to
+/// compare errors without their data.
 #[derive(Debug)]
 pub enum Error {
 	/// Expected more data but encountered the end of the input.
 	UnexpectedEnd,
 	/// Excess data appeared at the end of the input.
-	ExcessData,
+	ExcessData {
+		/// Number of unconsumed bytes, if known. Readers only read the next byte to detect excess
+		/// data, so there is at least one byte.
+		remaining: Option<usize>,
+		/// **alloc**: Up to the first 8 unconsumed bytes.
+		#[cfg(feature = "alloc")]
+		preview: ::alloc::vec::Vec<u8>,
+	},
 	/// Buffer was too small.
 	BufferTooSmall,
 	/// Allocation failure.
@@ -19,15 +30,78 @@ pub enum Error {
 	UsizeOverflow,
 	/// Configured size limit reached.
 	LimitReached,
+	/// Configured size limit reached with
+	/// [LimitBehavior::ErrorAfterRollback](crate::LimitBehavior::ErrorAfterRollback) while
+	/// serializing into a writer, after some bytes already reached it. Writers cannot be rolled
+	/// back, so the writer received a truncated document.
+	PartialWrite {
+		/// Number of bytes that were written to the writer, to resynchronize the stream.
+		written: usize,
+	},
+	/// Configured nesting depth limit reached.
+	DepthLimitReached,
+	/// Frame length prefix is larger than the available data.
+	FrameIncomplete,
+	/// Checksum does not match the data, so the data is corrupted.
+	ChecksumMismatch,
+	/// Data does not start with a valid header, see [envelope](crate::envelope).
+	InvalidHeader,
+	/// Header announces a format version that is not supported.
+	UnsupportedVersion(u8),
+	/// Serialized sequences or maps were not ended properly, or a length-prefixed sequence got a
+	/// different number of elements than announced.
+	UnbalancedCollection,
 
 	/// Invalid data type designator encountered.
 	InvalidType(u8),
 	/// VarInt too large for the given expected type.
 	VarIntTooLarge,
+	/// Length or index does not fit into `usize` of this platform, e.g. a document written on a
+	/// 64-bit platform is too large for a 16-bit target.
+	LengthExceedsPlatform(u64),
 	/// Wrong data type encountered (found, expected).
 	WrongType(Type, &'static [Type]),
+	/// Wrong data type encountered in a struct field.
+	WrongFieldType {
+		/// Name of the struct.
+		name: &'static str,
+		/// Name of the field.
+		field: &'static str,
+		/// Type that was found.
+		found: Type,
+		/// Types that were expected.
+		expected: &'static [Type],
+	},
 	/// String is not exactly one character.
 	NotOneChar,
+	/// Deserialized map contains the same key more than once, see
+	/// [Config::reject_duplicate_map_keys](crate::Config::reject_duplicate_map_keys). The
+	/// serialization counterpart is [Error::DuplicateSerializedKey].
+	DuplicateMapKey,
+	/// **alloc**: Serialized map or struct contains the given key more than once, see
+	/// [Config::detect_duplicate_keys](crate::Config::detect_duplicate_keys). The deserialization
+	/// counterpart is [Error::DuplicateMapKey].
+	#[cfg(feature = "alloc")]
+	DuplicateSerializedKey(::alloc::string::String),
+	/// Reference to a string that is not in the string table (yet).
+	UnknownStringRef(usize),
+	/// Map with index keys was deserialized by a type that needs field names, e.g. a struct with
+	/// `#[serde(flatten)]` or an internally tagged enum.
+	IndicesNotSupportedHere {
+		/// **alloc**: The error of the type, e.g. a missing field, in case the index keys were
+		/// intended after all.
+		#[cfg(feature = "alloc")]
+		cause: ::alloc::boxed::Box<Error>,
+	},
+	/// Type only accepts borrowed data, e.g. `&str` or `&[u8]`, but the data could not be
+	/// borrowed, because the input does not hold it for the deserializer's lifetime, e.g. a
+	/// reader. Strings of the string table cannot be borrowed either. Use owned types like
+	/// `String` or `Cow`s with [helpers::cow_str](crate::helpers::cow_str) instead.
+	CannotBorrow,
+	/// Duplicate map keys cannot be rejected, because the input can neither lend its data nor
+	/// record it to compare the encoded keys. Wrap the input in a `RecordingInput`. Without the
+	/// **alloc** feature, keys are never compared.
+	CannotRecord,
 
 	/// Formatting error. Happens serializing a `core::fmt::Display` value and could be due to an
 	/// output writing failure.
@@ -37,6 +111,15 @@ pub enum Error {
 	/// IO error.
 	#[cfg(feature = "std")]
 	Io(::std::io::Error),
+	/// **embedded-io**: IO error of the given kind, as the error types are generic.
+	#[cfg(feature = "embedded-io")]
+	EmbeddedIo(::embedded_io::ErrorKind),
+	/// **alloc**: Value cannot be represented in JSON, for the given reason.
+	#[cfg(feature = "alloc")]
+	NotJsonCompatible(&'static str),
+	/// **base64**: Text encoding of the data is invalid, for the given reason.
+	#[cfg(feature = "text")]
+	Encoding(&'static str),
 
 	/// **no-std + no-alloc**: Generic error message that can be created by data structures through
 	/// the `ser::Error` and `de::Error` traits.
@@ -47,32 +130,183 @@ pub enum Error {
 	Message(::alloc::string::String),
 }
 
+/// The kind of an [Error], without its data. See [Error::kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorKind {
+	/// See [Error::UnexpectedEnd].
+	UnexpectedEnd,
+	/// See [Error::ExcessData].
+	ExcessData,
+	/// See [Error::BufferTooSmall].
+	BufferTooSmall,
+	/// See [Error::Allocation].
+	Allocation,
+	/// See [Error::UsizeOverflow].
+	UsizeOverflow,
+	/// See [Error::LimitReached].
+	LimitReached,
+	/// See [Error::PartialWrite].
+	PartialWrite,
+	/// See [Error::DepthLimitReached].
+	DepthLimitReached,
+	/// See [Error::FrameIncomplete].
+	FrameIncomplete,
+	/// See [Error::ChecksumMismatch].
+	ChecksumMismatch,
+	/// See [Error::InvalidHeader].
+	InvalidHeader,
+	/// See [Error::UnsupportedVersion].
+	UnsupportedVersion,
+	/// See [Error::UnbalancedCollection].
+	UnbalancedCollection,
+	/// See [Error::InvalidType].
+	InvalidType,
+	/// See [Error::VarIntTooLarge].
+	VarIntTooLarge,
+	/// See [Error::LengthExceedsPlatform].
+	LengthExceedsPlatform,
+	/// See [Error::WrongType].
+	WrongType,
+	/// See [Error::WrongFieldType].
+	WrongFieldType,
+	/// See [Error::NotOneChar].
+	NotOneChar,
+	/// See [Error::DuplicateMapKey].
+	DuplicateMapKey,
+	/// **alloc**: See [Error::DuplicateSerializedKey].
+	#[cfg(feature = "alloc")]
+	DuplicateSerializedKey,
+	/// See [Error::UnknownStringRef].
+	UnknownStringRef,
+	/// See [Error::IndicesNotSupportedHere].
+	IndicesNotSupportedHere,
+	/// See [Error::CannotBorrow].
+	CannotBorrow,
+	/// See [Error::CannotRecord].
+	CannotRecord,
+	/// See [Error::Format].
+	Format,
+	/// See [Error::StringNotUtf8].
+	StringNotUtf8,
+	/// **std**: See [Error::Io].
+	#[cfg(feature = "std")]
+	Io,
+	/// **embedded-io**: See [Error::EmbeddedIo].
+	#[cfg(feature = "embedded-io")]
+	EmbeddedIo,
+	/// **alloc**: See [Error::NotJsonCompatible].
+	#[cfg(feature = "alloc")]
+	NotJsonCompatible,
+	/// **base64**: See [Error::Encoding].
+	#[cfg(feature = "text")]
+	Encoding,
+	/// See [Error::Custom].
+	Custom,
+	/// **alloc**: See [Error::Message].
+	#[cfg(feature = "alloc")]
+	Message,
+}
+
 impl Display for Error {
 	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
 		match self {
 			Error::UnexpectedEnd => {
 				write!(f, "Expected more data but encountered the end of the input")
 			}
-			Error::ExcessData => write!(f, "Excess data appeared at the end of the input"),
+			Error::ExcessData { remaining, .. } => {
+				write!(f, "Excess data appeared at the end of the input: ")?;
+				match remaining {
+					Some(remaining) => write!(f, "{remaining} bytes")?,
+					None => write!(f, "at least 1 byte")?,
+				}
+				#[cfg(feature = "alloc")]
+				if let Error::ExcessData { preview, .. } = self {
+					write!(f, " starting with 0x")?;
+					for byte in preview {
+						write!(f, "{byte:02X}")?;
+					}
+				}
+				Ok(())
+			}
 			Error::BufferTooSmall => write!(f, "Output or scratch buffer was too small"),
 			Error::Allocation => write!(f, "Allocator failed on allocating more space"),
 			Error::UsizeOverflow => write!(f, "Tried using more bytes than usize allows for"),
 			Error::LimitReached => write!(f, "Configured size limit reached"),
+			Error::PartialWrite { written } => {
+				write!(f, "Configured size limit reached after writing {written} bytes")
+			}
+			Error::DepthLimitReached => write!(f, "Configured nesting depth limit reached"),
+			Error::FrameIncomplete => {
+				write!(f, "Frame length prefix is larger than the available data")
+			}
+			Error::ChecksumMismatch => write!(f, "Checksum does not match the data"),
+			Error::InvalidHeader => {
+				write!(f, "Data does not start with a valid serde-brief header")
+			}
+			Error::UnsupportedVersion(version) => {
+				write!(f, "Format version {version} is not supported")
+			}
+			Error::UnbalancedCollection => {
+				write!(f, "Serialized sequences or maps were not ended properly")
+			}
 
 			Error::InvalidType(v) => {
 				write!(f, "Invalid data type designator encountered: {v:#02X}")
 			}
 			Error::VarIntTooLarge => write!(f, "VarInt too large for the given expected type"),
+			Error::LengthExceedsPlatform(len) => {
+				write!(f, "Length {len} exceeds the address space of this platform")
+			}
 			Error::WrongType(found, expected) => write!(
 				f,
 				"Wrong data type encountered. Found `{found:?}`, but expected one of `{expected:?}`"
 			),
+			Error::WrongFieldType { name, field, found, expected } => write!(
+				f,
+				"Wrong data type in `{name}.{field}`. Found `{found:?}`, but expected one of \
+				 `{expected:?}`"
+			),
 			Error::NotOneChar => write!(f, "String is not exactly one character"),
+			Error::DuplicateMapKey => write!(f, "Map contains the same key more than once"),
+			#[cfg(feature = "alloc")]
+			Error::DuplicateSerializedKey(key) => {
+				write!(f, "Serialized map contains the key {key} more than once")
+			}
+			Error::UnknownStringRef(index) => {
+				write!(f, "Reference to string {index}, which is not in the string table")
+			}
+			Error::IndicesNotSupportedHere { .. } => {
+				write!(
+					f,
+					"Index keys cannot be matched to field names here, e.g. for `#[serde(flatten)]` \
+					 or internally tagged enums. Serialize without `use_indices` or set \
+					 `Config::tag_fields` for internally tagged enums"
+				)?;
+				#[cfg(feature = "alloc")]
+				if let Error::IndicesNotSupportedHere { cause } = self {
+					write!(f, " (the type reported: {cause})")?;
+				}
+				Ok(())
+			}
+			Error::CannotBorrow => write!(
+				f,
+				"Borrowed data cannot be produced from this input, e.g. a reader. Deserialize owned \
+				 types instead"
+			),
+			Error::CannotRecord => write!(f, "Input cannot record map keys to detect duplicates"),
 
 			Error::Format(err) => write!(f, "Value formatting error: {err:#}"),
 			Error::StringNotUtf8(err) => write!(f, "String is not valid UTF-8: {err:#}"),
 			#[cfg(feature = "std")]
 			Error::Io(err) => write!(f, "IO error: {err:#}"),
+			#[cfg(feature = "embedded-io")]
+			Error::EmbeddedIo(kind) => write!(f, "IO error: {kind:?}"),
+			#[cfg(feature = "alloc")]
+			Error::NotJsonCompatible(reason) => {
+				write!(f, "Value cannot be represented in JSON: {reason}")
+			}
+			#[cfg(feature = "text")]
+			Error::Encoding(reason) => write!(f, "Invalid text encoding: {reason}"),
 
 			Error::Custom => write!(f, "Unknown custom error"),
 			#[cfg(feature = "alloc")]
@@ -81,6 +315,255 @@ impl Display for Error {
 	}
 }
 
+impl Error {
+	/// The kind of this error, without its data.
+	#[must_use]
+	pub const fn kind(&self) -> ErrorKind {
+		match self {
+			Error::UnexpectedEnd => ErrorKind::UnexpectedEnd,
+			Error::ExcessData { .. } => ErrorKind::ExcessData,
+			Error::BufferTooSmall => ErrorKind::BufferTooSmall,
+			Error::Allocation => ErrorKind::Allocation,
+			Error::UsizeOverflow => ErrorKind::UsizeOverflow,
+			Error::LimitReached => ErrorKind::LimitReached,
+			Error::PartialWrite { .. } => ErrorKind::PartialWrite,
+			Error::DepthLimitReached => ErrorKind::DepthLimitReached,
+			Error::FrameIncomplete => ErrorKind::FrameIncomplete,
+			Error::ChecksumMismatch => ErrorKind::ChecksumMismatch,
+			Error::InvalidHeader => ErrorKind::InvalidHeader,
+			Error::UnsupportedVersion(_) => ErrorKind::UnsupportedVersion,
+			Error::UnbalancedCollection => ErrorKind::UnbalancedCollection,
+			Error::InvalidType(_) => ErrorKind::InvalidType,
+			Error::VarIntTooLarge => ErrorKind::VarIntTooLarge,
+			Error::LengthExceedsPlatform(_) => ErrorKind::LengthExceedsPlatform,
+			Error::WrongType(..) => ErrorKind::WrongType,
+			Error::WrongFieldType { .. } => ErrorKind::WrongFieldType,
+			Error::NotOneChar => ErrorKind::NotOneChar,
+			Error::DuplicateMapKey => ErrorKind::DuplicateMapKey,
+			#[cfg(feature = "alloc")]
+			Error::DuplicateSerializedKey(_) => ErrorKind::DuplicateSerializedKey,
+			Error::UnknownStringRef(_) => ErrorKind::UnknownStringRef,
+			Error::IndicesNotSupportedHere { .. } => ErrorKind::IndicesNotSupportedHere,
+			Error::CannotBorrow => ErrorKind::CannotBorrow,
+			Error::CannotRecord => ErrorKind::CannotRecord,
+			Error::Format(_) => ErrorKind::Format,
+			Error::StringNotUtf8(_) => ErrorKind::StringNotUtf8,
+			#[cfg(feature = "std")]
+			Error::Io(_) => ErrorKind::Io,
+			#[cfg(feature = "embedded-io")]
+			Error::EmbeddedIo(_) => ErrorKind::EmbeddedIo,
+			#[cfg(feature = "alloc")]
+			Error::NotJsonCompatible(_) => ErrorKind::NotJsonCompatible,
+			#[cfg(feature = "text")]
+			Error::Encoding(_) => ErrorKind::Encoding,
+			Error::Custom => ErrorKind::Custom,
+			#[cfg(feature = "alloc")]
+			Error::Message(_) => ErrorKind::Message,
+		}
+	}
+
+	/// The type that was found, for [Error::WrongType] and [Error::WrongFieldType].
+	#[must_use]
+	pub const fn found_type(&self) -> Option<Type> {
+		match self {
+			Error::WrongType(found, _) | Error::WrongFieldType { found, .. } => Some(*found),
+			_ => None,
+		}
+	}
+
+	/// The types that were expected, for [Error::WrongType] and [Error::WrongFieldType].
+	#[must_use]
+	pub const fn expected_types(&self) -> Option<&'static [Type]> {
+		match self {
+			Error::WrongType(_, expected) | Error::WrongFieldType { expected, .. } => {
+				Some(expected)
+			}
+			_ => None,
+		}
+	}
+
+	/// Whether the error is likely caused by data written with the other identifier mode than
+	/// expected, i.e. field indices (`use_indices`) where names were expected or the other way
+	/// round. This is the case for [Error::IndicesNotSupportedHere] and for wrong types where an
+	/// unsigned integer was found but only strings were expected, or a string was found but only
+	/// unsigned integers were expected. These typically happen when reading struct data into maps
+	/// with string or integer keys, or with `strict_keys` enabled. The error does not tell whether
+	/// the value was a key, so a string value where an integer was expected is reported as well.
+	#[must_use]
+	pub fn is_index_mode_mismatch(&self) -> bool {
+		if matches!(self, Error::IndicesNotSupportedHere { .. }) {
+			return true;
+		}
+		let (Some(found), Some(expected)) = (self.found_type(), self.expected_types()) else {
+			return false;
+		};
+		let expects_index = expected.contains(&Type::UnsignedInt);
+		let expects_name = expected.contains(&Type::String);
+		match found {
+			Type::UnsignedInt => expects_name && !expects_index,
+			Type::String | Type::StringDef | Type::StringRef => expects_index && !expects_name,
+			_ => false,
+		}
+	}
+
+	/// Number of unconsumed bytes to show in [Error::ExcessData].
+	#[cfg(feature = "alloc")]
+	const EXCESS_PREVIEW: usize = 8;
+
+	/// Create an [Error::ExcessData] for the given unconsumed bytes of the input.
+	#[allow(clippy::missing_const_for_fn, reason = "Different feature sets")]
+	pub(crate) fn excess_data(remaining: &[u8]) -> Self {
+		Error::ExcessData {
+			remaining: Some(remaining.len()),
+			#[cfg(feature = "alloc")]
+			preview: remaining.iter().take(Self::EXCESS_PREVIEW).copied().collect(),
+		}
+	}
+
+	/// Create an [Error::ExcessData] for the next byte of an input, where the number of
+	/// unconsumed bytes is unknown.
+	#[cfg_attr(not(feature = "alloc"), expect(unused_variables, reason = "Only used with alloc"))]
+	#[allow(clippy::missing_const_for_fn, reason = "Different feature sets")]
+	pub(crate) fn excess_byte(byte: u8) -> Self {
+		Error::ExcessData {
+			remaining: None,
+			#[cfg(feature = "alloc")]
+			preview: ::alloc::vec![byte],
+		}
+	}
+}
+
+impl Clone for Error {
+	fn clone(&self) -> Self {
+		match self {
+			Error::UnexpectedEnd => Error::UnexpectedEnd,
+			Error::ExcessData {
+				remaining,
+				#[cfg(feature = "alloc")]
+				preview,
+			} => Error::ExcessData {
+				remaining: *remaining,
+				#[cfg(feature = "alloc")]
+				preview: preview.clone(),
+			},
+			Error::BufferTooSmall => Error::BufferTooSmall,
+			Error::Allocation => Error::Allocation,
+			Error::UsizeOverflow => Error::UsizeOverflow,
+			Error::LimitReached => Error::LimitReached,
+			Error::PartialWrite { written } => Error::PartialWrite { written: *written },
+			Error::DepthLimitReached => Error::DepthLimitReached,
+			Error::FrameIncomplete => Error::FrameIncomplete,
+			Error::ChecksumMismatch => Error::ChecksumMismatch,
+			Error::InvalidHeader => Error::InvalidHeader,
+			Error::UnsupportedVersion(version) => Error::UnsupportedVersion(*version),
+			Error::UnbalancedCollection => Error::UnbalancedCollection,
+			Error::InvalidType(byte) => Error::InvalidType(*byte),
+			Error::VarIntTooLarge => Error::VarIntTooLarge,
+			Error::LengthExceedsPlatform(len) => Error::LengthExceedsPlatform(*len),
+			Error::WrongType(found, expected) => Error::WrongType(*found, expected),
+			Error::WrongFieldType { name, field, found, expected } => {
+				Error::WrongFieldType { name, field, found: *found, expected }
+			}
+			Error::NotOneChar => Error::NotOneChar,
+			Error::DuplicateMapKey => Error::DuplicateMapKey,
+			#[cfg(feature = "alloc")]
+			Error::DuplicateSerializedKey(key) => Error::DuplicateSerializedKey(key.clone()),
+			Error::UnknownStringRef(index) => Error::UnknownStringRef(*index),
+			Error::IndicesNotSupportedHere {
+				#[cfg(feature = "alloc")]
+				cause,
+			} => Error::IndicesNotSupportedHere {
+				#[cfg(feature = "alloc")]
+				cause: cause.clone(),
+			},
+			Error::CannotBorrow => Error::CannotBorrow,
+			Error::CannotRecord => Error::CannotRecord,
+			Error::Format(err) => Error::Format(*err),
+			Error::StringNotUtf8(err) => Error::StringNotUtf8(*err),
+			#[cfg(feature = "std")]
+			Error::Io(err) => Error::Io(::std::io::Error::new(err.kind(), err.to_string())),
+			#[cfg(feature = "embedded-io")]
+			Error::EmbeddedIo(kind) => Error::EmbeddedIo(*kind),
+			#[cfg(feature = "alloc")]
+			Error::NotJsonCompatible(reason) => Error::NotJsonCompatible(reason),
+			#[cfg(feature = "text")]
+			Error::Encoding(reason) => Error::Encoding(reason),
+			Error::Custom => Error::Custom,
+			#[cfg(feature = "alloc")]
+			Error::Message(msg) => Error::Message(msg.clone()),
+		}
+	}
+}
+
+impl PartialEq for Error {
+	fn eq(&self, other: &Self) -> bool {
+		match (self, other) {
+			(
+				Error::ExcessData {
+					remaining,
+					#[cfg(feature = "alloc")]
+					preview,
+				},
+				Error::ExcessData {
+					remaining: other_remaining,
+					#[cfg(feature = "alloc")]
+						preview: other_preview,
+				},
+			) => {
+				#[cfg(feature = "alloc")]
+				if preview != other_preview {
+					return false;
+				}
+				remaining == other_remaining
+			}
+			(Error::UnsupportedVersion(a), Error::UnsupportedVersion(b))
+			| (Error::InvalidType(a), Error::InvalidType(b)) => a == b,
+			(Error::WrongType(found, expected), Error::WrongType(other_found, other_expected)) => {
+				found == other_found && expected == other_expected
+			}
+			(
+				Error::WrongFieldType { name, field, found, expected },
+				Error::WrongFieldType {
+					name: other_name,
+					field: other_field,
+					found: other_found,
+					expected: other_expected,
+				},
+			) => {
+				name == other_name
+					&& field == other_field
+					&& found == other_found
+					&& expected == other_expected
+			}
+			(Error::LengthExceedsPlatform(a), Error::LengthExceedsPlatform(b)) => a == b,
+			(Error::UnknownStringRef(a), Error::UnknownStringRef(b))
+			| (Error::PartialWrite { written: a }, Error::PartialWrite { written: b }) => a == b,
+			(Error::Format(a), Error::Format(b)) => a == b,
+			(Error::StringNotUtf8(a), Error::StringNotUtf8(b)) => a == b,
+			#[cfg(feature = "std")]
+			(Error::Io(a), Error::Io(b)) => a.kind() == b.kind(),
+			#[cfg(feature = "embedded-io")]
+			(Error::EmbeddedIo(a), Error::EmbeddedIo(b)) => a == b,
+			#[cfg(feature = "alloc")]
+			(Error::NotJsonCompatible(a), Error::NotJsonCompatible(b)) => a == b,
+			#[cfg(feature = "alloc")]
+			(
+				Error::IndicesNotSupportedHere { cause },
+				Error::IndicesNotSupportedHere { cause: other_cause },
+			) => cause == other_cause,
+			#[cfg(feature = "text")]
+			(Error::Encoding(a), Error::Encoding(b)) => a == b,
+			#[cfg(feature = "alloc")]
+			(Error::DuplicateSerializedKey(a), Error::DuplicateSerializedKey(b))
+			| (Error::Message(a), Error::Message(b)) => a == b,
+			// Variants with data are compared above, the remaining ones only have their kind.
+			_ => self.kind() == other.kind(),
+		}
+	}
+}
+
+impl Eq for Error {}
+
 impl ::core::error::Error for Error {
 	fn source(&self) -> Option<&(dyn ::core::error::Error + 'static)> {
 		match self {
@@ -0,0 +1,407 @@
+//! Formatting utilities for inspecting raw serialized bytes.
+
+use ::core::fmt::{self, Write};
+
+use crate::{
+	format::{decode_length, decode_unsigned, Type, VarInt},
+	io::Input,
+	Error, Result,
+};
+
+/// Maximum number of characters of strings to show.
+const STRING_PREVIEW: usize = 32;
+/// Maximum number of bytes to show.
+const BYTES_PREVIEW: usize = 16;
+
+/// Write an indented tree of the raw serialized bytes to the output, without knowing the schema.
+/// Each line starts with the hexadecimal offset of the item, followed by the type, lengths and a
+/// preview of the data. All consecutive values in the bytes are printed.
+///
+/// Malformed input is printed up to the point where it fails to parse, followed by an error line.
+/// Only errors of the output are returned.
+///
+/// ```rust
+/// let bytes = serde_brief::to_slice(&("key", [1_u8, 2]), &mut [0; 32]).unwrap().to_vec();
+/// let mut out = String::new();
+/// serde_brief::fmt::dump(&bytes, &mut out).unwrap();
+/// assert_eq!(
+/// 	out,
+/// 	"\
+/// 0000  SeqStart
+/// 0001    String (3 bytes) \"key\"
+/// 0006    SeqStart
+/// 0007      UnsignedInt 1
+/// 0009      UnsignedInt 2
+/// 000B    SeqEnd
+/// 000C  SeqEnd
+/// "
+/// );
+/// ```
+pub fn dump<W>(bytes: &[u8], out: &mut W) -> fmt::Result
+where
+	W: Write + ?Sized,
+{
+	let mut input = bytes;
+	dump_items(bytes, &mut input, out, 0, None).map(|_| ())
+}
+
+/// Write the items at the given depth, until the end of the collection or the input. With
+/// `remaining`, exactly that many values are written instead. Returns `false` if an error line was
+/// written and nothing more should be printed.
+fn dump_items<W>(
+	bytes: &[u8],
+	input: &mut &[u8],
+	out: &mut W,
+	depth: usize,
+	mut remaining: Option<usize>,
+) -> Result<bool, fmt::Error>
+where
+	W: Write + ?Sized,
+{
+	loop {
+		match &mut remaining {
+			Some(0) => return Ok(true),
+			Some(remaining) => *remaining -= 1,
+			None if depth == 0 && input.is_empty() => return Ok(true),
+			None => {}
+		}
+
+		let offset = bytes.len() - input.len();
+		let item = match read_item(input) {
+			Ok(item) => item,
+			Err(err) => return write_error(out, offset, depth, &err).map(|()| false),
+		};
+
+		if matches!(item, Item::End(_)) {
+			if depth == 0 || remaining.is_some() {
+				return write_error(out, offset, depth, &Error::UnbalancedCollection)
+					.map(|()| false);
+			}
+			write!(out, "{offset:04X}  {:indent$}", "", indent = (depth - 1) * 2)?;
+			write_item(out, &item)?;
+			out.write_char('\n')?;
+			return Ok(true);
+		}
+		write!(out, "{offset:04X}  {:indent$}", "", indent = depth * 2)?;
+		write_item(out, &item)?;
+		out.write_char('\n')?;
+
+		let elements = match item {
+			Item::Start(_) => None,
+			Item::SeqFixed(len) => Some(len),
+			_ => continue,
+		};
+		if depth >= crate::config::DEFAULT_MAX_DEPTH {
+			let offset = bytes.len() - input.len();
+			return write_error(out, offset, depth + 1, &Error::DepthLimitReached).map(|()| false);
+		}
+		if !dump_items(bytes, input, out, depth + 1, elements)? {
+			return Ok(false);
+		}
+	}
+}
+
+/// Single item of the format.
+#[derive(Debug)]
+enum Item<'a> {
+	/// Type without data.
+	Plain(Type),
+	/// Unsigned integer.
+	UnsignedInt(u128),
+	/// Signed integer.
+	SignedInt(i128),
+	/// Float of any precision, converted to `f64` where necessary.
+	Float(Type, f64),
+	/// Bytes or string.
+	Data(Type, &'a [u8]),
+	/// Reference to a string of the string table.
+	StringRef(usize),
+	/// Start of a sequence or map.
+	Start(Type),
+	/// Start of a sequence with the given number of elements.
+	SeqFixed(usize),
+	/// End of a sequence or map.
+	End(Type),
+}
+
+/// Read the next item from the input.
+fn read_item<'a>(input: &mut &'a [u8]) -> Result<Item<'a>> {
+	let byte = input.read_byte()?;
+	let t = Type::try_from(byte)?;
+	Ok(match t {
+		Type::Null | Type::BooleanFalse | Type::BooleanTrue | Type::Some => Item::Plain(t),
+		Type::UnsignedInt => Item::UnsignedInt(decode_unsigned(byte, input)?),
+		Type::SignedInt => Item::SignedInt(i128::decode(input)?),
+		Type::Float16 => {
+			let mut bytes = [0; 2];
+			input.read_exact(&mut bytes)?;
+			Item::Float(t, crate::format::f16_bits_to_f32(u16::from_le_bytes(bytes)).into())
+		}
+		Type::Float32 => {
+			let mut bytes = [0; 4];
+			input.read_exact(&mut bytes)?;
+			Item::Float(t, f32::from_le_bytes(bytes).into())
+		}
+		Type::Float64 => {
+			let mut bytes = [0; 8];
+			input.read_exact(&mut bytes)?;
+			Item::Float(t, f64::from_le_bytes(bytes))
+		}
+		Type::Float128 => {
+			let mut bytes = [0; 16];
+			input.read_exact(&mut bytes)?;
+			Item::Float(t, crate::format::f128_bits_to_f64(u128::from_le_bytes(bytes)))
+		}
+		Type::Bytes | Type::String | Type::StringDef => {
+			let len = decode_length(input)?;
+			let data = input.read_bytes::<()>(len, None)?.ok_or_else(|| Error::UnexpectedEnd)?;
+			Item::Data(t, data)
+		}
+		Type::SeqStart | Type::MapStart => Item::Start(t),
+		Type::SeqFixed => Item::SeqFixed(decode_length(input)?),
+		Type::StringRef => Item::StringRef(decode_length(input)?),
+		Type::SeqEnd | Type::MapEnd => Item::End(t),
+	})
+}
+
+/// Write the description of an item.
+fn write_item<W>(out: &mut W, item: &Item<'_>) -> fmt::Result
+where
+	W: Write + ?Sized,
+{
+	match item {
+		Item::Plain(t) | Item::Start(t) | Item::End(t) => out.write_str(t.name()),
+		Item::SeqFixed(len) => write!(out, "{} ({len} elements)", Type::SeqFixed.name()),
+		Item::StringRef(index) => write!(out, "{} {index}", Type::StringRef.name()),
+		Item::UnsignedInt(int) => write!(out, "{} {int}", Type::UnsignedInt.name()),
+		Item::SignedInt(int) => write!(out, "{} {int}", Type::SignedInt.name()),
+		Item::Float(t, float) => write!(out, "{} {float}", t.name()),
+		Item::Data(t, data) => {
+			write!(out, "{} ({} bytes) ", t.name(), data.len())?;
+			if !matches!(t, Type::String | Type::StringDef) {
+				return write_bytes_preview(out, data);
+			}
+			match ::core::str::from_utf8(data) {
+				Ok(s) => write_string_preview(out, s),
+				Err(_) => {
+					write_bytes_preview(out, data)?;
+					out.write_str(" (invalid UTF-8)")
+				}
+			}
+		}
+	}
+}
+
+/// Write the escaped, possibly shortened string.
+fn write_string_preview<W>(out: &mut W, s: &str) -> fmt::Result
+where
+	W: Write + ?Sized,
+{
+	out.write_char('"')?;
+	for c in s.chars().take(STRING_PREVIEW) {
+		write!(out, "{}", c.escape_debug())?;
+	}
+	out.write_char('"')?;
+	if s.chars().nth(STRING_PREVIEW).is_some() {
+		out.write_str("...")?;
+	}
+	Ok(())
+}
+
+/// Write the possibly shortened bytes in hexadecimal.
+fn write_bytes_preview<W>(out: &mut W, data: &[u8]) -> fmt::Result
+where
+	W: Write + ?Sized,
+{
+	out.write_str("0x")?;
+	for byte in data.iter().take(BYTES_PREVIEW) {
+		write!(out, "{byte:02X}")?;
+	}
+	if data.len() > BYTES_PREVIEW {
+		out.write_str("...")?;
+	}
+	Ok(())
+}
+
+/// Write the line for an error at the given offset.
+fn write_error<W>(out: &mut W, offset: usize, depth: usize, err: &Error) -> fmt::Result
+where
+	W: Write + ?Sized,
+{
+	writeln!(out, "{offset:04X}  {:indent$}error: {err}", "", indent = depth * 2)
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, reason = "Tests")]
+
+	use super::*;
+
+	/// Output that writes into a fixed buffer.
+	struct Buffer {
+		/// The buffer.
+		data: [u8; 1024],
+		/// Number of bytes written.
+		len: usize,
+	}
+
+	impl Buffer {
+		fn new() -> Self {
+			Self { data: [0; 1024], len: 0 }
+		}
+
+		fn as_str(&self) -> &str {
+			::core::str::from_utf8(self.data.get(.. self.len).unwrap()).unwrap()
+		}
+	}
+
+	impl Write for Buffer {
+		fn write_str(&mut self, s: &str) -> fmt::Result {
+			let end = self.len + s.len();
+			self.data.get_mut(self.len .. end).ok_or(fmt::Error)?.copy_from_slice(s.as_bytes());
+			self.len = end;
+			Ok(())
+		}
+	}
+
+	fn dumped(bytes: &[u8]) -> Buffer {
+		let mut out = Buffer::new();
+		dump(bytes, &mut out).unwrap();
+		out
+	}
+
+	#[test]
+	fn dump_prints_tree() {
+		let mut buffer = [0; 256];
+		let value = (
+			-5_i8,
+			1.5_f32,
+			[(true, ())],
+			"a string that is a lot longer than the preview\n",
+			::serde_bytes::Bytes::new(&[0xAB; 20]),
+		);
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert_eq!(
+			dumped(bytes).as_str(),
+			"\
+0000  SeqStart
+0001    SignedInt -5
+0003    Float32 1.5
+0008    SeqStart
+0009      SeqStart
+000A        BooleanTrue
+000B        Null
+000C      SeqEnd
+000D    SeqEnd
+000E    String (47 bytes) \"a string that is a lot longer th\"...
+003F    Bytes (20 bytes) 0xABABABABABABABABABABABABABABABAB...
+0055  SeqEnd
+"
+		);
+
+		// Multiple values and floats of all kinds.
+		let bytes = [
+			Type::Float16.into(),
+			0x00,
+			0x3C,
+			Type::MapStart.into(),
+			Type::String.into(),
+			1,
+			b'k',
+			Type::Bytes.into(),
+			2,
+			0xFF,
+			0xFE,
+			Type::MapEnd.into(),
+		];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"\
+0000  Float16 1
+0003  MapStart
+0004    String (1 bytes) \"k\"
+0007    Bytes (2 bytes) 0xFFFE
+000B  MapEnd
+"
+		);
+	}
+
+	#[test]
+	fn dump_prints_length_prefixed_seqs() {
+		let mut buffer = [0; 64];
+		let config = crate::Config { length_prefixed_seqs: true, ..Default::default() };
+		let bytes =
+			crate::to_slice_with_config(&([1_u8], [(); 0], 2_u8), &mut buffer, config).unwrap();
+		assert_eq!(
+			dumped(bytes).as_str(),
+			"\
+0000  SeqFixed (3 elements)
+0002    SeqFixed (1 elements)
+0004      UnsignedInt 1
+0006    SeqFixed (0 elements)
+0008    UnsignedInt 2
+"
+		);
+
+		let bytes = [Type::SeqFixed.into(), 2, Type::Null.into(), Type::SeqEnd.into()];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"0000  SeqFixed (2 elements)\n0002    Null\n0003    error: Serialized sequences \
+			 or maps were not ended properly\n"
+		);
+	}
+
+	#[test]
+	fn dump_prints_string_table() {
+		let bytes = [
+			Type::MapStart.into(),
+			Type::StringDef.into(),
+			1,
+			b'k',
+			Type::StringRef.into(),
+			0,
+			Type::MapEnd.into(),
+		];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"\
+0000  MapStart
+0001    StringDef (1 bytes) \"k\"
+0004    StringRef 0
+0006  MapEnd
+"
+		);
+	}
+
+	#[test]
+	fn dump_prints_errors() {
+		let bytes = [Type::SeqStart.into(), Type::UnsignedInt.into(), 0x80];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"0000  SeqStart\n0001    error: Expected more data but encountered the end of the input\n"
+		);
+
+		let bytes = [Type::SeqStart.into(), Type::Null.into()];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"0000  SeqStart\n0001    Null\n0002    error: Expected more data but encountered the end \
+			 of the input\n"
+		);
+
+		let bytes = [Type::Null.into(), 0xEE];
+		assert_eq!(
+			dumped(&bytes).as_str(),
+			"0000  Null\n0001  error: Invalid data type designator encountered: 0xEE\n"
+		);
+
+		let bytes = [Type::SeqEnd.into()];
+		assert!(dumped(&bytes).as_str().starts_with("0000  error: Serialized sequences"));
+
+		let bytes = [Type::String.into(), 2, 0xFF, 0xFE];
+		assert_eq!(dumped(&bytes).as_str(), "0000  String (2 bytes) 0xFFFE (invalid UTF-8)\n");
+
+		let mut out = Buffer::new();
+		out.len = 1020;
+		assert!(dump(&[Type::Null.into()], &mut out).is_err());
+	}
+}
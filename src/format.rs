@@ -1,4 +1,23 @@
-//! Data format internals.
+//! Building blocks of the binary format, for tools and other implementations of it.
+//!
+//! The type codes of [Type] and the [VarInt] encoding are part of the wire format, as described in
+//! the [format specification](crate::docs::format). They do not change within a major version,
+//! but new types might be added, so [Type] is non-exhaustive. [VarInt] is sealed, as the encoding
+//! is only defined for the primitive integers. To use the encoding in other protocols, see the
+//! stable, slice-oriented functions in [varint](crate::varint).
+//!
+//! ```rust
+//! use serde_brief::format::{decode_length, PayloadKind, Type};
+//!
+//! let mut buffer = [0; 16];
+//! let bytes = serde_brief::to_slice(&"hi", &mut buffer).unwrap();
+//! let t = Type::try_from(bytes[0]).unwrap();
+//! assert_eq!(t.name(), "String");
+//! assert_eq!(t.payload_kind(), PayloadKind::LengthPrefixed);
+//! let mut rest = &bytes[1 ..];
+//! let len = decode_length(&mut rest).unwrap();
+//! assert_eq!(&rest[.. len], b"hi");
+//! ```
 
 use crate::{
 	io::{Input, Output},
@@ -8,6 +27,7 @@ use crate::{
 /// The binary type identifier.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(u8)]
+#[non_exhaustive]
 pub enum Type {
 	/// The `null` or unit or none type. There is no additional byte value.
 	Null = 0,
@@ -20,6 +40,9 @@ pub enum Type {
 	/// Format: Next bytes are the `VarInt` encoding of the unsigned number. The most-significant
 	/// bit of each byte says whether there is a next byte. The bytes are in little-endian oder, so
 	/// the first byte contains the least significant bits.
+	///
+	/// Alternatively, values up to 63 can be inline in the type byte, which is then `64 + value`
+	/// without following bytes. These type bytes are recognized as this type.
 	UnsignedInt = 3,
 	/// The signed `integer` type.
 	///
@@ -45,6 +68,17 @@ pub enum Type {
 	///
 	/// Format: Same as bytes, but all bytes must be valid UTF-8.
 	String = 11,
+	/// The `string` type, which is added to the string table, only written when the key
+	/// dictionary is enabled. The strings of the table are numbered in the order of their
+	/// definitions, starting at 0.
+	///
+	/// Format: Same as string.
+	StringDef = 12,
+	/// Reference to a string of the string table, only written when the key dictionary is
+	/// enabled.
+	///
+	/// Format: The first bytes are the `VarInt` encoding of the string's number in the table.
+	StringRef = 13,
 	/// The `sequence` type consists of start, data and end. This is the start designator.
 	///
 	/// Format: Any number of elements follow, then the end designator.
@@ -58,6 +92,16 @@ pub enum Type {
 	MapStart = 17,
 	/// The end designator for the `map` type.
 	MapEnd = 18,
+	/// The `some` marker of an option, only written when explicit options are enabled.
+	///
+	/// Format: The contained value follows.
+	Some = 19,
+	/// The `sequence` type with a known number of elements N, only written when length-prefixed
+	/// sequences are enabled.
+	///
+	/// Format: The first bytes are the `VarInt` encoding of N. Then N elements follow, without end
+	/// designator.
+	SeqFixed = 20,
 }
 
 impl From<Type> for u8 {
@@ -84,17 +128,296 @@ impl TryFrom<u8> for Type {
 			8 => Ok(Self::Float128),
 			10 => Ok(Self::Bytes),
 			11 => Ok(Self::String),
+			12 => Ok(Self::StringDef),
+			13 => Ok(Self::StringRef),
 			15 => Ok(Self::SeqStart),
 			16 => Ok(Self::SeqEnd),
 			17 => Ok(Self::MapStart),
 			18 => Ok(Self::MapEnd),
+			19 => Ok(Self::Some),
+			20 => Ok(Self::SeqFixed),
+			INLINE_UNSIGNED_INT_START ..= INLINE_UNSIGNED_INT_END => Ok(Self::UnsignedInt),
 			_ => Err(crate::Error::InvalidType(value)),
 		}
 	}
 }
 
-/// The variable-length integer encoding implementation.
-pub trait VarInt: Sized {
+impl Type {
+	/// All types, ordered by their type code.
+	pub const ALL: [Self; 19] = [
+		Self::Null,
+		Self::BooleanFalse,
+		Self::BooleanTrue,
+		Self::UnsignedInt,
+		Self::SignedInt,
+		Self::Float16,
+		Self::Float32,
+		Self::Float64,
+		Self::Float128,
+		Self::Bytes,
+		Self::String,
+		Self::StringDef,
+		Self::StringRef,
+		Self::SeqStart,
+		Self::SeqEnd,
+		Self::MapStart,
+		Self::MapEnd,
+		Self::Some,
+		Self::SeqFixed,
+	];
+
+	/// The name of the type, as used in the format specification.
+	#[must_use]
+	pub const fn name(&self) -> &'static str {
+		match self {
+			Self::Null => "Null",
+			Self::BooleanFalse => "BooleanFalse",
+			Self::BooleanTrue => "BooleanTrue",
+			Self::UnsignedInt => "UnsignedInt",
+			Self::SignedInt => "SignedInt",
+			Self::Float16 => "Float16",
+			Self::Float32 => "Float32",
+			Self::Float64 => "Float64",
+			Self::Float128 => "Float128",
+			Self::Bytes => "Bytes",
+			Self::String => "String",
+			Self::StringDef => "StringDef",
+			Self::StringRef => "StringRef",
+			Self::SeqStart => "SeqStart",
+			Self::SeqEnd => "SeqEnd",
+			Self::MapStart => "MapStart",
+			Self::MapEnd => "MapEnd",
+			Self::Some => "Some",
+			Self::SeqFixed => "SeqFixed",
+		}
+	}
+
+	/// What follows the type byte as part of this type, not counting nested values of sequences,
+	/// maps and options. Unsigned integers with inline value (type bytes `64..=127`) have no
+	/// payload, although they are recognized as [Type::UnsignedInt].
+	#[must_use]
+	pub const fn payload_kind(&self) -> PayloadKind {
+		match self {
+			Self::Null
+			| Self::BooleanFalse
+			| Self::BooleanTrue
+			| Self::SeqStart
+			| Self::SeqEnd
+			| Self::MapStart
+			| Self::MapEnd
+			| Self::Some => PayloadKind::None,
+			Self::UnsignedInt | Self::SignedInt | Self::StringRef | Self::SeqFixed => {
+				PayloadKind::VarInt
+			}
+			Self::Float16 => PayloadKind::Fixed(2),
+			Self::Float32 => PayloadKind::Fixed(4),
+			Self::Float64 => PayloadKind::Fixed(8),
+			Self::Float128 => PayloadKind::Fixed(16),
+			Self::Bytes | Self::String | Self::StringDef => PayloadKind::LengthPrefixed,
+		}
+	}
+
+	/// Whether bytes follow the type byte as part of this type, see [Type::payload_kind].
+	#[must_use]
+	pub const fn has_payload(&self) -> bool {
+		!matches!(self.payload_kind(), PayloadKind::None)
+	}
+}
+
+/// The kind of payload following a type byte, see [Type::payload_kind].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum PayloadKind {
+	/// Nothing follows.
+	None,
+	/// A [VarInt] follows: the value of integers, the string number of string references or the
+	/// number of elements of fixed sequences.
+	VarInt,
+	/// The given number of bytes follow.
+	Fixed(usize),
+	/// A [VarInt] length N follows, then N bytes.
+	LengthPrefixed,
+}
+
+/// First type byte of unsigned integers with inline value, i.e. the value `0`.
+pub const INLINE_UNSIGNED_INT_START: u8 = 64;
+/// Last type byte of unsigned integers with inline value, i.e. the value `63`.
+pub const INLINE_UNSIGNED_INT_END: u8 = 127;
+
+/// The type byte of an unsigned integer with inline value, if the value is small enough.
+#[must_use]
+pub fn inline_unsigned_int<T>(value: T) -> Option<u8>
+where
+	T: TryInto<u8>,
+{
+	value
+		.try_into()
+		.ok()
+		.filter(|value| *value <= INLINE_UNSIGNED_INT_END - INLINE_UNSIGNED_INT_START)
+		.map(|value| INLINE_UNSIGNED_INT_START + value)
+}
+
+/// Decode an unsigned integer after its type byte, which might contain the value inline.
+pub(crate) fn decode_unsigned<'de, T, I>(type_byte: u8, input: &mut I) -> Result<T>
+where
+	T: VarInt,
+	I: Input<'de>,
+{
+	if (INLINE_UNSIGNED_INT_START ..= INLINE_UNSIGNED_INT_END).contains(&type_byte) {
+		// Small values are encoded as a single byte anyway.
+		T::decode(&mut [type_byte - INLINE_UNSIGNED_INT_START].as_slice())
+	} else {
+		T::decode(input)
+	}
+}
+
+/// The newtype struct name to serialize a `Float16` with. The newtype contains the float as `f32`,
+/// which is exact. Serializers knowing the name write it as `Float16` again, other formats just get
+/// the `f32`.
+pub(crate) const F16_BITS_NEWTYPE_NAME: &str = "\0serde_brief::Float16";
+/// The newtype struct name to serialize a `Float128` with. The newtype contains the float as `f64`
+/// if that is exact, otherwise the raw bits as `u128`. Serializers knowing the name write it as
+/// `Float128` again, other formats just get the `f64` or bits.
+pub(crate) const F128_BITS_NEWTYPE_NAME: &str = "\0serde_brief::Float128";
+/// The newtype struct name [Value](crate::value::Value) deserializes with. Deserializers knowing
+/// it visit `Float16` and `Float128` as a map with a single entry: the respective bits newtype
+/// name as key and the raw bits as value. This keeps the float width intact. Integers exceeding
+/// 128 bits are visited the same way with [BIG_INTEGER_NEWTYPE_NAME], if enabled.
+pub(crate) const VALUE_NEWTYPE_NAME: &str = "\0serde_brief::Value";
+/// The newtype struct name to serialize the encoding (type byte and var-int bytes) of an integer
+/// exceeding 128 bits with, see [BigInteger](crate::value::BigInteger). Serializers knowing it
+/// write the bytes inside as they are, other formats get the bytes.
+#[cfg(feature = "big-integers")]
+pub(crate) const BIG_INTEGER_NEWTYPE_NAME: &str = "\0serde_brief::BigInteger";
+/// The newtype struct name [RawValue](crate::raw::RawValue) serializes and deserializes with.
+/// Deserializers knowing it visit the encoded bytes of the next value, borrowed from the input.
+/// Serializers knowing it write the bytes inside as they are.
+pub(crate) const RAW_VALUE_NEWTYPE_NAME: &str = "\0serde_brief::RawValue";
+
+/// Convert the raw bits of a `Float16` to an `f32`. This is lossless.
+#[must_use]
+pub(crate) fn f16_bits_to_f32(bits: u16) -> f32 {
+	let sign = u32::from(bits & 0x8000) << 16;
+	let exponent = u32::from((bits >> 10) & 0x1F);
+	let mantissa = bits & 0x03FF;
+	match exponent {
+		0 => {
+			// Zero or subnormal: mantissa * 2^-24, which is exact in `f32`.
+			let value = f32::from(mantissa) * f32::from_bits(0x3380_0000);
+			if sign == 0 {
+				value
+			} else {
+				-value
+			}
+		}
+		0x1F => f32::from_bits(sign | 0x7F80_0000 | (u32::from(mantissa) << 13)),
+		_ => f32::from_bits(sign | ((exponent + 127 - 15) << 23) | (u32::from(mantissa) << 13)),
+	}
+}
+
+/// Convert an `f32` to the raw bits of a `Float16`, if it is exactly representable, e.g. because it
+/// was converted by [f16_bits_to_f32].
+#[must_use]
+pub(crate) fn f32_to_f16_bits(value: f32) -> Option<u16> {
+	let bits = value.to_bits();
+	let sign = ((bits >> 16) & 0x8000) as u16;
+	let exponent = (bits >> 23) & 0xFF;
+	let mantissa = bits & 0x007F_FFFF;
+	let magnitude = match exponent {
+		// Zero.
+		0 if mantissa == 0 => 0,
+		// Infinity or NaN, the payload must fit.
+		0xFF if mantissa & 0x1FFF == 0 => 0x7C00 | (mantissa >> 13),
+		// Normal in `Float16`, the mantissa must fit.
+		113 ..= 142 if mantissa & 0x1FFF == 0 => ((exponent - 112) << 10) | (mantissa >> 13),
+		// Subnormal in `Float16`, i.e. a multiple of 2^-24.
+		103 ..= 112 => {
+			let shift = 126 - exponent;
+			let mantissa = mantissa | 0x0080_0000;
+			if mantissa & ((1 << shift) - 1) != 0 {
+				return None;
+			}
+			mantissa >> shift
+		}
+		_ => return None,
+	};
+	Some(sign | magnitude as u16)
+}
+
+/// Convert an `f64` to the raw bits of a `Float128`. This is lossless.
+#[must_use]
+pub(crate) fn f64_to_f128_bits(value: f64) -> u128 {
+	let bits = value.to_bits();
+	let sign = u128::from(bits >> 63) << 127;
+	let exponent = (bits >> 52) & 0x7FF;
+	let mantissa = u128::from(bits & 0x000F_FFFF_FFFF_FFFF);
+	let magnitude = match exponent {
+		0 if mantissa == 0 => 0,
+		// Subnormal in `f64`, but normal in `Float128`: move the leading bit out of the mantissa.
+		0 => {
+			let leading = 127 - mantissa.leading_zeros();
+			let exponent = u128::from(leading) + 16383 - 1074;
+			(exponent << 112) | ((mantissa << (112 - leading)) & ((1 << 112) - 1))
+		}
+		0x7FF => (0x7FFF << 112) | (mantissa << 60),
+		_ => ((u128::from(exponent) + 16383 - 1023) << 112) | (mantissa << 60),
+	};
+	sign | magnitude
+}
+
+/// Convert the raw bits of a `Float128` to an `f64`. Precision is lost and values out of the `f64`
+/// range become infinity or zero. Rounding is to nearest, ties to even.
+#[must_use]
+pub(crate) fn f128_bits_to_f64(bits: u128) -> f64 {
+	let sign = u64::from(bits >> 127 == 1) << 63;
+	let exponent = i32::from(((bits >> 112) & 0x7FFF) as u16);
+	let mantissa = bits & ((1 << 112) - 1);
+
+	if exponent == 0x7FFF {
+		// Infinity or NaN, keeping the NaN payload's most significant bits (and the NaN).
+		let payload = if mantissa == 0 { 0 } else { (mantissa >> 60) as u64 | (1 << 51) };
+		return f64::from_bits(sign | 0x7FF0_0000_0000_0000 | payload);
+	}
+
+	let exponent = exponent - 16383 + 1023;
+	let magnitude = if exponent >= 0x7FF {
+		0x7FF0_0000_0000_0000
+	} else if exponent > 0 {
+		// Rounding might carry into the exponent, which is correct (up to infinity).
+		(u64::from(exponent.unsigned_abs()) << 52) + round_shift_right(mantissa, 60) as u64
+	} else {
+		// Subnormal (or zero) in `f64`.
+		let shift = 60 + 1 + exponent.unsigned_abs();
+		if shift >= 128 {
+			0
+		} else {
+			round_shift_right(mantissa | (1 << 112), shift) as u64
+		}
+	};
+	f64::from_bits(sign | magnitude)
+}
+
+/// Shift the value to the right, rounding to nearest, ties to even. The shift must be in `1..128`.
+const fn round_shift_right(value: u128, shift: u32) -> u128 {
+	let truncated = value >> shift;
+	let remainder = value & ((1 << shift) - 1);
+	let half = 1 << (shift - 1);
+	if remainder > half || (remainder == half && truncated & 1 == 1) {
+		truncated + 1
+	} else {
+		truncated
+	}
+}
+
+/// Sealing of [VarInt].
+mod private {
+	/// Implemented by the primitive integers only.
+	pub trait Sealed {}
+}
+
+/// The variable-length integer encoding implementation. It is sealed, as it is only implemented
+/// for the primitive integers.
+pub trait VarInt: Sized + private::Sealed {
 	/// Encode the integer into bytes.
 	fn encode<O: Output>(&self, output: &mut O) -> Result<()>;
 	/// Decode the integer from bytes.
@@ -104,15 +427,47 @@ pub trait VarInt: Sized {
 	const MAX_BYTES: usize = varint_max::<Self>();
 }
 
+/// Decode a var-int of up to 8 bytes from the first 8 bytes of the chunk at once, without a
+/// branch per byte. Returns the value and the number of bytes it takes up, or `None` if the var-int
+/// is longer or the chunk has fewer than 8 bytes for a var-int of more than 2 bytes.
+#[inline]
+fn decode_word(chunk: &[u8]) -> Option<(u64, usize)> {
+	// One and two bytes are the most common and do not need the word.
+	let first = *chunk.first()?;
+	if first & 0x80 == 0 {
+		return Some((u64::from(first), 1));
+	}
+	let second = *chunk.get(1)?;
+	if second & 0x80 == 0 {
+		return Some((u64::from(first & 0x7F) | (u64::from(second) << 7), 2));
+	}
+	let word = u64::from_le_bytes(chunk.get(.. 8)?.try_into().ok()?);
+	// The lowest cleared continuation bit marks the last byte.
+	let ends = !word & 0x8080_8080_8080_8080;
+	if ends == 0 {
+		return None;
+	}
+	let len = ends.trailing_zeros() as usize / 8 + 1;
+	// Keep the bytes up to the last one, without continuation bits.
+	let groups = word & (ends ^ (ends - 1)) & 0x7F7F_7F7F_7F7F_7F7F;
+	// Join the 7-bit groups into 14, 28 and finally 56 bits.
+	let groups = (groups & 0x007F_007F_007F_007F) | ((groups & 0x7F00_7F00_7F00_7F00) >> 1);
+	let groups = (groups & 0x0000_3FFF_0000_3FFF) | ((groups & 0x3FFF_0000_3FFF_0000) >> 2);
+	let value = (groups & 0x0000_0000_0FFF_FFFF) | ((groups & 0x0FFF_FFFF_0000_0000) >> 4);
+	Some((value, len))
+}
+
 /// Implement [VarInt] encoding for unsigned integers.
 macro_rules! impl_var_int_unsigned {
 	($($t:ty),*) => {
 		$(
+			impl private::Sealed for $t {}
+
 			impl VarInt for $t {
 				#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 				fn encode<O: Output>(&self, output: &mut O) -> Result<()> {
 					let mut value = *self;
-					for _ in 0..varint_max::<$t>() {
+					for _ in 1..varint_max::<$t>() {
 						let byte = value.to_le_bytes()[0];
 
 						if value < 0x80 {
@@ -123,11 +478,22 @@ macro_rules! impl_var_int_unsigned {
 						output.write_byte(byte | 0x80)?;
 						value >>= 7;
 					}
-					panic!("VarInt needed more than maximum bytes");
+					// The remaining bits always fit into the last byte.
+					output.write_byte(value.to_le_bytes()[0])
 				}
 
+				#[inline]
 				#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 				fn decode<'de, I: Input<'de>>(input: &mut I) -> Result<Self> {
+					if let Some((value, len)) = input.peek_chunk(8).and_then(decode_word) {
+						if len <= varint_max::<$t>() {
+							let value = <$t>::try_from(value).map_err(|_| Error::VarIntTooLarge)?;
+							input.skip_bytes(len)?;
+							return Ok(value);
+						}
+					}
+
+					// Byte by byte near the end of the input, for readers and for long var-ints.
 					let mut value = 0;
 					let mut bits = <$t>::BITS;
 					for i in 0..varint_max::<$t>() {
@@ -155,6 +521,8 @@ impl_var_int_unsigned!(u8, u16, u32, u64, u128, usize);
 macro_rules! impl_var_int_signed {
 	($($u:ty => $t:ty),*) => {
 		$(
+			impl private::Sealed for $t {}
+
 			impl VarInt for $t {
 				#[inline]
 				#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
@@ -184,10 +552,53 @@ macro_rules! impl_var_int_signed {
 }
 impl_var_int_signed!(u8 => i8, u16 => i16, u32 => i32, u64 => i64, u128 => i128, usize => isize);
 
-/// Returns the maximum number of bytes required to encode T.
+/// Returns the maximum number of bytes required to encode T as [VarInt].
+#[must_use]
 pub const fn varint_max<T: Sized>() -> usize {
 	let bits = ::core::mem::size_of::<T>() * 8;
-	(bits + 6) / 7
+	bits.div_ceil(7)
+}
+
+/// Decode a length, e.g. of a string or sequence, or an index into the string table. Lengths are
+/// written as var-ints of up to 64 bits by [encode_length], whatever the width of `usize` of the
+/// producer. Lengths exceeding `usize` of this platform return [Error::LengthExceedsPlatform]
+/// instead of [Error::VarIntTooLarge], e.g. a large document of a 64-bit producer on a 16-bit
+/// target.
+pub fn decode_length<'de, I>(input: &mut I) -> Result<usize>
+where
+	I: Input<'de>,
+{
+	decode_length_as(input)
+}
+
+/// Decode a length like [decode_length], but convert it to the given integer type, e.g. to check
+/// whether a length fits into `usize` of a narrower platform.
+///
+/// ```rust
+/// use serde_brief::{format::decode_length_as, Error};
+///
+/// let mut buffer = [0; 16];
+/// let len = serde_brief::format::encode_length(70_000, &mut buffer.as_mut_slice());
+/// assert!(len.is_ok());
+/// let result = decode_length_as::<u16, _>(&mut buffer.as_slice());
+/// assert!(matches!(result, Err(Error::LengthExceedsPlatform(70_000))));
+/// ```
+pub fn decode_length_as<'de, T, I>(input: &mut I) -> Result<T>
+where
+	T: TryFrom<u64>,
+	I: Input<'de>,
+{
+	let len = u64::decode(input)?;
+	T::try_from(len).map_err(|_| Error::LengthExceedsPlatform(len))
+}
+
+/// Encode a length or an index into the string table as var-int of up to 64 bits, which every
+/// platform decodes the same with [decode_length].
+pub fn encode_length<O>(len: usize, output: &mut O) -> Result<()>
+where
+	O: Output,
+{
+	u64::try_from(len).map_err(|_| Error::UsizeOverflow)?.encode(output)
 }
 
 #[cfg(test)]
@@ -198,9 +609,10 @@ mod tests {
 
 	#[test]
 	fn type_conversion_works() {
-		let valid_types = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 15, 16, 17, 18];
+		let valid_types = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 12, 13, 15, 16, 17, 18, 19, 20];
 		for byte in 0 ..= u8::MAX {
 			match Type::try_from(byte) {
+				Ok(t) if (64 ..= 127).contains(&byte) => assert_eq!(t, Type::UnsignedInt),
 				Ok(t) => {
 					assert!(
 						valid_types.contains(&byte),
@@ -209,13 +621,93 @@ mod tests {
 					assert_eq!(u8::from(t), byte);
 				}
 				Err(_) => assert!(
-					!valid_types.contains(&byte),
+					!valid_types.contains(&byte) && !(64 ..= 127).contains(&byte),
 					"Type should have been recognized from {byte}"
 				),
 			}
 		}
 	}
 
+	#[test]
+	fn type_helpers_describe_every_type() {
+		let expected = [
+			(Type::Null, 0, "Null", PayloadKind::None),
+			(Type::BooleanFalse, 1, "BooleanFalse", PayloadKind::None),
+			(Type::BooleanTrue, 2, "BooleanTrue", PayloadKind::None),
+			(Type::UnsignedInt, 3, "UnsignedInt", PayloadKind::VarInt),
+			(Type::SignedInt, 4, "SignedInt", PayloadKind::VarInt),
+			(Type::Float16, 5, "Float16", PayloadKind::Fixed(2)),
+			(Type::Float32, 6, "Float32", PayloadKind::Fixed(4)),
+			(Type::Float64, 7, "Float64", PayloadKind::Fixed(8)),
+			(Type::Float128, 8, "Float128", PayloadKind::Fixed(16)),
+			(Type::Bytes, 10, "Bytes", PayloadKind::LengthPrefixed),
+			(Type::String, 11, "String", PayloadKind::LengthPrefixed),
+			(Type::StringDef, 12, "StringDef", PayloadKind::LengthPrefixed),
+			(Type::StringRef, 13, "StringRef", PayloadKind::VarInt),
+			(Type::SeqStart, 15, "SeqStart", PayloadKind::None),
+			(Type::SeqEnd, 16, "SeqEnd", PayloadKind::None),
+			(Type::MapStart, 17, "MapStart", PayloadKind::None),
+			(Type::MapEnd, 18, "MapEnd", PayloadKind::None),
+			(Type::Some, 19, "Some", PayloadKind::None),
+			(Type::SeqFixed, 20, "SeqFixed", PayloadKind::VarInt),
+		];
+		assert_eq!(Type::ALL.len(), expected.len());
+		for (t, (expected_t, code, name, payload)) in Type::ALL.into_iter().zip(expected) {
+			assert_eq!(t, expected_t);
+			assert_eq!(u8::from(t), code);
+			assert_eq!(Type::try_from(code).unwrap(), t);
+			assert_eq!(t.name(), name);
+			assert_eq!(t.payload_kind(), payload);
+			assert_eq!(t.has_payload(), payload != PayloadKind::None);
+		}
+
+		for byte in INLINE_UNSIGNED_INT_START ..= INLINE_UNSIGNED_INT_END {
+			assert_eq!(inline_unsigned_int(byte - INLINE_UNSIGNED_INT_START), Some(byte));
+		}
+		assert_eq!(inline_unsigned_int(INLINE_UNSIGNED_INT_END), None);
+	}
+
+	/// Encode and decode the values of the given integer types with every possible output size
+	/// and input truncation, which must return errors instead of panicking.
+	macro_rules! check_varint_does_not_panic {
+		($($t:ty),*) => {
+			$(
+				let values = [<$t>::MIN, <$t>::MAX, 0, 1, 63, 64]
+					.into_iter()
+					.chain((0 .. <$t>::BITS).map(|shift| <$t>::MAX >> shift))
+					.chain((0 .. <$t>::BITS).map(|shift| <$t>::MIN >> shift));
+				for value in values {
+					let mut bytes = [0; <$t>::MAX_BYTES];
+					let mut output = bytes.as_mut_slice();
+					value.encode(&mut output).unwrap();
+					let len = <$t>::MAX_BYTES - output.len();
+					assert_eq!(<$t>::decode(&mut &bytes[.. len]).unwrap(), value);
+
+					for short in 0 .. len {
+						let mut output = &mut bytes[.. short];
+						assert!(matches!(value.encode(&mut output), Err(Error::BufferTooSmall)));
+						let result = <$t>::decode(&mut &bytes[.. short]);
+						assert!(matches!(result, Err(Error::UnexpectedEnd)));
+					}
+				}
+
+				// Continuation bits beyond the maximum length and overflowing last bytes.
+				let too_long = [0xFF; <$t>::MAX_BYTES + 1];
+				assert!(matches!(<$t>::decode(&mut too_long.as_slice()), Err(Error::VarIntTooLarge)));
+				for last in 0 ..= u8::MAX {
+					let mut bytes = [0x80; <$t>::MAX_BYTES];
+					bytes[<$t>::MAX_BYTES - 1] = last;
+					let _ = <$t>::decode(&mut bytes.as_slice());
+				}
+			)*
+		};
+	}
+
+	#[test]
+	fn varint_does_not_panic() {
+		check_varint_does_not_panic!(u8, u16, u32, u64, u128, usize);
+		check_varint_does_not_panic!(i8, i16, i32, i64, i128, isize);
+	}
 
 	#[test]
 	fn unsigned_varint_encode_works() {
@@ -275,6 +767,40 @@ mod tests {
 		assert!(matches!(result, Err(Error::VarIntTooLarge)));
 	}
 
+	#[test]
+	fn lengths_are_decoded_across_widths() {
+		// A 16-bit target reads the lengths a 64-bit producer writes, up to its own `usize`.
+		let mut bytes = [0; u64::MAX_BYTES];
+		encode_length(65535, &mut bytes.as_mut_slice()).unwrap();
+		assert_eq!(decode_length_as::<u16, _>(&mut bytes.as_slice()).unwrap(), 65535);
+		encode_length(65536, &mut bytes.as_mut_slice()).unwrap();
+		let result = decode_length_as::<u16, _>(&mut bytes.as_slice());
+		assert!(matches!(result, Err(Error::LengthExceedsPlatform(65536))));
+		assert_eq!(decode_length(&mut bytes.as_slice()).unwrap(), 65536);
+
+		// The same for 32-bit targets.
+		let len = 1_usize << 40;
+		encode_length(len, &mut bytes.as_mut_slice()).unwrap();
+		let result = decode_length_as::<u32, _>(&mut bytes.as_slice());
+		assert!(matches!(result, Err(Error::LengthExceedsPlatform(0x100_0000_0000))));
+		assert_eq!(decode_length(&mut bytes.as_slice()).unwrap(), len);
+
+		// Lengths are the same var-ints as `usize`, padding is accepted up to 64 bits.
+		let mut expected = [0; u64::MAX_BYTES];
+		let len = 300_usize;
+		len.encode(&mut expected.as_mut_slice()).unwrap();
+		let mut bytes = [0; u64::MAX_BYTES];
+		encode_length(len, &mut bytes.as_mut_slice()).unwrap();
+		assert_eq!(bytes, expected);
+		let padded = [0x80, 0x80, 0x80, 0x80, 0x00];
+		assert_eq!(decode_length_as::<u16, _>(&mut padded.as_slice()).unwrap(), 0);
+
+		// Var-ints exceeding 64 bits are still too large.
+		let bytes = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+		let result = decode_length_as::<u16, _>(&mut bytes.as_slice());
+		assert!(matches!(result, Err(Error::VarIntTooLarge)));
+	}
+
 	#[test]
 	fn signed_varint_encode_works() {
 		let mut bytes = [0; 1];
@@ -347,4 +873,124 @@ mod tests {
 		let result = i16::decode(&mut bytes.as_slice());
 		assert!(matches!(result, Err(Error::VarIntTooLarge)));
 	}
+
+	/// Input over a slice without [Input::peek_chunk], so that var-ints are decoded byte by byte.
+	struct Unbuffered<'a>(&'a [u8]);
+
+	impl<'a> Input<'a> for Unbuffered<'a> {
+		fn peek_byte(&mut self) -> Result<u8> {
+			self.0.peek_byte()
+		}
+
+		fn read_byte(&mut self) -> Result<u8> {
+			self.0.read_byte()
+		}
+
+		fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+			self.0.read_exact(buffer)
+		}
+
+		fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'a [u8]>>
+		where
+			B: crate::buffer::Buffer,
+		{
+			self.0.read_bytes(len, buffer)
+		}
+
+		fn skip_bytes(&mut self, len: usize) -> Result<()> {
+			self.0.skip_bytes(len)
+		}
+	}
+
+	/// Decode the bytes as `T` from inputs with and without [Input::peek_chunk] and check that
+	/// the values, errors and remaining bytes are the same.
+	fn assert_fast_path_matches<T>(bytes: &[u8])
+	where
+		T: VarInt + PartialEq + ::core::fmt::Debug,
+	{
+		let mut slow = Unbuffered(bytes);
+		let expected = T::decode(&mut slow).map_err(|err| err.kind());
+		let mut fast = bytes;
+		assert_eq!(T::decode(&mut fast).map_err(|err| err.kind()), expected, "{bytes:02X?}");
+		if expected.is_ok() {
+			assert_eq!(fast, slow.0, "{bytes:02X?}");
+		}
+
+		// Var-ints split between chunks or cut off by a limit, where only a part is peeked.
+		for split in 0 ..= bytes.len() {
+			let (first, second) = bytes.split_at(split);
+			let mut chunked = crate::io::ChunkedInput::new([first, second]);
+			assert_eq!(T::decode(&mut chunked).map_err(|err| err.kind()), expected);
+
+			let mut limited = crate::io::SizeLimit::new(bytes, split);
+			let mut slow = crate::io::SizeLimit::new(Unbuffered(bytes), split);
+			assert_eq!(
+				T::decode(&mut limited).map_err(|err| err.kind()),
+				T::decode(&mut slow).map_err(|err| err.kind()),
+				"{bytes:02X?} limited to {split}"
+			);
+		}
+	}
+
+	/// Check the fast path against the slow path for all integer types.
+	fn assert_fast_path_matches_all(bytes: &[u8]) {
+		assert_fast_path_matches::<u8>(bytes);
+		assert_fast_path_matches::<u16>(bytes);
+		assert_fast_path_matches::<u32>(bytes);
+		assert_fast_path_matches::<u64>(bytes);
+		assert_fast_path_matches::<u128>(bytes);
+		assert_fast_path_matches::<usize>(bytes);
+		assert_fast_path_matches::<i8>(bytes);
+		assert_fast_path_matches::<i16>(bytes);
+		assert_fast_path_matches::<i32>(bytes);
+		assert_fast_path_matches::<i64>(bytes);
+		assert_fast_path_matches::<i128>(bytes);
+	}
+
+	#[test]
+	fn varint_fast_path_matches_boundaries() {
+		for bits in 0 ..= 64 {
+			for value in [1_u64.checked_shl(bits).unwrap_or(0), u64::MAX >> (64 - bits).min(63)] {
+				let mut buffer = [0xFF; 16];
+				let mut output = buffer.as_mut_slice();
+				value.encode(&mut output).unwrap();
+				let len = 16 - output.len();
+				// With trailing bytes, exactly at the end and cut off.
+				assert_fast_path_matches_all(&buffer);
+				assert_fast_path_matches_all(&buffer[.. len]);
+				assert_fast_path_matches_all(&buffer[.. len - 1]);
+			}
+		}
+		// Padded and overlong var-ints.
+		assert_fast_path_matches_all(&[0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x80, 0x00, 0xFF]);
+		assert_fast_path_matches_all(&[0xFF; 24]);
+	}
+
+	/// Strategy for var-int-like bytes, with runs of continuation bits of all lengths.
+	fn any_varint_bytes() -> impl ::proptest::strategy::Strategy<Value = ([u8; 24], usize)> {
+		use ::proptest::prelude::*;
+
+		(any::<[u8; 24]>(), 0 .. 24_usize, 0 ..= 24_usize).prop_map(|(mut bytes, run, len)| {
+			for byte in bytes.iter_mut().take(run) {
+				*byte |= 0x80;
+			}
+			(bytes, len)
+		})
+	}
+
+	::proptest::proptest! {
+		#[test]
+		fn varint_fast_path_matches_slow_path(value: u64, shift in 0 .. 64_u32, signed: i64) {
+			let mut buffer = [0; 24];
+			let mut output = buffer.as_mut_slice();
+			(value >> shift).encode(&mut output).unwrap();
+			(signed >> shift).encode(&mut output).unwrap();
+			assert_fast_path_matches_all(&buffer);
+		}
+
+		#[test]
+		fn varint_fast_path_matches_slow_path_on_any_bytes((bytes, len) in any_varint_bytes()) {
+			assert_fast_path_matches_all(&bytes[.. len]);
+		}
+	}
 }
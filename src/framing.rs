@@ -0,0 +1,227 @@
+//! Length-prefixed framing for sending multiple messages over stream transports.
+//!
+//! Each frame consists of the message length as `VarInt` of up to 64 bits, followed by the
+//! message.
+//!
+//! ```rust
+//! # #[cfg(feature = "std")] {
+//! use serde_brief::framing::{read_framed, read_framed_borrowed, write_framed};
+//!
+//! let mut stream = Vec::new();
+//! write_framed(&"Hello", &mut stream).unwrap();
+//! write_framed(&"World", &mut stream).unwrap();
+//!
+//! let mut reader = stream.as_slice();
+//! let first: String = read_framed(&mut reader).unwrap();
+//! assert_eq!(first, "Hello");
+//!
+//! let mut input = stream.as_slice();
+//! while !input.is_empty() {
+//! 	let _message: &str = read_framed_borrowed(&mut input).unwrap();
+//! }
+//! # }
+//! ```
+
+use ::serde::Deserialize;
+#[cfg(feature = "std")]
+use ::serde::{de::DeserializeOwned, Serialize};
+#[cfg(feature = "std")]
+use ::std::io::{Read, Write};
+
+#[cfg(feature = "std")]
+use crate::format::{encode_length, VarInt};
+use crate::{format::decode_length, Config, Error, Result};
+
+/// Check the frame length against the configured size limit.
+const fn check_frame_len(len: usize, config: Config) -> Result<()> {
+	match config.max_size {
+		Some(max) if len > max.get() => Err(Error::LimitReached),
+		_ => Ok(()),
+	}
+}
+
+/// Write a value as frame into a [Write]r using the given configuration.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn write_framed_with_config<T, W>(value: &T, mut writer: W, config: Config) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	let len = crate::serialized_size_with_config(value, config)?;
+	check_frame_len(len, config)?;
+
+	let mut prefix = [0; u64::MAX_BYTES];
+	let mut output = prefix.as_mut_slice();
+	encode_length(len, &mut output)?;
+	let written = u64::MAX_BYTES - output.len();
+	writer.write_all(prefix.get(.. written).unwrap_or_default())?;
+
+	crate::to_writer_with_config(value, writer, config)
+}
+
+/// Write a value as frame into a [Write]r.
+#[cfg(feature = "std")]
+pub fn write_framed<T, W>(value: &T, writer: W) -> Result<()>
+where
+	T: Serialize,
+	W: Write,
+{
+	write_framed_with_config(value, writer, Config::default())
+}
+
+/// Read a frame from a [Read]er and deserialize the value using the given configuration. Only the
+/// frame is read from the reader.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn read_framed_with_config<R, T>(mut reader: R, config: Config) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	let mut prefix = [0; u64::MAX_BYTES];
+	let mut prefix_len = 0;
+	for byte in &mut prefix {
+		let mut read = [0];
+		reader.read_exact(&mut read).map_err(|err| {
+			if err.kind() == ::std::io::ErrorKind::UnexpectedEof {
+				Error::UnexpectedEnd
+			} else {
+				Error::Io(err)
+			}
+		})?;
+		let [read] = read;
+		*byte = read;
+		prefix_len += 1;
+		if *byte & 0x80 == 0 {
+			break;
+		}
+	}
+	let len = decode_length(&mut prefix.get(.. prefix_len).unwrap_or_default())?;
+	check_frame_len(len, config)?;
+
+	let mut frame = Vec::new();
+	let len64 = u64::try_from(len).map_err(|_| Error::UsizeOverflow)?;
+	if reader.take(len64).read_to_end(&mut frame)? != len {
+		return Err(Error::FrameIncomplete);
+	}
+
+	crate::from_slice_with_config(&frame, config)
+}
+
+/// Read a frame from a [Read]er and deserialize the value. Only the frame is read from the
+/// reader.
+#[cfg(feature = "std")]
+pub fn read_framed<R, T>(reader: R) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	read_framed_with_config(reader, Config::default())
+}
+
+/// Read a frame from the start of the input slice and deserialize the value using the given
+/// configuration. On success, the input is advanced to after the frame.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn read_framed_borrowed_with_config<'de, T>(input: &mut &'de [u8], config: Config) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	let mut rest = *input;
+	let len = decode_length(&mut rest)?;
+	check_frame_len(len, config)?;
+	if len > rest.len() {
+		return Err(Error::FrameIncomplete);
+	}
+
+	let (frame, rest) = rest.split_at(len);
+	let value = crate::from_slice_with_config(frame, config)?;
+	*input = rest;
+	Ok(value)
+}
+
+/// Read a frame from the start of the input slice and deserialize the value. On success, the
+/// input is advanced to after the frame.
+pub fn read_framed_borrowed<'de, T>(input: &mut &'de [u8]) -> Result<T>
+where
+	T: Deserialize<'de>,
+{
+	read_framed_borrowed_with_config(input, Config::default())
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, reason = "Tests")]
+
+	use super::*;
+	use crate::format::Type;
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn frames_roundtrip() {
+		let mut stream = Vec::new();
+		write_framed(&(1_u32, "first"), &mut stream).unwrap();
+		write_framed(&vec![0x1234_u16; 100], &mut stream).unwrap();
+		write_framed(&(), &mut stream).unwrap();
+		// Prefix, then the message.
+		assert_eq!(stream.get(.. 2).unwrap(), [11, Type::SeqStart.into()]);
+
+		let mut reader = stream.as_slice();
+		let first: (u32, String) = read_framed(&mut reader).unwrap();
+		assert_eq!(first, (1, "first".to_owned()));
+		let second: Vec<u16> = read_framed(&mut reader).unwrap();
+		assert_eq!(second, [0x1234; 100]);
+		read_framed::<_, ()>(&mut reader).unwrap();
+		assert!(reader.is_empty());
+		assert!(matches!(read_framed::<_, ()>(&mut reader), Err(Error::UnexpectedEnd)));
+
+		let mut input = stream.as_slice();
+		let first: (u32, &str) = read_framed_borrowed(&mut input).unwrap();
+		assert_eq!(first, (1, "first"));
+		let mut count = 1;
+		while !input.is_empty() {
+			_ = read_framed_borrowed::<crate::value::Value>(&mut input).unwrap();
+			count += 1;
+		}
+		assert_eq!(count, 3);
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn frames_are_limited() {
+		let config = Config { max_size: Some(10.try_into().unwrap()), ..Default::default() };
+		let mut stream = Vec::new();
+		let result = write_framed_with_config(&[1_u8; 20], &mut stream, config);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		assert!(stream.is_empty());
+
+		write_framed(&[1_u8; 20], &mut stream).unwrap();
+		let result = read_framed_with_config::<_, Vec<u8>>(stream.as_slice(), config);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		let mut input = stream.as_slice();
+		let result = read_framed_borrowed_with_config::<Vec<u8>>(&mut input, config);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		assert_eq!(input, stream);
+	}
+
+	#[test]
+	fn malformed_prefixes_are_reported() {
+		let bytes = [5, Type::Null.into()];
+		let mut input = bytes.as_slice();
+		let result = read_framed_borrowed::<()>(&mut input);
+		assert!(matches!(result, Err(Error::FrameIncomplete)));
+		assert_eq!(input, bytes);
+
+		let bytes = [0xFF; 20];
+		let result = read_framed_borrowed::<()>(&mut bytes.as_slice());
+		assert!(matches!(result, Err(Error::VarIntTooLarge)));
+
+		#[cfg(feature = "std")]
+		{
+			let result = read_framed::<_, ()>(bytes.as_slice());
+			assert!(matches!(result, Err(Error::VarIntTooLarge)));
+			let result = read_framed::<_, ()>([5, Type::Null.into()].as_slice());
+			assert!(matches!(result, Err(Error::FrameIncomplete)));
+		}
+	}
+}
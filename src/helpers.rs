@@ -0,0 +1,1452 @@
+//! Helpers for `#[serde(with = "...")]` to encode standard library types more compactly.
+//!
+//! Serde-Brief is not human-readable, so standard library types use serde's compact
+//! representations. Some of them are still large in this format, because octets are encoded as
+//! sequences of integers and structs carry their field names:
+//!
+//! | Type | Default | Helper | With helper |
+//! | --- | --- | --- | --- |
+//! | `Ipv4Addr` | 10 - 14 bytes | [ip_as_bytes] | 6 bytes |
+//! | `Ipv6Addr` | 34 - 50 bytes | [ip_as_bytes] | 18 bytes |
+//! | `IpAddr` | 16 - 56 bytes | [ip_as_bytes] | 6 or 18 bytes |
+//! | `SocketAddr` | 20 - 62 bytes | [socket_addr_as_bytes] | 8 or 20 bytes |
+//! | `Duration` | 17 - 33 bytes | [duration_as_nanos] | 2 - 15 bytes |
+//! | `SystemTime` | 43 - 56 bytes | [system_time_as_nanos] | 2 - 15 bytes |
+//! | `[u8; 32]` | 34 - 98 bytes | [byte_array] | 34 bytes |
+//!
+//! The default sizes are given for the string representation of field and variant names. Serde's
+//! implementations for `Duration` and `SystemTime` cannot be deserialized with `use_indices`, as
+//! they only accept field names, while the helpers work with both representations. The helpers
+//! change the encoding, so they can only read data written by the same helper.
+//!
+//! With the `alloc` feature, [cow_str] and [cow_bytes] deserialize `Cow`s borrowing from the
+//! input, which serde's implementations never do, and [shared_str] deserializes `Arc<str>` or
+//! `Rc<str>` without copying the string twice. They do not change the encoding.
+//!
+//! With the `chrono` or `time` features, `timestamp_seconds` and `timestamp_seconds_nanos` encode
+//! timestamps as seconds or as a struct of seconds and nanoseconds since the Unix epoch. Both
+//! accept either representation when deserializing, so the encoding can be changed later.
+//!
+//! With the `primitive-types` feature, `u256_bytes` encodes `U256` as 32 bytes instead of the hex
+//! string of its own implementation, which is meant for human-readable formats.
+//!
+//! With the `half` feature, `f16_as_float16` encodes `half::f16` as `Float16` instead of its bits
+//! as integer, so that it can be read as any other float.
+//!
+//! ```rust
+//! use std::{
+//! 	net::{IpAddr, SocketAddr},
+//! 	time::Duration,
+//! };
+//!
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Debug, PartialEq, Serialize, Deserialize)]
+//! struct Peer {
+//! 	#[serde(with = "serde_brief::helpers::ip_as_bytes")]
+//! 	ip: IpAddr,
+//! 	#[serde(with = "serde_brief::helpers::socket_addr_as_bytes")]
+//! 	addr: SocketAddr,
+//! 	#[serde(with = "serde_brief::helpers::duration_as_nanos")]
+//! 	timeout: Duration,
+//! }
+//!
+//! let peer = Peer {
+//! 	ip: [10, 0, 0, 1].into(),
+//! 	addr: ([10, 0, 0, 1], 8080).into(),
+//! 	timeout: Duration::from_secs(5),
+//! };
+//! let bytes = serde_brief::to_slice(&peer, &mut [0; 64]).unwrap().to_vec();
+//! let parsed: Peer = serde_brief::from_slice(&bytes).unwrap();
+//! assert_eq!(parsed, peer);
+//! ```
+
+use ::core::{
+	fmt,
+	net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, SocketAddrV4, SocketAddrV6},
+	time::Duration,
+};
+use ::serde::{de, Deserializer, Serializer};
+
+/// Nanoseconds per second.
+const NANOS_PER_SEC: u128 = 1_000_000_000;
+
+/// Types that are encoded as fixed bytes by [ip_as_bytes] and [socket_addr_as_bytes].
+pub trait FixedBytes: Sized {
+	/// Description of the expected bytes for error messages.
+	const EXPECTED: &'static str;
+	/// Write the bytes into the buffer and return the number of bytes used.
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize;
+	/// Parse the value from the bytes, returning `None` if the length does not fit.
+	fn from_bytes(bytes: &[u8]) -> Option<Self>;
+}
+
+impl FixedBytes for Ipv4Addr {
+	const EXPECTED: &'static str = "4 bytes of an IPv4 address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		write_bytes(buffer, &self.octets())
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		<[u8; 4]>::try_from(bytes).ok().map(Self::from)
+	}
+}
+
+impl FixedBytes for Ipv6Addr {
+	const EXPECTED: &'static str = "16 bytes of an IPv6 address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		write_bytes(buffer, &self.octets())
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		<[u8; 16]>::try_from(bytes).ok().map(Self::from)
+	}
+}
+
+impl FixedBytes for IpAddr {
+	const EXPECTED: &'static str = "4 or 16 bytes of an IP address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		match self {
+			IpAddr::V4(ip) => ip.to_bytes(buffer),
+			IpAddr::V6(ip) => ip.to_bytes(buffer),
+		}
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		Ipv4Addr::from_bytes(bytes)
+			.map(IpAddr::V4)
+			.or_else(|| Ipv6Addr::from_bytes(bytes).map(IpAddr::V6))
+	}
+}
+
+impl FixedBytes for SocketAddrV4 {
+	const EXPECTED: &'static str = "6 bytes of an IPv4 socket address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		let len = self.ip().to_bytes(buffer);
+		len + write_bytes(buffer.get_mut(len ..).unwrap_or_default(), &self.port().to_be_bytes())
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		let (ip, port) = split_port(bytes)?;
+		Some(Self::new(Ipv4Addr::from_bytes(ip)?, port))
+	}
+}
+
+impl FixedBytes for SocketAddrV6 {
+	const EXPECTED: &'static str = "18 bytes of an IPv6 socket address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		let len = self.ip().to_bytes(buffer);
+		len + write_bytes(buffer.get_mut(len ..).unwrap_or_default(), &self.port().to_be_bytes())
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		let (ip, port) = split_port(bytes)?;
+		Some(Self::new(Ipv6Addr::from_bytes(ip)?, port, 0, 0))
+	}
+}
+
+impl FixedBytes for SocketAddr {
+	const EXPECTED: &'static str = "6 or 18 bytes of a socket address";
+
+	fn to_bytes(&self, buffer: &mut [u8; 18]) -> usize {
+		match self {
+			SocketAddr::V4(addr) => addr.to_bytes(buffer),
+			SocketAddr::V6(addr) => addr.to_bytes(buffer),
+		}
+	}
+
+	fn from_bytes(bytes: &[u8]) -> Option<Self> {
+		SocketAddrV4::from_bytes(bytes)
+			.map(SocketAddr::V4)
+			.or_else(|| SocketAddrV6::from_bytes(bytes).map(SocketAddr::V6))
+	}
+}
+
+/// Copy the bytes to the start of the buffer and return the number of bytes written.
+fn write_bytes(buffer: &mut [u8], bytes: &[u8]) -> usize {
+	if let Some(target) = buffer.get_mut(.. bytes.len()) {
+		target.copy_from_slice(bytes);
+	}
+	bytes.len()
+}
+
+/// Split the big-endian port off the end of the socket address bytes.
+fn split_port(bytes: &[u8]) -> Option<(&[u8], u16)> {
+	let (ip, port) = bytes.split_at_checked(bytes.len().checked_sub(2)?)?;
+	Some((ip, u16::from_be_bytes(port.try_into().ok()?)))
+}
+
+/// Serialize [FixedBytes] types as bytes.
+fn serialize_fixed<T, S>(value: &T, serializer: S) -> Result<S::Ok, S::Error>
+where
+	T: FixedBytes,
+	S: Serializer,
+{
+	let mut buffer = [0; 18];
+	let len = value.to_bytes(&mut buffer);
+	serializer.serialize_bytes(buffer.get(.. len).unwrap_or_default())
+}
+
+/// Deserialize [FixedBytes] types from bytes.
+fn deserialize_fixed<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: FixedBytes,
+	D: Deserializer<'de>,
+{
+	/// Visitor for the bytes of [FixedBytes] types.
+	struct FixedBytesVisitor<T>(::core::marker::PhantomData<T>);
+
+	impl<T> de::Visitor<'_> for FixedBytesVisitor<T>
+	where
+		T: FixedBytes,
+	{
+		type Value = T;
+
+		fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+			formatter.write_str(T::EXPECTED)
+		}
+
+		fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+		where
+			E: de::Error,
+		{
+			T::from_bytes(v).ok_or_else(|| E::invalid_length(v.len(), &self))
+		}
+	}
+
+	deserializer.deserialize_bytes(FixedBytesVisitor(::core::marker::PhantomData))
+}
+
+/// Encode [`Ipv4Addr`], [`Ipv6Addr`] and [`IpAddr`] as bytes of their octets.
+pub mod ip_as_bytes {
+	use ::serde::{Deserializer, Serializer};
+
+	use super::FixedBytes;
+
+	/// Serialize the IP address as bytes.
+	pub fn serialize<T, S>(ip: &T, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: FixedBytes,
+		S: Serializer,
+	{
+		super::serialize_fixed(ip, serializer)
+	}
+
+	/// Deserialize the IP address from bytes.
+	pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+	where
+		T: FixedBytes,
+		D: Deserializer<'de>,
+	{
+		super::deserialize_fixed(deserializer)
+	}
+}
+
+/// Encode [`SocketAddrV4`], [`SocketAddrV6`] and [`SocketAddr`] as bytes of the IP address'
+/// octets, followed by the big-endian port. Like serde's implementation, this does not keep the
+/// flow info and scope ID of IPv6 socket addresses.
+pub mod socket_addr_as_bytes {
+	use ::serde::{Deserializer, Serializer};
+
+	use super::FixedBytes;
+
+	/// Serialize the socket address as bytes.
+	pub fn serialize<T, S>(addr: &T, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: FixedBytes,
+		S: Serializer,
+	{
+		super::serialize_fixed(addr, serializer)
+	}
+
+	/// Deserialize the socket address from bytes.
+	pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+	where
+		T: FixedBytes,
+		D: Deserializer<'de>,
+	{
+		super::deserialize_fixed(deserializer)
+	}
+}
+
+/// Encode byte arrays `[u8; N]`, e.g. hashes or keys, as bytes instead of a sequence of integers.
+/// Deserializing reads the bytes at once and checks the length, instead of visiting every byte.
+/// Sequences of integers, as written without the helper, are accepted as well.
+pub mod byte_array {
+	use ::core::fmt;
+	use ::serde::{de, Deserializer, Serializer};
+
+	/// Serialize the array as bytes.
+	pub fn serialize<const N: usize, S>(array: &[u8; N], serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_bytes(array)
+	}
+
+	/// Deserialize the array from bytes of exactly `N` length.
+	pub fn deserialize<'de, const N: usize, D>(deserializer: D) -> Result<[u8; N], D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// Visitor for the bytes of an array.
+		struct ByteArrayVisitor<const N: usize>;
+
+		impl<'de, const N: usize> de::Visitor<'de> for ByteArrayVisitor<N> {
+			type Value = [u8; N];
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(formatter, "{N} bytes")
+			}
+
+			fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				v.try_into().map_err(|_| E::invalid_length(v.len(), &self))
+			}
+
+			fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+			where
+				A: de::SeqAccess<'de>,
+			{
+				let mut array = [0; N];
+				for (i, byte) in array.iter_mut().enumerate() {
+					*byte =
+						seq.next_element()?.ok_or_else(|| de::Error::invalid_length(i, &self))?;
+				}
+				if seq.next_element::<de::IgnoredAny>()?.is_some() {
+					return Err(de::Error::invalid_length(N.saturating_add(1), &self));
+				}
+				Ok(array)
+			}
+		}
+
+		deserializer.deserialize_bytes(ByteArrayVisitor)
+	}
+}
+
+/// Encode [`U256`](::primitive_types::U256) as 32 big-endian bytes, like [byte_array]. Its own
+/// implementation always writes a `0x`-prefixed hex string of up to 66 characters, taking 5 - 68
+/// bytes, while this takes 34 bytes. Deserializing requires exactly 32 bytes, so integers of other
+/// widths are rejected instead of being padded or truncated.
+///
+/// ```rust
+/// use primitive_types::U256;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Transfer {
+/// 	#[serde(with = "serde_brief::helpers::u256_bytes")]
+/// 	amount: U256,
+/// }
+///
+/// let transfer = Transfer { amount: U256::MAX };
+/// let bytes = serde_brief::to_vec(&transfer).unwrap();
+/// assert_eq!(bytes.len(), 2 + 8 + 34);
+/// assert_eq!(serde_brief::from_slice::<Transfer>(&bytes).unwrap(), transfer);
+/// ```
+#[cfg(feature = "primitive-types")]
+pub mod u256_bytes {
+	use ::primitive_types::U256;
+	use ::serde::{Deserializer, Serializer};
+
+	/// Serialize the integer as 32 big-endian bytes.
+	pub fn serialize<S>(int: &U256, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		super::byte_array::serialize(&int.to_big_endian(), serializer)
+	}
+
+	/// Deserialize the integer from exactly 32 big-endian bytes.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<U256, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		super::byte_array::deserialize::<32, D>(deserializer)
+			.map(|bytes| U256::from_big_endian(&bytes))
+	}
+}
+
+/// Encode [`f16`](::half::f16) as `Float16`, taking 3 bytes. Its own implementation writes the
+/// bits as newtype struct of a `u16`, taking 1 - 4 bytes. Deserializing accepts any float,
+/// converting it to `f16`, and the bits of the `f16` implementation. Other formats get the float as
+/// `f32` instead, which is exact.
+///
+/// ```rust
+/// use half::f16;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Debug, PartialEq, Serialize, Deserialize)]
+/// struct Weights {
+/// 	#[serde(with = "serde_brief::helpers::f16_as_float16")]
+/// 	bias: f16,
+/// }
+///
+/// let weights = Weights { bias: f16::from_f32(-2.5) };
+/// let mut buffer = [0; 16];
+/// let bytes = serde_brief::to_slice(&weights, &mut buffer).unwrap();
+/// assert_eq!(bytes.len(), 1 + 6 + 3 + 1);
+/// assert_eq!(serde_brief::from_slice::<Weights>(bytes).unwrap(), weights);
+/// let bias: f32 = serde_brief::from_slice(&bytes[7 .. 10]).unwrap();
+/// assert_eq!(bias, -2.5);
+/// ```
+#[cfg(feature = "half")]
+pub mod f16_as_float16 {
+	use ::core::fmt;
+	use ::half::f16;
+	use ::serde::{de, Deserialize, Deserializer, Serializer};
+
+	/// Serialize the float as `Float16`.
+	pub fn serialize<S>(float: &f16, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_newtype_struct(
+			crate::format::F16_BITS_NEWTYPE_NAME,
+			&crate::ser::FloatBitsPayload(crate::ser::FloatBits::F16(float.to_bits())),
+		)
+	}
+
+	/// Deserialize the float from `Float16`, any other float or the bits of the `f16`
+	/// implementation.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<f16, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// Visitor for the float payload, another float or the bits.
+		struct F16Visitor;
+
+		impl<'de> de::Visitor<'de> for F16Visitor {
+			type Value = f16;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				formatter.write_str("a float")
+			}
+
+			fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+			where
+				D: Deserializer<'de>,
+			{
+				f32::deserialize(deserializer).map(f16::from_f32)
+			}
+
+			fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				u16::try_from(v)
+					.map(f16::from_bits)
+					.map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))
+			}
+
+			fn visit_f32<E>(self, v: f32) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(f16::from_f32(v))
+			}
+
+			fn visit_f64<E>(self, v: f64) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(f16::from_f64(v))
+			}
+		}
+
+		deserializer.deserialize_newtype_struct(crate::format::F16_BITS_NEWTYPE_NAME, F16Visitor)
+	}
+}
+
+/// Deserialize [`Cow<str>`](::alloc::borrow::Cow) borrowing from the input where possible. Serde's
+/// implementation always returns [`Cow::Owned`](::alloc::borrow::Cow::Owned), while this returns
+/// [`Cow::Borrowed`](::alloc::borrow::Cow::Borrowed) when deserializing from a slice. Strings from
+/// readers or the string table are still copied. The encoding is the same as without the helper.
+///
+/// ```rust
+/// use std::borrow::Cow;
+///
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Message<'a> {
+/// 	#[serde(borrow, with = "serde_brief::helpers::cow_str")]
+/// 	text: Cow<'a, str>,
+/// }
+///
+/// let bytes = serde_brief::to_vec(&Message { text: "Hello".into() }).unwrap();
+/// let parsed: Message = serde_brief::from_slice(&bytes).unwrap();
+/// assert!(matches!(parsed.text, Cow::Borrowed("Hello")));
+/// ```
+#[cfg(feature = "alloc")]
+pub mod cow_str {
+	use ::alloc::{borrow::Cow, string::String};
+	use ::core::fmt;
+	use ::serde::{de, Deserializer, Serializer};
+
+	/// Serialize the string.
+	pub fn serialize<S>(string: &str, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(string)
+	}
+
+	/// Deserialize the string, borrowing it if possible.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Cow<'de, str>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// Visitor keeping borrowed strings borrowed.
+		struct CowStrVisitor;
+
+		impl<'de> de::Visitor<'de> for CowStrVisitor {
+			type Value = Cow<'de, str>;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				formatter.write_str("a string")
+			}
+
+			fn visit_borrowed_str<E>(self, v: &'de str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Borrowed(v))
+			}
+
+			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Owned(v.into()))
+			}
+
+			fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Owned(v))
+			}
+		}
+
+		deserializer.deserialize_str(CowStrVisitor)
+	}
+}
+
+/// Deserialize [`Cow<[u8]>`](::alloc::borrow::Cow) borrowing from the input where possible, like
+/// [cow_str] does for strings. The bytes are encoded as bytes instead of a sequence of integers,
+/// but sequences of integers are accepted as well.
+#[cfg(feature = "alloc")]
+pub mod cow_bytes {
+	use ::alloc::{borrow::Cow, vec::Vec};
+	use ::core::fmt;
+	use ::serde::{de, Deserializer, Serializer};
+
+	/// Serialize the bytes.
+	pub fn serialize<S>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_bytes(bytes)
+	}
+
+	/// Deserialize the bytes, borrowing them if possible.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Cow<'de, [u8]>, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// Visitor keeping borrowed bytes borrowed.
+		struct CowBytesVisitor;
+
+		impl<'de> de::Visitor<'de> for CowBytesVisitor {
+			type Value = Cow<'de, [u8]>;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				formatter.write_str("bytes")
+			}
+
+			fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Borrowed(v))
+			}
+
+			fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Owned(v.into()))
+			}
+
+			fn visit_byte_buf<E>(self, v: Vec<u8>) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(Cow::Owned(v))
+			}
+
+			fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+			where
+				A: de::SeqAccess<'de>,
+			{
+				let mut bytes = Vec::with_capacity(seq.size_hint().unwrap_or(0).min(4096));
+				while let Some(byte) = seq.next_element()? {
+					bytes.push(byte);
+				}
+				Ok(Cow::Owned(bytes))
+			}
+		}
+
+		deserializer.deserialize_bytes(CowBytesVisitor)
+	}
+}
+
+/// Deserialize shared strings like `Arc<str>` or `Rc<str>` with a single allocation. Serde's
+/// implementations deserialize a `Box<str>` first and copy it into the new allocation. Any type
+/// that can be created from `&str` and viewed as `&str` works. The encoding is the same as without
+/// the helper.
+#[cfg(feature = "alloc")]
+pub mod shared_str {
+	use ::core::{fmt, marker::PhantomData};
+	use ::serde::{de, Deserializer, Serializer};
+
+	/// Serialize the string.
+	pub fn serialize<T, S>(string: &T, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: AsRef<str> + ?Sized,
+		S: Serializer,
+	{
+		serializer.serialize_str(string.as_ref())
+	}
+
+	/// Deserialize the string, allocating it once from the input.
+	pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+	where
+		T: for<'a> From<&'a str>,
+		D: Deserializer<'de>,
+	{
+		/// Visitor creating the string from the visited one.
+		struct SharedStrVisitor<T>(PhantomData<T>);
+
+		impl<T> de::Visitor<'_> for SharedStrVisitor<T>
+		where
+			T: for<'a> From<&'a str>,
+		{
+			type Value = T;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				formatter.write_str("a string")
+			}
+
+			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(T::from(v))
+			}
+		}
+
+		deserializer.deserialize_str(SharedStrVisitor(PhantomData))
+	}
+}
+
+/// Convert nanoseconds to a duration, returning `None` if it overflows.
+fn duration_from_nanos(nanos: u128) -> Option<Duration> {
+	let secs = u64::try_from(nanos / NANOS_PER_SEC).ok()?;
+	let nanos = u32::try_from(nanos % NANOS_PER_SEC).ok()?;
+	Some(Duration::new(secs, nanos))
+}
+
+/// Encode [`Duration`] as an unsigned integer of nanoseconds.
+pub mod duration_as_nanos {
+	use ::core::time::Duration;
+	use ::serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+	/// Serialize the duration as nanoseconds.
+	pub fn serialize<S>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_u128(duration.as_nanos())
+	}
+
+	/// Deserialize the duration from nanoseconds.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Duration, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let nanos = u128::deserialize(deserializer)?;
+		super::duration_from_nanos(nanos).ok_or_else(|| D::Error::custom("duration overflow"))
+	}
+}
+
+/// Encode [`SystemTime`](::std::time::SystemTime) as a signed integer of nanoseconds since the
+/// Unix epoch. Unlike serde's implementation, times before the epoch are supported.
+#[cfg(feature = "std")]
+pub mod system_time_as_nanos {
+	use ::serde::{de::Error as _, ser::Error as _, Deserialize, Deserializer, Serializer};
+	use ::std::time::{SystemTime, UNIX_EPOCH};
+
+	/// Serialize the time as nanoseconds since the Unix epoch.
+	pub fn serialize<S>(time: &SystemTime, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		let nanos = match time.duration_since(UNIX_EPOCH) {
+			Ok(after) => i128::try_from(after.as_nanos()).ok(),
+			Err(before) => i128::try_from(before.duration().as_nanos()).ok().map(|n| -n),
+		};
+		let nanos = nanos.ok_or_else(|| S::Error::custom("system time overflow"))?;
+		serializer.serialize_i128(nanos)
+	}
+
+	/// Deserialize the time from nanoseconds since the Unix epoch.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<SystemTime, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		let nanos = i128::deserialize(deserializer)?;
+		let duration = super::duration_from_nanos(nanos.unsigned_abs());
+		let time = if nanos < 0 {
+			duration.and_then(|duration| UNIX_EPOCH.checked_sub(duration))
+		} else {
+			duration.and_then(|duration| UNIX_EPOCH.checked_add(duration))
+		};
+		time.ok_or_else(|| D::Error::custom("system time overflow"))
+	}
+}
+
+/// Timestamps that are encoded by [timestamp_seconds] and [timestamp_seconds_nanos], as seconds
+/// since the Unix epoch and nanoseconds within the second.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub trait Timestamp: Sized {
+	/// Return the seconds since the Unix epoch and the nanoseconds within the second.
+	fn to_parts(&self) -> (i64, u32);
+	/// Create the timestamp from seconds since the Unix epoch and nanoseconds within the second.
+	/// Returns `None` if it is out of range.
+	fn from_parts(secs: i64, nanos: u32) -> Option<Self>;
+}
+
+#[cfg(feature = "chrono")]
+impl Timestamp for ::chrono::DateTime<::chrono::Utc> {
+	fn to_parts(&self) -> (i64, u32) {
+		(self.timestamp(), self.timestamp_subsec_nanos())
+	}
+
+	fn from_parts(secs: i64, nanos: u32) -> Option<Self> {
+		Self::from_timestamp(secs, nanos)
+	}
+}
+
+/// Timestamps are decoded in UTC.
+#[cfg(feature = "time")]
+impl Timestamp for ::time::OffsetDateTime {
+	fn to_parts(&self) -> (i64, u32) {
+		(self.unix_timestamp(), self.nanosecond())
+	}
+
+	fn from_parts(secs: i64, nanos: u32) -> Option<Self> {
+		Self::from_unix_timestamp(secs).ok()?.replace_nanosecond(nanos).ok()
+	}
+}
+
+/// Field of the timestamp struct, by name or index.
+#[cfg(any(feature = "chrono", feature = "time"))]
+enum TimestampField {
+	/// Seconds since the Unix epoch.
+	Secs,
+	/// Nanoseconds within the second.
+	Nanos,
+	/// Unknown field, which is ignored.
+	Other,
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl<'de> de::Deserialize<'de> for TimestampField {
+	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		/// Visitor for the field identifier.
+		struct FieldVisitor;
+
+		impl de::Visitor<'_> for FieldVisitor {
+			type Value = TimestampField;
+
+			fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+				formatter.write_str("a timestamp field identifier")
+			}
+
+			fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(match v {
+					0 => TimestampField::Secs,
+					1 => TimestampField::Nanos,
+					_ => TimestampField::Other,
+				})
+			}
+
+			fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+			where
+				E: de::Error,
+			{
+				Ok(match v {
+					"secs" => TimestampField::Secs,
+					"nanos" => TimestampField::Nanos,
+					_ => TimestampField::Other,
+				})
+			}
+		}
+
+		deserializer.deserialize_identifier(FieldVisitor)
+	}
+}
+
+/// Visitor for both timestamp representations.
+#[cfg(any(feature = "chrono", feature = "time"))]
+struct TimestampVisitor<T>(::core::marker::PhantomData<T>);
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl<T> TimestampVisitor<T>
+where
+	T: Timestamp,
+{
+	/// Create the timestamp from its parts or return an out of range error.
+	fn timestamp<E>(secs: i64, nanos: u32) -> Result<T, E>
+	where
+		E: de::Error,
+	{
+		T::from_parts(secs, nanos).ok_or_else(|| E::custom("timestamp out of range"))
+	}
+}
+
+#[cfg(any(feature = "chrono", feature = "time"))]
+impl<'de, T> de::Visitor<'de> for TimestampVisitor<T>
+where
+	T: Timestamp,
+{
+	type Value = T;
+
+	fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+		formatter.write_str("seconds or a struct of seconds and nanoseconds since the Unix epoch")
+	}
+
+	fn visit_i64<E>(self, v: i64) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		Self::timestamp(v, 0)
+	}
+
+	fn visit_u64<E>(self, v: u64) -> Result<Self::Value, E>
+	where
+		E: de::Error,
+	{
+		let secs =
+			i64::try_from(v).map_err(|_| E::invalid_value(de::Unexpected::Unsigned(v), &self))?;
+		Self::timestamp(secs, 0)
+	}
+
+	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::SeqAccess<'de>,
+	{
+		let secs = seq.next_element()?.ok_or_else(|| de::Error::invalid_length(0, &self))?;
+		let nanos = seq.next_element()?.unwrap_or_default();
+		while seq.next_element::<de::IgnoredAny>()?.is_some() {}
+		Self::timestamp(secs, nanos)
+	}
+
+	fn visit_map<A>(self, mut map: A) -> Result<Self::Value, A::Error>
+	where
+		A: de::MapAccess<'de>,
+	{
+		let mut secs = None;
+		let mut nanos = 0;
+		while let Some(field) = map.next_key()? {
+			match field {
+				TimestampField::Secs => secs = Some(map.next_value()?),
+				TimestampField::Nanos => nanos = map.next_value()?,
+				TimestampField::Other => _ = map.next_value::<de::IgnoredAny>()?,
+			}
+		}
+		let secs = secs.ok_or_else(|| de::Error::missing_field("secs"))?;
+		Self::timestamp(secs, nanos)
+	}
+}
+
+/// Deserialize a [Timestamp] from either seconds or a struct of seconds and nanoseconds.
+#[cfg(any(feature = "chrono", feature = "time"))]
+fn deserialize_timestamp<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+where
+	T: Timestamp,
+	D: Deserializer<'de>,
+{
+	deserializer.deserialize_any(TimestampVisitor(::core::marker::PhantomData))
+}
+
+/// Encode [Timestamp]s, e.g. `chrono::DateTime<Utc>` or `time::OffsetDateTime`, as a signed
+/// integer of seconds since the Unix epoch. Nanoseconds are truncated. Both representations of
+/// [timestamp_seconds_nanos] are accepted when deserializing.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod timestamp_seconds {
+	use ::serde::{Deserializer, Serializer};
+
+	use super::Timestamp;
+
+	/// Serialize the timestamp as seconds.
+	pub fn serialize<T, S>(timestamp: &T, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: Timestamp,
+		S: Serializer,
+	{
+		serializer.serialize_i64(timestamp.to_parts().0)
+	}
+
+	/// Deserialize the timestamp from seconds or a struct of seconds and nanoseconds.
+	pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+	where
+		T: Timestamp,
+		D: Deserializer<'de>,
+	{
+		super::deserialize_timestamp(deserializer)
+	}
+}
+
+/// Encode [Timestamp]s, e.g. `chrono::DateTime<Utc>` or `time::OffsetDateTime`, as a struct of
+/// seconds since the Unix epoch (`secs`) and nanoseconds within the second (`nanos`). Both
+/// representations of [timestamp_seconds] are accepted when deserializing.
+#[cfg(any(feature = "chrono", feature = "time"))]
+pub mod timestamp_seconds_nanos {
+	use ::serde::{ser::SerializeStruct, Deserializer, Serializer};
+
+	use super::Timestamp;
+
+	/// Serialize the timestamp as a struct of seconds and nanoseconds.
+	pub fn serialize<T, S>(timestamp: &T, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		T: Timestamp,
+		S: Serializer,
+	{
+		let (secs, nanos) = timestamp.to_parts();
+		let mut state = serializer.serialize_struct("Timestamp", 2)?;
+		state.serialize_field("secs", &secs)?;
+		state.serialize_field("nanos", &nanos)?;
+		state.end()
+	}
+
+	/// Deserialize the timestamp from seconds or a struct of seconds and nanoseconds.
+	pub fn deserialize<'de, T, D>(deserializer: D) -> Result<T, D::Error>
+	where
+		T: Timestamp,
+		D: Deserializer<'de>,
+	{
+		super::deserialize_timestamp(deserializer)
+	}
+}
+
+/// Deserialize a [`Uuid`](::uuid::Uuid) from 16 bytes or a string, as bytes or string.
+#[cfg(feature = "uuid")]
+fn deserialize_uuid<'de, D>(deserializer: D) -> Result<::uuid::Uuid, D::Error>
+where
+	D: Deserializer<'de>,
+{
+	/// Visitor for both UUID representations.
+	struct UuidVisitor;
+
+	impl de::Visitor<'_> for UuidVisitor {
+		type Value = ::uuid::Uuid;
+
+		fn expecting(&self, formatter: &mut fmt::Formatter<'_>) -> fmt::Result {
+			formatter.write_str("16 bytes or a string of a UUID")
+		}
+
+		fn visit_bytes<E>(self, v: &[u8]) -> Result<Self::Value, E>
+		where
+			E: de::Error,
+		{
+			::uuid::Uuid::from_slice(v)
+				.or_else(|_| ::uuid::Uuid::try_parse_ascii(v))
+				.map_err(|_| E::invalid_value(de::Unexpected::Bytes(v), &self))
+		}
+
+		fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
+		where
+			E: de::Error,
+		{
+			::uuid::Uuid::try_parse(v).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+		}
+	}
+
+	deserializer.deserialize_bytes(UuidVisitor)
+}
+
+/// Encode [`Uuid`](::uuid::Uuid)s as 16 bytes, taking up 18 bytes in total. Strings of UUIDs,
+/// e.g. written by human-readable producers, are accepted as well when deserializing.
+#[cfg(feature = "uuid")]
+pub mod uuid_bytes {
+	use ::serde::{Deserializer, Serializer};
+	use ::uuid::Uuid;
+
+	/// Serialize the UUID as bytes.
+	pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_bytes(uuid.as_bytes())
+	}
+
+	/// Deserialize the UUID from bytes or a string.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		super::deserialize_uuid(deserializer)
+	}
+}
+
+/// Encode [`Uuid`](::uuid::Uuid)s as hyphenated lowercase strings, taking up 38 bytes in total.
+/// Bytes are accepted as well when deserializing.
+#[cfg(feature = "uuid")]
+pub mod uuid_string {
+	use ::serde::{Deserializer, Serializer};
+	use ::uuid::Uuid;
+
+	/// Serialize the UUID as string.
+	pub fn serialize<S>(uuid: &Uuid, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_str(uuid.hyphenated().encode_lower(&mut Uuid::encode_buffer()))
+	}
+
+	/// Deserialize the UUID from a string or bytes.
+	pub fn deserialize<'de, D>(deserializer: D) -> Result<Uuid, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		super::deserialize_uuid(deserializer)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, reason = "Tests")]
+
+	use ::serde::{Deserialize, Serialize};
+
+	use super::*;
+	use crate::{format::Type, Error};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Peer {
+		#[serde(with = "ip_as_bytes")]
+		ip: Ipv4Addr,
+		#[serde(with = "ip_as_bytes")]
+		any_ip: IpAddr,
+		#[serde(with = "socket_addr_as_bytes")]
+		addr: SocketAddr,
+		#[serde(with = "duration_as_nanos")]
+		timeout: Duration,
+	}
+
+	#[test]
+	fn helpers_roundtrip() {
+		let v6 = Ipv6Addr::new(0x2001, 0xDB8, 0, 0, 0, 0, 0xFF00, 0x42);
+		let peers = [
+			Peer {
+				ip: Ipv4Addr::new(192, 168, 1, 1),
+				any_ip: v6.into(),
+				addr: SocketAddr::new(v6.into(), 443),
+				timeout: Duration::MAX,
+			},
+			Peer {
+				ip: Ipv4Addr::UNSPECIFIED,
+				any_ip: Ipv4Addr::BROADCAST.into(),
+				addr: SocketAddr::new(Ipv4Addr::LOCALHOST.into(), 8080),
+				timeout: Duration::new(90, 500),
+			},
+		];
+		let config = crate::Config { use_indices: true, ..Default::default() };
+		for peer in peers {
+			let mut buffer = [0; 256];
+			let bytes = crate::to_slice(&peer, &mut buffer).unwrap();
+			let parsed: Peer = crate::from_slice(bytes).unwrap();
+			assert_eq!(parsed, peer);
+			let bytes = crate::to_slice_with_config(&peer, &mut buffer, config).unwrap();
+			let parsed: Peer = crate::from_slice_with_config(bytes, config).unwrap();
+			assert_eq!(parsed, peer);
+		}
+	}
+
+	/// Serialize a value with the given helper and return the bytes.
+	fn serialized<F>(serialize: F) -> ([u8; 64], usize)
+	where
+		F: FnOnce(&mut crate::Serializer<crate::SliceOutput<'_>>) -> Result<(), Error>,
+	{
+		let mut buffer = [0; 64];
+		let mut ser = crate::Serializer::new(crate::SliceOutput::new(&mut buffer));
+		serialize(&mut ser).unwrap();
+		let len = ser.into_output().bytes_written();
+		(buffer, len)
+	}
+
+	#[test]
+	fn helpers_are_compact() {
+		let ip = Ipv4Addr::new(192, 168, 1, 1);
+		let (buffer, len) = serialized(|ser| ip_as_bytes::serialize(&ip, ser));
+		assert_eq!(buffer.get(.. len).unwrap(), [Type::Bytes.into(), 4, 192, 168, 1, 1]);
+
+		let v6 = Ipv6Addr::LOCALHOST;
+		let sizes = [
+			(IpAddr::from(ip), SocketAddr::new(ip.into(), 8080), 6, 8),
+			(IpAddr::from(v6), SocketAddr::new(v6.into(), 1), 18, 20),
+		];
+		for (ip, addr, ip_len, addr_len) in sizes {
+			assert_eq!(serialized(|ser| ip_as_bytes::serialize(&ip, ser)).1, ip_len);
+			assert_eq!(serialized(|ser| socket_addr_as_bytes::serialize(&addr, ser)).1, addr_len);
+		}
+
+		let duration = Duration::from_millis(1500);
+		assert_eq!(serialized(|ser| duration_as_nanos::serialize(&duration, ser)).1, 6);
+	}
+
+	#[test]
+	fn helpers_reject_invalid_data() {
+		let bytes = [Type::Bytes.into(), 5, 1, 2, 3, 4, 5];
+		let result =
+			ip_as_bytes::deserialize::<IpAddr, _>(&mut crate::Deserializer::new(bytes.as_slice()));
+		assert!(result.is_err());
+		let result = socket_addr_as_bytes::deserialize::<SocketAddr, _>(
+			&mut crate::Deserializer::new(bytes.as_slice()),
+		);
+		assert!(result.is_err());
+
+		let bytes = [Type::UnsignedInt.into(), 1];
+		let result = ip_as_bytes::deserialize::<Ipv4Addr, _>(&mut crate::Deserializer::new(
+			bytes.as_slice(),
+		));
+		assert!(matches!(result, Err(Error::WrongType(Type::UnsignedInt, _))));
+
+		let mut buffer = [0; 32];
+		let bytes = crate::to_slice(&u128::MAX, &mut buffer).unwrap();
+		let result = duration_as_nanos::deserialize(&mut crate::Deserializer::new(&*bytes));
+		assert!(result.is_err());
+	}
+
+	#[test]
+	fn byte_array_is_compatible_with_arrays() {
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Hashed {
+			#[serde(with = "byte_array")]
+			hash: [u8; 32],
+		}
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Plain {
+			hash: [u8; 32],
+		}
+
+		let hash = ::core::array::from_fn(|i| (i as u8).wrapping_mul(37));
+		let (buffer, len) = serialized(|ser| byte_array::serialize(&hash, ser));
+		assert_eq!(len, 34);
+		assert_eq!(buffer.get(.. 2).unwrap(), [Type::Bytes.into(), 32]);
+
+		let mut buffer = [0; 128];
+		let bytes = crate::to_slice(&Hashed { hash }, &mut buffer).unwrap();
+		let parsed: Hashed = crate::from_slice(bytes).unwrap();
+		assert_eq!(parsed.hash, hash);
+		let parsed: Plain = crate::from_slice(bytes).unwrap();
+		assert_eq!(parsed.hash, hash);
+
+		let bytes = crate::to_slice(&Plain { hash }, &mut buffer).unwrap();
+		let parsed: Hashed = crate::from_slice(bytes).unwrap();
+		assert_eq!(parsed.hash, hash);
+
+		for len in [31, 33] {
+			let mut bytes = [0; 35];
+			bytes.get_mut(.. 2).unwrap().copy_from_slice(&[Type::Bytes.into(), len]);
+			let result = byte_array::deserialize::<32, _>(&mut crate::Deserializer::new(
+				bytes.get(.. usize::from(len) + 2).unwrap(),
+			));
+			assert!(result.is_err());
+		}
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn cows_borrow_from_slices() {
+		use ::alloc::{borrow::Cow, rc::Rc, sync::Arc};
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Borrowing<'a> {
+			#[serde(borrow, with = "cow_str")]
+			text: Cow<'a, str>,
+			#[serde(borrow, with = "cow_bytes")]
+			data: Cow<'a, [u8]>,
+			#[serde(with = "shared_str")]
+			arc: Arc<str>,
+			#[serde(with = "shared_str")]
+			rc: Rc<str>,
+		}
+
+		let value = Borrowing {
+			text: "text".into(),
+			data: [1, 2, 3].as_slice().into(),
+			arc: "arc".into(),
+			rc: "rc".into(),
+		};
+		let bytes = crate::to_vec(&value).unwrap();
+		let parsed: Borrowing<'_> = crate::from_slice(&bytes).unwrap();
+		assert_eq!(parsed, value);
+		assert!(matches!(parsed.text, Cow::Borrowed(_)));
+		assert!(matches!(parsed.data, Cow::Borrowed(_)));
+
+		// Readers cannot lend their data.
+		#[cfg(feature = "std")]
+		{
+			let mut buffer = ::alloc::vec::Vec::new();
+			let mut de = crate::Deserializer::new(crate::io::IoReader::new(bytes.as_slice()))
+				.with_buffer(&mut buffer);
+			let parsed = Borrowing::deserialize(&mut de).unwrap();
+			assert_eq!(parsed, value);
+			assert!(matches!(parsed.text, Cow::Owned(_)));
+			assert!(matches!(parsed.data, Cow::Owned(_)));
+		}
+
+		// Bytes written as sequence of integers are accepted.
+		let bytes = crate::to_vec(&[1_u8, 2, 3]).unwrap();
+		let parsed =
+			cow_bytes::deserialize(&mut crate::Deserializer::new(bytes.as_slice())).unwrap();
+		assert_eq!(parsed, [1, 2, 3].as_slice());
+
+		let bytes = crate::to_vec(&5_u8).unwrap();
+		let result = cow_str::deserialize(&mut crate::Deserializer::new(bytes.as_slice()));
+		assert!(matches!(result, Err(Error::WrongType(Type::UnsignedInt, _))));
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn system_time_roundtrips() {
+		use ::std::time::{SystemTime, UNIX_EPOCH};
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Event {
+			#[serde(with = "system_time_as_nanos")]
+			time: SystemTime,
+		}
+
+		let times = [
+			UNIX_EPOCH,
+			UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789),
+			UNIX_EPOCH - Duration::new(86_400, 1),
+		];
+		for time in times {
+			let event = Event { time };
+			let bytes = crate::to_vec(&event).unwrap();
+			let parsed: Event = crate::from_slice(&bytes).unwrap();
+			assert_eq!(parsed, event);
+		}
+
+		let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+		assert_eq!(serialized(|ser| system_time_as_nanos::serialize(&time, ser)).1, 10);
+	}
+
+	#[cfg(feature = "chrono")]
+	#[test]
+	fn chrono_timestamps_have_exact_bytes() {
+		use ::chrono::{DateTime, Utc};
+
+		let timestamp = DateTime::<Utc>::from_timestamp(1_700_000_000, 123_456_789).unwrap();
+		let (buffer, len) = serialized(|ser| timestamp_seconds::serialize(&timestamp, ser));
+		let seconds = [Type::SignedInt.into(), 128, 196, 159, 213, 12];
+		assert_eq!(buffer.get(.. len).unwrap(), seconds);
+		let (buffer, len) = serialized(|ser| timestamp_seconds_nanos::serialize(&timestamp, ser));
+		let expected = [
+			Type::MapStart.into(),
+			Type::String.into(),
+			4,
+			b's',
+			b'e',
+			b'c',
+			b's',
+			Type::SignedInt.into(),
+			128,
+			196,
+			159,
+			213,
+			12,
+			Type::String.into(),
+			5,
+			b'n',
+			b'a',
+			b'n',
+			b'o',
+			b's',
+			Type::UnsignedInt.into(),
+			149,
+			154,
+			239,
+			58,
+			Type::MapEnd.into(),
+		];
+		assert_eq!(buffer.get(.. len).unwrap(), expected);
+
+		// Both representations are accepted by both helpers.
+		let truncated = DateTime::<Utc>::from_timestamp(1_700_000_000, 0).unwrap();
+		let parsed: DateTime<Utc> =
+			timestamp_seconds::deserialize(&mut crate::Deserializer::new(seconds.as_slice()))
+				.unwrap();
+		assert_eq!(parsed, truncated);
+		let parsed: DateTime<Utc> =
+			timestamp_seconds_nanos::deserialize(&mut crate::Deserializer::new(seconds.as_slice()))
+				.unwrap();
+		assert_eq!(parsed, truncated);
+		let parsed: DateTime<Utc> =
+			timestamp_seconds::deserialize(&mut crate::Deserializer::new(expected.as_slice()))
+				.unwrap();
+		assert_eq!(parsed, timestamp);
+	}
+
+	#[cfg(all(feature = "chrono", feature = "time"))]
+	#[test]
+	fn timestamps_roundtrip_across_helpers() {
+		use ::chrono::{DateTime, Utc};
+		use ::time::OffsetDateTime;
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct ChronoEvent {
+			#[serde(with = "timestamp_seconds_nanos")]
+			precise: DateTime<Utc>,
+			#[serde(with = "timestamp_seconds")]
+			coarse: DateTime<Utc>,
+		}
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct TimeEvent {
+			#[serde(with = "timestamp_seconds")]
+			precise: OffsetDateTime,
+			#[serde(with = "timestamp_seconds_nanos")]
+			coarse: OffsetDateTime,
+		}
+
+		let config = crate::Config { use_indices: true, ..Default::default() };
+		for secs in [-1_000_000_001, -1, 0, 1_700_000_000] {
+			let event = ChronoEvent {
+				precise: DateTime::from_timestamp(secs, 999_999_999).unwrap(),
+				coarse: DateTime::from_timestamp(secs, 0).unwrap(),
+			};
+			let mut buffer = [0; 128];
+			let bytes = crate::to_slice(&event, &mut buffer).unwrap();
+			let parsed: ChronoEvent = crate::from_slice(bytes).unwrap();
+			assert_eq!(parsed, event);
+			let parsed: TimeEvent = crate::from_slice(bytes).unwrap();
+			assert_eq!(
+				parsed.precise.unix_timestamp_nanos(),
+				i128::from(secs) * 1_000_000_000 + 999_999_999
+			);
+			assert_eq!(parsed.coarse.unix_timestamp(), secs);
+
+			let bytes = crate::to_slice_with_config(&parsed, &mut buffer, config).unwrap();
+			let parsed: ChronoEvent = crate::from_slice_with_config(bytes, config).unwrap();
+			assert_eq!(parsed.precise, DateTime::from_timestamp(secs, 0).unwrap());
+			assert_eq!(parsed.coarse, event.coarse);
+		}
+	}
+
+	#[cfg(feature = "uuid")]
+	#[test]
+	fn uuids_accept_both_representations() {
+		use ::uuid::Uuid;
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Ids {
+			#[serde(with = "uuid_bytes")]
+			compact: Uuid,
+			#[serde(with = "uuid_string")]
+			readable: Uuid,
+		}
+
+		let uuid = Uuid::from_u128(0x67E5_5044_10B1_426F_9247_BB68_0E5F_E0C8);
+		let (buffer, len) = serialized(|ser| uuid_bytes::serialize(&uuid, ser));
+		assert_eq!(len, 18);
+		assert_eq!(buffer.get(.. 2).unwrap(), [Type::Bytes.into(), 16]);
+		assert_eq!(buffer.get(2 .. len).unwrap(), uuid.as_bytes());
+		let (buffer, len) = serialized(|ser| uuid_string::serialize(&uuid, ser));
+		assert_eq!(len, 38);
+		assert_eq!(buffer.get(2 .. len).unwrap(), b"67e55044-10b1-426f-9247-bb680e5fe0c8");
+
+		let ids = Ids { compact: uuid, readable: uuid };
+		let mut buffer = [0; 128];
+		let bytes = crate::to_slice(&ids, &mut buffer).unwrap();
+		let parsed: Ids = crate::from_slice(bytes).unwrap();
+		assert_eq!(parsed, ids);
+
+		// Data written by a human-readable producer, and the other way around.
+		let mut buffer = [0; 128];
+		let bytes = crate::to_slice(&"67e55044-10b1-426f-9247-bb680e5fe0c8", &mut buffer).unwrap();
+		let parsed = uuid_bytes::deserialize(&mut crate::Deserializer::new(&*bytes)).unwrap();
+		assert_eq!(parsed, uuid);
+		let bytes =
+			crate::to_slice(&::serde_bytes::Bytes::new(uuid.as_bytes()), &mut buffer).unwrap();
+		let parsed = uuid_string::deserialize(&mut crate::Deserializer::new(&*bytes)).unwrap();
+		assert_eq!(parsed, uuid);
+
+		let bytes = crate::to_slice(&"not a uuid", &mut buffer).unwrap();
+		assert!(uuid_bytes::deserialize(&mut crate::Deserializer::new(&*bytes)).is_err());
+	}
+
+	#[cfg(feature = "primitive-types")]
+	#[test]
+	fn u256_roundtrips_as_bytes() {
+		use ::primitive_types::U256;
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Compact(#[serde(with = "u256_bytes")] U256);
+
+		#[derive(Debug, PartialEq, Serialize, Deserialize)]
+		struct Hex(U256);
+
+		let cases = [
+			(U256::zero(), 5),
+			(U256::from(0x1234_u32), 8),
+			(U256::from(u128::MAX), 36),
+			(U256::MAX, 68),
+		];
+		for (int, default_len) in cases {
+			let (buffer, len) = serialized(|ser| Compact(int).serialize(ser));
+			assert_eq!(len, 34);
+			assert_eq!(buffer.get(.. 2).unwrap(), [Type::Bytes.into(), 32]);
+			assert_eq!(buffer.get(2 .. len).unwrap(), int.to_big_endian());
+			let parsed: Compact = crate::from_slice(buffer.get(.. len).unwrap()).unwrap();
+			assert_eq!(parsed.0, int);
+
+			// The default is the hex string of `impl-serde`.
+			let mut buffer = [0; 128];
+			let bytes = crate::to_slice(&Hex(int), &mut buffer).unwrap();
+			assert_eq!(bytes.len(), default_len);
+			assert_eq!(bytes.first(), Some(&Type::String.into()));
+			let parsed: Hex = crate::from_slice(bytes).unwrap();
+			assert_eq!(parsed.0, int);
+		}
+
+		// Only exactly 32 bytes are accepted.
+		for len in [0, 31, 33] {
+			let mut buffer = [0; 64];
+			let bytes = ::serde_bytes::Bytes::new([0x01; 33].get(.. len).unwrap());
+			let bytes = crate::to_slice(&bytes, &mut buffer).unwrap();
+			let result = crate::from_slice::<Compact>(bytes);
+			assert!(result.is_err(), "{len} bytes: {result:?}");
+		}
+	}
+}
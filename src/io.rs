@@ -1,11 +1,33 @@
 //! Implementation of input and output: reading and writing bytes.
+//!
+//! [Serializer](crate::Serializer)s write to any [Output] and [Deserializer](crate::Deserializer)s
+//! read from any [Input]. Implement these traits to serialize into or deserialize from custom
+//! sinks and sources, e.g. ring buffers or memory-mapped regions. See the `custom_io` example.
+//!
+//! New methods may be added to the traits in minor versions, but only with default
+//! implementations, so that custom implementations keep working.
 
+#[cfg(feature = "alloc")]
+use ::alloc::string::String;
 #[cfg(feature = "std")]
-use ::std::io::{Read, Write};
+use ::std::io::{BufRead, IoSlice, Read, Write};
 
 use crate::{buffer::Buffer, Error, Result};
 
+/// Bytes read from an [Input].
+#[derive(Debug)]
+pub enum ReadBytes<'de, 's> {
+	/// Borrowed from the input for the whole deserialization.
+	Borrowed(&'de [u8]),
+	/// Only available until the next read, e.g. copied to the buffer.
+	Transient(&'s [u8]),
+}
+
 /// Generic interface for reading bytes from somewhere.
+///
+/// All methods return [Error::UnexpectedEnd] if the input ends before the requested data. Data
+/// that is consumed must not be returned again. `'de` is the lifetime of data that can be borrowed
+/// from the input for the whole deserialization, e.g. of a slice.
 pub trait Input<'de> {
 	/// Peek at the next byte without consuming it.
 	fn peek_byte(&mut self) -> Result<u8>;
@@ -16,11 +38,82 @@ pub trait Input<'de> {
 	/// Read (exactly) the given number of bytes. When possible, return the borrowed slice of the
 	/// input. If this is not possible, return `None` instead and write the output to the given
 	/// buffer. If the buffer does not exist, we are out of luck and need to return an error.
+	///
+	/// Borrowed data is returned as `Some` and the buffer is not touched. Otherwise, the data is
+	/// appended to the buffer, e.g. with [reserve_slice](Buffer::reserve_slice), so that it is at
+	/// the end of [as_slice](Buffer::as_slice) afterwards. Without a buffer, this returns
+	/// [Error::BufferTooSmall]. The deserializer clears the buffer before each read.
 	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
 	where
 		B: Buffer;
+	/// Read (exactly) the given number of bytes like [read_bytes](Self::read_bytes), but allow
+	/// returning data that is only valid until the next read. This allows inputs with internal
+	/// buffers to skip copying to the given buffer.
+	#[inline]
+	fn read_bytes_transient<'s, B>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut B>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		B: Buffer,
+	{
+		let mut buffer = buffer;
+		if let Some(data) = self.read_bytes(len, buffer.as_deref_mut())? {
+			return Ok(ReadBytes::Borrowed(data));
+		}
+		let buffer: &'s B = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		Ok(ReadBytes::Transient(buffer.as_slice()))
+	}
 	/// Skip the given number of bytes.
 	fn skip_bytes(&mut self, len: usize) -> Result<()>;
+	/// Return [Error::ExcessData] if there is more data in the input. The default implementation
+	/// peeks at the next byte, inputs that know their remaining length can report it.
+	#[inline]
+	fn check_excess(&mut self) -> Result<()> {
+		match self.peek_byte() {
+			Ok(byte) => Err(Error::excess_byte(byte)),
+			Err(_) => Ok(()),
+		}
+	}
+	/// Return the remaining data, if the input is a contiguous slice that lives for `'de`.
+	/// Comparing the remaining data before and after skipping a value gives the span of the value,
+	/// e.g. for [RawValue](crate::RawValue). The default implementation returns `None`.
+	#[inline]
+	fn remaining_borrowed(&self) -> Option<&'de [u8]> {
+		None
+	}
+	/// Whether [read_bytes](Self::read_bytes) can return data borrowed for `'de`. Otherwise, types
+	/// that only accept borrowed data, e.g. `&str`, fail with [Error::CannotBorrow]. The default
+	/// implementation returns `false`.
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		false
+	}
+	/// Peek at up to `n` of the next bytes without consuming them, e.g. to decode a var-int at
+	/// once. Fewer bytes are returned near the end of the input. Returns `None` if the input cannot
+	/// look ahead without copying, e.g. readers, which is what the default implementation does.
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		_ = n;
+		None
+	}
+	/// **alloc**: Start recording the consumed bytes, e.g. to compare the encoding of map keys.
+	/// Recordings can be nested and are ended by [finish_recording](Self::finish_recording).
+	/// Returns `false` if the input cannot record, which is what the default implementation does.
+	/// Wrap the input in a [RecordingInput] to record.
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn start_recording(&mut self) -> bool {
+		false
+	}
+	/// **alloc**: Stop the innermost recording and return the bytes consumed since it was started.
+	/// The default implementation returns no bytes.
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn finish_recording(&mut self) -> ::alloc::vec::Vec<u8> {
+		::alloc::vec::Vec::new()
+	}
 }
 
 impl<'de> Input<'de> for &'de [u8] {
@@ -63,59 +156,177 @@ impl<'de> Input<'de> for &'de [u8] {
 		*self = remaining;
 		Ok(())
 	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn check_excess(&mut self) -> Result<()> {
+		if self.is_empty() {
+			Ok(())
+		} else {
+			Err(Error::excess_data(self))
+		}
+	}
+
+	#[inline]
+	fn remaining_borrowed(&self) -> Option<&'de [u8]> {
+		Some(self)
+	}
+
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		true
+	}
+
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		Some(self.get(.. n).unwrap_or(self))
+	}
 }
 
 #[cfg(feature = "std")]
-impl<'de, R> Input<'de> for IoReader<R>
+impl<'de, R, S> Input<'de> for IoReader<R, S>
 where
 	R: Read,
+	S: AsRef<[u8]> + AsMut<[u8]>,
 {
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn peek_byte(&mut self) -> Result<u8> {
-		let byte = Input::read_byte(self)?;
-		self.next_byte = Some(byte);
-		Ok(byte)
+		if let Some(byte) = self.buffered().first() {
+			return Ok(*byte);
+		}
+		self.fill_buffer()?.first().copied().ok_or_else(|| Error::UnexpectedEnd)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn read_byte(&mut self) -> Result<u8> {
-		if let Some(byte) = self.next_byte.take() {
-			Ok(byte)
-		} else {
-			let mut bytes = self.reader.by_ref().bytes();
-			let byte = bytes.next().ok_or_else(|| Error::UnexpectedEnd)??;
-			Ok(byte)
-		}
+		let byte = self.peek_byte()?;
+		self.pos += 1;
+		Ok(byte)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
-		if buffer.is_empty() {
+		if let Some(available) = self.buffered().get(.. buffer.len()) {
+			buffer.copy_from_slice(available);
+			self.pos += buffer.len();
 			return Ok(());
 		}
 
-		if let Some(byte) = self.next_byte.take() {
-			let (first, remaining) =
-				buffer.split_first_mut().ok_or_else(|| Error::BufferTooSmall)?;
-			*first = byte;
+		loop {
+			let available = self.buffered();
+			let len = available.len().min(buffer.len());
+			let (target, remaining) = buffer.split_at_mut(len);
+			target.copy_from_slice(available.get(.. len).unwrap_or_default());
+			self.pos += len;
 			buffer = remaining;
+
+			if buffer.is_empty() {
+				return Ok(());
+			}
+
+			// The internal buffer is empty now. Read large data directly.
+			if buffer.len() >= self.buffer.as_ref().len() {
+				match self.reader.read_exact(buffer) {
+					Err(err) if err.kind() == ::std::io::ErrorKind::UnexpectedEof => {
+						return Err(Error::UnexpectedEnd)
+					}
+					res => res?,
+				}
+				return Ok(());
+			}
+
+			if self.fill_buffer()?.is_empty() {
+				return Err(Error::UnexpectedEnd);
+			}
+		}
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		if len == 0 {
+			return Ok(Some(&[]));
+		}
+
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		let write = buffer.reserve_slice(len)?;
+		self.read_exact(write)?;
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, len: usize) -> Result<()> {
+		let buffered = self.filled - self.pos;
+		if len <= buffered {
+			self.pos += len;
+			return Ok(());
 		}
+		self.pos = self.filled;
+		let len = len - buffered;
 
-		match self.reader.read_exact(buffer) {
+		let to_write = u64::try_from(len).map_err(|_| Error::UsizeOverflow)?;
+		let mut skip = self.reader.by_ref().take(to_write);
+		let result = ::std::io::copy(&mut skip, &mut ::std::io::sink());
+		match result {
 			Err(err) if err.kind() == ::std::io::ErrorKind::UnexpectedEof => {
 				return Err(Error::UnexpectedEnd)
 			}
-			res => res?,
+			Ok(bytes) if bytes != to_write => return Err(Error::UnexpectedEnd),
+			res => {
+				res?;
+			}
+		}
+		Ok(())
+	}
+}
+
+#[cfg(feature = "std")]
+impl<'de, R> Input<'de> for BufReadInput<R>
+where
+	R: BufRead,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.fill_buf()?.first().copied().ok_or_else(|| Error::UnexpectedEnd)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		let byte = self.peek_byte()?;
+		self.reader.consume(1);
+		Ok(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
+		while !buffer.is_empty() {
+			let available = self.fill_buf()?;
+			if available.is_empty() {
+				return Err(Error::UnexpectedEnd);
+			}
+
+			let len = available.len().min(buffer.len());
+			let (target, remaining) = buffer.split_at_mut(len);
+			target.copy_from_slice(available.get(.. len).unwrap_or_default());
+			self.reader.consume(len);
+			buffer = remaining;
 		}
 		Ok(())
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
-	fn read_bytes<B>(&mut self, mut len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
 	where
 		B: Buffer,
 	{
@@ -124,184 +335,1480 @@ where
 		}
 
 		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
-		if let Some(byte) = self.next_byte.take() {
-			buffer.push(byte)?;
-			len -= 1;
+		let write = buffer.reserve_slice(len)?;
+		self.read_exact(write)?;
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes_transient<'s, B>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut B>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		B: Buffer,
+	{
+		if self.fill_buf()?.len() >= len {
+			// Consumed on the next read, when the data is not used anymore.
+			self.pending = len;
+			let data = self.reader.fill_buf()?.get(.. len).unwrap_or_default();
+			return Ok(ReadBytes::Transient(data));
 		}
 
+		if len == 0 {
+			return Ok(ReadBytes::Borrowed(&[]));
+		}
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
 		let write = buffer.reserve_slice(len)?;
-		match self.reader.read_exact(write) {
-			Err(err) if err.kind() == ::std::io::ErrorKind::UnexpectedEof => {
-				return Err(Error::UnexpectedEnd)
+		self.read_exact(write)?;
+		let buffer: &'s B = buffer;
+		Ok(ReadBytes::Transient(buffer.as_slice()))
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, mut len: usize) -> Result<()> {
+		while len > 0 {
+			let available = self.fill_buf()?.len();
+			if available == 0 {
+				return Err(Error::UnexpectedEnd);
 			}
-			res => res?,
+
+			let skip = available.min(len);
+			self.reader.consume(skip);
+			len -= skip;
 		}
-		Ok(None)
+		Ok(())
+	}
+}
+
+impl<'de, C> Input<'de> for ChunkedInput<'de, C>
+where
+	C: Iterator<Item = &'de [u8]>,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.chunk().first().copied().ok_or_else(|| Error::UnexpectedEnd)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		let (byte, remaining) = self.chunk().split_first().ok_or_else(|| Error::UnexpectedEnd)?;
+		self.current = remaining;
+		Ok(*byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, mut buffer: &mut [u8]) -> Result<()> {
+		while !buffer.is_empty() {
+			let chunk = self.chunk();
+			if chunk.is_empty() {
+				return Err(Error::UnexpectedEnd);
+			}
+
+			let (source, remaining) = chunk.split_at(chunk.len().min(buffer.len()));
+			let (target, rest) = buffer.split_at_mut(source.len());
+			target.copy_from_slice(source);
+			self.current = remaining;
+			buffer = rest;
+		}
+		Ok(())
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
-	fn skip_bytes(&mut self, mut len: usize) -> Result<()> {
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
 		if len == 0 {
-			return Ok(());
+			return Ok(Some(&[]));
 		}
 
-		if self.next_byte.take().is_some() {
-			len -= 1;
+		if let Some((data, remaining)) = self.chunk().split_at_checked(len) {
+			self.current = remaining;
+			return Ok(Some(data));
 		}
 
-		#[expect(clippy::expect_used, reason = "Fundamental architecture assumption")]
-		let to_write = u64::try_from(len).expect("usize is smaller or equal to u64");
-		let mut skip = self.reader.by_ref().take(to_write);
-		let result = ::std::io::copy(&mut skip, &mut ::std::io::sink());
-		match result {
-			Err(err) if err.kind() == ::std::io::ErrorKind::UnexpectedEof => {
-				return Err(Error::UnexpectedEnd)
+		// The data straddles chunk boundaries, so it is copied piece by piece.
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		let mut len = len;
+		while len > 0 {
+			let chunk = self.chunk();
+			if chunk.is_empty() {
+				return Err(Error::UnexpectedEnd);
 			}
-			Ok(bytes) if bytes != to_write => return Err(Error::UnexpectedEnd),
-			res => {
-				res?;
+
+			let (data, remaining) = chunk.split_at(chunk.len().min(len));
+			buffer.extend_from_slice(data)?;
+			self.current = remaining;
+			len -= data.len();
+		}
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, mut len: usize) -> Result<()> {
+		while len > 0 {
+			let chunk = self.chunk();
+			if chunk.is_empty() {
+				return Err(Error::UnexpectedEnd);
 			}
+
+			let skip = chunk.len().min(len);
+			self.current = chunk.get(skip ..).unwrap_or_default();
+			len -= skip;
 		}
 		Ok(())
 	}
+
+	/// Data within a single chunk is borrowed, only data straddling chunk boundaries is copied.
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		true
+	}
+
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		let chunk = self.chunk();
+		Some(chunk.get(.. n).unwrap_or(chunk))
+	}
 }
 
+#[cfg(feature = "embedded-io")]
+impl<'de, R> Input<'de> for EmbeddedReader<R>
+where
+	R: ::embedded_io::Read,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		if let Some(byte) = self.peeked {
+			return Ok(byte);
+		}
+		let mut byte = [0];
+		self.reader.read_exact(&mut byte).map_err(embedded_read_error)?;
+		let [byte] = byte;
+		self.peeked = Some(byte);
+		Ok(byte)
+	}
 
-/// Generic interface for writing bytes to somewhere.
-pub trait Output {
-	/// Write a single byte.
-	fn write_byte(&mut self, byte: u8) -> Result<()>;
-	/// Write all bytes from the buffer.
-	fn write_all(&mut self, bytes: &[u8]) -> Result<()>;
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		if let Some(byte) = self.peeked.take() {
+			return Ok(byte);
+		}
+		let mut byte = [0];
+		self.reader.read_exact(&mut byte).map_err(embedded_read_error)?;
+		let [byte] = byte;
+		Ok(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		let buffer = match (self.peeked, buffer.split_first_mut()) {
+			(_, None) => return Ok(()),
+			(Some(byte), Some((first, remaining))) => {
+				*first = byte;
+				self.peeked = None;
+				remaining
+			}
+			(None, Some(_)) => buffer,
+		};
+		self.reader.read_exact(buffer).map_err(embedded_read_error)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		if len == 0 {
+			return Ok(Some(&[]));
+		}
+
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		let write = buffer.reserve_slice(len)?;
+		self.read_exact(write)?;
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, mut len: usize) -> Result<()> {
+		let mut discard = [0; 64];
+		while len > 0 {
+			let skip = discard.len().min(len);
+			self.read_exact(discard.get_mut(.. skip).unwrap_or_default())?;
+			len -= skip;
+		}
+		Ok(())
+	}
 }
 
-impl Output for &mut [u8] {
+#[cfg(feature = "bytes")]
+impl<'de, B> Input<'de> for BufInput<B>
+where
+	B: ::bytes::Buf,
+{
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
-	fn write_byte(&mut self, byte: u8) -> Result<()> {
-		// This somehow makes more optimized code gen.
-		if self.is_empty() {
-			return Err(Error::BufferTooSmall);
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.chunk().first().copied().ok_or_else(|| Error::UnexpectedEnd)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		let byte = self.peek_byte()?;
+		self.buf.advance(1);
+		Ok(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		self.consume_pending();
+		if self.buf.remaining() < buffer.len() {
+			return Err(Error::UnexpectedEnd);
 		}
+		self.buf.copy_to_slice(buffer);
+		Ok(())
+	}
 
-		let (write, remaining) =
-			::core::mem::take(self).split_first_mut().ok_or_else(|| Error::BufferTooSmall)?;
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes<T>(&mut self, len: usize, buffer: Option<&mut T>) -> Result<Option<&'de [u8]>>
+	where
+		T: Buffer,
+	{
+		if len == 0 {
+			return Ok(Some(&[]));
+		}
+
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		let write = buffer.reserve_slice(len)?;
+		self.read_exact(write)?;
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes_transient<'s, T>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut T>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		T: Buffer,
+	{
+		if self.chunk().len() >= len {
+			// Consumed on the next read, when the data is not used anymore.
+			self.pending = len;
+			let data = self.buf.chunk().get(.. len).unwrap_or_default();
+			return Ok(ReadBytes::Transient(data));
+		}
+
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		let write = buffer.reserve_slice(len)?;
+		self.read_exact(write)?;
+		let buffer: &'s T = buffer;
+		Ok(ReadBytes::Transient(buffer.as_slice()))
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, len: usize) -> Result<()> {
+		self.consume_pending();
+		if self.buf.remaining() < len {
+			return Err(Error::UnexpectedEnd);
+		}
+		self.buf.advance(len);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "text")]
+impl<'de> Input<'de> for TextInput<'_> {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.decoded()?.first().copied().ok_or_else(|| Error::UnexpectedEnd)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_byte(&mut self) -> Result<u8> {
+		let byte = self.peek_byte()?;
+		self.pos += 1;
+		Ok(byte)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		for byte in buffer {
+			*byte = self.read_byte()?;
+		}
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		if len == 0 {
+			return Ok(Some(&[]));
+		}
+		let buffer = buffer.ok_or_else(|| Error::BufferTooSmall)?;
+		self.read_exact(buffer.reserve_slice(len)?)?;
+		Ok(None)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn skip_bytes(&mut self, len: usize) -> Result<()> {
+		for _ in 0 .. len {
+			self.read_byte()?;
+		}
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn check_excess(&mut self) -> Result<()> {
+		// Invalid text after the data is an encoding error, not the end of the input.
+		match self.peek_byte() {
+			Ok(byte) => Err(Error::excess_byte(byte)),
+			Err(Error::UnexpectedEnd) => Ok(()),
+			Err(err) => Err(err),
+		}
+	}
+}
+
+/// Generic interface for writing bytes to somewhere.
+///
+/// Outputs that run out of space return [Error::BufferTooSmall]. After an error, the written data
+/// is incomplete and should be discarded.
+pub trait Output {
+	/// Write a single byte.
+	fn write_byte(&mut self, byte: u8) -> Result<()>;
+	/// Write all bytes from the buffer.
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()>;
+	/// Write all bytes like [write_all](Self::write_all), for data that is possibly large, e.g.
+	/// strings and bytes. Outputs with internal buffers can pass the data on without copying it
+	/// into the buffer first.
+	#[inline]
+	fn write_borrowed(&mut self, bytes: &[u8]) -> Result<()> {
+		self.write_all(bytes)
+	}
+	/// Flush buffered data to the underlying output, if there is any buffering.
+	#[inline]
+	fn flush(&mut self) -> Result<()> {
+		Ok(())
+	}
+}
+
+impl Output for &mut [u8] {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		// This somehow makes more optimized code gen.
+		if self.is_empty() {
+			return Err(Error::BufferTooSmall);
+		}
+
+		let (write, remaining) =
+			::core::mem::take(self).split_first_mut().ok_or_else(|| Error::BufferTooSmall)?;
 		*write = byte;
 		*self = remaining;
 
-		Ok(())
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		// This somehow makes more optimized code gen.
+		if self.is_empty() {
+			return Err(Error::BufferTooSmall);
+		}
+
+		let (write, remaining) = ::core::mem::take(self)
+			.split_at_mut_checked(bytes.len())
+			.ok_or_else(|| Error::BufferTooSmall)?;
+		write.copy_from_slice(bytes);
+		*self = remaining;
+
+		Ok(())
+	}
+}
+
+impl Output for SliceOutput<'_> {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		let write = self.buffer.get_mut(self.written).ok_or_else(|| Error::BufferTooSmall)?;
+		*write = byte;
+		self.written += 1;
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		let end = self.written.checked_add(bytes.len()).ok_or_else(|| Error::BufferTooSmall)?;
+		let write =
+			self.buffer.get_mut(self.written .. end).ok_or_else(|| Error::BufferTooSmall)?;
+		write.copy_from_slice(bytes);
+		self.written = end;
+		Ok(())
+	}
+}
+
+#[cfg(feature = "alloc")]
+impl Output for ::alloc::vec::Vec<u8> {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.push(byte);
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.extend_from_slice(bytes);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> Output for ::heapless::Vec<u8, N> {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.push(byte).map_err(|_| Error::BufferTooSmall)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.extend_from_slice(bytes).map_err(|_| Error::BufferTooSmall)
+	}
+}
+
+#[cfg(feature = "bytes")]
+impl Output for ::bytes::BytesMut {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		::bytes::BufMut::put_u8(self, byte);
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.extend_from_slice(bytes);
+		Ok(())
+	}
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> Output for ::arrayvec::ArrayVec<u8, N> {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.try_push(byte).map_err(|_| Error::BufferTooSmall)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.try_extend_from_slice(bytes).map_err(|_| Error::BufferTooSmall)
+	}
+}
+
+// Note: this is also implementing for `std::vec::Vec`.
+#[cfg(feature = "std")]
+impl<W> Output for IoWriter<W>
+where
+	W: Write,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		if self.buffer.len() < self.capacity {
+			self.buffer.push(byte);
+			Ok(())
+		} else {
+			self.write_all(&[byte])
+		}
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		if self.buffer.len() + bytes.len() > self.capacity {
+			if bytes.len() >= self.capacity {
+				return self.write_with_buffer(bytes);
+			}
+			self.flush_buffer()?;
+		}
+
+		self.buffer.extend_from_slice(bytes);
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_borrowed(&mut self, bytes: &[u8]) -> Result<()> {
+		if self.buffer.len() + bytes.len() > self.capacity {
+			return self.write_with_buffer(bytes);
+		}
+
+		self.buffer.extend_from_slice(bytes);
+		Ok(())
+	}
+
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn flush(&mut self) -> Result<()> {
+		self.flush_buffer()?;
+		self.writer.flush()?;
+		Ok(())
+	}
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W> Output for EmbeddedWriter<W>
+where
+	W: ::embedded_io::Write,
+{
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		self.writer.write_all(&[byte]).map_err(embedded_error)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.writer.write_all(bytes).map_err(embedded_error)
+	}
+
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn flush(&mut self) -> Result<()> {
+		self.writer.flush().map_err(embedded_error)
+	}
+}
+
+#[cfg(feature = "text")]
+impl Output for TextOutput {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, byte: u8) -> Result<()> {
+		match self.encoding {
+			TextEncoding::Base64 => {
+				let slot = self.group.get_mut(self.len).ok_or_else(|| Error::BufferTooSmall)?;
+				*slot = byte;
+				self.len += 1;
+				if self.len == self.group.len() {
+					push_base64(&mut self.text, &self.group);
+					self.len = 0;
+				}
+			}
+			TextEncoding::Hex => {
+				for nibble in [byte >> 4, byte & 0x0F] {
+					let digit = HEX_DIGITS.get(usize::from(nibble)).copied().unwrap_or(b'0');
+					self.text.push(char::from(digit));
+				}
+			}
+		}
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		bytes.iter().try_for_each(|byte| self.write_byte(*byte))
+	}
+}
+
+/// [Output] into a mutable slice of bytes, which keeps track of the number of bytes written.
+#[derive(Debug)]
+pub struct SliceOutput<'a> {
+	/// The whole slice that is written to.
+	buffer: &'a mut [u8],
+	/// The number of bytes written so far.
+	written: usize,
+}
+
+impl<'a> SliceOutput<'a> {
+	/// Create a new output writing to the start of the given slice.
+	#[must_use]
+	pub fn new(buffer: &'a mut [u8]) -> Self {
+		Self { buffer, written: 0 }
+	}
+
+	/// Return the number of bytes written so far.
+	#[must_use]
+	pub const fn bytes_written(&self) -> usize {
+		self.written
+	}
+
+	/// Consume the output and return the written part of the slice.
+	#[must_use]
+	pub fn into_written(self) -> &'a mut [u8] {
+		self.buffer.split_at_mut(self.written).0
+	}
+}
+
+/// [Output] that only counts the number of bytes written, without storing them.
+#[derive(Debug, Default)]
+pub struct SizeCounter {
+	/// The number of bytes written so far.
+	count: usize,
+}
+
+impl SizeCounter {
+	/// Create a new size counter, starting at 0.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { count: 0 }
+	}
+
+	/// Return the number of bytes written so far.
+	#[must_use]
+	pub const fn count(&self) -> usize {
+		self.count
+	}
+}
+
+impl Output for SizeCounter {
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
+	fn write_byte(&mut self, _byte: u8) -> Result<()> {
+		self.count = self.count.checked_add(1).ok_or_else(|| Error::UsizeOverflow)?;
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
+		self.count = self.count.checked_add(bytes.len()).ok_or_else(|| Error::UsizeOverflow)?;
+		Ok(())
+	}
+}
+
+/// Size of the chunks readers are read in, by default.
+#[cfg(feature = "std")]
+pub(crate) const READER_CHUNK_SIZE: usize = 8 * 1024;
+
+/// Create the [IoReader] of a `from_reader*` function, reading chunks into the given storage on
+/// the stack. Without the excess data check, more data may follow the value in the reader, so only
+/// the value's bytes are read then.
+#[cfg(feature = "std")]
+pub(crate) fn value_reader<R>(
+	reader: R,
+	storage: &mut [u8; READER_CHUNK_SIZE],
+	error_on_excess_data: bool,
+) -> IoReader<R, &mut [u8]>
+where
+	R: Read,
+{
+	let len = if error_on_excess_data { READER_CHUNK_SIZE } else { 1 };
+	IoReader::with_storage(reader, storage.get_mut(.. len).unwrap_or_default())
+}
+
+/// Wrapper for generic reader types as [Input]. Data is read from the reader in chunks into an
+/// internal buffer, so that small reads do not hit the reader. The reader is read ahead by up to
+/// a chunk, so data after the deserialized value is lost with the [IoReader].
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoReader<R, S = Box<[u8]>> {
+	/// The inner reader.
+	reader: R,
+	/// The internal buffer.
+	buffer: S,
+	/// The position of the next byte to read in the buffer.
+	pos: usize,
+	/// The number of valid bytes in the buffer.
+	filled: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R> IoReader<R>
+where
+	R: Read,
+{
+	/// The default buffer capacity.
+	pub const DEFAULT_CAPACITY: usize = READER_CHUNK_SIZE;
+
+	/// Create a new reader from the given reader, with the default buffer capacity.
+	#[must_use]
+	pub fn new(reader: R) -> Self {
+		Self::with_capacity(reader, Self::DEFAULT_CAPACITY)
+	}
+
+	/// Create a new reader from the given reader, reading chunks of up to `capacity` bytes. The
+	/// capacity is at least 1.
+	#[must_use]
+	pub fn with_capacity(reader: R, capacity: usize) -> Self {
+		Self::with_storage(reader, vec![0; capacity.max(1)].into_boxed_slice())
+	}
+}
+
+#[cfg(feature = "std")]
+impl<R, S> IoReader<R, S>
+where
+	R: Read,
+	S: AsRef<[u8]> + AsMut<[u8]>,
+{
+	/// Create a new reader from the given reader, reading chunks into the given storage, e.g. an
+	/// array on the stack to avoid allocating. With a single byte of storage, no more than the
+	/// consumed bytes and a peeked byte are read. Empty storage cannot hold any data, so reading
+	/// fails with [Error::UnexpectedEnd].
+	#[must_use]
+	pub const fn with_storage(reader: R, storage: S) -> Self {
+		Self { reader, buffer: storage, pos: 0, filled: 0 }
+	}
+
+	/// The buffered bytes, which were not read yet.
+	#[inline]
+	fn buffered(&self) -> &[u8] {
+		self.buffer.as_ref().get(self.pos .. self.filled).unwrap_or_default()
+	}
+
+	/// Return the buffered bytes, refilling the buffer from the reader if it is empty. Only
+	/// returns an empty slice at the end of the reader.
+	#[cold]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn fill_buffer(&mut self) -> Result<&[u8]> {
+		if self.pos >= self.filled {
+			self.filled = loop {
+				match self.reader.read(self.buffer.as_mut()) {
+					Err(err) if err.kind() == ::std::io::ErrorKind::Interrupted => {}
+					res => break res?,
+				}
+			};
+			self.pos = 0;
+		}
+		Ok(self.buffered())
+	}
+}
+
+/// Wrapper for [BufRead]ers as [Input]. Data that is in the reader's buffer already can be
+/// visited without copying it.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct BufReadInput<R> {
+	/// The inner reader.
+	reader: R,
+	/// Number of bytes handed out transiently, to be consumed before the next read.
+	pending: usize,
+}
+
+#[cfg(feature = "std")]
+impl<R> BufReadInput<R>
+where
+	R: BufRead,
+{
+	/// Create a new input from the given reader.
+	#[must_use]
+	pub const fn new(reader: R) -> Self {
+		Self { reader, pending: 0 }
+	}
+
+	/// Consume the input and return the reader, positioned directly after the read data.
+	#[must_use]
+	pub fn into_inner(mut self) -> R {
+		self.reader.consume(self.pending);
+		self.reader
+	}
+
+	/// Consume pending data and return the reader's buffer, refilling it if it is empty. Only
+	/// returns an empty slice at the end of the reader.
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn fill_buf(&mut self) -> Result<&[u8]> {
+		if self.pending > 0 {
+			self.reader.consume(self.pending);
+			self.pending = 0;
+		}
+
+		loop {
+			match self.reader.fill_buf() {
+				Err(err) if err.kind() == ::std::io::ErrorKind::Interrupted => {}
+				Err(err) => return Err(err.into()),
+				Ok(_) => break,
+			}
+		}
+		Ok(self.reader.fill_buf()?)
+	}
+}
+
+/// Wrapper for [`bytes::Buf`]s as [Input], e.g. `bytes::Bytes`, chains of them or `VecDeque<u8>`.
+/// Data within a single chunk of the buffer can be visited without copying it, data straddling
+/// chunk boundaries is copied to the deserializer's buffer.
+#[cfg(feature = "bytes")]
+#[derive(Debug)]
+pub struct BufInput<B> {
+	/// The inner buffer.
+	buf: B,
+	/// Number of bytes handed out transiently, to be consumed before the next read.
+	pending: usize,
+}
+
+#[cfg(feature = "bytes")]
+impl<B> BufInput<B>
+where
+	B: ::bytes::Buf,
+{
+	/// Create a new input from the given buffer.
+	#[must_use]
+	pub const fn new(buf: B) -> Self {
+		Self { buf, pending: 0 }
+	}
+
+	/// Consume the input and return the buffer, advanced to after the read data.
+	#[must_use]
+	pub fn into_inner(mut self) -> B {
+		self.consume_pending();
+		self.buf
+	}
+
+	/// Consume the data that was handed out transiently.
+	#[inline]
+	fn consume_pending(&mut self) {
+		if self.pending > 0 {
+			self.buf.advance(self.pending);
+			self.pending = 0;
+		}
+	}
+
+	/// Consume pending data and return the current chunk. Only returns an empty slice at the end
+	/// of the buffer.
+	#[inline]
+	fn chunk(&mut self) -> &[u8] {
+		self.consume_pending();
+		self.buf.chunk()
+	}
+}
+
+/// [Input] over byte chunks, e.g. scattered DMA buffers, without concatenating them first. Reads
+/// within a single chunk borrow from it, data straddling chunk boundaries is copied to the
+/// deserializer's buffer.
+#[derive(Debug)]
+pub struct ChunkedInput<'a, C> {
+	/// The remaining bytes of the current chunk.
+	current: &'a [u8],
+	/// The following chunks.
+	chunks: C,
+}
+
+impl<'a, C> ChunkedInput<'a, C>
+where
+	C: Iterator<Item = &'a [u8]>,
+{
+	/// Create a new input from the given chunks. For a slice of chunks, use
+	/// `ChunkedInput::new(chunks.iter().copied())`.
+	#[must_use]
+	pub fn new<T>(chunks: T) -> Self
+	where
+		T: IntoIterator<IntoIter = C>,
+	{
+		Self { current: &[], chunks: chunks.into_iter() }
+	}
+
+	/// Return the remaining bytes of the current chunk, moving on to the next non-empty chunk if
+	/// it is exhausted. Only returns an empty slice at the end of the chunks.
+	#[inline]
+	fn chunk(&mut self) -> &'a [u8] {
+		while self.current.is_empty() {
+			let Some(next) = self.chunks.next() else {
+				break;
+			};
+			self.current = next;
+		}
+		self.current
+	}
+}
+
+/// Text encoding of binary data for [TextOutput] and [TextInput].
+#[cfg(feature = "text")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TextEncoding {
+	/// Base64 with the standard alphabet and padding.
+	Base64,
+	/// Hexadecimal, two digits per byte. Lower case is written, both cases are read.
+	Hex,
+}
+
+#[cfg(feature = "text")]
+impl TextEncoding {
+	/// Number of characters needed to encode the given number of bytes.
+	#[must_use]
+	pub const fn encoded_len(self, len: usize) -> usize {
+		match self {
+			Self::Base64 => len.div_ceil(3).saturating_mul(4),
+			Self::Hex => len.saturating_mul(2),
+		}
+	}
+}
+
+/// [Output] that encodes the written bytes as text on the fly, so that the data can be embedded in
+/// text formats without an intermediate byte buffer. Call [finish](Self::finish) to get the text.
+#[cfg(feature = "text")]
+#[derive(Debug)]
+pub struct TextOutput {
+	/// The text encoding.
+	encoding: TextEncoding,
+	/// The encoded text so far.
+	text: String,
+	/// Bytes of the incomplete base64 group.
+	group: [u8; 3],
+	/// Number of bytes in the incomplete base64 group.
+	len: usize,
+}
+
+#[cfg(feature = "text")]
+impl TextOutput {
+	/// Create a new, empty output with the given encoding.
+	#[must_use]
+	pub const fn new(encoding: TextEncoding) -> Self {
+		Self { encoding, text: String::new(), group: [0; 3], len: 0 }
+	}
+
+	/// Create a new output with the given encoding and capacity for the text of `len` bytes.
+	#[must_use]
+	pub fn with_capacity(encoding: TextEncoding, len: usize) -> Self {
+		Self { text: String::with_capacity(encoding.encoded_len(len)), ..Self::new(encoding) }
+	}
+
+	/// Encode the remaining bytes, including base64 padding, and return the text.
+	#[must_use]
+	pub fn finish(mut self) -> String {
+		push_base64(&mut self.text, self.group.get(.. self.len).unwrap_or_default());
+		self.text
+	}
+}
+
+/// [Input] that decodes text on the fly. The data cannot be borrowed from the text, so strings
+/// and bytes are copied to the deserializer's buffer. Invalid characters, padding or lengths
+/// return [Error::Encoding]. Whitespace is not skipped.
+#[cfg(feature = "text")]
+#[derive(Debug)]
+pub struct TextInput<'a> {
+	/// The text encoding.
+	encoding: TextEncoding,
+	/// The remaining text.
+	text: &'a [u8],
+	/// The decoded bytes of the current group.
+	group: [u8; 3],
+	/// Position of the next byte in the group.
+	pos: usize,
+	/// Number of decoded bytes in the group.
+	filled: usize,
+}
+
+#[cfg(feature = "text")]
+impl<'a> TextInput<'a> {
+	/// Create a new input decoding the given text.
+	#[must_use]
+	pub const fn new(encoding: TextEncoding, text: &'a str) -> Self {
+		Self { encoding, text: text.as_bytes(), group: [0; 3], pos: 0, filled: 0 }
+	}
+
+	/// Return the decoded bytes of the current group, decoding the next group if it is exhausted.
+	/// Only returns an empty slice at the end of the text.
+	#[inline]
+	fn decoded(&mut self) -> Result<&[u8]> {
+		if self.pos >= self.filled && !self.text.is_empty() {
+			match self.encoding {
+				TextEncoding::Base64 => self.decode_base64()?,
+				TextEncoding::Hex => self.decode_hex()?,
+			}
+			self.pos = 0;
+		}
+		Ok(self.group.get(self.pos .. self.filled).unwrap_or_default())
+	}
+
+	/// Decode the next group of 4 base64 characters.
+	fn decode_base64(&mut self) -> Result<()> {
+		let (chars, remaining) = self
+			.text
+			.split_at_checked(4)
+			.ok_or_else(|| Error::Encoding("base64 length is not a multiple of 4"))?;
+		let padding = chars.iter().rev().take_while(|char| **char == b'=').count();
+		if padding > 2 || (padding > 0 && !remaining.is_empty()) {
+			return Err(Error::Encoding("invalid base64 padding"));
+		}
+
+		let mut bits = 0_u32;
+		for char in chars.get(.. 4 - padding).unwrap_or_default() {
+			let value =
+				base64_value(*char).ok_or_else(|| Error::Encoding("invalid base64 character"))?;
+			bits = (bits << 6) | u32::from(value);
+		}
+		let [_, a, b, c] = (bits << (6 * padding)).to_be_bytes();
+		self.group = [a, b, c];
+		self.filled = 3 - padding;
+		// Padded groups must not have bits set beyond the data, so that the encoding is unique.
+		if self.group.get(self.filled ..).unwrap_or_default().iter().any(|byte| *byte != 0) {
+			return Err(Error::Encoding("invalid base64 padding"));
+		}
+		self.text = remaining;
+		Ok(())
+	}
+
+	/// Decode the next pair of hexadecimal digits.
+	fn decode_hex(&mut self) -> Result<()> {
+		let Some(([high, low], remaining)) = self.text.split_first_chunk() else {
+			return Err(Error::Encoding("odd number of hex digits"));
+		};
+		let (Some(high), Some(low)) = (hex_value(*high), hex_value(*low)) else {
+			return Err(Error::Encoding("invalid hex digit"));
+		};
+		self.group = [(high << 4) | low, 0, 0];
+		self.filled = 1;
+		self.text = remaining;
+		Ok(())
+	}
+}
+
+/// The standard base64 alphabet.
+#[cfg(feature = "alloc")]
+const BASE64_ALPHABET: &[u8; 64] =
+	b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Lower case hexadecimal digits.
+#[cfg(feature = "text")]
+const HEX_DIGITS: &[u8; 16] = b"0123456789abcdef";
+
+/// Append the base64 encoding of up to 3 bytes to the text, padded to 4 characters. Nothing is
+/// appended for no bytes.
+#[cfg(feature = "alloc")]
+pub(crate) fn push_base64(text: &mut String, bytes: &[u8]) {
+	if bytes.is_empty() {
+		return;
+	}
+
+	let mut group = [0; 3];
+	for (byte, value) in group.iter_mut().zip(bytes) {
+		*byte = *value;
+	}
+	let [a, b, c] = group;
+	let bits = u32::from_be_bytes([0, a, b, c]);
+	for i in 0 .. 4 {
+		if i <= bytes.len() {
+			let index = (bits >> (18 - 6 * i)) & 0x3F;
+			text.push(char::from(BASE64_ALPHABET.get(index as usize).copied().unwrap_or(b'=')));
+		} else {
+			text.push('=');
+		}
+	}
+}
+
+/// Decode a character of the standard base64 alphabet.
+#[cfg(feature = "text")]
+const fn base64_value(char: u8) -> Option<u8> {
+	match char {
+		b'A' ..= b'Z' => Some(char - b'A'),
+		b'a' ..= b'z' => Some(char - b'a' + 26),
+		b'0' ..= b'9' => Some(char - b'0' + 52),
+		b'+' => Some(62),
+		b'/' => Some(63),
+		_ => None,
+	}
+}
+
+/// Decode a hexadecimal digit of either case.
+#[cfg(feature = "text")]
+const fn hex_value(digit: u8) -> Option<u8> {
+	match digit {
+		b'0' ..= b'9' => Some(digit - b'0'),
+		b'a' ..= b'f' => Some(digit - b'a' + 10),
+		b'A' ..= b'F' => Some(digit - b'A' + 10),
+		_ => None,
+	}
+}
+
+/// Wrapper for generic writer types as [Output]. Small writes are collected in an internal buffer,
+/// which is written to the writer when full, on [flush](Output::flush) and on drop.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub struct IoWriter<W>
+where
+	W: Write,
+{
+	/// The inner writer.
+	writer: W,
+	/// The buffer of data not yet written to the writer.
+	buffer: Vec<u8>,
+	/// The maximum number of bytes to buffer.
+	capacity: usize,
+	/// The number of bytes written to the writer so far.
+	written: usize,
+}
+
+#[cfg(feature = "std")]
+impl<W> IoWriter<W>
+where
+	W: Write,
+{
+	/// Create a new writer from the given writer, buffering up to `capacity` bytes. A capacity of
+	/// 0 disables buffering.
+	#[must_use]
+	pub fn with_capacity(writer: W, capacity: usize) -> Self {
+		Self { writer, buffer: Vec::with_capacity(capacity), capacity, written: 0 }
+	}
+
+	/// Return the number of bytes written to the writer so far, without the buffered data.
+	#[must_use]
+	pub const fn bytes_written(&self) -> usize {
+		self.written
+	}
+
+	/// Drop the buffered data without writing it to the writer, e.g. after serializing failed.
+	pub(crate) fn discard_buffer(&mut self) {
+		self.buffer.clear();
+	}
+
+	/// Write the buffered data to the writer. On error, only the data not yet written stays in the
+	/// buffer, so it is not written twice by a later flush or on drop.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn flush_buffer(&mut self) -> Result<()> {
+		let mut flushed = 0;
+		let result = loop {
+			let Some(remaining) = self.buffer.get(flushed ..).filter(|rest| !rest.is_empty())
+			else {
+				break Ok(());
+			};
+			match self.writer.write(remaining) {
+				Ok(0) => break Err(::std::io::Error::from(::std::io::ErrorKind::WriteZero).into()),
+				Ok(written) => flushed += written,
+				Err(err) if err.kind() == ::std::io::ErrorKind::Interrupted => {}
+				Err(err) => break Err(err.into()),
+			}
+		};
+		self.written += flushed;
+		self.buffer.drain(.. flushed);
+		result
+	}
+
+	/// Write the buffered data followed by the given bytes to the writer, using vectored writes so
+	/// that the bytes are not copied to the buffer.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_with_buffer(&mut self, bytes: &[u8]) -> Result<()> {
+		let mut sent = 0;
+		let mut slices = [IoSlice::new(&self.buffer), IoSlice::new(bytes)];
+		let mut slices = slices.as_mut_slice();
+		// Removes empty slices.
+		IoSlice::advance_slices(&mut slices, 0);
+		let result = loop {
+			if slices.is_empty() {
+				break Ok(());
+			}
+			match self.writer.write_vectored(slices) {
+				Ok(0) => break Err(::std::io::Error::from(::std::io::ErrorKind::WriteZero).into()),
+				Ok(written) => {
+					sent += written;
+					IoSlice::advance_slices(&mut slices, written);
+				}
+				Err(err) if err.kind() == ::std::io::ErrorKind::Interrupted => {}
+				Err(err) => break Err(err.into()),
+			}
+		};
+		self.written += sent;
+		// Keep only the buffered data that was not written yet.
+		self.buffer.drain(.. sent.min(self.buffer.len()));
+		result
+	}
+}
+
+#[cfg(feature = "std")]
+impl<W> Drop for IoWriter<W>
+where
+	W: Write,
+{
+	fn drop(&mut self) {
+		// Errors cannot be reported here, flush explicitly to get them.
+		_ = self.flush_buffer();
+	}
+}
+
+/// Wrapper for [`embedded_io::Read`]ers as [Input]. Reads are passed to the reader directly, so
+/// it is best to use buffered readers. One byte is kept for peeking.
+#[cfg(feature = "embedded-io")]
+#[derive(Debug)]
+pub struct EmbeddedReader<R> {
+	/// The inner reader.
+	reader: R,
+	/// Byte that was peeked at, but not consumed yet.
+	peeked: Option<u8>,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<R> EmbeddedReader<R>
+where
+	R: ::embedded_io::Read,
+{
+	/// Create a new input from the given reader.
+	#[must_use]
+	pub const fn new(reader: R) -> Self {
+		Self { reader, peeked: None }
+	}
+}
+
+/// Wrapper for [`embedded_io::Write`]rs as [Output]. Writes are passed to the writer directly, so
+/// it is best to use buffered writers.
+#[cfg(feature = "embedded-io")]
+#[derive(Debug)]
+pub struct EmbeddedWriter<W> {
+	/// The inner writer.
+	writer: W,
+}
+
+#[cfg(feature = "embedded-io")]
+impl<W> EmbeddedWriter<W>
+where
+	W: ::embedded_io::Write,
+{
+	/// Create a new output from the given writer.
+	#[must_use]
+	pub const fn new(writer: W) -> Self {
+		Self { writer }
+	}
+}
+
+/// Convert an [`embedded_io`] error to an [Error] of its kind.
+#[cfg(feature = "embedded-io")]
+fn embedded_error<E>(err: E) -> Error
+where
+	E: ::embedded_io::Error,
+{
+	Error::EmbeddedIo(err.kind())
+}
+
+/// Convert an [`embedded_io`] exact read error to an [Error], mapping the end of the reader to
+/// [Error::UnexpectedEnd].
+#[cfg(feature = "embedded-io")]
+fn embedded_read_error<E>(err: ::embedded_io::ReadExactError<E>) -> Error
+where
+	E: ::embedded_io::Error,
+{
+	match err {
+		::embedded_io::ReadExactError::UnexpectedEof => Error::UnexpectedEnd,
+		::embedded_io::ReadExactError::Other(err) => embedded_error(err),
+	}
+}
+
+/// **alloc**: [Input] wrapper that can record the bytes being read, see
+/// [Input::start_recording]. While not recording, reads are passed through.
+///
+/// This is needed to reject duplicate map keys with a [Deserializer](crate::Deserializer) that is
+/// constructed manually, if its input cannot lend its data like slices do, e.g. a reader. The
+/// free functions wrap their input when needed.
+///
+/// ```rust
+/// use serde_brief::{io::RecordingInput, value::Value, Deserializer, Error};
+///
+/// let entry = |value: u8| (Value::from("key"), Value::from(value));
+/// let bytes = serde_brief::to_vec(&Value::from_iter([entry(1), entry(2)])).unwrap();
+/// let input = RecordingInput::new(serde_brief::io::IoReader::new(bytes.as_slice()));
+/// let mut de = Deserializer::new(input).with_buffer(Vec::new()).reject_duplicate_map_keys(true);
+/// let result: Result<std::collections::HashMap<String, u8>, _> =
+/// 	serde::Deserialize::deserialize(&mut de);
+/// assert!(matches!(result, Err(Error::DuplicateMapKey)));
+/// ```
+#[cfg(feature = "alloc")]
+#[derive(Debug, Clone)]
+pub struct RecordingInput<I> {
+	/// The inner input.
+	inner: I,
+	/// The bytes recorded so far.
+	recorded: ::alloc::vec::Vec<u8>,
+	/// Start indices of the active (nested) recordings.
+	starts: ::alloc::vec::Vec<usize>,
+}
+
+#[cfg(feature = "alloc")]
+impl<I> RecordingInput<I> {
+	/// Create a new recording input, not recording yet.
+	#[must_use]
+	pub const fn new(inner: I) -> Self {
+		Self { inner, recorded: ::alloc::vec::Vec::new(), starts: ::alloc::vec::Vec::new() }
+	}
+
+	/// Consume the recording input and return the inner input.
+	#[must_use]
+	pub fn into_inner(self) -> I {
+		self.inner
 	}
 
+	/// Record the given bytes, if recording.
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
-	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
-		// This somehow makes more optimized code gen.
-		if self.is_empty() {
-			return Err(Error::BufferTooSmall);
+	fn record(&mut self, bytes: &[u8]) {
+		if !self.starts.is_empty() {
+			self.recorded.extend_from_slice(bytes);
 		}
+	}
 
-		let (write, remaining) = ::core::mem::take(self)
-			.split_at_mut_checked(bytes.len())
-			.ok_or_else(|| Error::BufferTooSmall)?;
-		write.copy_from_slice(bytes);
-		*self = remaining;
-
+	/// Skip bytes while recording them, reading them in chunks.
+	#[cold]
+	fn skip_recorded<'de>(&mut self, mut len: usize) -> Result<()>
+	where
+		I: Input<'de>,
+	{
+		let mut chunk = [0; 64];
+		while len > 0 {
+			let read = len.min(chunk.len());
+			let chunk = chunk.get_mut(.. read).unwrap_or_default();
+			self.read_exact(chunk)?;
+			len -= read;
+		}
 		Ok(())
 	}
 }
 
 #[cfg(feature = "alloc")]
-impl Output for ::alloc::vec::Vec<u8> {
+impl<'de, I> Input<'de> for RecordingInput<I>
+where
+	I: Input<'de>,
+{
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
-	fn write_byte(&mut self, byte: u8) -> Result<()> {
-		self.push(byte);
-		Ok(())
+	fn peek_byte(&mut self) -> Result<u8> {
+		self.inner.peek_byte()
 	}
 
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
-	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
-		self.extend_from_slice(bytes);
+	fn read_byte(&mut self) -> Result<u8> {
+		let byte = self.inner.read_byte()?;
+		self.record(&[byte]);
+		Ok(byte)
+	}
+
+	#[inline]
+	fn read_exact(&mut self, buffer: &mut [u8]) -> Result<()> {
+		self.inner.read_exact(buffer)?;
+		self.record(buffer);
 		Ok(())
 	}
-}
 
-#[cfg(feature = "heapless")]
-impl<const N: usize> Output for ::heapless::Vec<u8, N> {
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
-	fn write_byte(&mut self, byte: u8) -> Result<()> {
-		self.push(byte).map_err(|_| Error::BufferTooSmall)
+	fn read_bytes<B>(&mut self, len: usize, buffer: Option<&mut B>) -> Result<Option<&'de [u8]>>
+	where
+		B: Buffer,
+	{
+		if self.starts.is_empty() {
+			return self.inner.read_bytes(len, buffer);
+		}
+
+		let mut buffer = buffer;
+		let data = self.inner.read_bytes(len, buffer.as_deref_mut())?;
+		if let Some(data) = data {
+			self.record(data);
+		} else if let Some(buffer) = buffer {
+			// The data was appended to the buffer.
+			let copied = buffer.as_slice();
+			self.record(copied.get(copied.len().saturating_sub(len) ..).unwrap_or_default());
+		}
+		Ok(data)
 	}
 
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
-	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
-		self.extend_from_slice(bytes).map_err(|_| Error::BufferTooSmall)
+	fn read_bytes_transient<'s, B>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut B>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		B: Buffer,
+	{
+		let data = self.inner.read_bytes_transient(len, buffer)?;
+		if !self.starts.is_empty() {
+			let (ReadBytes::Borrowed(bytes) | ReadBytes::Transient(bytes)) = data;
+			self.recorded.extend_from_slice(bytes);
+		}
+		Ok(data)
 	}
-}
 
-// Note: this is also implementing for `std::vec::Vec`.
-#[cfg(feature = "std")]
-impl<W> Output for IoWriter<W>
-where
-	W: Write,
-{
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(byte)))]
-	fn write_byte(&mut self, byte: u8) -> Result<()> {
-		self.writer.write_all(&[byte])?;
-		Ok(())
+	fn skip_bytes(&mut self, len: usize) -> Result<()> {
+		if self.starts.is_empty() {
+			return self.inner.skip_bytes(len);
+		}
+		self.skip_recorded(len)
 	}
 
 	#[inline]
-	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
-	fn write_all(&mut self, bytes: &[u8]) -> Result<()> {
-		self.writer.write_all(bytes)?;
-		Ok(())
+	fn check_excess(&mut self) -> Result<()> {
+		self.inner.check_excess()
 	}
-}
 
+	#[inline]
+	fn remaining_borrowed(&self) -> Option<&'de [u8]> {
+		self.inner.remaining_borrowed()
+	}
 
-/// Wrapper for generic reader types as [Output].
-#[allow(dead_code, reason = "Different feature sets")]
-#[derive(Debug)]
-pub struct IoReader<R> {
-	/// The inner reader.
-	reader: R,
-	/// Next peeked byte if available.
-	next_byte: Option<u8>,
-}
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		self.inner.can_borrow()
+	}
 
-#[allow(dead_code, reason = "Different feature sets")]
-impl<R> IoReader<R> {
-	/// Create a new reader from the given reader.
-	#[must_use]
-	pub const fn new(reader: R) -> Self {
-		Self { reader, next_byte: None }
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		self.inner.peek_chunk(n)
 	}
-}
 
-/// Wrapper for generic writer types as [Output].
-#[allow(dead_code, reason = "Different feature sets")]
-#[derive(Debug)]
-pub struct IoWriter<W> {
-	/// The inner writer.
-	writer: W,
-}
+	#[inline]
+	fn start_recording(&mut self) -> bool {
+		self.starts.push(self.recorded.len());
+		true
+	}
 
-#[allow(dead_code, reason = "Different feature sets")]
-impl<W> IoWriter<W> {
-	/// Create a new writer from the given writer.
-	#[must_use]
-	pub const fn new(writer: W) -> Self {
-		Self { writer }
+	#[inline]
+	fn finish_recording(&mut self) -> ::alloc::vec::Vec<u8> {
+		let start = self.starts.pop().unwrap_or_default();
+		let recorded = self.recorded.get(start ..).unwrap_or_default().to_vec();
+		if self.starts.is_empty() {
+			self.recorded.clear();
+		}
+		recorded
 	}
 }
 
-/// [Input]/[Output] wrapper that limits the number of bytes being read/written.
+/// [Input]/[Output] wrapper that limits the number of bytes being read/written. Exceeding the limit
+/// returns [Error::LimitReached]. This is how `max_size` of the [Config](crate::Config) is applied,
+/// so wrap the input or output in it when constructing a [Serializer](crate::Serializer) or
+/// [Deserializer](crate::Deserializer) manually.
+///
+/// For inputs, the limit is the maximum number of bytes consumed from the wrapped [Input], i.e.
+/// the position in the data. Peeking does not count, neither does read-ahead of inputs buffering
+/// internally, e.g. [IoReader], so slices and readers of the same data reach the limit at the same
+/// point. Failed reads do not count either.
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_brief::{Error, Serializer, SizeLimit};
+///
+/// let mut buffer = [0; 64];
+/// let mut ser = Serializer::new(SizeLimit::new(buffer.as_mut_slice(), 4));
+/// assert!(matches!("too long".serialize(&mut ser), Err(Error::LimitReached)));
+/// ```
+#[derive(Debug)]
 pub struct SizeLimit<IO> {
 	/// The inner input/output.
 	inner: IO,
@@ -321,6 +1828,12 @@ impl<IO> SizeLimit<IO> {
 	pub fn into_inner(self) -> IO {
 		self.inner
 	}
+
+	/// The remaining number of bytes that can be read/written.
+	#[must_use]
+	pub const fn remaining(&self) -> usize {
+		self.limit
+	}
 }
 
 impl<'de, I> Input<'de> for SizeLimit<I>
@@ -343,9 +1856,10 @@ where
 		if self.limit == 0 {
 			return Err(Error::LimitReached);
 		}
-		self.limit -= 1;
 
-		self.inner.read_byte()
+		let byte = self.inner.read_byte()?;
+		self.limit -= 1;
+		Ok(byte)
 	}
 
 	#[inline]
@@ -354,9 +1868,10 @@ where
 		if self.limit < buffer.len() {
 			return Err(Error::LimitReached);
 		}
-		self.limit -= buffer.len();
 
-		self.inner.read_exact(buffer)
+		self.inner.read_exact(buffer)?;
+		self.limit -= buffer.len();
+		Ok(())
 	}
 
 	#[inline]
@@ -368,9 +1883,29 @@ where
 		if self.limit < len {
 			return Err(Error::LimitReached);
 		}
+
+		let data = self.inner.read_bytes(len, buffer)?;
 		self.limit -= len;
+		Ok(data)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(len)))]
+	fn read_bytes_transient<'s, B>(
+		&'s mut self,
+		len: usize,
+		buffer: Option<&'s mut B>,
+	) -> Result<ReadBytes<'de, 's>>
+	where
+		B: Buffer,
+	{
+		if self.limit < len {
+			return Err(Error::LimitReached);
+		}
 
-		self.inner.read_bytes(len, buffer)
+		let data = self.inner.read_bytes_transient(len, buffer)?;
+		self.limit -= len;
+		Ok(data)
 	}
 
 	#[inline]
@@ -379,9 +1914,50 @@ where
 		if self.limit < len {
 			return Err(Error::LimitReached);
 		}
+
+		self.inner.skip_bytes(len)?;
 		self.limit -= len;
+		Ok(())
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn check_excess(&mut self) -> Result<()> {
+		// Data beyond the limit was never meant to be read.
+		if self.limit == 0 {
+			return Ok(());
+		}
 
-		self.inner.skip_bytes(len)
+		self.inner.check_excess()
+	}
+
+	#[inline]
+	fn remaining_borrowed(&self) -> Option<&'de [u8]> {
+		let remaining = self.inner.remaining_borrowed()?;
+		Some(remaining.get(.. self.limit).unwrap_or(remaining))
+	}
+
+	#[inline]
+	fn can_borrow(&self) -> bool {
+		self.inner.can_borrow()
+	}
+
+	#[inline]
+	fn peek_chunk(&mut self, n: usize) -> Option<&[u8]> {
+		// Bytes beyond the limit cannot be read, so they are not shown either.
+		self.inner.peek_chunk(n.min(self.limit))
+	}
+
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn start_recording(&mut self) -> bool {
+		self.inner.start_recording()
+	}
+
+	#[cfg(feature = "alloc")]
+	#[inline]
+	fn finish_recording(&mut self) -> ::alloc::vec::Vec<u8> {
+		self.inner.finish_recording()
 	}
 }
 
@@ -410,6 +1986,23 @@ where
 
 		self.inner.write_all(bytes)
 	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn write_borrowed(&mut self, bytes: &[u8]) -> Result<()> {
+		if self.limit < bytes.len() {
+			return Err(Error::LimitReached);
+		}
+		self.limit -= bytes.len();
+
+		self.inner.write_borrowed(bytes)
+	}
+
+	#[inline]
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	fn flush(&mut self) -> Result<()> {
+		self.inner.flush()
+	}
 }
 
 #[cfg(test)]
@@ -520,6 +2113,21 @@ mod tests {
 		basic_input_works(IoReader::new(BASIC_INPUT_DATA));
 		read_bytes_works(IoReader::new(READ_BYTES_INPUT_DATA), Some(Vec::new()));
 
+		// Small internal buffers and chunks across buffer boundaries.
+		input_does_not_panic(IoReader::with_capacity(PANIC_INPUT_DATA, 0));
+		basic_input_works(IoReader::with_capacity(BASIC_INPUT_DATA, 1));
+		basic_input_works(IoReader::with_capacity(BASIC_INPUT_DATA, 3));
+		read_bytes_works(IoReader::with_capacity(READ_BYTES_INPUT_DATA, 4), Some(Vec::new()));
+		let data: Vec<u8> = (0 ..= 255).collect();
+		let mut input = IoReader::with_capacity(data.as_slice(), 7);
+		let mut target = [0; 20];
+		input.read_exact(&mut target).unwrap();
+		assert_eq!(target.as_slice(), data.get(.. 20).unwrap());
+		input.skip_bytes(100).unwrap();
+		assert_eq!(input.read_byte().unwrap(), 120);
+		input.skip_bytes(3).unwrap();
+		assert_eq!(input.peek_byte().unwrap(), 124);
+
 		// Buffer behavior from IO reader.
 		let mut input = IoReader::new(READ_BYTES_INPUT_DATA);
 		let mut buffer = Some(Vec::new());
@@ -528,6 +2136,362 @@ mod tests {
 		assert_eq!(buffer.unwrap().len(), 15);
 	}
 
+	#[cfg(feature = "std")]
+	#[test]
+	fn buf_read_input_behaves() {
+		use ::std::io::BufReader;
+
+		input_does_not_panic(BufReadInput::new(PANIC_INPUT_DATA));
+		basic_input_works(BufReadInput::new(BASIC_INPUT_DATA));
+		read_bytes_works(BufReadInput::new(READ_BYTES_INPUT_DATA), Some(Vec::new()));
+
+		// Small reader buffers and chunks across buffer boundaries.
+		basic_input_works(BufReadInput::new(BufReader::with_capacity(1, BASIC_INPUT_DATA)));
+		basic_input_works(BufReadInput::new(BufReader::with_capacity(3, BASIC_INPUT_DATA)));
+		read_bytes_works(
+			BufReadInput::new(BufReader::with_capacity(4, READ_BYTES_INPUT_DATA)),
+			Some(Vec::new()),
+		);
+
+		// Transient reads within the reader's buffer do not touch the buffer.
+		let data: Vec<u8> = (0 ..= 255).collect();
+		let mut input = BufReadInput::new(BufReader::with_capacity(16, data.as_slice()));
+		let mut buffer = Vec::new();
+		let ReadBytes::Transient(bytes) =
+			input.read_bytes_transient(10, Some(&mut buffer)).unwrap()
+		else {
+			panic!("Expected transient data");
+		};
+		assert_eq!(bytes, data.get(.. 10).unwrap());
+		assert!(buffer.is_empty());
+		assert_eq!(input.read_byte().unwrap(), 10);
+
+		// Data across the reader's buffer boundary is copied.
+		let ReadBytes::Transient(bytes) =
+			input.read_bytes_transient(10, Some(&mut buffer)).unwrap()
+		else {
+			panic!("Expected transient data");
+		};
+		assert_eq!(bytes, data.get(11 .. 21).unwrap());
+		assert_eq!(buffer.len(), 10);
+		assert_eq!(input.read_byte().unwrap(), 21);
+		input.skip_bytes(100).unwrap();
+		assert_eq!(input.peek_byte().unwrap(), 122);
+		assert!(input.read_bytes_transient(200, None::<&mut Vec<u8>>).is_err());
+	}
+
+	#[test]
+	fn chunked_input_behaves() {
+		input_does_not_panic(ChunkedInput::new([PANIC_INPUT_DATA]));
+		input_does_not_panic(ChunkedInput::new(PANIC_INPUT_DATA.chunks(1)));
+		basic_input_works(ChunkedInput::new([BASIC_INPUT_DATA]));
+		basic_input_works(ChunkedInput::new(BASIC_INPUT_DATA.chunks(1)));
+		basic_input_works(ChunkedInput::new(BASIC_INPUT_DATA.chunks(3)));
+		read_bytes_works(ChunkedInput::new([READ_BYTES_INPUT_DATA]), None::<()>);
+		#[cfg(feature = "heapless")]
+		read_bytes_works(
+			ChunkedInput::new(READ_BYTES_INPUT_DATA.chunks(4)),
+			Some(::heapless::Vec::<u8, 16>::new()),
+		);
+
+		// Empty chunks are skipped and reads within a chunk are borrowed.
+		let chunks: [&[u8]; 5] = [&[], &[0, 1, 2, 3], &[], &[4, 5], &[6, 7, 8]];
+		let mut input = ChunkedInput::new(chunks.iter().copied());
+		let mut buffer = crate::buffer::InlineBuffer::new();
+		assert_eq!(input.read_byte().unwrap(), 0);
+		let borrowed = input.read_bytes(3, Some(&mut buffer)).unwrap();
+		assert_eq!(borrowed, Some([1, 2, 3].as_slice()));
+		assert!(buffer.as_slice().is_empty());
+
+		// Reads across chunks are copied to the buffer.
+		let borrowed = input.read_bytes(4, Some(&mut buffer)).unwrap();
+		assert_eq!(borrowed, None);
+		assert_eq!(buffer.as_slice(), [4, 5, 6, 7]);
+		assert!(input.read_bytes::<()>(1, None).unwrap().is_some());
+		assert!(input.read_bytes::<()>(1, None).is_err());
+
+		let chunks: [&[u8]; 3] = [&[0, 1], &[2, 3], &[4, 5]];
+		let mut input = ChunkedInput::new(chunks.iter().copied());
+		assert!(matches!(input.read_bytes::<()>(3, None), Err(Error::BufferTooSmall)));
+	}
+
+	#[test]
+	fn size_limit_input_behaves() {
+		input_does_not_panic(SizeLimit::new(PANIC_INPUT_DATA, 5));
+		basic_input_works(SizeLimit::new(BASIC_INPUT_DATA, BASIC_INPUT_DATA.len()));
+		read_bytes_works(SizeLimit::new(READ_BYTES_INPUT_DATA, 20), None::<()>);
+
+		// Peeking is free, failed reads do not count.
+		let mut input = SizeLimit::new(BASIC_INPUT_DATA, 4);
+		assert_eq!(input.peek_byte().unwrap(), 0);
+		assert_eq!(input.remaining(), 4);
+		assert_eq!(input.read_byte().unwrap(), 0);
+		assert_eq!(input.remaining(), 3);
+		assert!(matches!(input.read_exact(&mut [0; 4]), Err(Error::LimitReached)));
+		assert!(matches!(input.skip_bytes(4), Err(Error::LimitReached)));
+		assert_eq!(input.remaining(), 3);
+		assert_eq!(input.read_bytes::<()>(3, None).unwrap(), Some([1, 2, 3].as_slice()));
+		assert_eq!(input.remaining(), 0);
+		assert!(matches!(input.peek_byte(), Err(Error::LimitReached)));
+		input.check_excess().unwrap();
+
+		let mut input = SizeLimit::new([1, 2].as_slice(), 4);
+		assert!(matches!(input.read_exact(&mut [0; 3]), Err(Error::UnexpectedEnd)));
+		assert!(matches!(input.read_bytes::<()>(3, None), Err(Error::UnexpectedEnd)));
+		assert_eq!(input.remaining(), 4);
+		input.skip_bytes(2).unwrap();
+		assert!(matches!(input.read_byte(), Err(Error::UnexpectedEnd)));
+		assert_eq!(input.remaining(), 2);
+	}
+
+	#[cfg(feature = "alloc")]
+	#[test]
+	fn recording_input_behaves() {
+		input_does_not_panic(RecordingInput::new(PANIC_INPUT_DATA));
+		basic_input_works(RecordingInput::new(BASIC_INPUT_DATA));
+		read_bytes_works(RecordingInput::new(READ_BYTES_INPUT_DATA), None::<()>);
+
+		let data: &[u8] = &[0, 1, 2, 3, 4, 5, 6, 7, 8, 9];
+		let mut input = RecordingInput::new(ChunkedInput::new(data.chunks(3)));
+		let mut buffer = ::alloc::vec::Vec::new();
+		assert_eq!(input.read_byte().unwrap(), 0);
+		assert!(input.start_recording());
+		assert_eq!(input.peek_byte().unwrap(), 1);
+		assert_eq!(input.read_byte().unwrap(), 1);
+		assert!(input.start_recording());
+		assert_eq!(input.read_bytes(3, Some(&mut buffer)).unwrap(), None);
+		assert_eq!(input.finish_recording(), [2, 3, 4]);
+		input.skip_bytes(2).unwrap();
+		assert_eq!(input.finish_recording(), [1, 2, 3, 4, 5, 6]);
+		assert_eq!(input.read_byte().unwrap(), 7);
+		assert!(input.start_recording());
+		assert!(input.finish_recording().is_empty());
+
+		// Wrappers pass recording on, other inputs do not record.
+		let mut limited = SizeLimit::new(RecordingInput::new(data), 4);
+		assert!(limited.start_recording());
+		limited.skip_bytes(3).unwrap();
+		assert_eq!(limited.finish_recording(), [0, 1, 2]);
+		let mut slice = data;
+		assert!(!slice.start_recording());
+	}
+
+	#[cfg(feature = "embedded-io")]
+	#[test]
+	fn embedded_reader_input_behaves() {
+		input_does_not_panic(EmbeddedReader::new(PANIC_INPUT_DATA));
+		basic_input_works(EmbeddedReader::new(BASIC_INPUT_DATA));
+		read_bytes_works(
+			EmbeddedReader::new(READ_BYTES_INPUT_DATA),
+			Some(crate::buffer::InlineBuffer::new()),
+		);
+
+		// Peeked bytes are part of the following reads.
+		let mut input = EmbeddedReader::new(BASIC_INPUT_DATA);
+		assert_eq!(input.peek_byte().unwrap(), 0);
+		let mut target = [0; 3];
+		input.read_exact(&mut target).unwrap();
+		assert_eq!(target, [0, 1, 2]);
+		assert_eq!(input.peek_byte().unwrap(), 3);
+		input.skip_bytes(5).unwrap();
+		assert_eq!(input.read_byte().unwrap(), 8);
+		assert!(matches!(input.skip_bytes(2), Err(Error::UnexpectedEnd)));
+	}
+
+	/// Operation on an input, to compare the behavior of inputs.
+	#[cfg(feature = "std")]
+	#[derive(Debug, Clone, Copy)]
+	enum Operation {
+		/// [Input::peek_byte].
+		Peek,
+		/// [Input::read_byte].
+		ReadByte,
+		/// [Input::read_exact] with the given length.
+		ReadExact(usize),
+		/// [Input::read_bytes] with the given length.
+		ReadBytes(usize),
+		/// [Input::read_bytes_transient] with the given length.
+		ReadBytesTransient(usize),
+		/// [Input::skip_bytes] with the given length.
+		Skip(usize),
+		/// [Input::check_excess].
+		CheckExcess,
+	}
+
+	/// Apply the operation and return the data it read or the kind of the error.
+	#[cfg(feature = "std")]
+	fn apply<'de, I: Input<'de>>(
+		input: &mut I,
+		operation: Operation,
+	) -> Result<Vec<u8>, crate::ErrorKind> {
+		let mut buffer = Vec::new();
+		match operation {
+			Operation::Peek => input.peek_byte().map(|byte| vec![byte]),
+			Operation::ReadByte => input.read_byte().map(|byte| vec![byte]),
+			Operation::ReadExact(len) => {
+				let mut target = vec![0; len];
+				input.read_exact(&mut target).map(|()| target)
+			}
+			Operation::ReadBytes(len) => input
+				.read_bytes(len, Some(&mut buffer))
+				.map(|borrowed| borrowed.map_or_else(|| buffer.clone(), <[u8]>::to_vec)),
+			Operation::ReadBytesTransient(len) => {
+				input.read_bytes_transient(len, Some(&mut buffer)).map(|data| {
+					let (ReadBytes::Borrowed(data) | ReadBytes::Transient(data)) = data;
+					data.to_vec()
+				})
+			}
+			Operation::Skip(len) => input.skip_bytes(len).map(|()| Vec::new()),
+			Operation::CheckExcess => input.check_excess().map(|()| Vec::new()),
+		}
+		.map_err(|err| err.kind())
+	}
+
+	/// Apply the operations to the input and to a slice of the same data and assert the same
+	/// results. Stops at the first error, after which inputs may have consumed different amounts.
+	#[cfg(feature = "std")]
+	fn assert_behaves_like_slice<'de, I: Input<'de>>(
+		data: &[u8],
+		mut input: I,
+		operations: &[Operation],
+	) {
+		let mut slice = data;
+		for (i, operation) in operations.iter().enumerate() {
+			let expected = apply(&mut slice, *operation);
+			let result = apply(&mut input, *operation);
+			assert_eq!(
+				result, expected,
+				"operation {i} ({operation:?}) of {operations:?} on {data:?}"
+			);
+			if expected.is_err() {
+				return;
+			}
+		}
+		// The remaining data is the same.
+		let expected = Ok(slice.to_vec());
+		assert_eq!(apply(&mut input, Operation::ReadBytes(slice.len())), expected);
+		assert_eq!(apply(&mut input, Operation::CheckExcess), Ok(Vec::new()));
+	}
+
+	/// Reader returning at most the given number of bytes per read and being interrupted before
+	/// every read, like slow network streams.
+	#[cfg(feature = "std")]
+	struct ShortReader<'a> {
+		/// The remaining data.
+		data: &'a [u8],
+		/// Maximum number of bytes per read.
+		max: usize,
+		/// Whether the next read is interrupted.
+		interrupt: bool,
+	}
+
+	#[cfg(feature = "std")]
+	impl ::std::io::Read for ShortReader<'_> {
+		fn read(&mut self, buf: &mut [u8]) -> ::std::io::Result<usize> {
+			self.interrupt = !self.interrupt;
+			if self.interrupt {
+				return Err(::std::io::ErrorKind::Interrupted.into());
+			}
+			let len = buf.len().min(self.max);
+			self.data.read(&mut buf[.. len])
+		}
+	}
+
+	/// Assert that all reader inputs behave like the slice input for the operations.
+	#[cfg(feature = "std")]
+	fn assert_readers_behave_like_slice(data: &[u8], operations: &[Operation]) {
+		use ::std::io::BufReader;
+
+		for capacity in [1, 2, 3, 8, IoReader::<&[u8]>::DEFAULT_CAPACITY] {
+			assert_behaves_like_slice(data, IoReader::with_capacity(data, capacity), operations);
+			let reader = BufReader::with_capacity(capacity, data);
+			assert_behaves_like_slice(data, BufReadInput::new(reader), operations);
+		}
+		for max in [1, 2, 5] {
+			let reader = ShortReader { data, max, interrupt: false };
+			assert_behaves_like_slice(data, IoReader::with_capacity(reader, 4), operations);
+		}
+		#[cfg(feature = "embedded-io")]
+		assert_behaves_like_slice(data, EmbeddedReader::new(data), operations);
+	}
+
+	#[cfg(feature = "std")]
+	#[test]
+	fn readers_with_peeked_byte_behave_like_slices() {
+		let data: Vec<u8> = (0 .. 12).collect();
+		for len in 0 ..= data.len() {
+			let data = data.get(.. len).unwrap();
+			// Lengths of 0, 1, N, exactly the rest after the peeked byte and one more.
+			let lengths = [0, 1, 2, 5, len.saturating_sub(1), len, len + 1];
+			for n in lengths {
+				for operation in [
+					Operation::ReadExact(n),
+					Operation::ReadBytes(n),
+					Operation::ReadBytesTransient(n),
+					Operation::Skip(n),
+				] {
+					for prefix in
+						[[].as_slice(), &[Operation::Peek], &[Operation::ReadByte, Operation::Peek]]
+					{
+						let mut operations = prefix.to_vec();
+						operations.extend([operation, Operation::Peek, Operation::ReadByte]);
+						assert_readers_behave_like_slice(data, &operations);
+					}
+				}
+			}
+			assert_readers_behave_like_slice(data, &[Operation::Peek, Operation::CheckExcess]);
+			assert_readers_behave_like_slice(
+				data,
+				&[Operation::Peek, Operation::ReadByte, Operation::CheckExcess],
+			);
+		}
+
+		// A 1-byte string after a peeked type byte at the end of the input.
+		let data = [crate::format::Type::String.into(), 1, b'a'];
+		let operations = [
+			Operation::Peek,
+			Operation::ReadByte,
+			Operation::ReadByte,
+			Operation::ReadBytes(1),
+			Operation::CheckExcess,
+		];
+		assert_readers_behave_like_slice(&data, &operations);
+		for capacity in [1, 2, 3] {
+			let mut buffer = Vec::new();
+			let mut de =
+				crate::Deserializer::new(IoReader::with_capacity(data.as_slice(), capacity))
+					.with_buffer(&mut buffer);
+			assert_eq!(<String as ::serde::Deserialize>::deserialize(&mut de).unwrap(), "a");
+			de.end().unwrap();
+		}
+	}
+
+	/// Strategy for arbitrary operations.
+	#[cfg(feature = "std")]
+	fn any_operation() -> impl ::proptest::strategy::Strategy<Value = Operation> {
+		use ::proptest::prelude::*;
+
+		prop_oneof![
+			Just(Operation::Peek),
+			Just(Operation::ReadByte),
+			(0 .. 20_usize).prop_map(Operation::ReadExact),
+			(0 .. 20_usize).prop_map(Operation::ReadBytes),
+			(0 .. 20_usize).prop_map(Operation::ReadBytesTransient),
+			(0 .. 20_usize).prop_map(Operation::Skip),
+			Just(Operation::CheckExcess),
+		]
+	}
+
+	#[cfg(feature = "std")]
+	::proptest::proptest! {
+		#[test]
+		fn readers_behave_like_slices(
+			data in ::proptest::collection::vec(0 ..= u8::MAX, 0 .. 64),
+			operations in ::proptest::collection::vec(any_operation(), 0 .. 16),
+		) {
+			assert_readers_behave_like_slice(&data, &operations);
+		}
+	}
 
 	fn output_does_not_panic<O: Output>(mut output: O) {
 		_ = output.write_byte(0);
@@ -557,6 +2521,20 @@ mod tests {
 		assert_eq!(buffer, BASIC_OUTPUT_DATA);
 	}
 
+	#[test]
+	fn slice_cursor_output_behaves() {
+		output_does_not_panic(SliceOutput::new(&mut [1, 2]));
+		let mut buffer = [0; 12];
+		let mut output = SliceOutput::new(&mut buffer);
+		basic_output_works(&mut output);
+		assert_eq!(output.bytes_written(), BASIC_OUTPUT_DATA.len());
+		assert!(matches!(output.write_all(&[1, 2, 3]), Err(Error::BufferTooSmall)));
+		assert_eq!(output.bytes_written(), BASIC_OUTPUT_DATA.len());
+		output.write_all(&[10, 11]).unwrap();
+		assert!(matches!(output.write_byte(12), Err(Error::BufferTooSmall)));
+		assert_eq!(output.into_written(), [0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11]);
+	}
+
 	#[cfg(feature = "alloc")]
 	#[test]
 	fn vec_output_behaves() {
@@ -575,12 +2553,97 @@ mod tests {
 		assert_eq!(&output, BASIC_OUTPUT_DATA);
 	}
 
+	#[cfg(feature = "arrayvec")]
+	#[test]
+	fn arrayvec_output_behaves() {
+		output_does_not_panic(::arrayvec::ArrayVec::<_, 2>::new());
+		let mut output = ::arrayvec::ArrayVec::<_, 10>::new();
+		basic_output_works(&mut output);
+		assert_eq!(output.as_slice(), BASIC_OUTPUT_DATA);
+		assert!(matches!(output.write_byte(10), Err(Error::BufferTooSmall)));
+	}
+
+	#[test]
+	fn size_counter_output_behaves() {
+		output_does_not_panic(SizeCounter::new());
+		let mut output = SizeCounter::new();
+		basic_output_works(&mut output);
+		assert_eq!(output.count(), BASIC_OUTPUT_DATA.len());
+	}
+
 	#[cfg(feature = "std")]
 	#[test]
 	fn writer_output_behaves() {
-		output_does_not_panic(IoWriter::new(Vec::new()));
-		let mut output = IoWriter::new(Vec::new());
+		output_does_not_panic(IoWriter::with_capacity(Vec::new(), 1024));
+		let mut output = IoWriter::with_capacity(Vec::new(), 1024);
+		basic_output_works(&mut output);
+		assert!(output.writer.is_empty());
+		output.flush().unwrap();
+		assert_eq!(&output.writer, BASIC_OUTPUT_DATA);
+
+		let mut output = IoWriter::with_capacity(Vec::new(), 0);
+		basic_output_works(&mut output);
+		assert_eq!(&output.writer, BASIC_OUTPUT_DATA);
+
+		let mut output = IoWriter::with_capacity(Vec::new(), 3);
 		basic_output_works(&mut output);
+		output.flush().unwrap();
 		assert_eq!(&output.writer, BASIC_OUTPUT_DATA);
+
+		let mut target = Vec::new();
+		let mut output = IoWriter::with_capacity(&mut target, 1024);
+		basic_output_works(&mut output);
+		drop(output);
+		assert_eq!(target, BASIC_OUTPUT_DATA);
+	}
+
+	#[cfg(feature = "text")]
+	#[test]
+	fn text_input_behaves() {
+		input_does_not_panic(TextInput::new(TextEncoding::Base64, "AA=="));
+		input_does_not_panic(TextInput::new(TextEncoding::Base64, "A"));
+		input_does_not_panic(TextInput::new(TextEncoding::Hex, "00"));
+		input_does_not_panic(TextInput::new(TextEncoding::Hex, "0"));
+		basic_input_works(TextInput::new(TextEncoding::Base64, "AAECAwQFBgcICQ=="));
+		basic_input_works(TextInput::new(TextEncoding::Hex, "00010203040506070809"));
+		read_bytes_works(
+			TextInput::new(TextEncoding::Base64, "BQUFBQUFBQUFBQUFBQUFBQUFBQU="),
+			Some(::alloc::vec::Vec::new()),
+		);
+		read_bytes_works(
+			TextInput::new(TextEncoding::Hex, &"05".repeat(20)),
+			Some(::alloc::vec::Vec::new()),
+		);
+	}
+
+	#[cfg(feature = "text")]
+	#[test]
+	fn text_output_behaves() {
+		output_does_not_panic(TextOutput::new(TextEncoding::Base64));
+		output_does_not_panic(TextOutput::new(TextEncoding::Hex));
+		let mut output = TextOutput::new(TextEncoding::Base64);
+		basic_output_works(&mut output);
+		assert_eq!(output.finish(), "AAECAwQFBgcICQ==");
+		let mut output = TextOutput::with_capacity(TextEncoding::Hex, 10);
+		basic_output_works(&mut output);
+		assert_eq!(output.finish(), "00010203040506070809");
+	}
+
+	#[cfg(feature = "embedded-io")]
+	#[test]
+	fn embedded_writer_output_behaves() {
+		output_does_not_panic(EmbeddedWriter::new([0; 2].as_mut_slice()));
+		let mut target = [0; 10];
+		let mut output = EmbeddedWriter::new(target.as_mut_slice());
+		basic_output_works(&mut output);
+		output.flush().unwrap();
+		assert_eq!(&target, BASIC_OUTPUT_DATA);
+
+		let mut target = [0; 2];
+		let mut output = EmbeddedWriter::new(target.as_mut_slice());
+		assert!(matches!(
+			output.write_all(&[1, 2, 3]),
+			Err(Error::EmbeddedIo(::embedded_io::ErrorKind::WriteZero))
+		));
 	}
 }
@@ -27,9 +27,22 @@
 //! | Feature Flag | Default | Description |
 //! | --- | --- | --- |
 //! | alloc | no | Enables the use of `alloc` types like serialization to a `Vec`. |
-//! | heapless | no | Enables serialization to a `heapless::Vec`. |
+//! | arrayvec | no | Enables serialization to an `arrayvec::ArrayVec` and its use as deserialization buffer. |
+//! | big-integers | no | Enables `Value::BigInteger`, which keeps integers exceeding 128 bits as their raw encoding instead of failing. |
+//! | bytes | no | Enables serialization to a `bytes::BytesMut` and deserialization from a `bytes::Buf`. |
+//! | chrono | no | Enables timestamp helpers for `chrono::DateTime<Utc>`. |
+//! | derive | no | Enables the derive macro for `MaxSize`, the maximum encoded size of a type. |
+//! | embedded-io | no | Enables serialization to an `embedded_io::Write`r and deserialization from an `embedded_io::Read`er. |
+//! | half | no | Enables the `f16_as_float16` helper, encoding `half::f16` as `Float16`. |
+//! | heapless | no | Enables serialization to a `heapless::Vec` and its use as deserialization buffer. |
+//! | json | no | Enables conversions between `Value` and `serde_json::Value`, and with **std** the streaming `convert` module. |
+//! | primitive-types | no | Enables the `u256_bytes` helper, encoding `primitive_types::U256` as 32 bytes. |
 //! | std | no | Enables the use of `std` types like serialization to a `Write`r and deserialization from a `Read`er. |
+//! | text | no | Enables serialization to and deserialization from base64 and hex text, e.g. to embed data in text configs. |
+//! | time | no | Enables timestamp helpers for `time::OffsetDateTime`. |
+//! | tokio | no | Enables async serialization to an `AsyncWrite`r and deserialization from an `AsyncRead`er. |
 //! | tracing | no | Enables tracing instrumentation. |
+//! | uuid | no | Enables compact UUID helpers and conversions between `Value` and `uuid::Uuid`. |
 //!
 //! ## Flavors / Modes
 //!
@@ -96,40 +109,120 @@
 //! }
 //! ```
 //!
+//! ### Transcoding
+//!
+//! The [Serializer] and [Deserializer] work with `serde_transcode`, so data can be converted from
+//! and to other formats like JSON without intermediate types or a [Value](value::Value):
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! let json = r#"{"name":"Holla","tags":["a","b"]}"#;
+//! let mut serializer = serde_brief::Serializer::new(Vec::new());
+//! serde_transcode::transcode(&mut serde_json::Deserializer::from_str(json), &mut serializer)
+//! 	.unwrap();
+//! let bytes = serializer.into_output();
+//!
+//! let mut output = Vec::new();
+//! serde_transcode::transcode(
+//! 	&mut serde_brief::Deserializer::new(bytes.as_slice()),
+//! 	&mut serde_json::Serializer::new(&mut output),
+//! )
+//! .unwrap();
+//! assert_eq!(output, json.as_bytes());
+//! # }
+//! ```
+//!
 //! ## Performance
 //!
 //! If you are interested in maximum performance, please take a look at the [PGO usage
 //! documentation](./docs/pgo/index.html).
 #![cfg_attr(not(feature = "std"), no_std)]
+// Panicking is a hard fault on embedded devices, so errors are returned instead.
+#![cfg_attr(
+	not(test),
+	deny(
+		clippy::panic,
+		clippy::unwrap_used,
+		clippy::expect_used,
+		clippy::indexing_slicing,
+		clippy::unreachable,
+		clippy::todo,
+		clippy::unimplemented
+	)
+)]
 
 #[cfg(feature = "alloc")]
 extern crate alloc;
 
-mod buffer;
+#[cfg(feature = "tokio")]
+mod async_io;
+pub mod buffer;
+pub mod checksum;
+#[cfg(feature = "alloc")]
+pub mod compat;
 mod config;
+#[cfg(all(feature = "std", feature = "json"))]
+pub mod convert;
 pub mod de;
 pub mod docs;
+pub mod envelope;
 mod error;
-mod format;
-mod io;
+pub mod fmt;
+pub mod format;
+pub mod framing;
+pub mod helpers;
+pub mod io;
+pub mod max_size;
+pub mod raw;
+#[cfg(feature = "alloc")]
+pub mod schema;
 pub mod ser;
+mod validate;
 #[cfg(feature = "alloc")]
 pub mod value;
+pub mod varint;
 
 #[allow(unused_imports, reason = "Different feature sets")]
 use ::serde::{de::DeserializeOwned, Deserialize, Serialize};
+/// Derive macro for [MaxSize], see [the module](max_size) for details.
+#[cfg(feature = "derive")]
+pub use ::serde_brief_derive::MaxSize;
 #[cfg(feature = "std")]
-use ::std::io::{Read, Write};
+use ::std::io::{BufRead, Read, Write};
 
+#[cfg(feature = "tokio")]
+pub use self::async_io::{
+	from_reader_async, from_reader_async_with_config, to_writer_async, to_writer_async_with_config,
+};
 #[cfg(feature = "alloc")]
 pub use self::value::{from_value, from_value_with_config, to_value, to_value_with_config};
-pub use self::{config::Config, de::Deserializer, error::Error, ser::Serializer};
+pub use self::{
+	config::{Config, LimitBehavior},
+	de::Deserializer,
+	error::{Error, ErrorKind},
+	io::{ChunkedInput, SizeLimit, SliceOutput},
+	max_size::MaxSize,
+	raw::RawValue,
+	ser::Serializer,
+};
 
 /// `Result` type that uses the `serde-brief` error.
 pub type Result<T, E = Error> = ::core::result::Result<T, E>;
 
+/// Return the unconsumed bytes of a size limited slice, or nothing if the limit is reached.
+fn remaining_within_limit(mut input: io::SizeLimit<&[u8]>) -> &[u8] {
+	if io::Input::peek_byte(&mut input).is_ok() {
+		input.into_inner()
+	} else {
+		&[]
+	}
+}
+
 /// Serialize a type into a slice of bytes using the given configuration. Returns the slice with the
 /// serialized data.
+///
+/// With [LimitBehavior::ErrorAfterRollback], the size is computed first and the buffer is only
+/// written to if the value fits.
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
 pub fn to_slice_with_config<'buf, T>(
 	value: &T,
@@ -139,19 +232,23 @@ pub fn to_slice_with_config<'buf, T>(
 where
 	T: Serialize,
 {
-	let remaining = if let Some(max) = config.max_size {
-		let mut ser = Serializer::new(io::SizeLimit::new(&mut *buffer, max.into()))
-			.use_indices(config.use_indices);
+	if config.on_limit == LimitBehavior::ErrorAfterRollback
+		&& serialized_size_with_config(value, config)? > buffer.len()
+	{
+		return Err(Error::BufferTooSmall);
+	}
+
+	let output = io::SliceOutput::new(buffer);
+	let output = if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(output, max.into())).with_config(config);
 		value.serialize(&mut ser)?;
-		ser.into_output().into_inner().len()
+		ser.into_output().into_inner()
 	} else {
-		let mut ser = Serializer::new(&mut *buffer).use_indices(config.use_indices);
+		let mut ser = Serializer::new(output).with_config(config);
 		value.serialize(&mut ser)?;
-		ser.into_output().len()
+		ser.into_output()
 	};
-
-	let used = buffer.len() - remaining;
-	Ok(buffer.split_at_mut(used).0)
+	Ok(output.into_written())
 }
 
 /// Serialize a type into a slice of bytes. Returns the slice with the serialized data.
@@ -162,32 +259,170 @@ where
 	to_slice_with_config(value, buffer, Config::default())
 }
 
+/// Serialize a type into a slice of bytes using the given configuration. Returns the number of
+/// bytes written to the start of the buffer.
+pub fn to_slice_len_with_config<T>(value: &T, buffer: &mut [u8], config: Config) -> Result<usize>
+where
+	T: Serialize,
+{
+	to_slice_with_config(value, buffer, config).map(|written| written.len())
+}
+
+/// Serialize a type into a slice of bytes. Returns the number of bytes written to the start of the
+/// buffer.
+pub fn to_slice_len<T>(value: &T, buffer: &mut [u8]) -> Result<usize>
+where
+	T: Serialize,
+{
+	to_slice_len_with_config(value, buffer, Config::default())
+}
+
+/// Compute the number of bytes the serialized type takes up using the given configuration. The
+/// value is serialized once without storing any of the output.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn serialized_size_with_config<T>(value: &T, config: Config) -> Result<usize>
+where
+	T: Serialize,
+{
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(io::SizeCounter::new(), max.into()))
+			.with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().into_inner().count())
+	} else {
+		let mut ser = Serializer::new(io::SizeCounter::new()).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().count())
+	}
+}
+
+/// Compute the number of bytes the serialized type takes up.
+pub fn serialized_size<T>(value: &T) -> Result<usize>
+where
+	T: Serialize,
+{
+	serialized_size_with_config(value, Config::default())
+}
+
 /// Serialize a type into a [Vec] of bytes using the given configuration.
 #[cfg(feature = "alloc")]
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
 pub fn to_vec_with_config<T>(value: &T, config: Config) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	serialize_into_vec(value, ::alloc::vec::Vec::new(), config)
+}
+
+/// Serialize a type into a [Vec] of bytes.
+#[cfg(feature = "alloc")]
+pub fn to_vec<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	to_vec_with_config(value, Config::default())
+}
+
+/// Serialize a type into a [Vec] of bytes using the given configuration, allocating the [Vec]
+/// exactly once. The output size is computed in a first pass, so the value is serialized twice.
+/// This trades serialization time for avoiding the reallocations of a growing [Vec] and its unused
+/// capacity, e.g. for large values or outputs that are kept around.
+#[cfg(feature = "alloc")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_vec_exact_with_config<T>(value: &T, config: Config) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	let mut output = ::alloc::vec::Vec::new();
+	output
+		.try_reserve_exact(serialized_size_with_config(value, config)?)
+		.map_err(|_| Error::Allocation)?;
+	serialize_into_vec(value, output, config)
+}
+
+/// Serialize a type into a [Vec] of bytes, allocating the [Vec] exactly once. See
+/// [to_vec_exact_with_config].
+#[cfg(feature = "alloc")]
+pub fn to_vec_exact<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	to_vec_exact_with_config(value, Config::default())
+}
+
+/// Serialize a type to the end of the [Vec] using the given configuration.
+#[cfg(feature = "alloc")]
+fn serialize_into_vec<T>(
+	value: &T,
+	output: ::alloc::vec::Vec<u8>,
+	config: Config,
+) -> Result<::alloc::vec::Vec<u8>>
 where
 	T: Serialize,
 {
 	if let Some(max) = config.max_size {
-		let mut ser = Serializer::new(io::SizeLimit::new(::alloc::vec::Vec::new(), max.into()))
-			.use_indices(config.use_indices);
+		let mut ser = Serializer::new(io::SizeLimit::new(output, max.into())).with_config(config);
 		value.serialize(&mut ser)?;
 		Ok(ser.into_output().into_inner())
 	} else {
-		let mut ser = Serializer::new(::alloc::vec::Vec::new()).use_indices(config.use_indices);
+		let mut ser = Serializer::new(output).with_config(config);
 		value.serialize(&mut ser)?;
 		Ok(ser.into_output())
 	}
 }
 
-/// Serialize a type into a [Vec] of bytes.
+/// Serialize a type into the given [Vec] of bytes using the given configuration. The [Vec] is
+/// cleared first and its allocation is reused, so that serializing many values does not allocate
+/// a new [Vec] each time.
+///
+/// The [Vec] is kept on errors, but its contents are unspecified, unless
+/// [LimitBehavior::ErrorAfterRollback] truncates it to its starting (empty) length.
 #[cfg(feature = "alloc")]
-pub fn to_vec<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_vec_in_with_config<T>(
+	value: &T,
+	buffer: &mut ::alloc::vec::Vec<u8>,
+	config: Config,
+) -> Result<()>
 where
 	T: Serialize,
 {
-	to_vec_with_config(value, Config::default())
+	buffer.clear();
+	let output = ::core::mem::take(buffer);
+
+	let (result, output) = if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(output, max.into())).with_config(config);
+		(value.serialize(&mut ser), ser.into_output().into_inner())
+	} else {
+		let mut ser = Serializer::new(output).with_config(config);
+		(value.serialize(&mut ser), ser.into_output())
+	};
+	*buffer = output;
+	if result.is_err() && config.on_limit == LimitBehavior::ErrorAfterRollback {
+		buffer.clear();
+	}
+	result
+}
+
+/// Serialize a type into the given [Vec] of bytes, reusing its allocation. The [Vec] is cleared
+/// first.
+#[cfg(feature = "alloc")]
+pub fn to_vec_in<T>(value: &T, buffer: &mut ::alloc::vec::Vec<u8>) -> Result<()>
+where
+	T: Serialize,
+{
+	to_vec_in_with_config(value, buffer, Config::default())
+}
+
+/// Serialize a type into a [Vec] of bytes with entries of maps and structs sorted by their
+/// serialized keys. The output is deterministic, regardless of field declaration or map iteration
+/// order, e.g. for hashing or signing.
+#[cfg(feature = "alloc")]
+pub fn to_vec_canonical<T>(value: &T) -> Result<::alloc::vec::Vec<u8>>
+where
+	T: Serialize,
+{
+	to_vec_with_config(value, Config { canonical: true, ..Default::default() })
 }
 
 /// Serialize a type into a [`heapless::Vec`] of bytes using the given configuration.
@@ -202,11 +437,11 @@ where
 {
 	if let Some(max) = config.max_size {
 		let mut ser = Serializer::new(io::SizeLimit::new(::heapless::Vec::new(), max.into()))
-			.use_indices(config.use_indices);
+			.with_config(config);
 		value.serialize(&mut ser)?;
 		Ok(ser.into_output().into_inner())
 	} else {
-		let mut ser = Serializer::new(::heapless::Vec::new()).use_indices(config.use_indices);
+		let mut ser = Serializer::new(::heapless::Vec::new()).with_config(config);
 		value.serialize(&mut ser)?;
 		Ok(ser.into_output())
 	}
@@ -221,7 +456,133 @@ where
 	to_heapless_vec_with_config(value, Config::default())
 }
 
+/// Serialize a type into an [`arrayvec::ArrayVec`] of bytes using the given configuration.
+#[cfg(feature = "arrayvec")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_arrayvec_with_config<const N: usize, T>(
+	value: &T,
+	config: Config,
+) -> Result<::arrayvec::ArrayVec<u8, N>>
+where
+	T: Serialize,
+{
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(::arrayvec::ArrayVec::new(), max.into()))
+			.with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().into_inner())
+	} else {
+		let mut ser = Serializer::new(::arrayvec::ArrayVec::new()).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output())
+	}
+}
+
+/// Serialize a type into an [`arrayvec::ArrayVec`] of bytes.
+#[cfg(feature = "arrayvec")]
+pub fn to_arrayvec<const N: usize, T>(value: &T) -> Result<::arrayvec::ArrayVec<u8, N>>
+where
+	T: Serialize,
+{
+	to_arrayvec_with_config(value, Config::default())
+}
+
+/// Serialize a type into a [`bytes::BytesMut`] using the given configuration.
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_bytes_mut_with_config<T>(value: &T, config: Config) -> Result<::bytes::BytesMut>
+where
+	T: Serialize,
+{
+	let output = ::bytes::BytesMut::new();
+
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(output, max.into())).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().into_inner())
+	} else {
+		let mut ser = Serializer::new(output).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output())
+	}
+}
+
+/// Serialize a type into a [`bytes::BytesMut`].
+#[cfg(feature = "bytes")]
+pub fn to_bytes_mut<T>(value: &T) -> Result<::bytes::BytesMut>
+where
+	T: Serialize,
+{
+	to_bytes_mut_with_config(value, Config::default())
+}
+
+/// Serialize a type into text of the given encoding using the given configuration. The bytes are
+/// encoded on the fly, without an intermediate byte buffer, and the text grows as needed.
+#[cfg(feature = "text")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+fn to_text_with_config<T>(
+	value: &T,
+	encoding: io::TextEncoding,
+	config: Config,
+) -> Result<::alloc::string::String>
+where
+	T: Serialize,
+{
+	let output = io::TextOutput::new(encoding);
+
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(output, max.into())).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().into_inner().finish())
+	} else {
+		let mut ser = Serializer::new(output).with_config(config);
+		value.serialize(&mut ser)?;
+		Ok(ser.into_output().finish())
+	}
+}
+
+/// Serialize a type into base64 text (standard alphabet, with padding) using the given
+/// configuration, e.g. to embed it in text configs or environment variables.
+#[cfg(feature = "text")]
+pub fn to_base64_with_config<T>(value: &T, config: Config) -> Result<::alloc::string::String>
+where
+	T: Serialize,
+{
+	to_text_with_config(value, io::TextEncoding::Base64, config)
+}
+
+/// Serialize a type into base64 text (standard alphabet, with padding), e.g. to embed it in text
+/// configs or environment variables.
+#[cfg(feature = "text")]
+pub fn to_base64<T>(value: &T) -> Result<::alloc::string::String>
+where
+	T: Serialize,
+{
+	to_base64_with_config(value, Config::default())
+}
+
+/// Serialize a type into lower case hexadecimal text using the given configuration.
+#[cfg(feature = "text")]
+pub fn to_hex_with_config<T>(value: &T, config: Config) -> Result<::alloc::string::String>
+where
+	T: Serialize,
+{
+	to_text_with_config(value, io::TextEncoding::Hex, config)
+}
+
+/// Serialize a type into lower case hexadecimal text.
+#[cfg(feature = "text")]
+pub fn to_hex<T>(value: &T) -> Result<::alloc::string::String>
+where
+	T: Serialize,
+{
+	to_hex_with_config(value, Config::default())
+}
+
 /// Serialize a type into a [Write]r using the given configuration.
+///
+/// On errors, data that was written to the writer stays there. See [LimitBehavior] for how to
+/// find out how much of it was written.
 #[cfg(feature = "std")]
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
 pub fn to_writer_with_config<T, W>(value: &T, writer: W, config: Config) -> Result<()>
@@ -229,17 +590,43 @@ where
 	T: Serialize,
 	W: Write,
 {
+	let writer = io::IoWriter::with_capacity(writer, config.writer_buffer_size);
 	if let Some(max) = config.max_size {
-		let mut ser = Serializer::new(io::SizeLimit::new(io::IoWriter::new(writer), max.into()))
-			.use_indices(config.use_indices);
-		value.serialize(&mut ser)?;
+		let mut ser = Serializer::new(io::SizeLimit::new(writer, max.into())).with_config(config);
+		if let Err(err) = value.serialize(&mut ser) {
+			return Err(rollback_writer(ser.into_output().into_inner(), err, config));
+		}
+		ser.flush()?;
 	} else {
-		let mut ser = Serializer::new(io::IoWriter::new(writer)).use_indices(config.use_indices);
-		value.serialize(&mut ser)?;
+		let mut ser = Serializer::new(writer).with_config(config);
+		if let Err(err) = value.serialize(&mut ser) {
+			return Err(rollback_writer(ser.into_output(), err, config));
+		}
+		ser.flush()?;
 	}
 	Ok(())
 }
 
+/// Apply [LimitBehavior::ErrorAfterRollback] to a writer after serializing failed: discard the
+/// buffered data and report how many bytes already reached the writer, if the limit was hit.
+#[cfg(feature = "std")]
+fn rollback_writer<W>(mut writer: io::IoWriter<W>, error: Error, config: Config) -> Error
+where
+	W: Write,
+{
+	if config.on_limit == LimitBehavior::Error {
+		return error;
+	}
+
+	writer.discard_buffer();
+	match error {
+		Error::LimitReached if writer.bytes_written() > 0 => {
+			Error::PartialWrite { written: writer.bytes_written() }
+		}
+		error => error,
+	}
+}
+
 /// Serialize a type into a [Write]r.
 #[cfg(feature = "std")]
 pub fn to_writer<T, W>(value: &T, writer: W) -> Result<()>
@@ -250,29 +637,154 @@ where
 	to_writer_with_config(value, writer, Config::default())
 }
 
+/// Serialize a type into an [`embedded_io::Write`]r using the given configuration. Writes are
+/// passed to the writer directly, so it is best to use a buffered writer.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn to_embedded_writer_with_config<T, W>(value: &T, writer: W, config: Config) -> Result<()>
+where
+	T: Serialize,
+	W: ::embedded_io::Write,
+{
+	let writer = io::EmbeddedWriter::new(writer);
+	if let Some(max) = config.max_size {
+		let mut ser = Serializer::new(io::SizeLimit::new(writer, max.into())).with_config(config);
+		value.serialize(&mut ser)?;
+		ser.flush()?;
+	} else {
+		let mut ser = Serializer::new(writer).with_config(config);
+		value.serialize(&mut ser)?;
+		ser.flush()?;
+	}
+	Ok(())
+}
+
+/// Serialize a type into an [`embedded_io::Write`]r.
+#[cfg(feature = "embedded-io")]
+pub fn to_embedded_writer<T, W>(value: &T, writer: W) -> Result<()>
+where
+	T: Serialize,
+	W: ::embedded_io::Write,
+{
+	to_embedded_writer_with_config(value, writer, Config::default())
+}
+
+/// Check that the bytes contain a well-formed value, without deserializing it. This is faster than
+/// deserializing a [Value](crate::value::Value) and does not allocate.
+///
+/// Sequences and maps must be balanced, integers must fit into 128 bits (or 1024 bits with
+/// **big-integers**, see [BigInteger](crate::value::BigInteger)) and strings must be valid UTF-8.
+/// The configured `max_size`, `max_depth` and `error_on_excess_data` are honored.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn validate(bytes: &[u8], config: Config) -> Result<()> {
+	let rest = if let Some(max) = config.max_size {
+		let mut input = io::SizeLimit::new(bytes, max.into());
+		validate::validate_value(&mut input, config.max_depth, &mut 0)?;
+		remaining_within_limit(input)
+	} else {
+		let mut input = bytes;
+		validate::validate_value(&mut input, config.max_depth, &mut 0)?;
+		input
+	};
+
+	if config.error_on_excess_data && !rest.is_empty() {
+		return Err(Error::excess_data(rest));
+	}
+
+	Ok(())
+}
+
 /// Deserialize a type from a slice of bytes using the given configuration.
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
 pub fn from_slice_with_config<'de, T>(bytes: &'de [u8], config: Config) -> Result<T>
 where
 	T: Deserialize<'de>,
 {
-	let error_on_excess = config.error_on_excess_data;
-
-	let (value, peek) = if let Some(max) = config.max_size {
+	if let Some(max) = config.max_size {
 		// The deserializer can parse both with and without `use_indices`.`
-		let mut de = Deserializer::new(io::SizeLimit::new(bytes, max.into()));
-		(T::deserialize(&mut de)?, io::Input::peek_byte(&mut de.into_input()))
+		let mut de = Deserializer::new(io::SizeLimit::new(bytes, max.into())).with_config(config);
+		let value = T::deserialize(&mut de)?;
+		de.end()?;
+		Ok(value)
 	} else {
 		// The deserializer can parse both with and without `use_indices`.`
-		let mut de = Deserializer::new(bytes);
-		(T::deserialize(&mut de)?, io::Input::peek_byte(&mut de.into_input()))
-	};
+		let mut de = Deserializer::new(bytes).with_config(config);
+		let value = T::deserialize(&mut de)?;
+		de.end()?;
+		Ok(value)
+	}
+}
 
-	if error_on_excess && peek.is_ok() {
-		return Err(Error::ExcessData);
+/// Deserialize a value from the input and check for excess data like [Deserializer::end]. Returns
+/// the value and the input after it. Inputs that cannot lend their data are only wrapped in a
+/// [RecordingInput](io::RecordingInput) when duplicate map keys are rejected, so that the default
+/// path reads from the input directly.
+pub(crate) fn deserialize_input<'de, I, B, T>(input: I, buffer: B, config: Config) -> Result<(T, I)>
+where
+	I: io::Input<'de>,
+	B: buffer::Buffer,
+	T: Deserialize<'de>,
+{
+	#[cfg(feature = "alloc")]
+	if config.reject_duplicate_map_keys && input.remaining_borrowed().is_none() {
+		let input = io::RecordingInput::new(input);
+		let mut de = Deserializer::new(input).with_config(config).with_buffer(buffer);
+		let value = T::deserialize(&mut de)?;
+		de.end()?;
+		return Ok((value, de.into_input().into_inner()));
 	}
 
-	Ok(value)
+	let mut de = Deserializer::new(input).with_config(config).with_buffer(buffer);
+	let value = T::deserialize(&mut de)?;
+	de.end()?;
+	Ok((value, de.into_input()))
+}
+
+/// Deserialize a type from the start of a slice of bytes using the given configuration. Returns
+/// the value and the remaining bytes after it, so consecutive values can be parsed from one buffer.
+///
+/// `error_on_excess_data` is ignored, the configured `max_size` limits the bytes of the value.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_slice_partial_with_config<'de, T>(
+	bytes: &'de [u8],
+	config: Config,
+) -> Result<(T, &'de [u8])>
+where
+	T: Deserialize<'de>,
+{
+	if let Some(max) = config.max_size {
+		let mut de = Deserializer::new(io::SizeLimit::new(bytes, max.into())).with_config(config);
+		Ok((T::deserialize(&mut de)?, de.into_input().into_inner()))
+	} else {
+		let mut de = Deserializer::new(bytes).with_config(config);
+		Ok((T::deserialize(&mut de)?, de.into_input()))
+	}
+}
+
+/// Deserialize a type from the start of a slice of bytes. Returns the value and the remaining
+/// bytes after it.
+///
+/// ```rust
+/// let mut buffer = [0; 32];
+/// let mut len = 0;
+/// for value in [1_u32, 20, 300] {
+/// 	len += serde_brief::to_slice_len(&value, &mut buffer[len ..]).unwrap();
+/// }
+///
+/// let mut bytes = &buffer[.. len];
+/// let mut values = Vec::new();
+/// while !bytes.is_empty() {
+/// 	let (value, rest) = serde_brief::from_slice_partial::<u32>(bytes).unwrap();
+/// 	values.push(value);
+/// 	bytes = rest;
+/// }
+/// assert_eq!(values, [1, 20, 300]);
+/// ```
+pub fn from_slice_partial<'de, T>(bytes: &'de [u8]) -> Result<(T, &'de [u8])>
+where
+	T: Deserialize<'de>,
+{
+	from_slice_partial_with_config(bytes, Config::default())
 }
 
 /// Deserialize a type from a slice of bytes.
@@ -283,35 +795,279 @@ where
 	from_slice_with_config(bytes, Config::default())
 }
 
+/// Deserialize only the value at the given path of map keys and sequence indices using the given
+/// configuration. The data on the way is walked without decoding it, see
+/// [find_path](raw::find_path) for how path components are matched. Returns `None` if the path
+/// does not exist.
+///
+/// ```rust
+/// # #[cfg(feature = "alloc")] {
+/// use serde::Serialize;
+///
+/// #[derive(Serialize)]
+/// struct Config {
+/// 	servers: Vec<Server>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Server {
+/// 	host: String,
+/// 	port: u16,
+/// }
+///
+/// let config = Config { servers: vec![Server { host: "localhost".to_owned(), port: 8080 }] };
+/// let bytes = serde_brief::to_vec(&config).unwrap();
+/// let port: Option<u16> = serde_brief::get_path(&bytes, &["servers", "0", "port"]).unwrap();
+/// assert_eq!(port, Some(8080));
+/// let missing: Option<u16> = serde_brief::get_path(&bytes, &["servers", "1", "port"]).unwrap();
+/// assert_eq!(missing, None);
+/// # }
+/// ```
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn get_path_with_config<'de, T>(
+	bytes: &'de [u8],
+	path: &[&str],
+	config: Config,
+) -> Result<Option<T>>
+where
+	T: Deserialize<'de>,
+{
+	let Some(bytes) = raw::find_path(bytes, path)? else {
+		return Ok(None);
+	};
+	from_slice_with_config(bytes, config).map(Some)
+}
+
+/// Deserialize only the value at the given path of map keys and sequence indices, see
+/// [get_path_with_config].
+pub fn get_path<'de, T>(bytes: &'de [u8], path: &[&str]) -> Result<Option<T>>
+where
+	T: Deserialize<'de>,
+{
+	get_path_with_config(bytes, path, Config::default())
+}
+
+/// Deserialize a type from byte chunks using the given configuration, without concatenating them
+/// first. Strings and bytes within a single chunk are borrowed, others are copied to the given
+/// buffer, e.g. a `heapless::Vec`.
+///
+/// ```rust
+/// # #[cfg(feature = "heapless")] {
+/// let bytes = serde_brief::to_slice(&("key", 5_u8), &mut [0; 16]).unwrap().to_vec();
+/// let (first, second) = bytes.split_at(4);
+/// let buffer = heapless::Vec::<u8, 16>::new();
+/// let value: (String, u8) = serde_brief::from_chunks([first, second], buffer).unwrap();
+/// assert_eq!(value, ("key".to_owned(), 5));
+/// # }
+/// ```
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_chunks_with_config<'de, C, B, T>(chunks: C, buffer: B, config: Config) -> Result<T>
+where
+	C: IntoIterator<Item = &'de [u8]>,
+	B: buffer::Buffer,
+	T: Deserialize<'de>,
+{
+	let input = io::ChunkedInput::new(chunks);
+
+	if let Some(max) = config.max_size {
+		// The deserializer can parse both with and without `use_indices`.`
+		let input = io::SizeLimit::new(input, max.into());
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	} else {
+		// The deserializer can parse both with and without `use_indices`.`
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	}
+}
+
+/// Deserialize a type from byte chunks, copying data straddling chunk boundaries to the given
+/// buffer.
+pub fn from_chunks<'de, C, B, T>(chunks: C, buffer: B) -> Result<T>
+where
+	C: IntoIterator<Item = &'de [u8]>,
+	B: buffer::Buffer,
+	T: Deserialize<'de>,
+{
+	from_chunks_with_config(chunks, buffer, Config::default())
+}
+
+/// Deserialize a type from a [`bytes::Buf`] using the given configuration. Data within a single
+/// chunk of the buffer is not copied, data straddling chunk boundaries is copied to a scratch
+/// buffer.
+///
+/// ```rust
+/// # #[cfg(feature = "bytes")] {
+/// use bytes::Buf;
+///
+/// let bytes = serde_brief::to_bytes_mut(&("key", 5_u8)).unwrap().freeze();
+/// let (first, second) = (bytes.slice(.. 4), bytes.slice(4 ..));
+/// let value: (String, u8) = serde_brief::from_buf(first.chain(second)).unwrap();
+/// assert_eq!(value, ("key".to_owned(), 5));
+/// # }
+/// ```
+#[cfg(feature = "bytes")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_buf_with_config<B, T>(buf: B, config: Config) -> Result<T>
+where
+	B: ::bytes::Buf,
+	T: DeserializeOwned,
+{
+	let input = io::BufInput::new(buf);
+	let buffer = ::alloc::vec::Vec::new();
+
+	if let Some(max) = config.max_size {
+		// The deserializer can parse both with and without `use_indices`.`
+		let input = io::SizeLimit::new(input, max.into());
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	} else {
+		// The deserializer can parse both with and without `use_indices`.`
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	}
+}
+
+/// Deserialize a type from a [`bytes::Buf`], e.g. `bytes::Bytes`, chains of them or
+/// `VecDeque<u8>`.
+#[cfg(feature = "bytes")]
+pub fn from_buf<B, T>(buf: B) -> Result<T>
+where
+	B: ::bytes::Buf,
+	T: DeserializeOwned,
+{
+	from_buf_with_config(buf, Config::default())
+}
+
+/// Deserialize a type from text of the given encoding using the given configuration. The text is
+/// decoded on the fly, strings and bytes are copied to a scratch buffer.
+#[cfg(feature = "text")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+fn from_text_with_config<T>(text: &str, encoding: io::TextEncoding, config: Config) -> Result<T>
+where
+	T: DeserializeOwned,
+{
+	let input = io::TextInput::new(encoding, text);
+	let buffer = ::alloc::vec::Vec::new();
+
+	if let Some(max) = config.max_size {
+		// The deserializer can parse both with and without `use_indices`.`
+		let input = io::SizeLimit::new(input, max.into());
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	} else {
+		// The deserializer can parse both with and without `use_indices`.`
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	}
+}
+
+/// Deserialize a type from base64 text (standard alphabet, with padding) using the given
+/// configuration. Invalid text returns [Error::Encoding].
+#[cfg(feature = "text")]
+pub fn from_base64_with_config<T>(text: &str, config: Config) -> Result<T>
+where
+	T: DeserializeOwned,
+{
+	from_text_with_config(text, io::TextEncoding::Base64, config)
+}
+
+/// Deserialize a type from base64 text (standard alphabet, with padding). Invalid text returns
+/// [Error::Encoding].
+///
+/// ```rust
+/// let text = serde_brief::to_base64(&("config", 5_u8)).unwrap();
+/// assert_eq!(text, "DwsGY29uZmlnAwUQ");
+/// let parsed: (String, u8) = serde_brief::from_base64(&text).unwrap();
+/// assert_eq!(parsed, ("config".to_owned(), 5));
+/// ```
+#[cfg(feature = "text")]
+pub fn from_base64<T>(text: &str) -> Result<T>
+where
+	T: DeserializeOwned,
+{
+	from_base64_with_config(text, Config::default())
+}
+
+/// Deserialize a type from hexadecimal text of either case using the given configuration.
+/// Invalid text returns [Error::Encoding].
+#[cfg(feature = "text")]
+pub fn from_hex_with_config<T>(text: &str, config: Config) -> Result<T>
+where
+	T: DeserializeOwned,
+{
+	from_text_with_config(text, io::TextEncoding::Hex, config)
+}
+
+/// Deserialize a type from hexadecimal text of either case. Invalid text returns
+/// [Error::Encoding].
+#[cfg(feature = "text")]
+pub fn from_hex<T>(text: &str) -> Result<T>
+where
+	T: DeserializeOwned,
+{
+	from_hex_with_config(text, Config::default())
+}
+
 /// Deserialize a type from a [Read]er using the given configuration.
+///
+/// Only the value is read from the reader when `error_on_excess_data` is disabled, so consecutive
+/// values can be read from a stream. Otherwise, the reader is read ahead in chunks, so data after
+/// the value is consumed and reported as excess data. Use [from_buf_reader_partial] to keep
+/// reading after the value in that case.
 #[cfg(feature = "std")]
-#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
 pub fn from_reader_with_config<R, T>(reader: R, config: Config) -> Result<T>
 where
 	R: Read,
 	T: DeserializeOwned,
 {
-	let error_on_excess = config.error_on_excess_data;
+	from_reader_with_buffer_and_config(reader, &mut Vec::new(), config)
+}
 
-	let (value, peek) = if let Some(max) = config.max_size {
+/// Deserialize a type from a [Read]er using the given configuration. The given [Vec] is used as
+/// scratch buffer for strings and bytes, so that its allocation can be reused for many values.
+/// The [Vec] is cleared first and its contents are unspecified afterwards. The reader is read like
+/// in [from_reader_with_config].
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_reader_with_buffer_and_config<R, T>(
+	reader: R,
+	buffer: &mut Vec<u8>,
+	config: Config,
+) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	buffer.clear();
+	let mut chunk = [0; io::READER_CHUNK_SIZE];
+	let reader = io::value_reader(reader, &mut chunk, config.error_on_excess_data);
+
+	if let Some(max) = config.max_size {
 		// The deserializer can parse both with and without `use_indices`.`
-		let mut de = Deserializer::new(io::SizeLimit::new(io::IoReader::new(reader), max.into()))
-			.with_buffer(Vec::new());
-		(T::deserialize(&mut de)?, io::Input::peek_byte(&mut de.into_input()))
+		let input = io::SizeLimit::new(reader, max.into());
+		let (value, _) = deserialize_input(input, &mut *buffer, config)?;
+		Ok(value)
 	} else {
 		// The deserializer can parse both with and without `use_indices`.`
-		let mut de = Deserializer::new(io::IoReader::new(reader)).with_buffer(Vec::new());
-		(T::deserialize(&mut de)?, io::Input::peek_byte(&mut de.into_input()))
-	};
-
-	if error_on_excess && peek.is_ok() {
-		return Err(Error::ExcessData);
+		let (value, _) = deserialize_input(reader, &mut *buffer, config)?;
+		Ok(value)
 	}
+}
 
-	Ok(value)
+/// Deserialize a type from a [Read]er, using the given [Vec] as scratch buffer. Its allocation is
+/// reused, see [from_reader_with_buffer_and_config]. The reader is read ahead in chunks.
+#[cfg(feature = "std")]
+pub fn from_reader_with_buffer<R, T>(reader: R, buffer: &mut Vec<u8>) -> Result<T>
+where
+	R: Read,
+	T: DeserializeOwned,
+{
+	from_reader_with_buffer_and_config(reader, buffer, Config::default())
 }
 
-/// Deserialize a type from a [Read]er.
+/// Deserialize a type from a [Read]er. The reader is read ahead in chunks, see
+/// [from_reader_with_config] for reading consecutive values.
 #[cfg(feature = "std")]
 pub fn from_reader<R, T>(reader: R) -> Result<T>
 where
@@ -321,5 +1077,196 @@ where
 	from_reader_with_config(reader, Config::default())
 }
 
+/// Deserialize a type from a [Read]er using the given configuration, borrowing strings and bytes
+/// from the given arena. The encoded value is appended to the arena before deserializing it, so
+/// that types containing `&str` or `&[u8]` can be read, at the cost of keeping all data of the
+/// value in the arena. The reader is read like in [from_reader_with_config].
+///
+/// ```rust
+/// #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+/// struct Config<'a> {
+/// 	name: &'a str,
+/// 	paths: Vec<&'a str>,
+/// }
+///
+/// let bytes = serde_brief::to_vec(&Config { name: "app", paths: vec!["/etc", "/home"] }).unwrap();
+/// let mut arena = Vec::new();
+/// let config: Config<'_> =
+/// 	serde_brief::from_reader_to_arena(bytes.as_slice(), &mut arena).unwrap();
+/// assert_eq!(config.paths, ["/etc", "/home"]);
+/// ```
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_reader_to_arena_with_config<'a, R, T>(
+	reader: R,
+	arena: &'a mut Vec<u8>,
+	config: Config,
+) -> Result<T>
+where
+	R: Read,
+	T: Deserialize<'a>,
+{
+	let mut chunk = [0; io::READER_CHUNK_SIZE];
+	let reader = io::value_reader(reader, &mut chunk, config.error_on_excess_data);
+	let mut bytes = if let Some(max) = config.max_size {
+		let input = io::RecordingInput::new(io::SizeLimit::new(reader, max.into()));
+		let mut de = Deserializer::new(input).with_config(config);
+		let bytes = de.read_value_bytes()?;
+		de.end()?;
+		bytes
+	} else {
+		let mut de = Deserializer::new(io::RecordingInput::new(reader)).with_config(config);
+		let bytes = de.read_value_bytes()?;
+		de.end()?;
+		bytes
+	};
+
+	let start = arena.len();
+	arena.append(&mut bytes);
+	let arena: &'a Vec<u8> = arena;
+	from_slice_with_config(arena.get(start ..).unwrap_or_default(), config)
+}
+
+/// Deserialize a type from a [Read]er, borrowing strings and bytes from the given arena. The reader
+/// is read ahead in chunks.
+#[cfg(feature = "std")]
+pub fn from_reader_to_arena<'a, R, T>(reader: R, arena: &'a mut Vec<u8>) -> Result<T>
+where
+	R: Read,
+	T: Deserialize<'a>,
+{
+	from_reader_to_arena_with_config(reader, arena, Config::default())
+}
+
+/// Deserialize a type from a [BufRead]er using the given configuration.
+///
+/// Strings and bytes that are in the reader's buffer already are visited without copying them
+/// to a scratch buffer first.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_buf_reader_with_config<R, T>(reader: R, config: Config) -> Result<T>
+where
+	R: BufRead,
+	T: DeserializeOwned,
+{
+	if let Some(max) = config.max_size {
+		// The deserializer can parse both with and without `use_indices`.`
+		let input = io::SizeLimit::new(io::BufReadInput::new(reader), max.into());
+		let (value, _) = deserialize_input(input, Vec::new(), config)?;
+		Ok(value)
+	} else {
+		// The deserializer can parse both with and without `use_indices`.`
+		let (value, _) = deserialize_input(io::BufReadInput::new(reader), Vec::new(), config)?;
+		Ok(value)
+	}
+}
+
+/// Deserialize a type from a [BufRead]er.
+#[cfg(feature = "std")]
+pub fn from_buf_reader<R, T>(reader: R) -> Result<T>
+where
+	R: BufRead,
+	T: DeserializeOwned,
+{
+	from_buf_reader_with_config(reader, Config::default())
+}
+
+/// Deserialize a type from the start of a [BufRead]er using the given configuration. Returns the
+/// value and the reader, positioned directly after the value, so that following data, e.g. an
+/// opaque payload after a header, can be read from it.
+///
+/// `error_on_excess_data` is ignored, the configured `max_size` limits the bytes of the value.
+/// Plain [Read]ers can be wrapped in a [`BufReader`](::std::io::BufReader), which keeps the data
+/// after the value in its buffer.
+#[cfg(feature = "std")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_buf_reader_partial_with_config<R, T>(reader: R, config: Config) -> Result<(T, R)>
+where
+	R: BufRead,
+	T: DeserializeOwned,
+{
+	// Excess data is not checked, as it is left in the reader.
+	let config = Config { error_on_excess_data: false, ..config };
+	if let Some(max) = config.max_size {
+		let input = io::SizeLimit::new(io::BufReadInput::new(reader), max.into());
+		let (value, input) = deserialize_input(input, Vec::new(), config)?;
+		Ok((value, input.into_inner().into_inner()))
+	} else {
+		let (value, input) = deserialize_input(io::BufReadInput::new(reader), Vec::new(), config)?;
+		Ok((value, input.into_inner()))
+	}
+}
+
+/// Deserialize a type from the start of a [BufRead]er. Returns the value and the reader,
+/// positioned directly after the value.
+///
+/// ```rust
+/// use std::io::{BufReader, Read};
+///
+/// #[derive(serde::Serialize, serde::Deserialize)]
+/// struct Header {
+/// 	kind: String,
+/// 	len: usize,
+/// }
+///
+/// let payload = b"opaque payload";
+/// let mut message =
+/// 	serde_brief::to_vec(&Header { kind: "blob".into(), len: payload.len() }).unwrap();
+/// message.extend_from_slice(payload);
+///
+/// let reader = BufReader::new(message.as_slice());
+/// let (header, mut reader): (Header, _) = serde_brief::from_buf_reader_partial(reader).unwrap();
+/// let mut data = vec![0; header.len];
+/// reader.read_exact(&mut data).unwrap();
+/// assert_eq!(data, payload);
+/// ```
+#[cfg(feature = "std")]
+pub fn from_buf_reader_partial<R, T>(reader: R) -> Result<(T, R)>
+where
+	R: BufRead,
+	T: DeserializeOwned,
+{
+	from_buf_reader_partial_with_config(reader, Config::default())
+}
+
+/// Deserialize a type from an [`embedded_io::Read`]er using the given configuration. Strings and
+/// bytes are read into the given buffer, e.g. a `heapless::Vec`, which must fit the largest one.
+///
+/// Only the value is read from the reader when `error_on_excess_data` is disabled, so consecutive
+/// values can be read from a stream. Otherwise, another byte is read to detect excess data.
+#[cfg(feature = "embedded-io")]
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all, fields(config)))]
+pub fn from_embedded_reader_with_config<R, B, T>(reader: R, buffer: B, config: Config) -> Result<T>
+where
+	R: ::embedded_io::Read,
+	B: buffer::Buffer,
+	T: DeserializeOwned,
+{
+	let input = io::EmbeddedReader::new(reader);
+
+	if let Some(max) = config.max_size {
+		// The deserializer can parse both with and without `use_indices`.`
+		let input = io::SizeLimit::new(input, max.into());
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	} else {
+		// The deserializer can parse both with and without `use_indices`.`
+		let (value, _) = deserialize_input(input, buffer, config)?;
+		Ok(value)
+	}
+}
+
+/// Deserialize a type from an [`embedded_io::Read`]er, reading strings and bytes into the given
+/// buffer.
+#[cfg(feature = "embedded-io")]
+pub fn from_embedded_reader<R, B, T>(reader: R, buffer: B) -> Result<T>
+where
+	R: ::embedded_io::Read,
+	B: buffer::Buffer,
+	T: DeserializeOwned,
+{
+	from_embedded_reader_with_config(reader, buffer, Config::default())
+}
+
 #[cfg(test)]
 mod tests;
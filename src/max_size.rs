@@ -0,0 +1,351 @@
+//! Compile-time maximum encoded sizes of types, e.g. to size `heapless::Vec` buffers.
+//!
+//! [MaxSize::MAX_SIZE] is the largest number of bytes a value of the type can be encoded to. It is
+//! the worst case of all configurations, i.e. both with field and variant names and with indices
+//! (`use_indices`), with and without `explicit_option` and `length_prefixed_seqs`. Types without a
+//! bounded size, like `str`, slices or maps, do not implement the trait.
+//!
+//! With the `derive` feature, the trait can be derived for structs and enums. The derive respects
+//! `#[serde(rename = "...")]`, `#[serde(skip)]` and `#[serde(transparent)]`. Attributes that
+//! change the structure or naming in ways the derive cannot follow, like `flatten`, `with`,
+//! `rename_all` or enum tagging, are rejected with a compile error. Implement the trait manually
+//! for those types, using the helpers in this module.
+//!
+//! ```rust
+//! # #[cfg(all(feature = "derive", feature = "heapless"))] {
+//! use serde::Serialize;
+//! use serde_brief::MaxSize;
+//!
+//! #[derive(Serialize, MaxSize)]
+//! struct Measurement {
+//! 	sensor: u8,
+//! 	values: [f32; 4],
+//! 	error: Option<u16>,
+//! }
+//!
+//! let measurement = Measurement { sensor: 3, values: [1.0; 4], error: None };
+//! let bytes: heapless::Vec<u8, { Measurement::MAX_SIZE }> =
+//! 	serde_brief::to_heapless_vec(&measurement).unwrap();
+//! assert!(bytes.len() <= Measurement::MAX_SIZE);
+//! # }
+//! ```
+//!
+//! Values without a bounded size can still be given buffers sized in constants with the helpers,
+//! if their length is limited by the application:
+//!
+//! ```rust
+//! # #[cfg(feature = "heapless")] {
+//! use serde_brief::max_size::bytes_size;
+//!
+//! const MAX_PAYLOAD: usize = 200;
+//! let payload = serde_bytes::Bytes::new(&[7; MAX_PAYLOAD]);
+//! let bytes: heapless::Vec<u8, { bytes_size(MAX_PAYLOAD) }> =
+//! 	serde_brief::to_heapless_vec(&payload).unwrap();
+//! assert_eq!(bytes.len(), bytes_size(MAX_PAYLOAD));
+//! # }
+//! ```
+
+use crate::format::VarInt;
+
+/// Maximum number of bytes a value of the type is encoded to, in all configurations.
+pub trait MaxSize {
+	/// The maximum number of bytes of the encoded value.
+	const MAX_SIZE: usize;
+}
+
+/// Number of bytes of the `VarInt` encoding of the unsigned integer.
+#[must_use]
+pub const fn varint_size(mut value: usize) -> usize {
+	let mut size = 1;
+	while value >= 0x80 {
+		value >>= 7;
+		size += 1;
+	}
+	size
+}
+
+/// Encoded size of a string of the given length in bytes.
+#[must_use]
+pub const fn str_size(len: usize) -> usize {
+	1 + varint_size(len) + len
+}
+
+/// Encoded size of bytes of the given length, e.g. serialized with `serde_bytes`.
+#[must_use]
+pub const fn bytes_size(len: usize) -> usize {
+	1 + varint_size(len) + len
+}
+
+/// Maximum encoded size of a struct field or enum variant key, which is either the name or the
+/// index, depending on `use_indices`.
+#[must_use]
+pub const fn key_size(name: &str, index: usize) -> usize {
+	max(str_size(name.len()), 1 + varint_size(index))
+}
+
+/// Maximum encoded size of a sequence of the given length without its elements, which is either
+/// the start and end designators or the length-prefixed start.
+#[must_use]
+pub const fn seq_overhead(len: usize) -> usize {
+	max(2, 1 + varint_size(len))
+}
+
+/// The larger of the two sizes, usable in constants.
+#[must_use]
+pub const fn max(a: usize, b: usize) -> usize {
+	if a > b {
+		a
+	} else {
+		b
+	}
+}
+
+/// Implement [MaxSize] for integers and their non-zero variants.
+macro_rules! impl_max_size_int {
+	($($t:ty, $non_zero:ty);* $(;)?) => {
+		$(
+			impl MaxSize for $t {
+				const MAX_SIZE: usize = 1 + <$t as VarInt>::MAX_BYTES;
+			}
+
+			impl MaxSize for $non_zero {
+				const MAX_SIZE: usize = <$t as MaxSize>::MAX_SIZE;
+			}
+		)*
+	};
+}
+
+impl_max_size_int!(
+	u8, ::core::num::NonZeroU8;
+	u16, ::core::num::NonZeroU16;
+	u32, ::core::num::NonZeroU32;
+	u64, ::core::num::NonZeroU64;
+	u128, ::core::num::NonZeroU128;
+	usize, ::core::num::NonZeroUsize;
+	i8, ::core::num::NonZeroI8;
+	i16, ::core::num::NonZeroI16;
+	i32, ::core::num::NonZeroI32;
+	i64, ::core::num::NonZeroI64;
+	i128, ::core::num::NonZeroI128;
+	isize, ::core::num::NonZeroIsize;
+);
+
+impl MaxSize for f32 {
+	const MAX_SIZE: usize = 1 + 4;
+}
+
+impl MaxSize for f64 {
+	const MAX_SIZE: usize = 1 + 8;
+}
+
+/// Without the [f16_as_float16](crate::helpers::f16_as_float16) helper, the bits are written as
+/// `u16`.
+#[cfg(feature = "half")]
+impl MaxSize for ::half::f16 {
+	const MAX_SIZE: usize = u16::MAX_SIZE;
+}
+
+impl MaxSize for bool {
+	const MAX_SIZE: usize = 1;
+}
+
+impl MaxSize for char {
+	const MAX_SIZE: usize = str_size(4);
+}
+
+impl MaxSize for () {
+	const MAX_SIZE: usize = 1;
+}
+
+impl<T: ?Sized> MaxSize for ::core::marker::PhantomData<T> {
+	const MAX_SIZE: usize = 1;
+}
+
+impl<T: MaxSize> MaxSize for Option<T> {
+	// `None` is a single byte, `Some` might be prefixed with a byte with `explicit_option`.
+	const MAX_SIZE: usize = 1 + T::MAX_SIZE;
+}
+
+impl<T: MaxSize, E: MaxSize> MaxSize for Result<T, E> {
+	// Newtype variants are maps with a single entry.
+	const MAX_SIZE: usize =
+		max(2 + key_size("Ok", 0) + T::MAX_SIZE, 2 + key_size("Err", 1) + E::MAX_SIZE);
+}
+
+impl<T: MaxSize + ?Sized> MaxSize for &T {
+	const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl<T: MaxSize + ?Sized> MaxSize for &mut T {
+	const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+#[cfg(feature = "alloc")]
+impl<T: MaxSize + ?Sized> MaxSize for ::alloc::boxed::Box<T> {
+	const MAX_SIZE: usize = T::MAX_SIZE;
+}
+
+impl<T: MaxSize, const N: usize> MaxSize for [T; N] {
+	// Arrays are tuples, which have a length prefix only with `length_prefixed_seqs`.
+	const MAX_SIZE: usize = seq_overhead(N) + N * T::MAX_SIZE;
+}
+
+/// Implement [MaxSize] for tuples.
+macro_rules! impl_max_size_tuple {
+	($($t:ident)+) => {
+		impl<$($t: MaxSize),+> MaxSize for ($($t,)+) {
+			const MAX_SIZE: usize = 2 $(+ $t::MAX_SIZE)+;
+		}
+	};
+}
+
+impl_max_size_tuple!(T0);
+impl_max_size_tuple!(T0 T1);
+impl_max_size_tuple!(T0 T1 T2);
+impl_max_size_tuple!(T0 T1 T2 T3);
+impl_max_size_tuple!(T0 T1 T2 T3 T4);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14);
+impl_max_size_tuple!(T0 T1 T2 T3 T4 T5 T6 T7 T8 T9 T10 T11 T12 T13 T14 T15);
+
+#[cfg(feature = "heapless")]
+impl<T: MaxSize, const N: usize> MaxSize for ::heapless::Vec<T, N> {
+	const MAX_SIZE: usize = seq_overhead(N) + N * T::MAX_SIZE;
+}
+
+#[cfg(feature = "heapless")]
+impl<const N: usize> MaxSize for ::heapless::String<N> {
+	const MAX_SIZE: usize = str_size(N);
+}
+
+#[cfg(feature = "arrayvec")]
+impl<T: MaxSize, const N: usize> MaxSize for ::arrayvec::ArrayVec<T, N> {
+	const MAX_SIZE: usize = seq_overhead(N) + N * T::MAX_SIZE;
+}
+
+#[cfg(feature = "arrayvec")]
+impl<const N: usize> MaxSize for ::arrayvec::ArrayString<N> {
+	const MAX_SIZE: usize = str_size(N);
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(
+		clippy::unwrap_used,
+		clippy::indexing_slicing,
+		clippy::cast_possible_wrap,
+		reason = "Tests"
+	)]
+
+	use super::*;
+	use crate::Config;
+
+	/// Assert that the value is encoded to exactly the maximum size in the worst configuration.
+	fn assert_max<T>(value: &T)
+	where
+		T: MaxSize + ::serde::Serialize,
+	{
+		let config = Config { explicit_option: true, ..Default::default() };
+		let size = crate::serialized_size_with_config(value, config).unwrap();
+		assert_eq!(size, T::MAX_SIZE);
+	}
+
+	/// Assert that the value is encoded to at most the maximum size in the given configuration.
+	fn assert_within<T>(value: &T, config: Config)
+	where
+		T: MaxSize + ::serde::Serialize,
+	{
+		let size = crate::serialized_size_with_config(value, config).unwrap();
+		assert!(size <= T::MAX_SIZE, "{size} > {}", T::MAX_SIZE);
+	}
+
+	#[test]
+	fn varint_sizes_are_correct() {
+		assert_eq!(varint_size(0), 1);
+		assert_eq!(varint_size(0x7F), 1);
+		assert_eq!(varint_size(0x80), 2);
+		assert_eq!(varint_size(0x3FFF), 2);
+		assert_eq!(varint_size(0x4000), 3);
+		assert_eq!(varint_size(usize::MAX), usize::MAX_BYTES);
+		assert_eq!(key_size("a", 0), 3);
+		assert_eq!(key_size("", 200), 3);
+		assert_eq!(seq_overhead(0), 2);
+		assert_eq!(seq_overhead(200), 3);
+	}
+
+	#[test]
+	fn sizes_are_upper_bounds() {
+		use ::serde_bytes::Bytes;
+
+		let configs = [
+			Config::default(),
+			Config { compact_ints: true, ..Default::default() },
+			Config { use_indices: true, explicit_option: true, ..Default::default() },
+		];
+		// Xorshift, to cover integers of all magnitudes without a dependency.
+		let mut state = 0x2545_F491_4F6C_DD1D_u64;
+		let mut random = move || {
+			state ^= state << 13;
+			state ^= state >> 7;
+			state ^= state << 17;
+			state
+		};
+
+		let data = [b'a'; 16_000];
+		for config in configs {
+			for _ in 0 .. 1000 {
+				let value = random() >> (random() % 64);
+				assert_within(&value, config);
+				assert_within(&(value as u32), config);
+				assert_within(&(value as u8), config);
+				assert_within(&(value as i64), config);
+				assert_within(&(value as i16), config);
+				assert_within(&(u128::from(value) << 64 | u128::from(random())), config);
+
+				let len = (value % 16_000) as usize;
+				let str = ::core::str::from_utf8(&data[.. len]).unwrap();
+				assert!(
+					crate::serialized_size_with_config(&str, config).unwrap()
+						<= str_size(str.len())
+				);
+				let bytes = Bytes::new(&data[.. len]);
+				assert!(
+					crate::serialized_size_with_config(&bytes, config).unwrap() <= bytes_size(len)
+				);
+			}
+		}
+	}
+
+	#[test]
+	fn primitives_reach_max_size() {
+		assert_max(&u8::MAX);
+		assert_max(&u16::MAX);
+		assert_max(&u32::MAX);
+		assert_max(&u64::MAX);
+		assert_max(&u128::MAX);
+		assert_max(&i8::MIN);
+		assert_max(&i16::MIN);
+		assert_max(&i32::MIN);
+		assert_max(&i64::MIN);
+		assert_max(&i128::MIN);
+		assert_max(&::core::num::NonZeroU32::MAX);
+		assert_max(&1.5_f32);
+		assert_max(&1.5_f64);
+		assert_max(&true);
+		assert_max(&'\u{10FFFF}');
+		assert_max(&());
+		assert_max(&Some(u16::MAX));
+		assert_max(&[i32::MIN; 3]);
+		assert_max(&(u8::MAX, Some(false), [(); 0]));
+		assert_max(&Result::<u8, i64>::Err(i64::MIN));
+		assert_max(&&u64::MAX);
+	}
+}
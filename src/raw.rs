@@ -0,0 +1,361 @@
+//! Raw values, which capture the encoded bytes of a value without decoding it.
+//!
+//! A [RawValue] as struct field (with `#[serde(borrow)]`) skips over its value when deserializing
+//! and keeps the bytes borrowed from the input. Skipping walks the wire format only, so strings
+//! are neither copied nor validated as UTF-8. This makes indexing large data, e.g. memory-mapped
+//! files, cheap, while the values that are actually needed are decoded later with
+//! [RawValue::deserialize_as].
+//!
+//! ```rust
+//! # #[cfg(feature = "alloc")] {
+//! use serde::{Deserialize, Serialize};
+//! use serde_brief::RawValue;
+//!
+//! #[derive(Serialize)]
+//! struct Entry {
+//! 	id: u32,
+//! 	payload: Vec<String>,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct LazyEntry<'a> {
+//! 	id: u32,
+//! 	#[serde(borrow)]
+//! 	payload: RawValue<'a>,
+//! }
+//!
+//! let mut buffer = [0; 64];
+//! let entry = Entry { id: 5, payload: vec!["large".to_owned(), "data".to_owned()] };
+//! let bytes = serde_brief::to_slice(&entry, &mut buffer).unwrap();
+//!
+//! let lazy: LazyEntry = serde_brief::from_slice(bytes).unwrap();
+//! assert_eq!(lazy.id, 5);
+//! let payload: Vec<&str> = lazy.payload.deserialize_as().unwrap();
+//! assert_eq!(payload, ["large", "data"]);
+//! # }
+//! ```
+//!
+//! The bytes can only be borrowed from inputs that are a contiguous slice, i.e. deserializing with
+//! [from_slice](crate::from_slice) and similar. Other inputs return an error for raw values.
+//!
+//! Raw values are self-contained, unless the data was serialized with `key_dictionary`, where
+//! they may refer to keys that were defined before them.
+//!
+//! A [MapReader] looks up single entries of an encoded map in the same way, without decoding the
+//! other entries or building a [Value](crate::value::Value). [find_path] follows a path of keys
+//! and indices through nested maps and sequences, see also [get_path](crate::get_path).
+
+use ::core::marker::PhantomData;
+use ::serde::{de::Visitor, Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::{
+	format::{decode_length, Type, VarInt},
+	Config, Error, Result,
+};
+
+/// The encoded bytes of a single value, borrowed from the input. See the [module
+/// documentation](self).
+///
+/// Serializing it with this crate's serializer writes the bytes as they are. Other serializers see
+/// a newtype struct around the bytes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RawValue<'a> {
+	/// The encoded value.
+	bytes: &'a [u8],
+}
+
+impl<'a> RawValue<'a> {
+	/// Create a raw value from the given encoded bytes. Returns an error if they are not exactly
+	/// one value, without validating strings as UTF-8.
+	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+	pub fn from_slice(bytes: &'a [u8]) -> Result<Self> {
+		let mut de = crate::Deserializer::new(bytes);
+		de.skip_value()?;
+		de.end()?;
+		Ok(Self { bytes })
+	}
+
+	/// Get the encoded bytes of the value.
+	#[must_use]
+	pub const fn as_bytes(&self) -> &'a [u8] {
+		self.bytes
+	}
+
+	/// Decode the value as the given type.
+	pub fn deserialize_as<T>(&self) -> Result<T>
+	where
+		T: Deserialize<'a>,
+	{
+		crate::from_slice(self.bytes)
+	}
+
+	/// Decode the value as the given type using the given configuration.
+	pub fn deserialize_as_with_config<T>(&self, config: Config) -> Result<T>
+	where
+		T: Deserialize<'a>,
+	{
+		crate::from_slice_with_config(self.bytes, config)
+	}
+}
+
+impl Serialize for RawValue<'_> {
+	fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer
+			.serialize_newtype_struct(crate::format::RAW_VALUE_NEWTYPE_NAME, &RawBytes(self.bytes))
+	}
+}
+
+/// The bytes of a raw value, serialized as bytes.
+pub(crate) struct RawBytes<'a>(pub(crate) &'a [u8]);
+
+impl Serialize for RawBytes<'_> {
+	fn serialize<S>(&self, serializer: S) -> ::core::result::Result<S::Ok, S::Error>
+	where
+		S: Serializer,
+	{
+		serializer.serialize_bytes(self.0)
+	}
+}
+
+impl<'de: 'a, 'a> Deserialize<'de> for RawValue<'a> {
+	fn deserialize<D>(deserializer: D) -> ::core::result::Result<Self, D::Error>
+	where
+		D: Deserializer<'de>,
+	{
+		deserializer.deserialize_newtype_struct(
+			crate::format::RAW_VALUE_NEWTYPE_NAME,
+			RawVisitor(PhantomData),
+		)
+	}
+}
+
+/// Visitor for [RawValue], which only accepts bytes borrowed from the input.
+struct RawVisitor<'a>(PhantomData<RawValue<'a>>);
+
+impl<'de: 'a, 'a> Visitor<'de> for RawVisitor<'a> {
+	type Value = RawValue<'a>;
+
+	fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+		formatter.write_str("a raw value borrowed from the input")
+	}
+
+	fn visit_borrowed_bytes<E>(self, v: &'de [u8]) -> ::core::result::Result<Self::Value, E>
+	where
+		E: ::serde::de::Error,
+	{
+		Ok(RawValue { bytes: v })
+	}
+}
+
+/// Skip the next value of the input and return its encoded bytes.
+fn skip_value<'de>(input: &mut &'de [u8]) -> Result<&'de [u8]> {
+	let mut de = crate::Deserializer::new(*input);
+	let span = de.skip_value_span()?.unwrap_or_default();
+	*input = de.into_input();
+	Ok(span)
+}
+
+/// Read-only access to the entries of an encoded map (or struct), borrowed from a slice. Entries
+/// are found by walking the wire format, so looking up a key is `O(n)` in the size of the map, but
+/// does not allocate and does not decode the other entries. Only works on slices, e.g.
+/// memory-mapped files.
+///
+/// Data serialized with `key_dictionary` is not supported, as keys refer to definitions outside of
+/// the map.
+///
+/// ```rust
+/// use serde::Serialize;
+/// use serde_brief::raw::MapReader;
+///
+/// #[derive(Serialize)]
+/// struct Record<'a> {
+/// 	id: u32,
+/// 	inner: Inner<'a>,
+/// }
+///
+/// #[derive(Serialize)]
+/// struct Inner<'a> {
+/// 	name: &'a str,
+/// }
+///
+/// let mut buffer = [0; 64];
+/// let record = Record { id: 7, inner: Inner { name: "nested" } };
+/// let bytes = serde_brief::to_slice(&record, &mut buffer).unwrap();
+///
+/// let inner = MapReader::new(bytes).unwrap().get("inner").unwrap().unwrap();
+/// let name = MapReader::new(inner).unwrap().get("name").unwrap().unwrap();
+/// assert_eq!(serde_brief::from_slice::<&str>(name).unwrap(), "nested");
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MapReader<'de> {
+	/// The encoded entries, following the map start.
+	entries: &'de [u8],
+}
+
+impl<'de> MapReader<'de> {
+	/// Create a map reader over the encoded map at the start of the given bytes. Only the type of
+	/// the value is checked, the entries are read lazily.
+	pub fn new(bytes: &'de [u8]) -> Result<Self> {
+		let (&byte, entries) = bytes.split_first().ok_or_else(|| Error::UnexpectedEnd)?;
+		let t = Type::try_from(byte)?;
+		if t != Type::MapStart {
+			return Err(Error::WrongType(t, &[Type::MapStart]));
+		}
+		Ok(Self { entries })
+	}
+
+	/// Iterate over the encoded keys and values of the map.
+	#[must_use]
+	pub const fn entries(&self) -> MapEntries<'de> {
+		MapEntries { remaining: self.entries, done: false }
+	}
+
+	/// Return the encoded value of the first entry with the given string key, if there is one.
+	/// Keys of other types, e.g. field indices of `use_indices`, never match.
+	pub fn get(&self, key: &str) -> Result<Option<&'de [u8]>> {
+		for entry in self.entries() {
+			let (encoded_key, value) = entry?;
+			if string_content(encoded_key)? == Some(key.as_bytes()) {
+				return Ok(Some(value));
+			}
+		}
+		Ok(None)
+	}
+}
+
+/// Return the content of an encoded string, or `None` for other types.
+fn string_content(mut encoded: &[u8]) -> Result<Option<&[u8]>> {
+	let Some((&byte, rest)) = encoded.split_first() else {
+		return Ok(None);
+	};
+	if byte != u8::from(Type::String) && byte != u8::from(Type::StringDef) {
+		return Ok(None);
+	}
+	encoded = rest;
+	let len = decode_length(&mut encoded)?;
+	Ok(encoded.get(.. len))
+}
+
+/// Iterator over the encoded keys and values of a map, see [MapReader::entries]. Stops after the
+/// first error.
+#[derive(Debug, Clone)]
+pub struct MapEntries<'de> {
+	/// The remaining encoded entries, including the map end.
+	remaining: &'de [u8],
+	/// Whether the end of the map or an error was reached.
+	done: bool,
+}
+
+impl<'de> MapEntries<'de> {
+	/// Read the next key and value.
+	fn read_entry(&mut self) -> Result<Option<(&'de [u8], &'de [u8])>> {
+		let (&byte, rest) = self.remaining.split_first().ok_or_else(|| Error::UnexpectedEnd)?;
+		if byte == u8::from(Type::MapEnd) {
+			self.remaining = rest;
+			return Ok(None);
+		}
+
+		let key = skip_value(&mut self.remaining)?;
+		let value = skip_value(&mut self.remaining)?;
+		Ok(Some((key, value)))
+	}
+}
+
+impl<'de> Iterator for MapEntries<'de> {
+	type Item = Result<(&'de [u8], &'de [u8])>;
+
+	fn next(&mut self) -> Option<Self::Item> {
+		if self.done {
+			return None;
+		}
+
+		let entry = self.read_entry();
+		if !matches!(entry, Ok(Some(_))) {
+			self.done = true;
+		}
+		entry.transpose()
+	}
+}
+
+impl ::core::iter::FusedIterator for MapEntries<'_> {}
+
+/// Find the encoded value at the given path of map keys and sequence indices, by walking the wire
+/// format without decoding anything else. Components match string keys, unsigned integer keys
+/// (e.g. field indices of `use_indices`) and sequence positions. `Some` markers are followed
+/// transparently and enum variants are addressed by their name (or index), like map keys.
+///
+/// Returns `None` if a component does not exist or addresses into a value that is neither a map
+/// nor a sequence. An empty path returns the given bytes. Data serialized with `key_dictionary`
+/// is not supported, see [MapReader].
+pub fn find_path<'de>(bytes: &'de [u8], path: &[&str]) -> Result<Option<&'de [u8]>> {
+	let mut current = bytes;
+	for component in path {
+		let Some(child) = find_child(current, component)? else {
+			return Ok(None);
+		};
+		current = child;
+	}
+	Ok(Some(current))
+}
+
+/// Find the encoded value of the map entry or sequence element addressed by the path component.
+fn find_child<'de>(mut node: &'de [u8], component: &str) -> Result<Option<&'de [u8]>> {
+	while node.first() == Some(&u8::from(Type::Some)) {
+		node = node.get(1 ..).unwrap_or_default();
+	}
+	let (&byte, mut rest) = node.split_first().ok_or_else(|| Error::UnexpectedEnd)?;
+	let index = component.parse::<u64>().ok();
+
+	match Type::try_from(byte)? {
+		Type::MapStart => {
+			for entry in MapReader::new(node)?.entries() {
+				let (key, value) = entry?;
+				let matches = match string_content(key)? {
+					Some(content) => content == component.as_bytes(),
+					None => index.is_some() && unsigned_key(key) == index,
+				};
+				if matches {
+					return Ok(Some(value));
+				}
+			}
+			Ok(None)
+		}
+		Type::SeqStart => {
+			let Some(index) = index else {
+				return Ok(None);
+			};
+			let mut position = 0;
+			while rest.first().ok_or_else(|| Error::UnexpectedEnd)? != &u8::from(Type::SeqEnd) {
+				let element = skip_value(&mut rest)?;
+				if position == index {
+					return Ok(Some(element));
+				}
+				position += 1;
+			}
+			Ok(None)
+		}
+		Type::SeqFixed => {
+			let len = u64::decode(&mut rest)?;
+			let Some(index) = index.filter(|index| *index < len) else {
+				return Ok(None);
+			};
+			for _ in 0 .. index {
+				skip_value(&mut rest)?;
+			}
+			skip_value(&mut rest).map(Some)
+		}
+		_ => Ok(None),
+	}
+}
+
+/// Return the value of an encoded unsigned integer key, or `None` for other types.
+fn unsigned_key(encoded: &[u8]) -> Option<u64> {
+	let &byte = encoded.first()?;
+	if Type::try_from(byte).ok()? != Type::UnsignedInt {
+		return None;
+	}
+	crate::from_slice(encoded).ok()
+}
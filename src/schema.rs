@@ -0,0 +1,724 @@
+//! Description of the encoded layout of types, e.g. to document the format for other languages.
+//!
+//! The schema is recorded from sample values with [schema_of], following the same serde calls as
+//! the [Serializer](crate::Serializer). Only what the samples contain can be recorded: `None`,
+//! empty sequences and maps have an element type of [Schema::Any], skipped fields are missing and
+//! enums only contain the variants that were seen. Schemas of multiple samples can be combined
+//! with [Schema::merge].
+//!
+//! The schema implements `Serialize`, so it can be written to JSON and shared:
+//!
+//! ```rust
+//! use serde::Serialize;
+//! use serde_brief::schema::{schema_of, Member, Schema};
+//!
+//! #[derive(Serialize)]
+//! struct Point {
+//! 	x: i32,
+//! 	label: Option<String>,
+//! }
+//!
+//! let schema = schema_of(&Point { x: 1, label: Some("origin".to_owned()) }).unwrap();
+//! assert_eq!(
+//! 	schema,
+//! 	Schema::Struct {
+//! 		name: "Point".to_owned(),
+//! 		fields: vec![
+//! 			Member { name: "x".to_owned(), index: 0, schema: Schema::Int },
+//! 			Member {
+//! 				name: "label".to_owned(),
+//! 				index: 1,
+//! 				schema: Schema::Option(Box::new(Schema::String)),
+//! 			},
+//! 		],
+//! 	}
+//! );
+//! ```
+
+use ::alloc::{borrow::ToOwned, boxed::Box, string::String, vec::Vec};
+use ::serde::{ser::SerializeStruct, Serialize};
+
+use crate::{
+	ser::FloatBits,
+	value::{Float, Integer, Value},
+	Error, Result,
+};
+
+/// Layout of an encoded value.
+///
+/// Structs are maps from their field names (or indices with `use_indices`) to the values. Enum
+/// variants are encoded as their name (or index) for unit variants and as a map with a single
+/// entry from the name to the content otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Schema {
+	/// Any value, because no sample contained a value or the samples had different layouts.
+	Any,
+	/// `Null`, e.g. unit or unit structs.
+	Null,
+	/// Boolean.
+	Bool,
+	/// Unsigned integer of any size.
+	UInt,
+	/// Signed integer of any size.
+	Int,
+	/// 16-bit float.
+	F16,
+	/// 32-bit float.
+	F32,
+	/// 64-bit float.
+	F64,
+	/// 128-bit float.
+	F128,
+	/// Bytes.
+	Bytes,
+	/// UTF-8 string.
+	String,
+	/// Optional value. `None` is `Null`, `Some` is the value itself, optionally preceded by a
+	/// marker with `explicit_option`.
+	Option(Box<Schema>),
+	/// Sequence of any length with values of the same layout.
+	Seq(Box<Schema>),
+	/// Sequence of fixed length, e.g. tuples, tuple structs and arrays.
+	Tuple(Vec<Schema>),
+	/// Map with keys and values of the same layout each.
+	Map {
+		/// Layout of the keys.
+		key: Box<Schema>,
+		/// Layout of the values.
+		value: Box<Schema>,
+	},
+	/// Struct with named fields.
+	Struct {
+		/// Name of the struct.
+		name: String,
+		/// The fields in the order they are encoded.
+		fields: Vec<Member>,
+	},
+	/// Enum with variants. Struct variants are described as [Schema::Struct] named after the
+	/// variant, unit variants as [Schema::Null].
+	Enum {
+		/// Name of the enum.
+		name: String,
+		/// The seen variants.
+		variants: Vec<Member>,
+	},
+}
+
+/// Field of a struct or variant of an enum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Member {
+	/// Name, used as key by default.
+	pub name: String,
+	/// Index, used as key with `use_indices`.
+	pub index: u32,
+	/// Layout of the value.
+	pub schema: Schema,
+}
+
+/// Record the schema of the given sample value.
+pub fn schema_of<T>(value: &T) -> Result<Schema>
+where
+	T: ?Sized + Serialize,
+{
+	value.serialize(SchemaSerializer)
+}
+
+impl Schema {
+	/// Combine the schemas of two samples of the same type. Sequences, maps and options merge
+	/// their contents, structs and enums merge their members by name. Different layouts become
+	/// [Schema::Any].
+	#[must_use]
+	pub fn merge(self, other: Self) -> Self {
+		match (self, other) {
+			(Schema::Any, schema) | (schema, Schema::Any) => schema,
+			(a, b) if a == b => a,
+			(Schema::Option(a), Schema::Option(b)) => Schema::Option(Box::new(a.merge(*b))),
+			(Schema::Seq(a), Schema::Seq(b)) => Schema::Seq(Box::new(a.merge(*b))),
+			(Schema::Tuple(a), Schema::Tuple(b)) if a.len() == b.len() => {
+				Schema::Tuple(a.into_iter().zip(b).map(|(a, b)| a.merge(b)).collect())
+			}
+			(
+				Schema::Map { key: key_a, value: value_a },
+				Schema::Map { key: key_b, value: value_b },
+			) => Schema::Map {
+				key: Box::new(key_a.merge(*key_b)),
+				value: Box::new(value_a.merge(*value_b)),
+			},
+			(Schema::Struct { name, fields: a }, Schema::Struct { name: name_b, fields: b })
+				if name == name_b =>
+			{
+				Schema::Struct { name, fields: merge_members(a, b) }
+			}
+			(Schema::Enum { name, variants: a }, Schema::Enum { name: name_b, variants: b })
+				if name == name_b =>
+			{
+				Schema::Enum { name, variants: merge_members(a, b) }
+			}
+			_ => Schema::Any,
+		}
+	}
+}
+
+/// Merge members with the same name and append the others.
+fn merge_members(mut members: Vec<Member>, others: Vec<Member>) -> Vec<Member> {
+	for other in others {
+		match members.iter_mut().find(|member| member.name == other.name) {
+			Some(member) => {
+				member.schema =
+					::core::mem::replace(&mut member.schema, Schema::Any).merge(other.schema);
+			}
+			None => members.push(other),
+		}
+	}
+	members
+}
+
+/// Merge the schemas of all items, [Schema::Any] if there are none.
+fn merge_all<'a, I>(values: I) -> Schema
+where
+	I: IntoIterator<Item = &'a Value<'a>>,
+{
+	values.into_iter().map(Schema::from).fold(Schema::Any, Schema::merge)
+}
+
+/// The schema of a [Value]. Values do not know about structs and enums, so they are described as
+/// maps and strings or integers.
+impl<'a> From<&Value<'a>> for Schema {
+	fn from(value: &Value<'a>) -> Self {
+		match value {
+			Value::Null => Schema::Null,
+			Value::Bool(_) => Schema::Bool,
+			Value::Integer(Integer::Unsigned(_)) => Schema::UInt,
+			Value::Integer(Integer::Signed(_)) => Schema::Int,
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) if int.is_signed() => Schema::Int,
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(_) => Schema::UInt,
+			Value::Float(Float::F16Bits(_)) => Schema::F16,
+			Value::Float(Float::F32(_)) => Schema::F32,
+			Value::Float(Float::F64(_)) => Schema::F64,
+			Value::Float(Float::F128Bits(_)) => Schema::F128,
+			Value::Bytes(_) => Schema::Bytes,
+			Value::String(_) => Schema::String,
+			Value::Array(values) => Schema::Seq(Box::new(merge_all(values))),
+			Value::Map(entries) => Schema::Map {
+				key: Box::new(merge_all(entries.iter().map(|(key, _)| key))),
+				value: Box::new(merge_all(entries.iter().map(|(_, value)| value))),
+			},
+		}
+	}
+}
+
+impl Serialize for Schema {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		use ::serde::ser::SerializeStructVariant;
+
+		/// Name of the enum.
+		const NAME: &str = "Schema";
+
+		match self {
+			Schema::Any => serializer.serialize_unit_variant(NAME, 0, "Any"),
+			Schema::Null => serializer.serialize_unit_variant(NAME, 1, "Null"),
+			Schema::Bool => serializer.serialize_unit_variant(NAME, 2, "Bool"),
+			Schema::UInt => serializer.serialize_unit_variant(NAME, 3, "UInt"),
+			Schema::Int => serializer.serialize_unit_variant(NAME, 4, "Int"),
+			Schema::F16 => serializer.serialize_unit_variant(NAME, 5, "F16"),
+			Schema::F32 => serializer.serialize_unit_variant(NAME, 6, "F32"),
+			Schema::F64 => serializer.serialize_unit_variant(NAME, 7, "F64"),
+			Schema::F128 => serializer.serialize_unit_variant(NAME, 8, "F128"),
+			Schema::Bytes => serializer.serialize_unit_variant(NAME, 9, "Bytes"),
+			Schema::String => serializer.serialize_unit_variant(NAME, 10, "String"),
+			Schema::Option(schema) => {
+				serializer.serialize_newtype_variant(NAME, 11, "Option", schema)
+			}
+			Schema::Seq(schema) => serializer.serialize_newtype_variant(NAME, 12, "Seq", schema),
+			Schema::Tuple(schemas) => {
+				serializer.serialize_newtype_variant(NAME, 13, "Tuple", schemas)
+			}
+			Schema::Map { key, value } => {
+				let mut map = serializer.serialize_struct_variant(NAME, 14, "Map", 2)?;
+				map.serialize_field("key", key)?;
+				map.serialize_field("value", value)?;
+				map.end()
+			}
+			Schema::Struct { name, fields } => {
+				let mut map = serializer.serialize_struct_variant(NAME, 15, "Struct", 2)?;
+				map.serialize_field("name", name)?;
+				map.serialize_field("fields", fields)?;
+				map.end()
+			}
+			Schema::Enum { name, variants } => {
+				let mut map = serializer.serialize_struct_variant(NAME, 16, "Enum", 2)?;
+				map.serialize_field("name", name)?;
+				map.serialize_field("variants", variants)?;
+				map.end()
+			}
+		}
+	}
+}
+
+impl Serialize for Member {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		let mut map = serializer.serialize_struct("Member", 3)?;
+		map.serialize_field("name", &self.name)?;
+		map.serialize_field("index", &self.index)?;
+		map.serialize_field("schema", &self.schema)?;
+		map.end()
+	}
+}
+
+/// Serializer recording the [Schema] of a value.
+#[derive(Debug, Clone, Copy)]
+struct SchemaSerializer;
+
+/// Enum variant that is being serialized.
+#[derive(Debug, Clone, Copy)]
+struct VariantInfo {
+	/// Name of the enum.
+	name: &'static str,
+	/// Index of the variant.
+	index: u32,
+	/// Name of the variant.
+	variant: &'static str,
+}
+
+impl VariantInfo {
+	/// Wrap the schema of the variant's content into an enum with the single variant.
+	fn wrap(self, schema: Schema) -> Schema {
+		Schema::Enum {
+			name: self.name.to_owned(),
+			variants: ::alloc::vec![Member {
+				name: self.variant.to_owned(),
+				index: self.index,
+				schema,
+			}],
+		}
+	}
+}
+
+impl ::serde::Serializer for SchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	type SerializeSeq = SeqSchemaSerializer;
+	type SerializeTuple = TupleSchemaSerializer;
+	type SerializeTupleStruct = TupleSchemaSerializer;
+	type SerializeTupleVariant = TupleSchemaSerializer;
+	type SerializeMap = MapSchemaSerializer;
+	type SerializeStruct = StructSchemaSerializer;
+	type SerializeStructVariant = StructSchemaSerializer;
+
+	#[inline]
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+
+	fn serialize_bool(self, _v: bool) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Bool)
+	}
+
+	fn serialize_i8(self, _v: i8) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Int)
+	}
+
+	fn serialize_i16(self, _v: i16) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Int)
+	}
+
+	fn serialize_i32(self, _v: i32) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Int)
+	}
+
+	fn serialize_i64(self, _v: i64) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Int)
+	}
+
+	fn serialize_i128(self, _v: i128) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Int)
+	}
+
+	fn serialize_u8(self, _v: u8) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::UInt)
+	}
+
+	fn serialize_u16(self, _v: u16) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::UInt)
+	}
+
+	fn serialize_u32(self, _v: u32) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::UInt)
+	}
+
+	fn serialize_u64(self, _v: u64) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::UInt)
+	}
+
+	fn serialize_u128(self, _v: u128) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::UInt)
+	}
+
+	fn serialize_f32(self, _v: f32) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::F32)
+	}
+
+	fn serialize_f64(self, _v: f64) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::F64)
+	}
+
+	fn serialize_char(self, _v: char) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::String)
+	}
+
+	fn serialize_str(self, _v: &str) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::String)
+	}
+
+	fn serialize_bytes(self, _v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Bytes)
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Option(Box::new(Schema::Any)))
+	}
+
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(Schema::Option(Box::new(value.serialize(self)?)))
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Null)
+	}
+
+	fn serialize_unit_struct(self, _name: &'static str) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Null)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		Ok(VariantInfo { name, index: variant_index, variant }.wrap(Schema::Null))
+	}
+
+	fn serialize_newtype_struct<T>(
+		self,
+		name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		match crate::ser::float_bits(name, value) {
+			Some(FloatBits::F16(_)) => Ok(Schema::F16),
+			Some(FloatBits::F128(_)) => Ok(Schema::F128),
+			None => value.serialize(self),
+		}
+	}
+
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Ok(VariantInfo { name, index: variant_index, variant }.wrap(value.serialize(self)?))
+	}
+
+	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		Ok(SeqSchemaSerializer { element: Schema::Any })
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		Ok(TupleSchemaSerializer { elements: Vec::with_capacity(len), variant: None })
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		_name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		Ok(TupleSchemaSerializer { elements: Vec::with_capacity(len), variant: None })
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		Ok(TupleSchemaSerializer {
+			elements: Vec::with_capacity(len),
+			variant: Some(VariantInfo { name, index: variant_index, variant }),
+		})
+	}
+
+	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		Ok(MapSchemaSerializer { key: Schema::Any, value: Schema::Any })
+	}
+
+	fn serialize_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStruct, Self::Error> {
+		Ok(StructSchemaSerializer {
+			name,
+			fields: Vec::with_capacity(len),
+			field_index: 0,
+			variant: None,
+		})
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		Ok(StructSchemaSerializer {
+			name: variant,
+			fields: Vec::with_capacity(len),
+			field_index: 0,
+			variant: Some(VariantInfo { name, index: variant_index, variant }),
+		})
+	}
+
+	fn collect_str<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + ::core::fmt::Display,
+	{
+		Ok(Schema::String)
+	}
+}
+
+/// Recorder of sequences, merging the schemas of all elements.
+#[derive(Debug)]
+struct SeqSchemaSerializer {
+	/// Merged schema of the elements so far.
+	element: Schema,
+}
+
+impl ::serde::ser::SerializeSeq for SeqSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		let element = value.serialize(SchemaSerializer)?;
+		self.element = ::core::mem::replace(&mut self.element, Schema::Any).merge(element);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Seq(Box::new(self.element)))
+	}
+}
+
+/// Recorder of tuples, tuple structs and tuple variants.
+#[derive(Debug)]
+struct TupleSchemaSerializer {
+	/// Schemas of the elements so far.
+	elements: Vec<Schema>,
+	/// The variant, if this is a tuple variant.
+	variant: Option<VariantInfo>,
+}
+
+impl TupleSchemaSerializer {
+	/// Record the next element.
+	fn push<T>(&mut self, value: &T) -> Result<()>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.elements.push(value.serialize(SchemaSerializer)?);
+		Ok(())
+	}
+
+	/// Finish the tuple, wrapped into the variant if there is one.
+	fn finish(self) -> Schema {
+		let schema = Schema::Tuple(self.elements);
+		match self.variant {
+			Some(variant) => variant.wrap(schema),
+			None => schema,
+		}
+	}
+}
+
+impl ::serde::ser::SerializeTuple for TupleSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_element<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.push(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.finish())
+	}
+}
+
+impl ::serde::ser::SerializeTupleStruct for TupleSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.push(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.finish())
+	}
+}
+
+impl ::serde::ser::SerializeTupleVariant for TupleSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.push(value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.finish())
+	}
+}
+
+/// Recorder of maps, merging the schemas of all keys and values.
+#[derive(Debug)]
+struct MapSchemaSerializer {
+	/// Merged schema of the keys so far.
+	key: Schema,
+	/// Merged schema of the values so far.
+	value: Schema,
+}
+
+impl ::serde::ser::SerializeMap for MapSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_key<T>(&mut self, key: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		let key = key.serialize(SchemaSerializer)?;
+		self.key = ::core::mem::replace(&mut self.key, Schema::Any).merge(key);
+		Ok(())
+	}
+
+	fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		let value = value.serialize(SchemaSerializer)?;
+		self.value = ::core::mem::replace(&mut self.value, Schema::Any).merge(value);
+		Ok(())
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(Schema::Map { key: Box::new(self.key), value: Box::new(self.value) })
+	}
+}
+
+/// Recorder of structs and struct variants.
+#[derive(Debug)]
+struct StructSchemaSerializer {
+	/// Name of the struct or variant.
+	name: &'static str,
+	/// The fields so far.
+	fields: Vec<Member>,
+	/// The current field index.
+	field_index: u32,
+	/// The variant, if this is a struct variant.
+	variant: Option<VariantInfo>,
+}
+
+impl StructSchemaSerializer {
+	/// Record the next field.
+	fn push<T>(&mut self, key: &'static str, value: &T) -> Result<()>
+	where
+		T: ?Sized + Serialize,
+	{
+		let schema = value.serialize(SchemaSerializer)?;
+		self.fields.push(Member { name: key.to_owned(), index: self.field_index, schema });
+		self.field_index += 1;
+		Ok(())
+	}
+
+	/// Finish the struct, wrapped into the variant if there is one.
+	fn finish(self) -> Schema {
+		let schema = Schema::Struct { name: self.name.to_owned(), fields: self.fields };
+		match self.variant {
+			Some(variant) => variant.wrap(schema),
+			None => schema,
+		}
+	}
+}
+
+impl SerializeStruct for StructSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.push(key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.finish())
+	}
+
+	fn skip_field(&mut self, _key: &'static str) -> Result<(), Self::Error> {
+		self.field_index += 1;
+		Ok(())
+	}
+}
+
+impl ::serde::ser::SerializeStructVariant for StructSchemaSerializer {
+	type Ok = Schema;
+	type Error = Error;
+
+	fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.push(key, value)
+	}
+
+	fn end(self) -> Result<Self::Ok, Self::Error> {
+		Ok(self.finish())
+	}
+
+	fn skip_field(&mut self, _key: &'static str) -> Result<(), Self::Error> {
+		self.field_index += 1;
+		Ok(())
+	}
+}
@@ -4,10 +4,17 @@
 	allow(clippy::used_underscore_binding, reason = "Only used in tracing::instrument")
 )]
 
+#[cfg(feature = "alloc")]
+use ::alloc::{
+	boxed::Box,
+	collections::{BTreeMap, BTreeSet},
+	string::ToString,
+	vec::Vec,
+};
 use ::serde::Serialize;
 
 use crate::{
-	format::{Type, VarInt},
+	format::{encode_length, Type, VarInt},
 	io::Output,
 	Config, Error,
 };
@@ -19,6 +26,41 @@ pub struct Serializer<O> {
 	output: O,
 	/// Serialize enum variants and struct fields by index instead of name-string.
 	use_indices: bool,
+	/// Write the `Some` marker before the values of options.
+	explicit_option: bool,
+	/// Whether to write small unsigned integers inline in the type byte.
+	compact_ints: bool,
+	/// Whether to write the number of elements of sequences with known length.
+	length_prefixed_seqs: bool,
+	/// Whether to report being human-readable to the serialized types.
+	human_readable: bool,
+	/// Names of the tag fields of internally tagged enums, which keep their name as key.
+	tag_fields: &'static [&'static str],
+	/// Whether to track the nesting of sequences and maps to detect unbalanced output.
+	debug_checks: bool,
+	/// Number of currently open sequences and maps, when `debug_checks` is enabled.
+	open_collections: usize,
+	/// Whether to sort map entries by their serialized keys.
+	#[cfg(feature = "alloc")]
+	canonical: bool,
+	/// Buffered entries of the currently open maps, when `canonical` is enabled.
+	#[cfg(feature = "alloc")]
+	entries: Vec<Vec<(Vec<u8>, Vec<u8>)>>,
+	/// Whether to write string keys once and refer to them by their number afterwards.
+	#[cfg(feature = "alloc")]
+	key_dictionary: bool,
+	/// Whether the next string is a key, when `key_dictionary` is enabled.
+	#[cfg(feature = "alloc")]
+	writing_key: bool,
+	/// Numbers of the keys in the string table, when `key_dictionary` is enabled.
+	#[cfg(feature = "alloc")]
+	key_numbers: BTreeMap<Box<str>, usize>,
+	/// Whether to return an error on duplicate keys in a map.
+	#[cfg(feature = "alloc")]
+	detect_duplicate_keys: bool,
+	/// Encoded keys of the currently open maps, when `detect_duplicate_keys` is enabled.
+	#[cfg(feature = "alloc")]
+	seen_keys: Vec<BTreeSet<Vec<u8>>>,
 }
 
 impl<O> Serializer<O> {
@@ -29,7 +71,54 @@ impl<O> Serializer<O> {
 		// Same bounds as `serde::Serializer` impl.
 		O: Output,
 	{
-		Self { output, use_indices: Config::default().use_indices }
+		Self {
+			output,
+			use_indices: Config::default().use_indices,
+			explicit_option: Config::default().explicit_option,
+			compact_ints: false,
+			length_prefixed_seqs: false,
+			human_readable: false,
+			tag_fields: &[],
+			debug_checks: false,
+			open_collections: 0,
+			#[cfg(feature = "alloc")]
+			canonical: false,
+			#[cfg(feature = "alloc")]
+			entries: Vec::new(),
+			#[cfg(feature = "alloc")]
+			key_dictionary: false,
+			#[cfg(feature = "alloc")]
+			writing_key: false,
+			#[cfg(feature = "alloc")]
+			key_numbers: BTreeMap::new(),
+			#[cfg(feature = "alloc")]
+			detect_duplicate_keys: false,
+			#[cfg(feature = "alloc")]
+			seen_keys: Vec::new(),
+		}
+	}
+
+	/// Use the serialization flags of the given configuration, e.g. `use_indices` and
+	/// `compact_ints`. `max_size` is not applied, wrap the output in a
+	/// [SizeLimit](crate::SizeLimit) for that.
+	#[must_use]
+	pub const fn with_config(self, config: Config) -> Self {
+		let serializer = self
+			.use_indices(config.use_indices)
+			.explicit_option(config.explicit_option)
+			.compact_ints(config.compact_ints)
+			.length_prefixed_seqs(config.length_prefixed_seqs)
+			.human_readable(match config.human_readable {
+				Some(human_readable) => human_readable,
+				None => false,
+			})
+			.tag_fields(config.tag_fields);
+		#[cfg(feature = "alloc")]
+		let serializer = serializer
+			.canonical(config.canonical)
+			.key_dictionary(config.key_dictionary)
+			.detect_duplicate_keys(config.detect_duplicate_keys);
+		serializer
 	}
 
 	/// Set whether to use indices instead of names for enum variants and struct fields.
@@ -39,11 +128,386 @@ impl<O> Serializer<O> {
 		self
 	}
 
-	/// Consume the serializer to get the output back.
+	/// Set whether to write a marker before the values of `Some` options, so that nested options
+	/// can be distinguished.
+	#[must_use]
+	pub const fn explicit_option(mut self, explicit_option: bool) -> Self {
+		self.explicit_option = explicit_option;
+		self
+	}
+
+	/// Set whether to write unsigned integers up to 63 inline in the type byte, so that they take a
+	/// single byte. Older versions cannot read this. Disabled by default.
+	#[must_use]
+	pub const fn compact_ints(mut self, compact_ints: bool) -> Self {
+		self.compact_ints = compact_ints;
+		self
+	}
+
+	/// Set whether to write sequences of known length with their number of elements instead of an
+	/// end designator, so that the deserializer knows the length up front. Older versions cannot
+	/// read this. Disabled by default.
+	#[must_use]
+	pub const fn length_prefixed_seqs(mut self, length_prefixed_seqs: bool) -> Self {
+		self.length_prefixed_seqs = length_prefixed_seqs;
+		self
+	}
+
+	/// Set whether types are told that the format is human-readable, so that they choose their
+	/// textual representation. Disabled by default.
+	#[must_use]
+	pub const fn human_readable(mut self, human_readable: bool) -> Self {
+		self.human_readable = human_readable;
+		self
+	}
+
+	/// Set the names of the tag fields of internally tagged enums, which are written with their
+	/// name in the `use_indices` format and do not count towards the field indices. See
+	/// [Config::tag_fields].
+	#[must_use]
+	pub const fn tag_fields(mut self, tag_fields: &'static [&'static str]) -> Self {
+		self.tag_fields = tag_fields;
+		self
+	}
+
+	/// Set whether to sort the entries of maps and structs by their serialized keys, for
+	/// deterministic output. Entries are buffered per map, so this allocates.
+	#[cfg(feature = "alloc")]
+	#[must_use]
+	pub const fn canonical(mut self, canonical: bool) -> Self {
+		self.canonical = canonical;
+		self
+	}
+
+	/// Set whether to write each string key of maps and structs only once and refer to it by its
+	/// number in a string table afterwards. The data must be read by a single deserializer from
+	/// the start and older versions cannot read it. Not applied in canonical mode. Disabled by
+	/// default.
+	#[cfg(feature = "alloc")]
+	#[must_use]
+	pub const fn key_dictionary(mut self, key_dictionary: bool) -> Self {
+		self.key_dictionary = key_dictionary;
+		self
+	}
+
+	/// Set whether to return [Error::DuplicateSerializedKey] when a map or struct contains the same
+	/// key more than once. The encoded keys are recorded per map, so this allocates. Disabled by
+	/// default.
+	#[cfg(feature = "alloc")]
+	#[must_use]
+	pub const fn detect_duplicate_keys(mut self, detect_duplicate_keys: bool) -> Self {
+		self.detect_duplicate_keys = detect_duplicate_keys;
+		self
+	}
+
+	/// Set whether to track the nesting of sequences and maps. When enabled, [finish](Self::finish)
+	/// reports output with unbalanced collections, e.g. when a misbehaving `Serialize`
+	/// implementation does not end a sequence. Disabled by default.
+	#[must_use]
+	pub const fn debug_checks(mut self, debug_checks: bool) -> Self {
+		self.debug_checks = debug_checks;
+		self
+	}
+
+	/// Flush the output, making sure buffered data is pushed to the underlying writer.
+	#[inline]
+	pub fn flush(&mut self) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		self.output.flush()
+	}
+
+	/// Consume the serializer to get the output back. Buffered outputs are not flushed, use
+	/// [flush](Self::flush) before to make sure all data is written.
 	#[inline]
 	pub fn into_output(self) -> O {
 		self.output
 	}
+
+	/// Consume the serializer to get the output back, like [into_output](Self::into_output).
+	/// With [debug_checks](Self::debug_checks) enabled, this returns
+	/// [Error::UnbalancedCollection] if not all sequences and maps were ended.
+	#[inline]
+	pub fn finish(self) -> crate::Result<O> {
+		if self.open_collections != 0 {
+			return Err(Error::UnbalancedCollection);
+		}
+		Ok(self.output)
+	}
+
+	/// Write the start designator of a sequence or map.
+	#[inline]
+	fn write_start(&mut self, t: Type) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		if self.debug_checks {
+			self.open_collections =
+				self.open_collections.checked_add(1).ok_or_else(|| Error::UsizeOverflow)?;
+		}
+		// Only keys that are strings themselves go into the string table.
+		#[cfg(feature = "alloc")]
+		{
+			self.writing_key = false;
+		}
+		self.output.write_byte(t.into())
+	}
+
+	/// Write the end designator of a sequence or map.
+	#[inline]
+	fn write_end(&mut self, t: Type) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		self.close_collection()?;
+		self.output.write_byte(t.into())
+	}
+
+	/// Keep track of a sequence or map being ended, when `debug_checks` is enabled.
+	#[inline]
+	fn close_collection(&mut self) -> crate::Result<()> {
+		if self.debug_checks {
+			self.open_collections =
+				self.open_collections.checked_sub(1).ok_or_else(|| Error::UnbalancedCollection)?;
+		}
+		Ok(())
+	}
+
+	/// Start a sequence, with its number of elements if it is known and length prefixes are
+	/// enabled. For tuple variants, the variant's map is ended together with the sequence.
+	#[inline]
+	fn begin_seq(
+		&mut self,
+		len: Option<usize>,
+		variant: bool,
+	) -> crate::Result<SequenceSerializer<'_, O>>
+	where
+		O: Output,
+	{
+		let remaining = len.filter(|_| self.length_prefixed_seqs);
+		if let Some(len) = remaining {
+			self.write_start(Type::SeqFixed)?;
+			encode_length(len, &mut self.output)?;
+		} else {
+			self.write_start(Type::SeqStart)?;
+		}
+		Ok(SequenceSerializer { serializer: self, remaining, variant })
+	}
+
+	/// Start a map of entries written with [write_key](Self::write_key) and
+	/// [write_value](Self::write_value).
+	#[inline]
+	fn begin_map(&mut self) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		self.write_start(Type::MapStart)?;
+		#[cfg(feature = "alloc")]
+		if self.canonical {
+			self.entries.push(Vec::new());
+		}
+		#[cfg(feature = "alloc")]
+		if self.detect_duplicate_keys {
+			self.seen_keys.push(BTreeSet::new());
+		}
+		Ok(())
+	}
+
+	/// Write the key of a map entry. In canonical mode, it is buffered instead. Otherwise, it is
+	/// recorded while writing it, if duplicate keys are detected.
+	#[inline]
+	fn write_key<T>(&mut self, key: &T) -> crate::Result<()>
+	where
+		O: Output,
+		T: ?Sized + Serialize,
+	{
+		#[cfg(feature = "alloc")]
+		if self.canonical {
+			let key = self.buffered(key)?;
+			if self.detect_duplicate_keys {
+				self.record_key(&key)?;
+			}
+			self.entries
+				.last_mut()
+				.ok_or_else(|| Error::UnbalancedCollection)?
+				.push((key, Vec::new()));
+			return Ok(());
+		}
+		#[cfg(feature = "alloc")]
+		if self.detect_duplicate_keys {
+			let key = self.write_recorded_key(key)?;
+			return self.record_key(&key);
+		}
+		#[cfg(feature = "alloc")]
+		if self.key_dictionary {
+			self.writing_key = true;
+			let result = key.serialize(&mut *self);
+			self.writing_key = false;
+			return result;
+		}
+		key.serialize(self)
+	}
+
+	/// Write the value of a map entry. In canonical mode, it is buffered instead.
+	#[inline]
+	fn write_value<T>(&mut self, value: &T) -> crate::Result<()>
+	where
+		O: Output,
+		T: ?Sized + Serialize,
+	{
+		#[cfg(feature = "alloc")]
+		if self.canonical {
+			let value = self.buffered(value)?;
+			let entry = self.entries.last_mut().and_then(|entries| entries.last_mut());
+			entry.ok_or_else(|| Error::UnbalancedCollection)?.1 = value;
+			return Ok(());
+		}
+		value.serialize(self)
+	}
+
+	/// End a map started with [begin_map](Self::begin_map). In canonical mode, the buffered
+	/// entries are written sorted by their keys first.
+	#[inline]
+	fn end_map(&mut self) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		#[cfg(feature = "alloc")]
+		if self.detect_duplicate_keys {
+			self.seen_keys.pop().ok_or_else(|| Error::UnbalancedCollection)?;
+		}
+		#[cfg(feature = "alloc")]
+		if self.canonical {
+			let mut entries = self.entries.pop().ok_or_else(|| Error::UnbalancedCollection)?;
+			entries.sort_by(|(a, _), (b, _)| a.cmp(b));
+			for (key, value) in entries {
+				self.output.write_all(&key)?;
+				self.output.write_all(&value)?;
+			}
+		}
+		self.write_end(Type::MapEnd)
+	}
+
+	/// Write a map key while recording its encoding, so that it does not need to be serialized
+	/// twice. Keys of the string table are recorded as references to them, so that the first
+	/// occurrence compares equal to the later ones.
+	#[cfg(feature = "alloc")]
+	fn write_recorded_key<T>(&mut self, key: &T) -> crate::Result<Vec<u8>>
+	where
+		O: Output,
+		T: ?Sized + Serialize,
+	{
+		let mut recorder = Serializer {
+			output: KeyRecorder { output: &mut self.output, bytes: Vec::new() },
+			use_indices: self.use_indices,
+			explicit_option: self.explicit_option,
+			compact_ints: self.compact_ints,
+			length_prefixed_seqs: self.length_prefixed_seqs,
+			human_readable: self.human_readable,
+			tag_fields: self.tag_fields,
+			debug_checks: self.debug_checks,
+			open_collections: self.open_collections,
+			canonical: false,
+			entries: Vec::new(),
+			key_dictionary: self.key_dictionary,
+			writing_key: self.key_dictionary,
+			key_numbers: ::core::mem::take(&mut self.key_numbers),
+			detect_duplicate_keys: true,
+			seen_keys: Vec::new(),
+		};
+		let result = key.serialize(&mut recorder);
+		self.open_collections = recorder.open_collections;
+		self.key_numbers = recorder.key_numbers;
+		result?;
+
+		let mut encoded = recorder.output.bytes;
+		if encoded.first() == Some(&Type::StringDef.into()) {
+			encoded.clear();
+			encoded.push(Type::StringRef.into());
+			encode_length(self.key_numbers.len().saturating_sub(1), &mut encoded)?;
+		}
+		Ok(encoded)
+	}
+
+	/// Record the encoded key of the current map, returning [Error::DuplicateSerializedKey] if it
+	/// was seen before.
+	#[cfg(feature = "alloc")]
+	fn record_key(&mut self, encoded: &[u8]) -> crate::Result<()> {
+		let keys = self.seen_keys.last_mut().ok_or_else(|| Error::UnbalancedCollection)?;
+		if keys.contains(encoded) {
+			let key = match encoded.split_first() {
+				Some((&byte, mut number)) if byte == u8::from(Type::StringRef) => {
+					let number = crate::format::decode_length(&mut number)?;
+					self.key_numbers
+						.iter()
+						.find(|(_, n)| **n == number)
+						.map(|(key, _)| crate::value::Value::from(&**key).to_string())
+				}
+				_ => crate::from_slice::<crate::value::Value<'_>>(encoded)
+					.ok()
+					.map(|key| key.to_string()),
+			};
+			let key = key.unwrap_or_else(|| ::alloc::format!("{encoded:02X?}"));
+			return Err(Error::DuplicateSerializedKey(key));
+		}
+		keys.insert(encoded.to_vec());
+		Ok(())
+	}
+
+	/// Write a string key via the string table: the string itself the first time, its number
+	/// afterwards.
+	#[cfg(feature = "alloc")]
+	fn write_table_string(&mut self, s: &str) -> crate::Result<()>
+	where
+		O: Output,
+	{
+		if let Some(number) = self.key_numbers.get(s).copied() {
+			self.output.write_byte(Type::StringRef.into())?;
+			return encode_length(number, &mut self.output);
+		}
+
+		self.key_numbers.insert(s.into(), self.key_numbers.len());
+		self.output.write_byte(Type::StringDef.into())?;
+		s.len().encode(&mut self.output)?;
+		self.output.write_borrowed(s.as_bytes())
+	}
+
+	/// Write an unsigned integer, inline in the type byte if enabled and small enough.
+	#[inline]
+	fn write_unsigned<T>(&mut self, value: T) -> crate::Result<()>
+	where
+		O: Output,
+		T: VarInt + Copy + TryInto<u8>,
+	{
+		if self.compact_ints {
+			if let Some(byte) = crate::format::inline_unsigned_int(value) {
+				return self.output.write_byte(byte);
+			}
+		}
+		self.output.write_byte(Type::UnsignedInt.into())?;
+		value.encode(&mut self.output)
+	}
+
+	/// Serialize a value into a separate buffer with the same settings.
+	#[cfg(feature = "alloc")]
+	fn buffered<T>(&self, value: &T) -> crate::Result<Vec<u8>>
+	where
+		T: ?Sized + Serialize,
+	{
+		let mut serializer = Serializer::new(Vec::new())
+			.use_indices(self.use_indices)
+			.explicit_option(self.explicit_option)
+			.compact_ints(self.compact_ints)
+			.length_prefixed_seqs(self.length_prefixed_seqs)
+			.human_readable(self.human_readable)
+			.tag_fields(self.tag_fields)
+			.canonical(self.canonical)
+			.detect_duplicate_keys(self.detect_duplicate_keys)
+			.debug_checks(self.debug_checks);
+		value.serialize(&mut serializer)?;
+		serializer.finish()
+	}
 }
 
 impl<'a, O> ::serde::Serializer for &'a mut Serializer<O>
@@ -53,17 +517,17 @@ where
 	type Ok = ();
 	type Error = Error;
 
-	type SerializeSeq = Self;
-	type SerializeTuple = Self;
-	type SerializeTupleStruct = Self;
-	type SerializeTupleVariant = Self;
+	type SerializeSeq = SequenceSerializer<'a, O>;
+	type SerializeTuple = SequenceSerializer<'a, O>;
+	type SerializeTupleStruct = SequenceSerializer<'a, O>;
+	type SerializeTupleVariant = SequenceSerializer<'a, O>;
 	type SerializeMap = Self;
 	type SerializeStruct = StructSerializer<'a, O>;
 	type SerializeStructVariant = StructSerializer<'a, O>;
 
 	#[inline]
 	fn is_human_readable(&self) -> bool {
-		false
+		self.human_readable
 	}
 
 	#[inline]
@@ -120,41 +584,31 @@ where
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::UnsignedInt.into())?;
-		v.encode(&mut self.output)?;
-		Ok(())
+		self.write_unsigned(v)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::UnsignedInt.into())?;
-		v.encode(&mut self.output)?;
-		Ok(())
+		self.write_unsigned(v)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::UnsignedInt.into())?;
-		v.encode(&mut self.output)?;
-		Ok(())
+		self.write_unsigned(v)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::UnsignedInt.into())?;
-		v.encode(&mut self.output)?;
-		Ok(())
+		self.write_unsigned(v)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::UnsignedInt.into())?;
-		v.encode(&mut self.output)?;
-		Ok(())
+		self.write_unsigned(v)
 	}
 
 	#[inline]
@@ -184,10 +638,15 @@ where
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		#[cfg(feature = "alloc")]
+		if ::core::mem::take(&mut self.writing_key) {
+			return self.write_table_string(v);
+		}
+
 		self.output.write_byte(Type::String.into())?;
 		let bytes = v.as_bytes();
-		bytes.len().encode(&mut self.output)?;
-		self.output.write_all(bytes)?;
+		encode_length(bytes.len(), &mut self.output)?;
+		self.output.write_borrowed(bytes)?;
 		Ok(())
 	}
 
@@ -195,8 +654,8 @@ where
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
 		self.output.write_byte(Type::Bytes.into())?;
-		v.len().encode(&mut self.output)?;
-		self.output.write_all(v)?;
+		encode_length(v.len(), &mut self.output)?;
+		self.output.write_borrowed(v)?;
 		Ok(())
 	}
 
@@ -213,6 +672,9 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
+		if self.explicit_option {
+			self.output.write_byte(Type::Some.into())?;
+		}
 		value.serialize(self)
 	}
 
@@ -249,12 +711,35 @@ where
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, value)))]
 	fn serialize_newtype_struct<T>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
 		T: ?Sized + serde::Serialize,
 	{
+		if name == crate::format::RAW_VALUE_NEWTYPE_NAME {
+			// Raw values are encoded already.
+			return value.serialize(RawValueSerializer { output: &mut self.output });
+		}
+		#[cfg(feature = "big-integers")]
+		if name == crate::format::BIG_INTEGER_NEWTYPE_NAME {
+			return value.serialize(RawValueSerializer { output: &mut self.output });
+		}
+
+		match float_bits(name, value) {
+			Some(FloatBits::F16(bits)) => {
+				self.output.write_byte(Type::Float16.into())?;
+				self.output.write_all(&bits.to_le_bytes())?;
+				return Ok(());
+			}
+			Some(FloatBits::F128(bits)) => {
+				self.output.write_byte(Type::Float128.into())?;
+				self.output.write_all(&bits.to_le_bytes())?;
+				return Ok(());
+			}
+			None => {}
+		}
+
 		value.serialize(self)
 	}
 
@@ -284,16 +769,14 @@ where
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
-	fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-		self.output.write_byte(Type::SeqStart.into())?;
-		Ok(self)
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		self.begin_seq(len, false)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
-	fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-		self.output.write_byte(Type::SeqStart.into())?;
-		Ok(self)
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.begin_seq(Some(len), false)
 	}
 
 	#[inline]
@@ -301,10 +784,9 @@ where
 	fn serialize_tuple_struct(
 		self,
 		_name: &'static str,
-		_len: usize,
+		len: usize,
 	) -> Result<Self::SerializeTupleStruct, Self::Error> {
-		self.output.write_byte(Type::SeqStart.into())?;
-		Ok(self)
+		self.begin_seq(Some(len), false)
 	}
 
 	#[inline]
@@ -314,22 +796,21 @@ where
 		_name: &'static str,
 		variant_index: u32,
 		variant: &'static str,
-		_len: usize,
+		len: usize,
 	) -> Result<Self::SerializeTupleVariant, Self::Error> {
-		self.output.write_byte(Type::MapStart.into())?;
+		self.write_start(Type::MapStart)?;
 		if self.use_indices {
 			variant_index.serialize(&mut *self)?;
 		} else {
 			variant.serialize(&mut *self)?;
 		}
-		self.output.write_byte(Type::SeqStart.into())?;
-		Ok(self)
+		self.begin_seq(Some(len), true)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		self.output.write_byte(Type::MapStart.into())?;
+		self.begin_map()?;
 		Ok(self)
 	}
 
@@ -340,7 +821,7 @@ where
 		_name: &'static str,
 		_len: usize,
 	) -> Result<Self::SerializeStruct, Self::Error> {
-		self.output.write_byte(Type::MapStart.into())?;
+		self.begin_map()?;
 		Ok(StructSerializer::new(self))
 	}
 
@@ -353,13 +834,13 @@ where
 		variant: &'static str,
 		_len: usize,
 	) -> Result<Self::SerializeStructVariant, Self::Error> {
-		self.output.write_byte(Type::MapStart.into())?;
+		self.write_start(Type::MapStart)?;
 		if self.use_indices {
 			variant_index.serialize(&mut *self)?;
 		} else {
 			variant.serialize(&mut *self)?;
 		}
-		self.output.write_byte(Type::MapStart.into())?;
+		self.begin_map()?;
 		Ok(StructSerializer::new(self))
 	}
 
@@ -403,7 +884,7 @@ where
 		write!(&mut counter, "{value}")?;
 		let len = counter.0;
 		self.output.write_byte(Type::String.into())?;
-		len.encode(&mut self.output)?;
+		encode_length(len, &mut self.output)?;
 
 		// Second pass to actually write the data.
 		let mut writer = OutputWriter(&mut self.output);
@@ -413,7 +894,49 @@ where
 	}
 }
 
-impl<'a, O> ::serde::ser::SerializeSeq for &'a mut Serializer<O>
+/// Sequence serializer that keeps track of the number of elements of sequences with length.
+#[derive(Debug)]
+pub struct SequenceSerializer<'a, O> {
+	/// The inner serializer.
+	serializer: &'a mut Serializer<O>,
+	/// Number of elements that are still to be serialized, if the length was written.
+	remaining: Option<usize>,
+	/// Whether the sequence is the content of a tuple variant, whose map is ended, too.
+	variant: bool,
+}
+
+impl<'a, O> SequenceSerializer<'a, O>
+where
+	O: Output,
+{
+	/// Serialize the next element, which must not exceed the written length.
+	#[inline]
+	fn write_element<T>(&mut self, value: &T) -> crate::Result<()>
+	where
+		T: ?Sized + Serialize,
+	{
+		if let Some(remaining) = &mut self.remaining {
+			*remaining = remaining.checked_sub(1).ok_or_else(|| Error::UnbalancedCollection)?;
+		}
+		value.serialize(&mut *self.serializer)
+	}
+
+	/// End the sequence, which must have exactly the written length.
+	#[inline]
+	fn finish(self) -> crate::Result<()> {
+		match self.remaining {
+			None => self.serializer.write_end(Type::SeqEnd)?,
+			Some(0) => self.serializer.close_collection()?,
+			Some(_) => return Err(Error::UnbalancedCollection),
+		}
+		if self.variant {
+			self.serializer.write_end(Type::MapEnd)?;
+		}
+		Ok(())
+	}
+}
+
+impl<'a, O> ::serde::ser::SerializeSeq for SequenceSerializer<'a, O>
 where
 	O: Output,
 {
@@ -426,18 +949,17 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(&mut **self)
+		self.write_element(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::SeqEnd.into())?;
-		Ok(())
+		self.finish()
 	}
 }
 
-impl<'a, O> ::serde::ser::SerializeTuple for &'a mut Serializer<O>
+impl<'a, O> ::serde::ser::SerializeTuple for SequenceSerializer<'a, O>
 where
 	O: Output,
 {
@@ -450,18 +972,17 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(&mut **self)
+		self.write_element(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::SeqEnd.into())?;
-		Ok(())
+		self.finish()
 	}
 }
 
-impl<'a, O> ::serde::ser::SerializeTupleStruct for &'a mut Serializer<O>
+impl<'a, O> ::serde::ser::SerializeTupleStruct for SequenceSerializer<'a, O>
 where
 	O: Output,
 {
@@ -474,18 +995,17 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(&mut **self)
+		self.write_element(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::SeqEnd.into())?;
-		Ok(())
+		self.finish()
 	}
 }
 
-impl<'a, O> ::serde::ser::SerializeTupleVariant for &'a mut Serializer<O>
+impl<'a, O> ::serde::ser::SerializeTupleVariant for SequenceSerializer<'a, O>
 where
 	O: Output,
 {
@@ -498,19 +1018,17 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(&mut **self)
+		self.write_element(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::SeqEnd.into())?;
-		self.output.write_byte(Type::MapEnd.into())?;
-		Ok(())
+		self.finish()
 	}
 }
 
-impl<'a, O> ::serde::ser::SerializeMap for &'a mut Serializer<O>
+impl<O> ::serde::ser::SerializeMap for &mut Serializer<O>
 where
 	O: Output,
 {
@@ -523,7 +1041,7 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		key.serialize(&mut **self)
+		self.write_key(key)
 	}
 
 	#[inline]
@@ -532,14 +1050,13 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(&mut **self)
+		self.write_value(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.output.write_byte(Type::MapEnd.into())?;
-		Ok(())
+		self.end_map()
 	}
 
 	#[inline]
@@ -561,13 +1078,15 @@ pub struct StructSerializer<'a, O> {
 	serializer: &'a mut Serializer<O>,
 	/// The current field index.
 	field_index: u32,
+	/// Whether no field was serialized or skipped yet.
+	first_field: bool,
 }
 
 impl<'a, O> StructSerializer<'a, O> {
 	/// Create a new struct serializer.
 	#[must_use]
 	fn new(serializer: &'a mut Serializer<O>) -> Self {
-		Self { serializer, field_index: 0 }
+		Self { serializer, field_index: 0, first_field: true }
 	}
 }
 
@@ -584,25 +1103,30 @@ where
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		if self.serializer.use_indices {
-			self.field_index.serialize(&mut *self.serializer)?;
+		let first_field = ::core::mem::replace(&mut self.first_field, false);
+		if !self.serializer.use_indices {
+			self.serializer.write_key(key)?;
+		} else if first_field && self.serializer.tag_fields.contains(&key) {
+			// Serde finds the tag by name and deserializes the remaining fields as the content.
+			self.serializer.write_key(key)?;
+			return self.serializer.write_value(value);
 		} else {
-			key.serialize(&mut *self.serializer)?;
+			self.serializer.write_key(&self.field_index)?;
 		}
 		self.field_index += 1;
-		value.serialize(&mut *self.serializer)
+		self.serializer.write_value(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.serializer.output.write_byte(Type::MapEnd.into())?;
-		Ok(())
+		self.serializer.end_map()
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn skip_field(&mut self, _key: &'static str) -> Result<(), Self::Error> {
+		self.first_field = false;
 		self.field_index += 1;
 		Ok(())
 	}
@@ -622,19 +1146,19 @@ where
 		T: ?Sized + Serialize,
 	{
 		if self.serializer.use_indices {
-			self.field_index.serialize(&mut *self.serializer)?;
+			self.serializer.write_key(&self.field_index)?;
 		} else {
-			key.serialize(&mut *self.serializer)?;
+			self.serializer.write_key(key)?;
 		}
 		self.field_index += 1;
-		value.serialize(&mut *self.serializer)
+		self.serializer.write_value(value)
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn end(self) -> Result<Self::Ok, Self::Error> {
-		self.serializer.output.write_byte(Type::MapEnd.into())?;
-		self.serializer.output.write_byte(Type::MapEnd.into())?;
+		self.serializer.end_map()?;
+		self.serializer.write_end(Type::MapEnd)?;
 		Ok(())
 	}
 
@@ -645,3 +1169,322 @@ where
 		Ok(())
 	}
 }
+
+/// Raw bits of a float that is not natively supported by serde.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum FloatBits {
+	/// Bits of a `Float16`.
+	F16(u16),
+	/// Bits of a `Float128`.
+	F128(u128),
+}
+
+/// Float that serializes itself as the closest float serde supports, see
+/// [F16_BITS_NEWTYPE_NAME](crate::format::F16_BITS_NEWTYPE_NAME) and
+/// [F128_BITS_NEWTYPE_NAME](crate::format::F128_BITS_NEWTYPE_NAME). [float_bits] converts it back
+/// to the exact bits.
+#[cfg(any(feature = "alloc", feature = "half"))]
+pub(crate) struct FloatBitsPayload(pub(crate) FloatBits);
+
+#[cfg(any(feature = "alloc", feature = "half"))]
+impl Serialize for FloatBitsPayload {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		match self.0 {
+			FloatBits::F16(bits) => serializer.serialize_f32(crate::format::f16_bits_to_f32(bits)),
+			FloatBits::F128(bits) => {
+				let float = crate::format::f128_bits_to_f64(bits);
+				if crate::format::f64_to_f128_bits(float) == bits {
+					serializer.serialize_f64(float)
+				} else {
+					serializer.serialize_u128(bits)
+				}
+			}
+		}
+	}
+}
+
+/// Get the float bits of a newtype struct, if it is one of the known float bits newtypes, see
+/// [FloatBitsPayload].
+pub(crate) fn float_bits<T>(name: &'static str, value: &T) -> Option<FloatBits>
+where
+	T: ?Sized + Serialize,
+{
+	let is_f16 = name == crate::format::F16_BITS_NEWTYPE_NAME;
+	let is_f128 = name == crate::format::F128_BITS_NEWTYPE_NAME;
+	if !is_f16 && !is_f128 {
+		return None;
+	}
+
+	// Other types with the same name are serialized as usual, since the bits serializer does not
+	// write any output.
+	match value.serialize(FloatBitsSerializer) {
+		Ok(bits @ FloatBits::F16(_)) if is_f16 => Some(bits),
+		Ok(bits @ FloatBits::F128(_)) if is_f128 => Some(bits),
+		_ => None,
+	}
+}
+
+/// Serializer that extracts the bits of a float newtype struct's [FloatBitsPayload]. Every other
+/// value is rejected.
+#[derive(Debug, Clone, Copy)]
+struct FloatBitsSerializer;
+
+/// Implement serializer methods that reject the value, for [FloatBitsSerializer] and
+/// [RawValueSerializer].
+macro_rules! reject_values {
+	($($method:ident($($arg:ty),*) -> $ret:ty;)*) => {
+		$(
+			#[inline]
+			fn $method(self, $(_: $arg),*) -> Result<$ret, Self::Error> {
+				Err(Error::Custom)
+			}
+		)*
+	};
+}
+
+impl ::serde::Serializer for FloatBitsSerializer {
+	type Ok = FloatBits;
+	type Error = Error;
+
+	type SerializeSeq = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeTuple = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeTupleStruct = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeTupleVariant = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeMap = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeStruct = ::serde::ser::Impossible<FloatBits, Error>;
+	type SerializeStructVariant = ::serde::ser::Impossible<FloatBits, Error>;
+
+	#[inline]
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+
+	#[inline]
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		crate::format::f32_to_f16_bits(v).map(FloatBits::F16).ok_or(Error::Custom)
+	}
+
+	#[inline]
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		Ok(FloatBits::F128(crate::format::f64_to_f128_bits(v)))
+	}
+
+	#[inline]
+	fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+		Ok(FloatBits::F128(v))
+	}
+
+	reject_values! {
+		serialize_bool(bool) -> FloatBits;
+		serialize_i8(i8) -> FloatBits;
+		serialize_i16(i16) -> FloatBits;
+		serialize_i32(i32) -> FloatBits;
+		serialize_i64(i64) -> FloatBits;
+		serialize_i128(i128) -> FloatBits;
+		serialize_u8(u8) -> FloatBits;
+		serialize_u16(u16) -> FloatBits;
+		serialize_u32(u32) -> FloatBits;
+		serialize_u64(u64) -> FloatBits;
+		serialize_char(char) -> FloatBits;
+		serialize_str(&str) -> FloatBits;
+		serialize_bytes(&[u8]) -> FloatBits;
+		serialize_none() -> FloatBits;
+		serialize_unit() -> FloatBits;
+		serialize_unit_struct(&'static str) -> FloatBits;
+		serialize_unit_variant(&'static str, u32, &'static str) -> FloatBits;
+		serialize_seq(Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_map(Option<usize>) -> Self::SerializeMap;
+		serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn collect_str<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + ::core::fmt::Display,
+	{
+		Err(Error::Custom)
+	}
+}
+
+/// [Output] that records the bytes of a map key while writing them, see
+/// [Serializer::write_recorded_key]. The output is a trait object, so that keys containing maps
+/// do not nest the type endlessly.
+#[cfg(feature = "alloc")]
+struct KeyRecorder<'a> {
+	/// The output to write to.
+	output: &'a mut dyn Output,
+	/// The bytes written so far.
+	bytes: Vec<u8>,
+}
+
+#[cfg(feature = "alloc")]
+impl Output for KeyRecorder<'_> {
+	#[inline]
+	fn write_byte(&mut self, byte: u8) -> crate::Result<()> {
+		self.bytes.push(byte);
+		self.output.write_byte(byte)
+	}
+
+	#[inline]
+	fn write_all(&mut self, bytes: &[u8]) -> crate::Result<()> {
+		self.bytes.extend_from_slice(bytes);
+		self.output.write_all(bytes)
+	}
+
+	#[inline]
+	fn write_borrowed(&mut self, bytes: &[u8]) -> crate::Result<()> {
+		self.bytes.extend_from_slice(bytes);
+		self.output.write_borrowed(bytes)
+	}
+
+	#[inline]
+	fn flush(&mut self) -> crate::Result<()> {
+		self.output.flush()
+	}
+}
+
+/// Serializer that writes the bytes of a [RawValue](crate::RawValue) to the output as they are.
+/// Every other value is rejected.
+#[derive(Debug)]
+struct RawValueSerializer<'a, O> {
+	/// The output to write to.
+	output: &'a mut O,
+}
+
+impl<O> ::serde::Serializer for RawValueSerializer<'_, O>
+where
+	O: Output,
+{
+	type Ok = ();
+	type Error = Error;
+
+	type SerializeSeq = ::serde::ser::Impossible<(), Error>;
+	type SerializeTuple = ::serde::ser::Impossible<(), Error>;
+	type SerializeTupleStruct = ::serde::ser::Impossible<(), Error>;
+	type SerializeTupleVariant = ::serde::ser::Impossible<(), Error>;
+	type SerializeMap = ::serde::ser::Impossible<(), Error>;
+	type SerializeStruct = ::serde::ser::Impossible<(), Error>;
+	type SerializeStructVariant = ::serde::ser::Impossible<(), Error>;
+
+	#[inline]
+	fn is_human_readable(&self) -> bool {
+		false
+	}
+
+	#[inline]
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		self.output.write_all(v)
+	}
+
+	reject_values! {
+		serialize_bool(bool) -> ();
+		serialize_i8(i8) -> ();
+		serialize_i16(i16) -> ();
+		serialize_i32(i32) -> ();
+		serialize_i64(i64) -> ();
+		serialize_i128(i128) -> ();
+		serialize_u8(u8) -> ();
+		serialize_u16(u16) -> ();
+		serialize_u32(u32) -> ();
+		serialize_u64(u64) -> ();
+		serialize_u128(u128) -> ();
+		serialize_f32(f32) -> ();
+		serialize_f64(f64) -> ();
+		serialize_char(char) -> ();
+		serialize_str(&str) -> ();
+		serialize_none() -> ();
+		serialize_unit() -> ();
+		serialize_unit_struct(&'static str) -> ();
+		serialize_unit_variant(&'static str, u32, &'static str) -> ();
+		serialize_seq(Option<usize>) -> Self::SerializeSeq;
+		serialize_tuple(usize) -> Self::SerializeTuple;
+		serialize_tuple_struct(&'static str, usize) -> Self::SerializeTupleStruct;
+		serialize_tuple_variant(&'static str, u32, &'static str, usize) -> Self::SerializeTupleVariant;
+		serialize_map(Option<usize>) -> Self::SerializeMap;
+		serialize_struct(&'static str, usize) -> Self::SerializeStruct;
+		serialize_struct_variant(&'static str, u32, &'static str, usize) -> Self::SerializeStructVariant;
+	}
+
+	#[inline]
+	fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn serialize_newtype_struct<T>(
+		self,
+		_name: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn serialize_newtype_variant<T>(
+		self,
+		_name: &'static str,
+		_variant_index: u32,
+		_variant: &'static str,
+		_value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		Err(Error::Custom)
+	}
+
+	#[inline]
+	fn collect_str<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + ::core::fmt::Display,
+	{
+		Err(Error::Custom)
+	}
+}
@@ -68,6 +68,27 @@ fn test_signed_int() {
 	test_deser_with_indices::<isize>(&[Type::SignedInt.into(), 0xFF, 0x01]);
 }
 
+#[test]
+fn test_integers_out_of_range() {
+	init_tracing();
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice(&u128::MAX, &mut buffer).unwrap();
+	assert!(crate::from_slice::<u64>(bytes).is_err());
+	assert!(crate::from_slice::<u32>(bytes).is_err());
+	assert!(crate::from_slice::<usize>(bytes).is_err());
+	assert!(crate::from_slice::<i128>(bytes).is_err());
+	assert_eq!(crate::from_slice::<u128>(bytes).unwrap(), u128::MAX);
+
+	let bytes = crate::to_slice(&i128::MIN, &mut buffer).unwrap();
+	assert!(crate::from_slice::<i64>(bytes).is_err());
+	assert!(crate::from_slice::<isize>(bytes).is_err());
+	assert!(crate::from_slice::<u128>(bytes).is_err());
+
+	let bytes = crate::to_slice(&(u64::from(u32::MAX) + 1), &mut buffer).unwrap();
+	assert!(crate::from_slice::<u32>(bytes).is_err());
+	assert_eq!(crate::from_slice::<u64>(bytes).unwrap(), u64::from(u32::MAX) + 1);
+}
+
 #[test]
 fn test_floats() {
 	init_tracing();
@@ -83,6 +104,130 @@ fn test_floats() {
 	test_deser_with_indices::<f64>(&[Type::Float64.into(), 1, 2, 3, 4, 5, 6, 7, 8]);
 }
 
+#[test]
+fn test_float16_float128() {
+	init_tracing();
+	let float16 = |bits: [u8; 2]| {
+		let bytes = [Type::Float16.into(), bits[0], bits[1]];
+		crate::from_slice::<f32>(&bytes).unwrap()
+	};
+	assert_eq!(float16([0x00, 0x3C]).to_bits(), 1.0_f32.to_bits());
+	assert_eq!(float16([0x00, 0xC1]).to_bits(), (-2.5_f32).to_bits());
+	assert_eq!(float16([0xFF, 0x7B]).to_bits(), 65504.0_f32.to_bits());
+	assert_eq!(float16([0x01, 0x00]).to_bits(), 0x3380_0000); // Smallest subnormal.
+	assert_eq!(float16([0x00, 0x80]).to_bits(), (-0.0_f32).to_bits());
+	assert_eq!(float16([0x00, 0x7C]).to_bits(), f32::INFINITY.to_bits());
+	assert!(float16([0x00, 0x7E]).is_nan());
+
+	let float128 = |bits: u128| {
+		let mut bytes = [0; 17];
+		bytes[0] = Type::Float128.into();
+		bytes[1 ..].copy_from_slice(&bits.to_le_bytes());
+		crate::from_slice::<f64>(&bytes).unwrap()
+	};
+	assert_eq!(float128(0x3FFF << 112).to_bits(), 1.0_f64.to_bits());
+	assert_eq!(float128(0xC000_8000 << 96).to_bits(), (-3.0_f64).to_bits());
+	// Rounded to the nearest `f64`.
+	assert_eq!(
+		float128(0x4000_921F_B544_42D1_8469_898C_C517_01B8).to_bits(),
+		::core::f64::consts::PI.to_bits()
+	);
+	assert_eq!(
+		float128(0x3FFF_0000_0000_0000_1800_0000_0000_0000).to_bits(),
+		0x3FF0_0000_0000_0002
+	);
+	// Out of range for `f64`.
+	assert_eq!(float128(0x7FFE << 112).to_bits(), f64::INFINITY.to_bits());
+	assert_eq!(float128(0x0001 << 112).to_bits(), 0.0_f64.to_bits());
+	assert_eq!(float128(0x3BCD << 112).to_bits(), 1); // Smallest `f64` subnormal.
+	assert_eq!(float128(0xFFFF << 112).to_bits(), f64::NEG_INFINITY.to_bits());
+	assert!(float128(0x7FFF_8000 << 96).is_nan());
+
+	// Self-describing deserialization.
+	let mut bytes = [0; 22];
+	bytes[.. 5].copy_from_slice(&[
+		Type::SeqStart.into(),
+		Type::Float16.into(),
+		0x00,
+		0x3C,
+		Type::Float128.into(),
+	]);
+	bytes[5 .. 21].copy_from_slice(&(0xC000_8000_u128 << 96).to_le_bytes());
+	bytes[21] = Type::SeqEnd.into();
+	let value: ::serde_json::Value = crate::from_slice(&bytes).unwrap();
+	assert_eq!(value, ::serde_json::json!([1.0, -3.0]));
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_f16() {
+	use ::half::f16;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Half(#[serde(with = "crate::helpers::f16_as_float16")] f16);
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct HalfFloats {
+		a: Half,
+		b: Half,
+		c: Option<Half>,
+		d: [Half; 2],
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Floats {
+		a: f32,
+		b: f64,
+		c: Option<f32>,
+		d: [f64; 2],
+	}
+	/// User type with the same name as `half::f16`.
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(rename = "f16")]
+	struct NotHalf(u16);
+
+	init_tracing();
+	test_serde(&Half(f16::from_f32(3.5)), &mut [0; 1024]);
+	test_serde_with_indices(&Half(f16::from_f32(3.5)), &mut [0; 1024]);
+	test_serde(&Half(f16::NEG_INFINITY), &mut [0; 1024]);
+	test_deser::<Half>(&[Type::Float16.into(), 0x00, 0x43]);
+	test_deser_with_indices::<Half>(&[Type::Float16.into(), 0x00, 0x43]);
+
+	let value = HalfFloats {
+		a: Half(f16::from_f32(1.5)),
+		b: Half(f16::MAX),
+		c: Some(Half(f16::from_f32(-0.25))),
+		d: [Half(f16::ZERO), Half(f16::MIN_POSITIVE)],
+	};
+	test_serde(&value, &mut [0; 1024]);
+	test_serde_with_indices(&value, &mut [0; 1024]);
+
+	// Cross-read as larger floats.
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+	let parsed: Floats = crate::from_slice(bytes).unwrap();
+	assert_eq!(
+		parsed,
+		Floats { a: 1.5, b: 65504.0, c: Some(-0.25), d: [0.0, f64::from(f16::MIN_POSITIVE)] }
+	);
+
+	// And read larger floats as f16.
+	let bytes = crate::to_slice(&parsed, &mut buffer).unwrap();
+	let parsed: HalfFloats = crate::from_slice(bytes).unwrap();
+	assert_eq!(parsed, value);
+
+	// Without the helper, `f16` and other types of the same name are written as they are.
+	test_serde(&f16::from_f32(3.5), &mut [0; 1024]);
+	let bytes = crate::to_slice(&f16::from_f32(3.5), &mut buffer).unwrap();
+	assert_eq!(bytes.first(), Some(&Type::UnsignedInt.into()));
+	let parsed: Half = crate::from_slice(bytes).unwrap();
+	assert_eq!(parsed, Half(f16::from_f32(3.5)));
+	let bytes = crate::to_slice(&NotHalf(0x4300), &mut buffer).unwrap();
+	assert_eq!(bytes.first(), Some(&Type::UnsignedInt.into()));
+	let parsed: NotHalf = crate::from_slice(bytes).unwrap();
+	assert_eq!(parsed, NotHalf(0x4300));
+	let result = crate::from_slice::<NotHalf>(&[Type::Float16.into(), 0x00, 0x43]);
+	assert!(result.is_err());
+}
+
 #[test]
 fn test_bytes() {
 	init_tracing();
@@ -108,6 +253,14 @@ fn test_char() {
 	test_serde_with_indices(&'😻', &mut [0; 1024]);
 	test_deser::<char>(&[Type::String.into(), 1, b'x']);
 	test_deser_with_indices::<char>(&[Type::String.into(), 1, b'x']);
+
+	for data in [
+		[Type::String.into(), 0].as_slice(),
+		&[Type::String.into(), 2, b'x', b'y'],
+		&[Type::String.into(), 5, b'x', b'x', b'x', b'x', b'x'],
+	] {
+		assert!(matches!(crate::from_slice::<char>(data), Err(crate::Error::NotOneChar)));
+	}
 }
 
 #[test]
@@ -230,6 +383,165 @@ fn test_option() {
 	test_deser_with_indices::<Option<i32>>(&[Type::SignedInt.into(), 5]);
 }
 
+#[cfg(feature = "std")]
+#[test]
+fn test_std_types() {
+	use ::std::{
+		net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr},
+		time::{Duration, SystemTime, UNIX_EPOCH},
+	};
+
+	init_tracing();
+	let v4 = Ipv4Addr::new(192, 168, 1, 1);
+	let v6 = Ipv6Addr::new(0x2001, 0xDB8, 0, 0, 0, 0, 0xFF00, 0x42);
+	let time = UNIX_EPOCH + Duration::new(1_700_000_000, 123_456_789);
+
+	// Octets are sequences of integers, structs carry their field names.
+	let mut buffer = [0; 1024];
+	assert_eq!(crate::to_slice(&v4, &mut buffer).unwrap().len(), 12);
+	assert_eq!(crate::to_slice(&v6, &mut buffer).unwrap().len(), 36);
+	assert_eq!(crate::to_slice(&IpAddr::V4(v4), &mut buffer).unwrap().len(), 18);
+	assert_eq!(crate::to_slice(&SocketAddr::new(v4.into(), 8080), &mut buffer).unwrap().len(), 23);
+	assert_eq!(crate::to_slice(&Duration::new(90, 500), &mut buffer).unwrap().len(), 20);
+	assert_eq!(crate::to_slice(&time, &mut buffer).unwrap().len(), 50);
+
+	test_serde(&v4, &mut [0; 1024]);
+	test_serde_with_indices(&v6, &mut [0; 1024]);
+	test_serde(&IpAddr::V6(v6), &mut [0; 1024]);
+	test_serde_with_indices(&IpAddr::V4(v4), &mut [0; 1024]);
+	test_serde(&SocketAddr::new(v6.into(), 443), &mut [0; 1024]);
+	test_serde_with_indices(&SocketAddr::new(v4.into(), 8080), &mut [0; 1024]);
+	test_serde(&Duration::MAX, &mut [0; 1024]);
+	test_serde(&time, &mut [0; 1024]);
+
+	// Serde's implementations only accept field names, not indices.
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_slice_with_config(&Duration::ZERO, &mut buffer, config).unwrap();
+	assert!(crate::from_slice_with_config::<Duration>(bytes, config).is_err());
+	let bytes = crate::to_slice_with_config(&time, &mut buffer, config).unwrap();
+	assert!(crate::from_slice_with_config::<SystemTime>(bytes, config).is_err());
+}
+
+#[test]
+fn test_explicit_option() {
+	init_tracing();
+	let config = Config { explicit_option: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	for value in [None, Some(None), Some(Some(5_u32))] {
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+		let parsed: Option<Option<u32>> = crate::from_slice_with_config(bytes, config).unwrap();
+		assert_eq!(parsed, value);
+	}
+	#[cfg(feature = "alloc")]
+	{
+		use ::alloc::{vec, vec::Vec};
+
+		let value = vec![None, Some(None), Some(Some(0_u8)), None];
+		let bytes = crate::to_vec_with_config(&value, config).unwrap();
+		let parsed: Vec<Option<Option<u8>>> = crate::from_slice(&bytes).unwrap();
+		assert_eq!(parsed, value);
+	}
+
+	let bytes = crate::to_slice_with_config(&Some(None::<u32>), &mut buffer, config).unwrap();
+	assert_eq!(bytes, [u8::from(Type::Some), Type::Null.into()]);
+	let bytes = crate::to_slice(&Some(None::<u32>), &mut buffer).unwrap();
+	assert_eq!(bytes, [u8::from(Type::Null)]);
+
+	// Both encodings are accepted.
+	let parsed: Option<u32> = crate::from_slice(&[Type::UnsignedInt.into(), 5]).unwrap();
+	assert_eq!(parsed, Some(5));
+	let parsed: Option<u32> =
+		crate::from_slice(&[Type::Some.into(), Type::UnsignedInt.into(), 5]).unwrap();
+	assert_eq!(parsed, Some(5));
+	let result = crate::from_slice::<u32>(&[Type::Some.into(), Type::UnsignedInt.into(), 5]);
+	assert!(matches!(result, Err(crate::Error::WrongType(Type::Some, _))));
+}
+
+#[test]
+fn test_compact_ints() {
+	init_tracing();
+	let config = Config { compact_ints: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	let uint = Type::UnsignedInt.into();
+
+	let bytes = crate::to_slice_with_config(&0_u8, &mut buffer, config).unwrap();
+	assert_eq!(bytes, [64]);
+	let bytes = crate::to_slice_with_config(&63_u128, &mut buffer, config).unwrap();
+	assert_eq!(bytes, [127]);
+	let bytes = crate::to_slice_with_config(&64_u16, &mut buffer, config).unwrap();
+	assert_eq!(bytes, [uint, 64]);
+	let bytes = crate::to_slice_with_config(&5_i32, &mut buffer, config).unwrap();
+	assert_eq!(bytes, [Type::SignedInt.into(), 10]);
+
+	for value in [0, 1, 62, 63, 64, 127, 128, u64::MAX] {
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+		assert_eq!(crate::from_slice::<u64>(bytes).unwrap(), value);
+		assert_eq!(crate::from_slice::<u128>(bytes).unwrap(), u128::from(value));
+		assert!(crate::validate(bytes, Config::default()).is_ok());
+	}
+	assert!(matches!(crate::from_slice::<u8>(&[127]), Ok(63)));
+	assert!(matches!(
+		crate::from_slice::<i8>(&[127]),
+		Err(crate::Error::WrongType(Type::UnsignedInt, _))
+	));
+	assert!(matches!(crate::from_slice::<u8>(&[128]), Err(crate::Error::InvalidType(128))));
+}
+
+#[test]
+fn test_length_prefixed_seqs() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Variant {
+		Pair(u8, u8),
+	}
+
+	init_tracing();
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	let fixed = Type::SeqFixed.into();
+	let uint = Type::UnsignedInt.into();
+
+	let bytes = crate::to_slice_with_config(&[1_u8, 2], &mut buffer, config).unwrap();
+	assert_eq!(bytes, [fixed, 2, uint, 1, uint, 2]);
+	let bytes = crate::to_slice_with_config(&[0_u8; 0], &mut buffer, config).unwrap();
+	assert_eq!(bytes, [fixed, 0]);
+	let bytes = crate::to_slice_with_config(&Variant::Pair(1, 2), &mut buffer, config).unwrap();
+	assert_eq!(
+		bytes,
+		[
+			Type::MapStart.into(),
+			Type::String.into(),
+			4,
+			b'P',
+			b'a',
+			b'i',
+			b'r',
+			fixed,
+			2,
+			uint,
+			1,
+			uint,
+			2,
+			Type::MapEnd.into()
+		]
+	);
+	assert_eq!(crate::from_slice::<Variant>(bytes).unwrap(), Variant::Pair(1, 2));
+
+	let value = (1_u8, [2_u16, 3], ("a", [true; 0]), Some([-1_i8]));
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+	assert_eq!(
+		crate::from_slice::<(u8, [u16; 2], (&str, [bool; 0]), Option<[i8; 1]>)>(bytes).unwrap(),
+		value
+	);
+	assert!(crate::validate(bytes, Config::default()).is_ok());
+
+	// Extra elements are not silently ignored.
+	assert!(crate::from_slice::<[u8; 1]>(&[fixed, 2, uint, 1, uint, 2]).is_err());
+	assert!(matches!(
+		crate::from_slice::<[u8; 2]>(&[fixed, 2, uint, 1]),
+		Err(crate::Error::UnexpectedEnd)
+	));
+}
+
 #[test]
 fn test_format_args() {
 	init_tracing();
@@ -238,7 +550,6 @@ fn test_format_args() {
 	assert_eq!(bytes, &[Type::String.into(), 5, b'X', b'Y', b'Z', b' ', b'5']);
 }
 
-
 #[test]
 fn test_empty_struct() {
 	#[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -0,0 +1,307 @@
+//! Tests for error kinds, cloning and comparing errors.
+
+use ::serde::{Deserialize, Serialize};
+
+use super::init_tracing;
+use crate::{format::Type, Config, Deserializer, Error, ErrorKind, Result};
+
+/// The kind of the error, if the result is an error.
+fn kind_of<T>(result: Result<T>) -> Option<ErrorKind> {
+	result.err().as_ref().map(Error::kind)
+}
+
+#[test]
+fn test_error_kinds_of_deserialization() {
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code, reason = "Only deserialized")]
+	struct Struct {
+		a: u8,
+		b: u8,
+		c: u8,
+	}
+
+	init_tracing();
+	let uint = Type::UnsignedInt.into();
+	let string = Type::String.into();
+
+	let cases = [
+		(kind_of(crate::from_slice::<u8>(&[])), ErrorKind::UnexpectedEnd),
+		(kind_of(crate::from_slice::<u8>(&[64, 64])), ErrorKind::ExcessData),
+		(kind_of(crate::from_slice::<u8>(&[9])), ErrorKind::InvalidType),
+		(kind_of(crate::from_slice::<u8>(&[uint, 0xFF, 0x7F])), ErrorKind::VarIntTooLarge),
+		(kind_of(crate::from_slice::<u8>(&[string, 0])), ErrorKind::WrongType),
+		(kind_of(crate::from_slice::<char>(&[string, 2, b'a', b'b'])), ErrorKind::NotOneChar),
+		(kind_of(crate::from_slice::<&str>(&[string, 1, 0xFF])), ErrorKind::StringNotUtf8),
+		(
+			kind_of(crate::from_slice::<Struct>(&[
+				Type::MapStart.into(),
+				uint,
+				0,
+				uint,
+				1,
+				uint,
+				1,
+				uint,
+				2,
+				uint,
+				2,
+				string,
+				1,
+				b'x',
+				Type::MapEnd.into(),
+			])),
+			ErrorKind::WrongFieldType,
+		),
+		(
+			kind_of(crate::framing::read_framed_borrowed::<u8>(&mut [5, 64].as_slice())),
+			ErrorKind::FrameIncomplete,
+		),
+		(
+			kind_of(crate::envelope::from_slice_with_header::<u8>(b"SBRG\x01\x00\x40")),
+			ErrorKind::InvalidHeader,
+		),
+		(
+			kind_of(crate::envelope::from_slice_with_header::<u8>(b"SBRF\xFF\x00\x40")),
+			ErrorKind::UnsupportedVersion,
+		),
+	];
+	for (kind, expected) in cases {
+		assert_eq!(kind, Some(expected));
+	}
+
+	let mut buffer = [0; 16];
+	let bytes = crate::to_slice(&[[1_u8]], &mut buffer).unwrap();
+	let mut de =
+		Deserializer::new(&*bytes).with_config(Config { max_depth: 1, ..Default::default() });
+	assert_eq!(kind_of(<[[u8; 1]; 1]>::deserialize(&mut de)), Some(ErrorKind::DepthLimitReached));
+
+	// Without `alloc`, there is no string table and references are rejected by type.
+	#[cfg(feature = "alloc")]
+	assert_eq!(
+		kind_of(crate::from_slice::<&str>(&[Type::StringRef.into(), 0])),
+		Some(ErrorKind::UnknownStringRef)
+	);
+
+	// A length of `u64::MAX` only fits into 64-bit platforms, where the data is missing.
+	let bytes = [string, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01];
+	#[cfg(target_pointer_width = "64")]
+	assert_eq!(kind_of(crate::from_slice::<&str>(&bytes)), Some(ErrorKind::UnexpectedEnd));
+	#[cfg(not(target_pointer_width = "64"))]
+	assert_eq!(kind_of(crate::from_slice::<&str>(&bytes)), Some(ErrorKind::LengthExceedsPlatform));
+
+	let bytes = crate::checksum::to_slice_crc32(&5_u8, &mut buffer).unwrap();
+	if let Some(last) = bytes.last_mut() {
+		*last ^= 1;
+	}
+	assert_eq!(
+		kind_of(crate::checksum::from_slice_crc32::<u8>(bytes)),
+		Some(ErrorKind::ChecksumMismatch)
+	);
+}
+
+#[test]
+fn test_error_kinds_of_serialization() {
+	/// Sequence with fewer elements than announced.
+	struct Short;
+
+	impl Serialize for Short {
+		fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			use ::serde::ser::SerializeSeq;
+
+			let seq = serializer.serialize_seq(Some(1))?;
+			seq.end()
+		}
+	}
+
+	init_tracing();
+	let mut buffer = [0; 4];
+	assert_eq!(kind_of(crate::to_slice(&"too long", &mut buffer)), Some(ErrorKind::BufferTooSmall));
+	let config = Config { max_size: ::core::num::NonZeroUsize::new(2), ..Default::default() };
+	assert_eq!(
+		kind_of(crate::to_slice_with_config(&"long", &mut buffer, config)),
+		Some(ErrorKind::LimitReached)
+	);
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	assert_eq!(
+		kind_of(crate::to_slice_with_config(&Short, &mut buffer, config)),
+		Some(ErrorKind::UnbalancedCollection)
+	);
+	assert_eq!(Error::from(::core::fmt::Error).kind(), ErrorKind::Format);
+
+	let custom = <Error as ::serde::de::Error>::custom("message");
+	#[cfg(not(feature = "alloc"))]
+	assert_eq!(custom.kind(), ErrorKind::Custom);
+	#[cfg(feature = "alloc")]
+	assert_eq!(custom, Error::Message("message".into()));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_error_kinds_with_alloc() {
+	use ::alloc::collections::BTreeMap;
+
+	use crate::value::Value;
+
+	#[derive(Debug, Serialize, Deserialize)]
+	#[serde(tag = "type")]
+	enum Tagged {
+		Variant { a: bool },
+	}
+
+	init_tracing();
+	let map_start = Type::MapStart.into();
+	let map_end = Type::MapEnd.into();
+	let string = Type::String.into();
+	let bytes = [map_start, string, 1, b'a', 64, string, 1, b'a', 65, map_end];
+	let strict = Config { reject_duplicate_map_keys: true, ..Default::default() };
+	assert_eq!(
+		kind_of(crate::from_slice_with_config::<BTreeMap<&str, u8>>(&bytes, strict)),
+		Some(ErrorKind::DuplicateMapKey)
+	);
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_vec_with_config(&Tagged::Variant { a: true }, config).unwrap();
+	let result = crate::from_slice_with_config::<Tagged>(&bytes, config);
+	assert!(result.as_ref().is_err_and(Error::is_index_mode_mismatch));
+	assert_eq!(kind_of(result), Some(ErrorKind::IndicesNotSupportedHere));
+
+	assert_eq!(
+		kind_of(Value::from(u128::MAX).to_json_string()),
+		Some(ErrorKind::NotJsonCompatible)
+	);
+	#[cfg(feature = "text")]
+	assert_eq!(kind_of(crate::from_base64::<u8>("!!")), Some(ErrorKind::Encoding));
+}
+
+#[test]
+fn test_errors_clone_and_compare() {
+	init_tracing();
+	let errors = [
+		Error::UnexpectedEnd,
+		Error::excess_data(&[1, 2, 3]),
+		Error::excess_byte(1),
+		Error::UnsupportedVersion(2),
+		Error::InvalidType(9),
+		Error::WrongType(Type::String, &[Type::UnsignedInt]),
+		Error::WrongType(Type::String, &[Type::Bytes]),
+		Error::WrongFieldType {
+			name: "Struct",
+			field: "a",
+			found: Type::Null,
+			expected: &[Type::String],
+		},
+		Error::UnknownStringRef(3),
+		Error::PartialWrite { written: 3 },
+		Error::LengthExceedsPlatform(3),
+		Error::CannotBorrow,
+		Error::Format(::core::fmt::Error),
+		Error::Custom,
+	];
+	for (i, error) in errors.iter().enumerate() {
+		assert_eq!(error.clone(), *error);
+		assert_eq!(error.clone().kind(), error.kind());
+		for (j, other) in errors.iter().enumerate() {
+			assert_eq!(error == other, i == j, "{error:?} vs {other:?}");
+		}
+	}
+	assert_ne!(Error::UnknownStringRef(3), Error::UnknownStringRef(4));
+	assert_ne!(Error::PartialWrite { written: 3 }, Error::PartialWrite { written: 4 });
+	#[cfg(feature = "alloc")]
+	assert_ne!(Error::excess_data(&[1, 2]), Error::excess_data(&[1, 3]));
+
+	#[cfg(feature = "alloc")]
+	{
+		assert_eq!(Error::Message("a".into()), Error::Message("a".into()));
+		assert_ne!(Error::Message("a".into()), Error::Message("b".into()));
+		assert_eq!(
+			Error::DuplicateSerializedKey("a".into()),
+			Error::DuplicateSerializedKey("a".into())
+		);
+		assert_ne!(Error::DuplicateSerializedKey("a".into()), Error::Message("a".into()));
+	}
+
+	#[cfg(feature = "std")]
+	{
+		use ::std::io;
+
+		let error = Error::from(io::Error::new(io::ErrorKind::InvalidData, "broken"));
+		let cloned = error.clone();
+		assert_eq!(cloned, error);
+		assert_eq!(cloned.to_string(), error.to_string());
+		assert_eq!(error, Error::from(io::Error::from(io::ErrorKind::InvalidData)));
+		assert_ne!(error, Error::from(io::Error::from(io::ErrorKind::UnexpectedEof)));
+		assert_eq!(error.kind(), ErrorKind::Io);
+	}
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_retry_on_index_mode_mismatch() {
+	use ::alloc::collections::BTreeMap;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Record {
+		id: u8,
+		level: u8,
+	}
+
+	/// Read the fields of any struct, whether it was written with names or indices.
+	fn read_fields(bytes: &[u8], config: Config) -> Result<usize> {
+		match crate::from_slice_with_config::<BTreeMap<&str, u8>>(bytes, config) {
+			Ok(fields) => Ok(fields.len()),
+			Err(err) if err.is_index_mode_mismatch() => {
+				crate::from_slice_with_config::<BTreeMap<u32, u8>>(bytes, config)
+					.map(|fields| fields.len())
+			}
+			Err(err) => Err(err),
+		}
+	}
+
+	init_tracing();
+	let record = Record { id: 1, level: 2 };
+	let indices = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_vec_with_config(&record, indices).unwrap();
+	let err = crate::from_slice::<BTreeMap<&str, u8>>(&bytes).unwrap_err();
+	assert_eq!(err.found_type(), Some(Type::UnsignedInt));
+	assert_eq!(err.expected_types(), Some([Type::String].as_slice()));
+	assert!(err.is_index_mode_mismatch());
+	assert_eq!(read_fields(&bytes, Config::default()).unwrap(), 2);
+
+	let bytes = crate::to_vec(&record).unwrap();
+	let err = crate::from_slice::<BTreeMap<u32, u8>>(&bytes).unwrap_err();
+	assert_eq!(err.found_type(), Some(Type::String));
+	assert!(err.is_index_mode_mismatch());
+	assert_eq!(read_fields(&bytes, Config::default()).unwrap(), 2);
+
+	// Mixed keys with strict keys, which can be read again without.
+	let uint = Type::UnsignedInt.into();
+	let bytes = [
+		Type::MapStart.into(),
+		uint,
+		0,
+		uint,
+		1,
+		Type::String.into(),
+		5,
+		b'l',
+		b'e',
+		b'v',
+		b'e',
+		b'l',
+		uint,
+		2,
+		Type::MapEnd.into(),
+	];
+	let strict = Config { strict_keys: true, ..Default::default() };
+	let err = crate::from_slice_with_config::<Record>(&bytes, strict).unwrap_err();
+	assert!(err.is_index_mode_mismatch());
+	assert_eq!(crate::from_slice::<Record>(&bytes).unwrap(), record);
+
+	// Other errors are not mismatches.
+	assert!(!Error::WrongType(Type::Null, &[Type::String]).is_index_mode_mismatch());
+	assert!(!Error::WrongType(Type::UnsignedInt, &[Type::UnsignedInt, Type::String])
+		.is_index_mode_mismatch());
+	assert!(!Error::UnexpectedEnd.is_index_mode_mismatch());
+	assert_eq!(Error::UnexpectedEnd.found_type(), None);
+	assert_eq!(Error::UnexpectedEnd.expected_types(), None);
+}
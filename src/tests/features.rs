@@ -4,7 +4,7 @@ use ::core::num::NonZeroUsize;
 use ::serde_bytes::Bytes;
 
 use super::*;
-use crate::{format::Type, Error};
+use crate::{buffer::InlineBuffer, format::Type, Deserializer, Error};
 
 #[test]
 fn test_string_is_bytes() {
@@ -26,6 +26,55 @@ fn test_bytes_is_byte_sequence() {
 	assert_eq!(parsed, (1, 2, 3));
 }
 
+#[cfg(feature = "alloc")]
+#[test]
+fn test_byte_sequence_is_bytes() {
+	use ::alloc::{vec, vec::Vec};
+	use ::serde_bytes::ByteBuf;
+
+	init_tracing();
+	let short = vec![0_u8, 1, 127, 128, 255];
+	let long: Vec<u8> = (0 ..= 255).collect();
+	for config in [Config::default(), Config { length_prefixed_seqs: true, ..Default::default() }] {
+		for value in [&short, &long] {
+			// Both producer styles, with and without `serde_bytes`.
+			for bytes in [
+				crate::to_vec_with_config(value, config).unwrap(),
+				crate::to_vec_with_config(&ByteBuf::from(value.clone()), config).unwrap(),
+			] {
+				let parsed: ByteBuf = crate::from_slice(&bytes).unwrap();
+				assert_eq!(&*parsed, value.as_slice());
+				let parsed: ByteBuf = crate::from_chunks(bytes.chunks(7), Vec::new()).unwrap();
+				assert_eq!(&*parsed, value.as_slice());
+			}
+		}
+
+		// Borrowed bytes cannot be borrowed from a sequence.
+		let bytes = crate::to_vec_with_config(&short, config).unwrap();
+		let result = crate::from_slice::<&Bytes>(&bytes);
+		assert!(matches!(result, Err(Error::CannotBorrow)));
+	}
+
+	// Elements must be bytes.
+	let bytes = crate::to_vec(&[1_u16, 256]).unwrap();
+	assert!(matches!(crate::from_slice::<ByteBuf>(&bytes), Err(Error::VarIntTooLarge)));
+	let bytes = crate::to_vec(&[1_i8, -1]).unwrap();
+	assert!(matches!(
+		crate::from_slice::<ByteBuf>(&bytes),
+		Err(Error::WrongType(Type::SignedInt, [Type::UnsignedInt]))
+	));
+	let bytes = crate::to_vec(&[[1_u8]]).unwrap();
+	assert!(matches!(
+		crate::from_slice::<ByteBuf>(&bytes),
+		Err(Error::WrongType(Type::SeqStart, [Type::UnsignedInt]))
+	));
+	let bytes = crate::to_vec(&vec![1_u8, 2]).unwrap();
+	assert!(matches!(
+		crate::from_slice::<ByteBuf>(bytes.get(.. bytes.len() - 1).unwrap()),
+		Err(Error::UnexpectedEnd)
+	));
+}
+
 #[test]
 fn test_string_is_char_sequence() {
 	init_tracing();
@@ -50,7 +99,7 @@ fn test_borrowing() {
 fn test_deser_calls_borrowed() {
 	struct Test;
 	struct Visitor;
-	impl<'a, 'de> ::serde::de::Visitor<'de> for &'a mut Visitor {
+	impl<'de> ::serde::de::Visitor<'de> for &mut Visitor {
 		type Value = Test;
 
 		fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
@@ -94,7 +143,247 @@ fn test_excess_data() {
 	let config = Config { error_on_excess_data: true, ..Default::default() };
 	let data = [Type::String.into(), 1, b'a', 0];
 	let result = crate::from_slice_with_config::<&str>(&data, config);
-	assert!(matches!(result, Err(Error::ExcessData)));
+	assert!(matches!(result, Err(Error::ExcessData { remaining: Some(1), .. })));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_excess_data_details() {
+	use ::alloc::{string::ToString, vec::Vec};
+
+	let config = Config { error_on_excess_data: true, ..Default::default() };
+	let mut data = Vec::from([Type::Null.into()]);
+	data.extend(0 .. 20);
+	let result = crate::from_slice_with_config::<()>(&data, config);
+	let Err(ref err @ Error::ExcessData { ref remaining, ref preview }) = result else {
+		panic!("unexpected result: {result:?}");
+	};
+	assert_eq!(*remaining, Some(20));
+	assert_eq!(*preview, (0 .. 8).collect::<Vec<u8>>());
+	assert_eq!(
+		err.to_string(),
+		"Excess data appeared at the end of the input: 20 bytes starting with 0x0001020304050607"
+	);
+
+	#[cfg(feature = "std")]
+	{
+		let result = crate::from_reader_with_config::<_, ()>(data.as_slice(), config);
+		let Err(ref err @ Error::ExcessData { ref remaining, ref preview }) = result else {
+			panic!("unexpected result: {result:?}");
+		};
+		assert_eq!(*remaining, None);
+		assert_eq!(*preview, [0]);
+		assert_eq!(
+			err.to_string(),
+			"Excess data appeared at the end of the input: at least 1 byte starting with 0x00"
+		);
+	}
+}
+
+#[test]
+fn test_partial_slices() {
+	let values = [("first", -1_i32), ("second", 200), ("third", 30_000)];
+	let mut buffer = [0; 64];
+	let mut len = 0;
+	for value in values {
+		len += crate::to_slice_len(&value, buffer.get_mut(len ..).unwrap()).unwrap();
+	}
+	assert_eq!(len, 11 + 13 + 13);
+	let bytes = buffer.get(.. len).unwrap();
+
+	let config = Config { max_size: Some(NonZeroUsize::new(13).unwrap()), ..Default::default() };
+	let mut rest = bytes;
+	let mut parsed = 0;
+	while !rest.is_empty() {
+		let (value, next) =
+			crate::from_slice_partial_with_config::<(&str, i32)>(rest, config).unwrap();
+		assert_eq!(Some(&value), values.get(parsed));
+		rest = next;
+		parsed += 1;
+	}
+	assert_eq!(parsed, 3);
+
+	let (value, rest) = crate::from_slice_partial::<(&str, i32)>(bytes).unwrap();
+	assert_eq!(value, ("first", -1));
+	assert_eq!(rest.len(), 13 + 13);
+
+	let config = Config { max_size: Some(NonZeroUsize::new(12).unwrap()), ..Default::default() };
+	let result = crate::from_slice_partial_with_config::<(&str, i32)>(rest, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	let result = crate::to_slice_len_with_config(&("second", 200), &mut buffer, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_header_with_trailing_payload() {
+	use ::std::io::{BufRead, BufReader, Read};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Header {
+		kind: ::std::string::String,
+		len: usize,
+	}
+
+	init_tracing();
+	let header = Header { kind: "a somewhat longer kind".to_owned(), len: 300 };
+	let payload: Vec<u8> = (0 .. 300).map(|i| (i % 256) as u8).collect();
+	let mut message = crate::to_vec(&header).unwrap();
+	let header_len = message.len();
+	message.extend_from_slice(&payload);
+	message.extend_from_slice(&[1, 2, 3]);
+
+	let (parsed, rest) = crate::from_slice_partial::<Header>(&message).unwrap();
+	assert_eq!(parsed, header);
+	assert_eq!(rest.get(.. parsed.len), Some(payload.as_slice()));
+
+	// Reader buffers smaller than the header, straddling its end and larger than the message.
+	for capacity in [1, 7, header_len, header_len + 1, 4096] {
+		let reader = BufReader::with_capacity(capacity, message.as_slice());
+		let (parsed, mut reader) = crate::from_buf_reader_partial::<_, Header>(reader).unwrap();
+		assert_eq!(parsed, header);
+		let mut data = vec![0; parsed.len];
+		reader.read_exact(&mut data).unwrap();
+		assert_eq!(data, payload);
+		assert_eq!(reader.fill_buf().unwrap().first(), Some(&1));
+	}
+
+	// Excess data is expected, but the limit applies to the header.
+	let config = Config { max_size: NonZeroUsize::new(header_len), ..Default::default() };
+	let (_, mut reader) =
+		crate::from_buf_reader_partial_with_config::<_, Header>(message.as_slice(), config)
+			.unwrap();
+	assert_eq!(reader.fill_buf().unwrap().len(), payload.len() + 3);
+	let config = Config { max_size: NonZeroUsize::new(header_len - 1), ..Default::default() };
+	let result =
+		crate::from_buf_reader_partial_with_config::<_, Header>(message.as_slice(), config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[test]
+fn test_fork_for_fallbacks() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Point {
+		x: i32,
+		y: i32,
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Named<'a> {
+		name: &'a str,
+	}
+
+	#[derive(Debug, PartialEq, Deserialize)]
+	#[serde(untagged)]
+	enum Untagged<'a> {
+		Point(Point),
+		#[serde(borrow)]
+		Named(Named<'a>),
+	}
+
+	/// Parse a point, falling back to a name.
+	fn point_or_named<'de>(
+		de: &mut Deserializer<&'de [u8]>,
+	) -> Result<Result<Point, Named<'de>>, Error> {
+		let mut fork = de.fork();
+		if let Ok(point) = Point::deserialize(&mut fork) {
+			*de = fork;
+			return Ok(Ok(point));
+		}
+		Ok(Err(Named::deserialize(de)?))
+	}
+
+	/// Parse a point, falling back to a name, with [Deserializer::try_parse].
+	fn try_point_or_named<'de>(
+		de: &mut Deserializer<&'de [u8]>,
+	) -> Result<Result<Point, Named<'de>>, Error> {
+		match de.try_parse() {
+			Ok(point) => Ok(Ok(point)),
+			Err(_) => Ok(Err(de.try_parse()?)),
+		}
+	}
+
+	init_tracing();
+	let mut buffer = [0; 64];
+	let mut len = crate::to_slice_len(&Point { x: 1, y: -2 }, &mut buffer).unwrap();
+	len += crate::to_slice_len(&Named { name: "a" }, buffer.get_mut(len ..).unwrap()).unwrap();
+	len += crate::to_slice_len(&Point { x: 3, y: 4 }, buffer.get_mut(len ..).unwrap()).unwrap();
+	let bytes = buffer.get(.. len).unwrap();
+
+	let mut de = Deserializer::new(bytes);
+	assert_eq!(point_or_named(&mut de).unwrap(), Ok(Point { x: 1, y: -2 }));
+	assert_eq!(point_or_named(&mut de).unwrap(), Err(Named { name: "a" }));
+	assert_eq!(point_or_named(&mut de).unwrap(), Ok(Point { x: 3, y: 4 }));
+	de.end().unwrap();
+
+	let mut de = Deserializer::new(bytes);
+	assert_eq!(try_point_or_named(&mut de).unwrap(), Ok(Point { x: 1, y: -2 }));
+	assert_eq!(try_point_or_named(&mut de).unwrap(), Err(Named { name: "a" }));
+	assert_eq!(try_point_or_named(&mut de).unwrap(), Ok(Point { x: 3, y: 4 }));
+	assert!(try_point_or_named(&mut de).is_err());
+	de.end().unwrap();
+
+	// Both branches parse like the untagged enum.
+	let (point, rest) = crate::from_slice_partial::<Untagged<'_>>(bytes).unwrap();
+	assert_eq!(point, Untagged::Point(Point { x: 1, y: -2 }));
+	let (named, _) = crate::from_slice_partial::<Untagged<'_>>(rest).unwrap();
+	assert_eq!(named, Untagged::Named(Named { name: "a" }));
+
+	// A clone is a checkpoint including the buffer.
+	let mut de = Deserializer::new(bytes).with_buffer(InlineBuffer::new());
+	let checkpoint = de.clone();
+	assert!(Named::deserialize(&mut de).is_err());
+	de = checkpoint;
+	assert_eq!(Point::deserialize(&mut de).unwrap(), Point { x: 1, y: -2 });
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_fork_with_string_table() {
+	use ::alloc::{borrow::ToOwned, string::String, vec::Vec};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Point {
+		x: i32,
+		y: i32,
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Named {
+		name: String,
+	}
+
+	init_tracing();
+	let mut ser = crate::Serializer::new(Vec::new()).key_dictionary(true);
+	Point { x: 1, y: 2 }.serialize(&mut ser).unwrap();
+	Named { name: "a".to_owned() }.serialize(&mut ser).unwrap();
+	Point { x: 3, y: 4 }.serialize(&mut ser).unwrap();
+	Named { name: "b".to_owned() }.serialize(&mut ser).unwrap();
+	let bytes = ser.into_output();
+
+	// Failed attempts define strings of the table, which are removed again, so that later
+	// references still point to the right strings.
+	let mut de = Deserializer::new(bytes.as_slice());
+	assert!(de.try_parse::<Named>().is_err());
+	assert_eq!(de.try_parse::<Point>().unwrap(), Point { x: 1, y: 2 });
+	assert!(de.try_parse::<Point>().is_err());
+	assert_eq!(de.try_parse::<Named>().unwrap(), Named { name: "a".to_owned() });
+	assert!(de.try_parse::<Named>().is_err());
+	assert_eq!(de.try_parse::<Point>().unwrap(), Point { x: 3, y: 4 });
+	assert_eq!(de.try_parse::<Named>().unwrap(), Named { name: "b".to_owned() });
+	de.end().unwrap();
+
+	// Forks share the table, but their definitions do not reach the original.
+	let mut de = Deserializer::new(bytes.as_slice());
+	assert!(Named::deserialize(&mut de.fork()).is_err());
+	assert_eq!(Point::deserialize(&mut de).unwrap(), Point { x: 1, y: 2 });
+	let mut fork = de.fork();
+	assert_eq!(Named::deserialize(&mut fork).unwrap(), Named { name: "a".to_owned() });
+	de = fork;
+	assert_eq!(Point::deserialize(&mut de.fork()).unwrap(), Point { x: 3, y: 4 });
+	assert_eq!(Point::deserialize(&mut de).unwrap(), Point { x: 3, y: 4 });
+	assert_eq!(Named::deserialize(&mut de).unwrap(), Named { name: "b".to_owned() });
+	de.end().unwrap();
 }
 
 #[test]
@@ -121,3 +410,1792 @@ fn test_max_size() {
 	let result = crate::to_slice_with_config(&data, &mut buffer, config);
 	assert!(result.is_ok());
 }
+
+#[test]
+fn test_limit_behavior() {
+	use crate::LimitBehavior;
+
+	init_tracing();
+	let data = Bytes::new(&[1, 2, 3, 4]);
+	let limited = Config { max_size: NonZeroUsize::new(5), ..Default::default() };
+	let rollback = Config { on_limit: LimitBehavior::ErrorAfterRollback, ..limited };
+
+	// Slices are only written to if the value fits.
+	let mut buffer = [0xAA; 16];
+	let result = crate::to_slice_with_config(&data, &mut buffer, limited);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	assert_ne!(buffer, [0xAA; 16]);
+
+	let mut buffer = [0xAA; 16];
+	let result = crate::to_slice_with_config(&data, &mut buffer, rollback);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	assert_eq!(buffer, [0xAA; 16]);
+
+	let mut buffer = [0xAA; 4];
+	let config = Config { on_limit: LimitBehavior::ErrorAfterRollback, ..Default::default() };
+	let result = crate::to_slice_with_config(&data, &mut buffer, config);
+	assert!(matches!(result, Err(Error::BufferTooSmall)));
+	assert_eq!(buffer, [0xAA; 4]);
+
+	let mut buffer = [0xAA; 16];
+	let written = crate::to_slice_with_config(&data, &mut buffer, config).unwrap();
+	assert_eq!(written, [Type::Bytes.into(), 4, 1, 2, 3, 4]);
+
+	#[cfg(feature = "alloc")]
+	{
+		let mut buffer = ::alloc::vec::Vec::new();
+		let result = crate::to_vec_in_with_config(&data, &mut buffer, limited);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		assert!(!buffer.is_empty());
+
+		let result = crate::to_vec_in_with_config(&data, &mut buffer, rollback);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		assert!(buffer.is_empty());
+	}
+
+	#[cfg(feature = "heapless")]
+	{
+		let result = crate::to_heapless_vec_with_config::<16, _>(&data, rollback);
+		assert!(matches!(result, Err(Error::LimitReached)));
+		let result = crate::to_heapless_vec_with_config::<4, _>(&data, config);
+		assert!(matches!(result, Err(Error::BufferTooSmall)));
+	}
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_limit_behavior_with_writers() {
+	use ::alloc::vec::Vec;
+
+	use crate::LimitBehavior;
+
+	init_tracing();
+	let value: Vec<&str> = (0 .. 20).map(|_| "entry").collect();
+	let expected = crate::to_vec(&value).unwrap();
+	let limited =
+		Config { max_size: NonZeroUsize::new(50), writer_buffer_size: 16, ..Default::default() };
+	let rollback = Config { on_limit: LimitBehavior::ErrorAfterRollback, ..limited };
+
+	// By default, the buffered data is written as well.
+	let mut writer = Vec::new();
+	let result = crate::to_writer_with_config(&value, &mut writer, limited);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	assert_eq!(writer.len(), 50);
+
+	// Only data that reached the writer before the limit is left, and its length is reported.
+	let mut writer = Vec::new();
+	let result = crate::to_writer_with_config(&value, &mut writer, rollback);
+	let Err(Error::PartialWrite { written }) = result else {
+		panic!("expected a partial write, got {result:?}");
+	};
+	assert!(written > 0 && written <= 50);
+	assert_eq!(writer.len(), written);
+	assert!(expected.starts_with(&writer));
+
+	// Nothing reaches the writer if the limit is hit while buffering.
+	let config = Config { writer_buffer_size: 64, ..rollback };
+	let mut writer = Vec::new();
+	let result = crate::to_writer_with_config(&value, &mut writer, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	assert!(writer.is_empty());
+
+	let config = Config { max_size: None, ..config };
+	crate::to_writer_with_config(&value, &mut writer, config).unwrap();
+	assert_eq!(writer, expected);
+}
+
+#[test]
+fn test_manual_serializer_with_size_limit() {
+	use crate::{Deserializer, Serializer, SizeLimit};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Data<'a> {
+		id: u32,
+		name: &'a str,
+	}
+
+	init_tracing();
+	let value = Data { id: 1, name: "a longer name" };
+	let config = Config { use_indices: true, ..Default::default() };
+	let mut buffer = [0; 64];
+	let expected = crate::to_slice_with_config(&value, &mut buffer, config).unwrap().to_vec();
+	assert_eq!(expected.len(), 23);
+
+	// The limit is reached in the middle of the struct, like with the free functions.
+	let config = Config { max_size: NonZeroUsize::new(10), ..config };
+	let mut ser = Serializer::new(SizeLimit::new(buffer.as_mut_slice(), 10)).with_config(config);
+	assert!(matches!(value.serialize(&mut ser), Err(Error::LimitReached)));
+	let result = crate::to_slice_with_config(&value, &mut buffer, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+
+	let mut ser = Serializer::new(SizeLimit::new(buffer.as_mut_slice(), 23)).with_config(config);
+	value.serialize(&mut ser).unwrap();
+	let rest = ser.into_output().into_inner().len();
+	assert_eq!(buffer.get(.. 64 - rest).unwrap(), expected);
+
+	let mut de = Deserializer::new(SizeLimit::new(expected.as_slice(), 10)).with_config(config);
+	assert!(matches!(Data::deserialize(&mut de), Err(Error::LimitReached)));
+}
+
+#[test]
+fn test_serialized_size() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Data<'a> {
+		name: &'a str,
+		#[serde(with = "serde_bytes")]
+		payload: &'a [u8],
+		values: [u32; 4],
+	}
+
+	let mut buffer = [0; 1024];
+	let value = Data { name: "Holla", payload: &[1, 2, 3, 4, 5], values: [0, 1, 1000, u32::MAX] };
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+	let size = crate::serialized_size(&value).unwrap();
+	assert_eq!(size, bytes.len());
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+	let size = crate::serialized_size_with_config(&value, config).unwrap();
+	assert_eq!(size, bytes.len());
+
+	let config = Config { max_size: Some(NonZeroUsize::new(size - 1).unwrap()), ..config };
+	let result = crate::serialized_size_with_config(&value, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_serialized_size_matches_all_configs() {
+	use ::alloc::{collections::BTreeMap, vec, vec::Vec};
+
+	#[derive(Debug, Serialize)]
+	struct Entry<'a> {
+		name: &'a str,
+		value: Option<u64>,
+		tags: Vec<&'a str>,
+	}
+
+	init_tracing();
+	let entries = [("b", 70_u64), ("a", 3)].map(|(name, value)| Entry {
+		name,
+		value: Some(value),
+		tags: vec![name, "tag"],
+	});
+	let map: BTreeMap<&str, u32> = [("zeta", 1), ("alpha", 2)].into();
+	let value = (entries, map, [1_u32, 2, 1000]);
+
+	for flags in 0_u8 .. 1 << 7 {
+		let flag = |bit: u8| flags & (1 << bit) != 0;
+		let config = Config {
+			use_indices: flag(0),
+			explicit_option: flag(1),
+			compact_ints: flag(2),
+			length_prefixed_seqs: flag(3),
+			canonical: flag(4),
+			key_dictionary: flag(5),
+			detect_duplicate_keys: flag(6),
+			..Default::default()
+		};
+		let bytes = crate::to_vec_with_config(&value, config).unwrap();
+		let size = crate::serialized_size_with_config(&value, config).unwrap();
+		assert_eq!(size, bytes.len(), "{config:?}");
+	}
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_to_vec_exact_preallocates() {
+	let value = ("Hello, my name is bumble bee, bumble bee!", [5_u64; 20], Bytes::new(&[1; 300]));
+	let bytes = crate::to_vec_exact(&value).unwrap();
+	assert_eq!(bytes.len(), crate::serialized_size(&value).unwrap());
+	assert_eq!(bytes.capacity(), bytes.len());
+	assert_eq!(bytes, crate::to_vec(&value).unwrap());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_to_vec_in_reuses_buffer() {
+	use ::alloc::{string::String, vec::Vec};
+
+	let mut buffer = Vec::new();
+	crate::to_vec_in(&"a long string to allocate the buffer once".repeat(4), &mut buffer).unwrap();
+	let capacity = buffer.capacity();
+	let pointer = buffer.as_ptr();
+
+	for value in ["short", "", "medium sized string"] {
+		crate::to_vec_in(&value, &mut buffer).unwrap();
+		assert_eq!(buffer, crate::to_vec(&value).unwrap());
+		assert_eq!(buffer.capacity(), capacity);
+		assert_eq!(buffer.as_ptr(), pointer);
+	}
+
+	let config = Config { max_size: Some(NonZeroUsize::new(10).unwrap()), ..Default::default() };
+	let result = crate::to_vec_in_with_config(&"exceeds the limit", &mut buffer, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	assert_eq!(buffer.capacity(), capacity);
+	crate::to_vec_in_with_config(&"fits", &mut buffer, config).unwrap();
+	assert_eq!(crate::from_slice::<String>(&buffer).unwrap(), "fits");
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_reader_reads_consecutive_values() {
+	init_tracing();
+	let messages = ["first", "a second message that is longer", "third"];
+	let mut stream = Vec::new();
+	for message in messages {
+		stream.extend(crate::to_vec(&message).unwrap());
+	}
+	let with_checksum = crate::checksum::to_vec_crc32(&messages).unwrap();
+	stream.extend_from_slice(&with_checksum);
+
+	// Without the excess data check, only the value is read and the next one stays in the reader.
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+	let mut reader = stream.as_slice();
+	for message in messages {
+		let value: String = crate::from_reader_with_config(&mut reader, config).unwrap();
+		assert_eq!(value, message);
+	}
+	let value: Vec<String> =
+		crate::checksum::from_reader_crc32_with_config(&mut reader, config).unwrap();
+	assert_eq!(value, messages);
+	assert!(reader.is_empty());
+
+	// With the check, the reader is read ahead and the following data is excess data.
+	let mut reader = stream.as_slice();
+	let result = crate::from_reader::<_, String>(&mut reader);
+	assert!(matches!(result, Err(Error::ExcessData { .. })));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_reader_with_buffer() {
+	let long = "a long string that needs the scratch buffer".repeat(4);
+	let mut buffer = Vec::new();
+	let bytes = crate::to_vec(&long).unwrap();
+	let value: String = crate::from_reader_with_buffer(bytes.as_slice(), &mut buffer).unwrap();
+	assert_eq!(value, long);
+	let capacity = buffer.capacity();
+	let pointer = buffer.as_ptr();
+	assert!(capacity >= long.len());
+
+	for expected in ["short", "", "medium sized string"] {
+		let bytes = crate::to_vec(&expected).unwrap();
+		let value: String = crate::from_reader_with_buffer(bytes.as_slice(), &mut buffer).unwrap();
+		assert_eq!(value, expected);
+		assert_eq!(buffer.capacity(), capacity);
+		assert_eq!(buffer.as_ptr(), pointer);
+	}
+
+	let config = Config { max_size: Some(NonZeroUsize::new(10).unwrap()), ..Default::default() };
+	let result = crate::from_reader_with_buffer_and_config::<_, String>(
+		bytes.as_slice(),
+		&mut buffer,
+		config,
+	);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	let bytes = crate::to_vec(&"fits").unwrap();
+	let value: String =
+		crate::from_reader_with_buffer_and_config(bytes.as_slice(), &mut buffer, config).unwrap();
+	assert_eq!(value, "fits");
+	assert_eq!(buffer.capacity(), capacity);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_enum_variant_names_from_reader() {
+	use crate::{io, Deserializer};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Enum {
+		Short,
+		AVariantNameThatDoesNotFitTheInlineBuffer,
+		Struct { a: bool },
+	}
+
+	let values =
+		[Enum::Short, Enum::AVariantNameThatDoesNotFitTheInlineBuffer, Enum::Struct { a: true }];
+	for value in &values {
+		let bytes = crate::to_vec(value).unwrap();
+		assert_eq!(&crate::from_slice::<Enum>(&bytes).unwrap(), value);
+		assert_eq!(&crate::from_reader::<_, Enum>(bytes.as_slice()).unwrap(), value);
+	}
+
+	// Short variant names do not need the buffer.
+	for value in [&Enum::Short, &Enum::Struct { a: false }] {
+		let bytes = crate::to_vec(value).unwrap();
+		let mut de = Deserializer::new(io::IoReader::new(bytes.as_slice()));
+		assert_eq!(&Enum::deserialize(&mut de).unwrap(), value);
+	}
+	let bytes = crate::to_vec(&Enum::AVariantNameThatDoesNotFitTheInlineBuffer).unwrap();
+	let mut de = Deserializer::new(io::IoReader::new(bytes.as_slice()));
+	assert!(matches!(Enum::deserialize(&mut de), Err(Error::BufferTooSmall)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_chars_from_reader_without_buffer() {
+	use crate::{io, Deserializer};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Chars {
+		a: char,
+		b: char,
+	}
+
+	let value = Chars { a: 'x', b: '😻' };
+	let bytes = crate::to_vec(&value).unwrap();
+	let mut de = Deserializer::new(io::IoReader::new(bytes.as_slice()));
+	assert_eq!(Chars::deserialize(&mut de).unwrap(), value);
+
+	// Too long strings are rejected without reading them.
+	let data = [Type::String.into(), 5, b'x', b'x', b'x', b'x', b'x'];
+	let mut de = Deserializer::new(data.as_slice());
+	assert!(matches!(char::deserialize(&mut de), Err(Error::NotOneChar)));
+	assert_eq!(de.into_input().len(), 5);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_writer_buffers_writes() {
+	/// Writer counting the `write` calls.
+	#[derive(Debug, Default)]
+	struct CountingWriter {
+		data: Vec<u8>,
+		writes: usize,
+		flushes: usize,
+	}
+
+	impl ::std::io::Write for CountingWriter {
+		fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+			self.writes += 1;
+			self.data.extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn flush(&mut self) -> ::std::io::Result<()> {
+			self.flushes += 1;
+			Ok(())
+		}
+	}
+
+	let value: Vec<(u32, &str, bool)> = (0 .. 1000).map(|i| (i, "entry", i % 2 == 0)).collect();
+	let expected = crate::to_vec(&value).unwrap();
+
+	let mut writer = CountingWriter::default();
+	crate::to_writer(&value, &mut writer).unwrap();
+	assert_eq!(writer.data, expected);
+	assert!(writer.writes <= expected.len().div_ceil(Config::default().writer_buffer_size));
+	assert_eq!(writer.flushes, 1);
+
+	let config = Config { writer_buffer_size: 0, ..Default::default() };
+	let mut writer = CountingWriter::default();
+	crate::to_writer_with_config(&value, &mut writer, config).unwrap();
+	assert_eq!(writer.data, expected);
+	assert!(writer.writes > 4000);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_writer_does_not_repeat_written_bytes() {
+	/// Writer taking at most 4 bytes per call, failing once on the second call.
+	#[derive(Debug, Default)]
+	struct FailingWriter {
+		data: Vec<u8>,
+		writes: usize,
+	}
+
+	impl ::std::io::Write for FailingWriter {
+		fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+			self.writes += 1;
+			if self.writes == 2 {
+				return Err(::std::io::Error::other("failing once"));
+			}
+			let chunk = buf.get(.. 4).unwrap_or(buf);
+			self.data.extend_from_slice(chunk);
+			Ok(chunk.len())
+		}
+
+		fn flush(&mut self) -> ::std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	init_tracing();
+	// Failing in the final flush, the rest is written on drop.
+	let value = (1_u32, "Hello, world!", true);
+	let expected = crate::to_vec(&value).unwrap();
+	let mut writer = FailingWriter::default();
+	assert!(crate::to_writer(&value, &mut writer).is_err());
+	assert_eq!(writer.data, expected);
+
+	// Failing in a write bypassing the buffer.
+	let value = (1_u32, "Hello, world! This is longer than the buffer.");
+	let expected = crate::to_vec(&value).unwrap();
+	let config = Config { writer_buffer_size: 8, ..Default::default() };
+	let mut writer = FailingWriter::default();
+	assert!(crate::to_writer_with_config(&value, &mut writer, config).is_err());
+	assert!(expected.starts_with(&writer.data));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_buf_reader_avoids_copies() {
+	use ::serde_bytes::ByteBuf;
+
+	use crate::{buffer::Buffer, io, Deserializer};
+
+	/// Buffer counting the bytes written to it.
+	#[derive(Debug, Default)]
+	struct CountingBuffer {
+		data: Vec<u8>,
+		written: usize,
+	}
+
+	impl Buffer for CountingBuffer {
+		fn clear(&mut self) {
+			self.data.clear();
+		}
+
+		fn as_slice(&self) -> &[u8] {
+			&self.data
+		}
+
+		fn push(&mut self, byte: u8) -> crate::Result<()> {
+			self.written += 1;
+			self.data.push(byte);
+			Ok(())
+		}
+
+		fn extend_from_slice(&mut self, bytes: &[u8]) -> crate::Result<()> {
+			self.written += bytes.len();
+			self.data.extend_from_slice(bytes);
+			Ok(())
+		}
+
+		fn reserve_slice(&mut self, len: usize) -> crate::Result<&mut [u8]> {
+			self.written += len;
+			self.data.reserve_slice(len)
+		}
+	}
+
+	let value: Vec<(String, ByteBuf)> = (0 .. 100)
+		.map(|i| (format!("This is a longer string with number {i}!"), ByteBuf::from(vec![i; 40])))
+		.collect();
+	let bytes = crate::to_vec(&value).unwrap();
+
+	let parsed: Vec<(String, ByteBuf)> = crate::from_reader(bytes.as_slice()).unwrap();
+	assert_eq!(parsed, value);
+	let parsed: Vec<(String, ByteBuf)> = crate::from_buf_reader(bytes.as_slice()).unwrap();
+	assert_eq!(parsed, value);
+	let parsed: Vec<(String, ByteBuf)> =
+		crate::from_buf_reader(::std::io::BufReader::with_capacity(64, bytes.as_slice())).unwrap();
+	assert_eq!(parsed, value);
+
+	let mut de = Deserializer::new(io::IoReader::new(bytes.as_slice()))
+		.with_buffer(CountingBuffer::default());
+	let parsed = Vec::<(String, ByteBuf)>::deserialize(&mut de).unwrap();
+	assert_eq!(parsed, value);
+	let copied_reader = de.into_parts().1.unwrap().written;
+
+	let mut de = Deserializer::new(io::BufReadInput::new(bytes.as_slice()))
+		.with_buffer(CountingBuffer::default());
+	let parsed = Vec::<(String, ByteBuf)>::deserialize(&mut de).unwrap();
+	assert_eq!(parsed, value);
+	let copied_buf_reader = de.into_parts().1.unwrap().written;
+
+	assert!(copied_reader > 100 * 70);
+	assert_eq!(copied_buf_reader, 0);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_from_reader_to_arena() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Borrowed<'a> {
+		name: &'a str,
+		#[serde(with = "serde_bytes")]
+		data: &'a [u8],
+		long: &'a str,
+	}
+
+	init_tracing();
+	let long = "a".repeat(1000);
+	let value = Borrowed { name: "name", data: &[1, 2, 3], long: &long };
+	let bytes = crate::to_vec(&value).unwrap();
+
+	let mut arena = b"existing".to_vec();
+	let parsed: Borrowed<'_> = crate::from_reader_to_arena(bytes.as_slice(), &mut arena).unwrap();
+	assert_eq!(parsed, value);
+	let long_ptr = parsed.long.as_ptr();
+	assert_eq!(arena.get(.. 8).unwrap(), b"existing");
+	assert_eq!(arena.get(8 ..).unwrap(), bytes);
+	assert!(arena.as_ptr_range().contains(&long_ptr));
+
+	// Only the value is appended, excess data and limits are checked.
+	let mut arena = Vec::new();
+	let mut input = bytes.clone();
+	input.push(Type::Null.into());
+	let result = crate::from_reader_to_arena::<_, Borrowed<'_>>(input.as_slice(), &mut arena);
+	assert!(matches!(result, Err(Error::ExcessData { .. })));
+	assert!(arena.is_empty());
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+	let parsed: Borrowed<'_> =
+		crate::from_reader_to_arena_with_config(input.as_slice(), &mut arena, config).unwrap();
+	assert_eq!(parsed, value);
+
+	let mut arena = Vec::new();
+	let config = Config { max_size: NonZeroUsize::new(100), ..Default::default() };
+	let result = crate::from_reader_to_arena_with_config::<_, Borrowed<'_>>(
+		bytes.as_slice(),
+		&mut arena,
+		config,
+	);
+	assert!(matches!(result, Err(Error::LimitReached)));
+
+	let result = crate::from_reader_to_arena::<_, Borrowed<'_>>(
+		bytes.get(.. bytes.len() - 1).unwrap(),
+		&mut arena,
+	);
+	assert!(matches!(result, Err(Error::UnexpectedEnd)));
+}
+
+#[cfg(feature = "heapless")]
+#[test]
+fn test_from_chunks_at_awkward_boundaries() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Reading {
+		id: u64,
+		label: ::heapless::String<64>,
+		samples: [i16; 3],
+	}
+
+	init_tracing();
+	let value = Reading {
+		id: u64::MAX / 3,
+		label: "A label that is longer than the inline buffer".try_into().unwrap(),
+		samples: [-300, 0, 300],
+	};
+	let mut buffer = [0; 128];
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+
+	// Every split position, including mid-varint and mid-string.
+	for split in 0 ..= bytes.len() {
+		let (first, second) = bytes.split_at(split);
+		let parsed: Reading =
+			crate::from_chunks([first, second], ::heapless::Vec::<u8, 64>::new()).unwrap();
+		assert_eq!(parsed, value);
+	}
+	let parsed: Reading =
+		crate::from_chunks(bytes.chunks(1), ::heapless::Vec::<u8, 64>::new()).unwrap();
+	assert_eq!(parsed, value);
+
+	// Too small buffers are reported.
+	let result =
+		crate::from_chunks::<_, _, Reading>(bytes.chunks(1), ::heapless::Vec::<u8, 8>::new());
+	assert!(matches!(result, Err(Error::BufferTooSmall)));
+
+	// Strings within a single chunk are borrowed.
+	let bytes = crate::to_slice(&("borrowed", 1_u8), &mut buffer).unwrap();
+	let (first, second) = bytes.split_at(bytes.len() - 2);
+	let parsed: (&str, u8) = crate::from_chunks([first, second], ()).unwrap();
+	assert_eq!(parsed, ("borrowed", 1));
+	let (first, second) = bytes.split_at(5);
+	let result = crate::from_chunks::<_, _, (&str, u8)>([first, second], ());
+	assert!(result.is_err());
+
+	// Configuration is applied.
+	let config = Config { max_size: Some(NonZeroUsize::new(4).unwrap()), ..Default::default() };
+	let result = crate::from_chunks_with_config::<_, _, (&str, u8)>([&*bytes], (), config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[cfg(feature = "bytes")]
+#[test]
+fn test_bytes_buf_at_awkward_boundaries() {
+	use ::alloc::{borrow::ToOwned, collections::VecDeque, string::String, vec, vec::Vec};
+	use ::bytes::Buf;
+
+	use crate::io::{BufInput, Input, ReadBytes};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Reading {
+		id: u64,
+		label: String,
+		#[serde(with = "serde_bytes")]
+		raw: Vec<u8>,
+		samples: [i16; 3],
+	}
+
+	init_tracing();
+	let value = Reading {
+		id: u64::MAX / 3,
+		label: "A label that is longer than the inline buffer".to_owned(),
+		raw: vec![0xAB; 20],
+		samples: [-300, 0, 300],
+	};
+	let bytes = crate::to_bytes_mut(&value).unwrap().freeze();
+	assert_eq!(bytes, crate::to_vec(&value).unwrap());
+
+	// Every split position, including mid-varint and mid-string.
+	for split in 0 ..= bytes.len() {
+		let chain = bytes.slice(.. split).chain(bytes.slice(split ..));
+		let parsed: Reading = crate::from_buf(chain).unwrap();
+		assert_eq!(parsed, value);
+	}
+	let parsed: Reading = crate::from_buf(bytes.clone()).unwrap();
+	assert_eq!(parsed, value);
+
+	// `VecDeque` wrapping around the end of its allocation has two chunks.
+	let (first, rest) = bytes.split_first().unwrap();
+	let mut deque: VecDeque<u8> = rest.iter().copied().collect();
+	deque.push_front(*first);
+	assert!(!deque.as_slices().1.is_empty());
+	let parsed: Reading = crate::from_buf(deque).unwrap();
+	assert_eq!(parsed, value);
+
+	// Data within a chunk is not copied.
+	let data = bytes.slice(1 ..);
+	let mut input = BufInput::new(data.clone());
+	let ReadBytes::Transient(read) = input.read_bytes_transient::<()>(4, None).unwrap() else {
+		panic!("transient read");
+	};
+	assert_eq!(read.as_ptr(), data.as_ptr());
+	assert_eq!(input.read_byte().unwrap(), data.get(4).copied().unwrap());
+	assert_eq!(input.into_inner().remaining(), data.len() - 5);
+
+	// Truncated data and configuration are handled.
+	let result = crate::from_buf::<_, Reading>(bytes.slice(.. bytes.len() - 1));
+	assert!(matches!(result, Err(Error::UnexpectedEnd)));
+	let config = Config { max_size: Some(NonZeroUsize::new(4).unwrap()), ..Default::default() };
+	let result = crate::from_buf_with_config::<_, Reading>(bytes.clone(), config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	let result = crate::to_bytes_mut_with_config(&value, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[cfg(all(feature = "embedded-io", feature = "heapless"))]
+#[test]
+fn test_embedded_io_roundtrip() {
+	type Message = (u32, ::heapless::String<64>, [i8; 3]);
+
+	init_tracing();
+	let first: Message =
+		(7, "A string longer than the inline buffer".try_into().unwrap(), [-1, 0, 1]);
+	let second: Message = (u32::MAX, ::heapless::String::new(), [0; 3]);
+
+	let mut stream = [0; 128];
+	let mut writer = stream.as_mut_slice();
+	crate::to_embedded_writer(&first, &mut writer).unwrap();
+	crate::to_embedded_writer(&second, &mut writer).unwrap();
+	let written = 128 - writer.len();
+	let mut expected = [0; 128];
+	let expected_len = crate::to_slice(&first, &mut expected).unwrap().len();
+	assert_eq!(stream.get(.. expected_len), expected.get(.. expected_len));
+
+	// Consecutive values are read from the stream.
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+	let mut reader = stream.get(.. written).unwrap();
+	let buffer = ::heapless::Vec::<u8, 64>::new();
+	let parsed: Message =
+		crate::from_embedded_reader_with_config(&mut reader, buffer, config).unwrap();
+	assert_eq!(parsed, first);
+	let buffer = ::heapless::Vec::<u8, 64>::new();
+	let parsed: Message =
+		crate::from_embedded_reader_with_config(&mut reader, buffer, config).unwrap();
+	assert_eq!(parsed, second);
+	assert!(reader.is_empty());
+
+	// Small buffers, limits and excess data are reported.
+	let bytes = stream.get(.. written).unwrap();
+	let result =
+		crate::from_embedded_reader::<_, _, Message>(bytes, ::heapless::Vec::<u8, 64>::new());
+	assert!(matches!(result, Err(Error::ExcessData { .. })));
+	let result =
+		crate::from_embedded_reader::<_, _, Message>(bytes, ::heapless::Vec::<u8, 8>::new());
+	assert!(matches!(result, Err(Error::BufferTooSmall)));
+	let config = Config { max_size: Some(NonZeroUsize::new(4).unwrap()), ..Default::default() };
+	let result = crate::to_embedded_writer_with_config(&first, [0; 128].as_mut_slice(), config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[test]
+fn test_debug_checks_detect_unbalanced_output() {
+	use ::serde::ser::{SerializeMap, SerializeSeq, Serializer as _};
+
+	/// Starts a sequence, but fails before ending it.
+	struct Failing;
+	impl Serialize for Failing {
+		fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			let mut seq = serializer.serialize_seq(None)?;
+			seq.serialize_element(&1_u8)?;
+			Err(::serde::ser::Error::custom("failed"))
+		}
+	}
+
+	/// Ignores the error of an element, leaving its sequence unbalanced.
+	struct Broken;
+	impl Serialize for Broken {
+		fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			let mut seq = serializer.serialize_seq(Some(2))?;
+			_ = seq.serialize_element(&Failing);
+			seq.serialize_element(&2_u8)?;
+			seq.end()
+		}
+	}
+
+	/// Announces more elements than it serializes.
+	struct Short;
+	impl Serialize for Short {
+		fn serialize<S: ::serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+			let mut seq = serializer.serialize_seq(Some(2))?;
+			seq.serialize_element(&1_u8)?;
+			seq.end()
+		}
+	}
+
+	let mut buffer = [0; 64];
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice()).debug_checks(true);
+	Broken.serialize(&mut ser).unwrap();
+	assert!(matches!(ser.finish(), Err(Error::UnbalancedCollection)));
+
+	// Without debug checks, the output is returned as is.
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice());
+	Broken.serialize(&mut ser).unwrap();
+	assert!(ser.finish().is_ok());
+
+	// Too many ends are reported right away.
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice()).debug_checks(true);
+	let map = (&mut ser).serialize_map(None).unwrap();
+	map.end().unwrap();
+	let map = &mut ser;
+	assert!(matches!(SerializeMap::end(map), Err(Error::UnbalancedCollection)));
+
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice()).debug_checks(true);
+	(1, "a", [(2, 3)], Some([4])).serialize(&mut ser).unwrap();
+	assert!(ser.finish().is_ok());
+
+	// Length-prefixed sequences always check the number of elements.
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice()).length_prefixed_seqs(true);
+	assert!(matches!(Short.serialize(&mut ser), Err(Error::UnbalancedCollection)));
+	let mut ser = crate::Serializer::new(buffer.as_mut_slice()).length_prefixed_seqs(true);
+	let mut seq = (&mut ser).serialize_seq(Some(0)).unwrap();
+	assert!(matches!(seq.serialize_element(&1_u8), Err(Error::UnbalancedCollection)));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_skip_unknown_fields() {
+	use ::std::collections::BTreeMap;
+
+	use crate::{io, Deserializer};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Known {
+		a: u8,
+		b: u8,
+	}
+	#[derive(Debug, Serialize, Deserialize)]
+	struct Full {
+		a: u8,
+		unknown: BTreeMap<String, Vec<String>>,
+		b: u8,
+	}
+
+	init_tracing();
+	let long = "This string is too long for the inline buffer.".to_owned();
+	let value = Full {
+		a: 1,
+		unknown: BTreeMap::from([(long.clone(), vec![long.clone(); 3]), ("x".to_owned(), vec![])]),
+		b: 2,
+	};
+	let bytes = crate::to_vec(&value).unwrap();
+
+	// Skipped strings do not need a buffer.
+	let mut de = Deserializer::new(io::IoReader::new(bytes.as_slice()));
+	let parsed = Known::deserialize(&mut de).unwrap();
+	assert_eq!(parsed, Known { a: 1, b: 2 });
+	assert!(Full::deserialize(&mut Deserializer::new(io::IoReader::new(bytes.as_slice()))).is_err());
+
+	// Skipped strings are not checked for UTF-8.
+	let bytes = [Type::SeqStart.into(), Type::String.into(), 1, 0xFF, Type::SeqEnd.into(), 5];
+	let mut de = Deserializer::new(bytes.as_slice());
+	de.skip_value().unwrap();
+	assert_eq!(de.into_input(), [5]);
+
+	let bytes = [Type::MapStart.into(), Type::Null.into(), Type::MapEnd.into()];
+	let result = Deserializer::new(bytes.as_slice()).skip_value();
+	assert!(matches!(result, Err(Error::WrongType(Type::MapEnd, _))));
+	// Unsigned and signed integers are skipped the same way, up to the same length.
+	#[cfg(not(feature = "big-integers"))]
+	for t in [Type::UnsignedInt, Type::SignedInt] {
+		let mut bytes = vec![t.into()];
+		bytes.extend([0xFF; <u128 as crate::format::VarInt>::MAX_BYTES - 1]);
+		bytes.push(0x01);
+		Deserializer::new(bytes.as_slice()).skip_value().unwrap();
+		bytes.insert(1, 0xFF);
+		let result = Deserializer::new(bytes.as_slice()).skip_value();
+		assert!(matches!(result, Err(Error::VarIntTooLarge)), "{t:?}");
+	}
+	// Length-prefixed sequences are skipped by their number of elements.
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	let bytes = crate::to_vec_with_config(&value, config).unwrap();
+	assert!(bytes.contains(&Type::SeqFixed.into()));
+	let parsed: Known = crate::from_slice(&bytes).unwrap();
+	assert_eq!(parsed, Known { a: 1, b: 2 });
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_length_prefixed_seqs_hint_size() {
+	use ::alloc::{vec, vec::Vec};
+
+	/// Records the size hint of the sequence.
+	#[derive(Debug)]
+	struct SizeHint(Option<usize>, Vec<u64>);
+	impl<'de> Deserialize<'de> for SizeHint {
+		fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			struct Visitor;
+			impl<'de> ::serde::de::Visitor<'de> for Visitor {
+				type Value = SizeHint;
+
+				fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+					formatter.write_str("a sequence")
+				}
+
+				fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
+				where
+					A: ::serde::de::SeqAccess<'de>,
+				{
+					let hint = seq.size_hint();
+					let mut values = Vec::new();
+					while let Some(value) = seq.next_element()? {
+						values.push(value);
+					}
+					Ok(SizeHint(hint, values))
+				}
+			}
+			deserializer.deserialize_seq(Visitor)
+		}
+	}
+
+	init_tracing();
+	let value = vec![1_u64, 2, 300];
+	let bytes = crate::to_vec(&value).unwrap();
+	let parsed: SizeHint = crate::from_slice(&bytes).unwrap();
+	assert_eq!((parsed.0, parsed.1), (None, value.clone()));
+
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	let bytes = crate::to_vec_with_config(&value, config).unwrap();
+	assert_eq!(bytes.get(.. 2), Some([Type::SeqFixed.into(), 3].as_slice()));
+	let parsed: SizeHint = crate::from_slice(&bytes).unwrap();
+	assert_eq!((parsed.0, parsed.1), (Some(3), value.clone()));
+	assert_eq!(crate::from_slice::<Vec<u64>>(&bytes).unwrap(), value);
+
+	// Types taking fewer elements than announced report how many they expected.
+	let result = crate::from_slice::<(u64, u64)>(&bytes);
+	assert_eq!(
+		result,
+		Err(Error::Message("invalid length 3, expected 2 elements in sequence".into()))
+	);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_to_writer_passes_large_bytes_through() {
+	use ::serde_bytes::ByteBuf;
+	use ::std::io::{IoSlice, Write};
+
+	/// Writer recording the slices it is given.
+	#[derive(Debug, Default)]
+	struct RecordingWriter {
+		/// Pointer and length of every written slice.
+		slices: Vec<(*const u8, usize)>,
+		/// All written data.
+		data: Vec<u8>,
+	}
+
+	impl Write for RecordingWriter {
+		fn write(&mut self, buf: &[u8]) -> ::std::io::Result<usize> {
+			self.slices.push((buf.as_ptr(), buf.len()));
+			self.data.extend_from_slice(buf);
+			Ok(buf.len())
+		}
+
+		fn write_vectored(&mut self, bufs: &[IoSlice<'_>]) -> ::std::io::Result<usize> {
+			bufs.iter().map(|buf| self.write(buf)).sum()
+		}
+
+		fn flush(&mut self) -> ::std::io::Result<()> {
+			Ok(())
+		}
+	}
+
+	init_tracing();
+	let payload = ByteBuf::from(vec![7; 100_000]);
+	let value = (1_u8, &payload, "end");
+	let mut writer = RecordingWriter::default();
+	crate::to_writer(&value, &mut writer).unwrap();
+	assert_eq!(writer.data, crate::to_vec(&value).unwrap());
+
+	// The payload is handed to the writer directly instead of being copied to the buffer.
+	assert!(writer.slices.contains(&(payload.as_ptr(), payload.len())));
+	let header = writer.slices.first().unwrap().1;
+	assert!(header < 16);
+
+	let config = Config { max_size: NonZeroUsize::new(200_000), ..Default::default() };
+	let mut writer = RecordingWriter::default();
+	crate::to_writer_with_config(&value, &mut writer, config).unwrap();
+	assert!(writer.slices.contains(&(payload.as_ptr(), payload.len())));
+}
+
+#[test]
+fn test_validate() {
+	let mut buffer = [0; 256];
+	let value = (1_u8, -2_i16, 3.5_f64, "string", Bytes::new(&[1, 2]), [(true, ())], Some(0_u128));
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+	crate::validate(bytes, Config::default()).unwrap();
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+	crate::validate(bytes, config).unwrap();
+
+	let seq = Type::SeqStart.into();
+	let map = Type::MapStart.into();
+	let uint = Type::UnsignedInt.into();
+	let string = Type::String.into();
+	let validate = |bytes: &[u8]| crate::validate(bytes, Config::default()).unwrap_err();
+	// Truncated varints.
+	assert!(matches!(validate(&[uint, 0x80]), Error::UnexpectedEnd));
+	assert!(matches!(validate(&[uint, 0x80, 0x80, 0x80, 0x80]), Error::UnexpectedEnd));
+	let mut too_large = [0xFF; 21];
+	too_large[0] = uint;
+	#[cfg(not(feature = "big-integers"))]
+	assert!(matches!(validate(&too_large), Error::VarIntTooLarge));
+	// Big integers are only limited by their maximum length.
+	#[cfg(feature = "big-integers")]
+	assert!(matches!(validate(&too_large), Error::UnexpectedEnd));
+	// Bad type codes.
+	assert!(matches!(validate(&[9]), Error::InvalidType(9)));
+	assert!(matches!(validate(&[seq, 0xFF]), Error::InvalidType(0xFF)));
+	// Unbalanced sequences and maps.
+	assert!(matches!(validate(&[seq, uint, 1]), Error::UnexpectedEnd));
+	assert!(matches!(validate(&[map, uint, 1]), Error::UnexpectedEnd));
+	assert!(matches!(validate(&[Type::SeqEnd.into()]), Error::WrongType(Type::SeqEnd, _)));
+	assert!(matches!(validate(&[seq, Type::MapEnd.into()]), Error::WrongType(Type::MapEnd, _)));
+	assert!(matches!(validate(&[map, Type::SeqEnd.into()]), Error::WrongType(Type::SeqEnd, _)));
+	assert!(matches!(
+		validate(&[map, uint, 1, Type::MapEnd.into()]),
+		Error::WrongType(Type::MapEnd, _)
+	));
+	// Length-prefixed sequences contain exactly the announced number of elements.
+	let fixed = Type::SeqFixed.into();
+	crate::validate(&[fixed, 2, uint, 1, Type::Null.into()], Config::default()).unwrap();
+	crate::validate(&[fixed, 0], Config::default()).unwrap();
+	assert!(matches!(validate(&[fixed, 2, uint, 1]), Error::UnexpectedEnd));
+	assert!(matches!(
+		validate(&[fixed, 1, Type::SeqEnd.into()]),
+		Error::WrongType(Type::SeqEnd, _)
+	));
+	// Invalid strings and truncated data.
+	assert!(matches!(validate(&[string, 2, 0xFF, 0xFE]), Error::StringNotUtf8(_)));
+	assert!(matches!(validate(&[string, 5, b'a']), Error::UnexpectedEnd));
+	assert!(matches!(validate(&[Type::Float64.into(), 0, 0]), Error::UnexpectedEnd));
+	assert!(matches!(validate(&[]), Error::UnexpectedEnd));
+
+	// Excess data and size limits.
+	let bytes = [uint, 1, uint, 2];
+	assert!(matches!(crate::validate(&bytes, Config::default()), Err(Error::ExcessData { .. })));
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+	crate::validate(&bytes, config).unwrap();
+	let config = Config { max_size: NonZeroUsize::new(3), ..Default::default() };
+	let bytes = [string, 3, b'a', b'b', b'c'];
+	assert!(matches!(crate::validate(&bytes, config), Err(Error::LimitReached)));
+}
+
+#[test]
+fn test_strict_keys() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Struct {
+		a: bool,
+		b: Inner,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Inner {
+		c: u8,
+	}
+
+	init_tracing();
+	let strict = Config { strict_keys: true, ..Default::default() };
+	let uint = Type::UnsignedInt.into();
+	let string = Type::String.into();
+	let map_start = Type::MapStart.into();
+	let map_end = Type::MapEnd.into();
+	let expected = Struct { a: true, b: Inner { c: 5 } };
+
+	// Mixed keys in one struct.
+	let bytes = [
+		map_start,
+		string,
+		1,
+		b'a',
+		Type::BooleanTrue.into(),
+		uint,
+		1,
+		map_start,
+		string,
+		1,
+		b'c',
+		uint,
+		5,
+		map_end,
+		map_end,
+	];
+	assert_eq!(crate::from_slice::<Struct>(&bytes).unwrap(), expected);
+	let result = crate::from_slice_with_config::<Struct>(&bytes, strict);
+	assert!(matches!(result, Err(Error::WrongType(Type::UnsignedInt, [Type::String]))));
+
+	let bytes = [
+		map_start,
+		uint,
+		0,
+		Type::BooleanTrue.into(),
+		string,
+		1,
+		b'b',
+		map_start,
+		uint,
+		0,
+		uint,
+		5,
+		map_end,
+		map_end,
+	];
+	assert_eq!(crate::from_slice::<Struct>(&bytes).unwrap(), expected);
+	let result = crate::from_slice_with_config::<Struct>(&bytes, strict);
+	assert!(matches!(result, Err(Error::WrongType(Type::String, [Type::UnsignedInt]))));
+
+	// Nested structs lock their key kind independently.
+	let bytes = [
+		map_start,
+		string,
+		1,
+		b'a',
+		Type::BooleanTrue.into(),
+		string,
+		1,
+		b'b',
+		map_start,
+		uint,
+		0,
+		uint,
+		5,
+		map_end,
+		map_end,
+	];
+	assert_eq!(crate::from_slice_with_config::<Struct>(&bytes, strict).unwrap(), expected);
+
+	// Maps are not affected.
+	#[cfg(feature = "alloc")]
+	{
+		let bytes = [map_start, string, 1, b'a', uint, 1, uint, 2, uint, 3, map_end];
+		let value = crate::from_slice_with_config::<crate::value::Value>(&bytes, strict).unwrap();
+		assert_eq!(value.as_map().unwrap().len(), 2);
+	}
+}
+
+#[test]
+fn test_index_keys_mixed_with_names() {
+	#[derive(Debug, PartialEq, Deserialize)]
+	struct Struct {
+		a: u8,
+		b: u8,
+		c: u8,
+	}
+
+	init_tracing();
+	let uint = Type::UnsignedInt.into();
+	let string = Type::String.into();
+	let map_start = Type::MapStart.into();
+	let map_end = Type::MapEnd.into();
+
+	// Index keys after names and unknown indices are still decoded.
+	let bytes = [
+		map_start, uint, 0, uint, 1, string, 1, b'b', uint, 2, uint, 9, uint, 0, uint, 2, uint, 3,
+		map_end,
+	];
+	assert_eq!(crate::from_slice::<Struct>(&bytes).unwrap(), Struct { a: 1, b: 2, c: 3 });
+
+	let bytes = [map_start, uint, 0, uint, 1, uint, 1, uint, 2, uint, 2, string, 1, b'x', map_end];
+	let result = crate::from_slice::<Struct>(&bytes);
+	assert!(matches!(result, Err(Error::WrongFieldType { name: "Struct", field: "c", .. })));
+}
+
+#[test]
+fn test_intern_field_names() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Struct<'a> {
+		#[serde(alias = "old_name")]
+		name: &'a str,
+		count: u8,
+		#[serde(borrow)]
+		inner: Option<Inner<'a>>,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Inner<'a> {
+		value: &'a str,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Renamed<'a> {
+		old_name: &'a str,
+		count: u8,
+		unknown: (u8, &'a str),
+		#[serde(borrow)]
+		inner: Option<Inner<'a>>,
+	}
+
+	init_tracing();
+	let interned = Config { intern_field_names: true, ..Default::default() };
+	let mut buffer = [0; 128];
+
+	let expected = Struct { name: "name", count: 5, inner: Some(Inner { value: "value" }) };
+	let bytes = crate::to_slice(&expected, &mut buffer).unwrap();
+	let parsed: Struct<'_> = crate::from_slice_with_config(bytes, interned).unwrap();
+	assert_eq!(parsed, expected);
+	let parsed: Struct<'_> = crate::from_slice(bytes).unwrap();
+	assert_eq!(parsed, expected);
+
+	// Aliases and unknown fields still work.
+	let renamed = Renamed {
+		old_name: "name",
+		count: 5,
+		unknown: (1, "ignored"),
+		inner: Some(Inner { value: "value" }),
+	};
+	let bytes = crate::to_slice(&renamed, &mut buffer).unwrap();
+	let parsed: Struct<'_> = crate::from_slice_with_config(bytes, interned).unwrap();
+	assert_eq!(parsed, expected);
+
+	// Keys that are not valid UTF-8 are still rejected.
+	let bytes = [Type::MapStart.into(), Type::String.into(), 2, 0xFF, 0xFE, Type::MapEnd.into()];
+	let result = crate::from_slice_with_config::<Struct<'_>>(&bytes, interned);
+	assert!(matches!(result, Err(Error::StringNotUtf8(_))));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_reject_duplicate_map_keys() {
+	use ::std::collections::{BTreeMap, HashMap};
+
+	use crate::value::Value;
+
+	init_tracing();
+	let strict = Config { reject_duplicate_map_keys: true, ..Default::default() };
+	let string = Type::String.into();
+	let map_start = Type::MapStart.into();
+	let map_end = Type::MapEnd.into();
+
+	// Same key twice with different values.
+	let bytes = [
+		map_start, string, 5, b'r', b'o', b'l', b'e', b's', string, 4, b'u', b's', b'e', b'r',
+		string, 5, b'r', b'o', b'l', b'e', b's', string, 5, b'a', b'd', b'm', b'i', b'n', map_end,
+	];
+	let lenient: HashMap<&str, &str> = crate::from_slice(&bytes).unwrap();
+	assert_eq!(lenient, HashMap::from([("roles", "admin")]));
+	let lenient: Value<'_> = crate::from_slice(&bytes).unwrap();
+	assert_eq!(lenient.as_map().unwrap().len(), 2);
+	let result = crate::from_slice_with_config::<HashMap<&str, &str>>(&bytes, strict);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+	let result = crate::from_slice_with_config::<Value<'_>>(&bytes, strict);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+	let result =
+		crate::from_reader_with_config::<_, BTreeMap<String, String>>(bytes.as_slice(), strict);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+	let limited = Config { max_size: Some(100.try_into().unwrap()), ..strict };
+	let result = crate::from_buf_reader_with_config::<_, BTreeMap<String, String>>(
+		bytes.as_slice(),
+		limited,
+	);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+
+	// Manually constructed deserializers need to record keys of inputs that cannot lend them.
+	let mut de = crate::Deserializer::new(crate::io::IoReader::new(bytes.as_slice()))
+		.with_buffer(Vec::new())
+		.with_config(strict);
+	let result = <BTreeMap<String, String>>::deserialize(&mut de);
+	assert!(matches!(result, Err(Error::CannotRecord)));
+	let input = crate::io::RecordingInput::new(crate::io::IoReader::new(bytes.as_slice()));
+	let mut de = crate::Deserializer::new(input).with_buffer(Vec::new()).with_config(strict);
+	let result = <BTreeMap<String, String>>::deserialize(&mut de);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+
+	// Distinct keys, nested maps as keys and ignored fields are fine.
+	let value = BTreeMap::from([
+		(BTreeMap::from([(1_u8, "a")]), vec![BTreeMap::from([("x", 1_u8), ("y", 1)])]),
+		(BTreeMap::from([(1_u8, "b")]), vec![BTreeMap::from([("x", 1_u8)])]),
+	]);
+	let bytes = crate::to_vec(&value).unwrap();
+	let parsed: BTreeMap<BTreeMap<u8, &str>, Vec<BTreeMap<&str, u8>>> =
+		crate::from_slice_with_config(&bytes, strict).unwrap();
+	assert_eq!(parsed, value);
+	let parsed: () = crate::from_slice_with_config(&crate::to_vec(&()).unwrap(), strict).unwrap();
+	assert_eq!(parsed, ());
+
+	// Keys are compared per map.
+	let value = vec![BTreeMap::from([("a", 1_u8)]), BTreeMap::from([("a", 2_u8)])];
+	let bytes = crate::to_vec(&value).unwrap();
+	let parsed: Vec<BTreeMap<&str, u8>> = crate::from_slice_with_config(&bytes, strict).unwrap();
+	assert_eq!(parsed, value);
+
+	// Keys of the string table are compared by their string.
+	let bytes = [
+		map_start,
+		Type::StringDef.into(),
+		1,
+		b'a',
+		Type::Null.into(),
+		Type::StringRef.into(),
+		0,
+		Type::Null.into(),
+		map_end,
+	];
+	let result = crate::from_slice_with_config::<Value<'_>>(&bytes, strict);
+	assert!(matches!(result, Err(Error::DuplicateMapKey)));
+}
+
+#[cfg(not(feature = "alloc"))]
+#[test]
+fn test_alloc_options_without_alloc() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Pair {
+		b: u8,
+		a: u8,
+	}
+
+	init_tracing();
+	// Serialization options are ignored.
+	let mut plain_buffer = [0; 64];
+	let plain = crate::to_slice(&Pair { b: 1, a: 2 }, &mut plain_buffer).unwrap();
+	let config = Config {
+		canonical: true,
+		key_dictionary: true,
+		detect_duplicate_keys: true,
+		..Default::default()
+	};
+	let mut buffer = [0; 64];
+	let bytes = crate::to_slice_with_config(&Pair { b: 1, a: 2 }, &mut buffer, config).unwrap();
+	assert_eq!(bytes, plain);
+
+	// Duplicate map keys cannot be rejected.
+	let strict = Config { reject_duplicate_map_keys: true, ..Default::default() };
+	let result = crate::from_slice_with_config::<Pair>(bytes, strict);
+	assert!(matches!(result, Err(Error::CannotRecord)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_canonical_output() {
+	use ::alloc::collections::BTreeMap;
+
+	#[derive(Debug, Serialize)]
+	struct First {
+		a: u8,
+		b: Inner,
+		c: BTreeMap<&'static str, u8>,
+	}
+	#[derive(Debug, Serialize)]
+	struct Second {
+		c: BTreeMap<&'static str, u8>,
+		b: Inner,
+		a: u8,
+	}
+	#[derive(Debug, Serialize)]
+	struct Inner {
+		y: bool,
+		x: bool,
+	}
+
+	init_tracing();
+	let map = BTreeMap::from([("aa", 1), ("b", 2)]);
+	let first = First { a: 1, b: Inner { y: true, x: false }, c: map.clone() };
+	let second = Second { c: map, b: Inner { y: true, x: false }, a: 1 };
+	assert_ne!(crate::to_vec(&first).unwrap(), crate::to_vec(&second).unwrap());
+
+	let bytes = crate::to_vec_canonical(&first).unwrap();
+	assert_eq!(bytes, crate::to_vec_canonical(&second).unwrap());
+	assert_eq!(bytes.len(), crate::serialized_size(&first).unwrap());
+	let string = u8::from(Type::String);
+	let uint = u8::from(Type::UnsignedInt);
+	let map_start = u8::from(Type::MapStart);
+	let map_end = u8::from(Type::MapEnd);
+	#[rustfmt::skip]
+	let expected = [
+		map_start,
+			string, 1, b'a', uint, 1,
+			string, 1, b'b', map_start,
+				string, 1, b'x', Type::BooleanFalse.into(),
+				string, 1, b'y', Type::BooleanTrue.into(),
+			map_end,
+			string, 1, b'c', map_start,
+				// Sorted by the serialized key, so the shorter string comes first.
+				string, 1, b'b', uint, 2,
+				string, 2, b'a', b'a', uint, 1,
+			map_end,
+		map_end,
+	];
+	assert_eq!(bytes, expected);
+
+	let config = Config { canonical: true, max_size: NonZeroUsize::new(10), ..Default::default() };
+	let result = crate::to_vec_with_config(&first, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_key_dictionary() {
+	use ::alloc::{borrow::ToOwned, collections::BTreeMap, string::String, vec, vec::Vec};
+
+	#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+	struct Point {
+		x: u8,
+		y: u8,
+	}
+	#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+	enum Key {
+		Unit,
+		Newtype(u8),
+	}
+
+	init_tracing();
+	let config = Config { key_dictionary: true, ..Default::default() };
+	let def = Type::StringDef.into();
+	let reference = Type::StringRef.into();
+	let uint = Type::UnsignedInt.into();
+	let map_start = Type::MapStart.into();
+	let map_end = Type::MapEnd.into();
+
+	let value = vec![Point { x: 1, y: 2 }; 2];
+	let bytes = crate::to_vec_with_config(&value, config).unwrap();
+	assert_eq!(
+		bytes,
+		[
+			Type::SeqStart.into(),
+			map_start,
+			def,
+			1,
+			b'x',
+			uint,
+			1,
+			def,
+			1,
+			b'y',
+			uint,
+			2,
+			map_end,
+			map_start,
+			reference,
+			0,
+			uint,
+			1,
+			reference,
+			1,
+			uint,
+			2,
+			map_end,
+			Type::SeqEnd.into()
+		]
+	);
+	assert_eq!(crate::from_slice::<Vec<Point>>(&bytes).unwrap(), value);
+	let strict = Config { strict_keys: true, intern_field_names: true, ..Default::default() };
+	assert_eq!(crate::from_slice_with_config::<Vec<Point>>(&bytes, strict).unwrap(), value);
+	crate::validate(&bytes, Config::default()).unwrap();
+	let parsed: crate::value::Value<'_> = crate::from_slice(&bytes).unwrap();
+	assert_eq!(crate::value::from_value::<Vec<Point>>(parsed).unwrap(), value);
+
+	// All string keys of maps use the table, other strings do not.
+	let value =
+		BTreeMap::from([("key".to_owned(), vec!["key".to_owned()]), ("other".to_owned(), vec![])]);
+	let maps = vec![value.clone(), value];
+	let bytes = crate::to_vec_with_config(&maps, config).unwrap();
+	assert_eq!(bytes.iter().filter(|byte| **byte == def).count(), 2);
+	assert_eq!(crate::from_slice::<Vec<BTreeMap<String, Vec<String>>>>(&bytes).unwrap(), maps);
+	let value = vec![BTreeMap::from([(Key::Unit, 'a'), (Key::Newtype(1), 'b')]); 2];
+	let bytes = crate::to_vec_with_config(&value, config).unwrap();
+	assert_eq!(crate::from_slice::<Vec<BTreeMap<Key, char>>>(&bytes).unwrap(), value);
+	let value = vec![BTreeMap::from([('k', Key::Unit)]); 2];
+	let bytes = crate::to_vec_with_config(&value, config).unwrap();
+	assert_eq!(crate::from_slice::<Vec<BTreeMap<char, Key>>>(&bytes).unwrap(), value);
+
+	// Skipped values define strings as well.
+	let bytes =
+		crate::to_vec_with_config(&(Point { x: 1, y: 2 }, Point { x: 3, y: 4 }), config).unwrap();
+	let (_, second): (crate::value::Value<'_>, Point) = crate::from_slice(&bytes).unwrap();
+	assert_eq!(second, Point { x: 3, y: 4 });
+	let (_, second): (::serde::de::IgnoredAny, Point) = crate::from_slice(&bytes).unwrap();
+	assert_eq!(second, Point { x: 3, y: 4 });
+
+	// Unknown references and canonical output.
+	let result = crate::from_slice::<String>(&[reference, 0]);
+	assert!(matches!(result, Err(Error::UnknownStringRef(0))));
+	assert!(matches!(crate::validate(&[reference, 0], config), Err(Error::UnknownStringRef(0))));
+	let canonical = Config { canonical: true, ..config };
+	let bytes = crate::to_vec_with_config(&vec![Point { x: 1, y: 2 }; 2], canonical).unwrap();
+	assert!(!bytes.contains(&def));
+}
+
+#[test]
+fn test_deserializer_with_config() {
+	use crate::Deserializer;
+
+	init_tracing();
+	let mut buffer = [0; 16];
+	let bytes = crate::to_slice(&(-1_i8, true), &mut buffer).unwrap();
+	let config = Config { lenient_numbers: true, ..Default::default() };
+	let mut de = Deserializer::new(&*bytes).with_config(config);
+	assert_eq!(<(i16, u8)>::deserialize(&mut de).unwrap(), (-1, 1));
+	de.end().unwrap();
+
+	let data = [Type::UnsignedInt.into(), 1, Type::Null.into(), Type::Null.into()];
+	let mut de = Deserializer::new(data.as_slice()).with_config(Config::default());
+	assert_eq!(u8::deserialize(&mut de).unwrap(), 1);
+	assert!(matches!(de.end(), Err(Error::ExcessData { remaining: Some(2), .. })));
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+	let mut de = Deserializer::new(data.as_slice()).with_config(config);
+	assert_eq!(u8::deserialize(&mut de).unwrap(), 1);
+	de.end().unwrap();
+	assert_eq!(de.into_input().len(), 2);
+
+	let config = Config { max_depth: 1, ..Default::default() };
+	let bytes = crate::to_slice(&[[1_u8]], &mut buffer).unwrap();
+	let mut de = Deserializer::new(&*bytes).with_config(config);
+	assert!(matches!(<[[u8; 1]; 1]>::deserialize(&mut de), Err(Error::DepthLimitReached)));
+}
+
+#[test]
+fn test_raw_value() {
+	use crate::RawValue;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Lazy<'a> {
+		id: u8,
+		#[serde(borrow)]
+		payload: RawValue<'a>,
+		after: bool,
+	}
+
+	init_tracing();
+	// Strings in raw values are not validated as UTF-8.
+	let payload = [
+		Type::SeqStart.into(),
+		Type::String.into(),
+		2,
+		0xFF,
+		0xFE,
+		Type::Some.into(),
+		Type::Null.into(),
+		Type::SeqEnd.into(),
+	];
+	let payload = RawValue::from_slice(&payload).unwrap();
+	assert_eq!(payload.as_bytes().len(), 8);
+	let value = Lazy { id: 1, payload, after: true };
+	let mut buffer = [0; 64];
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+	assert_eq!(crate::serialized_size(&value).unwrap(), bytes.len());
+	let parsed: Lazy = crate::from_slice(bytes).unwrap();
+	assert_eq!(parsed, value);
+	assert!(matches!(
+		parsed.payload.deserialize_as::<(&str, Option<()>)>(),
+		Err(Error::StringNotUtf8(_))
+	));
+	let (raw, unit): (&Bytes, Option<()>) = parsed.payload.deserialize_as().unwrap();
+	assert_eq!((raw.as_ref(), unit), ([0xFF, 0xFE].as_slice(), Some(())));
+
+	// Raw values must be exactly one value.
+	assert!(matches!(RawValue::from_slice(&[]), Err(Error::UnexpectedEnd)));
+	assert!(matches!(
+		RawValue::from_slice(&[Type::Null.into(), Type::Null.into()]),
+		Err(Error::ExcessData { .. })
+	));
+
+	// Limits apply to the skipped value.
+	let config = Config { max_size: Some(NonZeroUsize::new(6).unwrap()), ..Default::default() };
+	let result = crate::from_slice_with_config::<Lazy>(bytes, config);
+	assert!(matches!(result, Err(Error::LimitReached)));
+	let config = Config { max_depth: 0, ..Default::default() };
+	assert!(matches!(
+		payload.deserialize_as_with_config::<RawValue>(config),
+		Err(Error::DepthLimitReached)
+	));
+
+	// Non-borrowed inputs cannot provide raw values.
+	let result = crate::from_chunks::<_, _, Lazy>([&*bytes], ());
+	assert!(result.is_err());
+}
+
+#[cfg(feature = "text")]
+#[test]
+fn test_text_encodings() {
+	use ::alloc::{borrow::ToOwned, string::String, vec, vec::Vec};
+
+	init_tracing();
+	// All lengths of the last base64 group.
+	for data in [vec![], vec![1_u8], vec![1, 2], vec![1, 2, 3], (0 ..= 255).collect()] {
+		let bytes = crate::to_vec(&Bytes::new(&data)).unwrap();
+		let base64 = crate::to_base64(&Bytes::new(&data)).unwrap();
+		assert_eq!(base64.len(), bytes.len().div_ceil(3) * 4);
+		assert_eq!(crate::from_base64::<::serde_bytes::ByteBuf>(&base64).unwrap(), data);
+		let hex = crate::to_hex(&Bytes::new(&data)).unwrap();
+		assert_eq!(hex.len(), bytes.len() * 2);
+		assert_eq!(crate::from_hex::<::serde_bytes::ByteBuf>(&hex).unwrap(), data);
+		assert_eq!(crate::from_hex::<::serde_bytes::ByteBuf>(&hex.to_uppercase()).unwrap(), data);
+	}
+
+	assert_eq!(crate::to_base64(&"hi").unwrap(), "CwJoaQ==");
+	assert_eq!(crate::to_base64(&"abc").unwrap(), "CwNhYmM=");
+	assert_eq!(crate::to_hex(&"hi").unwrap(), "0b026869");
+	let strings = vec!["a".to_owned(), "long string that needs the buffer".to_owned()];
+	let text = crate::to_base64(&strings).unwrap();
+	assert_eq!(crate::from_base64::<Vec<String>>(&text).unwrap(), strings);
+
+	let invalid = |result: crate::Result<String>, reason: &str| match result {
+		Err(Error::Encoding(found)) => assert_eq!(found, reason),
+		other => panic!("expected encoding error `{reason}`, got {other:?}"),
+	};
+	invalid(crate::from_base64("CwJoaQ"), "base64 length is not a multiple of 4");
+	invalid(crate::from_base64("CwJoaQ="), "base64 length is not a multiple of 4");
+	invalid(crate::from_base64("CwJoa==="), "invalid base64 padding");
+	invalid(crate::from_base64("Cw==aQ=="), "invalid base64 padding");
+	invalid(crate::from_base64("CwJoaR=="), "invalid base64 padding");
+	invalid(crate::from_base64("CwJo aQ="), "invalid base64 character");
+	invalid(crate::from_base64("CwJo-Q=="), "invalid base64 character");
+	invalid(crate::from_base64("CwJoaQ==\n"), "invalid base64 padding");
+	invalid(crate::from_hex("0b02686"), "odd number of hex digits");
+	invalid(crate::from_hex("0b0268xy"), "invalid hex digit");
+	// Invalid text after the data is not mistaken for the end of the input.
+	invalid(crate::from_hex("0b026869zz"), "invalid hex digit");
+	assert!(matches!(crate::from_hex::<String>("0b02686900"), Err(Error::ExcessData { .. })));
+	assert!(matches!(crate::from_base64::<String>("CwJo"), Err(Error::UnexpectedEnd)));
+}
+
+#[test]
+fn test_detected_key_mode() {
+	use crate::de::KeyMode;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Kind {
+		Plain,
+		Tagged(u8),
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Record {
+		id: u8,
+		kind: Kind,
+	}
+
+	init_tracing();
+	let record = Record { id: 1, kind: Kind::Plain };
+	let mut buffer = [0; 64];
+	let mut output = [0; 64];
+	for use_indices in [false, true] {
+		let config = Config { use_indices, ..Default::default() };
+		let bytes = crate::to_slice_with_config(&record, &mut buffer, config).unwrap();
+		let mut de = Deserializer::new(&*bytes);
+		assert_eq!(de.detected_key_mode(), None);
+		let parsed = Record::deserialize(&mut de).unwrap();
+		let mode = de.detected_key_mode().unwrap();
+		assert_eq!(mode == KeyMode::Indices, use_indices);
+
+		// Written back the same way.
+		let config = Config { use_indices: mode == KeyMode::Indices, ..Default::default() };
+		assert_eq!(crate::to_slice_with_config(&parsed, &mut output, config).unwrap(), bytes);
+	}
+
+	// A unit variant as index after field names.
+	let uint = Type::UnsignedInt.into();
+	let bytes = [
+		Type::MapStart.into(),
+		Type::String.into(),
+		2,
+		b'i',
+		b'd',
+		uint,
+		1,
+		Type::String.into(),
+		4,
+		b'k',
+		b'i',
+		b'n',
+		b'd',
+		uint,
+		0,
+		Type::MapEnd.into(),
+	];
+	let mut de = Deserializer::new(bytes.as_slice());
+	assert_eq!(Record::deserialize(&mut de).unwrap(), record);
+	assert_eq!(de.detected_key_mode(), Some(KeyMode::Mixed));
+
+	// Values without identifiers.
+	let bytes = crate::to_slice(&[1_u8, 2], &mut buffer).unwrap();
+	let mut de = Deserializer::new(&*bytes);
+	<[u8; 2]>::deserialize(&mut de).unwrap();
+	assert_eq!(de.detected_key_mode(), None);
+
+	// Newtype variants are identified in their map.
+	let bytes = crate::to_slice(&Kind::Tagged(3), &mut buffer).unwrap();
+	let mut de = Deserializer::new(&*bytes);
+	assert_eq!(de.try_parse::<Kind>().unwrap(), Kind::Tagged(3));
+	assert_eq!(de.detected_key_mode(), Some(KeyMode::Names));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_cannot_borrow_from_readers() {
+	use ::std::collections::HashMap;
+
+	use crate::io::{Input, IoReader, SizeLimit};
+
+	/// Word that a custom visitor borrows from the input, without calling it borrowed.
+	#[derive(Debug, PartialEq)]
+	struct Word<'a>(&'a str);
+
+	impl<'de> Deserialize<'de> for Word<'de> {
+		fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+		where
+			D: ::serde::Deserializer<'de>,
+		{
+			struct Visitor;
+			impl<'de> ::serde::de::Visitor<'de> for Visitor {
+				type Value = Word<'de>;
+
+				fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+					formatter.write_str("a word")
+				}
+
+				fn visit_borrowed_str<E: ::serde::de::Error>(
+					self,
+					v: &'de str,
+				) -> Result<Self::Value, E> {
+					Ok(Word(v))
+				}
+			}
+			deserializer.deserialize_str(Visitor)
+		}
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code, reason = "Only deserialized")]
+	struct Text<'a> {
+		text: &'a str,
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code, reason = "Only deserialized")]
+	struct Data<'a> {
+		data: &'a [u8],
+	}
+
+	#[derive(Debug, Deserialize)]
+	#[allow(dead_code, reason = "Only deserialized")]
+	struct WrappedData<'a> {
+		#[serde(borrow)]
+		data: &'a Bytes,
+	}
+
+	init_tracing();
+	let map = HashMap::from([("first", 1_u32), ("second", 2)]);
+	let bytes = crate::to_vec(&map).unwrap();
+	assert_eq!(crate::from_slice::<HashMap<&str, u32>>(&bytes).unwrap(), map);
+	let mut de = Deserializer::new(SizeLimit::new(bytes.as_slice(), bytes.len()));
+	assert_eq!(HashMap::<&str, u32>::deserialize(&mut de).unwrap(), map);
+	assert!(bytes.as_slice().can_borrow());
+	assert!(SizeLimit::new(bytes.as_slice(), bytes.len()).can_borrow());
+
+	let mut buffer = Vec::new();
+	let reader = IoReader::new(bytes.as_slice());
+	assert!(!reader.can_borrow());
+	let mut de = Deserializer::new(reader).with_buffer(&mut buffer);
+	assert_eq!(HashMap::<&str, u32>::deserialize(&mut de).unwrap_err(), Error::CannotBorrow);
+	// Owned keys still work.
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert_eq!(HashMap::<String, u32>::deserialize(&mut de).unwrap().len(), 2);
+
+	let bytes = crate::to_vec(&HashMap::from([("text", "a string")])).unwrap();
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert_eq!(Text::deserialize(&mut de).unwrap_err(), Error::CannotBorrow);
+	assert_eq!(crate::from_slice::<Text>(&bytes).unwrap().text, "a string");
+
+	let bytes = crate::to_vec(&HashMap::from([("data", Bytes::new(&[1, 2, 3]))])).unwrap();
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert_eq!(Data::deserialize(&mut de).unwrap_err(), Error::CannotBorrow);
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert_eq!(WrappedData::deserialize(&mut de).unwrap_err(), Error::CannotBorrow);
+	assert_eq!(crate::from_slice::<Data>(&bytes).unwrap().data, [1, 2, 3]);
+	assert_eq!(crate::from_slice::<WrappedData>(&bytes).unwrap().data, [1, 2, 3].as_slice());
+
+	// Other type errors are unchanged.
+	let bytes = crate::to_vec(&5_u8).unwrap();
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert!(matches!(<&str>::deserialize(&mut de), Err(Error::WrongType(Type::UnsignedInt, _))));
+	// Custom visitors that need a borrow fail the same way.
+	let bytes = crate::to_vec(&"text").unwrap();
+	let mut de = Deserializer::new(IoReader::new(bytes.as_slice())).with_buffer(&mut buffer);
+	assert_eq!(Word::deserialize(&mut de).unwrap_err(), Error::CannotBorrow);
+	assert_eq!(crate::from_slice::<Word>(&bytes).unwrap(), Word("text"));
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_size_limit_is_the_same_for_slices_and_readers() {
+	use crate::io::{IoReader, SizeLimit};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Document {
+		id: u32,
+		name: String,
+		tags: Vec<String>,
+		data: Option<::serde_bytes::ByteBuf>,
+	}
+
+	init_tracing();
+	let documents = [
+		Document { id: 0, name: String::new(), tags: Vec::new(), data: None },
+		Document {
+			id: 1,
+			name: "a name that is longer than the inline buffer".to_owned(),
+			tags: vec!["a".to_owned(); 3],
+			data: Some(vec![7; 40].into()),
+		},
+	];
+	for document in documents {
+		for config in [Config::default(), Config { use_indices: true, ..Default::default() }] {
+			let bytes = crate::to_vec_with_config(&document, config).unwrap();
+			for max in [bytes.len(), bytes.len() - 1, 1] {
+				let config = Config { max_size: NonZeroUsize::new(max), ..config };
+				let from_slice = crate::from_slice_with_config::<Document>(&bytes, config);
+				let from_reader =
+					crate::from_reader_with_config::<_, Document>(bytes.as_slice(), config);
+				assert_eq!(from_slice, from_reader, "limit {max} of {}", bytes.len());
+				assert_eq!(from_slice.is_ok(), max == bytes.len());
+
+				// Small internal reader buffers do not change the outcome.
+				for capacity in [1, 7] {
+					let reader = IoReader::with_capacity(bytes.as_slice(), capacity);
+					let mut buffer = Vec::new();
+					let mut de = Deserializer::new(SizeLimit::new(reader, max))
+						.with_config(config)
+						.with_buffer(&mut buffer);
+					let result = Document::deserialize(&mut de);
+					assert_eq!(result.is_ok(), from_slice.is_ok());
+					if let Err(err) = result {
+						assert_eq!(Err(err), from_slice);
+					}
+				}
+			}
+
+			// The limit is consumed by exactly the document.
+			let mut de = Deserializer::new(SizeLimit::new(bytes.as_slice(), bytes.len() + 3));
+			Document::deserialize(&mut de).unwrap();
+			assert_eq!(de.into_input().remaining(), 3);
+		}
+	}
+}
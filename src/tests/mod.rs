@@ -2,6 +2,7 @@
 #![allow(clippy::unwrap_used, clippy::expect_used, clippy::print_stdout, reason = "Tests")]
 
 mod basic_types;
+mod errors;
 mod features;
 mod serde_features;
 mod special_handling;
@@ -32,7 +33,6 @@ pub fn init_tracing() {
 	println!("To see logs, run tests with the `std` and `tracing` feature enabled");
 }
 
-
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip(buffer)))]
 fn test_serde<'de, T>(value: &T, buffer: &'de mut [u8])
 where
@@ -238,8 +238,94 @@ fn test_tagged_enum() {
 		Type::MapEnd.into(),
 	]);
 
-	// Using `use_indices` with internally-tagged enums is not supported, it calls serialize_struct,
-	// but deserialize_any and does not recognize index keys.
+	// With indices, the tag field needs to be known to keep its name.
+	let config = Config { use_indices: true, tag_fields: &["type"], ..Default::default() };
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice_with_config(&Enum::VarA { a: true }, &mut buffer, config).unwrap();
+	assert_eq!(
+		bytes,
+		[
+			Type::MapStart.into(),
+			Type::String.into(),
+			4,
+			b't',
+			b'y',
+			b'p',
+			b'e',
+			Type::String.into(),
+			4,
+			b'V',
+			b'a',
+			b'r',
+			b'A',
+			Type::UnsignedInt.into(),
+			0,
+			Type::BooleanTrue.into(),
+			Type::MapEnd.into(),
+		]
+	);
+	let parsed: Enum = crate::from_slice_with_config(bytes, config).unwrap();
+	assert_eq!(parsed, Enum::VarA { a: true });
+}
+
+#[test]
+fn test_tagged_enum_with_indices() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Inner {
+		#[serde(skip_serializing_if = "Option::is_none")]
+		note: Option<u8>,
+		b: u8,
+		c: bool,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(tag = "kind")]
+	enum Enum {
+		Unit,
+		Struct { a: bool, b: u8 },
+		Newtype(Inner),
+	}
+
+	init_tracing();
+	let config = Config { use_indices: true, tag_fields: &["kind"], ..Default::default() };
+	let values = [
+		Enum::Unit,
+		Enum::Struct { a: true, b: 3 },
+		Enum::Newtype(Inner { note: None, b: 5, c: true }),
+	];
+	for value in values {
+		let mut buffer = [0; 1024];
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+		let parsed: Enum = crate::from_slice_with_config(bytes, config).unwrap();
+		assert_eq!(parsed, value);
+
+		#[cfg(feature = "alloc")]
+		{
+			let ir_value = crate::to_value_with_config(&value, config).unwrap();
+			assert_eq!(crate::to_vec_with_config(&ir_value, config).unwrap(), bytes);
+			let parsed: Enum = crate::from_value_with_config(ir_value, config).unwrap();
+			assert_eq!(parsed, value);
+		}
+	}
+
+	// Structs without the tag keep their indices, including skipped fields.
+	let mut buffer = [0; 1024];
+	let bytes =
+		crate::to_slice_with_config(&Inner { note: None, b: 1, c: false }, &mut buffer, config)
+			.unwrap();
+	assert_eq!(
+		bytes,
+		[
+			Type::MapStart.into(),
+			Type::UnsignedInt.into(),
+			1,
+			Type::UnsignedInt.into(),
+			1,
+			Type::UnsignedInt.into(),
+			2,
+			Type::BooleanFalse.into(),
+			Type::MapEnd.into(),
+		]
+	);
 }
 
 #[test]
@@ -292,7 +378,6 @@ fn test_tagged_enum_with_content() {
 	]);
 }
 
-
 #[test]
 fn test_untagged_enum() {
 	#[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -508,8 +593,10 @@ fn test_enum_other() {
 	.unwrap();
 	assert_eq!(parsed, Enum::Default);
 
-	// Using `use_indices` with internally-tagged enums is not supported, it calls serialize_struct,
-	// but deserialize_any and does not recognize index keys.
+	let config = Config { use_indices: true, tag_fields: &["t"], ..Default::default() };
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice_with_config(&Enum::VarA, &mut buffer, config).unwrap();
+	assert_eq!(crate::from_slice_with_config::<Enum>(bytes, config).unwrap(), Enum::VarA);
 }
 
 #[test]
@@ -555,6 +642,285 @@ fn test_flatten() {
 	]);
 }
 
+#[test]
+fn test_indices_not_supported_here() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Inner {
+		b: bool,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Outer {
+		a: bool,
+		#[serde(flatten)]
+		ext: Inner,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	#[serde(tag = "type")]
+	enum Enum {
+		VarA { a: bool },
+	}
+
+	init_tracing();
+	let config = Config { use_indices: true, ..Default::default() };
+
+	let result = crate::from_slice_with_config::<Outer>(
+		&[
+			Type::MapStart.into(),
+			Type::UnsignedInt.into(),
+			0,
+			Type::BooleanTrue.into(),
+			Type::UnsignedInt.into(),
+			1,
+			Type::BooleanTrue.into(),
+			Type::MapEnd.into(),
+		],
+		config,
+	);
+	assert!(matches!(result, Err(Error::IndicesNotSupportedHere { .. })));
+
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice_with_config(&Enum::VarA { a: true }, &mut buffer, config).unwrap();
+	let result = crate::from_slice_with_config::<Enum>(bytes, config);
+	assert!(matches!(result, Err(Error::IndicesNotSupportedHere { .. })));
+
+	// Errors of the map contents and structs that match indices themselves are unaffected.
+	let result = crate::from_slice_with_config::<Outer>(
+		&[
+			Type::MapStart.into(),
+			Type::UnsignedInt.into(),
+			0,
+			Type::String.into(),
+			Type::MapEnd.into(),
+		],
+		config,
+	);
+	assert!(matches!(result, Err(Error::UnexpectedEnd)));
+	let parsed: Inner = crate::from_slice_with_config(
+		&[
+			Type::MapStart.into(),
+			Type::UnsignedInt.into(),
+			0,
+			Type::BooleanTrue.into(),
+			Type::MapEnd.into(),
+		],
+		config,
+	)
+	.unwrap();
+	assert_eq!(parsed, Inner { b: true });
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_flattened_index_keys_keep_visitor_errors() {
+	use ::alloc::{boxed::Box, collections::BTreeMap, string::String};
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Record {
+		name: String,
+		#[serde(flatten)]
+		extra: BTreeMap<u32, String>,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Partial {
+		tag: String,
+		#[serde(flatten)]
+		extra: BTreeMap<u32, String>,
+	}
+
+	init_tracing();
+	// A string key shows that the map uses names, so the missing field is reported as is.
+	let partial = Partial { tag: "t".into(), extra: BTreeMap::from([(1, "one".into())]) };
+	let bytes = crate::to_vec(&partial).unwrap();
+	let missing = Error::Message("missing field `name`".into());
+	assert_eq!(crate::from_slice::<Record>(&bytes).unwrap_err(), missing);
+
+	// Only index keys could still be meant as field indices, but the original error is kept.
+	let extra = BTreeMap::<u32, String>::from([(1, "one".into())]);
+	let bytes = crate::to_vec(&extra).unwrap();
+	let err = crate::from_slice::<Record>(&bytes).unwrap_err();
+	assert_eq!(err, Error::IndicesNotSupportedHere { cause: Box::new(missing) });
+
+	let record = Record { name: "n".into(), extra: BTreeMap::from([(1, "one".into())]) };
+	let bytes = crate::to_vec(&record).unwrap();
+	assert_eq!(crate::from_slice::<Record>(&bytes).unwrap(), record);
+}
+
+#[test]
+fn test_skipped_fields_with_indices() {
+	/// Whether the number is zero.
+	#[allow(clippy::trivially_copy_pass_by_ref, reason = "Serde API")]
+	const fn is_zero(n: &u8) -> bool {
+		*n == 0
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Skipping {
+		#[serde(skip)]
+		skipped: u8,
+		a: u8,
+		#[serde(skip_serializing_if = "is_zero", default)]
+		maybe: u8,
+		#[serde(default)]
+		defaulted: u8,
+		#[serde(skip_serializing_if = "Option::is_none", default)]
+		last: Option<u8>,
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Enum {
+		Struct {
+			#[serde(skip_serializing_if = "is_zero", default)]
+			maybe: u8,
+			#[serde(skip)]
+			skipped: u8,
+			b: u8,
+		},
+	}
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct SerializeOnly {
+		a: u8,
+		#[serde(skip_serializing, default)]
+		not_written: u8,
+		c: u8,
+	}
+
+	init_tracing();
+	let config = Config { use_indices: true, ..Default::default() };
+	for (maybe, last) in [(0, None), (1, None), (0, Some(2)), (3, Some(4))] {
+		let value = Skipping { skipped: 0, a: 1, maybe, defaulted: 5, last };
+		test_serde_with_indices(&value, &mut [0; 1024]);
+		let value = Enum::Struct { maybe, skipped: 0, b: 6 };
+		test_serde_with_indices(&value, &mut [0; 1024]);
+
+		#[cfg(feature = "alloc")]
+		{
+			let value = Skipping { skipped: 0, a: 1, maybe, defaulted: 5, last };
+			let ir_value = crate::to_value_with_config(&value, config).unwrap();
+			assert_eq!(crate::from_value_with_config::<Skipping>(ir_value, config).unwrap(), value);
+		}
+	}
+
+	// Fields keep the index of their position among the fields serde considers, `skip` does not
+	// count, `skip_serializing_if` does.
+	let mut buffer = [0; 1024];
+	let value = Skipping { skipped: 0, a: 1, maybe: 0, defaulted: 5, last: None };
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+	assert_eq!(
+		bytes,
+		[
+			Type::MapStart.into(),
+			Type::UnsignedInt.into(),
+			0,
+			Type::UnsignedInt.into(),
+			1,
+			Type::UnsignedInt.into(),
+			2,
+			Type::UnsignedInt.into(),
+			5,
+			Type::MapEnd.into(),
+		]
+	);
+
+	// Fields skipped on one side only shift the indices, so they are not supported.
+	let value = SerializeOnly { a: 1, not_written: 0, c: 3 };
+	test_serde(&value, &mut [0; 1024]);
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+	assert!(crate::from_slice_with_config::<SerializeOnly>(bytes, config).is_err());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_detect_duplicate_keys() {
+	use ::alloc::collections::BTreeMap;
+
+	/// Key that counts how often it is serialized.
+	struct CountingKey<'a>(&'a ::core::cell::Cell<usize>);
+
+	impl Serialize for CountingKey<'_> {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: ::serde::Serializer,
+		{
+			self.0.set(self.0.get() + 1);
+			serializer.serialize_str("key")
+		}
+	}
+
+	#[derive(Debug, PartialEq, Serialize)]
+	struct Renamed {
+		id: u8,
+		#[serde(rename = "id")]
+		other_id: u8,
+	}
+
+	#[derive(Debug, PartialEq, Serialize)]
+	struct Meta {
+		name: &'static str,
+		version: u8,
+	}
+
+	#[derive(Debug, PartialEq, Serialize)]
+	struct Flattened {
+		version: u8,
+		#[serde(flatten)]
+		meta: Meta,
+	}
+
+	#[derive(Debug, PartialEq, Serialize)]
+	struct Nested {
+		version: u8,
+		meta: Meta,
+		map: BTreeMap<&'static str, Meta>,
+	}
+
+	init_tracing();
+	let config = Config { detect_duplicate_keys: true, ..Default::default() };
+	let result = crate::to_vec_with_config(&Renamed { id: 1, other_id: 2 }, config);
+	assert_eq!(result, Err(Error::DuplicateSerializedKey("\"id\"".into())));
+	// Without detection, the map silently contains the key twice.
+	assert!(crate::to_vec(&Renamed { id: 1, other_id: 2 }).is_ok());
+
+	let flattened = Flattened { version: 1, meta: Meta { name: "a", version: 2 } };
+	for config in [
+		config,
+		Config { canonical: true, ..config },
+		Config { key_dictionary: true, ..config },
+		Config { use_indices: true, ..config },
+	] {
+		let result = crate::to_vec_with_config(&flattened, config);
+		assert_eq!(result, Err(Error::DuplicateSerializedKey("\"version\"".into())), "{config:?}");
+	}
+
+	// The same keys in different maps are fine.
+	let meta = || Meta { name: "a", version: 2 };
+	let nested = Nested { version: 1, meta: meta(), map: [("a", meta()), ("b", meta())].into() };
+	for config in
+		[config, Config { canonical: true, ..config }, Config { key_dictionary: true, ..config }]
+	{
+		let bytes = crate::to_vec_with_config(&nested, config).unwrap();
+		let expected =
+			crate::to_vec_with_config(&nested, Config { detect_duplicate_keys: false, ..config });
+		assert_eq!(bytes, expected.unwrap());
+	}
+	let bytes = crate::to_vec_with_config(&nested, Config { use_indices: true, ..config });
+	assert!(bytes.is_ok());
+
+	// Keys are compared by their encoding, so integer keys are reported as well.
+	let mut ser = crate::Serializer::new(::alloc::vec::Vec::new()).detect_duplicate_keys(true);
+	let result = ::serde::Serializer::collect_map(&mut ser, [(1_u8, true), (1_u8, false)]);
+	assert_eq!(result, Err(Error::DuplicateSerializedKey("1".into())));
+
+	// Keys are serialized once while recording them.
+	let serialized = ::core::cell::Cell::new(0);
+	let mut ser = crate::Serializer::new(::alloc::vec::Vec::new()).detect_duplicate_keys(true);
+	::serde::Serializer::collect_map(&mut ser, [(CountingKey(&serialized), true)]).unwrap();
+	let mut expected = crate::Serializer::new(::alloc::vec::Vec::new());
+	::serde::Serializer::collect_map(&mut expected, [("key", true)]).unwrap();
+	assert_eq!(ser.into_output(), expected.into_output());
+	assert_eq!(serialized.get(), 1);
+}
+
 #[test]
 fn test_borrow() {
 	#[derive(Debug, PartialEq, Serialize, Deserialize)]
@@ -54,3 +54,74 @@ fn test_output_too_small() {
 	let result = crate::to_slice(&"hallo", &mut buffer);
 	assert!(matches!(result, Err(Error::BufferTooSmall)));
 }
+
+#[test]
+fn test_type_mismatch_context() {
+	#[derive(Debug, Serialize, Deserialize)]
+	struct Settings {
+		name: u8,
+		inner: Inner,
+	}
+	#[derive(Debug, Serialize, Deserialize)]
+	struct Inner {
+		timeout: u32,
+	}
+	#[derive(Debug, Serialize)]
+	struct Wrong {
+		timeout: &'static str,
+	}
+	#[derive(Debug, Serialize)]
+	struct WrongSettings {
+		name: u8,
+		inner: Wrong,
+	}
+	#[derive(Debug, Serialize, Deserialize)]
+	struct Long<T> {
+		a_field_name_that_is_longer_than_32_bytes: T,
+	}
+
+	init_tracing();
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice(&("name", Wrong { timeout: "1s" }), &mut buffer).unwrap();
+	let result = crate::from_slice::<(&str, Inner)>(bytes);
+	let Err(err) = result else { panic!("Deserialization should fail") };
+	assert!(matches!(
+		err,
+		Error::WrongFieldType {
+			name: "Inner",
+			field: "timeout",
+			found: Type::String,
+			expected: &[Type::UnsignedInt]
+		}
+	));
+	#[cfg(feature = "alloc")]
+	assert_eq!(
+		::alloc::string::ToString::to_string(&err),
+		"Wrong data type in `Inner.timeout`. Found `String`, but expected one of `[UnsignedInt]`"
+	);
+
+	// The innermost field is reported, also with indices.
+	let value = WrongSettings { name: 1, inner: Wrong { timeout: "1s" } };
+	for use_indices in [false, true] {
+		let config = Config { use_indices, ..Default::default() };
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+		let result = crate::from_slice::<Settings>(bytes);
+		assert!(matches!(
+			result,
+			Err(Error::WrongFieldType { name: "Inner", field: "timeout", .. })
+		));
+	}
+
+	// Field names are looked up for transient and long keys as well.
+	let value = Long { a_field_name_that_is_longer_than_32_bytes: "x" };
+	let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+	let result = crate::from_slice::<Long<u8>>(bytes);
+	assert!(matches!(
+		result,
+		Err(Error::WrongFieldType { field: "a_field_name_that_is_longer_than_32_bytes", .. })
+	));
+	let bytes = crate::to_slice(&Wrong { timeout: "1s" }, &mut buffer).unwrap();
+	let result =
+		crate::from_chunks::<_, _, Inner>(bytes.chunks(3), crate::buffer::InlineBuffer::new());
+	assert!(matches!(result, Err(Error::WrongFieldType { name: "Inner", field: "timeout", .. })));
+}
@@ -161,3 +161,500 @@ fn test_enum_variants_reordered() {
 	let parsed: V2 = crate::from_slice(bytes).unwrap();
 	assert_eq!(parsed, V2::Z);
 }
+
+#[test]
+fn test_integer_signedness_changed() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V1 {
+		a: u32,
+		b: i32,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V2 {
+		a: i64,
+		b: u64,
+	}
+
+	init_tracing();
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice(&V1 { a: u32::MAX, b: 7 }, &mut buffer).unwrap();
+	let result = crate::from_slice::<V2>(bytes);
+	assert!(matches!(result, Err(crate::Error::WrongFieldType { field: "a", .. })));
+	let parsed: V2 = crate::from_slice_with_config(bytes, lenient).unwrap();
+	assert_eq!(parsed, V2 { a: i64::from(u32::MAX), b: 7 });
+
+	// Values must still fit.
+	let bytes = crate::to_slice(&V1 { a: 0, b: -1 }, &mut buffer).unwrap();
+	assert!(crate::from_slice_with_config::<V2>(bytes, lenient).is_err());
+	for (value, fits) in [(u64::MAX, false), (i64::MAX.unsigned_abs(), true)] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice::<i64>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<i64>(bytes, lenient).is_ok(), fits);
+	}
+	for (value, fits) in [(i64::MIN, false), (-1, false), (0, true), (i64::MAX, true)] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice::<u64>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<u64>(bytes, lenient).is_ok(), fits);
+	}
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_integer_signedness_changed_in_value() {
+	use crate::value::Value;
+
+	assert_eq!(crate::from_value::<i64>(Value::from(u32::MAX)).unwrap(), i64::from(u32::MAX));
+	assert_eq!(crate::from_value::<u8>(Value::from(7_i32)).unwrap(), 7);
+	assert!(crate::from_value::<i64>(Value::from(u64::MAX)).is_err());
+	assert!(crate::from_value::<u64>(Value::from(-1_i8)).is_err());
+}
+
+#[test]
+fn test_integer_float_interchange() {
+	init_tracing();
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+
+	for (value, expected) in
+		[(3.0, Some(3)), (-0.0, Some(0)), (-1.0, None), (1.5, None), (256.0, None)]
+	{
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice::<u8>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<u8>(bytes, lenient).ok(), expected);
+	}
+	for value in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY, 1e300] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice_with_config::<i128>(bytes, lenient).is_err());
+	}
+	let bytes = crate::to_slice(&-128.0_f32, &mut buffer).unwrap();
+	assert_eq!(crate::from_slice_with_config::<i8>(bytes, lenient).unwrap(), -128);
+	// `Float128` only if it is exact as `f64`: 3.0, slightly more than 3.0 and 1.5.
+	for (bits, expected) in [
+		(0x4000_8000_u128 << 96, Some(3)),
+		(0x4000_8000 << 96 | 1, None),
+		(0x3FFF_8000 << 96, None),
+	] {
+		let mut bytes = [0; 17];
+		bytes[0] = crate::format::Type::Float128.into();
+		bytes[1 ..].copy_from_slice(&bits.to_le_bytes());
+		assert!(crate::from_slice::<u8>(&bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<u8>(&bytes, lenient).ok(), expected);
+	}
+
+	// Integers are only accepted if the float represents them exactly.
+	for (value, exact) in [(1_u64 << 53, true), ((1 << 53) + 1, false), (u64::MAX, false)] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice::<f64>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<f64>(bytes, lenient).is_ok(), exact);
+	}
+	for (value, exact) in [(-(1_i32 << 24), true), ((1 << 24) + 1, false)] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert_eq!(crate::from_slice_with_config::<f32>(bytes, lenient).is_ok(), exact);
+		assert!(crate::from_slice_with_config::<f64>(bytes, lenient).is_ok());
+	}
+	let bytes = crate::to_slice(&u128::MAX, &mut buffer).unwrap();
+	assert!(crate::from_slice_with_config::<f32>(bytes, lenient).is_err());
+	let bytes = crate::to_slice(&(1_u128 << 127), &mut buffer).unwrap();
+	let parsed: f32 = crate::from_slice_with_config(bytes, lenient).unwrap();
+	assert_eq!(parsed.to_bits(), 0x7F00_0000, "2^127");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_integer_float_interchange_in_value() {
+	use crate::value::Value;
+
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	assert!(crate::from_value::<u32>(Value::from(3.0_f64)).is_err());
+	assert_eq!(crate::from_value_with_config::<u32>(Value::from(3.0_f64), lenient).unwrap(), 3);
+	assert!(crate::from_value_with_config::<u32>(Value::from(3.5_f32), lenient).is_err());
+	assert!(crate::from_value_with_config::<i8>(Value::from(-129.0_f64), lenient).is_err());
+	assert!(crate::from_value::<f64>(Value::from(7_u8)).is_err());
+	let parsed: f64 = crate::from_value_with_config(Value::from(7_u8), lenient).unwrap();
+	assert_eq!(parsed.to_bits(), 7.0_f64.to_bits());
+	let value = Value::from((1_u64 << 53) + 1);
+	assert!(crate::from_value_with_config::<f64>(value, lenient).is_err());
+
+	let exact = Value::Float(crate::value::Float::F128Bits(0x4000_8000 << 96));
+	assert_eq!(crate::from_value_with_config::<u8>(exact, lenient).unwrap(), 3);
+	let rounded = Value::Float(crate::value::Float::F128Bits(0x4000_8000 << 96 | 1));
+	assert!(crate::from_value_with_config::<u8>(rounded, lenient).is_err());
+}
+
+#[test]
+fn test_compact_ints_compatibility() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V1 {
+		a: u32,
+		b: [u16; 3],
+		c: Unit,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V2 {
+		a: u32,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Unit {
+		A,
+		B,
+	}
+
+	init_tracing();
+	let value = V1 { a: 7, b: [1, 2, 300], c: Unit::B };
+	for use_indices in [false, true] {
+		let config = Config { use_indices, ..Default::default() };
+		let compact = Config { compact_ints: true, ..config };
+		let mut buffer = [0; 1024];
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+		let plain_len = bytes.len();
+		let bytes = crate::to_slice_with_config(&value, &mut buffer, compact).unwrap();
+		assert!(bytes.len() < plain_len);
+
+		// New versions read both encodings, also when skipping unknown fields.
+		assert_eq!(crate::from_slice::<V1>(bytes).unwrap(), value);
+		assert_eq!(crate::from_slice::<V2>(bytes).unwrap(), V2 { a: 7 });
+
+		// Older versions only know these types, so they fail on the inline `7` with an invalid
+		// type.
+		let known = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 15, 16, 17, 18, 19];
+		let inline = 64 + 7;
+		assert!(bytes.contains(&inline) && !known.contains(&inline));
+	}
+}
+
+#[test]
+fn test_length_prefixed_seqs_compatibility() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V1 {
+		a: u32,
+		b: [(u16, bool); 2],
+		c: u8,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V2 {
+		a: u32,
+		c: u8,
+	}
+
+	init_tracing();
+	let value = V1 { a: 7, b: [(1, true), (2, false)], c: 3 };
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+
+	// New versions read the sequences and skip them as unknown fields.
+	assert_eq!(crate::from_slice::<V1>(bytes).unwrap(), value);
+	assert_eq!(crate::from_slice::<V2>(bytes).unwrap(), V2 { a: 7, c: 3 });
+
+	// Older versions only know these types, so they fail on the length-prefixed sequences.
+	let known = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 15, 16, 17, 18, 19];
+	let fixed = crate::format::Type::SeqFixed.into();
+	assert!(bytes.contains(&fixed) && !known.contains(&fixed));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_key_dictionary_compatibility() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V1 {
+		inner: Inner,
+		name: u8,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Inner {
+		name: u8,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct V2 {
+		name: u8,
+	}
+
+	init_tracing();
+	let value = V1 { inner: Inner { name: 1 }, name: 2 };
+	let config = Config { key_dictionary: true, ..Default::default() };
+	let mut buffer = [0; 1024];
+	let bytes = crate::to_slice_with_config(&value, &mut buffer, config).unwrap();
+
+	// The field name is defined in the unknown field and referenced afterwards.
+	assert_eq!(crate::from_slice::<V1>(bytes).unwrap(), value);
+	assert_eq!(crate::from_slice::<V2>(bytes).unwrap(), V2 { name: 2 });
+	let reference = u8::from(crate::format::Type::StringRef);
+	assert_eq!(bytes.iter().filter(|byte| **byte == reference).count(), 1);
+
+	// Older versions only know these types, so they fail on the string table.
+	let known = [0, 1, 2, 3, 4, 5, 6, 7, 8, 10, 11, 15, 16, 17, 18, 19];
+	let def = crate::format::Type::StringDef.into();
+	assert!(bytes.contains(&def) && !known.contains(&def));
+}
+
+/// Check that booleans are accepted as integers of type `T` only with `lenient_numbers`, and that
+/// only `0` and `1` of type `T` are accepted as booleans.
+fn check_bool_int_coercion<T>(buffer: &mut [u8])
+where
+	T: Serialize + for<'de> Deserialize<'de> + From<bool> + PartialEq + ::core::fmt::Debug,
+{
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	for value in [false, true] {
+		let bytes = crate::to_slice(&value, buffer).unwrap();
+		assert!(crate::from_slice::<T>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<T>(bytes, lenient).unwrap(), T::from(value));
+
+		let bytes = crate::to_slice(&T::from(value), buffer).unwrap();
+		assert!(crate::from_slice::<bool>(bytes).is_err());
+		assert_eq!(crate::from_slice_with_config::<bool>(bytes, lenient).unwrap(), value);
+	}
+}
+
+#[test]
+fn test_bool_int_coercion() {
+	init_tracing();
+	let mut buffer = [0; 1024];
+	check_bool_int_coercion::<u8>(&mut buffer);
+	check_bool_int_coercion::<u16>(&mut buffer);
+	check_bool_int_coercion::<u32>(&mut buffer);
+	check_bool_int_coercion::<u64>(&mut buffer);
+	check_bool_int_coercion::<u128>(&mut buffer);
+	check_bool_int_coercion::<i8>(&mut buffer);
+	check_bool_int_coercion::<i16>(&mut buffer);
+	check_bool_int_coercion::<i32>(&mut buffer);
+	check_bool_int_coercion::<i64>(&mut buffer);
+	check_bool_int_coercion::<i128>(&mut buffer);
+
+	// Other integers are not booleans.
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	for value in [2, 7, u128::MAX] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice_with_config::<bool>(bytes, lenient).is_err());
+	}
+	for value in [-1, 2, i128::MIN, i128::MAX] {
+		let bytes = crate::to_slice(&value, &mut buffer).unwrap();
+		assert!(crate::from_slice_with_config::<bool>(bytes, lenient).is_err());
+	}
+	let bytes = crate::to_slice(&Some(1_u8), &mut buffer).unwrap();
+	assert_eq!(crate::from_slice_with_config::<Option<bool>>(bytes, lenient).unwrap(), Some(true));
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_bool_int_coercion_in_value() {
+	use crate::value::Value;
+
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	assert!(crate::from_value::<u8>(Value::from(true)).is_err());
+	assert!(crate::from_value::<bool>(Value::from(1_u8)).is_err());
+	assert_eq!(crate::from_value_with_config::<i128>(Value::from(true), lenient).unwrap(), 1);
+	assert_eq!(crate::from_value_with_config::<u16>(Value::from(false), lenient).unwrap(), 0);
+	assert!(crate::from_value_with_config::<bool>(Value::from(1_i64), lenient).unwrap());
+	assert!(!crate::from_value_with_config::<bool>(Value::from(0_u32), lenient).unwrap());
+	for value in [Value::from(2_u8), Value::from(-1_i8), Value::from(u128::MAX)] {
+		assert!(crate::from_value_with_config::<bool>(value, lenient).is_err());
+	}
+}
+
+/// Merged schema of all samples.
+#[cfg(feature = "alloc")]
+fn schema<T: Serialize>(samples: &[T]) -> crate::schema::Schema {
+	samples
+		.iter()
+		.map(|sample| crate::schema::schema_of(sample).unwrap())
+		.fold(crate::schema::Schema::Any, crate::schema::Schema::merge)
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compat_struct_field_added() {
+	use ::alloc::string::ToString;
+
+	use crate::compat::{check, check_with_config, Incompatibility};
+
+	#[derive(Serialize)]
+	struct V1 {
+		a: bool,
+	}
+	#[derive(Serialize)]
+	struct V2 {
+		b: bool,
+		a: bool,
+	}
+	#[derive(Serialize)]
+	struct V3 {
+		a: bool,
+		b: Option<bool>,
+	}
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let v1 = schema(&[V1 { a: true }]);
+	let v2 = schema(&[V2 { b: true, a: true }]);
+	let v3 = schema(&[V3 { a: true, b: None }]);
+
+	let missing = check(&v1, &v2);
+	assert_eq!(missing, [Incompatibility::MissingField { path: "V2".into(), field: "b".into() }]);
+	assert_eq!(missing.first().unwrap().to_string(), "Field `b` at `V2` is missing in old data");
+	assert_eq!(
+		check_with_config(&v1, &v2, config),
+		[
+			Incompatibility::IndexChanged { path: "V2".into(), name: "a".into(), old: 0, new: 1 },
+			Incompatibility::MissingField { path: "V2".into(), field: "a".into() },
+		]
+	);
+	assert!(check(&v1, &v3).is_empty());
+	assert!(check_with_config(&v1, &v3, config).is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compat_struct_fields_reordered() {
+	use ::alloc::string::ToString;
+
+	use crate::compat::{check, check_with_config};
+
+	#[derive(Serialize)]
+	struct V1 {
+		a: bool,
+		b: bool,
+		c: bool,
+	}
+	#[derive(Serialize)]
+	struct V2 {
+		b: bool,
+		c: bool,
+		a: bool,
+	}
+
+	let v1 = schema(&[V1 { a: true, b: false, c: true }]);
+	let v2 = schema(&[V2 { b: false, c: true, a: true }]);
+	assert!(check(&v1, &v2).is_empty());
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let moved = check_with_config(&v1, &v2, config);
+	assert_eq!(moved.len(), 3);
+	assert_eq!(moved.first().unwrap().to_string(), "Index of `a` at `V2` changed from 0 to 2");
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compat_field_type_changed() {
+	use ::alloc::string::ToString;
+
+	use crate::compat::{check, Incompatibility};
+
+	#[derive(Serialize)]
+	struct Inner {
+		value: bool,
+	}
+	#[derive(Serialize)]
+	struct V1 {
+		a: Inner,
+		b: f32,
+		c: u8,
+	}
+	#[derive(Serialize)]
+	struct V2 {
+		a: Inner2,
+		b: f64,
+		c: Option<u8>,
+	}
+	#[derive(Serialize)]
+	struct Inner2 {
+		value: u32,
+	}
+
+	let v1 = schema(&[V1 { a: Inner { value: true }, b: 1.0, c: 1 }]);
+	let v2 = schema(&[V2 { a: Inner2 { value: 1 }, b: 1.0, c: Some(1) }]);
+	let changed = check(&v1, &v2);
+	assert_eq!(
+		changed,
+		[Incompatibility::TypeChanged {
+			path: "V2.a.value".into(),
+			old: crate::schema::Schema::Bool,
+			new: crate::schema::Schema::UInt
+		}]
+	);
+	assert_eq!(
+		changed.first().unwrap().to_string(),
+		"Type at `V2.a.value` changed from bool to unsigned integer"
+	);
+	assert_eq!(check(&v2, &v1).len(), 2);
+
+	let lenient = Config { lenient_numbers: true, ..Default::default() };
+	let old = schema(&[1_u8]);
+	let new = schema(&[1_i8]);
+	assert_eq!(check(&old, &new).len(), 1);
+	assert!(crate::compat::check_with_config(&old, &new, lenient).is_empty());
+	assert!(crate::compat::check_with_config(&new, &old, lenient).is_empty());
+	// Booleans are valid integers, but only some integers are valid booleans.
+	assert!(crate::compat::check_with_config(&v1, &v2, lenient).is_empty());
+	assert_eq!(crate::compat::check_with_config(&v2, &v1, lenient).len(), 2);
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compat_enum_variant_added() {
+	use crate::compat::{check, check_with_config};
+
+	#[derive(Serialize)]
+	enum V1 {
+		Some(bool),
+		Multiple(bool, bool),
+	}
+	#[derive(Serialize)]
+	enum V2 {
+		None,
+		Some(bool),
+		Multiple(bool, bool),
+	}
+	#[derive(Serialize)]
+	enum V3 {
+		Some(bool),
+		Multiple(bool, bool),
+		None,
+	}
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let v1 = schema(&[V1::Some(true), V1::Multiple(false, true)]);
+	let v2 = schema(&[V2::None, V2::Some(true), V2::Multiple(false, true)]);
+	let v3 = schema(&[V3::None, V3::Some(true), V3::Multiple(false, true)]);
+	assert!(check(&v1, &v2).is_empty());
+	assert_eq!(check_with_config(&v1, &v2, config).len(), 2);
+	assert!(check_with_config(&v1, &v3, config).is_empty());
+}
+
+#[cfg(feature = "alloc")]
+#[test]
+fn test_compat_enum_variant_renamed() {
+	use ::alloc::string::ToString;
+
+	use crate::compat::{check, check_with_config, Incompatibility};
+
+	#[derive(Serialize)]
+	enum V1 {
+		X,
+		Y,
+		Z,
+	}
+	#[derive(Serialize)]
+	enum V2 {
+		Z,
+		X,
+		Why,
+	}
+
+	let v1 = schema(&[V1::X, V1::Y, V1::Z]);
+	let v2 = schema(&[V2::Z, V2::X, V2::Why]);
+	let unknown = check(&v1, &v2);
+	assert_eq!(
+		unknown,
+		[Incompatibility::UnknownVariant { path: "V2".into(), variant: "Y".into() }]
+	);
+	assert_eq!(unknown.first().unwrap().to_string(), "Variant `Y` at `V2` does not exist anymore");
+
+	let config = Config { use_indices: true, ..Default::default() };
+	assert_eq!(
+		check_with_config(&v1, &v2, config),
+		[
+			Incompatibility::IndexChanged { path: "V2".into(), name: "X".into(), old: 0, new: 1 },
+			Incompatibility::IndexChanged { path: "V2".into(), name: "Z".into(), old: 2, new: 0 },
+		]
+	);
+}
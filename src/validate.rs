@@ -0,0 +1,124 @@
+//! Validation of serialized data without deserializing it.
+
+use crate::{
+	format::{decode_length, decode_unsigned, Type, VarInt},
+	io::Input,
+	Error, Result,
+};
+
+/// Types that can start a value.
+pub(crate) const VALUE_TYPES: &[Type] = &[
+	Type::Null,
+	Type::BooleanFalse,
+	Type::BooleanTrue,
+	Type::UnsignedInt,
+	Type::SignedInt,
+	Type::Float16,
+	Type::Float32,
+	Type::Float64,
+	Type::Float128,
+	Type::Bytes,
+	Type::String,
+	Type::StringDef,
+	Type::StringRef,
+	Type::SeqStart,
+	Type::SeqFixed,
+	Type::MapStart,
+	Type::Some,
+];
+
+/// Check that the input starts with a single well-formed value and skip it. Sequences and maps
+/// must be ended by the matching designator, length-prefixed sequences must contain as many
+/// elements as announced, maps must contain pairs of keys and values, integers must fit into 128
+/// bits (or 1024 bits with **big-integers**, see [BigInteger](crate::value::BigInteger)) and
+/// strings must be valid UTF-8. References must point to strings that were defined before,
+/// `strings` is the number of definitions so far. Sequences, maps and `Some` markers may be nested
+/// `max_depth` levels deep.
+#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
+pub fn validate_value<'de, I>(input: &mut I, max_depth: usize, strings: &mut usize) -> Result<()>
+where
+	I: Input<'de>,
+{
+	let byte = input.read_byte()?;
+	let t = Type::try_from(byte)?;
+	let nested = || max_depth.checked_sub(1).ok_or_else(|| Error::DepthLimitReached);
+	match t {
+		Type::Null | Type::BooleanFalse | Type::BooleanTrue => {}
+		#[cfg(feature = "big-integers")]
+		Type::UnsignedInt | Type::SignedInt if byte == u8::from(t) => skip_var_int(input)?,
+		Type::UnsignedInt => _ = decode_unsigned::<u128, _>(byte, input)?,
+		Type::SignedInt => _ = i128::decode(input)?,
+		Type::Float16 => input.skip_bytes(2)?,
+		Type::Float32 => input.skip_bytes(4)?,
+		Type::Float64 => input.skip_bytes(8)?,
+		Type::Float128 => input.skip_bytes(16)?,
+		Type::Bytes => {
+			let len = decode_length(input)?;
+			input.skip_bytes(len)?;
+		}
+		Type::String => {
+			let len = decode_length(input)?;
+			let bytes = input.read_bytes::<()>(len, None)?.ok_or_else(|| Error::UnexpectedEnd)?;
+			_ = ::core::str::from_utf8(bytes)?;
+		}
+		Type::StringDef => {
+			let len = decode_length(input)?;
+			let bytes = input.read_bytes::<()>(len, None)?.ok_or_else(|| Error::UnexpectedEnd)?;
+			_ = ::core::str::from_utf8(bytes)?;
+			*strings += 1;
+		}
+		Type::StringRef => {
+			let index = decode_length(input)?;
+			if index >= *strings {
+				return Err(Error::UnknownStringRef(index));
+			}
+		}
+		Type::SeqStart => {
+			let depth = nested()?;
+			while input.peek_byte()? != u8::from(Type::SeqEnd) {
+				validate_value(input, depth, strings)?;
+			}
+			_ = input.read_byte()?;
+		}
+		Type::SeqFixed => {
+			let len = decode_length(input)?;
+			let depth = nested()?;
+			for _ in 0 .. len {
+				validate_value(input, depth, strings)?;
+			}
+		}
+		Type::MapStart => {
+			let depth = nested()?;
+			while input.peek_byte()? != u8::from(Type::MapEnd) {
+				validate_value(input, depth, strings)?;
+				// A map end instead of a value is reported as wrong type.
+				validate_value(input, depth, strings)?;
+			}
+			_ = input.read_byte()?;
+		}
+		Type::Some => validate_value(input, nested()?, strings)?,
+		Type::SeqEnd | Type::MapEnd => return Err(Error::WrongType(t, VALUE_TYPES)),
+	}
+	Ok(())
+}
+
+/// Maximum number of bytes of an integer's var-int in a value. With **big-integers**, integers may
+/// exceed 128 bits.
+#[cfg(feature = "big-integers")]
+const MAX_VARINT_BYTES: usize = crate::value::BigInteger::MAX_VARINT_BYTES;
+/// Maximum number of bytes of an integer's var-int in a value.
+#[cfg(not(feature = "big-integers"))]
+const MAX_VARINT_BYTES: usize = u128::MAX_BYTES;
+
+/// Skip the var-int of an integer in a value, which may take up to [MAX_VARINT_BYTES] bytes.
+pub(crate) fn skip_var_int<'de, I>(input: &mut I) -> Result<()>
+where
+	I: Input<'de>,
+{
+	for _ in 0 .. MAX_VARINT_BYTES {
+		if input.read_byte()? & 0x80 == 0 {
+			return Ok(());
+		}
+	}
+	Err(Error::VarIntTooLarge)
+}
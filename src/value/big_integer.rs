@@ -0,0 +1,237 @@
+//! **big-integers**: Integers that do not fit into 128 bits, kept as their raw encoding.
+
+use ::alloc::{borrow::Cow, vec::Vec};
+use ::core::{
+	cmp::Ordering,
+	fmt::Write,
+	hash::{Hash, Hasher},
+};
+
+use crate::format::Type;
+
+/// An integer that does not fit into `u128` or `i128`. The format's var-ints can encode them,
+/// e.g. written by a faulty producer or a future extension. Concrete types fail to deserialize
+/// them with [Error::VarIntTooLarge](crate::Error::VarIntTooLarge), but a
+/// [Value](super::Value) keeps the raw var-int bytes to inspect the document and to serialize it
+/// again without changes.
+///
+/// Big integers are compared and hashed by their numeric value, but never equal a
+/// [Value::Integer](super::Value::Integer). Negative ones are ordered before all other integers,
+/// the others after them, even if the var-int is only long because of padding. They are displayed
+/// in decimal. Serializers of other formats get the encoded bytes.
+///
+/// ```rust
+/// use serde_brief::value::{BigInteger, Value};
+///
+/// // `2^133`, one bit more than `u128` has.
+/// let mut bytes = vec![serde_brief::format::Type::UnsignedInt.into()];
+/// bytes.extend([0x80; 19]);
+/// bytes.push(0x01);
+/// let value = serde_brief::from_slice::<Value>(&bytes).unwrap();
+/// let Value::BigInteger(int) = &value else { panic!("expected a big integer") };
+/// assert_eq!(int.to_string(), "10889035741470030830827987437816582766592");
+/// assert_eq!(serde_brief::to_vec(&value).unwrap(), bytes);
+/// assert!(serde_brief::from_slice::<u128>(&bytes).is_err());
+/// ```
+#[derive(Debug, Clone)]
+pub struct BigInteger<'a> {
+	/// Whether this is a [Type::SignedInt], i.e. zigzag-encoded.
+	signed: bool,
+	/// The raw var-int bytes, without the type byte.
+	varint: Cow<'a, [u8]>,
+}
+
+impl<'a> BigInteger<'a> {
+	/// The maximum number of var-int bytes, i.e. up to 1024 bits. Longer var-ints fail to
+	/// deserialize, so that malicious input cannot allocate unbounded memory.
+	pub const MAX_VARINT_BYTES: usize = 1024_usize.div_ceil(7);
+
+	/// Create a big integer from the raw var-int bytes of an unsigned or (zigzag-encoded) signed
+	/// integer, without the type byte. Returns `None` if the bytes are not exactly one var-int of
+	/// at most [MAX_VARINT_BYTES](Self::MAX_VARINT_BYTES) bytes. Values that fit into 128 bits are
+	/// accepted as well.
+	#[must_use]
+	pub fn from_varint(signed: bool, varint: impl Into<Cow<'a, [u8]>>) -> Option<Self> {
+		let varint = varint.into();
+		let (last, rest) = varint.split_last()?;
+		let valid = varint.len() <= Self::MAX_VARINT_BYTES
+			&& last & 0x80 == 0
+			&& rest.iter().all(|byte| byte & 0x80 != 0);
+		valid.then_some(Self { signed, varint })
+	}
+
+	/// Create a big integer from its encoding, i.e. the type byte followed by the var-int bytes.
+	pub(crate) fn from_encoded(encoded: Cow<'a, [u8]>) -> Option<Self> {
+		let signed = match Type::try_from(*encoded.first()?).ok()? {
+			Type::UnsignedInt => false,
+			Type::SignedInt => true,
+			_ => return None,
+		};
+		match encoded {
+			Cow::Borrowed(encoded) => Self::from_varint(signed, encoded.get(1 ..)?),
+			Cow::Owned(mut encoded) => {
+				encoded.remove(0);
+				Self::from_varint(signed, encoded)
+			}
+		}
+	}
+
+	/// Whether this is a signed integer, i.e. encoded as [Type::SignedInt].
+	#[must_use]
+	pub const fn is_signed(&self) -> bool {
+		self.signed
+	}
+
+	/// Whether the integer is negative.
+	#[must_use]
+	pub fn is_negative(&self) -> bool {
+		// The lowest bit of zigzag-encoded integers is the sign.
+		self.signed && self.varint.first().is_some_and(|byte| byte & 1 != 0)
+	}
+
+	/// The raw var-int bytes, without the type byte.
+	#[must_use]
+	pub fn varint(&self) -> &[u8] {
+		&self.varint
+	}
+
+	/// The encoded integer, i.e. the type byte followed by the var-int bytes.
+	#[must_use]
+	pub fn to_bytes(&self) -> Vec<u8> {
+		let t = if self.signed { Type::SignedInt } else { Type::UnsignedInt };
+		let mut bytes = Vec::with_capacity(self.varint.len() + 1);
+		bytes.push(t.into());
+		bytes.extend_from_slice(&self.varint);
+		bytes
+	}
+
+	/// Clone this integer, borrowing the var-int bytes.
+	#[must_use]
+	pub fn borrow_clone(&self) -> BigInteger<'_> {
+		BigInteger { signed: self.signed, varint: Cow::Borrowed(&self.varint) }
+	}
+
+	/// Make the integer `'static` by cloning borrowed var-int bytes.
+	#[must_use]
+	pub fn into_owned(self) -> BigInteger<'static> {
+		BigInteger { signed: self.signed, varint: Cow::Owned(self.varint.into_owned()) }
+	}
+
+	/// The absolute value as little-endian 32-bit limbs, without leading zero limbs.
+	fn magnitude(&self) -> Vec<u32> {
+		let mut limbs = Vec::with_capacity(self.varint.len() * 7 / 32 + 1);
+		let mut pending = 0_u64;
+		let mut pending_bits = 0;
+		for byte in self.varint.iter() {
+			pending |= u64::from(byte & 0x7F) << pending_bits;
+			pending_bits += 7;
+			if pending_bits >= 32 {
+				limbs.push(low_limb(pending));
+				pending >>= 32;
+				pending_bits -= 32;
+			}
+		}
+		limbs.push(low_limb(pending));
+
+		if self.signed {
+			// Zigzag decoding: `n / 2` for even and `(n + 1) / 2` for odd (negative) numbers.
+			let negative = self.is_negative();
+			let mut carry = 0;
+			for limb in limbs.iter_mut().rev() {
+				let lowest = *limb & 1;
+				*limb = (*limb >> 1) | (carry << 31);
+				carry = lowest;
+			}
+			if negative {
+				let overflow = limbs.iter_mut().all(|limb| {
+					let (sum, overflow) = limb.overflowing_add(1);
+					*limb = sum;
+					overflow
+				});
+				if overflow {
+					limbs.push(1);
+				}
+			}
+		}
+
+		while limbs.last() == Some(&0) {
+			limbs.pop();
+		}
+		limbs
+	}
+}
+
+/// The lowest 32 bits.
+const fn low_limb(value: u64) -> u32 {
+	let [a, b, c, d, ..] = value.to_le_bytes();
+	u32::from_le_bytes([a, b, c, d])
+}
+
+impl PartialEq for BigInteger<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.cmp(other).is_eq()
+	}
+}
+
+impl Eq for BigInteger<'_> {}
+
+impl PartialOrd for BigInteger<'_> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl Ord for BigInteger<'_> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		let (negative, other_negative) = (self.is_negative(), other.is_negative());
+		let (magnitude, other_magnitude) = (self.magnitude(), other.magnitude());
+		let magnitude_order = magnitude
+			.len()
+			.cmp(&other_magnitude.len())
+			.then_with(|| magnitude.iter().rev().cmp(other_magnitude.iter().rev()));
+		match (negative, other_negative) {
+			(false, false) => magnitude_order,
+			(true, true) => magnitude_order.reverse(),
+			(negative, _) if negative => Ordering::Less,
+			_ => Ordering::Greater,
+		}
+	}
+}
+
+impl Hash for BigInteger<'_> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.is_negative().hash(state);
+		self.magnitude().hash(state);
+	}
+}
+
+impl ::core::fmt::Display for BigInteger<'_> {
+	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		/// Base of the decimal chunks.
+		const CHUNK: u64 = 1_000_000_000;
+
+		// Repeatedly divide by the chunk base to get the decimal digits in chunks of 9.
+		let mut limbs = self.magnitude();
+		let mut chunks = Vec::with_capacity(limbs.len() * 32 / 29 + 1);
+		while !limbs.is_empty() {
+			let mut remainder = 0_u64;
+			for limb in limbs.iter_mut().rev() {
+				let current = (remainder << 32) | u64::from(*limb);
+				// The quotient fits into 32 bits, as the remainder is less than the base.
+				*limb = low_limb(current / CHUNK);
+				remainder = current % CHUNK;
+			}
+			chunks.push(remainder);
+			while limbs.last() == Some(&0) {
+				limbs.pop();
+			}
+		}
+
+		if self.is_negative() {
+			f.write_char('-')?;
+		}
+		let mut chunks = chunks.iter().rev();
+		write!(f, "{}", chunks.next().copied().unwrap_or(0))?;
+		chunks.try_for_each(|chunk| write!(f, "{chunk:09}"))
+	}
+}
@@ -0,0 +1,114 @@
+//! Builders for [Value]s that borrow from their inputs.
+//!
+//! [to_value](super::to_value) always produces a `Value<'static>`, because serde only passes
+//! strings and bytes to the serializer with a lifetime that is not tied to the input. Every string
+//! and byte slice is therefore copied. When converting a large borrowed structure just to inspect
+//! or forward parts of it, the builders here can be used to construct the value by hand instead,
+//! borrowing all `&str`, `&String`, `&[u8]` and `&Vec<u8>` inputs without allocating for them.
+//!
+//! Only the structure of the value is built here, so the result is not guaranteed to match what
+//! [to_value](super::to_value) would produce for the same type. Structs serialize to maps with
+//! field names as keys (or indices with `use_indices`), which is easy to replicate:
+//!
+//! ```rust
+//! use serde_brief::value::{builder::MapBuilder, Value};
+//!
+//! #[derive(serde::Serialize)]
+//! struct Document {
+//! 	title: String,
+//! 	body: String,
+//! }
+//!
+//! let document = Document { title: "Title".to_owned(), body: "A long text.".repeat(1000) };
+//! let value =
+//! 	MapBuilder::new().entry("title", &document.title).entry("body", &document.body).build();
+//! assert!(matches!(value["body"], Value::String(std::borrow::Cow::Borrowed(_))));
+//! assert_eq!(value, serde_brief::value::to_value(&document).unwrap());
+//! ```
+
+use ::alloc::collections::VecDeque;
+
+use super::Value;
+
+/// Builder for a [Value::Map]. Entries are kept in insertion order, duplicate keys are not
+/// checked.
+#[derive(Debug, Clone, Default)]
+pub struct MapBuilder<'a> {
+	/// The entries so far.
+	entries: VecDeque<(Value<'a>, Value<'a>)>,
+}
+
+impl<'a> MapBuilder<'a> {
+	/// Create a new empty map builder.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { entries: VecDeque::new() }
+	}
+
+	/// Create a new empty map builder with space for the given number of entries.
+	#[must_use]
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self { entries: VecDeque::with_capacity(capacity) }
+	}
+
+	/// Append an entry to the map.
+	#[must_use]
+	pub fn entry(mut self, key: impl Into<Value<'a>>, value: impl Into<Value<'a>>) -> Self {
+		self.entries.push_back((key.into(), value.into()));
+		self
+	}
+
+	/// Build the [Value::Map].
+	#[must_use]
+	pub fn build(self) -> Value<'a> {
+		Value::Map(self.entries)
+	}
+}
+
+/// Builder for a [Value::Array].
+#[derive(Debug, Clone, Default)]
+pub struct ArrayBuilder<'a> {
+	/// The items so far.
+	items: VecDeque<Value<'a>>,
+}
+
+impl<'a> ArrayBuilder<'a> {
+	/// Create a new empty array builder.
+	#[must_use]
+	pub const fn new() -> Self {
+		Self { items: VecDeque::new() }
+	}
+
+	/// Create a new empty array builder with space for the given number of items.
+	#[must_use]
+	pub fn with_capacity(capacity: usize) -> Self {
+		Self { items: VecDeque::with_capacity(capacity) }
+	}
+
+	/// Append an item to the array.
+	#[must_use]
+	pub fn item(mut self, value: impl Into<Value<'a>>) -> Self {
+		self.items.push_back(value.into());
+		self
+	}
+
+	/// Build the [Value::Array].
+	#[must_use]
+	pub fn build(self) -> Value<'a> {
+		Value::Array(self.items)
+	}
+}
+
+impl<'a> From<MapBuilder<'a>> for Value<'a> {
+	#[inline]
+	fn from(builder: MapBuilder<'a>) -> Self {
+		builder.build()
+	}
+}
+
+impl<'a> From<ArrayBuilder<'a>> for Value<'a> {
+	#[inline]
+	fn from(builder: ArrayBuilder<'a>) -> Self {
+		builder.build()
+	}
+}
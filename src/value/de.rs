@@ -10,13 +10,62 @@ use super::*;
 
 /// Deserializer to deserialize a [Value] into any type.
 #[derive(Debug)]
-pub struct ValueDeserializer<'de>(Value<'de>);
+pub struct ValueDeserializer<'de> {
+	/// The value to deserialize.
+	value: Value<'de>,
+	/// Settings, which are passed on to nested values.
+	settings: Settings,
+}
+
+/// Settings of the [ValueDeserializer].
+#[derive(Debug, Clone, Copy)]
+struct Settings {
+	/// Whether unsigned integer identifiers are struct field or enum variant indices.
+	use_indices: bool,
+	/// Whether to report being human-readable.
+	human_readable: bool,
+	/// Whether integers and floats are accepted for each other.
+	lenient_numbers: bool,
+}
 
 impl<'de> ValueDeserializer<'de> {
 	/// Create a new deserializer from the given value.
 	#[must_use]
 	pub const fn new(value: Value<'de>) -> Self {
-		Self(value)
+		Self {
+			value,
+			settings: Settings {
+				use_indices: false,
+				human_readable: false,
+				lenient_numbers: false,
+			},
+		}
+	}
+
+	/// Set whether unsigned integers in place of struct field names and enum variant names are
+	/// their indices, like in the `use_indices` format. They are then visited as `u32` indices,
+	/// as by the [Deserializer](crate::Deserializer), instead of as plain integers.
+	#[must_use]
+	pub const fn use_indices(mut self, use_indices: bool) -> Self {
+		self.settings.use_indices = use_indices;
+		self
+	}
+
+	/// Set whether types are told that the format is human-readable, so that they expect their
+	/// textual representation. Disabled by default, like the binary format.
+	#[must_use]
+	pub const fn human_readable(mut self, human_readable: bool) -> Self {
+		self.settings.human_readable = human_readable;
+		self
+	}
+
+	/// Set whether to accept integral floats for integers and integers for floats, if they are
+	/// converted exactly, as well as booleans as `0` and `1` and vice versa. Disabled by default.
+	/// Integers are always accepted regardless of their signedness, as long as they fit.
+	#[must_use]
+	pub const fn lenient_numbers(mut self, lenient_numbers: bool) -> Self {
+		self.settings.lenient_numbers = lenient_numbers;
+		self
 	}
 
 	/// Deserialize the value.
@@ -25,18 +74,26 @@ impl<'de> ValueDeserializer<'de> {
 	where
 		V: ::serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Null => visitor.visit_none(),
 			Value::Bool(b) => visitor.visit_bool(b),
 			Value::Integer(int) => visit_integer(int, visitor),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(_) => Err(crate::Error::VarIntTooLarge),
 			Value::Float(Float::F32(float)) => visitor.visit_f32(float),
 			Value::Float(Float::F64(float)) => visitor.visit_f64(float),
+			Value::Float(Float::F16Bits(bits)) => {
+				visitor.visit_f32(crate::format::f16_bits_to_f32(bits))
+			}
+			Value::Float(Float::F128Bits(bits)) => {
+				visitor.visit_f64(crate::format::f128_bits_to_f64(bits))
+			}
 			Value::Bytes(Cow::Borrowed(bytes)) => visitor.visit_borrowed_bytes(bytes),
 			Value::Bytes(Cow::Owned(bytes)) => visitor.visit_byte_buf(bytes),
 			Value::String(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
 			Value::String(Cow::Owned(s)) => visitor.visit_string(s),
-			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr)),
-			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map)),
+			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr, self.settings)),
+			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map, self.settings)),
 		}
 	}
 }
@@ -46,7 +103,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 
 	#[inline]
 	fn is_human_readable(&self) -> bool {
-		true
+		self.settings.human_readable
 	}
 
 	#[inline]
@@ -64,8 +121,15 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Bool(value) => visitor.visit_bool(value),
+			Value::Integer(int) if self.settings.lenient_numbers => {
+				let value = match int {
+					Integer::Unsigned(int) => Some(int),
+					Integer::Signed(int) => u128::try_from(int).ok(),
+				};
+				crate::de::visit_int_as_bool(value, visitor)
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"bool")),
 		}
 	}
@@ -76,8 +140,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"i8")),
 		}
 	}
@@ -88,8 +158,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"i16")),
 		}
 	}
@@ -100,8 +176,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"i32")),
 		}
 	}
@@ -112,8 +194,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"i64")),
 		}
 	}
@@ -124,8 +212,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"u8")),
 		}
 	}
@@ -136,8 +230,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"u16")),
 		}
 	}
@@ -148,8 +248,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"u32")),
 		}
 	}
@@ -160,8 +266,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"u64")),
 		}
 	}
@@ -172,8 +284,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"i128")),
 		}
 	}
@@ -184,8 +302,14 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Integer(int) => visit_integer(int, visitor),
+			Value::Float(float) if self.settings.lenient_numbers => {
+				visit_float_as_int(float, visitor)
+			}
+			Value::Bool(value) if self.settings.lenient_numbers => {
+				visitor.visit_u8(u8::from(value))
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"u128")),
 		}
 	}
@@ -196,9 +320,18 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Float(Float::F32(float)) => visitor.visit_f32(float),
 			Value::Float(Float::F64(float)) => visitor.visit_f64(float),
+			Value::Float(Float::F16Bits(bits)) => {
+				visitor.visit_f32(crate::format::f16_bits_to_f32(bits))
+			}
+			Value::Float(Float::F128Bits(bits)) => {
+				visitor.visit_f64(crate::format::f128_bits_to_f64(bits))
+			}
+			Value::Integer(int) if self.settings.lenient_numbers => {
+				visit_int_as_float(int, true, visitor)
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"float")),
 		}
 	}
@@ -209,9 +342,18 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Float(Float::F32(float)) => visitor.visit_f32(float),
 			Value::Float(Float::F64(float)) => visitor.visit_f64(float),
+			Value::Float(Float::F16Bits(bits)) => {
+				visitor.visit_f32(crate::format::f16_bits_to_f32(bits))
+			}
+			Value::Float(Float::F128Bits(bits)) => {
+				visitor.visit_f64(crate::format::f128_bits_to_f64(bits))
+			}
+			Value::Integer(int) if self.settings.lenient_numbers => {
+				visit_int_as_float(int, false, visitor)
+			}
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"float")),
 		}
 	}
@@ -222,7 +364,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::String(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
 			Value::String(Cow::Owned(s)) => visitor.visit_string(s),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"char")),
@@ -235,7 +377,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::String(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
 			Value::String(Cow::Owned(s)) => visitor.visit_string(s),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"string")),
@@ -248,7 +390,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::String(Cow::Borrowed(s)) => visitor.visit_borrowed_str(s),
 			Value::String(Cow::Owned(s)) => visitor.visit_string(s),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"string")),
@@ -261,7 +403,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Bytes(Cow::Borrowed(bytes)) => visitor.visit_borrowed_bytes(bytes),
 			Value::Bytes(Cow::Owned(bytes)) => visitor.visit_byte_buf(bytes),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"bytes")),
@@ -274,7 +416,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Bytes(Cow::Borrowed(bytes)) => visitor.visit_borrowed_bytes(bytes),
 			Value::Bytes(Cow::Owned(bytes)) => visitor.visit_byte_buf(bytes),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"bytes")),
@@ -287,7 +429,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		if matches!(&self.0, Value::Null) {
+		if matches!(&self.value, Value::Null) {
 			visitor.visit_none()
 		} else {
 			visitor.visit_some(self)
@@ -300,7 +442,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Null => visitor.visit_unit(),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"unit")),
 		}
@@ -316,7 +458,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
 			Value::Null => visitor.visit_unit(),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"unit")),
 		}
@@ -326,13 +468,37 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, visitor)))]
 	fn deserialize_newtype_struct<V>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		visitor: V,
 	) -> Result<V::Value, Self::Error>
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		visitor.visit_newtype_struct(self)
+		match self.value {
+			// Keep the raw float bits for `Value`, see the name's documentation.
+			Value::Float(Float::F16Bits(bits)) if name == crate::format::VALUE_NEWTYPE_NAME => {
+				visitor.visit_map(crate::de::FloatBitsMapDeserializer::new(
+					crate::format::F16_BITS_NEWTYPE_NAME,
+					bits,
+				))
+			}
+			Value::Float(Float::F128Bits(bits)) if name == crate::format::VALUE_NEWTYPE_NAME => {
+				visitor.visit_map(crate::de::FloatBitsMapDeserializer::new(
+					crate::format::F128_BITS_NEWTYPE_NAME,
+					bits,
+				))
+			}
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) if name == crate::format::VALUE_NEWTYPE_NAME => {
+				visitor.visit_map(crate::de::BigIntegerMapDeserializer::new(&int.to_bytes()))
+			}
+			// `f16_as_float16` also accepts the bits (`u16`) of the `f16` implementation.
+			#[cfg(feature = "half")]
+			Value::Integer(_) if name == crate::format::F16_BITS_NEWTYPE_NAME => {
+				self.deserialize_u16(visitor)
+			}
+			_ => visitor.visit_newtype_struct(self),
+		}
 	}
 
 	#[inline]
@@ -341,8 +507,8 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr)),
+		match self.value {
+			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"sequence")),
 		}
 	}
@@ -353,8 +519,8 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr)),
+		match self.value {
+			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"tuple")),
 		}
 	}
@@ -370,8 +536,8 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr)),
+		match self.value {
+			Value::Array(arr) => visitor.visit_seq(ValueSeqDeserializer(arr, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"tuple struct")),
 		}
 	}
@@ -382,8 +548,8 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map)),
+		match self.value {
+			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"map")),
 		}
 	}
@@ -399,8 +565,8 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map)),
+		match self.value {
+			Value::Map(map) => visitor.visit_map(ValueMapDeserializer(map, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"map")),
 		}
 	}
@@ -416,12 +582,12 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
-			Value::Integer(Integer::Unsigned(int)) => {
-				visitor.visit_enum((int as u32).into_deserializer())
+		match self.value {
+			Value::Integer(Integer::Unsigned(index)) => {
+				visitor.visit_enum(index_to_u32(index)?.into_deserializer())
 			}
 			Value::String(s) => visitor.visit_enum(s.as_ref().into_deserializer()),
-			Value::Map(map) => visitor.visit_enum(ValueEnumDeserializer(map)),
+			Value::Map(map) => visitor.visit_enum(ValueEnumDeserializer(map, self.settings)),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"enum")),
 		}
 	}
@@ -432,7 +598,10 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 	where
 		V: serde::de::Visitor<'de>,
 	{
-		match self.0 {
+		match self.value {
+			Value::Integer(Integer::Unsigned(index)) if self.settings.use_indices => {
+				visitor.visit_u32(index_to_u32(index)?)
+			}
 			Value::Integer(Integer::Unsigned(_)) => self.deserialize_u32(visitor),
 			Value::String(_) => self.deserialize_str(visitor),
 			other => Err(Error::invalid_type(Unexpected::from(&other), &"identifier")),
@@ -451,7 +620,7 @@ impl<'de> ::serde::de::Deserializer<'de> for ValueDeserializer<'de> {
 
 /// Enum deserializer.
 #[derive(Debug)]
-struct ValueEnumDeserializer<'de>(VecDeque<(Value<'de>, Value<'de>)>);
+struct ValueEnumDeserializer<'de>(VecDeque<(Value<'de>, Value<'de>)>, Settings);
 
 impl<'de> ::serde::de::EnumAccess<'de> for ValueEnumDeserializer<'de> {
 	type Error = crate::Error;
@@ -463,14 +632,11 @@ impl<'de> ::serde::de::EnumAccess<'de> for ValueEnumDeserializer<'de> {
 	where
 		V: serde::de::DeserializeSeed<'de>,
 	{
-		if self.0.len() != 1 {
+		let (1, Some((key, value))) = (self.0.len(), self.0.pop_front()) else {
 			return Err(Error::invalid_length(1, &"exactly one key-value-pair"));
-		}
-
-		#[expect(clippy::unwrap_used, reason = "Was just checked")]
-		let (key, value) = self.0.pop_front().unwrap();
-		let res = seed.deserialize(ValueDeserializer(key))?;
-		Ok((res, ValueDeserializer(value)))
+		};
+		let res = seed.deserialize(ValueDeserializer { value: key, settings: self.1 })?;
+		Ok((res, ValueDeserializer { value, settings: self.1 }))
 	}
 }
 
@@ -480,7 +646,7 @@ impl<'de> ::serde::de::VariantAccess<'de> for ValueDeserializer<'de> {
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip_all))]
 	fn unit_variant(self) -> Result<(), Self::Error> {
-		Err(Error::invalid_type(Unexpected::from(&self.0), &"unit variant"))
+		Err(Error::invalid_type(Unexpected::from(&self.value), &"unit variant"))
 	}
 
 	#[inline]
@@ -517,7 +683,7 @@ impl<'de> ::serde::de::VariantAccess<'de> for ValueDeserializer<'de> {
 
 /// Sequence deserializer.
 #[derive(Debug)]
-struct ValueSeqDeserializer<'de>(VecDeque<Value<'de>>);
+struct ValueSeqDeserializer<'de>(VecDeque<Value<'de>>, Settings);
 
 impl<'de> ::serde::de::SeqAccess<'de> for ValueSeqDeserializer<'de> {
 	type Error = crate::Error;
@@ -534,7 +700,7 @@ impl<'de> ::serde::de::SeqAccess<'de> for ValueSeqDeserializer<'de> {
 		T: serde::de::DeserializeSeed<'de>,
 	{
 		if let Some(value) = self.0.pop_front() {
-			seed.deserialize(ValueDeserializer(value)).map(Some)
+			seed.deserialize(ValueDeserializer { value, settings: self.1 }).map(Some)
 		} else {
 			Ok(None)
 		}
@@ -543,7 +709,7 @@ impl<'de> ::serde::de::SeqAccess<'de> for ValueSeqDeserializer<'de> {
 
 /// Map deserializer.
 #[derive(Debug)]
-struct ValueMapDeserializer<'de>(VecDeque<(Value<'de>, Value<'de>)>);
+struct ValueMapDeserializer<'de>(VecDeque<(Value<'de>, Value<'de>)>, Settings);
 
 impl<'de> ::serde::de::MapAccess<'de> for ValueMapDeserializer<'de> {
 	type Error = crate::Error;
@@ -561,7 +727,7 @@ impl<'de> ::serde::de::MapAccess<'de> for ValueMapDeserializer<'de> {
 	{
 		if let Some((key, _value)) = self.0.front_mut() {
 			let value = ::core::mem::replace(key, Value::Null);
-			Ok(Some(seed.deserialize(ValueDeserializer(value))?))
+			Ok(Some(seed.deserialize(ValueDeserializer { value, settings: self.1 })?))
 		} else {
 			Ok(None)
 		}
@@ -574,7 +740,7 @@ impl<'de> ::serde::de::MapAccess<'de> for ValueMapDeserializer<'de> {
 		V: serde::de::DeserializeSeed<'de>,
 	{
 		if let Some((_key, value)) = self.0.pop_front() {
-			Ok(seed.deserialize(ValueDeserializer(value))?)
+			Ok(seed.deserialize(ValueDeserializer { value, settings: self.1 })?)
 		} else {
 			Err(Error::custom("next_value_seed called without next_key_seed"))
 		}
@@ -593,8 +759,8 @@ impl<'de> ::serde::de::MapAccess<'de> for ValueMapDeserializer<'de> {
 		V: serde::de::DeserializeSeed<'de>,
 	{
 		if let Some((key, value)) = self.0.pop_front() {
-			let key = kseed.deserialize(ValueDeserializer(key))?;
-			let value = vseed.deserialize(ValueDeserializer(value))?;
+			let key = kseed.deserialize(ValueDeserializer { value: key, settings: self.1 })?;
+			let value = vseed.deserialize(ValueDeserializer { value, settings: self.1 })?;
 			Ok(Some((key, value)))
 		} else {
 			Ok(None)
@@ -602,44 +768,76 @@ impl<'de> ::serde::de::MapAccess<'de> for ValueMapDeserializer<'de> {
 	}
 }
 
+/// Convert a field or variant index to `u32`, the type of indices in the binary format.
+fn index_to_u32(index: u128) -> Result<u32> {
+	u32::try_from(index)
+		.map_err(|_| Error::invalid_value(Unexpected::Other("large integer"), &"u32 index"))
+}
+
 /// Visit the integer, depending on its value / size.
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip(visitor)))]
 fn visit_integer<'de, V>(int: Integer, visitor: V) -> Result<V::Value>
 where
 	V: ::serde::de::Visitor<'de>,
 {
-	#[allow(clippy::cast_lossless, reason = "We won't change it")]
 	match int {
-		Integer::Unsigned(int) if int <= u8::MAX as u128 => visitor.visit_u8(int as u8),
-		Integer::Unsigned(int) if int <= u16::MAX as u128 => visitor.visit_u16(int as u16),
-		Integer::Unsigned(int) if int <= u32::MAX as u128 => visitor.visit_u32(int as u32),
-		Integer::Unsigned(int) if int <= u64::MAX as u128 => visitor.visit_u64(int as u64),
-		Integer::Unsigned(int) => visitor.visit_u128(int),
-		Integer::Signed(int) if (i8::MIN as i128 ..= i8::MAX as i128).contains(&int) => {
-			visitor.visit_i8(int as i8)
-		}
-		Integer::Signed(int) if (i16::MIN as i128 ..= i16::MAX as i128).contains(&int) => {
-			visitor.visit_i16(int as i16)
-		}
-		Integer::Signed(int) if (i32::MIN as i128 ..= i32::MAX as i128).contains(&int) => {
-			visitor.visit_i32(int as i32)
-		}
-		Integer::Signed(int) if (i64::MIN as i128 ..= i64::MAX as i128).contains(&int) => {
-			visitor.visit_i64(int as i64)
-		}
-		Integer::Signed(int) => visitor.visit_i128(int),
+		Integer::Unsigned(int) => crate::de::visit_unsigned(int, visitor),
+		Integer::Signed(int) => crate::de::visit_signed(int, visitor),
 	}
 }
 
+/// Visit the float as integer, see [visit_integral_float](crate::de::visit_integral_float).
+fn visit_float_as_int<'de, V>(float: Float, visitor: V) -> Result<V::Value>
+where
+	V: ::serde::de::Visitor<'de>,
+{
+	let value = match float {
+		Float::F16Bits(bits) => f64::from(crate::format::f16_bits_to_f32(bits)),
+		Float::F32(float) => f64::from(float),
+		Float::F64(float) => float,
+		Float::F128Bits(bits) => {
+			let value = crate::format::f128_bits_to_f64(bits);
+			if crate::format::f64_to_f128_bits(value) != bits {
+				// Rounded, so it is rejected as float.
+				return visitor.visit_f64(value);
+			}
+			value
+		}
+	};
+	crate::de::visit_integral_float(value, visitor)
+}
+
+/// Visit the integer as float, see [visit_exact_float](crate::de::visit_exact_float).
+fn visit_int_as_float<'de, V>(int: Integer, single_precision: bool, visitor: V) -> Result<V::Value>
+where
+	V: ::serde::de::Visitor<'de>,
+{
+	let value = match int {
+		Integer::Unsigned(int) => crate::de::unsigned_to_f64(int),
+		Integer::Signed(int) => crate::de::signed_to_f64(int),
+	};
+	crate::de::visit_exact_float(value, single_precision, visitor)
+}
+
 impl<'a, 'de> From<&'a Value<'de>> for Unexpected<'a> {
 	fn from(value: &'a Value<'de>) -> Self {
 		match value {
 			Value::Null => Unexpected::Unit,
 			Value::Bool(b) => Unexpected::Bool(*b),
-			Value::Integer(Integer::Unsigned(int)) => Unexpected::Unsigned(*int as u64),
-			Value::Integer(Integer::Signed(int)) => Unexpected::Signed(*int as i64),
+			Value::Integer(Integer::Unsigned(int)) => u64::try_from(*int)
+				.map_or(Unexpected::Other("large unsigned integer"), Unexpected::Unsigned),
+			Value::Integer(Integer::Signed(int)) => i64::try_from(*int)
+				.map_or(Unexpected::Other("large signed integer"), Unexpected::Signed),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(_) => Unexpected::Other("big integer"),
 			Value::Float(Float::F32(float)) => Unexpected::Float(f64::from(*float)),
 			Value::Float(Float::F64(float)) => Unexpected::Float(*float),
+			Value::Float(Float::F16Bits(bits)) => {
+				Unexpected::Float(f64::from(crate::format::f16_bits_to_f32(*bits)))
+			}
+			Value::Float(Float::F128Bits(bits)) => {
+				Unexpected::Float(crate::format::f128_bits_to_f64(*bits))
+			}
 			Value::Bytes(bytes) => Unexpected::Bytes(bytes),
 			Value::String(s) => Unexpected::Str(s),
 			Value::Array(_arr) => Unexpected::Seq,
@@ -0,0 +1,307 @@
+//! Structural comparison of two [Value]s, e.g. for debugging schema drift between services.
+
+use ::alloc::{collections::BTreeMap, vec::Vec};
+use ::core::mem::discriminant;
+use ::serde::{ser::SerializeStruct, Serialize};
+
+use super::Value;
+
+/// Options for [diff_with_options].
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub struct DiffOptions {
+	/// Whether to ignore the order of map entries. Entries are always matched by their key, but
+	/// by default, maps with the same entries in a different order are reported as
+	/// [Change::ValueChanged], the same as they are not equal as [Value]s.
+	pub ignore_map_order: bool,
+	/// Whether to compare arrays as (multi-)sets instead of by position. Elements only found in
+	/// the old array are reported as removed at their old index, elements only found in the new
+	/// array as added at their new index.
+	pub arrays_as_sets: bool,
+}
+
+impl DiffOptions {
+	/// Set whether to ignore the order of map entries.
+	#[must_use]
+	pub const fn ignore_map_order(mut self, ignore: bool) -> Self {
+		self.ignore_map_order = ignore;
+		self
+	}
+
+	/// Set whether to compare arrays as sets.
+	#[must_use]
+	pub const fn arrays_as_sets(mut self, as_sets: bool) -> Self {
+		self.arrays_as_sets = as_sets;
+		self
+	}
+}
+
+/// A single difference between two values, found by [diff].
+///
+/// It can be serialized, e.g. to log it as JSON. The path is then a list of the map keys and array
+/// indices, the change is externally tagged, e.g.
+/// `{"path":["users",0,"name"],"change":{"ValueChanged":{"old":"a","new":"b"}}}`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Difference<'a> {
+	/// Path from the root to the changed value. Empty if the roots themselves differ.
+	pub path: Vec<PathSegment<'a>>,
+	/// The kind of change.
+	pub change: Change<'a>,
+}
+
+/// Segment of the path to a [Difference].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment<'a> {
+	/// Map entry with the given key.
+	Key(Value<'a>),
+	/// Array element at the given index.
+	Index(usize),
+}
+
+/// Kind of a [Difference].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Change<'a> {
+	/// The value only exists in the new value.
+	Added(Value<'a>),
+	/// The value only exists in the old value.
+	Removed(Value<'a>),
+	/// The value is of a different kind, e.g. a string instead of an integer.
+	TypeChanged {
+		/// The old value.
+		old: Value<'a>,
+		/// The new value.
+		new: Value<'a>,
+	},
+	/// The value is of the same kind, but different.
+	ValueChanged {
+		/// The old value.
+		old: Value<'a>,
+		/// The new value.
+		new: Value<'a>,
+	},
+}
+
+/// Compare two values with the default [DiffOptions] and return all differences from `old` to
+/// `new`, in the order of the old value. The result is empty exactly if the values are equal.
+///
+/// ```rust
+/// use serde_brief::value::{diff, Change, PathSegment, Value};
+///
+/// let old = Value::from_iter([("name", Value::from("Holla")), ("age", Value::from(30_u8))]);
+/// let new = Value::from_iter([("name", Value::from("Holla")), ("age", Value::from("30"))]);
+/// let differences = diff(&old, &new);
+/// assert_eq!(differences.len(), 1);
+/// assert_eq!(differences[0].path, [PathSegment::Key(Value::from("age"))]);
+/// assert!(matches!(differences[0].change, Change::TypeChanged { .. }));
+/// ```
+#[must_use]
+pub fn diff<'a>(old: &'a Value<'_>, new: &'a Value<'_>) -> Vec<Difference<'a>> {
+	diff_with_options(old, new, DiffOptions::default())
+}
+
+/// Compare two values with the given options and return all differences from `old` to `new`.
+#[must_use]
+pub fn diff_with_options<'a>(
+	old: &'a Value<'_>,
+	new: &'a Value<'_>,
+	options: DiffOptions,
+) -> Vec<Difference<'a>> {
+	let mut differ = Differ { options, path: Vec::new(), differences: Vec::new() };
+	differ.compare(old, new);
+	differ.differences
+}
+
+/// State of a comparison.
+struct Differ<'a> {
+	/// Options for the comparison.
+	options: DiffOptions,
+	/// Path to the currently compared values.
+	path: Vec<PathSegment<'a>>,
+	/// Differences found so far.
+	differences: Vec<Difference<'a>>,
+}
+
+impl<'a> Differ<'a> {
+	/// Record a difference at the current path, extended by the segment, if given.
+	fn push(&mut self, segment: Option<PathSegment<'a>>, change: Change<'a>) {
+		let mut path = self.path.clone();
+		path.extend(segment);
+		self.differences.push(Difference { path, change });
+	}
+
+	/// Compare two values at the current path.
+	fn compare(&mut self, old: &'a Value<'_>, new: &'a Value<'_>) {
+		match (old, new) {
+			(Value::Array(old_array), Value::Array(new_array)) => {
+				if self.options.arrays_as_sets {
+					self.compare_sets(old_array, new_array);
+				} else {
+					self.compare_arrays(old_array, new_array);
+				}
+			}
+			(Value::Map(old_map), Value::Map(new_map)) => {
+				let found = self.differences.len();
+				self.compare_maps(old_map, new_map);
+				let keys_in_order =
+					old_map.iter().map(|(key, _)| key).eq(new_map.iter().map(|(key, _)| key));
+				if !self.options.ignore_map_order
+					&& found == self.differences.len()
+					&& !keys_in_order
+				{
+					self.push(
+						None,
+						Change::ValueChanged { old: old.borrow_clone(), new: new.borrow_clone() },
+					);
+				}
+			}
+			_ if discriminant(old) != discriminant(new) => {
+				self.push(
+					None,
+					Change::TypeChanged { old: old.borrow_clone(), new: new.borrow_clone() },
+				);
+			}
+			_ if old != new => {
+				self.push(
+					None,
+					Change::ValueChanged { old: old.borrow_clone(), new: new.borrow_clone() },
+				);
+			}
+			_ => {}
+		}
+	}
+
+	/// Compare the value at the given path segment.
+	fn compare_at(&mut self, segment: PathSegment<'a>, old: &'a Value<'_>, new: &'a Value<'_>) {
+		self.path.push(segment);
+		self.compare(old, new);
+		self.path.pop();
+	}
+
+	/// Compare arrays element by element.
+	fn compare_arrays<'v, I>(&mut self, old: I, new: I)
+	where
+		I: IntoIterator<Item = &'a Value<'v>>,
+		'v: 'a,
+	{
+		let mut old = old.into_iter();
+		let mut new = new.into_iter();
+		for index in 0 .. {
+			match (old.next(), new.next()) {
+				(Some(old), Some(new)) => self.compare_at(PathSegment::Index(index), old, new),
+				(Some(old), None) => {
+					self.push(Some(PathSegment::Index(index)), Change::Removed(old.borrow_clone()));
+				}
+				(None, Some(new)) => {
+					self.push(Some(PathSegment::Index(index)), Change::Added(new.borrow_clone()));
+				}
+				(None, None) => break,
+			}
+		}
+	}
+
+	/// Compare arrays as multi-sets.
+	fn compare_sets<'v, I>(&mut self, old: I, new: I)
+	where
+		I: IntoIterator<Item = &'a Value<'v>>,
+		'v: 'a,
+	{
+		let mut unmatched = BTreeMap::<&Value<'_>, Vec<usize>>::new();
+		let new = new.into_iter().collect::<Vec<_>>();
+		for (index, value) in new.iter().enumerate().rev() {
+			unmatched.entry(*value).or_default().push(index);
+		}
+		for (index, value) in old.into_iter().enumerate() {
+			if unmatched.get_mut(value).and_then(Vec::pop).is_none() {
+				self.push(Some(PathSegment::Index(index)), Change::Removed(value.borrow_clone()));
+			}
+		}
+		let mut added = unmatched.into_values().flatten().collect::<Vec<_>>();
+		added.sort_unstable();
+		for index in added {
+			if let Some(value) = new.get(index) {
+				self.push(Some(PathSegment::Index(index)), Change::Added(value.borrow_clone()));
+			}
+		}
+	}
+
+	/// Compare maps entry by entry, matching the entries by key. Duplicate keys are matched in
+	/// their order.
+	fn compare_maps<'v, I>(&mut self, old: I, new: I)
+	where
+		I: IntoIterator<Item = &'a (Value<'v>, Value<'v>)>,
+		'v: 'a,
+	{
+		let mut unmatched = BTreeMap::<&Value<'_>, Vec<usize>>::new();
+		let new = new.into_iter().collect::<Vec<_>>();
+		for (index, (key, _)) in new.iter().enumerate().rev() {
+			unmatched.entry(key).or_default().push(index);
+		}
+		for (key, old_value) in old {
+			let segment = PathSegment::Key(key.borrow_clone());
+			match unmatched.get_mut(key).and_then(Vec::pop).and_then(|index| new.get(index)) {
+				Some((_, new_value)) => self.compare_at(segment, old_value, new_value),
+				None => self.push(Some(segment), Change::Removed(old_value.borrow_clone())),
+			}
+		}
+		let mut added = unmatched.into_values().flatten().collect::<Vec<_>>();
+		added.sort_unstable();
+		for index in added {
+			if let Some((key, value)) = new.get(index) {
+				self.push(
+					Some(PathSegment::Key(key.borrow_clone())),
+					Change::Added(value.borrow_clone()),
+				);
+			}
+		}
+	}
+}
+
+impl Serialize for Difference<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		let mut ser = serializer.serialize_struct("Difference", 2)?;
+		ser.serialize_field("path", &self.path)?;
+		ser.serialize_field("change", &self.change)?;
+		ser.end()
+	}
+}
+
+impl Serialize for PathSegment<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		match self {
+			Self::Key(key) => key.serialize(serializer),
+			Self::Index(index) => index.serialize(serializer),
+		}
+	}
+}
+
+impl Serialize for Change<'_> {
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		use ::serde::ser::SerializeStructVariant;
+
+		match self {
+			Self::Added(value) => serializer.serialize_newtype_variant("Change", 0, "Added", value),
+			Self::Removed(value) => {
+				serializer.serialize_newtype_variant("Change", 1, "Removed", value)
+			}
+			Self::TypeChanged { old, new } | Self::ValueChanged { old, new } => {
+				let (index, variant) = match self {
+					Self::TypeChanged { .. } => (2, "TypeChanged"),
+					_ => (3, "ValueChanged"),
+				};
+				let mut ser = serializer.serialize_struct_variant("Change", index, variant, 2)?;
+				ser.serialize_field("old", old)?;
+				ser.serialize_field("new", new)?;
+				ser.end()
+			}
+		}
+	}
+}
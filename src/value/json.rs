@@ -0,0 +1,306 @@
+//! Conversion of [Value] to JSON text and, with the **json** feature, conversions between [Value]
+//! and [`serde_json::Value`].
+//!
+//! Converting from JSON is lossless: Numbers become unsigned integers if they are positive
+//! integers, signed integers if they are negative integers, and 64-bit floats otherwise. Strings,
+//! arrays and objects become strings, arrays and maps (with string keys). This is the same as
+//! deserializing a [Value] from JSON.
+//!
+//! Converting to JSON follows these rules, configurable by [JsonOptions]:
+//! - Bytes become arrays of numbers by default, or base64 strings (standard alphabet, with
+//!   padding).
+//! - Integers outside of the JSON number range (`i64::MIN ..= u64::MAX`) fail to convert by
+//!   default, or become decimal strings.
+//! - Floats become numbers, all precisions are converted to 64-bit floats. NaN and infinities
+//!   become `null`, as JSON does not support them.
+//! - Map keys must be strings, integers or bools. Integers and bools are converted to strings.
+//!
+//! ```rust
+//! use serde_brief::value::Value;
+//!
+//! let value = Value::from_iter([("name", Value::from("A \"quoted\" name")), ("id", 5_u8.into())]);
+//! assert_eq!(value.to_json_string().unwrap(), r#"{"name":"A \"quoted\" name","id":5}"#);
+//! ```
+
+#[cfg(feature = "json")]
+use ::alloc::borrow::Cow;
+use ::alloc::{
+	borrow::ToOwned,
+	string::{String, ToString},
+};
+use ::core::fmt::Write;
+
+#[cfg(feature = "json")]
+use super::OwnedValue;
+use super::{Float, Integer, Value};
+use crate::{Error, Result};
+
+/// Representation of [Value::Bytes] in JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonBytes {
+	/// Array of numbers, like when serializing bytes to JSON.
+	#[default]
+	Array,
+	/// Base64 string with the standard alphabet and padding.
+	Base64,
+}
+
+/// Representation of integers outside of the JSON number range.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum JsonLargeIntegers {
+	/// Fail converting with [Error::NotJsonCompatible].
+	#[default]
+	Error,
+	/// Decimal string.
+	String,
+}
+
+/// Options for converting a [Value] to JSON.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct JsonOptions {
+	/// Representation of bytes.
+	pub bytes: JsonBytes,
+	/// Representation of integers outside of the JSON number range.
+	pub large_integers: JsonLargeIntegers,
+}
+
+impl<'a> Value<'a> {
+	/// Convert this value to compact JSON text using the default [JsonOptions]. See the
+	/// [module documentation](self) for the conversion rules.
+	pub fn to_json_string(&self) -> Result<String> {
+		self.to_json_string_with(JsonOptions::default())
+	}
+
+	/// Convert this value to compact JSON text using the given options. See the
+	/// [module documentation](self) for the conversion rules.
+	pub fn to_json_string_with(&self, options: JsonOptions) -> Result<String> {
+		let mut json = String::new();
+		write_json(&mut json, self, options)?;
+		Ok(json)
+	}
+
+	/// **json**: Convert this value to a [`serde_json::Value`] using the given options. See the
+	/// [module documentation](self) for the conversion rules.
+	#[cfg(feature = "json")]
+	pub fn to_json_with(&self, options: JsonOptions) -> Result<::serde_json::Value> {
+		use ::serde_json::Value as Json;
+
+		Ok(match self {
+			Value::Null => Json::Null,
+			Value::Bool(b) => Json::Bool(*b),
+			Value::Integer(int) => integer_to_json(*int, options)?,
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => match options.large_integers {
+				JsonLargeIntegers::String => Json::String(int.to_string()),
+				JsonLargeIntegers::Error => {
+					return Err(Error::NotJsonCompatible("integer is out of the JSON number range"))
+				}
+			},
+			Value::Float(float) => float_to_json(*float),
+			Value::Bytes(bytes) => match options.bytes {
+				JsonBytes::Array => {
+					Json::Array(bytes.iter().map(|byte| Json::from(*byte)).collect())
+				}
+				JsonBytes::Base64 => Json::String(base64(bytes)),
+			},
+			Value::String(s) => Json::String(s.as_ref().to_owned()),
+			Value::Array(arr) => Json::Array(
+				arr.iter().map(|value| value.to_json_with(options)).collect::<Result<_>>()?,
+			),
+			Value::Map(map) => Json::Object(
+				map.iter()
+					.map(|(key, value)| Ok((json_key(key)?, value.to_json_with(options)?)))
+					.collect::<Result<_>>()?,
+			),
+		})
+	}
+}
+
+/// Write a value as compact JSON text.
+fn write_json(json: &mut String, value: &Value<'_>, options: JsonOptions) -> Result<()> {
+	match value {
+		Value::Null => json.push_str("null"),
+		Value::Bool(b) => write!(json, "{b}")?,
+		Value::Integer(int) => {
+			if is_json_number(*int) {
+				write!(json, "{int}")?;
+			} else if options.large_integers == JsonLargeIntegers::String {
+				write!(json, "\"{int}\"")?;
+			} else {
+				return Err(Error::NotJsonCompatible("integer is out of the JSON number range"));
+			}
+		}
+		#[cfg(feature = "big-integers")]
+		Value::BigInteger(int) => {
+			if options.large_integers == JsonLargeIntegers::String {
+				write!(json, "\"{int}\"")?;
+			} else {
+				return Err(Error::NotJsonCompatible("integer is out of the JSON number range"));
+			}
+		}
+		Value::Float(float) => {
+			let start = json.len();
+			match *float {
+				Float::F32(float) if float.is_finite() => write!(json, "{float}")?,
+				Float::F16Bits(bits) if crate::format::f16_bits_to_f32(bits).is_finite() => {
+					write!(json, "{}", crate::format::f16_bits_to_f32(bits))?;
+				}
+				float if float.to_f64().is_finite() => write!(json, "{}", float.to_f64())?,
+				_ => json.push_str("null"),
+			}
+			// Keep floats distinguishable from integers.
+			if json
+				.get(start ..)
+				.is_some_and(|number| number.bytes().all(|b| b.is_ascii_digit() || b == b'-'))
+			{
+				json.push_str(".0");
+			}
+		}
+		Value::Bytes(bytes) => match options.bytes {
+			JsonBytes::Array => {
+				json.push('[');
+				for (i, byte) in bytes.iter().enumerate() {
+					if i > 0 {
+						json.push(',');
+					}
+					write!(json, "{byte}")?;
+				}
+				json.push(']');
+			}
+			JsonBytes::Base64 => write!(json, "\"{}\"", base64(bytes))?,
+		},
+		Value::String(s) => super::write_escaped(json, s)?,
+		Value::Array(arr) => {
+			json.push('[');
+			for (i, value) in arr.iter().enumerate() {
+				if i > 0 {
+					json.push(',');
+				}
+				write_json(json, value, options)?;
+			}
+			json.push(']');
+		}
+		Value::Map(map) => {
+			json.push('{');
+			for (i, (key, value)) in map.iter().enumerate() {
+				if i > 0 {
+					json.push(',');
+				}
+				super::write_escaped(json, &json_key(key)?)?;
+				json.push(':');
+				write_json(json, value, options)?;
+			}
+			json.push('}');
+		}
+	}
+	Ok(())
+}
+
+/// Whether the integer is in the JSON number range (`i64::MIN ..= u64::MAX`).
+fn is_json_number(int: Integer) -> bool {
+	match int {
+		Integer::Unsigned(int) => u64::try_from(int).is_ok(),
+		Integer::Signed(int) => i64::try_from(int).is_ok(),
+	}
+}
+
+/// Convert an integer to a JSON number, or string if configured.
+#[cfg(feature = "json")]
+fn integer_to_json(int: Integer, options: JsonOptions) -> Result<::serde_json::Value> {
+	let number = match int {
+		Integer::Unsigned(int) => u64::try_from(int).ok().map(::serde_json::Number::from),
+		Integer::Signed(int) => i64::try_from(int).ok().map(::serde_json::Number::from),
+	};
+	match (number, options.large_integers) {
+		(Some(number), _) => Ok(::serde_json::Value::Number(number)),
+		(None, JsonLargeIntegers::String) => Ok(::serde_json::Value::String(int.to_string())),
+		(None, JsonLargeIntegers::Error) => {
+			Err(Error::NotJsonCompatible("integer is out of the JSON number range"))
+		}
+	}
+}
+
+/// Convert a float to a JSON number, or `null` if it is not finite.
+#[cfg(feature = "json")]
+fn float_to_json(float: Float) -> ::serde_json::Value {
+	let float = match float {
+		Float::F32(float) => f64::from(float),
+		Float::F64(float) => float,
+		Float::F16Bits(bits) => f64::from(crate::format::f16_bits_to_f32(bits)),
+		Float::F128Bits(bits) => crate::format::f128_bits_to_f64(bits),
+	};
+	::serde_json::Number::from_f64(float)
+		.map_or(::serde_json::Value::Null, ::serde_json::Value::Number)
+}
+
+/// Convert a map key to a JSON object key.
+fn json_key(key: &Value<'_>) -> Result<String> {
+	match key {
+		Value::String(s) => Ok(s.as_ref().to_owned()),
+		Value::Integer(int) => Ok(int.to_string()),
+		#[cfg(feature = "big-integers")]
+		Value::BigInteger(int) => Ok(int.to_string()),
+		Value::Bool(b) => Ok(b.to_string()),
+		_ => Err(Error::NotJsonCompatible("map key is not a string, integer or bool")),
+	}
+}
+
+/// Encode bytes as base64 with the standard alphabet and padding.
+pub(crate) fn base64(bytes: &[u8]) -> String {
+	let mut encoded = String::with_capacity(bytes.len().div_ceil(3) * 4);
+	for chunk in bytes.chunks(3) {
+		crate::io::push_base64(&mut encoded, chunk);
+	}
+	encoded
+}
+
+/// Convert a [`serde_json::Value`] to a [Value]. This never fails, see the
+/// [module documentation](self) for the conversion rules.
+#[cfg(feature = "json")]
+impl From<::serde_json::Value> for OwnedValue {
+	fn from(json: ::serde_json::Value) -> Self {
+		use ::serde_json::Value as Json;
+
+		let value = match json {
+			Json::Null => Value::Null,
+			Json::Bool(b) => Value::Bool(b),
+			Json::Number(number) => {
+				if let Some(int) = number.as_u64() {
+					Value::Integer(Integer::Unsigned(int.into()))
+				} else if let Some(int) = number.as_i64() {
+					Value::Integer(Integer::Signed(int.into()))
+				} else {
+					Value::Float(Float::F64(number.as_f64().unwrap_or(f64::NAN)))
+				}
+			}
+			Json::String(s) => Value::String(Cow::Owned(s)),
+			Json::Array(arr) => Value::Array(arr.into_iter().map(|v| Self::from(v).0).collect()),
+			Json::Object(obj) => Value::Map(
+				obj.into_iter()
+					.map(|(key, value)| (Value::String(Cow::Owned(key)), Self::from(value).0))
+					.collect(),
+			),
+		};
+		Self(value)
+	}
+}
+
+/// Convert a [Value] to a [`serde_json::Value`] using the default [JsonOptions].
+#[cfg(feature = "json")]
+impl<'a> TryFrom<Value<'a>> for ::serde_json::Value {
+	type Error = Error;
+
+	fn try_from(value: Value<'a>) -> Result<Self> {
+		value.to_json_with(JsonOptions::default())
+	}
+}
+
+/// Convert a [Value] to a [`serde_json::Value`] using the default [JsonOptions].
+#[cfg(feature = "json")]
+impl<'a> TryFrom<&Value<'a>> for ::serde_json::Value {
+	type Error = Error;
+
+	fn try_from(value: &Value<'a>) -> Result<Self> {
+		value.to_json_with(JsonOptions::default())
+	}
+}
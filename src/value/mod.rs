@@ -4,7 +4,12 @@
 	allow(clippy::used_underscore_binding, reason = "Only used in tracing::instrument")
 )]
 
+#[cfg(feature = "big-integers")]
+mod big_integer;
+pub mod builder;
 mod de;
+mod diff;
+pub mod json;
 mod ser;
 
 use ::alloc::{
@@ -15,13 +20,18 @@ use ::alloc::{
 	vec::Vec,
 };
 use ::core::{
+	cmp::Ordering,
 	fmt::Write,
+	hash::{Hash, Hasher},
 	marker::PhantomData,
-	ops::{Deref, DerefMut},
+	ops::{Deref, DerefMut, Index, IndexMut},
 };
 use ::serde::{Deserialize, Serialize};
 
-use crate::{Config, Result};
+#[cfg(feature = "big-integers")]
+pub use self::big_integer::BigInteger;
+pub use self::diff::{diff, diff_with_options, Change, DiffOptions, Difference, PathSegment};
+use crate::{de::KeyMode, Config, Result};
 
 /// Serialize a type to the generic [Value] type using the given configuration.
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip(value)))]
@@ -29,11 +39,16 @@ pub fn to_value_with_config<T>(value: &T, config: Config) -> Result<Value<'stati
 where
 	T: Serialize,
 {
-	let ser = ser::ValueSerializer::new(config.use_indices);
+	let ser = ser::ValueSerializer::new(config.use_indices)
+		.human_readable(config.human_readable.unwrap_or(false))
+		.tag_fields(config.tag_fields);
 	value.serialize(ser)
 }
 
 /// Serialize a type to the generic [Value] type.
+///
+/// All strings and bytes are copied into the value, as serde does not allow to borrow them from
+/// the input. Use the [builder]s to construct a value that borrows from its input.
 pub fn to_value<T>(value: &T) -> Result<Value<'static>>
 where
 	T: Serialize,
@@ -43,11 +58,14 @@ where
 
 /// Deserialize a type from a generic [Value] using the given configuration.
 #[cfg_attr(feature = "tracing", ::tracing::instrument(skip(value)))]
-pub fn from_value_with_config<'de, T>(value: Value<'de>, _config: Config) -> Result<T>
+pub fn from_value_with_config<'de, T>(value: Value<'de>, config: Config) -> Result<T>
 where
 	T: Deserialize<'de>,
 {
-	let de = de::ValueDeserializer::new(value);
+	let de = de::ValueDeserializer::new(value)
+		.use_indices(config.use_indices)
+		.human_readable(config.human_readable.unwrap_or(false))
+		.lenient_numbers(config.lenient_numbers);
 	T::deserialize(de)
 }
 
@@ -62,8 +80,24 @@ where
 /// Generic value that can contain any value in our data format. It can be deserialized and
 /// serialized to be exactly as when serializing or deserializing with the given type.
 ///
+/// A value does not know which of its maps were structs, so map keys are always written as they
+/// are, regardless of [Config::use_indices]. That config only applies when converting from or to a
+/// typed value, e.g. in [to_value_with_config]. A struct converted with indices stays in index
+/// mode when serializing the value without, and vice versa. Use [Value::detect_key_mode] to find
+/// out which mode a value is in.
+///
 /// Note: [Clone]ing this value will not borrow from owned values. For that, you need to call
 /// [Value::borrow_clone].
+///
+/// Values have a total order, so they can be used in sorted and hashed collections:
+/// - Different kinds are ordered as null < bool < integer < float < bytes < string < array < map.
+/// - Integers and floats are compared by their numeric value first, but values of different types
+///   are never equal (e.g. signed and unsigned integers). Use [Integer::eq_numeric] or
+///   [Value::normalize_integers] to ignore the signedness of integers.
+/// - NaN is equal to itself (regardless of its payload) and ordered after all numbers. `-0.0` and
+///   `0.0` are equal.
+/// - Bytes, strings, arrays and maps are compared lexicographically, maps entry-wise in their
+///   order. Use [Value::sort_maps] to make the order of map entries irrelevant.
 #[derive(Debug, Clone, Default)]
 pub enum Value<'a> {
 	/// Null / None / Unit type.
@@ -73,6 +107,9 @@ pub enum Value<'a> {
 	Bool(bool),
 	/// Integer value.
 	Integer(Integer),
+	/// **big-integers**: Integer that does not fit into 128 bits.
+	#[cfg(feature = "big-integers")]
+	BigInteger(BigInteger<'a>),
 	/// Float value.
 	Float(Float),
 	/// Bytes value.
@@ -86,10 +123,26 @@ pub enum Value<'a> {
 }
 
 /// Wrapper for an owned value, i.e. `Value<'static>`.
-#[derive(Debug, Clone, Default)]
+///
+/// It can be stored and passed around without lifetimes, e.g. to keep schema-less data in a
+/// database column:
+///
+/// ```rust
+/// use serde_brief::value::{OwnedValue, Value};
+///
+/// let value = OwnedValue::new(Value::from_iter([("theme", "dark"), ("language", "en")]));
+/// let column: Vec<u8> = value.to_vec().unwrap();
+/// // ... store and load the column ...
+/// let loaded = OwnedValue::from_slice(&column).unwrap();
+/// assert_eq!(loaded, value);
+/// assert_eq!(loaded["theme"], *"dark");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub struct OwnedValue(Value<'static>);
 
-/// The unsigned/signed integer value.
+/// The unsigned/signed integer value. Integers of different signedness are not equal, even if
+/// they have the same numeric value, see [Value]. Comparisons with primitive integers are
+/// numeric, regardless of the signedness.
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum Integer {
 	/// Unsigned integer.
@@ -105,6 +158,12 @@ pub enum Float {
 	F32(f32),
 	/// 64-bit float.
 	F64(f64),
+	/// Raw bits of a 16-bit float. Serde does not support it natively, so it is kept as bits to
+	/// re-serialize it without changes.
+	F16Bits(u16),
+	/// Raw bits of a 128-bit float. Serde does not support it natively, so it is kept as bits to
+	/// re-serialize it without changes.
+	F128Bits(u128),
 }
 
 impl<'a> Value<'a> {
@@ -114,6 +173,8 @@ impl<'a> Value<'a> {
 			Value::Null => Value::Null,
 			Value::Bool(b) => Value::Bool(*b),
 			Value::Integer(int) => Value::Integer(*int),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => Value::BigInteger(int.borrow_clone()),
 			Value::Float(float) => Value::Float(*float),
 			Value::Bytes(bytes) => Value::Bytes(Cow::Borrowed(bytes)),
 			Value::String(s) => Value::String(Cow::Borrowed(s)),
@@ -131,6 +192,8 @@ impl<'a> Value<'a> {
 			Value::Null => Value::Null,
 			Value::Bool(b) => Value::Bool(b),
 			Value::Integer(int) => Value::Integer(int),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => Value::BigInteger(int.into_owned()),
 			Value::Float(float) => Value::Float(float),
 			Value::Bytes(bytes) => Value::Bytes(bytes.into_owned().into()),
 			Value::String(s) => Value::String(s.into_owned().into()),
@@ -164,6 +227,8 @@ impl<'a> Value<'a> {
 		match self {
 			Value::Null => true,
 			Value::Bool(_) | Value::Integer(_) | Value::Float(_) => false,
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(_) => false,
 			Value::Bytes(bytes) => bytes.is_empty(),
 			Value::String(s) => s.is_empty(),
 			Value::Array(arr) => arr.is_empty(),
@@ -201,6 +266,47 @@ impl<'a> Value<'a> {
 		}
 	}
 
+	/// Return the integer as `u64` if this is a [Value::Integer] in its range. Signed integers
+	/// that are not negative are converted as well.
+	#[must_use]
+	pub fn as_u64(&self) -> Option<u64> {
+		self.as_int().and_then(|int| u64::try_from(int).ok())
+	}
+
+	/// Return the integer as `i64` if this is a [Value::Integer] in its range. Unsigned integers
+	/// up to `i64::MAX` are converted as well.
+	#[must_use]
+	pub fn as_i64(&self) -> Option<i64> {
+		self.as_int().and_then(|int| i64::try_from(int).ok())
+	}
+
+	/// Return the number as `f64` if this is a [Value::Float] or [Value::Integer]. Like in
+	/// `serde_json`, integers are converted to the nearest float, which loses precision for large
+	/// values. Floats of other precisions are converted as well.
+	#[must_use]
+	pub fn as_f64(&self) -> Option<f64> {
+		match self {
+			Value::Float(float) => Some(float.to_f64()),
+			#[expect(clippy::cast_precision_loss, reason = "Nearest float is intended")]
+			Value::Integer(Integer::Unsigned(int)) => Some(*int as f64),
+			#[expect(clippy::cast_precision_loss, reason = "Nearest float is intended")]
+			Value::Integer(Integer::Signed(int)) => Some(*int as f64),
+			_ => None,
+		}
+	}
+
+	/// Return the bool if this is a [Value::Bool] or the integers `0` or `1`, which is how
+	/// `lenient_numbers` of the [Config] treats booleans.
+	#[must_use]
+	pub const fn as_bool_lenient(&self) -> Option<bool> {
+		match self {
+			Value::Bool(b) => Some(*b),
+			Value::Integer(Integer::Unsigned(0) | Integer::Signed(0)) => Some(false),
+			Value::Integer(Integer::Unsigned(1) | Integer::Signed(1)) => Some(true),
+			_ => None,
+		}
+	}
+
 	/// Return the inner bytes if this is a [Value::Bytes].
 	#[must_use]
 	pub fn as_bytes(&self) -> Option<&[u8]> {
@@ -221,6 +327,38 @@ impl<'a> Value<'a> {
 		}
 	}
 
+	/// Return the inner bytes, borrowed or owned, if this is a [Value::Bytes]. Otherwise, the
+	/// value is returned unchanged as error.
+	pub fn try_into_bytes(self) -> ::core::result::Result<Cow<'a, [u8]>, Self> {
+		if let Value::Bytes(v) = self {
+			Ok(v)
+		} else {
+			Err(self)
+		}
+	}
+
+	/// Return the inner string, borrowed or owned, if this is a [Value::String]. Otherwise, the
+	/// value is returned unchanged as error.
+	pub fn try_into_string(self) -> ::core::result::Result<Cow<'a, str>, Self> {
+		if let Value::String(v) = self {
+			Ok(v)
+		} else {
+			Err(self)
+		}
+	}
+
+	/// **uuid**: Return the UUID if this is a [Value::Bytes] of 16 bytes or a [Value::String]
+	/// containing a UUID.
+	#[cfg(feature = "uuid")]
+	#[must_use]
+	pub fn as_uuid(&self) -> Option<::uuid::Uuid> {
+		match self {
+			Value::Bytes(bytes) => ::uuid::Uuid::from_slice(bytes).ok(),
+			Value::String(s) => ::uuid::Uuid::try_parse(s).ok(),
+			_ => None,
+		}
+	}
+
 	/// Return the inner array if this is a [Value::Array].
 	#[must_use]
 	pub const fn as_array(&self) -> Option<&VecDeque<Value<'a>>> {
@@ -241,15 +379,371 @@ impl<'a> Value<'a> {
 		}
 	}
 
-	/// Iterate over the inner values if this is a [Value::Array] or [Value::Map].
+	/// Return the mutable inner array if this is a [Value::Array].
 	#[must_use]
-	pub fn into_values(self) -> Iter<Value<'static>> {
-		match self.into_owned().0 {
+	pub fn as_array_mut(&mut self) -> Option<&mut VecDeque<Value<'a>>> {
+		if let Value::Array(v) = self {
+			Some(v)
+		} else {
+			None
+		}
+	}
+
+	/// Return the mutable inner map if this is a [Value::Map].
+	#[must_use]
+	pub fn as_map_mut(&mut self) -> Option<&mut VecDeque<(Value<'a>, Value<'a>)>> {
+		if let Value::Map(v) = self {
+			Some(v)
+		} else {
+			None
+		}
+	}
+
+	/// Insert an entry into this [Value::Map]. An existing entry with an equal key is replaced in
+	/// place and its value returned, otherwise the entry is appended. [Value::Null] is turned into
+	/// an empty map first.
+	///
+	/// # Panics
+	/// If the value is neither a map nor null.
+	#[expect(clippy::panic, reason = "Documented panic, like `serde_json`")]
+	pub fn map_insert(
+		&mut self,
+		key: impl Into<Value<'a>>,
+		value: impl Into<Value<'a>>,
+	) -> Option<Value<'a>> {
+		if matches!(self, Value::Null) {
+			*self = Value::Map(VecDeque::new());
+		}
+		let Value::Map(map) = self else {
+			panic!("Cannot insert into non-map value");
+		};
+
+		let key = key.into();
+		let value = value.into();
+		if let Some((_, existing)) = map.iter_mut().find(|(k, _)| *k == key) {
+			return Some(::core::mem::replace(existing, value));
+		}
+		map.push_back((key, value));
+		None
+	}
+
+	/// Remove the entry with the given key from this [Value::Map] and return its value. The order
+	/// of the remaining entries is preserved. Returns `None` if the key does not exist or the value
+	/// is not a map.
+	pub fn map_remove<Q>(&mut self, key: &Q) -> Option<Value<'a>>
+	where
+		Q: ?Sized,
+		Value<'a>: PartialEq<Q>,
+	{
+		let map = self.as_map_mut()?;
+		let position = map.iter().position(|(k, _)| k == key)?;
+		map.remove(position).map(|(_, value)| value)
+	}
+
+	/// Append a value to this [Value::Array]. [Value::Null] is turned into an empty array first.
+	///
+	/// # Panics
+	/// If the value is neither an array nor null.
+	pub fn array_push(&mut self, value: impl Into<Value<'a>>) {
+		self.array_insert(self.as_array().map_or(0, VecDeque::len), value);
+	}
+
+	/// Insert a value into this [Value::Array] at the given index, shifting all values after it.
+	/// [Value::Null] is turned into an empty array first.
+	///
+	/// # Panics
+	/// If the value is neither an array nor null or the index is greater than the array's length.
+	#[expect(clippy::panic, reason = "Documented panic, like `serde_json`")]
+	pub fn array_insert(&mut self, index: usize, value: impl Into<Value<'a>>) {
+		if matches!(self, Value::Null) {
+			*self = Value::Array(VecDeque::new());
+		}
+		let Value::Array(arr) = self else {
+			panic!("Cannot insert into non-array value");
+		};
+		arr.insert(index, value.into());
+	}
+
+	/// Apply a patch with JSON merge patch semantics ([RFC 7386](https://www.rfc-editor.org/rfc/rfc7386)),
+	/// e.g. to layer configurations:
+	/// - A map patch is merged into the value entry by entry, recursively. A value that is not a
+	///   map is turned into an empty map first.
+	/// - A [Value::Null] entry in a map patch removes the key.
+	/// - Any other patch replaces the value, including arrays.
+	///
+	/// Duplicate keys in the value's maps are merged first, keeping the value of the last
+	/// occurrence at the position of the first, like deserializing into a map keeps the last value.
+	///
+	/// ```rust
+	/// use serde_brief::value::Value;
+	///
+	/// let mut config = Value::from_iter([
+	/// 	("level", Value::from("info")),
+	/// 	("outputs", Value::from_iter(["stdout"])),
+	/// 	("color", Value::from(true)),
+	/// ]);
+	/// let patch = Value::from_iter([
+	/// 	("level", Value::from("debug")),
+	/// 	("outputs", Value::from_iter(["file"])),
+	/// 	("color", Value::Null),
+	/// ]);
+	/// config.merge_patch(patch);
+	/// assert_eq!(
+	/// 	config,
+	/// 	Value::from_iter([
+	/// 		("level", Value::from("debug")),
+	/// 		("outputs", Value::from_iter(["file"]))
+	/// 	])
+	/// );
+	/// ```
+	pub fn merge_patch(&mut self, patch: Value<'a>) {
+		self.merge(patch, false);
+	}
+
+	/// Apply a patch like [Value::merge_patch], but append the elements of array patches to
+	/// existing arrays instead of replacing them.
+	pub fn deep_merge(&mut self, patch: Value<'a>) {
+		self.merge(patch, true);
+	}
+
+	/// Merge the patch into this value, see [Value::merge_patch].
+	fn merge(&mut self, patch: Value<'a>, append_arrays: bool) {
+		match patch {
+			Value::Map(patch) => {
+				if !matches!(self, Value::Map(_)) {
+					*self = Value::Map(VecDeque::new());
+				}
+				let Value::Map(map) = self else {
+					return;
+				};
+				Self::merge_duplicate_keys(map);
+				for (key, value) in patch {
+					let position = map.iter().position(|(k, _)| *k == key);
+					match (position, value) {
+						(Some(position), Value::Null) => _ = map.remove(position),
+						(None, Value::Null) => {}
+						(Some(position), value) => {
+							if let Some((_, existing)) = map.get_mut(position) {
+								existing.merge(value, append_arrays);
+							}
+						}
+						(None, value) => {
+							let mut new = Value::Null;
+							new.merge(value, append_arrays);
+							map.push_back((key, new));
+						}
+					}
+				}
+			}
+			Value::Array(patch) if append_arrays => match self {
+				Value::Array(arr) => arr.extend(patch),
+				_ => *self = Value::Array(patch),
+			},
+			patch => *self = patch,
+		}
+	}
+
+	/// Remove later entries with keys equal to earlier ones, moving their value to the first entry.
+	fn merge_duplicate_keys(map: &mut VecDeque<(Value<'a>, Value<'a>)>) {
+		let mut index = 1;
+		while let Some((key, _)) = map.get(index) {
+			match map.iter().take(index).position(|(k, _)| k == key) {
+				Some(first) => {
+					let value = map.remove(index).map(|(_, value)| value);
+					if let (Some((_, existing)), Some(value)) = (map.get_mut(first), value) {
+						*existing = value;
+					}
+				}
+				None => index += 1,
+			}
+		}
+	}
+
+	/// Recursively sort the entries of all maps by key, keeping the order of entries with equal
+	/// keys. Afterwards, values with the same content serialize to identical bytes, regardless of
+	/// the original order of map entries.
+	pub fn sort_maps(&mut self) {
+		match self {
+			Value::Array(arr) => arr.iter_mut().for_each(Self::sort_maps),
+			Value::Map(map) => {
+				for (key, value) in map.iter_mut() {
+					key.sort_maps();
+					value.sort_maps();
+				}
+				map.make_contiguous().sort_by(|(a, _), (b, _)| a.cmp(b));
+			}
+			_ => {}
+		}
+	}
+
+	/// Recursively [normalize](Integer::normalize) all integers, including map keys. Afterwards,
+	/// values that only differ in the signedness of integers are equal, e.g. the values of `5_u8`
+	/// and `5_i8`.
+	pub fn normalize_integers(&mut self) {
+		match self {
+			Value::Integer(int) => *int = int.normalize(),
+			Value::Array(arr) => arr.iter_mut().for_each(Self::normalize_integers),
+			Value::Map(map) => {
+				for (key, value) in map.iter_mut() {
+					key.normalize_integers();
+					value.normalize_integers();
+				}
+			}
+			_ => {}
+		}
+	}
+
+	/// Whether the keys of all maps are integers, strings or both, like
+	/// [Deserializer::detected_key_mode](crate::Deserializer::detected_key_mode) for structs and
+	/// enums. `None` is returned if there are no integer or string keys. Values do not know which
+	/// maps were structs, so all maps are taken into account.
+	#[must_use]
+	pub fn detect_key_mode(&self) -> Option<KeyMode> {
+		let merge = |mode: Option<KeyMode>, other: Option<KeyMode>| match (mode, other) {
+			(Some(mode), Some(other)) => Some(mode.merge(other)),
+			(mode, other) => mode.or(other),
+		};
+		match self {
+			Value::Array(arr) => arr.iter().map(Self::detect_key_mode).fold(None, merge),
+			Value::Map(map) => map.iter().fold(None, |mode, (key, value)| {
+				let key_mode = match key {
+					Value::Integer(_) => Some(KeyMode::Indices),
+					Value::String(_) => Some(KeyMode::Names),
+					_ => None,
+				};
+				merge(merge(mode, key_mode), value.detect_key_mode())
+			}),
+			_ => None,
+		}
+	}
+
+	/// The rank of the value kind in the total order.
+	const fn kind_rank(&self) -> u8 {
+		match self {
+			Value::Null => 0,
+			Value::Bool(_) => 1,
+			Value::Integer(_) => 2,
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(_) => 2,
+			Value::Float(_) => 3,
+			Value::Bytes(_) => 4,
+			Value::String(_) => 5,
+			Value::Array(_) => 6,
+			Value::Map(_) => 7,
+		}
+	}
+
+	/// Iterate over the inner values if this is a [Value::Array] or [Value::Map]. Borrowed data
+	/// stays borrowed.
+	#[must_use]
+	pub fn into_values(self) -> Iter<'a, Value<'a>> {
+		match self {
 			Value::Array(arr) => Iter::new(arr.into_iter()),
 			Value::Map(map) => Iter::new(map.into_iter().map(|(_key, value)| value)),
 			_ => Iter::new([].into_iter()),
 		}
 	}
+
+	/// Iterate over references to the inner values if this is a [Value::Array] or [Value::Map].
+	#[must_use]
+	pub fn values(&self) -> impl DoubleEndedIterator<Item = &Value<'a>> {
+		let arr = self.as_array().into_iter().flatten();
+		arr.chain(self.as_map().into_iter().flatten().map(|(_key, value)| value))
+	}
+
+	/// Iterate over references to the keys and values if this is a [Value::Map].
+	#[must_use]
+	pub fn entries(&self) -> impl DoubleEndedIterator<Item = (&Value<'a>, &Value<'a>)> {
+		self.as_map().into_iter().flatten().map(|(key, value)| (key, value))
+	}
+
+	/// Return the value of the given string key if this is a [Value::Map] containing it.
+	#[must_use]
+	pub fn get(&self, key: &str) -> Option<&Value<'a>> {
+		self.as_map()?.iter().find(|(k, _)| k == key).map(|(_, value)| value)
+	}
+
+	/// Return the mutable value of the given string key if this is a [Value::Map] containing it.
+	#[must_use]
+	pub fn get_mut(&mut self, key: &str) -> Option<&mut Value<'a>> {
+		let Value::Map(map) = self else {
+			return None;
+		};
+		map.iter_mut().find(|(k, _)| k == key).map(|(_, value)| value)
+	}
+
+	/// Return the value at the given index if this is a [Value::Array] containing it.
+	#[must_use]
+	pub fn get_index(&self, index: usize) -> Option<&Value<'a>> {
+		self.as_array()?.get(index)
+	}
+
+	/// Return the mutable value at the given index if this is a [Value::Array] containing it.
+	#[must_use]
+	pub fn get_index_mut(&mut self, index: usize) -> Option<&mut Value<'a>> {
+		let Value::Array(arr) = self else {
+			return None;
+		};
+		arr.get_mut(index)
+	}
+
+	/// Look up a value by a JSON-pointer-like path, e.g. `/server/ports/0`. Path segments select
+	/// map entries by string key or by unsigned integer key (see
+	/// [use_indices](crate::Config::use_indices)) and array entries by index. `~1` and `~0` in
+	/// segments are unescaped to `/` and `~`. The empty path returns this value.
+	#[must_use]
+	pub fn pointer(&self, pointer: &str) -> Option<&Value<'a>> {
+		let mut value = self;
+		for segment in pointer_segments(pointer)? {
+			value = match value {
+				Value::Map(map) => {
+					&map.iter().find(|(key, _)| pointer_segment_matches(key, &segment))?.1
+				}
+				Value::Array(arr) => arr.get(segment.parse::<usize>().ok()?)?,
+				_ => return None,
+			};
+		}
+		Some(value)
+	}
+
+	/// Look up a mutable value by a JSON-pointer-like path, see [pointer](Self::pointer).
+	#[must_use]
+	pub fn pointer_mut(&mut self, pointer: &str) -> Option<&mut Value<'a>> {
+		let mut value = self;
+		for segment in pointer_segments(pointer)? {
+			value = match value {
+				Value::Map(map) => {
+					&mut map.iter_mut().find(|(key, _)| pointer_segment_matches(key, &segment))?.1
+				}
+				Value::Array(arr) => arr.get_mut(segment.parse::<usize>().ok()?)?,
+				_ => return None,
+			};
+		}
+		Some(value)
+	}
+}
+
+/// Split a JSON-pointer-like path into unescaped segments. Returns `None` if the path is neither
+/// empty nor starts with `/`.
+fn pointer_segments(pointer: &str) -> Option<impl Iterator<Item = Cow<'_, str>>> {
+	let segments =
+		if pointer.is_empty() { None } else { Some(pointer.strip_prefix('/')?.split('/')) };
+	Some(segments.into_iter().flatten().map(|segment| {
+		if segment.contains('~') {
+			Cow::Owned(segment.replace("~1", "/").replace("~0", "~"))
+		} else {
+			Cow::Borrowed(segment)
+		}
+	}))
+}
+
+/// Whether a map key matches the path segment, either as string or as unsigned integer.
+fn pointer_segment_matches(key: &Value<'_>, segment: &str) -> bool {
+	match key {
+		Value::String(s) => s == segment,
+		Value::Integer(Integer::Unsigned(int)) => segment.parse::<u128>().is_ok_and(|i| i == *int),
+		_ => false,
+	}
 }
 
 impl OwnedValue {
@@ -264,6 +758,51 @@ impl OwnedValue {
 	pub fn into_inner(self) -> Value<'static> {
 		self.0
 	}
+
+	/// Deserialize an owned value from bytes using the given configuration.
+	pub fn from_slice_with_config(bytes: &[u8], config: Config) -> Result<Self> {
+		crate::from_slice_with_config(bytes, config)
+	}
+
+	/// Deserialize an owned value from bytes.
+	pub fn from_slice(bytes: &[u8]) -> Result<Self> {
+		Self::from_slice_with_config(bytes, Config::default())
+	}
+
+	/// Serialize this value to bytes using the given configuration.
+	pub fn to_vec_with_config(&self, config: Config) -> Result<Vec<u8>> {
+		crate::to_vec_with_config(self, config)
+	}
+
+	/// Serialize this value to bytes.
+	pub fn to_vec(&self) -> Result<Vec<u8>> {
+		self.to_vec_with_config(Config::default())
+	}
+}
+
+impl<'a> From<Value<'a>> for OwnedValue {
+	#[inline]
+	fn from(value: Value<'a>) -> Self {
+		value.into_owned()
+	}
+}
+
+impl TryFrom<&[u8]> for OwnedValue {
+	type Error = crate::Error;
+
+	#[inline]
+	fn try_from(bytes: &[u8]) -> Result<Self> {
+		Self::from_slice(bytes)
+	}
+}
+
+impl TryFrom<&OwnedValue> for Vec<u8> {
+	type Error = crate::Error;
+
+	#[inline]
+	fn try_from(value: &OwnedValue) -> Result<Self> {
+		value.to_vec()
+	}
 }
 
 impl Deref for OwnedValue {
@@ -280,13 +819,29 @@ impl DerefMut for OwnedValue {
 	}
 }
 
+/// Strings are quoted and escaped like in JSON. The alternate form (`{:#}`) puts the items of
+/// arrays and maps on separate, indented lines.
 impl<'a> ::core::fmt::Display for Value<'a> {
 	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+		self.fmt_indented(f, 0)
+	}
+}
+
+impl Value<'_> {
+	/// Display the value at the given nesting level, which is the indentation of the alternate
+	/// form.
+	fn fmt_indented(
+		&self,
+		f: &mut ::core::fmt::Formatter<'_>,
+		level: usize,
+	) -> ::core::fmt::Result {
 		match self {
 			Value::Null => f.write_str("null"),
 			Value::Bool(b) if *b => f.write_str("true"),
 			Value::Bool(_) => f.write_str("false"),
 			Value::Integer(int) => ::core::fmt::Display::fmt(int, f),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => ::core::fmt::Display::fmt(int, f),
 			Value::Float(float) => ::core::fmt::Display::fmt(float, f),
 			Value::Bytes(bytes) => {
 				f.write_str("0x")?;
@@ -298,26 +853,28 @@ impl<'a> ::core::fmt::Display for Value<'a> {
 				}
 				Ok(())
 			}
-			Value::String(s) => f.write_str(s),
+			Value::String(s) => write_escaped(f, s),
 			Value::Array(arr) => {
 				f.write_char('[')?;
 				for (i, value) in arr.iter().enumerate() {
-					if i > 0 {
-						f.write_str(", ")?;
-					}
-					::core::fmt::Display::fmt(value, f)?;
+					write_separator(f, i, level + 1)?;
+					value.fmt_indented(f, level + 1)?;
+				}
+				if f.alternate() && !arr.is_empty() {
+					write_line_break(f, level)?;
 				}
 				f.write_char(']')
 			}
 			Value::Map(map) => {
 				f.write_char('{')?;
 				for (i, (key, value)) in map.iter().enumerate() {
-					if i > 0 {
-						f.write_str(", ")?;
-					}
-					::core::fmt::Display::fmt(key, f)?;
+					write_separator(f, i, level + 1)?;
+					key.fmt_indented(f, level + 1)?;
 					f.write_str(": ")?;
-					::core::fmt::Display::fmt(value, f)?;
+					value.fmt_indented(f, level + 1)?;
+				}
+				if f.alternate() && !map.is_empty() {
+					write_line_break(f, level)?;
 				}
 				f.write_char('}')
 			}
@@ -325,6 +882,48 @@ impl<'a> ::core::fmt::Display for Value<'a> {
 	}
 }
 
+/// Write the separator before the item at the given index of an array or map: A comma after the
+/// first item and, in the alternate form, a new line indented to the level of the items.
+fn write_separator(
+	f: &mut ::core::fmt::Formatter<'_>,
+	index: usize,
+	level: usize,
+) -> ::core::fmt::Result {
+	if index > 0 {
+		f.write_char(',')?;
+	}
+	if f.alternate() {
+		write_line_break(f, level)
+	} else if index > 0 {
+		f.write_char(' ')
+	} else {
+		Ok(())
+	}
+}
+
+/// Write a new line, indented to the given level.
+fn write_line_break(f: &mut ::core::fmt::Formatter<'_>, level: usize) -> ::core::fmt::Result {
+	f.write_char('\n')?;
+	(0 .. level).try_for_each(|_| f.write_str("  "))
+}
+
+/// Write a string in double quotes, escaping quotes, backslashes and control characters like JSON.
+fn write_escaped(f: &mut impl Write, s: &str) -> ::core::fmt::Result {
+	f.write_char('"')?;
+	for c in s.chars() {
+		match c {
+			'"' => f.write_str("\\\"")?,
+			'\\' => f.write_str("\\\\")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			c if u32::from(c) < 0x20 => write!(f, "\\u{:04x}", u32::from(c))?,
+			c => f.write_char(c)?,
+		}
+	}
+	f.write_char('"')
+}
+
 impl ::core::fmt::Display for Integer {
 	fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
 		match self {
@@ -339,6 +938,13 @@ impl ::core::fmt::Display for Float {
 		match self {
 			Float::F32(float) => ::core::fmt::Display::fmt(float, f),
 			Float::F64(float) => ::core::fmt::Display::fmt(float, f),
+			Float::F16Bits(bits) => {
+				::core::fmt::Display::fmt(&crate::format::f16_bits_to_f32(*bits), f)
+			}
+			// Displayed with `f64` precision only.
+			Float::F128Bits(bits) => {
+				::core::fmt::Display::fmt(&crate::format::f128_bits_to_f64(*bits), f)
+			}
 		}
 	}
 }
@@ -353,8 +959,23 @@ impl<'a> Serialize for Value<'a> {
 			Value::Bool(b) => serializer.serialize_bool(*b),
 			Value::Integer(Integer::Unsigned(int)) => serializer.serialize_u128(*int),
 			Value::Integer(Integer::Signed(int)) => serializer.serialize_i128(*int),
+			// Other formats get the encoded bytes.
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => serializer.serialize_newtype_struct(
+				crate::format::BIG_INTEGER_NEWTYPE_NAME,
+				&crate::raw::RawBytes(&int.to_bytes()),
+			),
 			Value::Float(Float::F32(float)) => serializer.serialize_f32(*float),
 			Value::Float(Float::F64(float)) => serializer.serialize_f64(*float),
+			// Other formats get the closest float serde supports, see the names' documentation.
+			Value::Float(Float::F16Bits(bits)) => serializer.serialize_newtype_struct(
+				crate::format::F16_BITS_NEWTYPE_NAME,
+				&crate::ser::FloatBitsPayload(crate::ser::FloatBits::F16(*bits)),
+			),
+			Value::Float(Float::F128Bits(bits)) => serializer.serialize_newtype_struct(
+				crate::format::F128_BITS_NEWTYPE_NAME,
+				&crate::ser::FloatBitsPayload(crate::ser::FloatBits::F128(*bits)),
+			),
 			Value::Bytes(bytes) => serializer.serialize_bytes(bytes),
 			Value::String(s) => serializer.serialize_str(s),
 			Value::Array(arr) => {
@@ -379,13 +1000,25 @@ impl<'a> Serialize for Value<'a> {
 	}
 }
 
+impl Serialize for OwnedValue {
+	#[inline]
+	fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+	where
+		S: ::serde::Serializer,
+	{
+		self.0.serialize(serializer)
+	}
+}
+
 impl<'de> Deserialize<'de> for Value<'de> {
 	#[inline]
 	fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
 	where
 		D: serde::Deserializer<'de>,
 	{
-		deserializer.deserialize_any(ValueVisitor::default())
+		// Deserializers not knowing the name treat it as a normal newtype struct.
+		deserializer
+			.deserialize_newtype_struct(crate::format::VALUE_NEWTYPE_NAME, ValueVisitor::default())
 	}
 }
 
@@ -395,10 +1028,21 @@ impl<'de> Deserialize<'de> for OwnedValue {
 	where
 		D: serde::Deserializer<'de>,
 	{
-		deserializer.deserialize_any(ValueVisitor::default()).map(Value::into_owned)
+		deserializer
+			.deserialize_newtype_struct(crate::format::VALUE_NEWTYPE_NAME, ValueVisitor::default())
+			.map(Value::into_owned)
 	}
 }
 
+/// Maximum number of elements to pre-allocate for size hints, which can come from untrusted length
+/// prefixes of other formats.
+const MAX_PREALLOCATION: usize = 4096;
+
+/// Create a collection with the capacity of the size hint, but at most [MAX_PREALLOCATION].
+fn with_capacity_hint<T>(hint: Option<usize>) -> VecDeque<T> {
+	hint.map_or_else(VecDeque::new, |hint| VecDeque::with_capacity(hint.min(MAX_PREALLOCATION)))
+}
+
 /// Serde [Visitor] for deserializing a [Value].
 #[derive(Debug, Clone, Copy, Default)]
 struct ValueVisitor<'a>(PhantomData<Value<'a>>);
@@ -539,12 +1183,20 @@ impl<'de> ::serde::de::Visitor<'de> for ValueVisitor<'de> {
 		Ok(Value::Null)
 	}
 
+	#[inline]
+	fn visit_newtype_struct<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+	where
+		D: serde::Deserializer<'de>,
+	{
+		deserializer.deserialize_any(self)
+	}
+
 	#[inline]
 	fn visit_seq<A>(self, mut seq: A) -> Result<Self::Value, A::Error>
 	where
 		A: serde::de::SeqAccess<'de>,
 	{
-		let mut arr = seq.size_hint().map_or_else(VecDeque::new, VecDeque::with_capacity);
+		let mut arr = with_capacity_hint(seq.size_hint());
 
 		while let Some(value) = seq.next_element()? {
 			arr.push_back(value);
@@ -558,23 +1210,63 @@ impl<'de> ::serde::de::Visitor<'de> for ValueVisitor<'de> {
 	where
 		A: serde::de::MapAccess<'de>,
 	{
-		let mut entries = map.size_hint().map_or_else(VecDeque::new, VecDeque::with_capacity);
+		let mut entries = with_capacity_hint(map.size_hint());
 
 		while let Some((key, value)) = map.next_entry()? {
 			entries.push_back((key, value));
 		}
 
+		// Raw float bits, see `VALUE_NEWTYPE_NAME`. Only this crate's deserializers produce maps
+		// with the crate-private names as keys.
+		if let [(Value::String(key), Value::Integer(Integer::Unsigned(bits)))] =
+			entries.make_contiguous()
+		{
+			if key == crate::format::F16_BITS_NEWTYPE_NAME {
+				if let Ok(bits) = u16::try_from(*bits) {
+					return Ok(Value::Float(Float::F16Bits(bits)));
+				}
+			} else if key == crate::format::F128_BITS_NEWTYPE_NAME {
+				return Ok(Value::Float(Float::F128Bits(*bits)));
+			}
+		}
+		#[cfg(feature = "big-integers")]
+		if is_big_integer_entry(&entries) {
+			return big_integer_from_entry(entries).ok_or_else(|| {
+				<A::Error as ::serde::de::Error>::custom("invalid big integer encoding")
+			});
+		}
+
 		Ok(Value::Map(entries))
 	}
 }
 
+/// Whether the map entries are an encoded big integer, see `BIG_INTEGER_NEWTYPE_NAME`.
+#[cfg(feature = "big-integers")]
+fn is_big_integer_entry(entries: &VecDeque<(Value<'_>, Value<'_>)>) -> bool {
+	entries.len() == 1
+		&& entries.front().is_some_and(|(key, value)| {
+			*key == *crate::format::BIG_INTEGER_NEWTYPE_NAME && matches!(value, Value::Bytes(_))
+		})
+}
+
+/// Convert the map entry of an encoded big integer to the value.
+#[cfg(feature = "big-integers")]
+fn big_integer_from_entry<'a>(mut entries: VecDeque<(Value<'a>, Value<'a>)>) -> Option<Value<'a>> {
+	let (_, Value::Bytes(encoded)) = entries.pop_front()? else {
+		return None;
+	};
+	BigInteger::from_encoded(encoded).map(Value::BigInteger)
+}
+
 impl<'a> PartialEq for Value<'a> {
 	fn eq(&self, other: &Self) -> bool {
 		match (self, other) {
 			(Self::Null, Self::Null) => true,
 			(Self::Bool(l0), Self::Bool(r0)) => l0 == r0,
 			(Self::Integer(l0), Self::Integer(r0)) => l0 == r0,
-			(Self::Float(l0), Self::Float(r0)) => l0 == r0,
+			#[cfg(feature = "big-integers")]
+			(Self::BigInteger(l0), Self::BigInteger(r0)) => l0 == r0,
+			(Self::Float(l0), Self::Float(r0)) => l0.total_cmp(r0).is_eq(),
 			(Self::Bytes(l0), Self::Bytes(r0)) => l0 == r0,
 			(Self::String(l0), Self::String(r0)) => l0 == r0,
 			(Self::Array(l0), Self::Array(r0)) => l0 == r0,
@@ -584,6 +1276,254 @@ impl<'a> PartialEq for Value<'a> {
 	}
 }
 
+impl<'a> Eq for Value<'a> {}
+
+impl<'a> PartialOrd for Value<'a> {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+impl<'a> Ord for Value<'a> {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match (self, other) {
+			(Self::Null, Self::Null) => Ordering::Equal,
+			(Self::Bool(l0), Self::Bool(r0)) => l0.cmp(r0),
+			(Self::Integer(l0), Self::Integer(r0)) => l0.cmp(r0),
+			#[cfg(feature = "big-integers")]
+			(Self::BigInteger(l0), Self::BigInteger(r0)) => l0.cmp(r0),
+			// Big integers are out of the range of other integers.
+			#[cfg(feature = "big-integers")]
+			(Self::Integer(_), Self::BigInteger(int)) if int.is_negative() => Ordering::Greater,
+			#[cfg(feature = "big-integers")]
+			(Self::Integer(_), Self::BigInteger(_)) => Ordering::Less,
+			#[cfg(feature = "big-integers")]
+			(Self::BigInteger(int), Self::Integer(_)) if int.is_negative() => Ordering::Less,
+			#[cfg(feature = "big-integers")]
+			(Self::BigInteger(_), Self::Integer(_)) => Ordering::Greater,
+			(Self::Float(l0), Self::Float(r0)) => l0.total_cmp(r0),
+			(Self::Bytes(l0), Self::Bytes(r0)) => l0.cmp(r0),
+			(Self::String(l0), Self::String(r0)) => l0.cmp(r0),
+			(Self::Array(l0), Self::Array(r0)) => l0.cmp(r0),
+			(Self::Map(l0), Self::Map(r0)) => l0.cmp(r0),
+			_ => self.kind_rank().cmp(&other.kind_rank()),
+		}
+	}
+}
+
+impl<'a> Hash for Value<'a> {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.kind_rank().hash(state);
+		match self {
+			Value::Null => {}
+			Value::Bool(b) => b.hash(state),
+			Value::Integer(int) => int.hash(state),
+			#[cfg(feature = "big-integers")]
+			Value::BigInteger(int) => int.hash(state),
+			Value::Float(float) => {
+				float.kind_rank().hash(state);
+				float.canonical_bits().hash(state);
+			}
+			Value::Bytes(bytes) => bytes.hash(state),
+			Value::String(s) => s.hash(state),
+			Value::Array(arr) => arr.hash(state),
+			Value::Map(map) => map.hash(state),
+		}
+	}
+}
+
+impl Integer {
+	/// Return the canonical representation of the integer: Non-negative integers are
+	/// [Integer::Unsigned], negative ones [Integer::Signed].
+	#[must_use]
+	pub const fn normalize(self) -> Self {
+		match self {
+			#[expect(clippy::cast_sign_loss, reason = "Checked to be non-negative")]
+			Integer::Signed(int) if int >= 0 => Integer::Unsigned(int as u128),
+			int => int,
+		}
+	}
+
+	/// Compare the numeric values, regardless of the signedness.
+	#[must_use]
+	pub fn eq_numeric(&self, other: &Self) -> bool {
+		self.normalize() == other.normalize()
+	}
+
+	/// Order by the numeric values, regardless of the signedness.
+	#[must_use]
+	pub fn cmp_numeric(&self, other: &Self) -> Ordering {
+		self.normalize().cmp(&other.normalize())
+	}
+}
+
+/// Implement conversions from and numeric comparisons with the primitive integers.
+macro_rules! impl_integer_primitive {
+	($variant:ident: $($int:ty),*) => {
+		$(
+			impl From<$int> for Integer {
+				fn from(int: $int) -> Self {
+					Integer::$variant(int.into())
+				}
+			}
+
+			impl PartialEq<$int> for Integer {
+				fn eq(&self, other: &$int) -> bool {
+					self.eq_numeric(&Integer::from(*other))
+				}
+			}
+
+			impl PartialOrd<$int> for Integer {
+				fn partial_cmp(&self, other: &$int) -> Option<Ordering> {
+					Some(self.cmp_numeric(&Integer::from(*other)))
+				}
+			}
+		)*
+	};
+}
+
+impl_integer_primitive!(Unsigned: u8, u16, u32, u64, u128);
+impl_integer_primitive!(Signed: i8, i16, i32, i64, i128);
+
+impl From<usize> for Integer {
+	fn from(int: usize) -> Self {
+		Integer::Unsigned(int as u128)
+	}
+}
+
+impl From<isize> for Integer {
+	fn from(int: isize) -> Self {
+		Integer::Signed(int as i128)
+	}
+}
+
+/// Orders integers by their numeric value, signed before unsigned for equal values.
+impl PartialOrd for Integer {
+	fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+		Some(self.cmp(other))
+	}
+}
+
+/// Orders integers by their numeric value, signed before unsigned for equal values.
+impl Ord for Integer {
+	fn cmp(&self, other: &Self) -> Ordering {
+		match (*self, *other) {
+			(Integer::Unsigned(l0), Integer::Unsigned(r0)) => l0.cmp(&r0),
+			(Integer::Signed(l0), Integer::Signed(r0)) => l0.cmp(&r0),
+			(Integer::Signed(l0), Integer::Unsigned(r0)) => match u128::try_from(l0) {
+				Ok(l0) => l0.cmp(&r0).then(Ordering::Less),
+				Err(_) => Ordering::Less,
+			},
+			(Integer::Unsigned(_), Integer::Signed(_)) => other.cmp(self).reverse(),
+		}
+	}
+}
+
+/// Implement [TryFrom] for the primitive integers, failing if the value is out of range.
+macro_rules! impl_integer_try_from {
+	($($int:ty),*) => {
+		$(
+			impl TryFrom<Integer> for $int {
+				type Error = ::core::num::TryFromIntError;
+
+				fn try_from(int: Integer) -> ::core::result::Result<Self, Self::Error> {
+					match int {
+						Integer::Unsigned(int) => int.try_into(),
+						Integer::Signed(int) => int.try_into(),
+					}
+				}
+			}
+		)*
+	};
+}
+
+impl_integer_try_from!(u8, u16, u32, u64, usize, i8, i16, i32, i64, isize);
+
+impl TryFrom<Integer> for u128 {
+	type Error = ::core::num::TryFromIntError;
+
+	fn try_from(int: Integer) -> ::core::result::Result<Self, Self::Error> {
+		match int {
+			Integer::Unsigned(int) => Ok(int),
+			Integer::Signed(int) => int.try_into(),
+		}
+	}
+}
+
+impl TryFrom<Integer> for i128 {
+	type Error = ::core::num::TryFromIntError;
+
+	fn try_from(int: Integer) -> ::core::result::Result<Self, Self::Error> {
+		match int {
+			Integer::Unsigned(int) => int.try_into(),
+			Integer::Signed(int) => Ok(int),
+		}
+	}
+}
+
+impl Float {
+	/// Compare floats by a total order: By numeric value first, where NaN is equal to itself and
+	/// ordered after all numbers and `-0.0` equals `0.0`. Floats of different precision are only
+	/// equal if their raw values are equal as well.
+	#[must_use]
+	pub fn total_cmp(&self, other: &Self) -> Ordering {
+		let (l0, r0) = (self.to_f64(), other.to_f64());
+		let numeric = match (l0.is_nan(), r0.is_nan()) {
+			(true, true) => Ordering::Equal,
+			(true, false) => Ordering::Greater,
+			(false, true) => Ordering::Less,
+			(false, false) => l0.partial_cmp(&r0).unwrap_or(Ordering::Equal),
+		};
+		numeric
+			.then_with(|| self.kind_rank().cmp(&other.kind_rank()))
+			.then_with(|| self.canonical_bits().cmp(&other.canonical_bits()))
+	}
+
+	/// The (possibly lossy) `f64` value of this float.
+	fn to_f64(self) -> f64 {
+		match self {
+			Float::F32(float) => f64::from(float),
+			Float::F64(float) => float,
+			Float::F16Bits(bits) => f64::from(crate::format::f16_bits_to_f32(bits)),
+			Float::F128Bits(bits) => crate::format::f128_bits_to_f64(bits),
+		}
+	}
+
+	/// The raw bits of this float, with all NaNs and zeros mapped to the same value.
+	fn canonical_bits(self) -> u128 {
+		let float = self.to_f64();
+		if float.is_nan() || self.is_zero() {
+			return 0;
+		}
+		match self {
+			Float::F32(float) => float.to_bits().into(),
+			Float::F64(float) => float.to_bits().into(),
+			Float::F16Bits(bits) => bits.into(),
+			Float::F128Bits(bits) => bits,
+		}
+	}
+
+	/// Whether this float is exactly zero (positive or negative).
+	fn is_zero(self) -> bool {
+		match self {
+			Float::F32(float) => float == 0.0,
+			Float::F64(float) => float == 0.0,
+			Float::F16Bits(bits) => bits & 0x7FFF == 0,
+			Float::F128Bits(bits) => bits & !(1 << 127) == 0,
+		}
+	}
+
+	/// The rank of the float precision in the total order.
+	const fn kind_rank(self) -> u8 {
+		match self {
+			Float::F16Bits(_) => 0,
+			Float::F32(_) => 1,
+			Float::F64(_) => 2,
+			Float::F128Bits(_) => 3,
+		}
+	}
+}
+
 impl<'a> PartialEq<bool> for Value<'a> {
 	fn eq(&self, other: &bool) -> bool {
 		match self {
@@ -596,7 +1536,7 @@ impl<'a> PartialEq<bool> for Value<'a> {
 impl<'a> PartialEq<u128> for Value<'a> {
 	fn eq(&self, other: &u128) -> bool {
 		match self {
-			Value::Integer(Integer::Unsigned(int)) => int == other,
+			Value::Integer(int) => int == other,
 			_ => false,
 		}
 	}
@@ -605,7 +1545,7 @@ impl<'a> PartialEq<u128> for Value<'a> {
 impl<'a> PartialEq<i128> for Value<'a> {
 	fn eq(&self, other: &i128) -> bool {
 		match self {
-			Value::Integer(Integer::Signed(int)) => int == other,
+			Value::Integer(int) => int == other,
 			_ => false,
 		}
 	}
@@ -616,6 +1556,10 @@ impl<'a> PartialEq<f32> for Value<'a> {
 		match self {
 			Value::Float(Float::F32(float)) => float == other,
 			Value::Float(Float::F64(float)) => *float == f64::from(*other),
+			Value::Float(Float::F16Bits(bits)) => crate::format::f16_bits_to_f32(*bits) == *other,
+			Value::Float(Float::F128Bits(bits)) => {
+				crate::format::f128_bits_to_f64(*bits) == f64::from(*other)
+			}
 			_ => false,
 		}
 	}
@@ -626,6 +1570,10 @@ impl<'a> PartialEq<f64> for Value<'a> {
 		match self {
 			Value::Float(Float::F32(float)) => f64::from(*float) == *other,
 			Value::Float(Float::F64(float)) => float == other,
+			Value::Float(Float::F16Bits(bits)) => {
+				f64::from(crate::format::f16_bits_to_f32(*bits)) == *other
+			}
+			Value::Float(Float::F128Bits(bits)) => crate::format::f128_bits_to_f64(*bits) == *other,
 			_ => false,
 		}
 	}
@@ -649,6 +1597,68 @@ impl<'a> PartialEq<str> for Value<'a> {
 	}
 }
 
+/// Null value to return references to for missing values.
+static NULL: Value<'static> = Value::Null;
+
+/// Index into a [Value::Map] by string key. Returns [Value::Null] if the key does not exist or the
+/// value is not a map, like `serde_json`.
+impl<'a> Index<&str> for Value<'a> {
+	type Output = Value<'a>;
+
+	fn index(&self, key: &str) -> &Self::Output {
+		self.get(key).unwrap_or(&NULL)
+	}
+}
+
+/// Index into a [Value::Array]. Returns [Value::Null] if the index is out of bounds or the value is
+/// not an array, like `serde_json`.
+impl<'a> Index<usize> for Value<'a> {
+	type Output = Value<'a>;
+
+	fn index(&self, index: usize) -> &Self::Output {
+		self.get_index(index).unwrap_or(&NULL)
+	}
+}
+
+/// Mutably index into a [Value::Map] by string key, like `serde_json`. A missing key is inserted
+/// with [Value::Null] and a [Value::Null] is turned into an empty map first.
+///
+/// # Panics
+/// If the value is neither a map nor null.
+impl<'a> IndexMut<&str> for Value<'a> {
+	#[expect(clippy::panic, reason = "Documented panic, like `serde_json`")]
+	fn index_mut(&mut self, key: &str) -> &mut Self::Output {
+		if matches!(self, Value::Null) {
+			*self = Value::Map(VecDeque::new());
+		}
+		let Value::Map(map) = self else {
+			panic!("Cannot index into non-map value with a string key");
+		};
+
+		let position = map.iter().position(|(k, _)| k == key).unwrap_or_else(|| {
+			map.push_back((Value::String(Cow::Owned(key.to_owned())), Value::Null));
+			map.len() - 1
+		});
+		&mut map[position].1
+	}
+}
+
+/// Mutably index into a [Value::Array], like `serde_json`.
+///
+/// # Panics
+/// If the value is not an array or the index is out of bounds.
+impl<'a> IndexMut<usize> for Value<'a> {
+	#[expect(clippy::panic, reason = "Documented panic, like `serde_json`")]
+	fn index_mut(&mut self, index: usize) -> &mut Self::Output {
+		let Value::Array(arr) = self else {
+			panic!("Cannot index into non-array value with an index");
+		};
+		let len = arr.len();
+		arr.get_mut(index)
+			.unwrap_or_else(|| panic!("Index {index} out of bounds for array of length {len}"))
+	}
+}
+
 impl<'a> From<Option<Value<'a>>> for Value<'a> {
 	#[inline]
 	fn from(value: Option<Value<'a>>) -> Self {
@@ -782,6 +1792,13 @@ impl<'a> From<Vec<u8>> for Value<'a> {
 	}
 }
 
+impl<'a> From<&'a Vec<u8>> for Value<'a> {
+	#[inline]
+	fn from(value: &'a Vec<u8>) -> Self {
+		Value::Bytes(Cow::Borrowed(value))
+	}
+}
+
 impl<'a> From<Cow<'a, [u8]>> for Value<'a> {
 	#[inline]
 	fn from(value: Cow<'a, [u8]>) -> Self {
@@ -789,6 +1806,15 @@ impl<'a> From<Cow<'a, [u8]>> for Value<'a> {
 	}
 }
 
+/// **uuid**: UUIDs are converted to [Value::Bytes] of 16 bytes.
+#[cfg(feature = "uuid")]
+impl<'a> From<::uuid::Uuid> for Value<'a> {
+	#[inline]
+	fn from(value: ::uuid::Uuid) -> Self {
+		Value::Bytes(Cow::Owned(value.as_bytes().to_vec()))
+	}
+}
+
 impl<'a> From<&'a str> for Value<'a> {
 	#[inline]
 	fn from(value: &'a str) -> Self {
@@ -803,6 +1829,13 @@ impl<'a> From<String> for Value<'a> {
 	}
 }
 
+impl<'a> From<&'a String> for Value<'a> {
+	#[inline]
+	fn from(value: &'a String) -> Self {
+		Value::String(Cow::Borrowed(value))
+	}
+}
+
 impl<'a> From<Cow<'a, str>> for Value<'a> {
 	#[inline]
 	fn from(value: Cow<'a, str>) -> Self {
@@ -857,19 +1890,19 @@ impl<I, T> AllIteratorTrait<T> for I where
 
 /// Generic iterator.
 #[derive(Debug)]
-pub struct Iter<T>(Box<dyn AllIteratorTrait<T> + Send + Sync>);
+pub struct Iter<'a, T>(Box<dyn AllIteratorTrait<T> + Send + Sync + 'a>);
 
-impl<T> Iter<T> {
+impl<'a, T> Iter<'a, T> {
 	/// Create a new generic iterator.
 	fn new<I>(iter: I) -> Self
 	where
-		I: AllIteratorTrait<T> + Send + Sync + 'static,
+		I: AllIteratorTrait<T> + Send + Sync + 'a,
 	{
 		Self(Box::new(iter))
 	}
 }
 
-impl<T> Iterator for Iter<T> {
+impl<'a, T> Iterator for Iter<'a, T> {
 	type Item = T;
 
 	fn next(&mut self) -> Option<Self::Item> {
@@ -899,13 +1932,13 @@ impl<T> Iterator for Iter<T> {
 	}
 }
 
-impl<T> ExactSizeIterator for Iter<T> {
+impl<'a, T> ExactSizeIterator for Iter<'a, T> {
 	fn len(&self) -> usize {
 		self.0.len()
 	}
 }
 
-impl<T> DoubleEndedIterator for Iter<T> {
+impl<'a, T> DoubleEndedIterator for Iter<'a, T> {
 	fn next_back(&mut self) -> Option<Self::Item> {
 		self.0.next_back()
 	}
@@ -915,6 +1948,5 @@ impl<T> DoubleEndedIterator for Iter<T> {
 	}
 }
 
-
 #[cfg(test)]
 mod tests;
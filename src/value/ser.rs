@@ -7,20 +7,41 @@
 use ::alloc::{borrow::ToOwned, vec};
 
 use super::*;
-use crate::{Error, Result};
+use crate::{ser::FloatBits, Error, Result};
 
 /// Serializer to serialize any type into a [Value].
 #[derive(Debug, Clone, Copy)]
 pub struct ValueSerializer {
 	/// Whether to use the `use_indices` format.
 	use_indices: bool,
+	/// Whether to report being human-readable to the serialized types.
+	human_readable: bool,
+	/// Names of the tag fields of internally tagged enums, which keep their name as key.
+	tag_fields: &'static [&'static str],
 }
 
 impl ValueSerializer {
 	/// Create a new serializer.
 	#[must_use]
 	pub const fn new(use_indices: bool) -> Self {
-		Self { use_indices }
+		Self { use_indices, human_readable: false, tag_fields: &[] }
+	}
+
+	/// Set whether types are told that the format is human-readable, so that they choose their
+	/// textual representation. Disabled by default, like the binary format.
+	#[must_use]
+	pub const fn human_readable(mut self, human_readable: bool) -> Self {
+		self.human_readable = human_readable;
+		self
+	}
+
+	/// Set the names of the tag fields of internally tagged enums, which keep their name as key
+	/// with `use_indices` and do not count towards the field indices. See
+	/// [Config::tag_fields](crate::Config::tag_fields).
+	#[must_use]
+	pub const fn tag_fields(mut self, tag_fields: &'static [&'static str]) -> Self {
+		self.tag_fields = tag_fields;
+		self
 	}
 }
 
@@ -37,7 +58,7 @@ impl ::serde::ser::Serializer for ValueSerializer {
 
 	#[inline]
 	fn is_human_readable(&self) -> bool {
-		true
+		self.human_readable
 	}
 
 	#[inline]
@@ -184,13 +205,26 @@ impl ::serde::ser::Serializer for ValueSerializer {
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self, value)))]
 	fn serialize_newtype_struct<T>(
 		self,
-		_name: &'static str,
+		name: &'static str,
 		value: &T,
 	) -> Result<Self::Ok, Self::Error>
 	where
 		T: ?Sized + serde::Serialize,
 	{
-		value.serialize(self)
+		#[cfg(feature = "big-integers")]
+		if name == crate::format::BIG_INTEGER_NEWTYPE_NAME {
+			if let Value::Bytes(encoded) = value.serialize(self)? {
+				return super::BigInteger::from_encoded(encoded)
+					.map(Value::BigInteger)
+					.ok_or_else(|| ::serde::ser::Error::custom("invalid big integer encoding"));
+			}
+		}
+
+		match crate::ser::float_bits(name, value) {
+			Some(FloatBits::F16(bits)) => Ok(Value::Float(Float::F16Bits(bits))),
+			Some(FloatBits::F128(bits)) => Ok(Value::Float(Float::F128Bits(bits))),
+			None => value.serialize(self),
+		}
 	}
 
 	#[inline]
@@ -217,14 +251,14 @@ impl ::serde::ser::Serializer for ValueSerializer {
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
-		let arr = len.map_or_else(VecDeque::new, VecDeque::with_capacity);
+		let arr = with_capacity_hint(len);
 		Ok(ValueSeqSerializer { serializer: self, arr })
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
-		let arr = VecDeque::with_capacity(len);
+		let arr = with_capacity_hint(Some(len));
 		Ok(ValueSeqSerializer { serializer: self, arr })
 	}
 
@@ -235,7 +269,7 @@ impl ::serde::ser::Serializer for ValueSerializer {
 		_name: &'static str,
 		len: usize,
 	) -> Result<Self::SerializeTupleStruct, Self::Error> {
-		let arr = VecDeque::with_capacity(len);
+		let arr = with_capacity_hint(Some(len));
 		Ok(ValueSeqSerializer { serializer: self, arr })
 	}
 
@@ -253,13 +287,13 @@ impl ::serde::ser::Serializer for ValueSerializer {
 		} else {
 			Value::String(Cow::Borrowed(variant))
 		};
-		Ok(ValueSeqVariantSerializer { serializer: self, key, arr: VecDeque::with_capacity(len) })
+		Ok(ValueSeqVariantSerializer { serializer: self, key, arr: with_capacity_hint(Some(len)) })
 	}
 
 	#[inline]
 	#[cfg_attr(feature = "tracing", ::tracing::instrument(skip(self)))]
 	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
-		let map = len.map_or_else(VecDeque::new, VecDeque::with_capacity);
+		let map = with_capacity_hint(len);
 		Ok(ValueMapSerializer { serializer: self, map, field_index: 0 })
 	}
 
@@ -270,7 +304,7 @@ impl ::serde::ser::Serializer for ValueSerializer {
 		_name: &'static str,
 		len: usize,
 	) -> Result<Self::SerializeStruct, Self::Error> {
-		let map = VecDeque::with_capacity(len);
+		let map = with_capacity_hint(Some(len));
 		Ok(ValueMapSerializer { serializer: self, map, field_index: 0 })
 	}
 
@@ -291,7 +325,7 @@ impl ::serde::ser::Serializer for ValueSerializer {
 		Ok(ValueMapVariantSerializer {
 			serializer: self,
 			key,
-			map: VecDeque::with_capacity(len),
+			map: with_capacity_hint(Some(len)),
 			field_index: 0,
 		})
 	}
@@ -401,9 +435,12 @@ impl ::serde::ser::SerializeMap for ValueMapSerializer {
 	where
 		T: ?Sized + Serialize,
 	{
-		#![expect(clippy::expect_used, reason = "Serde requirement")]
-		self.map.back_mut().expect("serialize_key is called before serialize_value").1 =
-			value.serialize(self.serializer)?;
+		let entry = self.map.back_mut().ok_or_else(|| {
+			<Error as ::serde::ser::Error>::custom(
+				"serialize_key must be called before serialize_value",
+			)
+		})?;
+		entry.1 = value.serialize(self.serializer)?;
 		Ok(())
 	}
 
@@ -424,10 +461,16 @@ impl ::serde::ser::SerializeStruct for ValueMapSerializer {
 	where
 		T: ?Sized + Serialize,
 	{
-		let key = if self.serializer.use_indices {
-			Value::Integer(Integer::Unsigned(u128::from(self.field_index)))
-		} else {
+		let first_field = self.field_index == 0 && self.map.is_empty();
+		let key = if !self.serializer.use_indices {
 			Value::String(Cow::Borrowed(key))
+		} else if first_field && self.serializer.tag_fields.contains(&key) {
+			// Serde finds the tag by name and deserializes the remaining fields as the content.
+			let value = value.serialize(self.serializer)?;
+			self.map.push_back((Value::String(Cow::Borrowed(key)), value));
+			return Ok(());
+		} else {
+			Value::Integer(Integer::Unsigned(u128::from(self.field_index)))
 		};
 		self.field_index += 1;
 		let value = value.serialize(self.serializer)?;
@@ -2,7 +2,7 @@
 #![allow(clippy::unwrap_used, clippy::expect_used, clippy::print_stdout, reason = "Tests")]
 #![allow(clippy::too_many_lines, reason = "Byte lists and such :P")]
 
-use ::alloc::{borrow::ToOwned, vec};
+use ::alloc::{borrow::ToOwned, format, string::ToString, vec};
 use ::core::fmt::Debug;
 use ::serde::de::DeserializeOwned;
 use ::serde_bytes::ByteBuf;
@@ -78,7 +78,6 @@ where
 	assert_eq!(serialized, bytes);
 }
 
-
 #[test]
 fn test_unit() {
 	init_tracing();
@@ -210,6 +209,83 @@ fn test_floats() {
 	);
 }
 
+#[test]
+fn test_raw_floats() {
+	init_tracing();
+	let f16_one = [Type::Float16.into(), 0x00, 0x3C];
+	let mut f128_one = [0; 17];
+	f128_one[0] = Type::Float128.into();
+	f128_one[15 ..].copy_from_slice(&[0xFF, 0x3F]);
+
+	test_deser::<Value<'_>>(&f16_one, &Value::Float(Float::F16Bits(0x3C00)));
+	test_deser_with_indices::<Value<'_>>(&f16_one, &Value::Float(Float::F16Bits(0x3C00)));
+	test_deser::<Value<'_>>(&f128_one, &Value::Float(Float::F128Bits(0x3FFF << 112)));
+	test_deser_with_indices::<Value<'_>>(&f128_one, &Value::Float(Float::F128Bits(0x3FFF << 112)));
+
+	let mut bytes = vec![Type::SeqStart.into()];
+	bytes.extend_from_slice(&f16_one);
+	bytes.extend_from_slice(&f128_one);
+	bytes.extend_from_slice(&[Type::Float32.into(), 0x00, 0x00, 0x80, 0x3F]);
+	bytes.push(Type::SeqEnd.into());
+	let value = Value::Array(
+		[
+			Value::Float(Float::F16Bits(0x3C00)),
+			Value::Float(Float::F128Bits(0x3FFF << 112)),
+			Value::Float(Float::F32(1.0)),
+		]
+		.into(),
+	);
+	test_deser::<Value<'_>>(&bytes, &value);
+	test_deser_with_indices::<Value<'_>>(&bytes, &value);
+	let floats: Vec<f64> = crate::from_value(value.clone()).unwrap();
+	assert_eq!(floats, [1.0, 1.0, 1.0]);
+
+	assert_eq!(Value::Float(Float::F16Bits(0xC100)), -2.5_f32);
+	assert_eq!(Value::Float(Float::F16Bits(0x0001)), 2.0_f64.powi(-24));
+	assert_eq!(Value::Float(Float::F128Bits(0xC000_8000 << 96)), -3.0_f64);
+	assert_eq!(Value::Float(Float::F128Bits(0x3FFF << 112 | 1)), 1.0_f64);
+	assert!(Value::Float(Float::F16Bits(0x7E00)).as_float().is_some());
+	assert_eq!(value.to_string(), "[1, 1, 1]");
+
+	// Other formats get plain floats, or the bits if a `Float128` does not fit into an `f64`.
+	assert_eq!(::serde_json::to_string(&value).unwrap(), "[1.0,1.0,1.0]");
+	let inexact = Value::Float(Float::F128Bits(0x3FFF << 112 | 1));
+	assert_eq!(::serde_json::to_string(&inexact).unwrap(), (0x3FFF_u128 << 112 | 1).to_string());
+
+	// This crate's serializers get the exact bits back through the portable payloads.
+	for bits in [0x3C00, 0x0001, 0x8000, 0x7C00, 0xFE01, 0x03FF, 0x7BFF] {
+		let value = Value::Float(Float::F16Bits(bits));
+		assert_eq!(crate::to_value(&value).unwrap(), value);
+		assert_eq!(crate::from_slice::<Value<'_>>(&crate::to_vec(&value).unwrap()).unwrap(), value);
+	}
+	for bits in [0x3FFF << 112 | 1, 0xC000_8000 << 96, 0x3C01 << 112, 0x7FFF << 112 | 1 << 111] {
+		let value = Value::Float(Float::F128Bits(bits));
+		assert_eq!(crate::to_value(&value).unwrap(), value);
+		assert_eq!(crate::from_slice::<Value<'_>>(&crate::to_vec(&value).unwrap()).unwrap(), value);
+	}
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_half_f16() {
+	use ::half::f16;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Half(#[serde(with = "crate::helpers::f16_as_float16")] f16);
+
+	init_tracing();
+	let half = Half(f16::from_f32(-2.5));
+	test_serde(&half, &Value::Float(Float::F16Bits(0xC100)));
+	test_serde_with_indices(&half, &Value::Float(Float::F16Bits(0xC100)));
+	test_deser::<Half>(&[Type::Float16.into(), 0x00, 0xC1], &Value::Float(Float::F16Bits(0xC100)));
+	let value: Half = crate::from_value(Value::Float(Float::F32(-2.5))).unwrap();
+	assert_eq!(value, half);
+	// Without the helper, the bits are kept as integer.
+	test_serde(&f16::from_f32(-2.5), &Value::from(0xC100_u16));
+	let value: Half = crate::from_value(Value::from(0xC100_u16)).unwrap();
+	assert_eq!(value, half);
+}
+
 #[test]
 fn test_bytes() {
 	init_tracing();
@@ -831,3 +907,1197 @@ fn test_enums_with_discriminants() {
 	test_deser_with_indices::<Enum>(&[Type::UnsignedInt.into(), 0], &val_a_index);
 	test_deser_with_indices::<Enum>(&[Type::UnsignedInt.into(), 1], &val_b_index);
 }
+
+#[test]
+fn test_accessors() {
+	#[derive(Debug, Serialize)]
+	struct Server {
+		host: &'static str,
+		ports: [u16; 2],
+	}
+	#[derive(Debug, Serialize)]
+	struct Settings {
+		server: Server,
+		#[serde(rename = "a/b~c")]
+		escaped: bool,
+	}
+
+	let settings =
+		Settings { server: Server { host: "localhost", ports: [80, 443] }, escaped: true };
+	let mut value = crate::to_value(&settings).unwrap();
+
+	assert_eq!(value.get("server").and_then(|server| server.get("host")).unwrap(), "localhost");
+	assert!(value.get("missing").is_none());
+	assert!(value.get_index(0).is_none());
+	assert_eq!(value["server"]["ports"][1], 443_u128);
+	assert_eq!(value["server"]["ports"].get_index(0).unwrap(), &80_u128);
+	assert_eq!(value["server"]["missing"][5], Value::Null);
+	assert_eq!(value["server"]["host"]["not a map"], Value::Null);
+
+	assert_eq!(value.pointer("").unwrap(), &value);
+	assert_eq!(value.pointer("/server/ports/0").unwrap(), &80_u128);
+	assert_eq!(value.pointer("/a~1b~0c").unwrap(), &true);
+	assert!(value.pointer("/server/ports/2").is_none());
+	assert!(value.pointer("/server/host/0").is_none());
+	assert!(value.pointer("server").is_none());
+
+	// Patch values in place.
+	*value.get_mut("server").unwrap().get_mut("host").unwrap() = Value::from("example.com");
+	*value.pointer_mut("/server/ports/0").unwrap() = Value::from(8080_u16);
+	*value["server"]["ports"].get_index_mut(1).unwrap() = Value::from(8443_u16);
+	value["server"]["tls"] = Value::from(true);
+	value["new"]["nested"] = Value::from("inserted");
+	value["server"]["ports"][0] = Value::from(8000_u16);
+	assert_eq!(value["server"]["host"], *"example.com");
+	assert_eq!(value["server"]["ports"][0], 8000_u128);
+	assert_eq!(value["server"]["ports"][1], 8443_u128);
+	assert_eq!(value["server"]["tls"], true);
+	assert_eq!(value.pointer("/new/nested").unwrap(), "inserted");
+
+	// Integer keys from `use_indices`.
+	let config = Config { use_indices: true, ..Default::default() };
+	let value = crate::to_value_with_config(&settings, config).unwrap();
+	assert_eq!(value.pointer("/0/1/1").unwrap(), &443_u128);
+	assert!(value.get("server").is_none());
+}
+
+#[test]
+#[should_panic = "out of bounds"]
+fn test_index_mut_out_of_bounds() {
+	let mut value = Value::from_iter([1_u8, 2]);
+	value[2] = Value::Null;
+}
+
+#[test]
+fn test_mutation_helpers() {
+	let mut value = Value::Null;
+	assert_eq!(value.map_insert("a", 1_u8), None);
+	assert_eq!(value.map_insert("b", 2_u8), None);
+	assert_eq!(value.map_insert(3_u8, "c"), None);
+	// Replacing keeps the position.
+	assert_eq!(value.map_insert("a", 10_u8), Some(Value::from(1_u8)));
+	assert_eq!(
+		value,
+		Value::from_iter([
+			(Value::from("a"), Value::from(10_u8)),
+			(Value::from("b"), Value::from(2_u8)),
+			(Value::from(3_u8), Value::from("c")),
+		])
+	);
+	let bytes = crate::to_vec(&value).unwrap();
+	let parsed: OwnedValue = crate::from_slice(&bytes).unwrap();
+	assert_eq!(*parsed, value);
+
+	assert_eq!(value.map_remove("b"), Some(Value::from(2_u8)));
+	assert_eq!(value.map_remove("b"), None);
+	assert_eq!(value.map_remove(&Value::from(3_u8)), Some(Value::from("c")));
+	value.as_map_mut().unwrap().push_front((Value::from("z"), Value::Null));
+	let keys: Vec<_> = value.as_map().unwrap().iter().map(|(key, _)| key.as_string()).collect();
+	assert_eq!(keys, [Some("z"), Some("a")]);
+	assert!(value.as_array_mut().is_none());
+	assert_eq!(Value::from(1_u8).map_remove("a"), None);
+
+	let mut value = Value::Null;
+	value.array_push(2_u8);
+	value.array_push("end");
+	value.array_insert(0, 1_u8);
+	value.array_insert(3, Value::Null);
+	value.as_array_mut().unwrap().pop_back();
+	assert_eq!(value, Value::from_iter([Value::from(1_u8), Value::from(2_u8), Value::from("end")]));
+	assert!(value.as_map_mut().is_none());
+
+	// Also available on owned values.
+	let mut owned = OwnedValue::new(Value::Null);
+	owned.array_push(true);
+	assert_eq!(owned[0], true);
+}
+
+#[test]
+#[should_panic = "non-map"]
+fn test_map_insert_into_non_map() {
+	let mut value = Value::from(1_u8);
+	value.map_insert("a", 1_u8);
+}
+
+#[test]
+fn test_total_order() {
+	use ::alloc::collections::{BTreeMap, BTreeSet};
+
+	let ordered = [
+		Value::Null,
+		Value::from(false),
+		Value::from(true),
+		Value::from(-5_i8),
+		Value::from(1_i8),
+		Value::from(1_u8),
+		Value::from(2_i8),
+		Value::from(u128::MAX),
+		Value::from(f64::NEG_INFINITY),
+		Value::Float(Float::F16Bits(0x3C00)),
+		Value::from(1.0_f32),
+		Value::from(1.0_f64),
+		Value::Float(Float::F128Bits(0x3FFF_u128 << 112)),
+		Value::from(f64::INFINITY),
+		Value::from(f32::NAN),
+		Value::from(f64::NAN),
+		Value::from(b"a".as_slice()),
+		Value::from(""),
+		Value::from("a"),
+		Value::from("b"),
+		Value::from_iter([1_u8]),
+		Value::from_iter([1_u8, 0]),
+		Value::from_iter([2_u8]),
+		Value::from_iter([("a", 1_u8)]),
+		Value::from_iter([("a", 2_u8)]),
+	];
+	for (i, a) in ordered.iter().enumerate() {
+		for (j, b) in ordered.iter().enumerate() {
+			assert_eq!(a.cmp(b), i.cmp(&j), "{a:?} vs {b:?}");
+			assert_eq!(a == b, i == j, "{a:?} vs {b:?}");
+		}
+	}
+
+	// NaN equals itself and zeros are equal.
+	assert_eq!(Value::from(f64::NAN), Value::from(-f64::NAN));
+	assert_eq!(Value::from(0.0_f64), Value::from(-0.0_f64));
+	#[cfg(feature = "std")]
+	{
+		let set: ::std::collections::HashSet<Value<'_>> =
+			[Value::from(f64::NAN), Value::from(-f64::NAN), Value::from(0.0), Value::from(-0.0)]
+				.into_iter()
+				.collect();
+		assert_eq!(set.len(), 2);
+	}
+
+	let set: BTreeSet<Value<'_>> = ordered.iter().rev().cloned().collect();
+	assert!(set.iter().eq(ordered.iter()));
+	let mut map = BTreeMap::new();
+	map.insert(OwnedValue::new(Value::from("key")), 1);
+	assert_eq!(map.get(&OwnedValue::new(Value::from("key"))), Some(&1));
+}
+
+#[test]
+fn test_sort_maps() {
+	let mut a: Value<'_> = Value::from_iter([
+		(Value::from("b"), Value::from_iter([("y", 1_u8), ("x", 2_u8)])),
+		(Value::from("a"), Value::from_iter([Value::from_iter([("d", 3_u8), ("c", 4_u8)])])),
+	]);
+	let mut b: Value<'_> = Value::from_iter([
+		(Value::from("a"), Value::from_iter([Value::from_iter([("c", 4_u8), ("d", 3_u8)])])),
+		(Value::from("b"), Value::from_iter([("x", 2_u8), ("y", 1_u8)])),
+	]);
+	assert_ne!(a, b);
+	assert_ne!(crate::to_vec(&a).unwrap(), crate::to_vec(&b).unwrap());
+
+	a.sort_maps();
+	b.sort_maps();
+	assert_eq!(a, b);
+	assert_eq!(crate::to_vec(&a).unwrap(), crate::to_vec(&b).unwrap());
+	assert_eq!(a.pointer("/a/0").unwrap().as_map().unwrap().front().unwrap().0, *"c");
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_conversion_rules() {
+	use ::serde_json::json;
+
+	use super::json::{JsonBytes, JsonLargeIntegers, JsonOptions};
+
+	let value = OwnedValue::from(json!({"a": [1, -1, 1.5, "s", null, true]}));
+	assert_eq!(value["a"][0], 1_u128);
+	assert_eq!(value["a"][1], -1_i128);
+	assert_eq!(value["a"][2], 1.5_f64);
+
+	let bytes = Value::from(b"hello".as_slice());
+	assert_eq!(::serde_json::Value::try_from(&bytes).unwrap(), json!([104, 101, 108, 108, 111]));
+	let options = JsonOptions { bytes: JsonBytes::Base64, ..Default::default() };
+	assert_eq!(bytes.to_json_with(options).unwrap(), json!("aGVsbG8="));
+	for (input, expected) in [("", ""), ("f", "Zg=="), ("fo", "Zm8="), ("foo", "Zm9v")] {
+		let value = Value::from(input.as_bytes());
+		assert_eq!(value.to_json_with(options).unwrap(), json!(expected));
+	}
+
+	let large = Value::from(u128::from(u64::MAX) + 1);
+	assert!(matches!(
+		::serde_json::Value::try_from(&large),
+		Err(crate::Error::NotJsonCompatible(_))
+	));
+	let options = JsonOptions { large_integers: JsonLargeIntegers::String, ..Default::default() };
+	assert_eq!(large.to_json_with(options).unwrap(), json!("18446744073709551616"));
+	let small = Value::from(i128::from(i64::MIN) - 1);
+	assert_eq!(small.to_json_with(options).unwrap(), json!("-9223372036854775809"));
+	assert_eq!(::serde_json::Value::try_from(Value::from(u64::MAX)).unwrap(), json!(u64::MAX));
+
+	assert_eq!(::serde_json::Value::try_from(Value::from(f64::NAN)).unwrap(), json!(null));
+	assert_eq!(
+		::serde_json::Value::try_from(Value::Float(Float::F16Bits(0x3C00))).unwrap(),
+		json!(1.0)
+	);
+
+	let map = Value::from_iter([(Value::from(1_u8), Value::from(true))]);
+	assert_eq!(::serde_json::Value::try_from(&map).unwrap(), json!({"1": true}));
+	let map = Value::from_iter([(Value::Null, Value::from(true))]);
+	assert!(::serde_json::Value::try_from(&map).is_err());
+}
+
+#[cfg(feature = "uuid")]
+#[test]
+fn test_uuid_conversion() {
+	let uuid = ::uuid::Uuid::from_u128(0x67E5_5044_10B1_426F_9247_BB68_0E5F_E0C8);
+	let value = Value::from(uuid);
+	assert_eq!(value, Value::Bytes(Cow::Borrowed(uuid.as_bytes())));
+	assert_eq!(value.as_uuid(), Some(uuid));
+	assert_eq!(Value::from("67e55044-10b1-426f-9247-bb680e5fe0c8").as_uuid(), Some(uuid));
+	assert_eq!(Value::from(&[1_u8, 2, 3][..]).as_uuid(), None);
+	assert_eq!(Value::from("not a uuid").as_uuid(), None);
+	assert_eq!(Value::from(5_u8).as_uuid(), None);
+}
+
+#[test]
+fn test_human_readable_uuid() {
+	let uuid = ::uuid::Uuid::from_u128(0x67E5_5044_10B1_426F_9247_BB68_0E5F_E0C8);
+
+	let direct = crate::to_vec(&uuid).unwrap();
+	let via_value = crate::to_vec(&crate::to_value(&uuid).unwrap()).unwrap();
+	assert_eq!(direct, via_value);
+
+	for human_readable in [false, true] {
+		let config = Config { human_readable: Some(human_readable), ..Default::default() };
+		let direct = crate::to_vec_with_config(&uuid, config).unwrap();
+		let value = crate::to_value_with_config(&uuid, config).unwrap();
+		let via_value = crate::to_vec_with_config(&value, config).unwrap();
+		assert_eq!(direct, via_value);
+
+		let parsed: ::uuid::Uuid = crate::from_slice_with_config(&direct, config).unwrap();
+		assert_eq!(parsed, uuid);
+		let parsed: ::uuid::Uuid = crate::from_value_with_config(value, config).unwrap();
+		assert_eq!(parsed, uuid);
+	}
+}
+
+#[test]
+fn test_from_value_with_indices() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Struct {
+		a: bool,
+		b: u8,
+	}
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	enum Enum {
+		Unit,
+		Tuple(bool, u8),
+		Struct { a: bool, b: u8 },
+	}
+
+	let config = Config { use_indices: true, ..Default::default() };
+	test_serde_with_indices(
+		&Struct { a: true, b: 5 },
+		&Value::from_iter([(0_u32, Value::from(true)), (1_u32, Value::from(5_u8))]),
+	);
+	test_serde_with_indices(&Enum::Unit, &Value::from(0_u32));
+	test_serde_with_indices(
+		&Enum::Tuple(false, 1),
+		&Value::from_iter([(1_u32, Value::Array([false.into(), 1_u8.into()].into()))]),
+	);
+	test_serde_with_indices(
+		&Enum::Struct { a: true, b: 2 },
+		&Value::from_iter([(
+			2_u32,
+			Value::from_iter([(0_u32, Value::from(true)), (1_u32, Value::from(2_u8))]),
+		)]),
+	);
+
+	let too_large = Value::from(u64::from(u32::MAX) + 1);
+	assert!(crate::from_value_with_config::<Enum>(too_large.clone(), config).is_err());
+	let bytes = crate::to_vec(&too_large).unwrap();
+	assert!(crate::from_slice_with_config::<Enum>(&bytes, config).is_err());
+}
+
+#[test]
+fn test_index_identifiers() {
+	/// Identifier that only accepts `u32` indices, like a hand-written field visitor.
+	#[derive(Debug, PartialEq)]
+	struct Index(u32);
+
+	impl<'de> Deserialize<'de> for Index {
+		fn deserialize<D: ::serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+			/// Visitor of `u32` indices.
+			struct IndexVisitor;
+			impl ::serde::de::Visitor<'_> for IndexVisitor {
+				type Value = Index;
+
+				fn expecting(&self, formatter: &mut ::core::fmt::Formatter) -> ::core::fmt::Result {
+					formatter.write_str("an index")
+				}
+
+				fn visit_u32<E: ::serde::de::Error>(self, v: u32) -> Result<Self::Value, E> {
+					Ok(Index(v))
+				}
+			}
+
+			deserializer.deserialize_identifier(IndexVisitor)
+		}
+	}
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = crate::to_vec(&3_u8).unwrap();
+	assert_eq!(crate::from_slice_with_config::<Index>(&bytes, config).unwrap(), Index(3));
+	assert_eq!(
+		crate::from_value_with_config::<Index>(Value::from(3_u8), config).unwrap(),
+		Index(3)
+	);
+	// Without `use_indices`, integers are visited by their smallest type.
+	assert!(crate::from_value::<Index>(Value::from(3_u8)).is_err());
+}
+
+/// Day numbers that are written as text in human-readable formats, like dates in `chrono`.
+mod day_format {
+	use ::alloc::{format, string::String};
+	use ::serde::{de::Error, Deserialize, Deserializer, Serializer};
+
+	#[allow(clippy::trivially_copy_pass_by_ref, reason = "Signature required by serde")]
+	pub fn serialize<S: Serializer>(day: &u32, serializer: S) -> Result<S::Ok, S::Error> {
+		if serializer.is_human_readable() {
+			serializer.serialize_str(&format!("day {day}"))
+		} else {
+			serializer.serialize_u32(*day)
+		}
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u32, D::Error> {
+		if deserializer.is_human_readable() {
+			let text = String::deserialize(deserializer)?;
+			text.strip_prefix("day ")
+				.and_then(|day| day.parse().ok())
+				.ok_or_else(|| D::Error::custom("expected a day"))
+		} else {
+			u32::deserialize(deserializer)
+		}
+	}
+}
+
+#[test]
+fn test_human_readable_round_trip() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Event {
+		name: String,
+		#[serde(with = "day_format")]
+		day: u32,
+	}
+
+	let event = Event { name: "launch".to_owned(), day: 42 };
+	let bytes = crate::to_vec(&event).unwrap();
+	let value: Value<'_> = crate::from_slice(&bytes).unwrap();
+	assert_eq!(value.get("day"), Some(&Value::from(42_u32)));
+	let parsed: Event = crate::from_value(value).unwrap();
+	assert_eq!(parsed, event);
+
+	let config = Config { human_readable: Some(true), ..Default::default() };
+	let value = crate::to_value_with_config(&event, config).unwrap();
+	assert_eq!(value.get("day"), Some(&Value::from("day 42")));
+	let parsed: Event = crate::from_value_with_config(value, config).unwrap();
+	assert_eq!(parsed, event);
+}
+
+#[test]
+fn test_builders_borrow() {
+	use super::builder::{ArrayBuilder, MapBuilder};
+	#[derive(Serialize)]
+	struct Borrowed<'a> {
+		name: &'a str,
+		#[serde(with = "serde_bytes")]
+		data: &'a [u8],
+		list: (u8, &'a str),
+	}
+
+	let name = String::from("name");
+	let data = vec![1_u8, 2, 3];
+	let value = MapBuilder::with_capacity(3)
+		.entry("name", &name)
+		.entry("data", &data)
+		.entry("list", ArrayBuilder::new().item(1_u8).item(name.as_str()))
+		.build();
+	assert!(
+		matches!(&value["name"], Value::String(Cow::Borrowed(s)) if s.as_ptr() == name.as_ptr())
+	);
+	assert!(
+		matches!(&value["data"], Value::Bytes(Cow::Borrowed(b)) if b.as_ptr() == data.as_ptr())
+	);
+	assert!(matches!(&value["list"][1], Value::String(Cow::Borrowed(_))));
+
+	let expected = to_value(&Borrowed { name: &name, data: &data, list: (1, &name) }).unwrap();
+	assert_eq!(value, expected);
+}
+
+#[test]
+fn test_owned_value_bytes_roundtrip() {
+	fn assert_serialize<T: Serialize + DeserializeOwned>(value: &T) -> Vec<u8> {
+		let bytes = crate::to_vec(value).unwrap();
+		let _: T = crate::from_slice(&bytes).unwrap();
+		bytes
+	}
+
+	let value = OwnedValue::from(Value::from_iter([
+		(Value::from("bytes"), Value::from(vec![1_u8, 2, 3])),
+		(Value::from("list"), Value::from_iter([Value::from(-1_i8), Value::Null])),
+	]));
+	let bytes = value.to_vec().unwrap();
+	assert_eq!(bytes, crate::to_vec(&*value).unwrap());
+	assert_eq!(bytes, assert_serialize(&value));
+	assert_eq!(bytes, Vec::try_from(&value).unwrap());
+	assert_eq!(OwnedValue::from_slice(&bytes).unwrap(), value);
+	assert_eq!(OwnedValue::try_from(bytes.as_slice()).unwrap(), value);
+
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = value.to_vec_with_config(config).unwrap();
+	assert_eq!(OwnedValue::from_slice_with_config(&bytes, config).unwrap(), value);
+
+	let result = OwnedValue::from_slice(&[Type::Null.into(), Type::Null.into()]);
+	assert!(matches!(result, Err(crate::Error::ExcessData { .. })));
+}
+
+#[test]
+fn test_iteration_keeps_borrows() {
+	let data = [1_u8, 2, 3];
+	let array = Value::from_iter([Value::from("a"), Value::from(&data[..])]);
+	assert_eq!(array.values().count(), 2);
+	assert_eq!(array.entries().count(), 0);
+	let mut values = array.into_values();
+	assert_eq!(values.len(), 2);
+	assert!(matches!(values.next(), Some(Value::String(Cow::Borrowed("a")))));
+	assert!(
+		matches!(values.next(), Some(Value::Bytes(Cow::Borrowed(b))) if b.as_ptr() == data.as_ptr())
+	);
+	assert!(values.next().is_none());
+
+	let map = Value::from_iter([("key", "value"), ("other", "second")]);
+	let keys: Vec<_> = map.entries().map(|(key, _)| key.as_string().unwrap()).collect();
+	assert_eq!(keys, ["key", "other"]);
+	assert_eq!(map.values().next_back().unwrap(), "second");
+	let values: Vec<_> = map.into_values().collect();
+	assert!(matches!(
+		values.as_slice(),
+		[Value::String(Cow::Borrowed("value")), Value::String(Cow::Borrowed("second"))]
+	));
+
+	assert_eq!(Value::from(5_u8).values().count(), 0);
+	assert_eq!(Value::Null.into_values().len(), 0);
+}
+
+#[test]
+fn test_untrusted_size_hints() {
+	/// Iterator claiming to have way more items than it has.
+	struct Lying<I>(I);
+
+	impl<I: Iterator> Iterator for Lying<I> {
+		type Item = I::Item;
+
+		fn next(&mut self) -> Option<Self::Item> {
+			self.0.next()
+		}
+
+		fn size_hint(&self) -> (usize, Option<usize>) {
+			(usize::MAX, Some(usize::MAX))
+		}
+	}
+
+	/// Type serializing as sequence or struct with a huge length.
+	struct Huge(bool);
+
+	impl Serialize for Huge {
+		fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+		where
+			S: ::serde::Serializer,
+		{
+			use ::serde::ser::{SerializeSeq, SerializeStruct};
+
+			if self.0 {
+				let mut seq = serializer.serialize_seq(Some(usize::MAX))?;
+				seq.serialize_element(&1_u8)?;
+				seq.end()
+			} else {
+				let mut map = serializer.serialize_struct("Huge", usize::MAX)?;
+				map.serialize_field("a", &true)?;
+				map.end()
+			}
+		}
+	}
+
+	let deserializer = ::serde::de::value::SeqDeserializer::<_, ::serde::de::value::Error>::new(
+		Lying([1_u8, 2].into_iter()),
+	);
+	let value = Value::deserialize(deserializer).unwrap();
+	assert_eq!(value, Value::Array([1_u8.into(), 2_u8.into()].into()));
+
+	let deserializer = ::serde::de::value::MapDeserializer::<_, ::serde::de::value::Error>::new(
+		Lying([("a", true)].into_iter()),
+	);
+	let value = Value::deserialize(deserializer).unwrap();
+	assert_eq!(value, Value::from_iter([("a", true)]));
+
+	let value = crate::to_value(&Huge(true)).unwrap();
+	assert_eq!(value, Value::Array([1_u8.into()].into()));
+	let value = crate::to_value(&Huge(false)).unwrap();
+	assert_eq!(value, Value::from_iter([("a", true)]));
+}
+
+#[test]
+fn test_integers_out_of_range() {
+	assert!(crate::from_value::<u64>(Value::from(u128::MAX)).is_err());
+	assert!(crate::from_value::<usize>(Value::from(u128::MAX)).is_err());
+	assert!(crate::from_value::<i64>(Value::from(i128::MIN)).is_err());
+	assert_eq!(crate::from_value::<u128>(Value::from(u128::MAX)).unwrap(), u128::MAX);
+
+	// Large integers are not truncated in error messages.
+	let Err(crate::Error::Message(message)) = crate::from_value::<String>(Value::from(u128::MAX))
+	else {
+		panic!("expected an error message");
+	};
+	assert_eq!(message, "invalid type: large unsigned integer, expected string");
+	let Err(crate::Error::Message(message)) = crate::from_value::<bool>(Value::from(i128::MIN))
+	else {
+		panic!("expected an error message");
+	};
+	assert_eq!(message, "invalid type: large signed integer, expected bool");
+	let Err(crate::Error::Message(message)) = crate::from_value::<bool>(Value::from(1_u128 << 64))
+	else {
+		panic!("expected an error message");
+	};
+	assert_eq!(message, "invalid type: large unsigned integer, expected bool");
+	let Err(crate::Error::Message(message)) = crate::from_value::<bool>(Value::from(7_u128)) else {
+		panic!("expected an error message");
+	};
+	assert_eq!(message, "invalid type: integer `7`, expected bool");
+}
+
+#[test]
+fn test_number_accessors() {
+	// Unsigned and signed integers are converted when in range.
+	assert_eq!(Value::from(u64::MAX).as_u64(), Some(u64::MAX));
+	assert_eq!(Value::from(u128::from(u64::MAX) + 1).as_u64(), None);
+	assert_eq!(Value::from(0_i64).as_u64(), Some(0));
+	assert_eq!(Value::from(i64::MAX).as_u64(), Some(i64::MAX as u64));
+	assert_eq!(Value::from(-1_i64).as_u64(), None);
+	assert_eq!(Value::from(i64::MIN).as_i64(), Some(i64::MIN));
+	assert_eq!(Value::from(i128::from(i64::MIN) - 1).as_i64(), None);
+	assert_eq!(Value::from(i128::from(i64::MAX) + 1).as_i64(), None);
+	assert_eq!(Value::from(i64::MAX as u64).as_i64(), Some(i64::MAX));
+	assert_eq!(Value::from(i64::MAX as u64 + 1).as_i64(), None);
+	assert_eq!(Value::from(1.0_f64).as_u64(), None);
+	assert_eq!(Value::from("1").as_i64(), None);
+
+	// Integers and floats of all precisions are available as `f64`.
+	assert_eq!(Value::from(1.5_f64).as_f64(), Some(1.5));
+	assert_eq!(Value::from(1.5_f32).as_f64(), Some(1.5));
+	assert_eq!(Value::Float(Float::F16Bits(0x3E00)).as_f64(), Some(1.5));
+	assert_eq!(Value::from(u64::MAX).as_f64(), Some(18_446_744_073_709_552_000.0));
+	assert_eq!(Value::from(-3_i8).as_f64(), Some(-3.0));
+	assert_eq!(Value::from(true).as_f64(), None);
+	assert!(Value::from(f64::NAN).as_f64().unwrap().is_nan());
+
+	// Booleans and the integers 0 and 1.
+	assert_eq!(Value::from(true).as_bool_lenient(), Some(true));
+	assert_eq!(Value::from(0_u8).as_bool_lenient(), Some(false));
+	assert_eq!(Value::from(1_i8).as_bool_lenient(), Some(true));
+	assert_eq!(Value::from(2_u8).as_bool_lenient(), None);
+	assert_eq!(Value::from(-1_i8).as_bool_lenient(), None);
+	assert_eq!(Value::from("true").as_bool_lenient(), None);
+}
+
+#[test]
+fn test_integer_try_from() {
+	assert_eq!(u8::try_from(Integer::Unsigned(255)), Ok(255));
+	assert!(u8::try_from(Integer::Unsigned(256)).is_err());
+	assert_eq!(u8::try_from(Integer::Signed(255)), Ok(255));
+	assert!(u8::try_from(Integer::Signed(-1)).is_err());
+	assert_eq!(i8::try_from(Integer::Signed(-128)), Ok(-128));
+	assert!(i8::try_from(Integer::Signed(-129)).is_err());
+	assert_eq!(i8::try_from(Integer::Unsigned(127)), Ok(127));
+	assert!(i8::try_from(Integer::Unsigned(128)).is_err());
+	assert_eq!(usize::try_from(Integer::Signed(7)), Ok(7));
+	assert_eq!(isize::try_from(Integer::Unsigned(7)), Ok(7));
+	assert_eq!(u128::try_from(Integer::Unsigned(u128::MAX)), Ok(u128::MAX));
+	assert_eq!(u128::try_from(Integer::Signed(i128::MAX)), Ok(i128::MAX as u128));
+	assert!(u128::try_from(Integer::Signed(-1)).is_err());
+	assert_eq!(i128::try_from(Integer::Signed(i128::MIN)), Ok(i128::MIN));
+	assert_eq!(i128::try_from(Integer::Unsigned(i128::MAX as u128)), Ok(i128::MAX));
+	assert!(i128::try_from(Integer::Unsigned(i128::MAX as u128 + 1)).is_err());
+}
+
+#[test]
+fn test_try_into_cow() {
+	let data = vec![1, 2, 3];
+	let value = Value::from(data.as_slice());
+	assert!(matches!(value.try_into_bytes(), Ok(Cow::Borrowed([1, 2, 3]))));
+	let value = Value::from(data.clone());
+	assert!(matches!(value.try_into_bytes(), Ok(Cow::Owned(bytes)) if bytes == data));
+	let value = Value::from("borrowed");
+	assert!(matches!(value.try_into_string(), Ok(Cow::Borrowed("borrowed"))));
+	assert_eq!(Value::from("s".to_owned()).try_into_string().unwrap(), "s");
+
+	// Other values are handed back.
+	assert_eq!(Value::from("s").try_into_bytes(), Err(Value::from("s")));
+	assert_eq!(Value::from(data.as_slice()).try_into_string(), Err(Value::from(data.as_slice())));
+	assert_eq!(Value::Null.try_into_string(), Err(Value::Null));
+}
+
+#[test]
+fn test_integer_signedness() {
+	// Values of different signedness are different values.
+	assert_ne!(Value::from(5_u8), Value::from(5_i8));
+	assert_ne!(Integer::Unsigned(5), Integer::Signed(5));
+	assert!(Integer::Signed(5) < Integer::Unsigned(5));
+
+	// But numeric comparisons ignore the signedness.
+	assert!(Integer::Signed(5).eq_numeric(&Integer::Unsigned(5)));
+	assert!(!Integer::Signed(-5).eq_numeric(&Integer::Unsigned(5)));
+	assert_eq!(Integer::Signed(5).cmp_numeric(&Integer::Unsigned(5)), Ordering::Equal);
+	assert_eq!(Integer::Signed(-1).cmp_numeric(&Integer::Unsigned(0)), Ordering::Less);
+	assert_eq!(
+		Integer::Unsigned(u128::MAX).cmp_numeric(&Integer::Signed(i128::MAX)),
+		Ordering::Greater
+	);
+	assert_eq!(Integer::Signed(5), 5_u64);
+	assert_eq!(Integer::Unsigned(5), 5_i64);
+	assert_ne!(Integer::Signed(-1), u128::MAX);
+	assert_ne!(Integer::Unsigned(u128::MAX), -1_i128);
+	assert!(Integer::Signed(-1) < 0_u8);
+	assert!(Integer::Unsigned(u128::MAX) > i128::MAX);
+	assert_eq!(Value::from(5_i8), 5_u128);
+	assert_eq!(Value::from(5_u8), 5_i128);
+	assert_ne!(Value::from(-5_i8), 5_u128);
+	assert_ne!(Value::from(5.0_f64), 5_u128);
+
+	// Normalization makes non-negative integers unsigned.
+	assert_eq!(Integer::Signed(5).normalize(), Integer::Unsigned(5));
+	assert_eq!(Integer::Signed(0).normalize(), Integer::Unsigned(0));
+	assert_eq!(Integer::Signed(-5).normalize(), Integer::Signed(-5));
+	assert_eq!(Integer::Signed(i128::MAX).normalize(), Integer::Unsigned(i128::MAX as u128));
+	assert_eq!(Integer::Unsigned(u128::MAX).normalize(), Integer::Unsigned(u128::MAX));
+	let mut signed = Value::from_iter([(Value::from(1_i8), Value::from_iter([2_i32, -3]))]);
+	signed.normalize_integers();
+	let expected = Value::from_iter([(
+		Value::from(1_u8),
+		Value::Array([Value::from(2_u32), Value::from(-3_i32)].into()),
+	)]);
+	assert_eq!(signed, expected);
+
+	assert_eq!(Integer::from(7_usize), Integer::Unsigned(7));
+	assert_eq!(Integer::from(-7_isize), Integer::Signed(-7));
+	assert_eq!(Integer::from(u64::MAX), Integer::Unsigned(u64::MAX.into()));
+}
+
+#[test]
+fn test_display() {
+	let value = Value::from_iter([
+		(
+			Value::from("say \"hi\"\n"),
+			Value::from_iter([Value::from(1_u8), Value::from("\u{1}\\")]),
+		),
+		(Value::from(2_u8), Value::Array(VecDeque::new())),
+		(Value::from(b"\x01\x02".as_slice()), Value::Map(VecDeque::new())),
+	]);
+	assert_eq!(value.to_string(), r#"{"say \"hi\"\n": [1, "\u0001\\"], 2: [], 0x0102: {}}"#);
+	assert_eq!(
+		format!("{value:#}"),
+		r#"{
+  "say \"hi\"\n": [
+    1,
+    "\u0001\\"
+  ],
+  2: [],
+  0x0102: {}
+}"#
+	);
+	assert_eq!(format!("{:#}", Value::from(1.5_f32)), "1.5");
+}
+
+#[test]
+fn test_json_string() {
+	use super::json::{JsonBytes, JsonLargeIntegers, JsonOptions};
+
+	let value = Value::from_iter([
+		(Value::from("text"), Value::from("\"quoted\"\t\u{1}")),
+		(Value::from(1_u8), Value::from_iter([Value::from(-1_i8), Value::Null, true.into()])),
+		(Value::from(false), Value::from(b"hi".as_slice())),
+		(Value::from("floats"), Value::from_iter([1.0_f64, -2.0, 0.1, f64::NAN])),
+	]);
+	assert_eq!(
+		value.to_json_string().unwrap(),
+		r#"{"text":"\"quoted\"\t\u0001","1":[-1,null,true],"false":[104,105],"floats":[1.0,-2.0,0.1,null]}"#
+	);
+	let options = JsonOptions { bytes: JsonBytes::Base64, ..Default::default() };
+	assert_eq!(Value::from(b"hi".as_slice()).to_json_string_with(options).unwrap(), r#""aGk=""#);
+
+	// Floats keep their precision.
+	assert_eq!(Value::from(0.1_f32).to_json_string().unwrap(), "0.1");
+	assert_eq!(Value::Float(Float::F16Bits(0x3E00)).to_json_string().unwrap(), "1.5");
+	assert_eq!(Value::from(f32::INFINITY).to_json_string().unwrap(), "null");
+
+	// Large integers and keys.
+	let large = Value::from(u128::from(u64::MAX) + 1);
+	assert!(matches!(large.to_json_string(), Err(crate::Error::NotJsonCompatible(_))));
+	let options = JsonOptions { large_integers: JsonLargeIntegers::String, ..Default::default() };
+	assert_eq!(large.to_json_string_with(options).unwrap(), r#""18446744073709551616""#);
+	let map = Value::from_iter([(Value::Null, Value::from(true))]);
+	assert!(matches!(map.to_json_string(), Err(crate::Error::NotJsonCompatible(_))));
+}
+
+#[test]
+fn test_detect_key_mode() {
+	use crate::de::KeyMode;
+
+	#[derive(Serialize)]
+	struct Point {
+		x: i32,
+		y: i32,
+	}
+
+	init_tracing();
+	let points = vec![Point { x: 1, y: 2 }, Point { x: 3, y: 4 }];
+	let names = crate::to_value(&points).unwrap();
+	assert_eq!(names.detect_key_mode(), Some(KeyMode::Names));
+	let config = Config { use_indices: true, ..Default::default() };
+	let indices = crate::to_value_with_config(&points, config).unwrap();
+	assert_eq!(indices.detect_key_mode(), Some(KeyMode::Indices));
+
+	let mixed = Value::Array([names, indices].into());
+	assert_eq!(mixed.detect_key_mode(), Some(KeyMode::Mixed));
+	let nested = Value::from_iter([("outer", Value::from_iter([(1_u8, true)]))]);
+	assert_eq!(nested.detect_key_mode(), Some(KeyMode::Mixed));
+
+	assert_eq!(Value::from(5_u8).detect_key_mode(), None);
+	assert_eq!(Value::Array([Value::Null].into()).detect_key_mode(), None);
+	assert_eq!(Value::from_iter([(true, 1_u8)]).detect_key_mode(), None);
+}
+
+#[test]
+fn test_config_does_not_rekey_values() {
+	use crate::de::KeyMode;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Point {
+		x: i32,
+		y: i32,
+	}
+
+	init_tracing();
+	let point = Point { x: 1, y: 2 };
+	let indices = Config { use_indices: true, ..Default::default() };
+
+	// Index keys stay indices when writing with names.
+	let value = crate::to_value_with_config(&point, indices).unwrap();
+	let bytes = crate::to_vec(&value).unwrap();
+	assert_eq!(bytes, crate::to_vec_with_config(&point, indices).unwrap());
+	let parsed = crate::from_slice::<Value<'_>>(&bytes).unwrap();
+	assert_eq!(parsed.detect_key_mode(), Some(KeyMode::Indices));
+
+	// Name keys stay names when writing with indices.
+	let value = crate::to_value(&point).unwrap();
+	let bytes = crate::to_vec_with_config(&value, indices).unwrap();
+	assert_eq!(bytes, crate::to_vec(&point).unwrap());
+	let parsed = crate::from_slice::<Value<'_>>(&bytes).unwrap();
+	assert_eq!(parsed.detect_key_mode(), Some(KeyMode::Names));
+
+	// Both can be read back into the struct regardless.
+	assert_eq!(crate::from_slice::<Point>(&bytes).unwrap(), point);
+	let value = crate::to_value_with_config(&point, indices).unwrap();
+	assert_eq!(crate::from_value::<Point>(value).unwrap(), point);
+}
+
+#[test]
+fn test_diff_versions() {
+	#[derive(Serialize)]
+	struct V1 {
+		a: bool,
+		b: bool,
+		c: bool,
+		list: Vec<u8>,
+	}
+	#[derive(Serialize)]
+	struct V2 {
+		b: bool,
+		c: &'static str,
+		d: bool,
+		a: bool,
+		list: Vec<u8>,
+	}
+
+	init_tracing();
+	let old = crate::to_value(&V1 { a: true, b: false, c: true, list: vec![1, 2, 3] }).unwrap();
+	let new =
+		crate::to_value(&V2 { b: true, c: "yes", d: false, a: true, list: vec![3, 1] }).unwrap();
+	let key = |name: &'static str| PathSegment::Key(Value::from(name));
+	let differences = diff(&old, &new);
+	assert_eq!(
+		differences,
+		[
+			Difference {
+				path: vec![key("b")],
+				change: Change::ValueChanged { old: Value::from(false), new: Value::from(true) },
+			},
+			Difference {
+				path: vec![key("c")],
+				change: Change::TypeChanged { old: Value::from(true), new: Value::from("yes") },
+			},
+			Difference {
+				path: vec![key("list"), PathSegment::Index(0)],
+				change: Change::ValueChanged { old: Value::from(1_u8), new: Value::from(3_u8) },
+			},
+			Difference {
+				path: vec![key("list"), PathSegment::Index(1)],
+				change: Change::ValueChanged { old: Value::from(2_u8), new: Value::from(1_u8) },
+			},
+			Difference {
+				path: vec![key("list"), PathSegment::Index(2)],
+				change: Change::Removed(Value::from(3_u8)),
+			},
+			Difference { path: vec![key("d")], change: Change::Added(Value::from(false)) },
+		]
+	);
+	assert_eq!(
+		serde_json::to_string(&differences.get(2).unwrap()).unwrap(),
+		r#"{"path":["list",0],"change":{"ValueChanged":{"old":1,"new":3}}}"#
+	);
+	assert_eq!(
+		serde_json::to_string(&differences.last().unwrap()).unwrap(),
+		r#"{"path":["d"],"change":{"Added":false}}"#
+	);
+
+	let options = DiffOptions::default().arrays_as_sets(true);
+	let differences = diff_with_options(&old, &new, options);
+	assert_eq!(
+		differences.get(2).unwrap(),
+		&Difference {
+			path: vec![key("list"), PathSegment::Index(1)],
+			change: Change::Removed(Value::from(2_u8)),
+		}
+	);
+	assert_eq!(differences.len(), 4);
+
+	// Reordered fields.
+	let old = crate::to_value(&V1 { a: true, b: true, c: false, list: vec![] }).unwrap();
+	let mut new = old.clone();
+	new.as_map_mut().unwrap().rotate_left(1);
+	assert_eq!(
+		diff(&old, &new),
+		[Difference {
+			path: vec![],
+			change: Change::ValueChanged { old: old.clone(), new: new.clone() },
+		}]
+	);
+	assert!(diff_with_options(&old, &new, DiffOptions::default().ignore_map_order(true)).is_empty());
+
+	// Struct written with indices.
+	let config = Config { use_indices: true, ..Default::default() };
+	let indices =
+		crate::to_value_with_config(&V1 { a: true, b: true, c: false, list: vec![] }, config)
+			.unwrap();
+	let differences = diff(&old, &indices);
+	assert_eq!(differences.len(), 8);
+	assert!(differences.iter().take(4).all(|d| matches!(d.change, Change::Removed(_))));
+	assert!(differences.iter().skip(4).all(|d| matches!(d.change, Change::Added(_))));
+}
+
+#[test]
+fn test_diff_sets_and_duplicates() {
+	init_tracing();
+	let options = DiffOptions::default().arrays_as_sets(true);
+	let old = Value::from_iter([1_u8, 2, 2, 3]);
+	let new = Value::from_iter([2_u8, 4, 3, 1, 4]);
+	assert_eq!(
+		diff_with_options(&old, &new, options),
+		[
+			Difference { path: vec![PathSegment::Index(2)], change: Change::Removed(2_u8.into()) },
+			Difference { path: vec![PathSegment::Index(1)], change: Change::Added(4_u8.into()) },
+			Difference { path: vec![PathSegment::Index(4)], change: Change::Added(4_u8.into()) },
+		]
+	);
+
+	let old = Value::Map([("a".into(), 1_u8.into()), ("a".into(), 2_u8.into())].into());
+	let new = Value::Map([("a".into(), 1_u8.into())].into());
+	assert_eq!(
+		diff(&old, &new),
+		[Difference {
+			path: vec![PathSegment::Key("a".into())],
+			change: Change::Removed(2_u8.into()),
+		}]
+	);
+	assert_eq!(
+		diff(&Value::Null, &Value::Array([].into())),
+		[Difference {
+			path: vec![],
+			change: Change::TypeChanged { old: Value::Null, new: Value::Array([].into()) },
+		}]
+	);
+}
+
+/// Strategy for arbitrary values.
+fn any_value() -> impl ::proptest::strategy::Strategy<Value = Value<'static>> {
+	use ::proptest::prelude::*;
+
+	let leaf = prop_oneof![
+		Just(Value::Null),
+		any::<bool>().prop_map(Value::Bool),
+		any::<u128>().prop_map(Value::from),
+		any::<i64>().prop_map(Value::from),
+		any::<f64>().prop_map(Value::from),
+		any::<Vec<u8>>().prop_map(Value::from),
+		".{0,8}".prop_map(Value::from),
+	];
+	leaf.prop_recursive(4, 64, 8, |inner| {
+		prop_oneof![
+			prop::collection::vec_deque(inner.clone(), 0 .. 8).prop_map(Value::Array),
+			prop::collection::vec_deque((inner.clone(), inner), 0 .. 8).prop_map(Value::Map),
+		]
+	})
+}
+
+::proptest::proptest! {
+	#[test]
+	fn diff_with_itself_is_empty(value in any_value(), map_order: bool, sets: bool) {
+		let options = DiffOptions::default().ignore_map_order(map_order).arrays_as_sets(sets);
+		::proptest::prop_assert!(diff_with_options(&value, &value, options).is_empty());
+		::proptest::prop_assert!(diff(&value, &value.clone()).is_empty());
+	}
+}
+
+#[test]
+fn test_merge_patch() {
+	init_tracing();
+	// Examples of RFC 7386.
+	let map =
+		|entries: &[(&'static str, Value<'static>)]| Value::from_iter(entries.iter().cloned());
+	let cases = [
+		(map(&[("a", "b".into())]), map(&[("a", "c".into())]), map(&[("a", "c".into())])),
+		(
+			map(&[("a", "b".into())]),
+			map(&[("b", "c".into())]),
+			map(&[("a", "b".into()), ("b", "c".into())]),
+		),
+		(map(&[("a", "b".into())]), map(&[("a", Value::Null)]), map(&[])),
+		(
+			map(&[("a", "b".into()), ("b", "c".into())]),
+			map(&[("a", Value::Null)]),
+			map(&[("b", "c".into())]),
+		),
+		(
+			map(&[("a", Value::from_iter(["b"]))]),
+			map(&[("a", "c".into())]),
+			map(&[("a", "c".into())]),
+		),
+		(
+			map(&[("a", "c".into())]),
+			map(&[("a", Value::from_iter(["b"]))]),
+			map(&[("a", Value::from_iter(["b"]))]),
+		),
+		(
+			map(&[("a", map(&[("b", "c".into())]))]),
+			map(&[("a", map(&[("b", "d".into()), ("c", Value::Null)]))]),
+			map(&[("a", map(&[("b", "d".into())]))]),
+		),
+		(
+			map(&[("a", Value::from_iter([map(&[("b", "c".into())])]))]),
+			map(&[("a", Value::from_iter([1_u8]))]),
+			map(&[("a", Value::from_iter([1_u8]))]),
+		),
+		(Value::from_iter(["a", "b"]), Value::from_iter(["c", "d"]), Value::from_iter(["c", "d"])),
+		(map(&[("a", "b".into())]), Value::from_iter(["c"]), Value::from_iter(["c"])),
+		(map(&[("a", "foo".into())]), Value::Null, Value::Null),
+		(map(&[("a", "foo".into())]), "bar".into(), "bar".into()),
+		(
+			map(&[("e", Value::Null)]),
+			map(&[("a", 1_u8.into())]),
+			map(&[("e", Value::Null), ("a", 1_u8.into())]),
+		),
+		(
+			Value::from_iter([1_u8, 2]),
+			map(&[("a", "b".into()), ("c", Value::Null)]),
+			map(&[("a", "b".into())]),
+		),
+		(
+			map(&[]),
+			map(&[("a", map(&[("bb", map(&[("ccc", Value::Null)]))]))]),
+			map(&[("a", map(&[("bb", map(&[]))]))]),
+		),
+	];
+	for (mut target, patch, expected) in cases {
+		target.merge_patch(patch);
+		assert_eq!(target, expected);
+	}
+
+	// Duplicate keys keep the last value at the first position.
+	let mut target = Value::Map(
+		[("a".into(), 1_u8.into()), ("b".into(), 2_u8.into()), ("a".into(), 3_u8.into())].into(),
+	);
+	target.merge_patch(map(&[("c", 4_u8.into())]));
+	assert_eq!(target, map(&[("a", 3_u8.into()), ("b", 2_u8.into()), ("c", 4_u8.into())]));
+	let mut target = Value::Map([("a".into(), 1_u8.into()), ("a".into(), 2_u8.into())].into());
+	target.merge_patch(map(&[("a", Value::Null)]));
+	assert_eq!(target, map(&[]));
+	// Duplicate keys in the patch are applied in order.
+	let mut target = map(&[]);
+	target.merge_patch(Value::Map(
+		[("a".into(), map(&[("x", 1_u8.into())])), ("a".into(), map(&[("y", 2_u8.into())]))].into(),
+	));
+	assert_eq!(target, map(&[("a", map(&[("x", 1_u8.into()), ("y", 2_u8.into())]))]));
+}
+
+#[test]
+fn test_deep_merge() {
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Settings {
+		name: String,
+		plugins: Vec<String>,
+		limits: Vec<u32>,
+	}
+
+	init_tracing();
+	let base = Settings { name: "base".to_owned(), plugins: vec!["a".to_owned()], limits: vec![1] };
+	let mut value = crate::to_value(&base).unwrap();
+	let patch = Value::from_iter([
+		("plugins", Value::from_iter(["b", "c"])),
+		("limits", Value::from_iter([2_u32])),
+	]);
+	value.deep_merge(patch.clone());
+	assert_eq!(
+		value.deserialize_as::<Settings>().unwrap(),
+		Settings {
+			name: "base".to_owned(),
+			plugins: vec!["a".to_owned(), "b".to_owned(), "c".to_owned()],
+			limits: vec![1, 2],
+		}
+	);
+
+	let mut value = crate::to_value(&base).unwrap();
+	value.merge_patch(patch);
+	assert_eq!(
+		value.deserialize_as::<Settings>().unwrap(),
+		Settings {
+			name: "base".to_owned(),
+			plugins: vec!["b".to_owned(), "c".to_owned()],
+			limits: vec![2],
+		}
+	);
+
+	// Arrays are only appended to arrays, everything else is replaced.
+	let mut value = Value::from(5_u8);
+	value.deep_merge(Value::from_iter([1_u8]));
+	assert_eq!(value, Value::from_iter([1_u8]));
+	let mut value = Value::from_iter([1_u8]);
+	value.deep_merge(Value::from_iter([Value::Null]));
+	assert_eq!(value, Value::from_iter([Value::from(1_u8), Value::Null]));
+	let mut value =
+		Value::from_iter([("nested", Value::from_iter([("list", Value::from_iter([1_u8]))]))]);
+	value.deep_merge(Value::from_iter([(
+		"nested",
+		Value::from_iter([("list", Value::from_iter([2_u8]))]),
+	)]));
+	assert_eq!(value["nested"]["list"], Value::from_iter([1_u8, 2]));
+}
+
+#[cfg(feature = "big-integers")]
+#[test]
+fn test_big_integers() {
+	use super::json::{JsonLargeIntegers, JsonOptions};
+	use crate::Error;
+
+	init_tracing();
+	// 20 var-int bytes, i.e. 140 bits.
+	let varint = [[0xFF; 19].as_slice(), &[0x7F]].concat();
+	let unsigned = [&[Type::UnsignedInt.into()], varint.as_slice()].concat();
+	let signed = [&[Type::SignedInt.into()], varint.as_slice()].concat();
+	// 1 with unnecessary padding.
+	let padded = [&[Type::UnsignedInt.into(), 0x81], [0x80; 18].as_slice(), &[0x00]].concat();
+
+	for (bytes, decimal) in [
+		(&unsigned, "1393796574908163946345982392040522594123775"),
+		(&signed, "-696898287454081973172991196020261297061888"),
+		(&padded, "1"),
+	] {
+		let value = crate::from_slice::<Value<'_>>(bytes).unwrap();
+		let Value::BigInteger(int) = &value else {
+			panic!("Expected a big integer, got {value:?}");
+		};
+		assert_eq!(int.to_string(), decimal);
+		assert_eq!(value.to_string(), decimal);
+		assert_eq!(int.to_bytes(), *bytes);
+		assert_eq!(crate::to_vec(&value).unwrap(), *bytes);
+		crate::validate(bytes, Config::default()).unwrap();
+		crate::from_slice::<::serde::de::IgnoredAny>(bytes).unwrap();
+		assert_eq!(crate::to_value(&value).unwrap(), value);
+		assert_eq!(crate::from_value::<Value<'_>>(value.clone()).unwrap(), value);
+		assert_eq!(value.clone().into_owned().to_vec().unwrap(), *bytes);
+
+		assert!(crate::from_slice::<u128>(bytes).is_err());
+		assert!(crate::from_slice::<i128>(bytes).is_err());
+		assert!(crate::from_value::<u128>(value.clone()).is_err());
+		assert!(crate::from_value::<::serde::de::IgnoredAny>(value.clone()).is_err());
+
+		assert!(matches!(value.to_json_string(), Err(Error::NotJsonCompatible(_))));
+		let options =
+			JsonOptions { large_integers: JsonLargeIntegers::String, ..Default::default() };
+		assert_eq!(value.to_json_string_with(options).unwrap(), format!("\"{decimal}\""));
+		// Other serializers get the encoding as bytes.
+		assert_eq!(
+			::serde_json::to_string(&value).unwrap(),
+			::serde_json::to_string(bytes).unwrap()
+		);
+	}
+
+	// Ignored big integers are skipped like in values, but not beyond the maximum length.
+	let too_long_signed =
+		[&[Type::SignedInt.into()], vec![0xFF; BigInteger::MAX_VARINT_BYTES].as_slice(), &[0x01]]
+			.concat();
+	assert!(matches!(
+		crate::from_slice::<::serde::de::IgnoredAny>(&too_long_signed),
+		Err(Error::VarIntTooLarge)
+	));
+
+	assert!(matches!(crate::from_slice::<u128>(&unsigned), Err(Error::VarIntTooLarge)));
+	assert!(matches!(crate::from_slice::<i128>(&signed), Err(Error::VarIntTooLarge)));
+
+	// Integers that fit stay integers.
+	let bytes = [Type::SignedInt.into(), 0x03];
+	assert_eq!(crate::from_slice::<Value<'_>>(&bytes).unwrap(), Value::from(-2_i8));
+	let bytes = crate::to_vec(&u128::MAX).unwrap();
+	assert_eq!(crate::from_slice::<Value<'_>>(&bytes).unwrap(), Value::from(u128::MAX));
+
+	// Ordering by numeric value.
+	let positive = crate::from_slice::<Value<'_>>(&unsigned).unwrap();
+	let negative = crate::from_slice::<Value<'_>>(&signed).unwrap();
+	let one = crate::from_slice::<Value<'_>>(&padded).unwrap();
+	let mut values = vec![
+		positive.clone(),
+		Value::from(u128::MAX),
+		one.clone(),
+		negative.clone(),
+		Value::from(i128::MIN),
+	];
+	values.sort();
+	// Big integers are ordered outside of the range of other integers, even if they are small.
+	assert_eq!(
+		values,
+		[negative, Value::from(i128::MIN), Value::from(u128::MAX), one.clone(), positive]
+	);
+	let other_one = BigInteger::from_varint(false, [0x81, 0x00].as_slice()).unwrap();
+	assert_eq!(one, Value::BigInteger(other_one));
+	assert_ne!(one, Value::from(1_u8));
+
+	// Invalid var-ints.
+	assert!(BigInteger::from_varint(false, [0x80].as_slice()).is_none());
+	assert!(BigInteger::from_varint(false, [0x00, 0x00].as_slice()).is_none());
+	assert!(BigInteger::from_varint(false, [].as_slice()).is_none());
+	let too_long =
+		[&[Type::UnsignedInt.into()], vec![0xFF; BigInteger::MAX_VARINT_BYTES].as_slice(), &[0x01]]
+			.concat();
+	assert!(matches!(crate::from_slice::<Value<'_>>(&too_long), Err(Error::VarIntTooLarge)));
+	assert!(matches!(crate::validate(&too_long, Config::default()), Err(Error::VarIntTooLarge)));
+	let longest = [
+		&[Type::UnsignedInt.into()],
+		vec![0xFF; BigInteger::MAX_VARINT_BYTES - 1].as_slice(),
+		&[0x01],
+	]
+	.concat();
+	assert_eq!(crate::to_vec(&crate::from_slice::<Value<'_>>(&longest).unwrap()).unwrap(), longest);
+}
@@ -0,0 +1,139 @@
+//! Slice-oriented functions for the [VarInt](crate::format::VarInt) encoding, e.g. for outer
+//! protocols around the format that use var-ints as well. The encoding is the same as within the
+//! format: little-endian groups of 7 bits, where the highest bit of each byte marks that another
+//! byte follows, and zigzag encoding for signed integers.
+//!
+//! ```rust
+//! use serde_brief::varint;
+//!
+//! let mut buffer = [0; 16];
+//! let written = varint::encode_u64(300, &mut buffer).unwrap();
+//! assert_eq!(written, varint::encoded_len(300));
+//! assert_eq!(&buffer[.. written], [0xAC, 0x02]);
+//!
+//! let (value, read) = varint::decode_u64(&buffer).unwrap();
+//! assert_eq!((value, read), (300, 2));
+//! ```
+
+use crate::{format::VarInt, Result};
+
+/// Encode the integer to the start of the buffer and return the number of bytes written. Returns
+/// [Error::BufferTooSmall](crate::Error::BufferTooSmall) if the buffer is too short, leaving a
+/// partial encoding in the buffer.
+pub fn encode_u64(value: u64, buffer: &mut [u8]) -> Result<usize> {
+	encode(value, buffer)
+}
+
+/// Decode an integer from the start of the bytes and return it together with the number of bytes
+/// read. Returns [Error::UnexpectedEnd](crate::Error::UnexpectedEnd) if the bytes end within the
+/// var-int and [Error::VarIntTooLarge](crate::Error::VarIntTooLarge) if it exceeds 64 bits.
+pub fn decode_u64(bytes: &[u8]) -> Result<(u64, usize)> {
+	decode(bytes)
+}
+
+/// Encode the zigzag-encoded integer to the start of the buffer and return the number of bytes
+/// written, like [encode_u64].
+pub fn encode_i64(value: i64, buffer: &mut [u8]) -> Result<usize> {
+	encode(value, buffer)
+}
+
+/// Decode a zigzag-encoded integer from the start of the bytes and return it together with the
+/// number of bytes read, like [decode_u64].
+pub fn decode_i64(bytes: &[u8]) -> Result<(i64, usize)> {
+	decode(bytes)
+}
+
+/// Return the number of bytes the unsigned integer takes up when encoded, between 1 and 10.
+#[must_use]
+pub const fn encoded_len(value: u64) -> usize {
+	let mut len = 1;
+	let mut rest = value >> 7;
+	while rest != 0 {
+		len += 1;
+		rest >>= 7;
+	}
+	len
+}
+
+/// Encode the integer to the start of the buffer and return the number of bytes written.
+fn encode<T: VarInt>(value: T, buffer: &mut [u8]) -> Result<usize> {
+	let capacity = buffer.len();
+	let mut output = buffer;
+	value.encode(&mut output)?;
+	Ok(capacity - output.len())
+}
+
+/// Decode an integer from the start of the bytes and return it with the number of bytes read.
+fn decode<T: VarInt>(bytes: &[u8]) -> Result<(T, usize)> {
+	let mut input = bytes;
+	let value = T::decode(&mut input)?;
+	Ok((value, bytes.len() - input.len()))
+}
+
+#[cfg(test)]
+mod tests {
+	#![allow(clippy::unwrap_used, reason = "Tests")]
+
+	use super::*;
+	use crate::Error;
+
+	#[test]
+	fn unsigned_boundaries_roundtrip() {
+		let cases: [(u64, &[u8]); 6] = [
+			(0, &[0x00]),
+			(0x7F, &[0x7F]),
+			(0x80, &[0x80, 0x01]),
+			(0x3FFF, &[0xFF, 0x7F]),
+			(0x4000, &[0x80, 0x80, 0x01]),
+			(u64::MAX, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+		];
+		for (value, expected) in cases {
+			let mut buffer = [0xAA; 12];
+			let written = encode_u64(value, &mut buffer).unwrap();
+			assert_eq!(buffer.get(.. written).unwrap(), expected, "{value:#X}");
+			assert_eq!(encoded_len(value), written);
+			assert_eq!(decode_u64(&buffer).unwrap(), (value, written));
+
+			// The same encoding as within the format.
+			let mut document = [0; 12];
+			let document = crate::to_slice(&value, &mut document).unwrap();
+			assert_eq!(document.get(1 ..).unwrap(), expected);
+		}
+	}
+
+	#[test]
+	fn signed_boundaries_roundtrip() {
+		let cases: [(i64, &[u8]); 7] = [
+			(0, &[0x00]),
+			(-1, &[0x01]),
+			(1, &[0x02]),
+			(-64, &[0x7F]),
+			(64, &[0x80, 0x01]),
+			(i64::MAX, &[0xFE, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+			(i64::MIN, &[0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x01]),
+		];
+		for (value, expected) in cases {
+			let mut buffer = [0xAA; 12];
+			let written = encode_i64(value, &mut buffer).unwrap();
+			assert_eq!(buffer.get(.. written).unwrap(), expected, "{value}");
+			assert_eq!(decode_i64(&buffer).unwrap(), (value, written));
+		}
+	}
+
+	#[test]
+	fn errors_are_reported() {
+		let mut buffer = [0; 1];
+		assert!(matches!(encode_u64(0x80, &mut buffer), Err(Error::BufferTooSmall)));
+		assert!(matches!(encode_u64(0, &mut []), Err(Error::BufferTooSmall)));
+		assert_eq!(encode_u64(0x7F, &mut buffer).unwrap(), 1);
+
+		assert!(matches!(decode_u64(&[]), Err(Error::UnexpectedEnd)));
+		assert!(matches!(decode_u64(&[0x80, 0x80]), Err(Error::UnexpectedEnd)));
+		let too_large = [0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0x02];
+		assert!(matches!(decode_u64(&too_large), Err(Error::VarIntTooLarge)));
+		assert!(matches!(decode_i64(&too_large), Err(Error::VarIntTooLarge)));
+
+		// Only the var-int is read, trailing bytes are left for the protocol.
+		assert_eq!(decode_u64(&[0x80, 0x01, 0xFF]).unwrap(), (0x80, 2));
+	}
+}
@@ -0,0 +1,175 @@
+//! Allocation counting tests.
+#![cfg(feature = "std")]
+
+use ::serde::Deserialize;
+use ::std::{
+	alloc::{GlobalAlloc, Layout, System},
+	cell::Cell,
+};
+
+/// Allocator counting the allocations of the current thread.
+struct CountingAllocator;
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+thread_local! {
+	static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+// SAFETY: Forwards to the system allocator.
+unsafe impl GlobalAlloc for CountingAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		_ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+		// SAFETY: Same contract.
+		unsafe { System.alloc(layout) }
+	}
+
+	unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+		// SAFETY: Same contract.
+		unsafe { System.dealloc(ptr, layout) }
+	}
+
+	unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+		_ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+		// SAFETY: Same contract.
+		unsafe { System.realloc(ptr, layout, new_size) }
+	}
+}
+
+/// Count the allocations of the current thread while running the function.
+fn count_allocations<T>(f: impl FnOnce() -> T) -> (T, usize) {
+	let before = ALLOCATIONS.with(Cell::get);
+	let result = f();
+	let after = ALLOCATIONS.with(Cell::get);
+	(result, after - before)
+}
+
+#[derive(Debug, PartialEq, serde::Serialize, Deserialize)]
+struct ManyFields {
+	first_field: u32,
+	second_field: u32,
+	third_field: u32,
+	fourth_field: u32,
+	fifth_field: u32,
+	sixth_field: u32,
+	seventh_field: u32,
+	eighth_field: u32,
+	ninth_field: u32,
+	tenth_field: u32,
+	eleventh_field: u32,
+	twelfth_field: u32,
+	thirteenth_field: u32,
+	fourteenth_field: u32,
+	fifteenth_field: u32,
+	sixteenth_field: u32,
+	seventeenth_field: u32,
+	eighteenth_field: u32,
+	nineteenth_field: u32,
+	twentieth_field: u32,
+}
+
+#[test]
+fn short_field_names_do_not_allocate() {
+	let value = ManyFields {
+		first_field: 1,
+		second_field: 2,
+		third_field: 3,
+		fourth_field: 4,
+		fifth_field: 5,
+		sixth_field: 6,
+		seventh_field: 7,
+		eighth_field: 8,
+		ninth_field: 9,
+		tenth_field: 10,
+		eleventh_field: 11,
+		twelfth_field: 12,
+		thirteenth_field: 13,
+		fourteenth_field: 14,
+		fifteenth_field: 15,
+		sixteenth_field: 16,
+		seventeenth_field: 17,
+		eighteenth_field: 18,
+		nineteenth_field: 19,
+		twentieth_field: 20,
+	};
+	let bytes = serde_brief::to_vec(&value).unwrap();
+
+	let (parsed, allocations) =
+		count_allocations(|| serde_brief::from_reader::<_, ManyFields>(bytes.as_slice()));
+	assert_eq!(parsed.unwrap(), value);
+	assert_eq!(allocations, 0);
+
+	// Longer data still uses the buffer.
+	let long = "a string that does not fit into the inline buffer".to_owned();
+	let bytes = serde_brief::to_vec(&long).unwrap();
+	let (parsed, allocations) =
+		count_allocations(|| serde_brief::from_reader::<_, String>(bytes.as_slice()));
+	assert_eq!(parsed.unwrap(), long);
+	assert!(allocations > 0);
+}
+
+#[test]
+fn map_reader_does_not_allocate() {
+	let value = vec![("key", vec!["a string that does not fit into the inline buffer"; 3]); 20];
+	let map: ::std::collections::BTreeMap<_, _> =
+		value.into_iter().enumerate().map(|(i, (key, v))| (format!("{key}-{i}"), v)).collect();
+	let bytes = serde_brief::to_vec(&map).unwrap();
+
+	let (found, allocations) = count_allocations(|| {
+		serde_brief::raw::MapReader::new(&bytes).and_then(|reader| reader.get("key-19"))
+	});
+	assert!(found.unwrap().is_some());
+	assert_eq!(allocations, 0);
+}
+
+#[test]
+fn boxed_and_shared_strings_allocate_once() {
+	use ::std::{borrow::Cow, rc::Rc, sync::Arc};
+
+	#[derive(Deserialize)]
+	struct Boxed {
+		text: Box<str>,
+	}
+
+	#[derive(Deserialize)]
+	struct Shared {
+		#[serde(with = "serde_brief::helpers::shared_str")]
+		arc: Arc<str>,
+		#[serde(with = "serde_brief::helpers::shared_str")]
+		rc: Rc<str>,
+	}
+
+	#[derive(Deserialize)]
+	struct Borrowed<'a> {
+		#[serde(borrow, with = "serde_brief::helpers::cow_str")]
+		text: Cow<'a, str>,
+		#[serde(borrow, with = "serde_brief::helpers::cow_bytes")]
+		data: Cow<'a, [u8]>,
+	}
+
+	let text = "a string that does not fit into any inline buffer";
+	let map: ::std::collections::BTreeMap<_, _> = [("text", text)].into_iter().collect();
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	let (parsed, allocations) = count_allocations(|| serde_brief::from_slice::<Boxed>(&bytes));
+	assert_eq!(&*parsed.unwrap().text, text);
+	assert_eq!(allocations, 1);
+
+	let map: ::std::collections::BTreeMap<_, _> =
+		[("arc", text), ("rc", text)].into_iter().collect();
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	let (parsed, allocations) = count_allocations(|| serde_brief::from_slice::<Shared>(&bytes));
+	let parsed = parsed.unwrap();
+	assert_eq!((&*parsed.arc, &*parsed.rc), (text, text));
+	assert_eq!(allocations, 2);
+
+	let mut map = ::std::collections::BTreeMap::new();
+	map.insert("text", serde_brief::value::Value::from(text));
+	map.insert("data", serde_brief::value::Value::Bytes(text.as_bytes().into()));
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	let (parsed, allocations) = count_allocations(|| serde_brief::from_slice::<Borrowed>(&bytes));
+	let parsed = parsed.unwrap();
+	assert!(matches!(parsed.text, Cow::Borrowed(_)));
+	assert!(matches!(parsed.data, Cow::Borrowed(_)));
+	assert_eq!(allocations, 0);
+}
@@ -0,0 +1,148 @@
+//! Test the streaming conversion between JSON and the binary format.
+#![cfg(all(feature = "std", feature = "json"))]
+
+use ::serde_brief::{
+	convert::{brief_to_json, brief_to_json_with, json_to_brief},
+	value::{
+		json::{JsonBytes, JsonLargeIntegers, JsonOptions},
+		Value,
+	},
+	Config, ErrorKind,
+};
+
+/// Convert JSON to the binary format.
+fn to_brief(json: &str, config: Config) -> Vec<u8> {
+	let mut bytes = Vec::new();
+	json_to_brief(json.as_bytes(), &mut bytes, config).expect("converting from JSON");
+	bytes
+}
+
+/// Convert the binary format to JSON.
+fn to_json(bytes: &[u8], pretty: bool) -> String {
+	let mut json = Vec::new();
+	brief_to_json(bytes, &mut json, pretty).expect("converting to JSON");
+	String::from_utf8(json).expect("JSON is UTF-8")
+}
+
+#[test]
+fn test_convert_json_blobs() {
+	for json in super::json_data::json_blobs() {
+		let expected: serde_json::Value = serde_json::from_str(&json).expect("parsing JSON");
+		// The order of keys is preserved.
+		let mut minified = Vec::new();
+		serde_transcode::transcode(
+			&mut serde_json::Deserializer::from_str(&json),
+			&mut serde_json::Serializer::new(&mut minified),
+		)
+		.expect("minifying JSON");
+		let minified = String::from_utf8(minified).expect("JSON is UTF-8");
+
+		let bytes = to_brief(&json, Config::default());
+		let value: Value<'_> = serde_brief::from_slice(&bytes).expect("deserializing");
+		let typed: Value<'_> = serde_json::from_str(&json).expect("parsing JSON");
+		assert_eq!(value, typed);
+		assert_eq!(to_json(&bytes, false), minified);
+		let pretty: serde_json::Value =
+			serde_json::from_str(&to_json(&bytes, true)).expect("parsing pretty JSON");
+		assert_eq!(pretty, expected);
+
+		let config = Config { key_dictionary: true, ..Default::default() };
+		let bytes = to_brief(&json, config);
+		assert_eq!(to_json(&bytes, false), minified);
+	}
+}
+
+#[test]
+fn test_convert_json_incompatible_values() {
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	struct Blob {
+		#[serde(with = "serde_bytes")]
+		data: Vec<u8>,
+		large: u128,
+	}
+
+	let blob = Blob { data: vec![0, 1, 255], large: u128::MAX };
+	let bytes = serde_brief::to_vec(&blob).unwrap();
+	let mut json = Vec::new();
+	assert!(brief_to_json(bytes.as_slice(), &mut json, false).is_err());
+
+	let mut json = Vec::new();
+	let options = JsonOptions { large_integers: JsonLargeIntegers::String, ..Default::default() };
+	brief_to_json_with(bytes.as_slice(), &mut json, false, options, Config::default()).unwrap();
+	let json = String::from_utf8(json).unwrap();
+	assert_eq!(json, format!(r#"{{"data":[0,1,255],"large":"{}"}}"#, u128::MAX));
+
+	// Bytes come back as an array of integers, which is still accepted for bytes.
+	let blob = Blob { data: vec![0, 1, 255], large: 5 };
+	let bytes = serde_brief::to_vec(&blob).unwrap();
+	let json = to_json(&bytes, false);
+	let parsed: Blob = serde_brief::from_slice(&to_brief(&json, Config::default())).unwrap();
+	assert_eq!(parsed, blob);
+
+	let mut json = Vec::new();
+	let options = JsonOptions { bytes: JsonBytes::Base64, ..Default::default() };
+	brief_to_json_with(bytes.as_slice(), &mut json, false, options, Config::default()).unwrap();
+	assert_eq!(json, br#"{"data":"AAH/","large":5}"#);
+
+	let map = Value::from_iter([(Value::Bytes(b"key".as_slice().into()), Value::Null)]);
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	assert!(brief_to_json(bytes.as_slice(), &mut Vec::new(), false).is_err());
+	let map = Value::from_iter([(Value::from(5_u8), Value::from(true))]);
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	assert_eq!(to_json(&bytes, false), r#"{"5":true}"#);
+}
+
+#[test]
+fn test_convert_invalid_input() {
+	let mut bytes = Vec::new();
+	assert!(json_to_brief(b"[1, 2".as_slice(), &mut bytes, Config::default()).is_err());
+	assert!(json_to_brief(b"[1, 2] 3".as_slice(), &mut Vec::new(), Config::default()).is_err());
+	json_to_brief(b" [1, 2]\n".as_slice(), &mut Vec::new(), Config::default()).unwrap();
+
+	let config = Config { max_size: Some(4.try_into().unwrap()), ..Default::default() };
+	let result = json_to_brief(b"\"too long\"".as_slice(), &mut Vec::new(), config);
+	assert!(result.is_err());
+
+	let bytes = serde_brief::to_vec(&[1, 2]).unwrap();
+	assert!(brief_to_json(bytes.get(.. bytes.len() - 1).unwrap(), &mut Vec::new(), false).is_err());
+}
+
+#[test]
+fn test_convert_error_kinds() {
+	let err = json_to_brief(b"[1, [2".as_slice(), &mut Vec::new(), Config::default()).unwrap_err();
+	assert_eq!(err.kind(), ErrorKind::UnexpectedEnd);
+
+	let bytes = serde_brief::to_vec(&[[1, 2], [3, 4]]).unwrap();
+	let truncated = bytes.get(.. bytes.len() - 2).unwrap();
+	let err = brief_to_json(truncated, &mut Vec::new(), false).unwrap_err();
+	assert_eq!(err.kind(), ErrorKind::UnexpectedEnd);
+
+	let mut deep = Value::Null;
+	for _ in 0 .. 200 {
+		deep = Value::Array([deep].into());
+	}
+	let bytes = serde_brief::to_vec(&deep).unwrap();
+	let err = brief_to_json(bytes.as_slice(), &mut Vec::new(), false).unwrap_err();
+	assert_eq!(err.kind(), ErrorKind::DepthLimitReached);
+	assert_eq!(err.to_string(), "Configured nesting depth limit reached");
+
+	let config = Config { max_depth: 1, ..Default::default() };
+	let err = brief_to_json_with(truncated, &mut Vec::new(), false, JsonOptions::default(), config)
+		.unwrap_err();
+	assert_eq!(err.kind(), ErrorKind::DepthLimitReached);
+}
+
+#[test]
+fn test_convert_consecutive_values() {
+	let mut bytes = serde_brief::to_vec(&[1, 2]).unwrap();
+	bytes.extend(serde_brief::to_vec(&"next").unwrap());
+	let config = Config { error_on_excess_data: false, ..Default::default() };
+
+	let mut reader = bytes.as_slice();
+	let mut json = Vec::new();
+	brief_to_json_with(&mut reader, &mut json, false, JsonOptions::default(), config).unwrap();
+	json.push(b' ');
+	brief_to_json_with(&mut reader, &mut json, false, JsonOptions::default(), config).unwrap();
+	assert_eq!(json, br#"[1,2] "next""#);
+	assert!(reader.is_empty());
+}
@@ -0,0 +1,101 @@
+//! Inputs found by or written for the fuzz targets in `fuzz`, which must not panic.
+#![cfg(feature = "std")]
+
+use ::serde::de::IgnoredAny;
+use ::serde_brief::{value::Value, Config, Error};
+
+/// Read all inputs in the fuzz regression folder.
+fn fuzz_inputs() -> Vec<(String, Vec<u8>)> {
+	let mut inputs = Vec::new();
+	for entry in std::fs::read_dir("./tests/data/fuzz").expect("finding fuzz data") {
+		let file = entry.expect("getting directory entry").path();
+		let name = file.file_name().unwrap_or_default().to_string_lossy().into_owned();
+		inputs.push((name, std::fs::read(&file).expect("reading fuzz input")));
+	}
+	assert!(!inputs.is_empty(), "no fuzz inputs found");
+	inputs
+}
+
+#[test]
+fn fuzz_regressions() {
+	for (name, data) in fuzz_inputs() {
+		println!("Testing `{name}`");
+		let value = serde_brief::from_slice::<Value<'_>>(&data);
+		_ = serde_brief::from_slice::<IgnoredAny>(&data);
+		_ = serde_brief::from_reader::<_, IgnoredAny>(data.as_slice());
+		let valid = serde_brief::validate(&data, Config::default());
+		if let Ok(value) = value {
+			assert!(valid.is_ok(), "deserialized data must be valid");
+			serde_brief::to_vec(&value).unwrap();
+		}
+
+		if name.starts_with("deep-") && name != "deep-within-limit.bin" {
+			assert!(matches!(valid, Err(Error::DepthLimitReached)));
+		}
+	}
+}
+
+#[test]
+fn max_depth_is_configurable() {
+	let data = std::fs::read("./tests/data/fuzz/deep-within-limit.bin").unwrap();
+	serde_brief::from_slice::<Value<'_>>(&data).unwrap();
+	serde_brief::validate(&data, Config::default()).unwrap();
+
+	let config = Config { max_depth: 127, ..Default::default() };
+	let result = serde_brief::from_slice_with_config::<Value<'_>>(&data, config);
+	assert!(matches!(result, Err(Error::DepthLimitReached)));
+	let result = serde_brief::from_slice_with_config::<IgnoredAny>(&data, config);
+	assert!(matches!(result, Err(Error::DepthLimitReached)));
+	let result = serde_brief::validate(&data, config);
+	assert!(matches!(result, Err(Error::DepthLimitReached)));
+}
+
+#[test]
+fn empty_and_tiny_data_at_the_end() {
+	use ::serde::Deserialize;
+	use ::serde_brief::{format::Type, io::IoReader, value::OwnedValue, Deserializer};
+
+	let string = Type::String.into();
+	let bytes = Type::Bytes.into();
+	let (seq_start, seq_end) = (Type::SeqStart.into(), Type::SeqEnd.into());
+	let (map_start, map_end) = (Type::MapStart.into(), Type::MapEnd.into());
+	let seq_fixed = Type::SeqFixed.into();
+	let mut nested_empty = vec![seq_start; 100];
+	nested_empty.extend([map_start, map_end]);
+	nested_empty.extend([seq_end; 100]);
+	let inputs: [&[u8]; 10] = [
+		&[string, 0],
+		&[string, 1, b'a'],
+		&[bytes, 0],
+		&[bytes, 1, 0],
+		&[seq_start, seq_end],
+		&[seq_fixed, 0],
+		&[map_start, map_end],
+		&[seq_start, string, 0, bytes, 0, seq_start, seq_end, seq_end],
+		&[map_start, string, 0, string, 0, map_end],
+		&nested_empty,
+	];
+
+	for data in inputs {
+		let value = serde_brief::from_slice::<Value<'_>>(data).unwrap();
+		serde_brief::validate(data, Config::default()).unwrap();
+		serde_brief::from_slice::<IgnoredAny>(data).unwrap();
+		serde_brief::from_reader::<_, IgnoredAny>(data).unwrap();
+		assert_eq!(*serde_brief::from_reader::<_, OwnedValue>(data).unwrap(), value);
+
+		// Internal buffers that end right within or after the data.
+		for capacity in 1 ..= data.len() {
+			let mut buffer = Vec::new();
+			let mut de =
+				Deserializer::new(IoReader::with_capacity(data, capacity)).with_buffer(&mut buffer);
+			assert_eq!(*OwnedValue::deserialize(&mut de).unwrap(), value, "{data:?}");
+			de.end().unwrap();
+		}
+
+		// The same data truncated is incomplete.
+		if let Some((_, truncated)) = data.split_last() {
+			assert!(serde_brief::from_slice::<Value<'_>>(truncated).is_err());
+			assert!(serde_brief::from_reader::<_, IgnoredAny>(truncated).is_err());
+		}
+	}
+}
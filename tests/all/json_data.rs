@@ -1,7 +1,7 @@
 //! Test with JSON blobs.
 #![cfg(feature = "std")]
 
-use ::serde_brief::value::Value;
+use ::serde_brief::{value::Value, Config};
 
 fn roundtrip(value: Value<'_>) {
 	let bytes = serde_brief::to_vec(&value).expect("serializing");
@@ -9,16 +9,83 @@ fn roundtrip(value: Value<'_>) {
 	assert_eq!(parsed, value);
 }
 
-#[test]
-fn test_json_blobs() {
+/// Read all JSON files in the test data folder.
+pub fn json_blobs() -> Vec<String> {
+	let mut blobs = Vec::new();
 	for entry in std::fs::read_dir("./tests/data").expect("finding test data") {
 		let entry = entry.expect("getting directory entry");
 		let file = entry.path();
-		if file.ends_with(".json") {
+		if file.extension().is_some_and(|ext| ext == "json") {
 			println!("Testing `{}`", file.display());
-			let json = std::fs::read_to_string(file).expect("reading JSON file");
-			let value: Value = serde_json::from_str(&json).expect("parsing JSON");
-			roundtrip(value);
+			blobs.push(std::fs::read_to_string(file).expect("reading JSON file"));
 		}
 	}
+	assert!(!blobs.is_empty(), "no JSON test data found");
+	blobs
+}
+
+#[test]
+fn test_json_blobs() {
+	for json in json_blobs() {
+		let value: Value = serde_json::from_str(&json).expect("parsing JSON");
+		roundtrip(value);
+	}
+}
+
+#[test]
+fn test_json_blobs_with_key_dictionary() {
+	let config = Config { key_dictionary: true, ..Default::default() };
+	for json in json_blobs() {
+		let value: Value = serde_json::from_str(&json).expect("parsing JSON");
+		let plain = serde_brief::to_vec(&value).expect("serializing");
+		let bytes = serde_brief::to_vec_with_config(&value, config).expect("serializing");
+		let parsed: Value<'_> = serde_brief::from_slice(&bytes).expect("deserializing");
+		assert_eq!(parsed, value);
+		println!("{} bytes with key dictionary, {} bytes without", bytes.len(), plain.len());
+		assert!(bytes.len() <= plain.len());
+	}
+
+	// Repeated objects approach the size of indices.
+	let json = serde_json::json!({ "coordinate_x": 1, "coordinate_y": 2, "label": "point" });
+	let values = vec![json; 1000];
+	let plain = serde_brief::to_vec(&values).expect("serializing");
+	let bytes = serde_brief::to_vec_with_config(&values, config).expect("serializing");
+	assert!(bytes.len() * 2 < plain.len(), "{} vs {}", bytes.len(), plain.len());
+}
+
+#[cfg(feature = "json")]
+#[test]
+fn test_json_value_conversions() {
+	use ::serde_brief::value::OwnedValue;
+
+	for json in json_blobs() {
+		let json: serde_json::Value = serde_json::from_str(&json).expect("parsing JSON");
+
+		let converted = OwnedValue::from(json.clone());
+		let deserialized: OwnedValue = serde_json::from_value(json.clone()).expect("deserializing");
+		assert_eq!(converted, deserialized);
+		roundtrip(converted.borrow_clone());
+
+		let back = serde_json::Value::try_from(&*converted).expect("converting back");
+		assert_eq!(back, json);
+		let serialized = serde_json::to_value(&*converted).expect("serializing");
+		assert_eq!(back, serialized);
+	}
+}
+
+#[test]
+fn test_json_string_output() {
+	for json in json_blobs() {
+		let expected: serde_json::Value = serde_json::from_str(&json).expect("parsing JSON");
+		let value: Value = serde_json::from_str(&json).expect("parsing JSON");
+
+		let output = value.to_json_string().expect("writing JSON");
+		let parsed: serde_json::Value = serde_json::from_str(&output).expect("parsing output");
+		assert_eq!(parsed, expected);
+
+		// Pretty display is valid JSON as well, since JSON data has only string keys and no bytes.
+		let pretty: serde_json::Value =
+			serde_json::from_str(&format!("{value:#}")).expect("parsing display");
+		assert_eq!(pretty, expected);
+	}
 }
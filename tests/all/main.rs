@@ -1,4 +1,13 @@
 //! All integration tests go in this folder to speed up compilation.
 #![allow(clippy::unwrap_used, clippy::expect_used, clippy::print_stdout, reason = "Tests")]
 
+mod allocations;
+mod convert;
+mod fuzz_regressions;
 mod json_data;
+#[cfg(feature = "derive")]
+mod max_size;
+mod raw;
+mod schema;
+mod transcode;
+mod wrapped;
@@ -0,0 +1,119 @@
+//! Tests for the `MaxSize` derive.
+
+use ::serde::Serialize;
+use ::serde_brief::{Config, MaxSize};
+
+#[derive(Debug, Serialize, MaxSize)]
+struct Named {
+	id: u32,
+	#[serde(rename = "v")]
+	values: [i16; 3],
+	#[serde(skip)]
+	_cache: u128,
+	flag: Option<bool>,
+	r#type: Unit,
+}
+
+#[derive(Debug, Serialize, MaxSize)]
+struct Unit;
+
+#[derive(Debug, Serialize, MaxSize)]
+struct Tuple(u8, f64);
+
+#[derive(Debug, Serialize, MaxSize)]
+struct Newtype(u64);
+
+#[derive(Debug, Serialize, MaxSize)]
+#[serde(transparent)]
+struct Transparent {
+	inner: i64,
+}
+
+#[derive(Debug, Serialize, MaxSize)]
+struct Generic<T> {
+	value: T,
+}
+
+#[derive(Debug, Serialize, MaxSize)]
+enum Message {
+	Ping,
+	Data(Named),
+	Pair(u8, u8),
+	#[serde(rename = "C")]
+	Coordinates {
+		x: f32,
+		y: f32,
+	},
+}
+
+#[derive(Debug, Serialize, MaxSize)]
+enum Empty {}
+
+/// Configurations with field names and indices, both with explicit options.
+fn configs() -> [Config; 2] {
+	[false, true].map(|use_indices| Config {
+		use_indices,
+		explicit_option: true,
+		..Default::default()
+	})
+}
+
+/// Encoded sizes with field names and indices, checked against the maximum size.
+fn sizes<T>(value: &T) -> [usize; 2]
+where
+	T: Serialize + MaxSize,
+{
+	configs().map(|config| {
+		let size = serde_brief::serialized_size_with_config(value, config).unwrap();
+		assert!(size <= T::MAX_SIZE, "{size} > {}", T::MAX_SIZE);
+		size
+	})
+}
+
+/// Value with the largest encoding.
+const fn worst_named() -> Named {
+	Named { id: u32::MAX, values: [i16::MIN; 3], _cache: 0, flag: Some(true), r#type: Unit }
+}
+
+#[test]
+fn structs_are_bounded() {
+	assert_eq!(Named::MAX_SIZE, 2 + (4 + 6) + (3 + 2 + 3 * 4) + (6 + 2) + (6 + 1));
+	assert_eq!(
+		sizes(&worst_named()),
+		[Named::MAX_SIZE, 2 + (2 + 6) + (2 + 14) + (2 + 2) + (2 + 1)]
+	);
+	assert_eq!(sizes(&Named { id: 0, values: [0; 3], _cache: 0, flag: None, r#type: Unit })[0], 33);
+
+	assert_eq!(sizes(&Unit), [1, 1]);
+	assert_eq!(sizes(&Tuple(u8::MAX, 1.0)), [Tuple::MAX_SIZE; 2]);
+	assert_eq!(
+		Tuple::MAX_SIZE,
+		serde_brief::max_size::seq_overhead(2) + u8::MAX_SIZE + f64::MAX_SIZE
+	);
+	let config = Config { length_prefixed_seqs: true, ..Default::default() };
+	let size = serde_brief::serialized_size_with_config(&Tuple(u8::MAX, 1.0), config).unwrap();
+	assert!(size <= Tuple::MAX_SIZE, "{size} > {}", Tuple::MAX_SIZE);
+	assert_eq!(sizes(&Newtype(u64::MAX)), [u64::MAX_SIZE; 2]);
+	assert_eq!(sizes(&Transparent { inner: i64::MIN }), [i64::MAX_SIZE; 2]);
+	assert_eq!(sizes(&Generic { value: [u16::MAX; 2] }), [Generic::<[u16; 2]>::MAX_SIZE, 14]);
+}
+
+#[test]
+fn enums_are_bounded() {
+	assert_eq!(Message::MAX_SIZE, 2 + 6 + Named::MAX_SIZE);
+	assert_eq!(sizes(&Message::Data(worst_named()))[0], Message::MAX_SIZE);
+	assert_eq!(sizes(&Message::Ping), [6, 2]);
+	assert_eq!(sizes(&Message::Pair(u8::MAX, u8::MAX)), [2 + 6 + 2 + 3 + 3, 2 + 2 + 2 + 3 + 3]);
+	assert_eq!(sizes(&Message::Coordinates { x: 1.0, y: 2.0 })[0], 2 + 3 + 2 + 2 * (3 + 5));
+	assert_eq!(Empty::MAX_SIZE, 0);
+}
+
+#[test]
+fn max_size_sizes_buffers() {
+	let mut buffer = [0; Message::MAX_SIZE];
+	let [config, _] = configs();
+	let bytes =
+		serde_brief::to_slice_with_config(&Message::Data(worst_named()), &mut buffer, config)
+			.unwrap();
+	assert_eq!(bytes.len(), Message::MAX_SIZE);
+}
@@ -0,0 +1,203 @@
+//! Tests for lazy access to raw encoded data.
+#![cfg(feature = "alloc")]
+
+use ::serde::Serialize;
+use ::serde_brief::{raw::MapReader, value::Value, Config, Error};
+
+#[derive(Debug, Serialize)]
+struct Record {
+	id: u64,
+	name: String,
+	tags: Vec<String>,
+	location: Location,
+}
+
+#[derive(Debug, Serialize)]
+struct Location {
+	city: String,
+	coordinates: Coordinates,
+}
+
+#[derive(Debug, Serialize)]
+struct Coordinates {
+	lat: f64,
+	lon: f64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	label: Option<String>,
+}
+
+/// A large map of records, keyed by their name.
+fn records() -> Vec<(String, Record)> {
+	(0 .. 1000_u32)
+		.map(|i| {
+			let record = Record {
+				id: u64::from(i) * 7919,
+				name: format!("record-{i}"),
+				tags: (0 .. i % 5).map(|t| format!("tag-{t}")).collect(),
+				location: Location {
+					city: format!("city-{}", i % 13),
+					coordinates: Coordinates {
+						lat: f64::from(i) / 10.0,
+						lon: -f64::from(i),
+						label: (i % 3 == 0).then(|| format!("label-{i}")),
+					},
+				},
+			};
+			(record.name.clone(), record)
+		})
+		.collect()
+}
+
+/// Follow the path of keys through nested maps.
+fn lookup<'de>(bytes: &'de [u8], path: &[&str]) -> Result<Option<&'de [u8]>, Error> {
+	let mut current = bytes;
+	for key in path {
+		let Some(value) = MapReader::new(current)?.get(key)? else {
+			return Ok(None);
+		};
+		current = value;
+	}
+	Ok(Some(current))
+}
+
+#[test]
+fn nested_field_matches_value() {
+	let records = records();
+	let map: std::collections::BTreeMap<_, _> = records.iter().map(|(k, v)| (k, v)).collect();
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	let value: Value = serde_brief::from_slice(&bytes).unwrap();
+
+	for i in [0, 3, 500, 998, 999] {
+		let name = format!("record-{i}");
+		for field in ["lat", "lon", "label"] {
+			let raw = lookup(&bytes, &[&name, "location", "coordinates", field]).unwrap();
+			let expected =
+				value.get(&name).unwrap().get("location").unwrap().get("coordinates").unwrap();
+			match expected.get(field) {
+				Some(expected) => {
+					let parsed: Value = serde_brief::from_slice(raw.unwrap()).unwrap();
+					assert_eq!(&parsed, expected);
+				}
+				None => assert!(raw.is_none()),
+			}
+		}
+	}
+	assert_eq!(lookup(&bytes, &["record-1000"]).unwrap(), None);
+
+	// Entries yield the encoded keys and values.
+	let reader = MapReader::new(&bytes).unwrap();
+	let entries: Vec<_> = reader.entries().collect::<Result<_, _>>().unwrap();
+	assert_eq!(entries.len(), value.as_map().unwrap().len());
+	for ((key, raw), (expected_key, expected)) in entries.iter().zip(value.entries()) {
+		assert_eq!(&serde_brief::from_slice::<Value>(key).unwrap(), expected_key);
+		assert_eq!(&serde_brief::from_slice::<Value>(raw).unwrap(), expected);
+	}
+}
+
+#[test]
+fn map_reader_errors() {
+	let bytes = serde_brief::to_vec(&[("a", 1_u8), ("b", 2)]).unwrap();
+	assert!(matches!(MapReader::new(&bytes), Err(Error::WrongType(..))));
+	assert!(matches!(MapReader::new(&[]), Err(Error::UnexpectedEnd)));
+
+	let map = std::collections::BTreeMap::from([("a", 1_u8), ("b", 2)]);
+	let bytes = serde_brief::to_vec(&map).unwrap();
+	let truncated = bytes.get(.. bytes.len() - 2).unwrap();
+	let reader = MapReader::new(truncated).unwrap();
+	let a = reader.get("a").unwrap().unwrap();
+	assert_eq!(serde_brief::from_slice::<u8>(a).unwrap(), 1);
+	assert!(matches!(reader.get("c"), Err(Error::UnexpectedEnd)));
+	let mut entries = reader.entries();
+	assert!(entries.next().unwrap().is_ok());
+	assert!(entries.next().unwrap().is_err());
+	assert!(entries.next().is_none());
+
+	// Index keys are not matched by name.
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes =
+		serde_brief::to_vec_with_config(&Coordinates { lat: 1.0, lon: 2.0, label: None }, config)
+			.unwrap();
+	let reader = MapReader::new(&bytes).unwrap();
+	assert_eq!(reader.get("lat").unwrap(), None);
+	assert_eq!(reader.entries().count(), 2);
+}
+
+#[derive(Debug, Serialize)]
+enum Shape {
+	Circle(Coordinates),
+	Polygon(Vec<Coordinates>),
+}
+
+#[derive(Debug, Serialize)]
+struct Drawing {
+	name: String,
+	shapes: Vec<Shape>,
+	layers: std::collections::BTreeMap<u32, Option<Shape>>,
+}
+
+fn drawing() -> Drawing {
+	let point = |lat, lon| Coordinates { lat, lon, label: None };
+	Drawing {
+		name: "sketch".to_owned(),
+		shapes: vec![
+			Shape::Circle(Coordinates { lat: 1.0, lon: 2.0, label: Some("center".to_owned()) }),
+			Shape::Polygon(vec![point(0.0, 0.0), point(3.0, 4.0)]),
+		],
+		layers: [(7, Some(Shape::Circle(point(5.0, 6.0)))), (8, None)].into(),
+	}
+}
+
+#[test]
+fn get_path_components() {
+	for length_prefixed_seqs in [false, true] {
+		let config = Config { length_prefixed_seqs, ..Default::default() };
+		let bytes = serde_brief::to_vec_with_config(&drawing(), config).unwrap();
+
+		// Map keys, sequence indices and enum variants.
+		let name: Option<&str> = serde_brief::get_path(&bytes, &["name"]).unwrap();
+		assert_eq!(name, Some("sketch"));
+		let label: Option<&str> =
+			serde_brief::get_path(&bytes, &["shapes", "0", "Circle", "label"]).unwrap();
+		assert_eq!(label, Some("center"));
+		let lon: Option<f64> =
+			serde_brief::get_path(&bytes, &["shapes", "1", "Polygon", "1", "lon"]).unwrap();
+		assert_eq!(lon, Some(4.0));
+
+		// Integer map keys and `Some` markers.
+		let lat: Option<f64> =
+			serde_brief::get_path(&bytes, &["layers", "7", "Circle", "lat"]).unwrap();
+		assert_eq!(lat, Some(5.0));
+		let empty: Option<Option<Value>> = serde_brief::get_path(&bytes, &["layers", "8"]).unwrap();
+		assert_eq!(empty, Some(None));
+
+		// Missing paths.
+		for path in [
+			&["missing"][..],
+			&["shapes", "2"],
+			&["shapes", "first"],
+			&["shapes", "0", "Polygon"],
+			&["shapes", "0", "Circle", "label", "deeper"],
+			&["layers", "8", "Circle"],
+			&["layers", "9"],
+		] {
+			let value: Option<Value> = serde_brief::get_path(&bytes, path).unwrap();
+			assert_eq!(value, None, "{path:?}");
+		}
+
+		// Type mismatches at the leaf.
+		let result = serde_brief::get_path::<u32>(&bytes, &["name"]);
+		assert!(matches!(result, Err(Error::WrongType(..))));
+
+		// The empty path is the whole value.
+		let whole: Option<Value> = serde_brief::get_path(&bytes, &[]).unwrap();
+		assert_eq!(whole, Some(serde_brief::from_slice(&bytes).unwrap()));
+	}
+
+	// Field indices.
+	let config = Config { use_indices: true, ..Default::default() };
+	let bytes = serde_brief::to_vec_with_config(&drawing(), config).unwrap();
+	let lon: Option<f64> = serde_brief::get_path(&bytes, &["1", "1", "1", "0", "1"]).unwrap();
+	assert_eq!(lon, Some(0.0));
+	let name: Option<&str> = serde_brief::get_path(&bytes, &["name"]).unwrap();
+	assert_eq!(name, None);
+}
@@ -0,0 +1,213 @@
+//! Tests for recording schemas from sample values.
+#![cfg(feature = "alloc")]
+
+use ::serde::Serialize;
+use ::serde_brief::{
+	schema::{schema_of, Member, Schema},
+	value::Value,
+};
+use ::std::collections::BTreeMap;
+
+#[derive(Debug, Serialize)]
+struct Order {
+	id: u64,
+	#[serde(skip_serializing_if = "Option::is_none")]
+	note: Option<String>,
+	customer: Customer,
+	items: Vec<(String, u32)>,
+	status: Status,
+	#[serde(with = "serde_bytes")]
+	signature: Vec<u8>,
+	metadata: BTreeMap<String, f64>,
+}
+
+#[derive(Debug, Serialize)]
+struct Customer {
+	name: String,
+	email: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+enum Status {
+	Open,
+	Shipped { tracking: String },
+}
+
+/// Order with all fields set.
+fn order(status: Status) -> Order {
+	Order {
+		id: 7,
+		note: None,
+		customer: Customer { name: "Jane".to_owned(), email: None },
+		items: vec![("apple".to_owned(), 3), ("pear".to_owned(), 1)],
+		status,
+		signature: vec![1, 2, 3],
+		metadata: BTreeMap::from([("weight".to_owned(), 1.5)]),
+	}
+}
+
+#[test]
+fn schema_snapshot() {
+	let schema = schema_of(&order(Status::Shipped { tracking: "X1".to_owned() })).unwrap();
+	let json = serde_json::to_string_pretty(&schema).unwrap();
+	assert_eq!(
+		json,
+		r#"{
+  "Struct": {
+    "name": "Order",
+    "fields": [
+      {
+        "name": "id",
+        "index": 0,
+        "schema": "UInt"
+      },
+      {
+        "name": "customer",
+        "index": 2,
+        "schema": {
+          "Struct": {
+            "name": "Customer",
+            "fields": [
+              {
+                "name": "name",
+                "index": 0,
+                "schema": "String"
+              },
+              {
+                "name": "email",
+                "index": 1,
+                "schema": {
+                  "Option": "Any"
+                }
+              }
+            ]
+          }
+        }
+      },
+      {
+        "name": "items",
+        "index": 3,
+        "schema": {
+          "Seq": {
+            "Tuple": [
+              "String",
+              "UInt"
+            ]
+          }
+        }
+      },
+      {
+        "name": "status",
+        "index": 4,
+        "schema": {
+          "Enum": {
+            "name": "Status",
+            "variants": [
+              {
+                "name": "Shipped",
+                "index": 1,
+                "schema": {
+                  "Struct": {
+                    "name": "Shipped",
+                    "fields": [
+                      {
+                        "name": "tracking",
+                        "index": 0,
+                        "schema": "String"
+                      }
+                    ]
+                  }
+                }
+              }
+            ]
+          }
+        }
+      },
+      {
+        "name": "signature",
+        "index": 5,
+        "schema": "Bytes"
+      },
+      {
+        "name": "metadata",
+        "index": 6,
+        "schema": {
+          "Map": {
+            "key": "String",
+            "value": "F64"
+          }
+        }
+      }
+    ]
+  }
+}"#
+	);
+}
+
+#[test]
+fn merging_samples() {
+	let first = schema_of(&order(Status::Open)).unwrap();
+	let mut second = order(Status::Shipped { tracking: "X1".to_owned() });
+	second.note = Some("fragile".to_owned());
+	second.customer.email = Some("jane@example.com".to_owned());
+	let second = schema_of(&second).unwrap();
+
+	let Schema::Struct { fields, .. } = first.merge(second) else {
+		panic!("orders are structs");
+	};
+	let names: Vec<_> = fields.iter().map(|field| (field.name.as_str(), field.index)).collect();
+	assert_eq!(
+		names,
+		[
+			("id", 0),
+			("customer", 2),
+			("items", 3),
+			("status", 4),
+			("signature", 5),
+			("metadata", 6),
+			("note", 1)
+		]
+	);
+	let Schema::Struct { fields: customer, .. } = &fields.get(1).unwrap().schema else {
+		panic!("customers are structs");
+	};
+	assert_eq!(customer.get(1).unwrap().schema, Schema::Option(Box::new(Schema::String)));
+	let Schema::Enum { variants, .. } = &fields.get(3).unwrap().schema else {
+		panic!("status is an enum");
+	};
+	let [open, shipped] = variants.as_slice() else {
+		panic!("both variants were seen");
+	};
+	assert_eq!(*open, Member { name: "Open".to_owned(), index: 0, schema: Schema::Null });
+	assert_eq!(shipped.name, "Shipped");
+
+	assert_eq!(Schema::UInt.merge(Schema::String), Schema::Any);
+	assert_eq!(
+		schema_of(&[Some(1_i8), None]).unwrap(),
+		Schema::Tuple(vec![
+			Schema::Option(Box::new(Schema::Int)),
+			Schema::Option(Box::new(Schema::Any))
+		])
+	);
+	assert_eq!(
+		schema_of(&vec![None, Some(1_i8)]).unwrap(),
+		Schema::Seq(Box::new(Schema::Option(Box::new(Schema::Int))))
+	);
+}
+
+#[test]
+fn schema_from_value() {
+	let value = Value::from_iter([("a", Value::Array([1_u8.into(), 2_u8.into()].into()))]);
+	assert_eq!(
+		Schema::from(&value),
+		Schema::Map {
+			key: Box::new(Schema::String),
+			value: Box::new(Schema::Seq(Box::new(Schema::UInt)))
+		}
+	);
+	assert_eq!(Schema::from(&Value::Array([].into())), Schema::Seq(Box::new(Schema::Any)));
+	assert_eq!(
+		Schema::from(&Value::Array([Value::Null, true.into()].into())),
+		Schema::Seq(Box::new(Schema::Any))
+	);
+}
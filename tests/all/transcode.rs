@@ -0,0 +1,98 @@
+//! Test transcoding between JSON and the binary format with `serde_transcode`, without
+//! intermediate types.
+#![cfg(feature = "std")]
+
+use ::serde_brief::{value::Value, Config, Deserializer, Serializer};
+
+/// Transcode JSON to the binary format.
+fn json_to_brief(json: &str, config: Config) -> Vec<u8> {
+	let mut json_de = serde_json::Deserializer::from_str(json);
+	let mut brief_ser = Serializer::new(Vec::new()).use_indices(config.use_indices);
+	serde_transcode::transcode(&mut json_de, &mut brief_ser).expect("transcoding from JSON");
+	json_de.end().expect("JSON has trailing data");
+	brief_ser.into_output()
+}
+
+/// Transcode the binary format to JSON.
+fn brief_to_json(bytes: &[u8]) -> String {
+	let mut brief_de = Deserializer::new(bytes);
+	let mut json = Vec::new();
+	let mut json_ser = serde_json::Serializer::new(&mut json);
+	serde_transcode::transcode(&mut brief_de, &mut json_ser).expect("transcoding to JSON");
+	String::from_utf8(json).expect("JSON is UTF-8")
+}
+
+#[test]
+fn test_transcode_json_blobs() {
+	for json in super::json_data::json_blobs() {
+		let expected: serde_json::Value = serde_json::from_str(&json).expect("parsing JSON");
+
+		let bytes = json_to_brief(&json, Config::default());
+		let value: Value<'_> = serde_brief::from_slice(&bytes).expect("deserializing");
+		let typed: Value<'_> = serde_json::from_str(&json).expect("parsing JSON");
+		assert_eq!(value, typed);
+
+		let transcoded = brief_to_json(&bytes);
+		let back: serde_json::Value = serde_json::from_str(&transcoded).expect("parsing JSON");
+		assert_eq!(back, expected);
+
+		// The order of keys is preserved.
+		let mut minified = Vec::new();
+		serde_transcode::transcode(
+			&mut serde_json::Deserializer::from_str(&json),
+			&mut serde_json::Serializer::new(&mut minified),
+		)
+		.expect("minifying JSON");
+		assert_eq!(transcoded.as_bytes(), minified);
+
+		let bytes = json_to_brief(&json, Config { use_indices: true, ..Default::default() });
+		assert_eq!(brief_to_json(&bytes), transcoded);
+	}
+}
+
+#[test]
+fn test_transcode_enums_and_options() {
+	#[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+	enum Enum {
+		Unit,
+		Newtype(Option<u8>),
+		Tuple(i8, f32),
+		Struct { a: String, b: Option<bool> },
+	}
+
+	let values = vec![
+		Enum::Unit,
+		Enum::Newtype(Some(5)),
+		Enum::Newtype(None),
+		Enum::Tuple(-1, 1.5),
+		Enum::Struct { a: "a".to_owned(), b: Some(true) },
+		Enum::Struct { a: "b".to_owned(), b: None },
+	];
+	let bytes = serde_brief::to_vec(&values).expect("serializing");
+	let json = brief_to_json(&bytes);
+	assert_eq!(json, serde_json::to_string(&values).expect("serializing JSON"));
+	let parsed: Vec<Enum> = serde_json::from_str(&json).expect("parsing JSON");
+	assert_eq!(parsed, values);
+
+	let bytes = json_to_brief(&json, Config::default());
+	let parsed: Vec<Enum> = serde_brief::from_slice(&bytes).expect("deserializing");
+	assert_eq!(parsed, values);
+}
+
+#[test]
+fn test_transcode_special_values() {
+	let config = Config { explicit_option: true, ..Default::default() };
+	let value = (
+		Some(Some(1_u8)),
+		Some(None::<u8>),
+		serde_bytes::Bytes::new(&[1, 2]),
+		u128::from(u64::MAX),
+		-5_i128,
+	);
+	let bytes = serde_brief::to_vec_with_config(&value, config).expect("serializing");
+	assert_eq!(brief_to_json(&bytes), format!("[1,null,[1,2],{},-5]", u64::MAX));
+
+	// Large integers are written as JSON numbers, even if most parsers cannot read them.
+	let bytes = serde_brief::to_vec(&u128::MAX).expect("serializing");
+	assert_eq!(brief_to_json(&bytes), u128::MAX.to_string());
+}
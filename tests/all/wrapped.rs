@@ -0,0 +1,241 @@
+//! Tests for serializers wrapping this crate's, e.g. to track paths, which must not change the
+//! encoding of types with special newtype names.
+#![cfg(feature = "alloc")]
+
+use ::serde::{Serialize, Serializer};
+use ::serde_brief::{
+	format::Type,
+	value::{Float, Value},
+};
+
+/// Serializer forwarding everything to the inner serializer.
+struct Wrapped<S>(S);
+
+impl<S> Serializer for Wrapped<S>
+where
+	S: Serializer,
+{
+	type Ok = S::Ok;
+	type Error = S::Error;
+	type SerializeSeq = S::SerializeSeq;
+	type SerializeTuple = S::SerializeTuple;
+	type SerializeTupleStruct = S::SerializeTupleStruct;
+	type SerializeTupleVariant = S::SerializeTupleVariant;
+	type SerializeMap = S::SerializeMap;
+	type SerializeStruct = S::SerializeStruct;
+	type SerializeStructVariant = S::SerializeStructVariant;
+
+	fn serialize_bool(self, v: bool) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_bool(v)
+	}
+
+	fn serialize_i8(self, v: i8) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_i8(v)
+	}
+
+	fn serialize_i16(self, v: i16) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_i16(v)
+	}
+
+	fn serialize_i32(self, v: i32) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_i32(v)
+	}
+
+	fn serialize_i64(self, v: i64) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_i64(v)
+	}
+
+	fn serialize_i128(self, v: i128) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_i128(v)
+	}
+
+	fn serialize_u8(self, v: u8) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_u8(v)
+	}
+
+	fn serialize_u16(self, v: u16) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_u16(v)
+	}
+
+	fn serialize_u32(self, v: u32) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_u32(v)
+	}
+
+	fn serialize_u64(self, v: u64) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_u64(v)
+	}
+
+	fn serialize_u128(self, v: u128) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_u128(v)
+	}
+
+	fn serialize_f32(self, v: f32) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_f32(v)
+	}
+
+	fn serialize_f64(self, v: f64) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_f64(v)
+	}
+
+	fn serialize_char(self, v: char) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_char(v)
+	}
+
+	fn serialize_str(self, v: &str) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_str(v)
+	}
+
+	fn serialize_bytes(self, v: &[u8]) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_bytes(v)
+	}
+
+	fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_none()
+	}
+
+	fn serialize_some<T>(self, value: &T) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.0.serialize_some(value)
+	}
+
+	fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_unit()
+	}
+
+	fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_unit_struct(name)
+	}
+
+	fn serialize_unit_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+	) -> Result<Self::Ok, Self::Error> {
+		self.0.serialize_unit_variant(name, variant_index, variant)
+	}
+
+	fn serialize_newtype_struct<T>(
+		self,
+		name: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.0.serialize_newtype_struct(name, value)
+	}
+
+	fn serialize_newtype_variant<T>(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		value: &T,
+	) -> Result<Self::Ok, Self::Error>
+	where
+		T: ?Sized + Serialize,
+	{
+		self.0.serialize_newtype_variant(name, variant_index, variant, value)
+	}
+
+	fn serialize_seq(self, len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+		self.0.serialize_seq(len)
+	}
+
+	fn serialize_tuple(self, len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+		self.0.serialize_tuple(len)
+	}
+
+	fn serialize_tuple_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleStruct, Self::Error> {
+		self.0.serialize_tuple_struct(name, len)
+	}
+
+	fn serialize_tuple_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeTupleVariant, Self::Error> {
+		self.0.serialize_tuple_variant(name, variant_index, variant, len)
+	}
+
+	fn serialize_map(self, len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+		self.0.serialize_map(len)
+	}
+
+	fn serialize_struct(
+		self,
+		name: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStruct, Self::Error> {
+		self.0.serialize_struct(name, len)
+	}
+
+	fn serialize_struct_variant(
+		self,
+		name: &'static str,
+		variant_index: u32,
+		variant: &'static str,
+		len: usize,
+	) -> Result<Self::SerializeStructVariant, Self::Error> {
+		self.0.serialize_struct_variant(name, variant_index, variant, len)
+	}
+
+	fn is_human_readable(&self) -> bool {
+		self.0.is_human_readable()
+	}
+}
+
+/// Serialize the value through the wrapper.
+fn to_vec_wrapped<T>(value: &T) -> Vec<u8>
+where
+	T: ?Sized + Serialize,
+{
+	let mut serializer = ::serde_brief::ser::Serializer::new(Vec::new());
+	value.serialize(Wrapped(&mut serializer)).unwrap();
+	serializer.into_output()
+}
+
+#[test]
+fn test_wrapped_value_floats() {
+	let f16 = Value::Float(Float::F16Bits(0xC100));
+	let bytes = to_vec_wrapped(&f16);
+	assert_eq!(bytes, [Type::Float16.into(), 0x00, 0xC1]);
+	assert_eq!(bytes, ::serde_brief::to_vec(&f16).unwrap());
+
+	// Exact as `f64` and not.
+	for bits in [0x3FFF << 112, 0x3FFF << 112 | 1] {
+		let f128 = Value::Float(Float::F128Bits(bits));
+		let bytes = to_vec_wrapped(&f128);
+		assert_eq!(bytes.first(), Some(&Type::Float128.into()));
+		assert_eq!(bytes, ::serde_brief::to_vec(&f128).unwrap());
+		assert_eq!(::serde_brief::from_slice::<Value<'_>>(&bytes).unwrap(), f128);
+	}
+}
+
+#[cfg(feature = "half")]
+#[test]
+fn test_wrapped_f16_helper() {
+	use ::half::f16;
+	use ::serde::Deserialize;
+
+	#[derive(Debug, PartialEq, Serialize, Deserialize)]
+	struct Half(#[serde(with = "serde_brief::helpers::f16_as_float16")] f16);
+
+	let half = Half(f16::from_f32(-2.5));
+	let bytes = to_vec_wrapped(&half);
+	assert_eq!(bytes, [Type::Float16.into(), 0x00, 0xC1]);
+	assert_eq!(::serde_brief::from_slice::<Half>(&bytes).unwrap(), half);
+
+	// Other formats get the float.
+	assert_eq!(::serde_json::to_string(&half).unwrap(), "-2.5");
+	assert_eq!(::serde_json::from_str::<Half>("-2.5").unwrap(), half);
+}